@@ -49,7 +49,11 @@ macro_rules! for_each_schema_type {
         $macro!(crate::ClientInfo);
         $macro!(crate::ClientNotification);
         $macro!(crate::ClientRequest);
+        $macro!(crate::ControlOssDownloadParams);
+        $macro!(crate::ControlOssDownloadResponse);
         $macro!(crate::ConversationSummary);
+        $macro!(crate::EnsureOssReadyParams);
+        $macro!(crate::EnsureOssReadyResponse);
         $macro!(crate::ExecCommandApprovalParams);
         $macro!(crate::ExecCommandApprovalResponse);
         $macro!(crate::ExecOneOffCommandParams);
@@ -71,6 +75,7 @@ macro_rules! for_each_schema_type {
         $macro!(crate::InterruptConversationResponse);
         $macro!(crate::ListConversationsParams);
         $macro!(crate::ListConversationsResponse);
+        $macro!(crate::ListLocalModelsResponse);
         $macro!(crate::LoginApiKeyParams);
         $macro!(crate::LoginApiKeyResponse);
         $macro!(crate::LoginChatGptCompleteNotification);
@@ -79,6 +84,9 @@ macro_rules! for_each_schema_type {
         $macro!(crate::LogoutChatGptResponse);
         $macro!(crate::NewConversationParams);
         $macro!(crate::NewConversationResponse);
+        $macro!(crate::OssDownloadAction);
+        $macro!(crate::OssDownloadsResponse);
+        $macro!(crate::OssStatusResponse);
         $macro!(crate::Profile);
         $macro!(crate::RemoveConversationListenerParams);
         $macro!(crate::RemoveConversationSubscriptionResponse);
@@ -100,6 +108,8 @@ macro_rules! for_each_schema_type {
         $macro!(codex_protocol::protocol::EventMsg);
         $macro!(codex_protocol::protocol::FileChange);
         $macro!(codex_protocol::parse_command::ParsedCommand);
+        $macro!(codex_protocol::oss::ModelDownloadProgress);
+        $macro!(codex_protocol::oss::OssEvent);
         $macro!(codex_protocol::protocol::SandboxPolicy);
     };
 }
@@ -10,6 +10,11 @@ use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::config_types::SandboxMode;
 use codex_protocol::config_types::Verbosity;
+use codex_protocol::oss::DownloadStatus;
+use codex_protocol::oss::LocalModel;
+use codex_protocol::oss::ModelDownloadProgress;
+use codex_protocol::oss::OssEvent;
+use codex_protocol::oss::OssRuntimeStatus;
 use codex_protocol::parse_command::ParsedCommand;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::EventMsg;
@@ -197,6 +202,34 @@ client_request_definitions! {
         params: #[ts(type = "undefined")] #[serde(skip_serializing_if = "Option::is_none")] Option<()>,
         response: GetAccountRateLimitsResponse,
     },
+    /// List the models the local `oss` server has downloaded.
+    ListLocalModels {
+        params: #[ts(type = "undefined")] #[serde(skip_serializing_if = "Option::is_none")] Option<()>,
+        response: ListLocalModelsResponse,
+    },
+    /// Make sure the local `oss` server is reachable and has the model,
+    /// downloading it when allowed. Download progress is sent as
+    /// `ossDownloadProgress` notifications before the response.
+    EnsureOssReady {
+        params: EnsureOssReadyParams,
+        response: EnsureOssReadyResponse,
+    },
+    /// Runtime status of the local `oss` server.
+    OssStatus {
+        params: #[ts(type = "undefined")] #[serde(skip_serializing_if = "Option::is_none")] Option<()>,
+        response: OssStatusResponse,
+    },
+    /// Model downloads pending in this app server, including those started
+    /// by `ensureOssReady`.
+    OssDownloads {
+        params: #[ts(type = "undefined")] #[serde(skip_serializing_if = "Option::is_none")] Option<()>,
+        response: OssDownloadsResponse,
+    },
+    /// Pause, resume, or cancel a pending model download.
+    ControlOssDownload {
+        params: ControlOssDownloadParams,
+        response: ControlOssDownloadResponse,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
@@ -477,6 +510,70 @@ pub struct GetAccountRateLimitsResponse {
     pub rate_limits: RateLimitSnapshot,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ListLocalModelsResponse {
+    /// Sorted by name. Servers other than Ollama only report model names.
+    pub models: Vec<LocalModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct EnsureOssReadyParams {
+    /// Model to prepare; defaults to the configured `oss.model`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Download the model when the server does not have it. Without this a
+    /// missing model fails the request.
+    #[serde(default)]
+    pub allow_download: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct EnsureOssReadyResponse {
+    /// The model that is ready.
+    pub model: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct OssStatusResponse {
+    pub status: OssRuntimeStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct OssDownloadsResponse {
+    /// Oldest first; the first that is not paused is the one downloading.
+    pub downloads: Vec<DownloadStatus>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlOssDownloadParams {
+    /// `id` of a download listed by `ossDownloads`.
+    #[ts(type = "number")]
+    pub id: u64,
+    pub action: OssDownloadAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub enum OssDownloadAction {
+    Pause,
+    Resume,
+    Cancel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlOssDownloadResponse {
+    /// Whether the download was still pending; `false` when it had already
+    /// finished.
+    pub found: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct GetAuthStatusResponse {
@@ -603,6 +700,8 @@ pub struct SandboxSettings {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub network_access: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_inference_access: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub exclude_tmpdir_env_var: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exclude_slash_tmp: Option<bool>,
@@ -883,6 +982,13 @@ pub enum ServerNotification {
 
     /// The special session configured event for a new or resumed conversation.
     SessionConfigured(SessionConfiguredNotification),
+
+    /// Progress of a model download started by `ensureOssReady`.
+    OssDownloadProgress(ModelDownloadProgress),
+
+    /// The local server started or went away, or a model was downloaded,
+    /// loaded, or unloaded.
+    OssEvent(OssEvent),
 }
 
 impl ServerNotification {
@@ -891,6 +997,8 @@ impl ServerNotification {
             ServerNotification::AuthStatusChange(params) => serde_json::to_value(params),
             ServerNotification::LoginChatGptComplete(params) => serde_json::to_value(params),
             ServerNotification::SessionConfigured(params) => serde_json::to_value(params),
+            ServerNotification::OssDownloadProgress(params) => serde_json::to_value(params),
+            ServerNotification::OssEvent(params) => serde_json::to_value(params),
         }
     }
 }
@@ -1059,4 +1167,27 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn serialize_ensure_oss_ready() -> Result<()> {
+        let request = ClientRequest::EnsureOssReady {
+            request_id: RequestId::Integer(3),
+            params: EnsureOssReadyParams {
+                model: Some("gpt-oss:20b".to_string()),
+                allow_download: true,
+            },
+        };
+        assert_eq!(
+            json!({
+                "method": "ensureOssReady",
+                "id": 3,
+                "params": {
+                    "model": "gpt-oss:20b",
+                    "allowDownload": true
+                }
+            }),
+            serde_json::to_value(&request)?,
+        );
+        Ok(())
+    }
 }
@@ -97,6 +97,7 @@ use codex_protocol::protocol::RateLimitSnapshot;
 use codex_protocol::protocol::USER_MESSAGE_BEGIN;
 use codex_protocol::user_input::UserInput as CoreInputItem;
 use codex_utils_json_to_toml::json_to_toml;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::path::PathBuf;
@@ -255,9 +256,66 @@ impl CodexMessageProcessor {
             } => {
                 self.get_account_rate_limits(request_id).await;
             }
+            ClientRequest::ListLocalModels {
+                request_id,
+                params: _,
+            } => {
+                let config = self.config.clone();
+                self.spawn_oss_request(request_id, async move {
+                    crate::oss::list_local_models(&config).await
+                });
+            }
+            ClientRequest::EnsureOssReady { request_id, params } => {
+                let config = self.config.clone();
+                let outgoing = self.outgoing.clone();
+                self.spawn_oss_request(request_id, async move {
+                    crate::oss::ensure_oss_ready(&config, params, outgoing).await
+                });
+            }
+            ClientRequest::OssStatus {
+                request_id,
+                params: _,
+            } => {
+                let config = self.config.clone();
+                self.spawn_oss_request(
+                    request_id,
+                    async move { crate::oss::oss_status(&config).await },
+                );
+            }
+            ClientRequest::OssDownloads {
+                request_id,
+                params: _,
+            } => {
+                self.outgoing
+                    .send_response(request_id, crate::oss::oss_downloads())
+                    .await;
+            }
+            ClientRequest::ControlOssDownload { request_id, params } => {
+                self.outgoing
+                    .send_response(request_id, crate::oss::control_oss_download(params))
+                    .await;
+            }
         }
     }
 
+    /// Answer a request about the local `oss` server in the background: they
+    /// wait on that server, and downloads can take hours.
+    fn spawn_oss_request<T>(
+        &self,
+        request_id: RequestId,
+        request: impl Future<Output = Result<T, JSONRPCErrorError>> + Send + 'static,
+    ) where
+        T: Serialize + Send + 'static,
+    {
+        let outgoing = self.outgoing.clone();
+        tokio::spawn(async move {
+            match request.await {
+                Ok(response) => outgoing.send_response(request_id, response).await,
+                Err(error) => outgoing.send_error(request_id, error).await,
+            }
+        });
+    }
+
     async fn login_api_key(&mut self, request_id: RequestId, params: LoginApiKeyParams) {
         if matches!(
             self.config.forced_login_method,
@@ -1495,6 +1553,8 @@ async fn derive_config_from_params(
         show_raw_agent_reasoning: None,
         tools_web_search_request: None,
         additional_writable_roots: Vec::new(),
+        oss_provider: None,
+        oss_base_url: None,
     };
 
     let cli_overrides = cli_overrides
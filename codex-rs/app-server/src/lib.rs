@@ -28,6 +28,11 @@ mod error_code;
 mod fuzzy_file_search;
 mod message_processor;
 mod models;
+#[cfg(feature = "oss")]
+mod oss;
+#[cfg(not(feature = "oss"))]
+#[path = "oss_unsupported.rs"]
+mod oss;
 mod outgoing_message;
 
 /// Size of the bounded channels used to communicate between tasks. The value
@@ -35,6 +35,7 @@ impl MessageProcessor {
         config: Arc<Config>,
     ) -> Self {
         let outgoing = Arc::new(outgoing);
+        crate::oss::spawn_event_forwarder(outgoing.clone());
         let auth_manager = AuthManager::shared(config.codex_home.clone(), false);
         let conversation_manager = Arc::new(ConversationManager::new(
             auth_manager.clone(),
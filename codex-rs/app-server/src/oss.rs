@@ -0,0 +1,167 @@
+//! Requests about the local `oss` server, so that frontends can drive
+//! local-model setup with their own UI.
+
+use std::sync::Arc;
+
+use codex_app_server_protocol::ControlOssDownloadParams;
+use codex_app_server_protocol::ControlOssDownloadResponse;
+use codex_app_server_protocol::EnsureOssReadyParams;
+use codex_app_server_protocol::EnsureOssReadyResponse;
+use codex_app_server_protocol::JSONRPCErrorError;
+use codex_app_server_protocol::ListLocalModelsResponse;
+use codex_app_server_protocol::OssDownloadAction;
+use codex_app_server_protocol::OssDownloadsResponse;
+use codex_app_server_protocol::OssStatusResponse;
+use codex_app_server_protocol::ServerNotification;
+use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
+use codex_core::DEFAULT_OSS_MODEL;
+use codex_core::config::Config;
+use codex_core::protocol_config_types::OssBackend;
+use codex_ollama::DownloadManager;
+use codex_ollama::JsonProgressReporter;
+use codex_ollama::OllamaClient;
+use codex_ollama::OssError;
+use codex_ollama::PreflightRequirements;
+use codex_protocol::oss::ModelDownloadProgress;
+use codex_protocol::oss::OssEvent;
+use serde_json::json;
+use tokio::sync::broadcast;
+use tokio::sync::mpsc;
+
+use crate::error_code::INTERNAL_ERROR_CODE;
+use crate::outgoing_message::OutgoingMessageSender;
+
+pub(crate) async fn list_local_models(
+    config: &Config,
+) -> Result<ListLocalModelsResponse, JSONRPCErrorError> {
+    let models = codex_ollama::list_local_models(config)
+        .await
+        .map_err(|err| internal_error(format!("failed to list local models: {err}")))?;
+    Ok(ListLocalModelsResponse { models })
+}
+
+pub(crate) async fn oss_status(config: &Config) -> Result<OssStatusResponse, JSONRPCErrorError> {
+    let client = OllamaClient::from_oss_provider(config).map_err(|err| {
+        internal_error(format!(
+            "failed to configure the local server client: {err}"
+        ))
+    })?;
+    let backend = config.oss.provider.unwrap_or(OssBackend::Ollama);
+    let status =
+        codex_ollama::runtime_status(&client, backend, &codex_ollama::oss_state_dir(config))
+            .await
+            .map_err(|err| {
+                internal_error(format!("failed to read the local server status: {err}"))
+            })?;
+    Ok(OssStatusResponse { status })
+}
+
+/// Run the same checks as `codex exec --oss`, sending download progress as
+/// [`ServerNotification::OssDownloadProgress`] ahead of the response.
+pub(crate) async fn ensure_oss_ready(
+    config: &Config,
+    params: EnsureOssReadyParams,
+    outgoing: Arc<OutgoingMessageSender>,
+) -> Result<EnsureOssReadyResponse, JSONRPCErrorError> {
+    let EnsureOssReadyParams {
+        model,
+        allow_download,
+    } = params;
+    let mut target = config.clone();
+    target.model = oss_model(config, model);
+    let requirements = PreflightRequirements {
+        allow_download,
+        capabilities: vec!["tools".to_string()],
+    };
+
+    // The reporter is called synchronously, so progress goes through a
+    // channel to a task that sends it.
+    let (tx, mut rx) = mpsc::unbounded_channel::<ModelDownloadProgress>();
+    let forward = tokio::spawn(async move {
+        while let Some(progress) = rx.recv().await {
+            outgoing
+                .send_server_notification(ServerNotification::OssDownloadProgress(progress))
+                .await;
+        }
+    });
+    let mut reporter = JsonProgressReporter::new(target.model.clone(), move |progress| {
+        let _ = tx.send(progress.clone());
+        Ok(())
+    });
+    let result = codex_ollama::preflight_oss(&target, &requirements, &mut reporter).await;
+    drop(reporter);
+    let _ = forward.await;
+
+    result.map_err(oss_error)?;
+    Ok(EnsureOssReadyResponse {
+        model: target.model,
+    })
+}
+
+pub(crate) fn oss_downloads() -> OssDownloadsResponse {
+    OssDownloadsResponse {
+        downloads: DownloadManager::global().status(),
+    }
+}
+
+pub(crate) fn control_oss_download(params: ControlOssDownloadParams) -> ControlOssDownloadResponse {
+    let ControlOssDownloadParams { id, action } = params;
+    let downloads = DownloadManager::global();
+    let found = match action {
+        OssDownloadAction::Pause => downloads.pause(id),
+        OssDownloadAction::Resume => downloads.resume(id),
+        OssDownloadAction::Cancel => downloads.cancel(id),
+    };
+    ControlOssDownloadResponse { found }
+}
+
+/// Forward every [`OssEvent`] published in this process as
+/// [`ServerNotification::OssEvent`]. Download progress is left out; it goes
+/// to the `ensureOssReady` request that started the download.
+pub(crate) fn spawn_event_forwarder(outgoing: Arc<OutgoingMessageSender>) {
+    let mut events = codex_ollama::subscribe_oss_events();
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(OssEvent::DownloadProgress(_)) => {}
+                Ok(event) => {
+                    outgoing
+                        .send_server_notification(ServerNotification::OssEvent(event))
+                        .await;
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// The requested model, else the session's model when it is local, else
+/// `oss.model`, else the default local model.
+fn oss_model(config: &Config, requested: Option<String>) -> String {
+    requested
+        .or_else(|| {
+            (config.model_provider_id == BUILT_IN_OSS_MODEL_PROVIDER_ID)
+                .then(|| config.model.clone())
+        })
+        .or_else(|| config.oss.model.clone())
+        .unwrap_or_else(|| DEFAULT_OSS_MODEL.to_string())
+}
+
+/// Clients branch on `data.exitCode`, the code `codex exec --oss` exits with
+/// for the same failure.
+fn oss_error(err: OssError) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INTERNAL_ERROR_CODE,
+        message: err.to_string(),
+        data: Some(json!({ "exitCode": err.exit_code() })),
+    }
+}
+
+fn internal_error(message: String) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INTERNAL_ERROR_CODE,
+        message,
+        data: None,
+    }
+}
@@ -0,0 +1,57 @@
+//! Stand-ins for [`crate::oss`] in a build without local backend support:
+//! requests about the local server fail, and there are no downloads.
+
+use std::sync::Arc;
+
+use codex_app_server_protocol::ControlOssDownloadParams;
+use codex_app_server_protocol::ControlOssDownloadResponse;
+use codex_app_server_protocol::EnsureOssReadyParams;
+use codex_app_server_protocol::EnsureOssReadyResponse;
+use codex_app_server_protocol::JSONRPCErrorError;
+use codex_app_server_protocol::ListLocalModelsResponse;
+use codex_app_server_protocol::OssDownloadsResponse;
+use codex_app_server_protocol::OssStatusResponse;
+use codex_core::config::Config;
+
+use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+use crate::outgoing_message::OutgoingMessageSender;
+
+pub(crate) async fn list_local_models(
+    _config: &Config,
+) -> Result<ListLocalModelsResponse, JSONRPCErrorError> {
+    Err(unsupported())
+}
+
+pub(crate) async fn oss_status(_config: &Config) -> Result<OssStatusResponse, JSONRPCErrorError> {
+    Err(unsupported())
+}
+
+pub(crate) async fn ensure_oss_ready(
+    _config: &Config,
+    _params: EnsureOssReadyParams,
+    _outgoing: Arc<OutgoingMessageSender>,
+) -> Result<EnsureOssReadyResponse, JSONRPCErrorError> {
+    Err(unsupported())
+}
+
+pub(crate) fn oss_downloads() -> OssDownloadsResponse {
+    OssDownloadsResponse {
+        downloads: Vec::new(),
+    }
+}
+
+pub(crate) fn control_oss_download(
+    _params: ControlOssDownloadParams,
+) -> ControlOssDownloadResponse {
+    ControlOssDownloadResponse { found: false }
+}
+
+pub(crate) fn spawn_event_forwarder(_outgoing: Arc<OutgoingMessageSender>) {}
+
+fn unsupported() -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INVALID_REQUEST_ERROR_CODE,
+        message: "codex was built without local backend support".to_string(),
+        data: None,
+    }
+}
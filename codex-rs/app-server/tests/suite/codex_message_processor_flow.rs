@@ -481,6 +481,8 @@ async fn test_send_user_turn_updates_sandbox_and_cwd_between_turns() {
             sandbox_policy: SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![first_cwd.clone()],
                 network_access: false,
+                local_network_ports: vec![],
+                local_network_sockets: vec![],
                 exclude_tmpdir_env_var: false,
                 exclude_slash_tmp: false,
             },
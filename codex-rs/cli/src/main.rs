@@ -25,8 +25,26 @@ use std::path::PathBuf;
 use supports_color::Stream;
 
 mod mcp_cmd;
+#[cfg(feature = "oss")]
+mod model_completion;
+#[cfg(feature = "oss")]
+mod models_cmd;
+#[cfg(feature = "oss")]
+mod oss_cmd;
+#[cfg(feature = "oss")]
+mod oss_setup;
+#[cfg(not(feature = "oss"))]
+mod oss_unsupported;
 
 use crate::mcp_cmd::McpCli;
+#[cfg(feature = "oss")]
+use crate::models_cmd::ModelsCli;
+#[cfg(feature = "oss")]
+use crate::oss_cmd::OssCli;
+#[cfg(not(feature = "oss"))]
+use crate::oss_unsupported::ModelsCli;
+#[cfg(not(feature = "oss"))]
+use crate::oss_unsupported::OssCli;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 
@@ -52,6 +70,14 @@ struct MultitoolCli {
     #[clap(flatten)]
     pub feature_toggles: FeatureToggles,
 
+    /// Only use the local model server: no catalog searches, model downloads,
+    /// or update checks. Equivalent to `-c oss.offline=true`.
+    #[arg(long = "offline", global = true)]
+    pub offline: bool,
+
+    #[clap(flatten)]
+    pub oss_output: OssOutputFlags,
+
     #[clap(flatten)]
     interactive: TuiCli,
 
@@ -80,6 +106,12 @@ enum Subcommand {
     /// [experimental] Run the app server.
     AppServer,
 
+    /// [experimental] Manage models on the local open source provider.
+    Models(ModelsCli),
+
+    /// [experimental] Diagnose and manage the local open source provider.
+    Oss(OssCli),
+
     /// Generate shell completion scripts.
     Completion(CompletionCommand),
 
@@ -221,6 +253,8 @@ fn format_exit_messages(exit_info: AppExitInfo, color_enabled: bool) -> Vec<Stri
     let AppExitInfo {
         token_usage,
         conversation_id,
+        #[cfg_attr(not(feature = "oss"), allow(unused_variables))]
+        local_energy,
         ..
     } = exit_info;
 
@@ -232,6 +266,10 @@ fn format_exit_messages(exit_info: AppExitInfo, color_enabled: bool) -> Vec<Stri
         "{}",
         codex_core::protocol::FinalOutput::from(token_usage)
     )];
+    #[cfg(feature = "oss")]
+    if let Some(energy) = &local_energy {
+        lines.push(codex_ollama::format_session_energy(energy));
+    }
 
     if let Some(session_id) = conversation_id {
         let resume_cmd = format!("codex resume {session_id}");
@@ -298,6 +336,33 @@ impl FeatureToggles {
     }
 }
 
+/// Output of the local provider's startup path: server probes, model checks,
+/// and downloads.
+#[derive(Debug, Default, Parser, Clone)]
+struct OssOutputFlags {
+    /// Print only errors while preparing a local model. Equivalent to
+    /// `-c oss.verbosity=quiet`.
+    #[arg(long = "quiet", global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Print every probe, retry, and request made while preparing a local
+    /// model. Equivalent to `-c oss.verbosity=verbose`.
+    #[arg(long = "verbose", global = true)]
+    verbose: bool,
+}
+
+impl OssOutputFlags {
+    fn to_overrides(&self) -> Vec<String> {
+        if self.quiet {
+            vec!["oss.verbosity=quiet".to_string()]
+        } else if self.verbose {
+            vec!["oss.verbosity=verbose".to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
 #[derive(Debug, Parser)]
 struct FeaturesCli {
     #[command(subcommand)]
@@ -340,6 +405,8 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
     let MultitoolCli {
         config_overrides: mut root_config_overrides,
         feature_toggles,
+        offline,
+        oss_output,
         mut interactive,
         subcommand,
     } = MultitoolCli::parse();
@@ -348,6 +415,14 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
     root_config_overrides
         .raw_overrides
         .extend(feature_toggles.to_overrides());
+    if offline {
+        root_config_overrides
+            .raw_overrides
+            .push("oss.offline=true".to_string());
+    }
+    root_config_overrides
+        .raw_overrides
+        .extend(oss_output.to_overrides());
 
     match subcommand {
         None => {
@@ -376,6 +451,17 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
         Some(Subcommand::AppServer) => {
             codex_app_server::run_main(codex_linux_sandbox_exe, root_config_overrides).await?;
         }
+        Some(Subcommand::Models(mut models_cli)) => {
+            prepend_config_flags(
+                &mut models_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            models_cli.run().await?;
+        }
+        Some(Subcommand::Oss(mut oss_cli)) => {
+            prepend_config_flags(&mut oss_cli.config_overrides, root_config_overrides.clone());
+            oss_cli.run().await?;
+        }
         Some(Subcommand::Resume(ResumeCommand {
             session_id,
             last,
@@ -556,6 +642,12 @@ fn merge_resume_cli_flags(interactive: &mut TuiCli, resume_cli: TuiCli) {
     if resume_cli.oss {
         interactive.oss = true;
     }
+    if let Some(oss_provider) = resume_cli.oss_provider {
+        interactive.oss_provider = Some(oss_provider);
+    }
+    if let Some(oss_url) = resume_cli.oss_url {
+        interactive.oss_url = Some(oss_url);
+    }
     if let Some(profile) = resume_cli.config_profile {
         interactive.config_profile = Some(profile);
     }
@@ -596,7 +688,12 @@ fn merge_resume_cli_flags(interactive: &mut TuiCli, resume_cli: TuiCli) {
 fn print_completion(cmd: CompletionCommand) {
     let mut app = MultitoolCli::command();
     let name = "codex";
-    generate(cmd.shell, &mut app, name, &mut std::io::stdout());
+    let mut script = Vec::new();
+    generate(cmd.shell, &mut app, name, &mut script);
+    let script = String::from_utf8_lossy(&script).into_owned();
+    #[cfg(feature = "oss")]
+    let script = model_completion::augment_completion_script(cmd.shell, script);
+    print!("{script}");
 }
 
 #[cfg(test)]
@@ -613,6 +710,8 @@ mod tests {
             config_overrides: root_overrides,
             subcommand,
             feature_toggles: _,
+            offline: _,
+            oss_output: _,
         } = cli;
 
         let Subcommand::Resume(ResumeCommand {
@@ -638,6 +737,7 @@ mod tests {
             conversation_id: conversation
                 .map(ConversationId::from_string)
                 .map(Result::unwrap),
+            local_energy: None,
             update_action: None,
         }
     }
@@ -647,6 +747,7 @@ mod tests {
         let exit_info = AppExitInfo {
             token_usage: TokenUsage::default(),
             conversation_id: None,
+            local_energy: None,
             update_action: None,
         };
         let lines = format_exit_messages(exit_info, false);
@@ -675,6 +776,34 @@ mod tests {
         assert!(lines[1].contains("\u{1b}[36m"));
     }
 
+    #[cfg(feature = "oss")]
+    #[test]
+    fn format_exit_messages_reports_local_energy() {
+        let exit_info = AppExitInfo {
+            local_energy: Some(codex_protocol::oss::SessionEnergy {
+                model: "gpt-oss:20b".to_string(),
+                ended_at: "2026-10-14T12:00:00+00:00".to_string(),
+                input_tokens: 0,
+                output_tokens: 2,
+                busy_ms: 1_000,
+                tokens_per_second: None,
+                average_watts: None,
+                power_source: None,
+                energy_wh: None,
+                cloud_cost_usd: 0.0,
+            }),
+            ..sample_exit_info(None)
+        };
+        let lines = format_exit_messages(exit_info, false);
+        assert_eq!(
+            lines,
+            vec![
+                "Token usage: total=2 input=0 output=2".to_string(),
+                "Local inference: 2 output tokens, energy not measured (set oss.power_watts); $0.00 at cloud prices".to_string(),
+            ]
+        );
+    }
+
     #[test]
     fn resume_model_flag_applies_when_no_root_flags() {
         let interactive = finalize_from_args(["codex", "resume", "-m", "gpt-5-test"].as_ref());
@@ -781,4 +910,25 @@ mod tests {
         assert!(!interactive.resume_last);
         assert_eq!(interactive.resume_session_id, None);
     }
+
+    #[cfg(feature = "oss")]
+    #[test]
+    fn oss_output_flags_become_verbosity_override() {
+        let cli =
+            MultitoolCli::try_parse_from(["codex", "oss", "status", "--verbose"]).expect("parse");
+        assert_eq!(
+            cli.oss_output.to_overrides(),
+            vec!["oss.verbosity=verbose".to_string()]
+        );
+        let cli =
+            MultitoolCli::try_parse_from(["codex", "--quiet", "oss", "status"]).expect("parse");
+        assert_eq!(
+            cli.oss_output.to_overrides(),
+            vec!["oss.verbosity=quiet".to_string()]
+        );
+        assert!(
+            MultitoolCli::try_parse_from(["codex", "--quiet", "--verbose", "oss", "status"])
+                .is_err()
+        );
+    }
 }
@@ -0,0 +1,161 @@
+//! Shell completion of local model names for `-m`/`--model` in `--oss` mode.
+//!
+//! The scripts generated by `clap_complete` are static, so the scripts for
+//! bash, zsh, and fish are extended with a hook that calls the hidden
+//! `codex models complete` command. That command answers from a small cache
+//! so pressing TAB does not have to wait for the local server every time.
+
+use clap_complete::Shell;
+use codex_core::config::Config;
+use codex_ollama::OllamaClient;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+/// How long a cached list of model names is trusted before it is refreshed.
+const CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Upper bound on how long a completion request may wait for the server.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(2);
+
+const CACHE_FILE: &str = "model-names.cache";
+
+fn cache_path(config: &Config) -> PathBuf {
+    codex_ollama::oss_cache_dir(config).join(CACHE_FILE)
+}
+
+fn read_cache(path: &Path) -> Option<(Vec<String>, SystemTime)> {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let contents = std::fs::read_to_string(path).ok()?;
+    let names = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    Some((names, modified))
+}
+
+fn is_fresh(modified: SystemTime, now: SystemTime) -> bool {
+    now.duration_since(modified)
+        .map(|age| age < CACHE_TTL)
+        .unwrap_or(true)
+}
+
+/// Names of the models available on the local server, served from the cache
+/// when it is fresh. A stale cache is still used if the server is unreachable.
+pub async fn cached_model_names(config: &Config) -> Vec<String> {
+    let path = cache_path(config);
+    let cached = read_cache(&path);
+    if let Some((names, modified)) = &cached
+        && is_fresh(*modified, SystemTime::now())
+    {
+        return names.clone();
+    }
+
+    let fetched = tokio::time::timeout(FETCH_TIMEOUT, async {
+        let client = OllamaClient::try_from_oss_provider(config).await?;
+        client.fetch_models().await
+    })
+    .await;
+    match fetched {
+        Ok(Ok(mut names)) => {
+            names.sort();
+            let _ = codex_ollama::write_atomic(&path, names.join("\n"));
+            names
+        }
+        _ => cached.map(|(names, _)| names).unwrap_or_default(),
+    }
+}
+
+/// Drop the cached names, e.g. after a model was pulled or removed.
+pub fn invalidate_model_name_cache(config: &Config) {
+    let _ = std::fs::remove_file(cache_path(config));
+}
+
+/// Add local-model completion for `-m`/`--model` to a generated script. Shells
+/// without a hook are returned unchanged.
+pub fn augment_completion_script(shell: Shell, script: String) -> String {
+    match shell {
+        Shell::Bash => format!("{script}{BASH_HOOK}"),
+        Shell::Zsh => {
+            let script = script.replace(":MODEL:_default'", ":MODEL:_codex_oss_models'");
+            format!("{script}{ZSH_HOOK}")
+        }
+        Shell::Fish => format!("{script}{FISH_HOOK}"),
+        _ => script,
+    }
+}
+
+const BASH_HOOK: &str = r#"
+_codex_with_oss_models() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    if [[ "$prev" == "-m" || "$prev" == "--model" ]] && [[ " ${COMP_WORDS[*]} " == *" --oss "* ]]; then
+        COMPREPLY=( $(compgen -W "$(codex models complete 2>/dev/null)" -- "$cur") )
+        return 0
+    fi
+    _codex "$@"
+}
+complete -F _codex_with_oss_models -o nosort -o bashdefault -o default codex
+"#;
+
+const ZSH_HOOK: &str = r#"
+_codex_oss_models() {
+    if (( ${words[(I)--oss]} )); then
+        local -a models
+        models=(${(f)"$(codex models complete 2>/dev/null)"})
+        _describe 'local model' models
+    else
+        _default
+    fi
+}
+"#;
+
+const FISH_HOOK: &str = r#"
+complete -c codex -n '__fish_contains_opt oss' -s m -l model -f -a '(codex models complete 2>/dev/null)'
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn cache_freshness_respects_ttl() {
+        let now = SystemTime::now();
+        assert!(is_fresh(now - Duration::from_secs(10), now));
+        assert!(!is_fresh(now - CACHE_TTL - Duration::from_secs(1), now));
+        // Clock skew that puts the cache in the future is treated as fresh.
+        assert!(is_fresh(now + Duration::from_secs(10), now));
+    }
+
+    #[test]
+    fn zsh_model_action_is_replaced() {
+        let script = "'-m+[Model the agent should use]:MODEL:_default' \\\n".to_string();
+        let augmented = augment_completion_script(Shell::Zsh, script);
+        assert!(
+            augmented.starts_with("'-m+[Model the agent should use]:MODEL:_codex_oss_models' \\\n")
+        );
+        assert!(augmented.contains("_codex_oss_models() {"));
+    }
+
+    #[test]
+    fn unsupported_shells_are_unchanged() {
+        let script = "Register-ArgumentCompleter".to_string();
+        assert_eq!(
+            augment_completion_script(Shell::PowerShell, script.clone()),
+            script
+        );
+    }
+
+    #[test]
+    fn read_cache_skips_blank_lines() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join(CACHE_FILE);
+        std::fs::write(&path, "gpt-oss:20b\n\nmistral\n").expect("write cache");
+        let (names, _) = read_cache(&path).expect("cache");
+        assert_eq!(names, vec!["gpt-oss:20b", "mistral"]);
+    }
+}
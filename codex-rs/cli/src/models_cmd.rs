@@ -0,0 +1,1041 @@
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::protocol_config_types::OssBackend;
+use codex_ollama::CatalogClient;
+use codex_ollama::CliProgressReporter;
+use codex_ollama::JsonProgressReporter;
+use codex_ollama::MessageId;
+use codex_ollama::OllamaClient;
+use codex_ollama::OssError;
+use codex_ollama::PullEvent;
+use codex_ollama::PullProgressReporter;
+use codex_ollama::RegistryClient;
+use codex_ollama::format_size;
+use codex_ollama::license_accepted;
+use codex_ollama::message;
+use codex_protocol::oss::BlobProblemKind;
+use codex_protocol::oss::CatalogModel;
+use codex_protocol::oss::LocalModel;
+use codex_protocol::oss::ModelDetails;
+use codex_protocol::oss::ModelIntegrityReport;
+use codex_protocol::oss::ModelPullOutcome;
+use codex_protocol::oss::ModelRemoveOutcome;
+use codex_protocol::oss::ModelUpdateStatus;
+use std::io::IsTerminal;
+use std::io::Write;
+
+use crate::model_completion;
+use crate::oss_cmd::ensure_local_backends;
+
+/// [experimental] Manage models served by the local open source provider.
+///
+/// Subcommands:
+/// - `list` — list locally available models (with `--json`)
+/// - `pull` — download a model so it is available offline
+/// - `rm`   — delete a downloaded model
+/// - `show` — print full metadata for one model
+/// - `search` — find downloadable models and their quantizations
+/// - `upgrade` — re-download models that have a newer revision (`--check` only reports)
+/// - `verify` — check downloaded models against their manifests (`--repair` re-downloads)
+#[derive(Debug, clap::Parser)]
+pub struct ModelsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: ModelsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ModelsSubcommand {
+    /// [experimental] List models available on the local server.
+    List(ListArgs),
+
+    /// [experimental] Download a model to the local server.
+    Pull(PullArgs),
+
+    /// [experimental] Delete a model from the local server.
+    #[clap(visible_alias = "remove")]
+    Rm(RmArgs),
+
+    /// [experimental] Show detailed metadata for a local model.
+    Show(ShowArgs),
+
+    /// [experimental] Search the online catalog for models to download.
+    Search(SearchArgs),
+
+    /// [experimental] Re-download local models that were updated upstream.
+    Upgrade(UpgradeArgs),
+
+    /// [experimental] Check downloaded models for missing or corrupted files.
+    Verify(VerifyArgs),
+
+    /// Print local model names for shell completion, one per line.
+    #[clap(hide = true)]
+    Complete,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ListArgs {
+    /// Output the models as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct PullArgs {
+    /// Name of the model to download, e.g. `gpt-oss:20b`.
+    pub model: String,
+
+    /// Quantization variant to download, appended to the model tag
+    /// (e.g. `--quant q4_K_M`).
+    #[arg(long, value_name = "QUANT")]
+    pub quant: Option<String>,
+
+    /// Download without asking for confirmation.
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+
+    /// Print newline-delimited JSON instead of a progress bar: one progress
+    /// event per line while downloading, then the outcome. Requires `--yes`
+    /// when the model is not downloaded yet.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct RmArgs {
+    /// Name of the model to delete.
+    pub model: String,
+
+    /// Delete without asking for confirmation.
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+
+    /// Print the outcome as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ShowArgs {
+    /// Name of the model to inspect.
+    pub model: String,
+
+    /// Output the metadata as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SearchArgs {
+    /// Words to look for in model names, e.g. `gpt-oss` or `qwen coder`.
+    #[arg(required = true, num_args = 1..)]
+    pub query: Vec<String>,
+
+    /// Maximum number of models to show.
+    #[arg(long, default_value_t = 10)]
+    pub limit: usize,
+
+    /// Output the results as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct UpgradeArgs {
+    /// Models to check; defaults to every local model.
+    pub models: Vec<String>,
+
+    /// Only report which models have updates; download nothing.
+    #[arg(long)]
+    pub check: bool,
+
+    /// Re-download without asking for confirmation.
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+
+    /// Print the update status as JSON instead of a table.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct VerifyArgs {
+    /// Models to verify; defaults to every downloaded model.
+    pub models: Vec<String>,
+
+    /// Delete damaged files and download the affected models again.
+    #[arg(long)]
+    pub repair: bool,
+
+    /// Repair without asking for confirmation.
+    #[arg(long, short = 'y', requires = "repair")]
+    pub yes: bool,
+
+    /// Print the integrity reports as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl ModelsCli {
+    pub async fn run(self) -> Result<()> {
+        let ModelsCli {
+            config_overrides,
+            subcommand,
+        } = self;
+        ensure_local_backends()?;
+
+        match subcommand {
+            ModelsSubcommand::List(args) => {
+                run_list(&config_overrides, args).await?;
+            }
+            ModelsSubcommand::Pull(args) => {
+                run_pull(&config_overrides, args).await?;
+            }
+            ModelsSubcommand::Rm(args) => {
+                run_rm(&config_overrides, args).await?;
+            }
+            ModelsSubcommand::Show(args) => {
+                run_show(&config_overrides, args).await?;
+            }
+            ModelsSubcommand::Search(args) => {
+                run_search(&config_overrides, args).await?;
+            }
+            ModelsSubcommand::Upgrade(args) => {
+                run_upgrade(&config_overrides, args).await?;
+            }
+            ModelsSubcommand::Verify(args) => {
+                run_verify(&config_overrides, args).await?;
+            }
+            ModelsSubcommand::Complete => {
+                let config = load_config(&config_overrides).await?;
+                for name in model_completion::cached_model_names(&config).await {
+                    println!("{name}");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn load_config(config_overrides: &CliConfigOverrides) -> Result<Config> {
+    let overrides = config_overrides.parse_overrides().map_err(|e| anyhow!(e))?;
+    Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
+        .await
+        .context("failed to load configuration")
+}
+
+async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) -> Result<()> {
+    let config = load_config(config_overrides).await?;
+    let client = OllamaClient::try_from_oss_provider(&config).await?;
+    let mut models = client
+        .fetch_local_models()
+        .await
+        .context("failed to list local models")?;
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if list_args.json {
+        print_json(&models)?;
+        return Ok(());
+    }
+
+    if models.is_empty() {
+        println!(
+            "No local models found. Try `ollama pull {}`.",
+            codex_ollama::DEFAULT_OSS_MODEL
+        );
+        return Ok(());
+    }
+
+    let rows: Vec<[String; 5]> = models.iter().map(model_row).collect();
+    let headers = ["Name", "Size", "Quant", "Context", "Loaded"];
+    let mut widths = headers.map(str::len);
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.len());
+        }
+    }
+
+    print_row(&headers.map(str::to_string), &widths);
+    for row in &rows {
+        print_row(row, &widths);
+    }
+
+    Ok(())
+}
+
+async fn run_pull(config_overrides: &CliConfigOverrides, pull_args: PullArgs) -> Result<()> {
+    let PullArgs {
+        model,
+        quant,
+        yes,
+        json,
+    } = pull_args;
+    let model = model_with_quant(&model, quant.as_deref());
+
+    let config = load_config(config_overrides).await?;
+    let mut client = OllamaClient::try_from_oss_provider(&config).await?;
+    let models = client
+        .fetch_models()
+        .await
+        .context("failed to list local models")?;
+    if models.iter().any(|m| m == &model) {
+        if json {
+            print_json_line(&ModelPullOutcome {
+                model,
+                already_present: true,
+            })?;
+        } else {
+            println!("Model '{model}' is already available locally.");
+        }
+        return Ok(());
+    }
+
+    ensure_online(&config, "Downloading models")?;
+    if !accept_model_license(&config, &mut client, &model, !yes).await? {
+        println!("Aborted.");
+        return Ok(());
+    }
+    if !yes
+        && !confirm(&message(
+            MessageId::PromptDownloadModel,
+            &[("model", &model)],
+        ))?
+    {
+        println!("Aborted.");
+        return Ok(());
+    }
+
+    model_completion::invalidate_model_name_cache(&config);
+    if json {
+        let mut reporter = JsonProgressReporter::new(model.clone(), |progress| {
+            print_json_line(progress).map_err(std::io::Error::other)
+        });
+        client.pull_with_reporter(&model, &mut reporter).await?;
+        print_json_line(&ModelPullOutcome {
+            model,
+            already_present: false,
+        })?;
+    } else {
+        let mut reporter = CliProgressReporter::from_oss_config(&config.oss);
+        client.pull_with_reporter(&model, &mut reporter).await?;
+        println!("Downloaded model '{model}'.");
+    }
+    Ok(())
+}
+
+async fn run_rm(config_overrides: &CliConfigOverrides, rm_args: RmArgs) -> Result<()> {
+    let RmArgs { model, yes, json } = rm_args;
+
+    let config = load_config(config_overrides).await?;
+    let client = OllamaClient::try_from_oss_provider(&config).await?;
+    let models = client
+        .fetch_local_models()
+        .await
+        .context("failed to list local models")?;
+    let Some(entry) = models.iter().find(|m| m.name == model) else {
+        if json {
+            print_json(&ModelRemoveOutcome {
+                model,
+                removed: false,
+                size_bytes: None,
+            })?;
+        } else {
+            println!("No local model named '{model}' found.");
+        }
+        return Ok(());
+    };
+
+    let size_bytes = entry.size_bytes;
+    let size = size_bytes
+        .map(|bytes| format!(" ({})", format_size(bytes)))
+        .unwrap_or_default();
+    if !yes && !confirm(&format!("Delete model '{model}'{size}?"))? {
+        println!("Aborted.");
+        return Ok(());
+    }
+
+    client.delete_model(&model).await?;
+    model_completion::invalidate_model_name_cache(&config);
+    if json {
+        print_json(&ModelRemoveOutcome {
+            model,
+            removed: true,
+            size_bytes,
+        })?;
+    } else {
+        println!("Deleted model '{model}'{size}.");
+    }
+    Ok(())
+}
+
+async fn run_show(config_overrides: &CliConfigOverrides, show_args: ShowArgs) -> Result<()> {
+    let ShowArgs { model, json } = show_args;
+
+    let config = load_config(config_overrides).await?;
+    let client = OllamaClient::try_from_oss_provider(&config).await?;
+    let mut details = client
+        .fetch_model_details(&model)
+        .await
+        .with_context(|| format!("failed to look up model '{model}'"))?;
+    details.last_used =
+        codex_ollama::model_last_used(&codex_ollama::oss_state_dir(&config), &model)
+            .map(|at| at.to_rfc3339());
+
+    if json {
+        print_json(&details)?;
+    } else {
+        for line in format_model_details(&details) {
+            println!("{line}");
+        }
+    }
+    Ok(())
+}
+
+fn format_model_details(details: &ModelDetails) -> Vec<String> {
+    let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    let capabilities = if details.capabilities.is_empty() {
+        "-".to_string()
+    } else {
+        details.capabilities.join(", ")
+    };
+    let fields = [
+        ("Name", details.name.clone()),
+        ("Architecture", or_dash(details.architecture.clone())),
+        ("Parameters", or_dash(details.parameter_size.clone())),
+        ("Quantization", or_dash(details.quantization.clone())),
+        ("Size", or_dash(details.size_bytes.map(format_size))),
+        (
+            "Context",
+            or_dash(details.context_length.map(|len| format!("{len} tokens"))),
+        ),
+        ("Capabilities", capabilities),
+        (
+            "Loaded",
+            if details.loaded { "yes" } else { "no" }.to_string(),
+        ),
+        (
+            "Last used",
+            details
+                .last_used
+                .clone()
+                .unwrap_or_else(|| "never (by Codex)".to_string()),
+        ),
+        ("File", or_dash(details.file_path.clone())),
+    ];
+    let width = fields
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0)
+        + 1;
+    fields
+        .into_iter()
+        .map(|(label, value)| format!("{:<width$} {value}", format!("{label}:")))
+        .collect()
+}
+
+async fn run_search(config_overrides: &CliConfigOverrides, search_args: SearchArgs) -> Result<()> {
+    let SearchArgs { query, limit, json } = search_args;
+    let query = query.join(" ");
+
+    let config = load_config(config_overrides).await?;
+    ensure_online(&config, "Searching the model catalog")?;
+
+    let models = CatalogClient::from_oss_config(&config)?
+        .search(&query, limit)
+        .await
+        .with_context(|| format!("failed to search the catalog for '{query}'"))?;
+
+    if json {
+        print_json(&models)?;
+        return Ok(());
+    }
+
+    if models.is_empty() {
+        println!("No downloadable models match '{query}'.");
+        return Ok(());
+    }
+    for line in format_catalog_models(&models) {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+async fn run_upgrade(
+    config_overrides: &CliConfigOverrides,
+    upgrade_args: UpgradeArgs,
+) -> Result<()> {
+    let UpgradeArgs {
+        models,
+        check,
+        yes,
+        json,
+    } = upgrade_args;
+
+    let config = load_config(config_overrides).await?;
+    ensure_online(&config, "Checking for model updates")?;
+    let client = OllamaClient::try_from_oss_provider(&config).await?;
+    let mut statuses =
+        codex_ollama::check_model_updates(&client, &RegistryClient::default(), &models)
+            .await
+            .context("failed to check for model updates")?;
+    statuses.sort_by(|a, b| a.model.cmp(&b.model));
+    let outdated: Vec<&str> = statuses
+        .iter()
+        .filter(|status| status.update_available)
+        .map(|status| status.model.as_str())
+        .collect();
+
+    if json {
+        print_json(&statuses)?;
+    } else {
+        for line in format_update_statuses(&statuses) {
+            println!("{line}");
+        }
+    }
+    if outdated.is_empty() {
+        return Ok(());
+    }
+    if check {
+        if !json {
+            println!();
+            println!(
+                "{} update(s) available. Run `codex models upgrade` to download them.",
+                outdated.len()
+            );
+        }
+        return Ok(());
+    }
+
+    let prompt = format!("Re-download {}?", outdated.join(", "));
+    if !yes && !confirm(&prompt)? {
+        if !json {
+            println!("Aborted.");
+        }
+        return Ok(());
+    }
+    for model in outdated {
+        if json {
+            client
+                .pull_with_reporter(model, &mut SilentProgressReporter)
+                .await?;
+        } else {
+            let mut reporter = CliProgressReporter::from_oss_config(&config.oss);
+            client.pull_with_reporter(model, &mut reporter).await?;
+            println!("Updated model '{model}'.");
+        }
+    }
+    Ok(())
+}
+
+async fn run_verify(config_overrides: &CliConfigOverrides, verify_args: VerifyArgs) -> Result<()> {
+    let VerifyArgs {
+        models,
+        repair,
+        yes,
+        json,
+    } = verify_args;
+
+    let config = load_config(config_overrides).await?;
+    if let Some(backend @ (OssBackend::LmStudio | OssBackend::Generic)) = config.oss.provider {
+        anyhow::bail!(
+            "`codex models verify` checks Ollama's model store; {backend} is not supported."
+        );
+    }
+    let models_dir = codex_ollama::ollama_models_dir()
+        .ok_or_else(|| anyhow!("could not determine the Ollama models directory"))?;
+    if !json {
+        eprintln!("Verifying models in {}...", models_dir.display());
+    }
+    let reports = {
+        let models_dir = models_dir.clone();
+        tokio::task::spawn_blocking(move || codex_ollama::verify_models(&models_dir, &models))
+            .await?
+            .context("failed to verify models")?
+    };
+
+    if json {
+        print_json(&reports)?;
+    } else {
+        for line in format_integrity_reports(&reports) {
+            println!("{line}");
+        }
+    }
+    let damaged: Vec<&ModelIntegrityReport> = reports
+        .iter()
+        .filter(|report| !report.problems.is_empty())
+        .collect();
+    if damaged.is_empty() {
+        return Ok(());
+    }
+    if !repair {
+        if !json {
+            println!();
+            println!(
+                "{} model(s) are damaged. Run `codex models verify --repair` to download them again.",
+                damaged.len()
+            );
+        }
+        std::process::exit(1);
+    }
+
+    ensure_online(&config, "Re-downloading damaged models")?;
+    let names: Vec<&str> = damaged.iter().map(|report| report.model.as_str()).collect();
+    if !yes
+        && !confirm(&format!(
+            "Delete the damaged files and re-download {}?",
+            names.join(", ")
+        ))?
+    {
+        if !json {
+            println!("Aborted.");
+        }
+        return Ok(());
+    }
+    let client = OllamaClient::try_from_oss_provider(&config).await?;
+    for report in damaged {
+        codex_ollama::remove_damaged_blobs(&models_dir, report)
+            .with_context(|| format!("failed to remove damaged files of '{}'", report.model))?;
+        if json {
+            client
+                .pull_with_reporter(&report.model, &mut SilentProgressReporter)
+                .await?;
+        } else {
+            let mut reporter = CliProgressReporter::from_oss_config(&config.oss);
+            client
+                .pull_with_reporter(&report.model, &mut reporter)
+                .await?;
+            println!("Repaired model '{}'.", report.model);
+        }
+    }
+    Ok(())
+}
+
+fn format_integrity_reports(reports: &[ModelIntegrityReport]) -> Vec<String> {
+    let width = reports
+        .iter()
+        .map(|report| report.model.len())
+        .max()
+        .unwrap_or(0);
+    let mut lines = Vec::new();
+    for report in reports {
+        if report.problems.is_empty() {
+            lines.push(format!("{:<width$}  ok", report.model));
+            continue;
+        }
+        lines.push(format!(
+            "{:<width$}  {} damaged file(s)",
+            report.model,
+            report.problems.len()
+        ));
+        for problem in &report.problems {
+            let size =
+                |bytes: Option<u64>| bytes.map(format_size).unwrap_or_else(|| "?".to_string());
+            let state = match problem.kind {
+                BlobProblemKind::Missing => "missing".to_string(),
+                BlobProblemKind::Incomplete => match problem.actual_size {
+                    Some(actual) => format!(
+                        "incomplete ({} of {})",
+                        format_size(actual),
+                        size(problem.expected_size)
+                    ),
+                    None => "incomplete (partial download)".to_string(),
+                },
+                BlobProblemKind::ChecksumMismatch => "checksum mismatch".to_string(),
+                BlobProblemKind::InvalidManifest => "unreadable manifest".to_string(),
+            };
+            let name = if problem.digest.is_empty() {
+                problem.path.as_str()
+            } else {
+                short_digest(&problem.digest)
+            };
+            lines.push(format!("  {name}  {state}"));
+        }
+    }
+    lines
+}
+
+/// "sha256:0123456789abcdef..." -> "sha256:0123456789ab".
+fn short_digest(digest: &str) -> &str {
+    let end = digest.find(':').map_or(0, |i| i + 1) + 12;
+    digest.get(..end).unwrap_or(digest)
+}
+
+fn format_update_statuses(statuses: &[ModelUpdateStatus]) -> Vec<String> {
+    let width = statuses
+        .iter()
+        .map(|status| status.model.len())
+        .max()
+        .unwrap_or(0);
+    statuses
+        .iter()
+        .map(|status| {
+            let state = match (&status.error, status.update_available) {
+                (Some(err), _) => format!("not checked: {err}"),
+                (None, true) => "update available".to_string(),
+                (None, false) => "up to date".to_string(),
+            };
+            format!("{:<width$}  {state}", status.model)
+        })
+        .collect()
+}
+
+fn format_catalog_models(models: &[CatalogModel]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for model in models {
+        let downloads = model
+            .downloads
+            .map(|n| format!("  ({n} downloads)"))
+            .unwrap_or_default();
+        lines.push(format!("{}{downloads}", model.name));
+        if let Some(license) = &model.license {
+            lines.push(format!("  license: {}", license.key()));
+        }
+        if model.variants.is_empty() {
+            lines.push("  no GGUF files found".to_string());
+        }
+        let width = model
+            .variants
+            .iter()
+            .map(|variant| variant.quant.len())
+            .max()
+            .unwrap_or(0);
+        for variant in &model.variants {
+            let size = variant
+                .size_bytes
+                .map(format_size)
+                .unwrap_or_else(|| "-".to_string());
+            lines.push(format!("  {:<width$}  {size}", variant.quant));
+        }
+    }
+    if let Some(example) = models.iter().find(|model| !model.variants.is_empty()) {
+        lines.push(String::new());
+        lines.push(format!(
+            "Download one with `codex models pull {} --quant {}`.",
+            example.name, example.variants[0].quant
+        ));
+    }
+    lines
+}
+
+fn ensure_online(config: &Config, action: &str) -> Result<()> {
+    if config.oss.offline {
+        return Err(codex_ollama::offline_error(action).into());
+    }
+    Ok(())
+}
+
+fn print_json<T: serde::Serialize>(value: &T) -> Result<()> {
+    let output = serde_json::to_string_pretty(value)?;
+    println!("{output}");
+    Ok(())
+}
+
+/// Print `value` as a single line, for newline-delimited JSON streams.
+fn print_json_line<T: serde::Serialize>(value: &T) -> Result<()> {
+    let line = serde_json::to_string(value)?;
+    println!("{line}");
+    Ok(())
+}
+
+/// Discards pull progress so `--json` output stays machine-readable.
+struct SilentProgressReporter;
+
+impl PullProgressReporter for SilentProgressReporter {
+    fn on_event(&mut self, _event: &PullEvent) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Apply a `--quant` selection to a model reference. Quantized variants are
+/// published as tag suffixes, so `llama3.1:8b` + `q4_K_M` becomes
+/// `llama3.1:8b-q4_K_M` and an untagged `mistral` becomes `mistral:q4_K_M`.
+pub(crate) fn model_with_quant(model: &str, quant: Option<&str>) -> String {
+    match quant.map(str::trim).filter(|q| !q.is_empty()) {
+        Some(quant) if model.contains(':') => format!("{model}-{quant}"),
+        Some(quant) => format!("{model}:{quant}"),
+        None => model.to_string(),
+    }
+}
+
+/// Ask a yes/no question on stderr. Refuses (rather than blocking) when stdin
+/// is not interactive so scripts must pass `--yes` explicitly.
+/// Show the license of catalog model `model` and make sure it is accepted:
+/// already listed in `oss.accepted_licenses`, or accepted at a prompt (when
+/// `interactive` and there is a terminal) and recorded there. Returns
+/// `false` when the user declines.
+pub(crate) async fn accept_model_license(
+    config: &Config,
+    client: &mut OllamaClient,
+    model: &str,
+    interactive: bool,
+) -> Result<bool> {
+    let Some(license) = client
+        .model_license(model)
+        .await
+        .with_context(|| format!("failed to look up the license of '{model}'"))?
+    else {
+        return Ok(true);
+    };
+    let key = license.key().to_string();
+    eprintln!(
+        "{}",
+        message(
+            MessageId::LicenseInfo,
+            &[("model", model), ("license", &key)]
+        )
+    );
+    if let Some(url) = &license.url {
+        eprintln!("{}", message(MessageId::LicenseTerms, &[("url", url)]));
+    }
+    if license_accepted(&config.oss.accepted_licenses, &license) {
+        return Ok(true);
+    }
+    if !interactive || !std::io::stdin().is_terminal() {
+        return Err(OssError::LicenseNotAccepted {
+            model: model.to_string(),
+            license: key,
+            url: license.url,
+        }
+        .into());
+    }
+    if !confirm(&message(
+        MessageId::PromptAcceptLicense,
+        &[("license", &key)],
+    ))? {
+        return Ok(false);
+    }
+    codex_core::config::add_oss_accepted_license(&config.codex_home, &key)?;
+    client.accept_license(&key);
+    Ok(true)
+}
+
+pub(crate) fn confirm(prompt: &str) -> Result<bool> {
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!("{prompt} Refusing to prompt without a terminal; pass --yes to continue.");
+    }
+    eprint!("{prompt} [y/N] ");
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes" | "Yes" | "YES"))
+}
+
+fn model_row(model: &LocalModel) -> [String; 5] {
+    [
+        model.name.clone(),
+        model
+            .size_bytes
+            .map(format_size)
+            .unwrap_or_else(|| "-".to_string()),
+        model
+            .quantization
+            .clone()
+            .unwrap_or_else(|| "-".to_string()),
+        model
+            .context_length
+            .map(|len| len.to_string())
+            .unwrap_or_else(|| "-".to_string()),
+        if model.loaded { "yes" } else { "no" }.to_string(),
+    ]
+}
+
+fn print_row(row: &[String; 5], widths: &[usize; 5]) {
+    println!(
+        "{name:<name_w$}  {size:<size_w$}  {quant:<quant_w$}  {context:<context_w$}  {loaded:<loaded_w$}",
+        name = row[0].as_str(),
+        size = row[1].as_str(),
+        quant = row[2].as_str(),
+        context = row[3].as_str(),
+        loaded = row[4].as_str(),
+        name_w = widths[0],
+        size_w = widths[1],
+        quant_w = widths[2],
+        context_w = widths[3],
+        loaded_w = widths[4],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::oss::CatalogVariant;
+    use codex_protocol::oss::ModelLicense;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn model_with_quant_appends_tag_suffix() {
+        assert_eq!(model_with_quant("mistral", None), "mistral");
+        assert_eq!(
+            model_with_quant("mistral", Some("q4_K_M")),
+            "mistral:q4_K_M"
+        );
+        assert_eq!(
+            model_with_quant("llama3.1:8b", Some("q4_K_M")),
+            "llama3.1:8b-q4_K_M"
+        );
+        assert_eq!(model_with_quant("llama3.1:8b", Some(" ")), "llama3.1:8b");
+    }
+
+    #[test]
+    fn update_statuses_show_state_per_model() {
+        let status = |model: &str, update_available: bool, error: Option<&str>| ModelUpdateStatus {
+            model: model.to_string(),
+            local_digest: Some("aa".to_string()),
+            remote_digest: None,
+            update_available,
+            error: error.map(str::to_string),
+        };
+        assert_eq!(
+            format_update_statuses(&[
+                status("gpt-oss:20b", false, None),
+                status("mistral:latest", true, None),
+                status(
+                    "mine:latest",
+                    false,
+                    Some("'mine:latest' is no longer published")
+                ),
+            ]),
+            vec![
+                "gpt-oss:20b     up to date",
+                "mistral:latest  update available",
+                "mine:latest     not checked: 'mine:latest' is no longer published",
+            ]
+        );
+    }
+
+    #[test]
+    fn integrity_reports_list_damaged_files() {
+        use codex_protocol::oss::BlobProblem;
+
+        let problem = |kind, actual_size| BlobProblem {
+            digest: "sha256:0123456789abcdef0123".to_string(),
+            path: "/models/blobs/sha256-0123456789abcdef0123".to_string(),
+            kind,
+            expected_size: Some(2 * 1024 * 1024 * 1024),
+            actual_size,
+        };
+        let reports = vec![
+            ModelIntegrityReport {
+                model: "gpt-oss:20b".to_string(),
+                manifest_path: "/models/manifests/a".to_string(),
+                problems: vec![],
+            },
+            ModelIntegrityReport {
+                model: "mistral:latest".to_string(),
+                manifest_path: "/models/manifests/b".to_string(),
+                problems: vec![
+                    problem(BlobProblemKind::Incomplete, Some(1024 * 1024 * 1024)),
+                    problem(BlobProblemKind::Missing, None),
+                ],
+            },
+        ];
+        assert_eq!(
+            format_integrity_reports(&reports),
+            vec![
+                "gpt-oss:20b     ok",
+                "mistral:latest  2 damaged file(s)",
+                "  sha256:0123456789ab  incomplete (1.00 GB of 2.00 GB)",
+                "  sha256:0123456789ab  missing",
+            ]
+        );
+    }
+
+    #[test]
+    fn catalog_models_list_variants_and_pull_hint() {
+        let models = vec![CatalogModel {
+            name: "hf.co/org/gpt-oss-GGUF".to_string(),
+            repo: "org/gpt-oss-GGUF".to_string(),
+            downloads: Some(1200),
+            last_modified: None,
+            license: Some(ModelLicense {
+                id: "apache-2.0".to_string(),
+                name: None,
+                url: None,
+            }),
+            variants: vec![
+                CatalogVariant {
+                    quant: "Q4_K_M".to_string(),
+                    file: "gpt-oss-Q4_K_M.gguf".to_string(),
+                    size_bytes: Some(2 * 1024 * 1024 * 1024),
+                },
+                CatalogVariant {
+                    quant: "F16".to_string(),
+                    file: "gpt-oss-F16.gguf".to_string(),
+                    size_bytes: None,
+                },
+            ],
+        }];
+        assert_eq!(
+            format_catalog_models(&models),
+            vec![
+                "hf.co/org/gpt-oss-GGUF  (1200 downloads)",
+                "  license: apache-2.0",
+                "  Q4_K_M  2.00 GB",
+                "  F16     -",
+                "",
+                "Download one with `codex models pull hf.co/org/gpt-oss-GGUF --quant Q4_K_M`.",
+            ]
+        );
+    }
+
+    #[test]
+    fn model_details_lists_every_field() {
+        let details = ModelDetails {
+            name: "gpt-oss:20b".to_string(),
+            architecture: Some("gptoss".to_string()),
+            quantization: Some("MXFP4".to_string()),
+            parameter_size: Some("20.9B".to_string()),
+            file_path: None,
+            size_bytes: Some(13_780_173_839),
+            context_length: Some(131072),
+            capabilities: vec!["completion".to_string(), "tools".to_string()],
+            loaded: false,
+            last_used: None,
+        };
+        assert_eq!(
+            format_model_details(&details),
+            vec![
+                "Name:         gpt-oss:20b",
+                "Architecture: gptoss",
+                "Parameters:   20.9B",
+                "Quantization: MXFP4",
+                "Size:         12.83 GB",
+                "Context:      131072 tokens",
+                "Capabilities: completion, tools",
+                "Loaded:       no",
+                "Last used:    never (by Codex)",
+                "File:         -",
+            ]
+        );
+    }
+
+    #[test]
+    fn model_row_fills_missing_fields_with_dashes() {
+        let model = LocalModel {
+            name: "mistral".to_string(),
+            size_bytes: None,
+            quantization: None,
+            parameter_size: None,
+            context_length: None,
+            loaded: true,
+        };
+        assert_eq!(
+            model_row(&model),
+            [
+                "mistral".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                "yes".to_string(),
+            ]
+        );
+    }
+}
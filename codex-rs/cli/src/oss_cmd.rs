@@ -0,0 +1,1430 @@
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use codex_common::CliConfigOverrides;
+use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config_types::OssConfigToml;
+use codex_core::config_types::OssVerbosity;
+use codex_core::oss_credentials;
+use codex_core::oss_credentials::OssTokenStore;
+use codex_core::protocol_config_types::OssBackend;
+use codex_ollama::CheckStatus;
+use codex_ollama::CliProgressReporter;
+use codex_ollama::GenerateOptions;
+use codex_ollama::LogFilter;
+use codex_ollama::LogLevel;
+use codex_ollama::OllamaClient;
+use codex_ollama::OssReadiness;
+use codex_ollama::ReadinessCheck;
+use codex_ollama::ServeOptions;
+use codex_protocol::oss::BenchRun;
+use codex_protocol::oss::ConformanceReport;
+use codex_protocol::oss::EnergyTotals;
+use codex_protocol::oss::EvalReport;
+use codex_protocol::oss::ModelUnloadOutcome;
+use codex_protocol::oss::OssEnvironmentManifest;
+use codex_protocol::oss::OssImportOutcome;
+use codex_protocol::oss::OssRuntimeStatus;
+use codex_protocol::oss::ServerStatus;
+use codex_protocol::oss::WarmupReport;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::models_cmd::confirm;
+use crate::oss_setup;
+
+/// How long `serve start` waits for a freshly spawned server to respond.
+const SERVER_START_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// [experimental] Inspect and manage the local open source ("oss") provider.
+///
+/// Subcommands:
+/// - `doctor` — diagnose why `--oss` is not working (with `--json`)
+/// - `serve`  — start, stop, restart, or inspect the local server
+/// - `bench`  — measure prefill/generation throughput of a local model
+/// - `eval`   — score a local model on bundled coding-agent tasks
+/// - `conformance` — report which OpenAI-compatible features a server lacks
+/// - `status` — show loaded models, their memory use, server uptime, and the energy of local sessions
+/// - `warmup` — load a model ahead of time so the next session starts instantly
+/// - `logs`   — print or follow the server log, filtered by level
+/// - `unload` — free memory by unloading one or all loaded models
+/// - `config` — interactively pick a backend and model and save them to config.toml
+///   (`--schema` prints the JSON schema of the `[oss]` table)
+/// - `export` — write the provider, models, digests, and sampling parameters to a manifest
+/// - `import` — download the models of a manifest and adopt its provider settings
+/// - `login`  — store the token of a remote server in the OS keyring
+/// - `clean-cache` — delete cached data such as model name lists
+#[derive(Debug, clap::Parser)]
+pub struct OssCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Print the external commands (`lms`, `ollama serve`, ...) that would
+    /// run instead of running them.
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    #[command(subcommand)]
+    pub subcommand: OssSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum OssSubcommand {
+    /// [experimental] Run the `--oss` readiness checks and print remediation steps.
+    Doctor(DoctorArgs),
+
+    /// [experimental] Manage the local `ollama serve` process.
+    Serve(ServeArgs),
+
+    /// [experimental] Measure throughput of a local model across context sizes.
+    Bench(BenchArgs),
+
+    /// [experimental] Score a local model on tool calls, patches, and diffs.
+    Eval(EvalArgs),
+
+    /// [experimental] Check which OpenAI-compatible API features Codex relies on a server supports.
+    Conformance(ConformanceArgs),
+
+    /// [experimental] Show loaded models, memory footprint, server uptime, and
+    /// the energy and equivalent cloud cost of local sessions.
+    Status(StatusArgs),
+
+    /// [experimental] Load a model and run a short generation so the next session starts fast.
+    Warmup(WarmupArgs),
+
+    /// [experimental] Print the local server's log.
+    Logs(LogsArgs),
+
+    /// [experimental] Unload a model (or every loaded model) to free VRAM.
+    Unload(UnloadArgs),
+
+    /// [experimental] Interactively set up the local provider and save it to config.toml.
+    Config(ConfigArgs),
+
+    /// [experimental] Write a portable manifest of the local provider and its models.
+    Export(ExportArgs),
+
+    /// [experimental] Provision this machine from a manifest written by `codex oss export`.
+    Import(ImportArgs),
+
+    /// [experimental] Store the token a remote server expects in the OS keyring.
+    Login(LoginArgs),
+
+    /// [experimental] Delete the OSS cache directory and print the space freed.
+    CleanCache,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct DoctorArgs {
+    /// Model to check for, defaults to the `--oss` default model.
+    #[arg(long, short = 'm')]
+    pub model: Option<String>,
+
+    /// Output the report as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct BenchArgs {
+    /// Model to benchmark, defaults to the `--oss` default model.
+    #[arg(long, short = 'm')]
+    pub model: Option<String>,
+
+    /// Comma-separated prompt sizes to test, in approximate tokens.
+    #[arg(long = "context-sizes", value_delimiter = ',', value_name = "TOKENS")]
+    pub context_sizes: Vec<u32>,
+
+    /// Number of tokens to generate for each run.
+    #[arg(long = "max-tokens", default_value_t = codex_ollama::DEFAULT_BENCH_MAX_TOKENS)]
+    pub max_tokens: u32,
+
+    /// Output the results as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct EvalArgs {
+    /// Model to evaluate, defaults to the `--oss` default model.
+    #[arg(long, short = 'm')]
+    pub model: Option<String>,
+
+    /// Output the report as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ConformanceArgs {
+    /// Base URL of the server, e.g. "http://localhost:8000/v1"; defaults to
+    /// the configured `oss` provider.
+    #[arg(long = "base-url", value_name = "URL")]
+    pub base_url: Option<String>,
+
+    /// Model to run the chat checks with, defaults to the first one the
+    /// server lists.
+    #[arg(long, short = 'm')]
+    pub model: Option<String>,
+
+    /// Output the report as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct StatusArgs {
+    /// Output the status as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct WarmupArgs {
+    /// Model to load, defaults to the `--oss` default model.
+    #[arg(long, short = 'm')]
+    pub model: Option<String>,
+
+    /// How long the server should keep the model loaded afterwards, e.g.
+    /// `30m` or `2h`. Defaults to the server's own setting.
+    #[arg(long = "keep-alive", value_name = "DURATION")]
+    pub keep_alive: Option<String>,
+
+    /// Output the timings as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct LogsArgs {
+    /// Only show lines at this level or above: debug, info, warn, or error.
+    #[arg(long, value_parser = parse_log_level, default_value = "debug")]
+    pub level: LogLevel,
+
+    /// Number of lines from the end of the log to print.
+    #[arg(long, short = 'n', default_value_t = 50)]
+    pub lines: usize,
+
+    /// Keep printing new lines as they are written.
+    #[arg(long, short = 'f')]
+    pub follow: bool,
+
+    /// Read this log file instead of looking for the server's log.
+    #[arg(long, value_name = "FILE")]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct UnloadArgs {
+    /// Model to unload; unloads every loaded model when omitted.
+    pub model: Option<String>,
+
+    /// Print the unloaded models as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ConfigArgs {
+    /// Print the JSON schema of the `[oss]` table instead of running the
+    /// wizard, for editors to validate and complete config.toml.
+    #[arg(long)]
+    pub schema: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ExportArgs {
+    /// Write the manifest to this file instead of stdout.
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ImportArgs {
+    /// Manifest written by `codex oss export`.
+    #[arg(value_name = "FILE")]
+    pub path: PathBuf,
+
+    /// Download and save the settings without asking for confirmation.
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct LoginArgs {
+    /// Base URL of the server, e.g. `https://gpu-box.lan:1234/v1`.
+    #[arg(value_name = "URL")]
+    pub url: String,
+
+    /// Read the token from stdin instead of prompting for it.
+    #[arg(long)]
+    pub with_token: bool,
+
+    /// Forget the stored token instead of saving one.
+    #[arg(long, conflicts_with = "with_token")]
+    pub logout: bool,
+}
+
+fn parse_log_level(value: &str) -> Result<LogLevel, String> {
+    LogLevel::parse(value)
+        .ok_or_else(|| format!("unknown level `{value}`; expected debug, info, warn, or error"))
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ServeArgs {
+    #[command(subcommand)]
+    pub action: ServeAction,
+
+    /// Print the resulting server status as JSON.
+    #[arg(long, global = true)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ServeAction {
+    /// Start the server in the background.
+    Start(ServeStartArgs),
+
+    /// Stop a server previously started with `codex oss serve start`.
+    Stop,
+
+    /// Report whether the server is running.
+    Status,
+
+    /// Stop and start the server again.
+    Restart(ServeStartArgs),
+}
+
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ServeStartArgs {
+    /// Port for the server to listen on.
+    #[arg(long)]
+    pub port: Option<u16>,
+
+    /// Allow cross-origin requests from any origin (sets `OLLAMA_ORIGINS=*`).
+    #[arg(long)]
+    pub cors: bool,
+}
+
+impl OssCli {
+    pub async fn run(self) -> Result<()> {
+        let OssCli {
+            config_overrides,
+            dry_run,
+            subcommand,
+        } = self;
+        codex_ollama::set_print_only(dry_run);
+        // Checking a server's API or storing its token needs no backend
+        // support.
+        if !matches!(
+            subcommand,
+            OssSubcommand::Conformance(_) | OssSubcommand::Login(_) | OssSubcommand::CleanCache
+        ) {
+            ensure_local_backends()?;
+        }
+
+        match subcommand {
+            OssSubcommand::Doctor(args) => {
+                run_doctor(&config_overrides, args).await?;
+            }
+            OssSubcommand::Serve(args) => {
+                run_serve(&config_overrides, args).await?;
+            }
+            OssSubcommand::Bench(args) => {
+                run_bench(&config_overrides, args).await?;
+            }
+            OssSubcommand::Eval(args) => {
+                run_eval(&config_overrides, args).await?;
+            }
+            OssSubcommand::Conformance(args) => {
+                run_conformance(&config_overrides, args).await?;
+            }
+            OssSubcommand::Status(args) => {
+                run_status(&config_overrides, args).await?;
+            }
+            OssSubcommand::Warmup(args) => {
+                run_warmup(&config_overrides, args).await?;
+            }
+            OssSubcommand::Logs(args) => {
+                run_logs(&config_overrides, args).await?;
+            }
+            OssSubcommand::Unload(args) => {
+                run_unload(&config_overrides, args).await?;
+            }
+            OssSubcommand::Config(ConfigArgs { schema: true }) => {
+                let schema = serde_json::to_string_pretty(&OssConfigToml::json_schema())?;
+                println!("{schema}");
+            }
+            OssSubcommand::Config(ConfigArgs { schema: false }) => {
+                oss_setup::run_setup_wizard(&config_overrides).await?;
+            }
+            OssSubcommand::Export(args) => {
+                run_export(&config_overrides, args).await?;
+            }
+            OssSubcommand::Import(args) => {
+                run_import(&config_overrides, args).await?;
+            }
+            OssSubcommand::Login(args) => {
+                run_login(&config_overrides, args).await?;
+            }
+            OssSubcommand::CleanCache => {
+                let config = load_oss_config(&config_overrides, None).await?;
+                let freed = codex_ollama::clean_oss_cache(&config)?;
+                println!(
+                    "Freed {} from {}.",
+                    codex_ollama::format_size(freed),
+                    codex_ollama::oss_cache_dir(&config).display()
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(any(feature = "lmstudio", feature = "ollama"))]
+pub(crate) fn ensure_local_backends() -> Result<()> {
+    Ok(())
+}
+
+/// Fail cleanly in a build compiled without any local backend.
+#[cfg(not(any(feature = "lmstudio", feature = "ollama")))]
+pub(crate) fn ensure_local_backends() -> Result<()> {
+    Err(anyhow!("codex was built without local backend support"))
+}
+
+/// Load the configuration the same way `codex --oss` would.
+async fn load_oss_config(
+    config_overrides: &CliConfigOverrides,
+    model: Option<String>,
+) -> Result<Config> {
+    let overrides = config_overrides.parse_overrides().map_err(|e| anyhow!(e))?;
+    let config_overrides = ConfigOverrides {
+        model,
+        model_provider: Some(BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string()),
+        ..Default::default()
+    };
+    Config::load_with_cli_overrides(overrides, config_overrides)
+        .await
+        .context("failed to load configuration")
+}
+
+async fn run_doctor(config_overrides: &CliConfigOverrides, args: DoctorArgs) -> Result<()> {
+    let DoctorArgs { model, json } = args;
+    let config = load_oss_config(config_overrides, model).await?;
+    let report = codex_ollama::check_oss_readiness(&config).await;
+
+    if json {
+        let output = serde_json::to_string_pretty(&report)?;
+        println!("{output}");
+    } else {
+        for line in format_doctor_report(&report) {
+            println!("{line}");
+        }
+    }
+
+    if !report.is_ready() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+async fn run_serve(config_overrides: &CliConfigOverrides, args: ServeArgs) -> Result<()> {
+    let ServeArgs { action, json } = args;
+    let config = load_oss_config(config_overrides, None).await?;
+    let state_dir = codex_ollama::oss_state_dir(&config);
+    let quiet = json || config.oss.verbosity == OssVerbosity::Quiet;
+
+    let client = match action {
+        ServeAction::Start(start) => serve_start(&config, &state_dir, start, quiet).await?,
+        ServeAction::Stop => serve_stop(&config, &state_dir, quiet).await?,
+        ServeAction::Status => {
+            let client = serve_client(&config, None)?;
+            if !json {
+                print_server_status(&codex_ollama::server_status(&client, &state_dir).await);
+            }
+            client
+        }
+        ServeAction::Restart(start) => {
+            serve_stop(&config, &state_dir, quiet).await?;
+            serve_start(&config, &state_dir, start, quiet).await?
+        }
+    };
+
+    if json {
+        let output =
+            serde_json::to_string_pretty(&codex_ollama::server_status(&client, &state_dir).await)?;
+        println!("{output}");
+    }
+    Ok(())
+}
+
+/// Client for the server targeted by `--port`, or the configured provider.
+fn serve_client(config: &Config, port: Option<u16>) -> Result<OllamaClient> {
+    let verbose = config.oss.verbosity == OssVerbosity::Verbose;
+    match port {
+        Some(port) => Ok(
+            OllamaClient::from_host_root(format!("http://127.0.0.1:{port}")).with_verbose(verbose),
+        ),
+        None => {
+            let base_url = config
+                .model_providers
+                .get(BUILT_IN_OSS_MODEL_PROVIDER_ID)
+                .and_then(|provider| provider.base_url.clone())
+                .ok_or_else(|| {
+                    anyhow!("built-in provider {BUILT_IN_OSS_MODEL_PROVIDER_ID} has no base_url")
+                })?;
+            Ok(
+                OllamaClient::from_host_root(codex_ollama::base_url_to_host_root(&base_url))
+                    .with_verbose(verbose),
+            )
+        }
+    }
+}
+
+/// Start the server unless one is already reachable. Returns a client for it.
+async fn serve_start(
+    config: &Config,
+    state_dir: &Path,
+    start: ServeStartArgs,
+    quiet: bool,
+) -> Result<OllamaClient> {
+    let client = serve_client(config, start.port)?;
+    if client.fetch_version().await.is_ok() {
+        if !quiet {
+            println!(
+                "Ollama server is already running at {}.",
+                client.host_root()
+            );
+        }
+        return Ok(client);
+    }
+
+    let binary = codex_ollama::find_ollama_binary().ok_or_else(|| {
+        anyhow!(
+            "ollama executable not found. Install it from https://github.com/ollama/ollama?tab=readme-ov-file#ollama"
+        )
+    })?;
+    let options = ServeOptions {
+        port: start.port,
+        cors: start.cors,
+    };
+    if config.oss.verbosity == OssVerbosity::Verbose {
+        eprintln!("[oss] spawning `{} serve`", binary.display());
+    }
+    let Some(pid) = codex_ollama::spawn_server(&binary, &options, state_dir)
+        .with_context(|| format!("failed to start {}", binary.display()))?
+    else {
+        return Ok(client);
+    };
+    let log_path = codex_ollama::server_log_path(state_dir);
+    client
+        .wait_until_reachable(SERVER_START_TIMEOUT)
+        .await
+        .with_context(|| {
+            format!(
+                "server (pid {pid}) did not become reachable; see {}",
+                log_path.display()
+            )
+        })?;
+    if !quiet {
+        println!(
+            "Started Ollama server (pid {pid}) at {}. Logs: {}",
+            client.host_root(),
+            log_path.display()
+        );
+    }
+    Ok(client)
+}
+
+async fn serve_stop(config: &Config, state_dir: &Path, quiet: bool) -> Result<OllamaClient> {
+    let stopped = codex_ollama::stop_managed_server(state_dir).await?;
+    if !quiet {
+        match stopped {
+            Some(pid) => println!("Stopped Ollama server (pid {pid})."),
+            None => println!("No Ollama server started by Codex is running."),
+        }
+    }
+    serve_client(config, None)
+}
+
+fn print_server_status(status: &ServerStatus) {
+    let managed = status
+        .managed_pid
+        .map(|pid| format!(" (started by Codex, pid {pid})"))
+        .unwrap_or_default();
+    match &status.version {
+        Some(version) => {
+            println!(
+                "Ollama {version} is running at {}{managed}.",
+                status.host_root
+            );
+        }
+        None => {
+            println!("No Ollama server is reachable at {}.", status.host_root);
+        }
+    }
+}
+
+async fn run_bench(config_overrides: &CliConfigOverrides, args: BenchArgs) -> Result<()> {
+    let BenchArgs {
+        model,
+        context_sizes,
+        max_tokens,
+        json,
+    } = args;
+    let config = load_oss_config(config_overrides, model).await?;
+    let client = OllamaClient::try_from_oss_provider(&config).await?;
+    let context_sizes = if context_sizes.is_empty() {
+        codex_ollama::DEFAULT_BENCH_CONTEXT_SIZES.to_vec()
+    } else {
+        context_sizes
+    };
+
+    if !json {
+        eprintln!(
+            "Benchmarking '{}' ({} runs, {max_tokens} tokens each)...",
+            config.model,
+            context_sizes.len()
+        );
+    }
+    let results = codex_ollama::run_benchmark(&client, &config.model, &context_sizes, max_tokens)
+        .await
+        .with_context(|| format!("benchmark of '{}' failed", config.model))?;
+    let rows: Vec<BenchRun> = results.iter().map(BenchRun::from).collect();
+
+    if json {
+        let output = serde_json::to_string_pretty(&rows)?;
+        println!("{output}");
+    } else {
+        for line in format_bench_table(&rows) {
+            println!("{line}");
+        }
+    }
+    Ok(())
+}
+
+async fn run_eval(config_overrides: &CliConfigOverrides, args: EvalArgs) -> Result<()> {
+    let EvalArgs { model, json } = args;
+    let config = load_oss_config(config_overrides, model).await?;
+    let client = OllamaClient::try_from_oss_provider(&config).await?;
+
+    if !json {
+        eprintln!("Evaluating '{}' on coding-agent tasks...", config.model);
+    }
+    let report = codex_ollama::run_eval(&client, &config.model).await;
+
+    if json {
+        let output = serde_json::to_string_pretty(&report)?;
+        println!("{output}");
+    } else {
+        for line in format_eval_report(&report) {
+            println!("{line}");
+        }
+    }
+    Ok(())
+}
+
+async fn run_conformance(
+    config_overrides: &CliConfigOverrides,
+    args: ConformanceArgs,
+) -> Result<()> {
+    let ConformanceArgs {
+        base_url,
+        model,
+        json,
+    } = args;
+    let config = load_oss_config(config_overrides, None).await?;
+    let client = match base_url {
+        Some(base_url) => OllamaClient::builder()
+            .base_url(base_url)
+            .openai_compatible(true)
+            .verbose(config.oss.verbosity == OssVerbosity::Verbose)
+            .build()?,
+        None => OllamaClient::from_oss_provider(&config)?,
+    };
+    let report = codex_ollama::run_conformance(&client, model.as_deref()).await;
+
+    if json {
+        let output = serde_json::to_string_pretty(&report)?;
+        println!("{output}");
+    } else {
+        for line in format_conformance_report(&report) {
+            println!("{line}");
+        }
+    }
+
+    if !report.passed() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+async fn run_status(config_overrides: &CliConfigOverrides, args: StatusArgs) -> Result<()> {
+    let config = load_oss_config(config_overrides, None).await?;
+    let state_dir = codex_ollama::oss_state_dir(&config);
+    let client = serve_client(&config, None)?;
+
+    let status = codex_ollama::runtime_status(&client, OssBackend::Ollama, &state_dir)
+        .await
+        .context("failed to query loaded models")?;
+    let energy = codex_ollama::energy_totals(&state_dir);
+
+    if args.json {
+        let mut output = serde_json::to_value(&status)?;
+        output["energy"] = serde_json::to_value(&energy)?;
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        for line in format_runtime_status(&status)
+            .into_iter()
+            .chain(format_energy_totals(&energy))
+        {
+            println!("{line}");
+        }
+    }
+    Ok(())
+}
+
+async fn run_unload(config_overrides: &CliConfigOverrides, args: UnloadArgs) -> Result<()> {
+    let UnloadArgs { model, json } = args;
+    let config = load_oss_config(config_overrides, None).await?;
+    let unloaded = codex_ollama::unload_models(&config, model.as_deref())
+        .await
+        .context("failed to unload models")?;
+
+    if json {
+        let output = serde_json::to_string_pretty(&ModelUnloadOutcome { unloaded })?;
+        println!("{output}");
+    } else if unloaded.is_empty() {
+        println!("No models are loaded.");
+    } else {
+        for model in &unloaded {
+            println!("Unloaded {model}.");
+        }
+    }
+    Ok(())
+}
+
+async fn run_export(config_overrides: &CliConfigOverrides, args: ExportArgs) -> Result<()> {
+    let ExportArgs { output } = args;
+    let config = load_oss_config(config_overrides, None).await?;
+    let manifest = codex_ollama::export_environment(&config)
+        .await
+        .context("failed to export the local models")?;
+    let json = serde_json::to_string_pretty(&manifest)?;
+    match output {
+        Some(path) => {
+            codex_ollama::write_atomic(&path, format!("{json}\n"))
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            eprintln!(
+                "Exported {} model(s) to {}.",
+                manifest.models.len(),
+                path.display()
+            );
+        }
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+async fn run_import(config_overrides: &CliConfigOverrides, args: ImportArgs) -> Result<()> {
+    let ImportArgs { path, yes } = args;
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let manifest: OssEnvironmentManifest = serde_json::from_str(&text)
+        .with_context(|| format!("{} is not a `codex oss export` manifest", path.display()))?;
+
+    let overrides = config_overrides.parse_overrides().map_err(|e| anyhow!(e))?;
+    let config = Config::load_with_cli_overrides(
+        overrides,
+        ConfigOverrides {
+            model: Some(manifest.default_model.clone()),
+            model_provider: Some(BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string()),
+            oss_provider: manifest.provider,
+            ..Default::default()
+        },
+    )
+    .await
+    .context("failed to load configuration")?;
+
+    let names: Vec<&str> = manifest.models.iter().map(|m| m.name.as_str()).collect();
+    if !yes
+        && !names.is_empty()
+        && !confirm(&format!(
+            "Download any of {} missing from this machine?",
+            names.join(", ")
+        ))?
+    {
+        println!("Aborted.");
+        return Ok(());
+    }
+    let mut reporter = CliProgressReporter::from_oss_config(&config.oss);
+    let outcome = codex_ollama::import_environment(&config, &manifest, &mut reporter)
+        .await
+        .context("failed to import the manifest")?;
+    crate::model_completion::invalidate_model_name_cache(&config);
+    for line in format_import_outcome(&outcome) {
+        println!("{line}");
+    }
+
+    let mut settings: Vec<(&[&str], &str)> = Vec::new();
+    let provider = manifest.provider.map(|backend| backend.to_string());
+    if let Some(provider) = provider.as_deref() {
+        settings.push((&["oss", "provider"], provider));
+    }
+    settings.push((&["oss", "model"], manifest.default_model.as_str()));
+    if yes
+        || confirm(&format!(
+            "Make '{}' the default `--oss` model in config.toml?",
+            manifest.default_model
+        ))?
+    {
+        codex_core::config_edit::persist_overrides(&config.codex_home, None, &settings)
+            .await
+            .context("failed to update config.toml")?;
+        println!("Saved the provider settings to config.toml.");
+    }
+    if !outcome.unavailable.is_empty() {
+        anyhow::bail!(
+            "{} model(s) are not available on this server; download them there and run `codex oss import` again.",
+            outcome.unavailable.len()
+        );
+    }
+    Ok(())
+}
+
+async fn run_login(config_overrides: &CliConfigOverrides, args: LoginArgs) -> Result<()> {
+    let LoginArgs {
+        url,
+        with_token,
+        logout,
+    } = args;
+    let config = load_oss_config(config_overrides, None).await?;
+
+    if logout {
+        let removed = oss_credentials::delete_oss_token(&config.codex_home, &url)?;
+        if removed {
+            println!("Removed the token for {url}.");
+        } else {
+            println!("No token was stored for {url}.");
+        }
+        return Ok(());
+    }
+
+    let token = if with_token {
+        let mut token = String::new();
+        std::io::stdin()
+            .read_to_string(&mut token)
+            .context("failed to read the token from stdin")?;
+        token
+    } else {
+        eprint!("Token for {url}: ");
+        std::io::stderr().flush()?;
+        let mut token = String::new();
+        std::io::stdin()
+            .read_line(&mut token)
+            .context("failed to read the token")?;
+        token
+    };
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(anyhow!("no token given"));
+    }
+
+    match oss_credentials::save_oss_token(&config.codex_home, &url, token)? {
+        OssTokenStore::Keyring => println!("Saved the token for {url} to the OS keyring."),
+        OssTokenStore::File => println!(
+            "The OS keyring is unavailable; saved the token for {url} to {}.",
+            oss_credentials::fallback_file_path(&config.codex_home).display()
+        ),
+    }
+    Ok(())
+}
+
+fn format_import_outcome(outcome: &OssImportOutcome) -> Vec<String> {
+    let OssImportOutcome {
+        present,
+        pulled,
+        unavailable,
+        mismatched,
+    } = outcome;
+    let mut lines = Vec::new();
+    for (label, models) in [
+        ("Already present", present),
+        ("Downloaded", pulled),
+        ("Not available", unavailable),
+        ("Differs from the manifest", mismatched),
+    ] {
+        if !models.is_empty() {
+            lines.push(format!("{label}: {}", models.join(", ")));
+        }
+    }
+    if lines.is_empty() {
+        lines.push("The manifest lists no models.".to_string());
+    }
+    lines
+}
+
+async fn run_logs(config_overrides: &CliConfigOverrides, args: LogsArgs) -> Result<()> {
+    let LogsArgs {
+        level,
+        lines,
+        follow,
+        path,
+    } = args;
+    let path = match path {
+        Some(path) => path,
+        None => {
+            let config = load_oss_config(config_overrides, None).await?;
+            let state_dir = codex_ollama::oss_state_dir(&config);
+            codex_ollama::server_log_candidates(&state_dir, config.oss.provider)
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow!(NO_SERVER_LOG_HINT))?
+        }
+    };
+
+    let (tail, offset) = codex_ollama::read_last_lines(&path, lines)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    eprintln!("==> {} <==", path.display());
+    let mut filter = LogFilter::new(level);
+    for line in tail.iter().filter(|line| filter.accepts(line)) {
+        println!("{line}");
+    }
+
+    if follow {
+        codex_ollama::follow_log(&path, offset, |line| {
+            if filter.accepts(line) {
+                println!("{line}");
+            }
+        })
+        .await
+        .with_context(|| format!("failed to follow {}", path.display()))?;
+    }
+    Ok(())
+}
+
+const NO_SERVER_LOG_HINT: &str = "could not find the server log. Servers started with `codex oss serve start` log to ollama-serve.log in the OSS state directory (~/.local/state/codex/oss on Linux); a systemd-managed Ollama logs to the journal (`journalctl -u ollama -f`). Pass --path to read a specific file.";
+
+/// Prompt used for warm-up; the output is discarded.
+const WARMUP_PROMPT: &str = "Reply with OK.";
+
+async fn run_warmup(config_overrides: &CliConfigOverrides, args: WarmupArgs) -> Result<()> {
+    let WarmupArgs {
+        model,
+        keep_alive,
+        json,
+    } = args;
+    let config = load_oss_config(config_overrides, model).await?;
+    let client = OllamaClient::try_from_oss_provider(&config).await?;
+
+    let options = GenerateOptions {
+        num_predict: Some(1),
+        keep_alive,
+        ..Default::default()
+    };
+    let stats = client
+        .generate_with_stats(&config.model, WARMUP_PROMPT, &options)
+        .await
+        .with_context(|| format!("failed to warm up '{}'", config.model))?;
+    let millis = |d: Duration| u64::try_from(d.as_millis()).unwrap_or(u64::MAX);
+    let report = WarmupReport {
+        model: config.model.clone(),
+        load_ms: millis(stats.load_duration),
+        time_to_first_token_ms: stats.time_to_first_token.map(millis),
+        total_ms: millis(stats.total_duration),
+    };
+
+    if json {
+        let output = serde_json::to_string_pretty(&report)?;
+        println!("{output}");
+    } else {
+        println!("{}", format_warmup_report(&report));
+    }
+    Ok(())
+}
+
+fn format_warmup_report(report: &WarmupReport) -> String {
+    let seconds = |ms: u64| format!("{:.1}s", ms as f64 / 1000.0);
+    let load = if report.load_ms == 0 {
+        "already loaded".to_string()
+    } else {
+        format!("loaded in {}", seconds(report.load_ms))
+    };
+    let first_token = report
+        .time_to_first_token_ms
+        .map(|ms| format!("first token after {}", seconds(ms)))
+        .unwrap_or_else(|| "no tokens generated".to_string());
+    format!("Warmed up '{}': {load}, {first_token}.", report.model)
+}
+
+fn format_runtime_status(status: &OssRuntimeStatus) -> Vec<String> {
+    let server = &status.server;
+    let Some(version) = &server.version else {
+        return vec![format!(
+            "No Ollama server is reachable at {}.",
+            server.host_root
+        )];
+    };
+
+    let mut lines = vec![format!("Ollama {version} at {}", server.host_root)];
+    lines.push(format!(
+        "  Uptime:          {}",
+        status
+            .uptime_secs
+            .map(format_duration_secs)
+            .unwrap_or_else(|| "unknown (not started by Codex)".to_string())
+    ));
+    lines.push(format!(
+        "  Active requests: {}",
+        status
+            .active_requests
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "not reported".to_string())
+    ));
+
+    if status.loaded_models.is_empty() {
+        lines.push("  No models loaded.".to_string());
+        return lines;
+    }
+
+    let total: u64 = status
+        .loaded_models
+        .iter()
+        .filter_map(|m| m.size_bytes)
+        .sum();
+    lines.push(format!(
+        "  Loaded models ({} in memory):",
+        codex_ollama::format_size(total)
+    ));
+    let name_width = status
+        .loaded_models
+        .iter()
+        .map(|m| m.name.len())
+        .max()
+        .unwrap_or(0);
+    for model in &status.loaded_models {
+        let size = model
+            .size_bytes
+            .map(codex_ollama::format_size)
+            .unwrap_or_else(|| "-".to_string());
+        let placement = match (model.size_bytes, model.vram_bytes) {
+            (Some(size), Some(vram)) if size > 0 => {
+                let gpu = vram.min(size) * 100 / size;
+                format!("{gpu}% GPU")
+            }
+            _ => "-".to_string(),
+        };
+        lines.push(format!(
+            "    {name:<name_width$}  {size:>9}  {placement}",
+            name = model.name,
+        ));
+    }
+    lines
+}
+
+/// Energy and equivalent cloud cost of the local sessions recorded so far;
+/// nothing before the first one ended.
+fn format_energy_totals(totals: &EnergyTotals) -> Vec<String> {
+    if totals.sessions == 0 {
+        return Vec::new();
+    }
+    let sessions = match totals.sessions {
+        1 => "1 session".to_string(),
+        n => format!("{n} sessions"),
+    };
+    let energy = match totals.sessions_without_energy {
+        0 => format!("about {:.1} Wh", totals.energy_wh),
+        n if n == totals.sessions => "not measured (set oss.power_watts)".to_string(),
+        n => format!("about {:.1} Wh, {n} not measured", totals.energy_wh),
+    };
+    let mut lines = vec![
+        format!("  Local inference ({sessions}):"),
+        format!(
+            "    Tokens:       {} in, {} out",
+            totals.input_tokens, totals.output_tokens
+        ),
+        format!("    Energy:       {energy}"),
+        format!(
+            "    Cloud cost:   ${:.2} at cloud prices",
+            totals.cloud_cost_usd
+        ),
+    ];
+    if let Some(last) = &totals.last_session {
+        lines.push(format!(
+            "    Last session: {}",
+            codex_ollama::format_session_energy(last)
+        ));
+    }
+    lines
+}
+
+/// Render a duration in seconds as e.g. "2h 05m", "4m 10s", or "42s".
+fn format_duration_secs(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds:02}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+fn format_bench_table(rows: &[BenchRun]) -> Vec<String> {
+    let rate = |value: Option<f64>| value.map_or_else(|| "-".to_string(), |v| format!("{v:.1}"));
+    let mut lines = vec![format!(
+        "{:>8}  {:>13}  {:>13}  {:>10}  {:>10}",
+        "Context", "Prompt tokens", "Prefill tok/s", "Gen tok/s", "TTFT"
+    )];
+    for row in rows {
+        let ttft = row
+            .time_to_first_token_ms
+            .map_or_else(|| "-".to_string(), |ms| format!("{ms} ms"));
+        lines.push(format!(
+            "{:>8}  {:>13}  {:>13}  {:>10}  {:>10}",
+            row.context_size,
+            row.prompt_tokens,
+            rate(row.prefill_tokens_per_sec),
+            rate(row.generation_tokens_per_sec),
+            ttft,
+        ));
+    }
+    lines
+}
+
+fn format_eval_report(report: &EvalReport) -> Vec<String> {
+    let id_width = report
+        .tasks
+        .iter()
+        .map(|task| task.id.len())
+        .max()
+        .unwrap_or(0);
+    let mut lines = Vec::new();
+    for task in &report.tasks {
+        let mark = if task.passed { "pass" } else { "FAIL" };
+        let mut line = format!(
+            "{mark}  {:<id_width$}  {:>6.1} s",
+            task.id,
+            task.elapsed_ms as f64 / 1000.0
+        );
+        if let Some(failure) = &task.failure {
+            let first_line = failure.lines().next().unwrap_or_default();
+            line.push_str(&format!("  {first_line}"));
+        }
+        lines.push(line);
+    }
+    let passed = report.tasks.iter().filter(|task| task.passed).count();
+    let score = report.score_percent();
+    let verdict = match score {
+        80.. => "usable with Codex",
+        50..80 => "usable for simple tasks; expect some failed tool calls or edits",
+        _ => "not recommended for Codex",
+    };
+    lines.push(String::new());
+    lines.push(format!(
+        "{}: {passed}/{} tasks passed ({score}%), {verdict}.",
+        report.model,
+        report.tasks.len()
+    ));
+    lines
+}
+
+pub(crate) fn format_doctor_report(report: &OssReadiness) -> Vec<String> {
+    let mut lines = vec![format!(
+        "Checking local OSS setup for model '{}' at {}",
+        report.model, report.host_root
+    )];
+    lines.extend(format_checks(&report.checks));
+    lines.extend(format_remediation_steps(
+        &report.remediation_steps(),
+        "Everything looks good.",
+    ));
+    lines
+}
+
+fn format_conformance_report(report: &ConformanceReport) -> Vec<String> {
+    let mut lines = vec![match &report.model {
+        Some(model) => format!(
+            "Checking the OpenAI-compatible API at {} with model '{model}'",
+            report.base_url
+        ),
+        None => format!("Checking the OpenAI-compatible API at {}", report.base_url),
+    }];
+    lines.extend(format_checks(&report.checks));
+    lines.extend(format_remediation_steps(
+        &report.remediation_steps(),
+        "The server supports everything Codex relies on.",
+    ));
+    lines
+}
+
+fn format_checks(checks: &[ReadinessCheck]) -> Vec<String> {
+    let name_width = checks
+        .iter()
+        .map(|check| check.name.len())
+        .max()
+        .unwrap_or(0);
+    checks
+        .iter()
+        .map(|check| {
+            let status = match check.status {
+                CheckStatus::Ok => "ok",
+                CheckStatus::Warn => "warn",
+                CheckStatus::Fail => "FAIL",
+                CheckStatus::Skipped => "skip",
+            };
+            format!(
+                "  [{status:<4}] {name:<name_width$}  {detail}",
+                name = check.name,
+                detail = check.detail,
+            )
+        })
+        .collect()
+}
+
+fn format_remediation_steps(steps: &[&str], all_good: &str) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    if steps.is_empty() {
+        lines.push(all_good.to_string());
+    } else {
+        lines.push("To fix:".to_string());
+        for (i, step) in steps.iter().enumerate() {
+            lines.push(format!("  {}. {step}", i + 1));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn import_outcome_lists_each_group() {
+        let outcome = OssImportOutcome {
+            present: vec!["gpt-oss:20b".to_string()],
+            pulled: vec!["qwen3:8b".to_string(), "mistral:latest".to_string()],
+            unavailable: Vec::new(),
+            mismatched: vec!["gpt-oss:20b".to_string()],
+        };
+        assert_eq!(
+            format_import_outcome(&outcome),
+            vec![
+                "Already present: gpt-oss:20b",
+                "Downloaded: qwen3:8b, mistral:latest",
+                "Differs from the manifest: gpt-oss:20b",
+            ]
+        );
+    }
+
+    #[test]
+    fn warmup_report_mentions_load_and_first_token() {
+        let mut report = WarmupReport {
+            model: "gpt-oss:20b".to_string(),
+            load_ms: 8_240,
+            time_to_first_token_ms: Some(9_050),
+            total_ms: 9_100,
+        };
+        assert_eq!(
+            format_warmup_report(&report),
+            "Warmed up 'gpt-oss:20b': loaded in 8.2s, first token after 9.1s."
+        );
+        report.load_ms = 0;
+        report.time_to_first_token_ms = Some(120);
+        assert_eq!(
+            format_warmup_report(&report),
+            "Warmed up 'gpt-oss:20b': already loaded, first token after 0.1s."
+        );
+    }
+
+    #[test]
+    fn runtime_status_lists_loaded_models() {
+        let status = OssRuntimeStatus {
+            server: ServerStatus {
+                host_root: "http://localhost:11434".to_string(),
+                running: true,
+                version: Some("0.11.4".to_string()),
+                managed_pid: Some(42),
+            },
+            uptime_secs: Some(3_900),
+            active_requests: None,
+            loaded_models: vec![codex_protocol::oss::LoadedModel {
+                name: "gpt-oss:20b".to_string(),
+                size_bytes: Some(4 * 1024 * 1024 * 1024),
+                vram_bytes: Some(3 * 1024 * 1024 * 1024),
+                context_length: Some(8192),
+                expires_at: None,
+            }],
+        };
+        assert_eq!(
+            format_runtime_status(&status),
+            vec![
+                "Ollama 0.11.4 at http://localhost:11434",
+                "  Uptime:          1h 05m",
+                "  Active requests: not reported",
+                "  Loaded models (4.00 GB in memory):",
+                "    gpt-oss:20b    4.00 GB  75% GPU",
+            ]
+        );
+    }
+
+    #[test]
+    fn energy_totals_sum_up_the_recorded_sessions() {
+        assert_eq!(
+            format_energy_totals(&EnergyTotals::default()),
+            Vec::<String>::new()
+        );
+
+        let totals = EnergyTotals {
+            sessions: 3,
+            input_tokens: 120_000,
+            output_tokens: 8_000,
+            energy_wh: 12.34,
+            sessions_without_energy: 1,
+            cloud_cost_usd: 0.23,
+            last_session: None,
+        };
+        assert_eq!(
+            format_energy_totals(&totals),
+            vec![
+                "  Local inference (3 sessions):",
+                "    Tokens:       120000 in, 8000 out",
+                "    Energy:       about 12.3 Wh, 1 not measured",
+                "    Cloud cost:   $0.23 at cloud prices",
+            ]
+        );
+    }
+
+    #[test]
+    fn bench_table_formats_rates_and_missing_values() {
+        let rows = vec![
+            BenchRun {
+                context_size: 512,
+                prompt_tokens: 530,
+                completion_tokens: 128,
+                prefill_tokens_per_sec: Some(1234.56),
+                generation_tokens_per_sec: Some(42.0),
+                time_to_first_token_ms: Some(310),
+            },
+            BenchRun {
+                context_size: 8192,
+                prompt_tokens: 0,
+                completion_tokens: 0,
+                prefill_tokens_per_sec: None,
+                generation_tokens_per_sec: None,
+                time_to_first_token_ms: None,
+            },
+        ];
+        assert_eq!(
+            format_bench_table(&rows),
+            vec![
+                " Context  Prompt tokens  Prefill tok/s   Gen tok/s        TTFT",
+                "     512            530         1234.6        42.0      310 ms",
+                "    8192              0              -           -           -",
+            ]
+        );
+    }
+
+    #[test]
+    fn eval_report_lists_tasks_and_a_verdict() {
+        let task = |id: &str, failure: Option<&str>| codex_protocol::oss::EvalTaskResult {
+            id: id.to_string(),
+            category: codex_protocol::oss::EvalCategory::Patch,
+            passed: failure.is_none(),
+            failure: failure.map(str::to_string),
+            elapsed_ms: 2_340,
+        };
+        let report = EvalReport {
+            model: "qwen3:8b".to_string(),
+            tasks: vec![
+                task("tool_call_git_log", None),
+                task(
+                    "patch_rename",
+                    Some("the lines to replace are not in the file:\ndef greet(name):"),
+                ),
+            ],
+        };
+        assert_eq!(
+            format_eval_report(&report),
+            vec![
+                "pass  tool_call_git_log     2.3 s",
+                "FAIL  patch_rename          2.3 s  the lines to replace are not in the file:",
+                "",
+                "qwen3:8b: 1/2 tasks passed (50%), usable for simple tasks; expect some failed tool calls or edits.",
+            ]
+        );
+    }
+
+    #[test]
+    fn doctor_report_numbers_remediation_steps() {
+        let report = OssReadiness {
+            host_root: "http://localhost:11434".to_string(),
+            model: "gpt-oss:20b".to_string(),
+            checks: vec![
+                ReadinessCheck {
+                    name: "server".to_string(),
+                    status: CheckStatus::Fail,
+                    detail: "No Ollama server reachable".to_string(),
+                    remediation: Some("Start the server with `ollama serve`.".to_string()),
+                },
+                ReadinessCheck {
+                    name: "memory".to_string(),
+                    status: CheckStatus::Ok,
+                    detail: "32.00 GB of memory".to_string(),
+                    remediation: None,
+                },
+            ],
+        };
+        assert_eq!(
+            format_doctor_report(&report),
+            vec![
+                "Checking local OSS setup for model 'gpt-oss:20b' at http://localhost:11434",
+                "  [FAIL] server  No Ollama server reachable",
+                "  [ok  ] memory  32.00 GB of memory",
+                "",
+                "To fix:",
+                "  1. Start the server with `ollama serve`.",
+            ]
+        );
+    }
+
+    #[test]
+    fn conformance_report_lists_gaps() {
+        let report = ConformanceReport {
+            base_url: "http://localhost:8000/v1".to_string(),
+            model: Some("qwen3-8b".to_string()),
+            checks: vec![
+                ReadinessCheck {
+                    name: "streaming".to_string(),
+                    status: CheckStatus::Ok,
+                    detail: "3 chunks".to_string(),
+                    remediation: None,
+                },
+                ReadinessCheck {
+                    name: "usage".to_string(),
+                    status: CheckStatus::Warn,
+                    detail: "no chunk reports `usage`".to_string(),
+                    remediation: Some("Honor `stream_options.include_usage`.".to_string()),
+                },
+            ],
+        };
+        assert_eq!(
+            format_conformance_report(&report),
+            vec![
+                "Checking the OpenAI-compatible API at http://localhost:8000/v1 with model 'qwen3-8b'",
+                "  [ok  ] streaming  3 chunks",
+                "  [warn] usage      no chunk reports `usage`",
+                "",
+                "To fix:",
+                "  1. Honor `stream_options.include_usage`.",
+            ]
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//! `codex oss config`: a guided first-run setup of the local provider that
+//! ends with an `[oss]` table written to `config.toml`.
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use codex_common::CliConfigOverrides;
+use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config_types::OssConfigToml;
+use codex_core::protocol_config_types::OssBackend;
+use codex_ollama::CliProgressReporter;
+use codex_ollama::DetectedBackend;
+use codex_ollama::MessageId;
+use codex_ollama::OllamaClient;
+use codex_ollama::message;
+use std::io::IsTerminal;
+use std::io::Write;
+
+use crate::models_cmd::accept_model_license;
+use crate::models_cmd::confirm;
+use crate::models_cmd::model_with_quant;
+use crate::oss_cmd::format_doctor_report;
+
+pub async fn run_setup_wizard(config_overrides: &CliConfigOverrides) -> Result<()> {
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "`codex oss config` is interactive; edit the [oss] table in config.toml instead."
+        );
+    }
+
+    println!("Looking for local model servers...");
+    let detected = codex_ollama::detect_backends().await;
+    for (i, found) in detected.iter().enumerate() {
+        println!("  {}) {}", i + 1, describe_backend(found));
+    }
+    let default = default_backend_index(&detected);
+    let choice = prompt_line("Which server should Codex use?", &(default + 1).to_string())?;
+    let found = choice
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|i| detected.get(i))
+        .ok_or_else(|| anyhow!("'{choice}' is not one of the listed servers"))?;
+    let backend = found.backend;
+    if !found.running {
+        let hint = match backend {
+            OssBackend::Ollama => "Start it with `codex oss serve start`",
+            _ => "Start its server",
+        };
+        println!(
+            "{backend} is not answering at {}. {hint}, then run `codex oss config` again.",
+            found.host_root
+        );
+        return Ok(());
+    }
+
+    let config = load_setup_config(config_overrides, backend, None).await?;
+    let mut client = OllamaClient::try_from_oss_provider(&config).await?;
+    let mut models = match backend {
+        OssBackend::Ollama => client
+            .fetch_models()
+            .await
+            .context("failed to list local models")?,
+        _ => client
+            .fetch_openai_model_ids()
+            .await
+            .context("failed to list models")?,
+    };
+    models.sort();
+    println!();
+    if models.is_empty() {
+        println!("No models are available on the server yet.");
+    }
+    for (i, model) in models.iter().enumerate() {
+        println!("  {}) {model}", i + 1);
+    }
+    let default_model = config.model.clone();
+    let answer = prompt_line(
+        "Model to use (number, or a name to download)",
+        &default_model,
+    )?;
+    let mut model = resolve_model_choice(&answer, &models);
+
+    if !models.contains(&model) {
+        if backend != OssBackend::Ollama {
+            anyhow::bail!(
+                "'{model}' is not available on the {backend} server. Download it there first, then run `codex oss config` again."
+            );
+        }
+        let quant = prompt_line("Quantization, e.g. q4_K_M (empty for the default)", "")?;
+        model = model_with_quant(&model, Some(&quant));
+        if config.oss.offline {
+            return Err(codex_ollama::offline_error("Downloading models").into());
+        }
+        if !accept_model_license(&config, &mut client, &model, true).await?
+            || !confirm(&message(MessageId::PromptDownloadNow, &[("model", &model)]))?
+        {
+            println!("Aborted.");
+            return Ok(());
+        }
+        let mut reporter = CliProgressReporter::from_oss_config(&config.oss);
+        client.pull_with_reporter(&model, &mut reporter).await?;
+        crate::model_completion::invalidate_model_name_cache(&config);
+    }
+
+    let config = load_setup_config(config_overrides, backend, Some(model.clone())).await?;
+    println!();
+    let report = codex_ollama::check_oss_readiness(&config).await;
+    for line in format_doctor_report(&report) {
+        println!("{line}");
+    }
+
+    println!();
+    println!("{}", format_oss_table(backend, &model));
+    let config_path = config.codex_home.join("config.toml");
+    if !confirm(&format!(
+        "Save these settings to {}?",
+        config_path.display()
+    ))? {
+        println!("Nothing was written.");
+        return Ok(());
+    }
+    let provider = backend.to_string();
+    codex_core::config_edit::persist_overrides(
+        &config.codex_home,
+        None,
+        &[
+            (&["oss", "provider"], provider.as_str()),
+            (&["oss", "model"], model.as_str()),
+        ],
+    )
+    .await
+    .context("failed to update config.toml")?;
+    println!("Saved. Start a local session with `codex --oss`.");
+    let schema = serde_json::to_value(OssConfigToml::json_schema())?;
+    let other_settings = format_other_settings(&schema, &["provider", "model"]);
+    if !other_settings.is_empty() {
+        println!();
+        println!("Other settings of the [oss] table:");
+        for line in other_settings {
+            println!("{line}");
+        }
+    }
+    Ok(())
+}
+
+async fn load_setup_config(
+    config_overrides: &CliConfigOverrides,
+    backend: OssBackend,
+    model: Option<String>,
+) -> Result<Config> {
+    let overrides = config_overrides.parse_overrides().map_err(|e| anyhow!(e))?;
+    let config_overrides = ConfigOverrides {
+        model,
+        model_provider: Some(BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string()),
+        oss_provider: Some(backend),
+        ..Default::default()
+    };
+    Config::load_with_cli_overrides(overrides, config_overrides)
+        .await
+        .context("failed to load configuration")
+}
+
+fn describe_backend(found: &DetectedBackend) -> String {
+    let state = match (found.running, &found.install_path) {
+        (true, _) => format!("running at {}", found.host_root),
+        (false, Some(path)) => format!("installed at {}, not running", path.display()),
+        (false, None) => "not found".to_string(),
+    };
+    format!("{} ({state})", found.backend)
+}
+
+/// Prefer a running server, then an installed one, then the first listed.
+fn default_backend_index(detected: &[DetectedBackend]) -> usize {
+    detected
+        .iter()
+        .position(|found| found.running)
+        .or_else(|| detected.iter().position(DetectedBackend::is_available))
+        .unwrap_or(0)
+}
+
+/// Interpret the model answer: a number picks from `models`, anything else
+/// is taken as a model name.
+fn resolve_model_choice(answer: &str, models: &[String]) -> String {
+    answer
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|i| models.get(i))
+        .cloned()
+        .unwrap_or_else(|| answer.to_string())
+}
+
+fn format_oss_table(backend: OssBackend, model: &str) -> String {
+    format!("[oss]\nprovider = \"{backend}\"\nmodel = \"{model}\"")
+}
+
+/// One line per key of the `[oss]` schema not in `skip`, with the first
+/// sentence of its documentation.
+fn format_other_settings(schema: &serde_json::Value, skip: &[&str]) -> Vec<String> {
+    let Some(properties) = schema
+        .get("properties")
+        .and_then(serde_json::Value::as_object)
+    else {
+        return Vec::new();
+    };
+    let entries: Vec<(&str, &str)> = properties
+        .iter()
+        .filter(|(key, _)| !skip.contains(&key.as_str()))
+        .map(|(key, property)| {
+            let description = property
+                .get("description")
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or_default();
+            let first_sentence = description
+                .split_once(". ")
+                .map_or(description, |(sentence, _)| sentence)
+                .trim_end_matches('.');
+            (key.as_str(), first_sentence)
+        })
+        .collect();
+    let key_width = entries.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    entries
+        .into_iter()
+        .map(|(key, description)| format!("  {key:<key_width$}  {description}"))
+        .collect()
+}
+
+/// Ask for a line of input on stderr, returning `default` for an empty answer.
+fn prompt_line(prompt: &str, default: &str) -> Result<String> {
+    if default.is_empty() {
+        eprint!("{prompt}: ");
+    } else {
+        eprint!("{prompt} [{default}]: ");
+    }
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok(if answer.is_empty() {
+        default.to_string()
+    } else {
+        answer.to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn detected(backend: OssBackend, installed: bool, running: bool) -> DetectedBackend {
+        DetectedBackend {
+            backend,
+            install_path: installed.then(|| PathBuf::from("/usr/bin/x")),
+            running,
+            host_root: "http://localhost:1".to_string(),
+        }
+    }
+
+    #[test]
+    fn default_backend_prefers_running_then_installed() {
+        assert_eq!(
+            default_backend_index(&[
+                detected(OssBackend::Ollama, true, false),
+                detected(OssBackend::LmStudio, true, true),
+            ]),
+            1
+        );
+        assert_eq!(
+            default_backend_index(&[
+                detected(OssBackend::Ollama, false, false),
+                detected(OssBackend::LmStudio, true, false),
+            ]),
+            1
+        );
+        assert_eq!(
+            default_backend_index(&[
+                detected(OssBackend::Ollama, false, false),
+                detected(OssBackend::LmStudio, false, false),
+            ]),
+            0
+        );
+    }
+
+    #[test]
+    fn model_choice_accepts_number_or_name() {
+        let models = vec!["gpt-oss:20b".to_string(), "mistral:latest".to_string()];
+        assert_eq!(resolve_model_choice("2", &models), "mistral:latest");
+        assert_eq!(resolve_model_choice("qwen3:8b", &models), "qwen3:8b");
+        assert_eq!(resolve_model_choice("7", &models), "7");
+    }
+
+    #[test]
+    fn oss_table_uses_wire_names() {
+        assert_eq!(
+            format_oss_table(OssBackend::LmStudio, "openai/gpt-oss-20b"),
+            "[oss]\nprovider = \"lmstudio\"\nmodel = \"openai/gpt-oss-20b\""
+        );
+    }
+
+    #[test]
+    fn other_settings_come_from_the_schema() {
+        let schema = serde_json::json!({
+            "properties": {
+                "model": {"description": "Model to use."},
+                "offline": {"description": "Never reach the network. No downloads."},
+                "unload_on_exit": {"description": "Unload the model when Codex exits."},
+            }
+        });
+        assert_eq!(
+            format_other_settings(&schema, &["provider", "model"]),
+            vec![
+                "  offline         Never reach the network",
+                "  unload_on_exit  Unload the model when Codex exits",
+            ]
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! `codex oss` and `codex models` in a build without local backend support.
+//! The commands still parse, so that scripts get an explanation rather than
+//! a usage error.
+
+use anyhow::Result;
+use anyhow::anyhow;
+use codex_common::CliConfigOverrides;
+
+/// [experimental] Inspect and manage the local open source ("oss") provider.
+#[derive(Debug, clap::Parser)]
+pub struct OssCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true, hide = true)]
+    _args: Vec<String>,
+}
+
+impl OssCli {
+    pub async fn run(self) -> Result<()> {
+        Err(unsupported())
+    }
+}
+
+/// [experimental] Manage models served by the local open source provider.
+#[derive(Debug, clap::Parser)]
+pub struct ModelsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true, hide = true)]
+    _args: Vec<String>,
+}
+
+impl ModelsCli {
+    pub async fn run(self) -> Result<()> {
+        Err(unsupported())
+    }
+}
+
+fn unsupported() -> anyhow::Error {
+    anyhow!("codex was built without local backend support")
+}
@@ -13,6 +13,12 @@ mod sandbox_mode_cli_arg;
 #[cfg(feature = "cli")]
 pub use sandbox_mode_cli_arg::SandboxModeCliArg;
 
+#[cfg(feature = "cli")]
+mod oss_backend_cli_arg;
+
+#[cfg(feature = "cli")]
+pub use oss_backend_cli_arg::OssBackendCliArg;
+
 #[cfg(feature = "cli")]
 pub mod format_env_display;
 
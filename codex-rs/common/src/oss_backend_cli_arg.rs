@@ -0,0 +1,25 @@
+//! Standard type to use with the `--oss-provider` CLI option.
+
+use clap::ValueEnum;
+use codex_protocol::config_types::OssBackend;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "lowercase")]
+pub enum OssBackendCliArg {
+    /// Ollama's native API; missing models are pulled automatically.
+    Ollama,
+    /// LM Studio's OpenAI-compatible server.
+    LmStudio,
+    /// Any other OpenAI-compatible server.
+    Generic,
+}
+
+impl From<OssBackendCliArg> for OssBackend {
+    fn from(value: OssBackendCliArg) -> Self {
+        match value {
+            OssBackendCliArg::Ollama => OssBackend::Ollama,
+            OssBackendCliArg::LmStudio => OssBackend::LmStudio,
+            OssBackendCliArg::Generic => OssBackend::Generic,
+        }
+    }
+}
@@ -7,6 +7,8 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
         SandboxPolicy::WorkspaceWrite {
             writable_roots,
             network_access,
+            local_network_ports,
+            local_network_sockets,
             exclude_tmpdir_env_var,
             exclude_slash_tmp,
         } => {
@@ -29,6 +31,17 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
             summary.push_str(&format!(" [{}]", writable_entries.join(", ")));
             if *network_access {
                 summary.push_str(" (network access enabled)");
+            } else if !local_network_ports.is_empty() || !local_network_sockets.is_empty() {
+                let reachable: Vec<String> = local_network_ports
+                    .iter()
+                    .map(|port| format!("localhost:{port}"))
+                    .chain(
+                        local_network_sockets
+                            .iter()
+                            .map(|socket| socket.to_string_lossy().to_string()),
+                    )
+                    .collect();
+                summary.push_str(&format!(" ({} reachable)", reachable.join(", ")));
             }
             summary
         }
@@ -1,20 +1,40 @@
 use std::time::Duration;
+use std::time::Instant;
 
 use crate::ModelProviderInfo;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
+use crate::coalescing_sender::CoalescingSender;
 use crate::error::CodexErr;
 use crate::error::ConnectionFailedError;
 use crate::error::ResponseStreamFailed;
 use crate::error::Result;
 use crate::error::RetryLimitReachedError;
 use crate::error::UnexpectedResponseError;
+use crate::harmony::HarmonyChannels;
+use crate::harmony::HarmonySegment;
 use crate::model_family::ModelFamily;
+use crate::oss_cache;
+use crate::oss_cache::CacheKey;
+use crate::oss_circuit::OssCircuit;
+use crate::oss_latency::LatencyBudget;
+use crate::oss_latency::StreamTimeouts;
+use crate::oss_limiter::OssLimiter;
+use crate::oss_sse_dump::SseDump;
+use crate::protocol::RequestTimingEvent;
+use crate::protocol::TokenUsage;
+use crate::request_stages::BodyProgress;
+use crate::request_stages::timed_body;
 use crate::tools::spec::create_tools_json_for_chat_completions_api;
 use crate::util::backoff;
+use crate::util::retry_after;
 use bytes::Bytes;
+use chrono::Utc;
+use codex_otel::otel_event_manager::LocalInferenceError;
 use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::config_types::OssBackend;
+use codex_protocol::config_types::OssSampling;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ReasoningItemContent;
 use codex_protocol::models::ResponseItem;
@@ -24,18 +44,58 @@ use futures::StreamExt;
 use futures::TryStreamExt;
 use reqwest::StatusCode;
 use serde_json::json;
+use std::path::Path;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::Context;
 use std::task::Poll;
+use tokio::sync::OwnedSemaphorePermit;
 use tokio::sync::mpsc;
 use tokio::time::timeout;
 use tracing::debug;
 use tracing::trace;
+use tracing::warn;
+
+/// How long to wait for the usage chunk after the choice finished.
+const USAGE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long a local response may go without a new token once generation
+/// started, unless `oss.stall_timeout_secs` says otherwise.
+pub(crate) const DEFAULT_STALL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// What a request to the local `oss` server adds to a Chat Completions
+/// request.
+pub(crate) struct OssRequest<'a> {
+    pub(crate) backend: OssBackend,
+    /// Added to the request body.
+    pub(crate) sampling: &'a OssSampling,
+    /// Counts each attempt and stops requests to a server that keeps failing.
+    pub(crate) circuit: &'a OssCircuit,
+    /// Sizes the stream's timeouts after the speeds observed, unless the
+    /// provider sets `stream_idle_timeout_ms`.
+    pub(crate) latency: &'a LatencyBudget,
+    /// Makes each request wait for a free slot of the server and hold it until
+    /// its stream ends.
+    pub(crate) limiter: Option<&'a OssLimiter>,
+    /// An auxiliary request (compaction, review) whose answer may come from,
+    /// and goes to, the cache when it is deterministic.
+    pub(crate) cacheable: bool,
+    /// File the response's SSE frames are appended to
+    /// (`oss.debug_dump_sse`).
+    pub(crate) dump_sse: Option<&'a Path>,
+    /// Gives up on a response that sends no token for this long, even while
+    /// it sends other frames; zero disables the check.
+    pub(crate) stall_timeout: Duration,
+}
 
-/// Implementation for the classic Chat Completions API.
+/// Implementation for the classic Chat Completions API. With `oss`, the
+/// server is asked for token usage, each request is reported to OTEL as
+/// local inference, and the stream ends with a
+/// [`ResponseEvent::RequestTiming`].
 pub(crate) async fn stream_chat_completions(
     prompt: &Prompt,
     model_family: &ModelFamily,
+    oss: Option<OssRequest<'_>>,
     client: &reqwest::Client,
     provider: &ModelProviderInfo,
     otel_event_manager: &OtelEventManager,
@@ -46,6 +106,203 @@ pub(crate) async fn stream_chat_completions(
         ));
     }
 
+    let payload =
+        chat_completions_payload(prompt, model_family, oss.as_ref().map(|oss| oss.sampling))?;
+    let oss_backend = oss.as_ref().map(|oss| oss.backend);
+
+    debug!(
+        "POST to {}: {}",
+        provider.get_full_url(&None),
+        serde_json::to_string_pretty(&payload).unwrap_or_default()
+    );
+
+    let cache_key = oss
+        .as_ref()
+        .filter(|oss| oss.cacheable)
+        .and_then(|_| CacheKey::for_request(&provider.get_full_url(&None), &payload));
+    if let Some(cached) = cache_key.as_ref().and_then(oss_cache::replay) {
+        debug!("answering the request from the local response cache");
+        return Ok(cached);
+    }
+
+    // A long prompt keeps a local server busy before its first token for
+    // longer than a short one; size the wait after the speeds seen so far.
+    let timeouts = match &oss {
+        Some(oss) if provider.stream_idle_timeout_ms.is_none() => oss
+            .latency
+            .timeouts(payload.to_string().len(), provider.stream_idle_timeout()),
+        _ => StreamTimeouts::fixed(provider.stream_idle_timeout()),
+    };
+
+    let mut attempt = 0;
+    let max_retries = provider.request_max_retries();
+    loop {
+        attempt += 1;
+        if let Some(oss) = &oss {
+            oss.circuit.check(oss.backend)?;
+        }
+        let slot = match oss.as_ref().and_then(|oss| oss.limiter) {
+            Some(limiter) => limiter.acquire().await,
+            None => None,
+        };
+
+        let req_builder = provider
+            .create_request_builder(client, &None)
+            .await?
+            .header(reqwest::header::ACCEPT, "text/event-stream");
+        // Local requests note when their body is written, which times the
+        // stages before the response headers.
+        let (req_builder, body_progress) = if oss.is_some() {
+            let body = payload.to_string().into_bytes();
+            let len = body.len();
+            let (body, progress) = timed_body(body);
+            let req_builder = req_builder
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(reqwest::header::CONTENT_LENGTH, len)
+                .body(body);
+            (req_builder, Some(progress))
+        } else {
+            (req_builder.json(&payload), None)
+        };
+
+        let sent = Instant::now();
+        let res = otel_event_manager
+            .log_request(attempt, || req_builder.send())
+            .await;
+
+        match res {
+            Ok(resp) if resp.status().is_success() => {
+                if let Some(oss) = &oss {
+                    oss.circuit.record_success();
+                }
+                let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+                // A cacheable answer passes through the cache on its way.
+                let tx_event = match cache_key {
+                    Some(key) => {
+                        let (tx_answer, rx_answer) = mpsc::channel(1600);
+                        tokio::spawn(oss_cache::forward_and_record(key, rx_answer, tx_event));
+                        tx_answer
+                    }
+                    None => tx_event,
+                };
+                let stream = resp.bytes_stream().map_err(|e| {
+                    CodexErr::ResponseStreamFailed(ResponseStreamFailed {
+                        source: e,
+                        request_id: None,
+                    })
+                });
+                #[cfg(feature = "fault-injection")]
+                let stream = crate::fault_injection::apply_injected(stream);
+                let dump = oss.as_ref().and_then(|oss| {
+                    let path = oss.dump_sse?;
+                    SseDump::open(path, oss.backend, &model_family.slug)
+                });
+                tokio::spawn(process_chat_sse(
+                    stream,
+                    tx_event,
+                    timeouts,
+                    otel_event_manager.clone(),
+                    dump,
+                    oss.as_ref()
+                        .map(|oss| oss.stall_timeout)
+                        .filter(|stall| !stall.is_zero()),
+                    oss.as_ref().map(|oss| RequestClock {
+                        backend: oss.backend,
+                        budget: oss.latency.clone(),
+                        _slot: slot,
+                        sent,
+                        tls: provider.get_full_url(&None).starts_with("https://"),
+                        body: body_progress,
+                        connected: Instant::now(),
+                        first_token: None,
+                        output_tokens: 0,
+                    }),
+                    oss_backend.is_some(),
+                    // gpt-oss served without a harmony parser streams its
+                    // channels as content.
+                    (model_family.family == "gpt-oss").then(HarmonyChannels::default),
+                ));
+                return Ok(ResponseStream { rx_event });
+            }
+            Ok(res) => {
+                let status = res.status();
+                let retry_hint = retry_after(res.headers(), Utc::now());
+                if let Some(oss) = &oss {
+                    otel_event_manager.local_inference_failed(
+                        &oss.backend.to_string(),
+                        LocalInferenceError::Status,
+                        Some(status.as_u16()),
+                        &status,
+                    );
+                    // A rejected request says nothing about the server's
+                    // health; only server errors count, and not those of a
+                    // server that asks to be retried later, such as a proxy
+                    // shedding load.
+                    if status.is_server_error() && retry_hint.is_none() {
+                        oss.circuit.record_failure();
+                    }
+                }
+                if let Some(oss) = &oss
+                    && status == StatusCode::NOT_FOUND
+                {
+                    let body = (res.text().await).unwrap_or_default();
+                    return Err(CodexErr::LocalModelMissing(format!(
+                        "Model '{}' is not available on the {} server ({}). Download or load it there first, or pick another model with /model.",
+                        model_family.slug,
+                        oss.backend,
+                        local_error_message(&body),
+                    )));
+                }
+                if !(status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()) {
+                    let body = (res.text().await).unwrap_or_default();
+                    return Err(CodexErr::UnexpectedStatus(UnexpectedResponseError {
+                        status,
+                        body,
+                        request_id: None,
+                    }));
+                }
+
+                if attempt > max_retries {
+                    return Err(CodexErr::RetryLimit(RetryLimitReachedError {
+                        status,
+                        request_id: None,
+                    }));
+                }
+
+                let delay = retry_hint.unwrap_or_else(|| backoff(attempt));
+                debug!("{status}; retrying in {}ms", delay.as_millis());
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => {
+                if let Some(oss) = &oss {
+                    otel_event_manager.local_inference_failed(
+                        &oss.backend.to_string(),
+                        LocalInferenceError::Connection,
+                        None,
+                        &e,
+                    );
+                    oss.circuit.record_failure();
+                }
+                if attempt > max_retries {
+                    return Err(CodexErr::ConnectionFailed(ConnectionFailedError {
+                        source: e,
+                    }));
+                }
+                let delay = backoff(attempt);
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+/// The body of a Chat Completions request for `prompt`. With
+/// `oss_sampling` the request goes to the local `oss` server, which is sent
+/// the sampling parameters and asked for token usage.
+fn chat_completions_payload(
+    prompt: &Prompt,
+    model_family: &ModelFamily,
+    oss_sampling: Option<&OssSampling>,
+) -> Result<serde_json::Value> {
     // Build messages array
     let mut messages = Vec::<serde_json::Value>::new();
 
@@ -279,106 +536,94 @@ pub(crate) async fn stream_chat_completions(
     }
 
     let tools_json = create_tools_json_for_chat_completions_api(&prompt.tools)?;
-    let payload = json!({
+    let mut payload = json!({
         "model": model_family.slug,
         "messages": messages,
         "stream": true,
         "tools": tools_json,
     });
+    if let Some(sampling) = oss_sampling
+        && let (Some(payload), Ok(serde_json::Value::Object(params))) =
+            (payload.as_object_mut(), serde_json::to_value(sampling))
+    {
+        payload.extend(params);
+    }
+    if oss_sampling.is_some()
+        && let Some(payload) = payload.as_object_mut()
+    {
+        // Local servers only report usage when asked, in a last chunk.
+        payload.insert(
+            "stream_options".to_string(),
+            json!({ "include_usage": true }),
+        );
+    }
+    Ok(payload)
+}
 
-    debug!(
-        "POST to {}: {}",
-        provider.get_full_url(&None),
-        serde_json::to_string_pretty(&payload).unwrap_or_default()
+/// Give up on a response that sent no token for `stalled_for`. Dropping the
+/// stream closes the connection, which makes the server stop generating; the
+/// error is retried like a dropped stream.
+async fn report_stall(
+    tx_event: &mut CoalescingSender,
+    otel_event_manager: &OtelEventManager,
+    clock: Option<&RequestClock>,
+    stalled_for: Duration,
+    frames_since_token: u64,
+) {
+    let message = format!(
+        "generation stalled: no new tokens for {}s",
+        stalled_for.as_secs()
     );
-
-    let mut attempt = 0;
-    let max_retries = provider.request_max_retries();
-    loop {
-        attempt += 1;
-
-        let req_builder = provider.create_request_builder(client, &None).await?;
-
-        let res = otel_event_manager
-            .log_request(attempt, || {
-                req_builder
-                    .header(reqwest::header::ACCEPT, "text/event-stream")
-                    .json(&payload)
-                    .send()
-            })
-            .await;
-
-        match res {
-            Ok(resp) if resp.status().is_success() => {
-                let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
-                let stream = resp.bytes_stream().map_err(|e| {
-                    CodexErr::ResponseStreamFailed(ResponseStreamFailed {
-                        source: e,
-                        request_id: None,
-                    })
-                });
-                tokio::spawn(process_chat_sse(
-                    stream,
-                    tx_event,
-                    provider.stream_idle_timeout(),
-                    otel_event_manager.clone(),
-                ));
-                return Ok(ResponseStream { rx_event });
-            }
-            Ok(res) => {
-                let status = res.status();
-                if !(status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()) {
-                    let body = (res.text().await).unwrap_or_default();
-                    return Err(CodexErr::UnexpectedStatus(UnexpectedResponseError {
-                        status,
-                        body,
-                        request_id: None,
-                    }));
-                }
-
-                if attempt > max_retries {
-                    return Err(CodexErr::RetryLimit(RetryLimitReachedError {
-                        status,
-                        request_id: None,
-                    }));
-                }
-
-                let retry_after_secs = res
-                    .headers()
-                    .get(reqwest::header::RETRY_AFTER)
-                    .and_then(|v| v.to_str().ok())
-                    .and_then(|s| s.parse::<u64>().ok());
-
-                let delay = retry_after_secs
-                    .map(|s| Duration::from_millis(s * 1_000))
-                    .unwrap_or_else(|| backoff(attempt));
-                tokio::time::sleep(delay).await;
-            }
-            Err(e) => {
-                if attempt > max_retries {
-                    return Err(CodexErr::ConnectionFailed(ConnectionFailedError {
-                        source: e,
-                    }));
-                }
-                let delay = backoff(attempt);
-                tokio::time::sleep(delay).await;
-            }
-        }
+    warn!(
+        "{message}; {frames_since_token} frames without tokens arrived meanwhile, after {} tokens",
+        clock.map_or(0, |clock| clock.output_tokens)
+    );
+    if let Some(clock) = clock {
+        otel_event_manager.local_inference_failed(
+            &clock.backend.to_string(),
+            LocalInferenceError::Stalled,
+            None,
+            &message,
+        );
     }
+    let _ = tx_event.send(Err(CodexErr::Stream(message, None))).await;
+}
+
+/// The message of a local server's error response, which Ollama sends as
+/// `{"error": "..."}` and LM Studio as `{"error": {"message": "..."}}`.
+fn local_error_message(body: &str) -> String {
+    let value = serde_json::from_str::<serde_json::Value>(body).ok();
+    let error = value.as_ref().and_then(|value| value.get("error"));
+    error
+        .and_then(serde_json::Value::as_str)
+        .or_else(|| {
+            error
+                .and_then(|error| error.get("message"))
+                .and_then(serde_json::Value::as_str)
+        })
+        .unwrap_or(body)
+        .trim()
+        .to_string()
 }
 
 /// Lightweight SSE processor for the Chat Completions streaming format. The
 /// output is mapped onto Codex's internal [`ResponseEvent`] so that the rest
 /// of the pipeline can stay agnostic of the underlying wire format.
-async fn process_chat_sse<S>(
+pub(crate) async fn process_chat_sse<S>(
     stream: S,
     tx_event: mpsc::Sender<Result<ResponseEvent>>,
-    idle_timeout: Duration,
+    timeouts: StreamTimeouts,
     otel_event_manager: OtelEventManager,
+    mut dump: Option<SseDump>,
+    stall_timeout: Option<Duration>,
+    mut clock: Option<RequestClock>,
+    wait_for_usage: bool,
+    mut harmony: Option<HarmonyChannels>,
 ) where
     S: Stream<Item = Result<Bytes>> + Unpin,
 {
     let mut stream = stream.eventsource();
+    let mut tx_event = CoalescingSender::new(tx_event);
 
     // State to accumulate a function call across streaming chunks.
     // OpenAI may split the `arguments` string over multiple `delta` events
@@ -396,16 +641,73 @@ async fn process_chat_sse<S>(
     let mut fn_call_state = FunctionCallState::default();
     let mut assistant_text = String::new();
     let mut reasoning_text = String::new();
+    let mut token_usage: Option<TokenUsage> = None;
+    // Set once the choice finished while the usage chunk is still due.
+    let mut finished = false;
+    // Until the first token the server is processing the prompt.
+    let mut generating = false;
+    // Some servers hang mid-answer while still sending frames without
+    // tokens, which the idle timeout never notices.
+    let mut last_token = Instant::now();
+    let mut frames_since_token = 0u64;
 
     loop {
+        let watch_stall = stall_timeout.filter(|_| generating && !finished);
+        let since_token = last_token.elapsed();
+        if watch_stall.is_some_and(|stall| since_token >= stall) {
+            report_stall(
+                &mut tx_event,
+                &otel_event_manager,
+                clock.as_ref(),
+                since_token,
+                frames_since_token,
+            )
+            .await;
+            return;
+        }
         let start = std::time::Instant::now();
-        let response = timeout(idle_timeout, stream.next()).await;
+        let wait = if finished {
+            USAGE_TIMEOUT
+        } else if generating {
+            timeouts.idle
+        } else {
+            timeouts.first_token
+        };
+        // Wake up for the stall even when the server goes silent.
+        let wait = watch_stall.map_or(wait, |stall| wait.min(stall - since_token));
+        let response = tokio::select! {
+            // The turn was interrupted and the stream dropped. Returning drops
+            // the response, which closes the connection and makes the server
+            // stop generating instead of finishing the answer for nobody.
+            () = tx_event.closed() => return,
+            response = timeout(wait, stream.next()) => response,
+        };
         let duration = start.elapsed();
         otel_event_manager.log_sse_event(&response, duration);
 
+        if let Some(dump) = dump.as_mut() {
+            match &response {
+                Ok(Some(Ok(ev))) => dump.frame(&ev.event, &ev.data),
+                Ok(Some(Err(e))) => dump.end(&format!("stream failed: {e}")),
+                Ok(None) => dump.end("stream closed"),
+                Err(_) => dump.end(&format!("no frame within {wait:?}")),
+            }
+        }
+
         let sse = match response {
-            Ok(Some(Ok(ev))) => ev,
+            Ok(Some(Ok(ev))) => {
+                frames_since_token += 1;
+                ev
+            }
             Ok(Some(Err(e))) => {
+                if let Some(clock) = &clock {
+                    otel_event_manager.local_inference_failed(
+                        &clock.backend.to_string(),
+                        LocalInferenceError::Stream,
+                        None,
+                        &e,
+                    );
+                }
                 let _ = tx_event
                     .send(Err(CodexErr::Stream(e.to_string(), None)))
                     .await;
@@ -413,15 +715,37 @@ async fn process_chat_sse<S>(
             }
             Ok(None) => {
                 // Stream closed gracefully – emit Completed with dummy id.
-                let _ = tx_event
-                    .send(Ok(ResponseEvent::Completed {
-                        response_id: String::new(),
-                        token_usage: None,
-                    }))
-                    .await;
+                send_completed(
+                    &mut tx_event,
+                    &otel_event_manager,
+                    clock.as_ref(),
+                    token_usage,
+                )
+                .await;
+                return;
+            }
+            Err(_) if finished => {
+                // The server did not send usage after all.
+                send_completed(
+                    &mut tx_event,
+                    &otel_event_manager,
+                    clock.as_ref(),
+                    token_usage,
+                )
+                .await;
                 return;
             }
+            // Reported at the top of the loop.
+            Err(_) if watch_stall.is_some_and(|stall| last_token.elapsed() >= stall) => continue,
             Err(_) => {
+                if let Some(clock) = &clock {
+                    otel_event_manager.local_inference_failed(
+                        &clock.backend.to_string(),
+                        LocalInferenceError::IdleTimeout,
+                        None,
+                        &"idle timeout waiting for SSE",
+                    );
+                }
                 let _ = tx_event
                     .send(Err(CodexErr::Stream(
                         "idle timeout waiting for SSE".into(),
@@ -434,6 +758,16 @@ async fn process_chat_sse<S>(
 
         // OpenAI Chat streaming sends a literal string "[DONE]" when finished.
         if sse.data.trim() == "[DONE]" {
+            if let Some(harmony) = harmony.as_mut() {
+                let segments = harmony.finish();
+                send_segments(
+                    &mut tx_event,
+                    segments,
+                    &mut assistant_text,
+                    &mut reasoning_text,
+                )
+                .await;
+            }
             // Emit any finalized items before closing so downstream consumers receive
             // terminal events for both assistant content and raw reasoning.
             if !assistant_text.is_empty() {
@@ -459,12 +793,13 @@ async fn process_chat_sse<S>(
                 let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
             }
 
-            let _ = tx_event
-                .send(Ok(ResponseEvent::Completed {
-                    response_id: String::new(),
-                    token_usage: None,
-                }))
-                .await;
+            send_completed(
+                &mut tx_event,
+                &otel_event_manager,
+                clock.as_ref(),
+                token_usage,
+            )
+            .await;
             return;
         }
 
@@ -475,9 +810,32 @@ async fn process_chat_sse<S>(
         };
         trace!("chat_completions received SSE chunk: {chunk:?}");
 
+        if let Some(usage) = chunk.get("usage").and_then(parse_usage) {
+            token_usage = Some(usage);
+            if finished {
+                send_completed(
+                    &mut tx_event,
+                    &otel_event_manager,
+                    clock.as_ref(),
+                    token_usage,
+                )
+                .await;
+                return;
+            }
+        }
+
         let choice_opt = chunk.get("choices").and_then(|c| c.get(0));
 
         if let Some(choice) = choice_opt {
+            if generates_output(choice) {
+                generating = true;
+                last_token = Instant::now();
+                frames_since_token = 0;
+                if let Some(clock) = clock.as_mut() {
+                    clock.on_token();
+                }
+            }
+
             // Handle assistant content tokens as streaming deltas.
             if let Some(content) = choice
                 .get("delta")
@@ -485,10 +843,17 @@ async fn process_chat_sse<S>(
                 .and_then(|c| c.as_str())
                 && !content.is_empty()
             {
-                assistant_text.push_str(content);
-                let _ = tx_event
-                    .send(Ok(ResponseEvent::OutputTextDelta(content.to_string())))
-                    .await;
+                let segments = match harmony.as_mut() {
+                    Some(harmony) => harmony.push(content),
+                    None => vec![HarmonySegment::Message(content.to_string())],
+                };
+                send_segments(
+                    &mut tx_event,
+                    segments,
+                    &mut assistant_text,
+                    &mut reasoning_text,
+                )
+                .await;
             }
 
             // Forward any reasoning/thinking deltas if present.
@@ -519,8 +884,8 @@ async fn process_chat_sse<S>(
                 if let Some(reasoning) = maybe_text {
                     // Accumulate so we can emit a terminal Reasoning item at the end.
                     reasoning_text.push_str(&reasoning);
-                    let _ = tx_event
-                        .send(Ok(ResponseEvent::ReasoningContentDelta(reasoning)))
+                    tx_event
+                        .send_delta(ResponseEvent::ReasoningContentDelta(reasoning))
                         .await;
                 }
             }
@@ -532,8 +897,8 @@ async fn process_chat_sse<S>(
                 if let Some(s) = message_reasoning.as_str() {
                     if !s.is_empty() {
                         reasoning_text.push_str(s);
-                        let _ = tx_event
-                            .send(Ok(ResponseEvent::ReasoningContentDelta(s.to_string())))
+                        tx_event
+                            .send_delta(ResponseEvent::ReasoningContentDelta(s.to_string()))
                             .await;
                     }
                 } else if let Some(obj) = message_reasoning.as_object()
@@ -544,8 +909,8 @@ async fn process_chat_sse<S>(
                     && !s.is_empty()
                 {
                     reasoning_text.push_str(s);
-                    let _ = tx_event
-                        .send(Ok(ResponseEvent::ReasoningContentDelta(s.to_string())))
+                    tx_event
+                        .send_delta(ResponseEvent::ReasoningContentDelta(s.to_string()))
                         .await;
                 }
             }
@@ -580,6 +945,16 @@ async fn process_chat_sse<S>(
 
             // Emit end-of-turn when finish_reason signals completion.
             if let Some(finish_reason) = choice.get("finish_reason").and_then(|v| v.as_str()) {
+                if let Some(harmony) = harmony.as_mut() {
+                    let segments = harmony.finish();
+                    send_segments(
+                        &mut tx_event,
+                        segments,
+                        &mut assistant_text,
+                        &mut reasoning_text,
+                    )
+                    .await;
+                }
                 match finish_reason {
                     "tool_calls" if fn_call_state.active => {
                         // First, flush the terminal raw reasoning so UIs can finalize
@@ -635,13 +1010,20 @@ async fn process_chat_sse<S>(
                     _ => {}
                 }
 
+                // Usage follows in a chunk of its own when it was asked for.
+                if wait_for_usage && token_usage.is_none() {
+                    finished = true;
+                    continue;
+                }
+
                 // Emit Completed regardless of reason so the agent can advance.
-                let _ = tx_event
-                    .send(Ok(ResponseEvent::Completed {
-                        response_id: String::new(),
-                        token_usage: None,
-                    }))
-                    .await;
+                send_completed(
+                    &mut tx_event,
+                    &otel_event_manager,
+                    clock.as_ref(),
+                    token_usage,
+                )
+                .await;
 
                 // Prepare for potential next turn (should not happen in same stream).
                 // fn_call_state = FunctionCallState::default();
@@ -652,6 +1034,151 @@ async fn process_chat_sse<S>(
     }
 }
 
+/// Stream content `segments` as text or reasoning deltas, accumulating them
+/// for the terminal items.
+async fn send_segments(
+    tx_event: &mut CoalescingSender,
+    segments: Vec<HarmonySegment>,
+    assistant_text: &mut String,
+    reasoning_text: &mut String,
+) {
+    for segment in segments {
+        let event = match segment {
+            HarmonySegment::Message(text) => {
+                assistant_text.push_str(&text);
+                ResponseEvent::OutputTextDelta(text)
+            }
+            HarmonySegment::Reasoning(text) => {
+                reasoning_text.push_str(&text);
+                ResponseEvent::ReasoningContentDelta(text)
+            }
+        };
+        tx_event.send_delta(event).await;
+    }
+}
+
+/// Timestamps of one request, reported as a [`RequestTimingEvent`] when its
+/// stream ends and recorded in `budget` for the timeouts of later requests.
+pub(crate) struct RequestClock {
+    backend: OssBackend,
+    budget: LatencyBudget,
+    /// The server slot the request holds until its stream ends.
+    _slot: Option<OwnedSemaphorePermit>,
+    sent: Instant,
+    /// Whether the request went over https.
+    tls: bool,
+    /// When the request body was written, timing the connection.
+    body: Option<Arc<BodyProgress>>,
+    /// When the response headers arrived.
+    connected: Instant,
+    first_token: Option<Instant>,
+    output_tokens: u64,
+}
+
+impl RequestClock {
+    fn on_token(&mut self) {
+        self.first_token.get_or_insert_with(Instant::now);
+        self.output_tokens += 1;
+    }
+
+    fn finish(&self, now: Instant) -> RequestTimingEvent {
+        let millis = |from: Instant, to: Instant| {
+            u64::try_from(to.saturating_duration_since(from).as_millis()).unwrap_or(u64::MAX)
+        };
+        let body_started = self.body.as_ref().and_then(|body| body.started());
+        let body_written = self.body.as_ref().and_then(|body| body.written());
+        RequestTimingEvent {
+            connection_ms: body_started.map(|started| millis(self.sent, started)),
+            tls: self.tls,
+            request_write_ms: body_started
+                .zip(body_written)
+                .map(|(started, written)| millis(started, written)),
+            connect_ms: millis(self.sent, self.connected),
+            prefill_ms: self.first_token.map(|first| millis(self.connected, first)),
+            time_to_first_token_ms: self.first_token.map(|first| millis(self.sent, first)),
+            generation_ms: self.first_token.map(|first| millis(first, now)),
+            output_tokens: self.output_tokens,
+            total_ms: millis(self.sent, now),
+        }
+    }
+}
+
+/// Whether a streamed choice carries generated text, reasoning, or tool call
+/// arguments, i.e. a token.
+fn generates_output(choice: &serde_json::Value) -> bool {
+    let Some(delta) = choice.get("delta") else {
+        return false;
+    };
+    ["content", "reasoning", "tool_calls"]
+        .iter()
+        .any(|key| match delta.get(key) {
+            Some(serde_json::Value::String(text)) => !text.is_empty(),
+            Some(serde_json::Value::Null) | None => false,
+            Some(_) => true,
+        })
+}
+
+/// Token counts of the `usage` object sent with
+/// `stream_options.include_usage`; `null` in the other chunks.
+pub(crate) fn parse_usage(usage: &serde_json::Value) -> Option<TokenUsage> {
+    let count = |pointer: &str| {
+        usage
+            .pointer(pointer)
+            .and_then(serde_json::Value::as_i64)
+            .unwrap_or(0)
+    };
+    let input_tokens = usage.get("prompt_tokens")?.as_i64()?;
+    let output_tokens = count("/completion_tokens");
+    Some(TokenUsage {
+        input_tokens,
+        cached_input_tokens: count("/prompt_tokens_details/cached_tokens"),
+        output_tokens,
+        reasoning_output_tokens: count("/completion_tokens_details/reasoning_tokens"),
+        total_tokens: usage
+            .get("total_tokens")
+            .and_then(serde_json::Value::as_i64)
+            .unwrap_or(input_tokens.saturating_add(output_tokens)),
+    })
+}
+
+/// End the stream with the request's timing and token usage.
+async fn send_completed(
+    tx_event: &mut CoalescingSender,
+    otel_event_manager: &OtelEventManager,
+    clock: Option<&RequestClock>,
+    token_usage: Option<TokenUsage>,
+) {
+    send_timing(tx_event, otel_event_manager, clock, token_usage.as_ref()).await;
+    let _ = tx_event
+        .send(Ok(ResponseEvent::Completed {
+            response_id: String::new(),
+            token_usage,
+        }))
+        .await;
+}
+
+async fn send_timing(
+    tx_event: &mut CoalescingSender,
+    otel_event_manager: &OtelEventManager,
+    clock: Option<&RequestClock>,
+    token_usage: Option<&TokenUsage>,
+) {
+    if let Some(clock) = clock {
+        let mut timing = clock.finish(Instant::now());
+        // Streamed chunks approximate tokens; the server's count is exact.
+        if let Some(output_tokens) =
+            token_usage.and_then(|usage| u64::try_from(usage.output_tokens).ok())
+        {
+            timing.output_tokens = output_tokens;
+        }
+        otel_event_manager.local_inference(&clock.backend.to_string(), &timing);
+        clock.budget.record(&timing, token_usage);
+        let _ = tx_event
+            .send(Ok(ResponseEvent::RequestTiming(timing)))
+            .await;
+    }
+}
+
 /// Optional client-side aggregation helper
 ///
 /// Stream adapter that merges the incremental `OutputItemDone` chunks coming from
@@ -754,6 +1281,9 @@ where
                 Poll::Ready(Some(Ok(ResponseEvent::RateLimits(snapshot)))) => {
                     return Poll::Ready(Some(Ok(ResponseEvent::RateLimits(snapshot))));
                 }
+                Poll::Ready(Some(Ok(ResponseEvent::RequestTiming(timing)))) => {
+                    return Poll::Ready(Some(Ok(ResponseEvent::RequestTiming(timing))));
+                }
                 Poll::Ready(Some(Ok(ResponseEvent::Completed {
                     response_id,
                     token_usage,
@@ -898,3 +1428,282 @@ impl<S> AggregatedChatStream<S> {
         Self::new(inner, AggregateMode::Streaming)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn a_stream_sending_frames_without_tokens_is_given_up_on() {
+        let token =
+            Bytes::from_static(b"data: {\"choices\":[{\"delta\":{\"content\":\"Hi\"}}]}\n\n");
+        let empty = Bytes::from_static(b"data: {\"choices\":[{\"delta\":{\"content\":\"\"}}]}\n\n");
+        let stream = Box::pin(futures::stream::unfold(0u32, move |sent| {
+            let frame = if sent == 0 {
+                token.clone()
+            } else {
+                empty.clone()
+            };
+            async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Some((Ok::<_, CodexErr>(frame), sent + 1))
+            }
+        }));
+        let (tx, mut rx) = mpsc::channel(16);
+        tokio::spawn(process_chat_sse(
+            stream,
+            tx,
+            StreamTimeouts::fixed(Duration::from_secs(60)),
+            OtelEventManager::new(
+                codex_protocol::ConversationId::new(),
+                "test",
+                "test",
+                None,
+                None,
+                None,
+                false,
+                "test".to_string(),
+            ),
+            None,
+            Some(Duration::from_millis(200)),
+            None,
+            false,
+            None,
+        ));
+
+        let mut events = Vec::new();
+        while let Ok(Some(event)) = timeout(Duration::from_secs(5), rx.recv()).await {
+            events.push(match event {
+                Ok(ResponseEvent::OutputTextDelta(text)) => text,
+                Err(CodexErr::Stream(message, None)) => message,
+                other => format!("{other:?}"),
+            });
+        }
+        assert_eq!(
+            events,
+            vec![
+                "Hi".to_string(),
+                "generation stalled: no new tokens for 0s".to_string()
+            ]
+        );
+    }
+
+    const ANSWER: &str = concat!(
+        "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n",
+        "data: [DONE]\n\n",
+    );
+
+    /// The events `process_chat_sse` sends for [`ANSWER`] with `plan`
+    /// applied: text deltas, "completed", or the message of a stream error.
+    async fn events_with_faults(
+        plan: crate::fault_injection::FaultPlan,
+        timeouts: StreamTimeouts,
+    ) -> Vec<String> {
+        let stream = plan.apply(futures::stream::iter(vec![Ok::<_, CodexErr>(
+            Bytes::from_static(ANSWER.as_bytes()),
+        )]));
+        let (tx, mut rx) = mpsc::channel(16);
+        tokio::spawn(process_chat_sse(
+            stream,
+            tx,
+            timeouts,
+            OtelEventManager::new(
+                codex_protocol::ConversationId::new(),
+                "test",
+                "test",
+                None,
+                None,
+                None,
+                false,
+                "test".to_string(),
+            ),
+            None,
+            None,
+            None,
+            false,
+            None,
+        ));
+
+        let mut events = Vec::new();
+        while let Ok(Some(event)) = timeout(Duration::from_secs(5), rx.recv()).await {
+            match event {
+                Ok(ResponseEvent::OutputTextDelta(text)) => events.push(text),
+                Ok(ResponseEvent::Completed { .. }) => events.push("completed".to_string()),
+                Ok(_) => {}
+                Err(CodexErr::Stream(message, None)) => events.push(message),
+                Err(other) => events.push(format!("{other:?}")),
+            }
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn a_server_dropping_the_connection_mid_answer_fails_the_stream() {
+        let events = events_with_faults(
+            crate::fault_injection::FaultPlan::new().disconnect_after(1),
+            StreamTimeouts::fixed(Duration::from_secs(60)),
+        )
+        .await;
+        assert_eq!(
+            events,
+            vec![
+                "Hel".to_string(),
+                "connection reset by fault injection".to_string()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn malformed_frames_are_skipped() {
+        let events = events_with_faults(
+            crate::fault_injection::FaultPlan::new().malformed(0, "data: {\"choices\":[\n\n"),
+            StreamTimeouts::fixed(Duration::from_secs(60)),
+        )
+        .await;
+        assert_eq!(events, vec!["lo".to_string(), "completed".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn a_frame_slower_than_the_idle_timeout_fails_the_stream() {
+        let events = events_with_faults(
+            crate::fault_injection::FaultPlan::new().delay(1, Duration::from_millis(500)),
+            StreamTimeouts::fixed(Duration::from_millis(100)),
+        )
+        .await;
+        assert_eq!(
+            events,
+            vec![
+                "Hel".to_string(),
+                "idle timeout waiting for SSE".to_string()
+            ]
+        );
+
+        let events = events_with_faults(
+            crate::fault_injection::FaultPlan::new().delay(1, Duration::from_millis(20)),
+            StreamTimeouts::fixed(Duration::from_millis(500)),
+        )
+        .await;
+        assert_eq!(
+            events,
+            vec!["Hel".to_string(), "lo".to_string(), "completed".to_string()]
+        );
+    }
+
+    #[test]
+    fn usage_counts_from_a_server_cannot_overflow_the_total() {
+        let usage = parse_usage(&json!({
+            "prompt_tokens": i64::MAX,
+            "completion_tokens": 7,
+        }));
+        assert_eq!(usage.map(|usage| usage.total_tokens), Some(i64::MAX));
+    }
+
+    /// `value` with the keys of its objects sorted, so snapshots do not depend
+    /// on whether serde_json preserves insertion order in this build.
+    fn sorted_keys(value: serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => {
+                let sorted: std::collections::BTreeMap<_, _> = map
+                    .into_iter()
+                    .map(|(key, value)| (key, sorted_keys(value)))
+                    .collect();
+                serde_json::Value::Object(sorted.into_iter().collect())
+            }
+            serde_json::Value::Array(items) => {
+                serde_json::Value::Array(items.into_iter().map(sorted_keys).collect())
+            }
+            other => other,
+        }
+    }
+
+    fn payload_snapshot(prompt: &Prompt, oss_sampling: Option<&OssSampling>) -> String {
+        let model_family =
+            crate::model_family::find_family_for_model("gpt-oss:20b").expect("gpt-oss family");
+        let payload =
+            chat_completions_payload(prompt, &model_family, oss_sampling).expect("payload");
+        serde_json::to_string_pretty(&sorted_keys(payload)).expect("json")
+    }
+
+    fn tool_call_prompt() -> Prompt {
+        Prompt {
+            input: vec![
+                ResponseItem::Message {
+                    id: None,
+                    role: "user".to_string(),
+                    content: vec![ContentItem::InputText {
+                        text: "List the files.".to_string(),
+                    }],
+                },
+                ResponseItem::FunctionCall {
+                    id: None,
+                    name: "shell".to_string(),
+                    arguments: r#"{"command":["ls"]}"#.to_string(),
+                    call_id: "call_1".to_string(),
+                },
+                ResponseItem::FunctionCallOutput {
+                    call_id: "call_1".to_string(),
+                    output: codex_protocol::models::FunctionCallOutputPayload {
+                        content: "Cargo.toml\nsrc".to_string(),
+                        success: Some(true),
+                    },
+                },
+                ResponseItem::Message {
+                    id: None,
+                    role: "assistant".to_string(),
+                    content: vec![ContentItem::OutputText {
+                        text: "There is a Cargo.toml and a src directory.".to_string(),
+                    }],
+                },
+            ],
+            base_instructions_override: Some("You are a coding agent.".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn local_request_payloads_match_their_snapshots() {
+        let prompt = tool_call_prompt();
+        let sampling = OssSampling {
+            temperature: Some(0.2),
+            top_p: None,
+            max_tokens: Some(4096),
+        };
+        insta::assert_snapshot!(
+            "local_request_with_sampling",
+            payload_snapshot(&prompt, Some(&sampling))
+        );
+        insta::assert_snapshot!(
+            "local_request_with_default_sampling",
+            payload_snapshot(&prompt, Some(&OssSampling::default()))
+        );
+    }
+
+    #[test]
+    fn remote_request_payload_matches_its_snapshot() {
+        insta::assert_snapshot!(
+            "remote_request",
+            payload_snapshot(&tool_call_prompt(), None)
+        );
+    }
+
+    #[test]
+    fn local_error_messages_are_read_from_either_shape() {
+        assert_eq!(
+            local_error_message(r#"{"error":"model \"qwen3\" not found, try pulling it first"}"#),
+            r#"model "qwen3" not found, try pulling it first"#
+        );
+        assert_eq!(
+            local_error_message(
+                r#"{"error":{"message":"Model not found","type":"invalid_request_error"}}"#
+            ),
+            "Model not found"
+        );
+        assert_eq!(
+            local_error_message("404 page not found\n"),
+            "404 page not found"
+        );
+    }
+}
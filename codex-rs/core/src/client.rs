@@ -1,5 +1,6 @@
 use std::io::BufRead;
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::OnceLock;
 use std::time::Duration;
@@ -10,6 +11,7 @@ use chrono::Utc;
 use codex_app_server_protocol::AuthMode;
 use codex_otel::otel_event_manager::OtelEventManager;
 use codex_protocol::ConversationId;
+use codex_protocol::config_types::OssBackend;
 use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
 use codex_protocol::models::ResponseItem;
@@ -31,6 +33,8 @@ use tracing::warn;
 use crate::AuthManager;
 use crate::auth::CodexAuth;
 use crate::chat_completions::AggregateStreamExt;
+use crate::chat_completions::DEFAULT_STALL_TIMEOUT;
+use crate::chat_completions::OssRequest;
 use crate::chat_completions::stream_chat_completions;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
@@ -40,6 +44,7 @@ use crate::client_common::create_reasoning_param_for_request;
 use crate::client_common::create_text_param_for_request;
 use crate::config::Config;
 use crate::default_client::create_client;
+use crate::default_client::create_oss_client;
 use crate::error::CodexErr;
 use crate::error::ConnectionFailedError;
 use crate::error::ResponseStreamFailed;
@@ -49,9 +54,17 @@ use crate::error::UnexpectedResponseError;
 use crate::error::UsageLimitReachedError;
 use crate::flags::CODEX_RS_SSE_FIXTURE;
 use crate::model_family::ModelFamily;
+use crate::model_family::derive_default_model_family;
+use crate::model_family::find_family_for_model;
+use crate::model_provider_info::BUILT_IN_OSS_MODEL_PROVIDER_ID;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
 use crate::openai_model_info::get_model_info;
+use crate::oss_circuit::OssCircuit;
+use crate::oss_credentials::remote_oss_token;
+use crate::oss_latency::LatencyBudget;
+use crate::oss_limiter::OssLimiter;
+use crate::oss_limiter::default_max_in_flight;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::RateLimitWindow;
 use crate::protocol::TokenUsage;
@@ -76,6 +89,19 @@ struct Error {
     resets_at: Option<i64>,
 }
 
+/// Body of an OpenAI-compatible `/embeddings` response.
+#[derive(Debug, Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    #[serde(default)]
+    index: usize,
+    embedding: Vec<f32>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ModelClient {
     config: Arc<Config>,
@@ -86,6 +112,9 @@ pub struct ModelClient {
     conversation_id: ConversationId,
     effort: Option<ReasoningEffortConfig>,
     summary: ReasoningSummaryConfig,
+    oss_circuit: OssCircuit,
+    oss_latency: LatencyBudget,
+    oss_limiter: Option<OssLimiter>,
 }
 
 impl ModelClient {
@@ -98,7 +127,26 @@ impl ModelClient {
         summary: ReasoningSummaryConfig,
         conversation_id: ConversationId,
     ) -> Self {
-        let client = create_client();
+        let is_oss = config.model_provider_id == BUILT_IN_OSS_MODEL_PROVIDER_ID;
+        let client = if is_oss {
+            let token = provider
+                .base_url
+                .as_deref()
+                .and_then(|base_url| remote_oss_token(&config.codex_home, base_url));
+            create_oss_client(&config.oss.connection, token.as_deref())
+        } else {
+            create_client()
+        };
+        let oss_limiter = provider
+            .base_url
+            .as_deref()
+            .filter(|_| is_oss)
+            .and_then(|base_url| {
+                let max_in_flight = config.oss.max_concurrent_requests.unwrap_or_else(|| {
+                    default_max_in_flight(config.oss.provider.unwrap_or(OssBackend::Ollama))
+                });
+                OssLimiter::for_server(base_url, max_in_flight)
+            });
 
         Self {
             config,
@@ -109,7 +157,141 @@ impl ModelClient {
             conversation_id,
             effort,
             summary,
+            oss_circuit: OssCircuit::default(),
+            oss_latency: LatencyBudget::default(),
+            oss_limiter,
+        }
+    }
+
+    /// Count failed requests to the local server together with the other
+    /// clients of the session.
+    pub(crate) fn set_oss_circuit(&mut self, circuit: OssCircuit) {
+        self.oss_circuit = circuit;
+    }
+
+    pub(crate) fn get_oss_circuit(&self) -> OssCircuit {
+        self.oss_circuit.clone()
+    }
+
+    /// Size stream timeouts after the local server's speeds observed by the
+    /// other clients of the session.
+    pub(crate) fn set_latency_budget(&mut self, budget: LatencyBudget) {
+        self.oss_latency = budget;
+    }
+
+    pub(crate) fn get_latency_budget(&self) -> LatencyBudget {
+        self.oss_latency.clone()
+    }
+
+    /// Client for summarizing the conversation: `oss.summary_model` on the
+    /// same local server when one is set, with `oss.summary_context_window`
+    /// as its window, otherwise this client.
+    pub(crate) fn summary_client(&self) -> Self {
+        match self
+            .config
+            .oss
+            .summary_model
+            .as_deref()
+            .filter(|_| self.get_oss_backend().is_some())
+        {
+            Some(model) if model != self.config.model => {
+                self.with_model(model, self.config.oss.summary_context_window)
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Client for the cloud model that reviews the local model's answers
+    /// (`oss.cloud_review_model`), when this is the `oss` provider, one is
+    /// set, and offline mode does not forbid it.
+    pub(crate) fn cloud_review_client(&self) -> Option<Self> {
+        self.get_oss_backend()?;
+        if self.config.oss.offline {
+            return None;
+        }
+        let model = self.config.oss.cloud_review_model.as_deref()?;
+        let provider_id = &self.config.oss.cloud_review_provider;
+        let provider = self.config.model_providers.get(provider_id)?.clone();
+
+        let mut config = (*self.config).clone();
+        config.model = model.to_string();
+        config.model_family =
+            find_family_for_model(model).unwrap_or_else(|| derive_default_model_family(model));
+        config.model_context_window =
+            get_model_info(&config.model_family).map(|info| info.context_window);
+        config.model_provider_id = provider_id.clone();
+        config.model_provider = provider.clone();
+        Some(Self::new(
+            Arc::new(config),
+            self.auth_manager.clone(),
+            self.otel_event_manager.with_model(model, model),
+            provider,
+            None,
+            self.summary,
+            self.conversation_id,
+        ))
+    }
+
+    /// This client talking to `model` instead. It shares the connection
+    /// pool, circuit, and request limit of the server with this client, but
+    /// not the observed speeds, which belong to the other model.
+    /// `context_window` overrides the window known for `model`.
+    fn with_model(&self, model: &str, context_window: Option<i64>) -> Self {
+        let mut config = (*self.config).clone();
+        config.model = model.to_string();
+        config.model_family =
+            find_family_for_model(model).unwrap_or_else(|| derive_default_model_family(model));
+        config.model_context_window = context_window
+            .or_else(|| get_model_info(&config.model_family).map(|info| info.context_window));
+        Self {
+            config: Arc::new(config),
+            otel_event_manager: self.otel_event_manager.with_model(model, model),
+            oss_latency: LatencyBudget::default(),
+            ..self.clone()
+        }
+    }
+
+    /// Embed `inputs` with `oss.embedding_model` on the local server,
+    /// returning one vector per input, in order.
+    pub(crate) async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        let Some(model) = self.get_embedding_model() else {
+            return Err(CodexErr::UnsupportedOperation(
+                "embeddings need a local embedding model; set `oss.embedding_model`".to_string(),
+            ));
+        };
+        let request = self
+            .provider
+            .apply_http_headers(self.client.post(self.provider.get_embeddings_url()))
+            .json(&serde_json::json!({ "model": &model, "input": inputs }));
+        let response = request
+            .send()
+            .await
+            .map_err(|source| CodexErr::ConnectionFailed(ConnectionFailedError { source }))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(CodexErr::UnexpectedStatus(UnexpectedResponseError {
+                status,
+                body: response.text().await.unwrap_or_default(),
+                request_id: None,
+            }));
         }
+        let body: EmbeddingsResponse = response
+            .json()
+            .await
+            .map_err(|source| CodexErr::ConnectionFailed(ConnectionFailedError { source }))?;
+        let mut data = body.data;
+        if data.len() != inputs.len() {
+            return Err(CodexErr::Stream(
+                format!(
+                    "embedding with {model} returned {} vectors for {} inputs",
+                    data.len(),
+                    inputs.len()
+                ),
+                None,
+            ));
+        }
+        data.sort_by_key(|item| item.index);
+        Ok(data.into_iter().map(|item| item.embedding).collect())
     }
 
     pub fn get_model_context_window(&self) -> Option<i64> {
@@ -142,9 +324,24 @@ impl ModelClient {
             WireApi::Responses => self.stream_responses(prompt, task_kind).await,
             WireApi::Chat => {
                 // Create the raw streaming connection first.
+                let oss = self.get_oss_backend().map(|backend| OssRequest {
+                    backend,
+                    sampling: &self.config.oss.sampling,
+                    circuit: &self.oss_circuit,
+                    latency: &self.oss_latency,
+                    limiter: self.oss_limiter.as_ref(),
+                    cacheable: task_kind != TaskKind::Regular,
+                    dump_sse: self.config.oss.debug_dump_sse.as_deref(),
+                    stall_timeout: self
+                        .config
+                        .oss
+                        .stall_timeout
+                        .unwrap_or(DEFAULT_STALL_TIMEOUT),
+                });
                 let response_stream = stream_chat_completions(
                     prompt,
                     &self.config.model_family,
+                    oss,
                     &self.client,
                     &self.provider,
                     &self.otel_event_manager,
@@ -463,6 +660,23 @@ impl ModelClient {
         self.config.model.clone()
     }
 
+    /// Backend of the local server, when this is the `oss` provider.
+    pub fn get_oss_backend(&self) -> Option<OssBackend> {
+        (self.config.model_provider_id == BUILT_IN_OSS_MODEL_PROVIDER_ID)
+            .then(|| self.config.oss.provider.unwrap_or(OssBackend::Ollama))
+    }
+
+    /// Model that embeds text on the local server (`oss.embedding_model`),
+    /// when this is the `oss` provider and one is set.
+    pub(crate) fn get_embedding_model(&self) -> Option<String> {
+        self.get_oss_backend()
+            .and(self.config.oss.embedding_model.clone())
+    }
+
+    pub(crate) fn get_codex_home(&self) -> PathBuf {
+        self.config.codex_home.clone()
+    }
+
     /// Returns the currently configured model family.
     pub fn get_model_family(&self) -> ModelFamily {
         self.config.model_family.clone()
@@ -1389,6 +1603,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn summaries_go_to_the_configured_local_model() -> std::io::Result<()> {
+        use crate::config::ConfigOverrides;
+        use crate::config::ConfigToml;
+
+        let codex_home = tempfile::TempDir::new()?;
+        let client_for = |model_provider: &str| -> std::io::Result<ModelClient> {
+            let cfg = toml::from_str::<ConfigToml>(
+                r#"
+[oss]
+summary_model = "qwen3:1.7b"
+"#,
+            )
+            .expect("TOML deserialization should succeed");
+            let config = Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides {
+                    model: Some("gpt-oss:20b".to_string()),
+                    model_provider: Some(model_provider.to_string()),
+                    ..Default::default()
+                },
+                codex_home.path().to_path_buf(),
+            )?;
+            let provider = config.model_provider.clone();
+            Ok(ModelClient::new(
+                Arc::new(config),
+                None,
+                otel_event_manager(),
+                provider,
+                None,
+                ReasoningSummaryConfig::Auto,
+                ConversationId::new(),
+            ))
+        };
+
+        let oss = client_for(BUILT_IN_OSS_MODEL_PROVIDER_ID)?;
+        assert_eq!(oss.summary_client().get_model(), "qwen3:1.7b");
+        assert_eq!(oss.get_model(), "gpt-oss:20b");
+
+        let cloud = client_for("openai")?;
+        assert_eq!(cloud.summary_client().get_model(), "gpt-oss:20b");
+
+        Ok(())
+    }
+
     #[test]
     fn test_try_parse_retry_after() {
         let err = Error {
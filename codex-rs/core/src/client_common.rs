@@ -2,6 +2,7 @@ use crate::client_common::tools::ToolSpec;
 use crate::error::Result;
 use crate::model_family::ModelFamily;
 use crate::protocol::RateLimitSnapshot;
+use crate::protocol::RequestTimingEvent;
 use crate::protocol::TokenUsage;
 use codex_apply_patch::APPLY_PATCH_TOOL_INSTRUCTIONS;
 use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
@@ -204,6 +205,9 @@ pub enum ResponseEvent {
         call_id: String,
     },
     RateLimits(RateLimitSnapshot),
+    /// Latency of the request, sent once before `Completed` when timing was
+    /// requested.
+    RequestTiming(RequestTimingEvent),
 }
 
 #[derive(Debug, Serialize)]
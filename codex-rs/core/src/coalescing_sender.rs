@@ -0,0 +1,176 @@
+//! Backpressure between a streamed model response and its consumer.
+//!
+//! When the consumer falls behind (a slow terminal redrawing every token,
+//! say), waiting for room in its channel stops the response body from being
+//! read; a local server then sees a stalled connection and may time the
+//! request out. Instead, text deltas that do not fit are held back and
+//! merged, so reading continues and the consumer later receives fewer,
+//! larger deltas with the same text.
+
+use std::collections::VecDeque;
+
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+
+use crate::client_common::ResponseEvent;
+use crate::error::Result;
+
+/// Most bytes of delta text held back; past this the stream waits for the
+/// consumer after all rather than buffering without bound.
+const MAX_PENDING_BYTES: usize = 1024 * 1024;
+
+/// Sender of a response's events that does not wait on the consumer for
+/// text and reasoning deltas.
+pub(crate) struct CoalescingSender {
+    tx: mpsc::Sender<Result<ResponseEvent>>,
+    /// Deltas the consumer had no room for, in order; consecutive deltas of
+    /// the same kind are merged.
+    pending: VecDeque<ResponseEvent>,
+    pending_bytes: usize,
+}
+
+impl CoalescingSender {
+    pub(crate) fn new(tx: mpsc::Sender<Result<ResponseEvent>>) -> Self {
+        Self {
+            tx,
+            pending: VecDeque::new(),
+            pending_bytes: 0,
+        }
+    }
+
+    /// Resolves once the consumer dropped its end of the stream.
+    pub(crate) async fn closed(&self) {
+        self.tx.closed().await;
+    }
+
+    /// Send an `OutputTextDelta` or `ReasoningContentDelta`, holding it back
+    /// while the consumer has no room.
+    pub(crate) async fn send_delta(&mut self, event: ResponseEvent) {
+        self.send_ready();
+        if self.pending.is_empty() {
+            match self.tx.try_send(Ok(event)) {
+                Ok(()) | Err(TrySendError::Closed(_)) => return,
+                Err(TrySendError::Full(Ok(event))) => self.hold(event),
+                Err(TrySendError::Full(Err(_))) => return,
+            }
+        } else {
+            self.hold(event);
+        }
+        if self.pending_bytes > MAX_PENDING_BYTES {
+            self.flush().await;
+        }
+    }
+
+    /// Send any other event after the deltas held back, waiting for room.
+    pub(crate) async fn send(
+        &mut self,
+        event: Result<ResponseEvent>,
+    ) -> std::result::Result<(), mpsc::error::SendError<Result<ResponseEvent>>> {
+        self.flush().await;
+        self.tx.send(event).await
+    }
+
+    fn hold(&mut self, event: ResponseEvent) {
+        self.pending_bytes += delta_len(&event);
+        match (self.pending.back_mut(), event) {
+            (Some(ResponseEvent::OutputTextDelta(held)), ResponseEvent::OutputTextDelta(text))
+            | (
+                Some(ResponseEvent::ReasoningContentDelta(held)),
+                ResponseEvent::ReasoningContentDelta(text),
+            ) => held.push_str(&text),
+            (_, event) => self.pending.push_back(event),
+        }
+    }
+
+    /// Pass held deltas on as far as the consumer has room.
+    fn send_ready(&mut self) {
+        while let Some(event) = self.pending.pop_front() {
+            let len = delta_len(&event);
+            match self.tx.try_send(Ok(event)) {
+                Ok(()) => self.pending_bytes -= len,
+                Err(TrySendError::Full(Ok(event))) => {
+                    self.pending.push_front(event);
+                    return;
+                }
+                Err(TrySendError::Full(Err(_)) | TrySendError::Closed(_)) => {
+                    self.pending.clear();
+                    self.pending_bytes = 0;
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn flush(&mut self) {
+        while let Some(event) = self.pending.pop_front() {
+            if self.tx.send(Ok(event)).await.is_err() {
+                self.pending.clear();
+                break;
+            }
+        }
+        self.pending_bytes = 0;
+    }
+}
+
+fn delta_len(event: &ResponseEvent) -> usize {
+    match event {
+        ResponseEvent::OutputTextDelta(text) | ResponseEvent::ReasoningContentDelta(text) => {
+            text.len()
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn describe(event: Result<ResponseEvent>) -> String {
+        match event {
+            Ok(ResponseEvent::OutputTextDelta(text)) => format!("text:{text}"),
+            Ok(ResponseEvent::ReasoningContentDelta(text)) => format!("reasoning:{text}"),
+            Ok(ResponseEvent::Completed { .. }) => "completed".to_string(),
+            other => format!("{other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn deltas_a_slow_consumer_has_no_room_for_are_merged_in_order() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut sender = CoalescingSender::new(tx);
+        // None of these wait, although the consumer reads nothing.
+        for delta in ["a", "b", "c"] {
+            sender
+                .send_delta(ResponseEvent::OutputTextDelta(delta.to_string()))
+                .await;
+        }
+        sender
+            .send_delta(ResponseEvent::ReasoningContentDelta("r".to_string()))
+            .await;
+        sender
+            .send_delta(ResponseEvent::OutputTextDelta("d".to_string()))
+            .await;
+
+        let consumer = tokio::spawn(async move {
+            let mut received = Vec::new();
+            while let Some(event) = rx.recv().await {
+                received.push(describe(event));
+            }
+            received
+        });
+        sender
+            .send(Ok(ResponseEvent::Completed {
+                response_id: String::new(),
+                token_usage: None,
+            }))
+            .await
+            .expect("send");
+        drop(sender);
+
+        assert_eq!(
+            consumer.await.expect("consumer"),
+            vec!["text:a", "text:bc", "reasoning:r", "text:d", "completed"]
+        );
+    }
+}
@@ -62,14 +62,20 @@ use crate::conversation_history::ConversationHistory;
 use crate::environment_context::EnvironmentContext;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
+use crate::error::Retriability;
 #[cfg(test)]
 use crate::exec::StreamOutput;
 // Removed: legacy executor wiring replaced by ToolOrchestrator flows.
 // legacy normalize_exec_result no longer used after orchestrator migration
 use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::model_family::ModelFamily;
+use crate::model_family::derive_default_model_family;
 use crate::model_family::find_family_for_model;
 use crate::openai_model_info::get_model_info;
+use crate::oss_circuit::OssCircuit;
+use crate::oss_latency::LatencyBudget;
+use crate::oss_semantic_index::WorkspaceIndexer;
 use crate::project_doc::get_user_instructions;
 use crate::protocol::AgentMessageDeltaEvent;
 use crate::protocol::AgentReasoningDeltaEvent;
@@ -118,6 +124,7 @@ use crate::user_notification::UserNotification;
 use crate::util::backoff;
 use codex_async_utils::OrCancelExt;
 use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::config_types::OssSampling;
 use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
 use codex_protocol::custom_prompts::CustomPrompt;
@@ -128,6 +135,7 @@ use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::InitialHistory;
 use codex_protocol::user_input::UserInput;
 
+mod cloud_review;
 pub mod compact;
 use self::compact::build_compacted_history;
 use self::compact::collect_user_messages;
@@ -176,6 +184,7 @@ impl Codex {
             approval_policy: config.approval_policy,
             sandbox_policy: config.sandbox_policy.clone(),
             cwd: config.cwd.clone(),
+            oss_sampling: config.oss.sampling,
             original_config_do_not_use: Arc::clone(&config),
         };
 
@@ -312,6 +321,9 @@ pub(crate) struct SessionConfiguration {
     /// operate deterministically.
     cwd: PathBuf,
 
+    /// Sampling parameters for the local `oss` provider.
+    oss_sampling: OssSampling,
+
     // TODO(pakrym): Remove config from here
     original_config_do_not_use: Arc<Config>,
 }
@@ -337,6 +349,9 @@ impl SessionConfiguration {
         if let Some(cwd) = updates.cwd.clone() {
             next_configuration.cwd = cwd;
         }
+        if let Some(oss_sampling) = updates.oss_sampling {
+            next_configuration.oss_sampling = oss_sampling;
+        }
         next_configuration
     }
 }
@@ -350,6 +365,20 @@ pub(crate) struct SessionSettingsUpdate {
     pub(crate) reasoning_effort: Option<Option<ReasoningEffortConfig>>,
     pub(crate) reasoning_summary: Option<ReasoningSummaryConfig>,
     pub(crate) final_output_json_schema: Option<Option<Value>>,
+    pub(crate) oss_sampling: Option<OssSampling>,
+}
+
+/// Capabilities of `model` for a turn. A model switched to mid-session, e.g.
+/// another local `--oss` model, gets its own defaults instead of inheriting
+/// those of the model the session started with.
+fn model_family_for_turn(model: &str, config: &Config) -> ModelFamily {
+    find_family_for_model(model).unwrap_or_else(|| {
+        if model == config.model {
+            config.model_family.clone()
+        } else {
+            derive_default_model_family(model)
+        }
+    })
 }
 
 impl Session {
@@ -363,13 +392,13 @@ impl Session {
         sub_id: String,
     ) -> TurnContext {
         let config = session_configuration.original_config_do_not_use.clone();
-        let model_family = find_family_for_model(&session_configuration.model)
-            .unwrap_or_else(|| config.model_family.clone());
+        let model_family = model_family_for_turn(&session_configuration.model, &config);
         let mut per_turn_config = (*config).clone();
         per_turn_config.model = session_configuration.model.clone();
         per_turn_config.model_family = model_family.clone();
         per_turn_config.model_reasoning_effort = session_configuration.model_reasoning_effort;
         per_turn_config.model_reasoning_summary = session_configuration.model_reasoning_summary;
+        per_turn_config.oss.sampling = session_configuration.oss_sampling;
         if let Some(model_info) = get_model_info(&model_family) {
             per_turn_config.model_context_window = Some(model_info.context_window);
         }
@@ -389,10 +418,11 @@ impl Session {
             conversation_id,
         );
 
-        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+        let mut tools_config = ToolsConfig::new(&ToolsConfigParams {
             model_family: &model_family,
             features: &config.features,
         });
+        tools_config.semantic_search = client.get_embedding_model().is_some();
 
         let item_collector = ItemCollector::new(tx_event, conversation_id, sub_id.clone());
 
@@ -504,7 +534,10 @@ impl Session {
                 error!("{message}");
                 post_session_configured_error_events.push(Event {
                     id: INITIAL_SUBMIT_ID.to_owned(),
-                    msg: EventMsg::Error(ErrorEvent { message }),
+                    msg: EventMsg::Error(ErrorEvent {
+                        message,
+                        connection_lost: false,
+                    }),
                 });
                 (McpConnectionManager::default(), Default::default())
             }
@@ -520,6 +553,7 @@ impl Session {
                     id: INITIAL_SUBMIT_ID.to_owned(),
                     msg: EventMsg::Error(ErrorEvent {
                         message: display_message,
+                        connection_lost: false,
                     }),
                 });
             }
@@ -562,6 +596,9 @@ impl Session {
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager,
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            oss_circuit: OssCircuit::default(),
+            oss_latency: LatencyBudget::default(),
+            semantic_index: WorkspaceIndexer::default(),
         };
 
         let sess = Arc::new(Session {
@@ -668,6 +705,27 @@ impl Session {
             self.get_tx_event(),
             sub_id,
         );
+        turn_context
+            .client
+            .set_oss_circuit(self.services.oss_circuit.clone());
+        turn_context
+            .client
+            .set_latency_budget(self.services.oss_latency.clone());
+        // The first turn is made as the session starts, so `semantic_search`
+        // finds the workspace indexed, or well on the way, by the time the
+        // model first calls it. A turn in another directory moves the index
+        // there.
+        if turn_context.tools_config.semantic_search
+            && let Err(err) = self
+                .services
+                .semantic_index
+                .start(&turn_context.client, &turn_context.cwd)
+        {
+            warn!(
+                "not indexing {} for semantic search: {err}",
+                turn_context.cwd.display()
+            );
+        }
         if let Some(final_schema) = updates.final_output_json_schema {
             turn_context.final_output_json_schema = final_schema;
         }
@@ -1107,6 +1165,7 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                 model,
                 effort,
                 summary,
+                oss_sampling,
             } => {
                 let updates = SessionSettingsUpdate {
                     cwd,
@@ -1115,6 +1174,7 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                     model,
                     reasoning_effort: effort,
                     reasoning_summary: summary,
+                    oss_sampling,
                     ..Default::default()
                 };
                 sess.update_settings(updates).await;
@@ -1141,6 +1201,7 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                             reasoning_effort: Some(effort),
                             reasoning_summary: Some(summary),
                             final_output_json_schema: Some(final_output_json_schema),
+                            oss_sampling: None,
                         },
                     ),
                     Op::UserInput { items } => (items, SessionSettingsUpdate::default()),
@@ -1315,6 +1376,7 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                         id: sub.id.clone(),
                         msg: EventMsg::Error(ErrorEvent {
                             message: "Failed to shutdown rollout recorder".to_string(),
+                            connection_lost: false,
                         }),
                     };
                     sess.send_event_raw(event).await;
@@ -1421,7 +1483,7 @@ async fn spawn_review_thread(
         );
 
     let per_turn_config = Arc::new(per_turn_config);
-    let client = ModelClient::new(
+    let mut client = ModelClient::new(
         per_turn_config.clone(),
         auth_manager,
         otel_event_manager,
@@ -1430,6 +1492,8 @@ async fn spawn_review_thread(
         per_turn_config.model_reasoning_summary,
         sess.conversation_id,
     );
+    client.set_oss_circuit(parent_turn_context.client.get_oss_circuit());
+    client.set_latency_budget(parent_turn_context.client.get_latency_budget());
 
     let review_turn_context = TurnContext {
         sub_id: sub_id.to_string(),
@@ -1495,6 +1559,15 @@ pub(crate) async fn run_task(
     });
     sess.send_event(&turn_context, event).await;
 
+    // What the user asked, for a cloud review of the local model's answer.
+    let request_text = input
+        .iter()
+        .filter_map(|item| match item {
+            UserInput::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
     // For review threads, keep an isolated in-memory history so the
     // model sees a fresh conversation without the parent session's history.
@@ -1699,6 +1772,7 @@ pub(crate) async fn run_task(
                             message: format!(
                                 "Conversation is still above the token limit after automatic summarization (limit {limit_str}, current {current_tokens}). Please start a new session or trim your input."
                             ),
+                            connection_lost: false,
                         });
                         sess.send_event(&turn_context, event).await;
                         break;
@@ -1714,6 +1788,28 @@ pub(crate) async fn run_task(
                     last_agent_message = get_last_assistant_message_from_turn(
                         &items_to_record_in_conversation_history,
                     );
+                    if task_kind == TaskKind::Regular
+                        && !is_review_mode
+                        && let Some(draft) = last_agent_message.as_deref()
+                    {
+                        let diff = turn_diff_tracker
+                            .lock()
+                            .await
+                            .get_unified_diff()
+                            .ok()
+                            .flatten();
+                        if let Some(revised) = cloud_review::review_turn(
+                            &sess,
+                            &turn_context,
+                            &request_text,
+                            draft,
+                            diff.as_deref(),
+                        )
+                        .await
+                        {
+                            last_agent_message = Some(revised);
+                        }
+                    }
                     sess.notifier()
                         .notify(&UserNotification::AgentTurnComplete {
                             thread_id: sess.conversation_id.to_string(),
@@ -1734,6 +1830,7 @@ pub(crate) async fn run_task(
                 info!("Turn error: {e:#}");
                 let event = EventMsg::Error(ErrorEvent {
                     message: e.to_string(),
+                    connection_lost: e.is_connection_lost(),
                 });
                 sess.send_event(&turn_context, event).await;
                 // let the user continue the conversation
@@ -1827,10 +1924,6 @@ async fn run_turn(
         .await
         {
             Ok(output) => return Ok(output),
-            Err(CodexErr::TurnAborted) => return Err(CodexErr::TurnAborted),
-            Err(CodexErr::Interrupted) => return Err(CodexErr::Interrupted),
-            Err(CodexErr::EnvVar(var)) => return Err(CodexErr::EnvVar(var)),
-            Err(e @ CodexErr::Fatal(_)) => return Err(e),
             Err(e @ CodexErr::ContextWindowExceeded) => {
                 sess.set_total_tokens_full(turn_context.as_ref()).await;
                 return Err(e);
@@ -1843,7 +1936,7 @@ async fn run_turn(
                 }
                 return Err(CodexErr::UsageLimitReached(e));
             }
-            Err(CodexErr::UsageNotIncluded) => return Err(CodexErr::UsageNotIncluded),
+            Err(e) if e.retriability() != Retriability::Retriable => return Err(e),
             Err(e) => {
                 // Use the configured provider-specific stream retry budget.
                 let max_retries = turn_context.client.get_provider().stream_max_retries();
@@ -1963,6 +2056,7 @@ async fn try_run_turn(
         model: turn_context.client.get_model(),
         effort: turn_context.client.get_reasoning_effort(),
         summary: turn_context.client.get_reasoning_summary(),
+        oss_backend: turn_context.client.get_oss_backend(),
     });
     sess.persist_rollout_items(&[rollout_item]).await;
     let mut stream = turn_context
@@ -2084,6 +2178,10 @@ async fn try_run_turn(
                 sess.update_rate_limits(turn_context.as_ref(), snapshot)
                     .await;
             }
+            ResponseEvent::RequestTiming(timing) => {
+                sess.send_event(&turn_context, EventMsg::RequestTiming(timing))
+                    .await;
+            }
             ResponseEvent::Completed {
                 response_id: _,
                 token_usage,
@@ -2358,6 +2456,29 @@ mod tests {
     use std::sync::Arc;
     use std::time::Duration as StdDuration;
 
+    #[test]
+    fn switched_models_get_their_own_family() {
+        let codex_home = tempfile::tempdir().expect("create temp dir");
+        let config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides {
+                model: Some("gpt-oss:20b".to_string()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )
+        .expect("load test config");
+
+        assert_eq!(
+            model_family_for_turn("gpt-oss:20b", &config),
+            config.model_family
+        );
+        assert_eq!(
+            model_family_for_turn("qwen3:8b", &config),
+            derive_default_model_family("qwen3:8b")
+        );
+    }
+
     #[test]
     fn reconstruct_history_matches_live_compactions() {
         let (session, turn_context) = make_session_and_context();
@@ -2626,6 +2747,7 @@ mod tests {
             approval_policy: config.approval_policy,
             sandbox_policy: config.sandbox_policy.clone(),
             cwd: config.cwd.clone(),
+            oss_sampling: config.oss.sampling,
             original_config_do_not_use: Arc::clone(&config),
         };
 
@@ -2641,6 +2763,9 @@ mod tests {
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            oss_circuit: OssCircuit::default(),
+            oss_latency: LatencyBudget::default(),
+            semantic_index: WorkspaceIndexer::default(),
         };
 
         let turn_context = Session::make_turn_context(
@@ -2695,6 +2820,7 @@ mod tests {
             approval_policy: config.approval_policy,
             sandbox_policy: config.sandbox_policy.clone(),
             cwd: config.cwd.clone(),
+            oss_sampling: config.oss.sampling,
             original_config_do_not_use: Arc::clone(&config),
         };
 
@@ -2710,6 +2836,9 @@ mod tests {
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            oss_circuit: OssCircuit::default(),
+            oss_latency: LatencyBudget::default(),
+            semantic_index: WorkspaceIndexer::default(),
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
@@ -0,0 +1,208 @@
+//! Cloud review of the local model's answers (`oss.cloud_review_model`).
+//!
+//! The local model does the work of a turn, tool calls and all. Once it has
+//! answered, the cloud model reads the request, the answer, and the diff of
+//! the turn, and either approves the answer or writes the one the user should
+//! read instead. The cloud model sees one request per turn rather than every
+//! step of it, which is where the savings come from.
+
+use askama::Template;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use futures::prelude::*;
+
+use super::Session;
+use super::TurnContext;
+use super::get_last_assistant_message_from_turn;
+use crate::ModelClient;
+use crate::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::error::CodexErr;
+use crate::error::Result as CodexResult;
+use crate::protocol::CloudReviewEvent;
+use crate::protocol::EventMsg;
+use crate::state::TaskKind;
+use crate::truncate::truncate_middle;
+
+const REVIEW_INSTRUCTIONS: &str = include_str!("../../templates/cloud_review/instructions.md");
+
+/// The reviewer's whole answer when the draft stands.
+const APPROVED: &str = "APPROVED";
+
+/// Cap on the diff sent for review (approx. 4 bytes/token), so that a turn
+/// that rewrote many files does not cost more to review than to redo.
+const MAX_DIFF_BYTES: usize = 40_000;
+
+#[derive(Template)]
+#[template(path = "cloud_review/request.md", escape = "none")]
+struct ReviewRequestTemplate<'a> {
+    request: &'a str,
+    draft: &'a str,
+    diff: Option<&'a str>,
+}
+
+/// Have the cloud reviewer look at `draft`, the local model's answer to
+/// `request`, and report the outcome. Returns the revised answer when the
+/// reviewer replaced the draft. A failed review keeps the draft.
+pub(crate) async fn review_turn(
+    sess: &Session,
+    turn_context: &TurnContext,
+    request: &str,
+    draft: &str,
+    diff: Option<&str>,
+) -> Option<String> {
+    let reviewer = turn_context.client.cloud_review_client()?;
+    let reviewer_model = reviewer.get_model();
+    let draft_model = turn_context.client.get_model();
+
+    let outcome = match request_review(&reviewer, request, draft, diff).await {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            sess.notify_background_event(
+                turn_context,
+                format!("Review by {reviewer_model} failed: {err}. Keeping the local answer."),
+            )
+            .await;
+            return None;
+        }
+    };
+
+    let revised_message = revised_answer(&outcome);
+    if let Some(revised) = &revised_message {
+        sess.record_conversation_items(&[revision_item(&reviewer_model, revised)])
+            .await;
+    }
+    sess.send_event(
+        turn_context,
+        EventMsg::CloudReview(CloudReviewEvent {
+            draft_model,
+            reviewer_model,
+            revised_message: revised_message.clone(),
+        }),
+    )
+    .await;
+    revised_message
+}
+
+/// The reviewer's answer replacing the draft, or `None` when it approved it.
+fn revised_answer(outcome: &str) -> Option<String> {
+    let outcome = outcome.trim();
+    if outcome.is_empty() || outcome.trim_matches(['`', '.', '*']) == APPROVED {
+        None
+    } else {
+        Some(outcome.to_string())
+    }
+}
+
+/// The revised answer as recorded after the draft. The draft stays in the
+/// history, where the rollout already has it, so the revision says that it
+/// supersedes it; later turns then build on the revision alone.
+fn revision_item(reviewer_model: &str, revised: &str) -> ResponseItem {
+    ResponseItem::Message {
+        id: None,
+        role: "assistant".to_string(),
+        content: vec![ContentItem::OutputText {
+            text: format!(
+                "[Revised by {reviewer_model}; this answer supersedes the previous one.]\n\n{revised}"
+            ),
+        }],
+    }
+}
+
+async fn request_review(
+    reviewer: &ModelClient,
+    request: &str,
+    draft: &str,
+    diff: Option<&str>,
+) -> CodexResult<String> {
+    let diff = diff.map(|diff| {
+        if diff.len() > MAX_DIFF_BYTES {
+            truncate_middle(diff, MAX_DIFF_BYTES).0
+        } else {
+            diff.to_string()
+        }
+    });
+    let text = ReviewRequestTemplate {
+        request,
+        draft,
+        diff: diff.as_deref(),
+    }
+    .render()
+    .map_err(|err| CodexErr::Fatal(format!("failed to render the review request: {err}")))?;
+    let prompt = Prompt {
+        input: vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText { text }],
+        }],
+        base_instructions_override: Some(REVIEW_INSTRUCTIONS.to_string()),
+        ..Default::default()
+    };
+
+    let mut stream = reviewer
+        .stream_with_task_kind(&prompt, TaskKind::Regular)
+        .await?;
+    let mut items = Vec::new();
+    loop {
+        match stream.next().await {
+            Some(Ok(ResponseEvent::OutputItemDone(item))) => items.push(item),
+            Some(Ok(ResponseEvent::Completed { .. })) => break,
+            Some(Ok(_)) => continue,
+            Some(Err(err)) => return Err(err),
+            None => {
+                return Err(CodexErr::Stream(
+                    "stream closed before response.completed".into(),
+                    None,
+                ));
+            }
+        }
+    }
+    Ok(get_last_assistant_message_from_turn(&items).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn an_approval_keeps_the_draft() {
+        assert_eq!(revised_answer("APPROVED"), None);
+        assert_eq!(revised_answer("`APPROVED`\n"), None);
+        assert_eq!(revised_answer(""), None);
+        assert_eq!(
+            revised_answer("The fix is incomplete: `parse` still panics on empty input."),
+            Some("The fix is incomplete: `parse` still panics on empty input.".to_string())
+        );
+    }
+
+    #[test]
+    fn a_revision_supersedes_the_draft() {
+        let ResponseItem::Message { role, content, .. } = revision_item("gpt-5", "Use `?`.") else {
+            panic!("expected a message");
+        };
+        assert_eq!(role, "assistant");
+        assert_eq!(
+            content,
+            vec![ContentItem::OutputText {
+                text: "[Revised by gpt-5; this answer supersedes the previous one.]\n\nUse `?`."
+                    .to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn the_request_shows_the_diff_when_there_is_one() {
+        let render = |diff| {
+            ReviewRequestTemplate {
+                request: "Fix the crash",
+                draft: "Fixed it.",
+                diff,
+            }
+            .render()
+            .expect("template renders")
+        };
+        assert!(render(Some("+fn fixed() {}")).contains("```diff\n+fn fixed() {}\n```"));
+        assert!(render(None).contains("It did not change any files."));
+    }
+}
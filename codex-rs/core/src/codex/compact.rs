@@ -3,6 +3,7 @@ use std::sync::Arc;
 use super::Session;
 use super::TurnContext;
 use super::get_last_assistant_message_from_turn;
+use crate::ModelClient;
 use crate::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::error::CodexErr;
@@ -28,6 +29,12 @@ use futures::prelude::*;
 pub const SUMMARIZATION_PROMPT: &str = include_str!("../../templates/compact/prompt.md");
 const COMPACT_USER_MESSAGE_MAX_TOKENS: usize = 20_000;
 
+/// Tokens of a summarizer's context window kept free for the summary when
+/// the history is trimmed to fit it.
+const SUMMARY_RESERVED_TOKENS: usize = 2_000;
+
+const APPROX_BYTES_PER_TOKEN: usize = 4;
+
 #[derive(Template)]
 #[template(path = "compact/history_bridge.md", escape = "none")]
 struct HistoryBridgeTemplate<'a> {
@@ -67,7 +74,16 @@ async fn run_compact_task_inner(
     let mut turn_input = sess
         .turn_input_with_history(vec![initial_input_for_turn.clone().into()])
         .await;
-    let mut truncated_count = 0usize;
+    // A designated summarizer may have a far smaller context window than the
+    // session's model, and local servers tend to cut an oversized prompt
+    // silently instead of refusing it, so the oldest turns are dropped up
+    // front until the prompt fits.
+    let mut client = turn_context.client.summary_client();
+    let full_turn_input = turn_input.clone();
+    let summarizer_window = (client.get_model() != turn_context.client.get_model())
+        .then(|| client.get_model_context_window())
+        .flatten();
+    let mut truncated_count = trim_to_context_window(&mut turn_input, summarizer_window);
 
     let max_retries = turn_context.client.get_provider().stream_max_retries();
     let mut retries = 0;
@@ -79,6 +95,7 @@ async fn run_compact_task_inner(
         model: turn_context.client.get_model(),
         effort: turn_context.client.get_reasoning_effort(),
         summary: turn_context.client.get_reasoning_summary(),
+        oss_backend: turn_context.client.get_oss_backend(),
     });
     sess.persist_rollout_items(&[rollout_item]).await;
 
@@ -87,7 +104,8 @@ async fn run_compact_task_inner(
             input: turn_input.clone(),
             ..Default::default()
         };
-        let attempt_result = drain_to_completed(&sess, turn_context.as_ref(), &prompt).await;
+        let attempt_result =
+            drain_to_completed(&sess, turn_context.as_ref(), &client, &prompt).await;
 
         match attempt_result {
             Ok(()) => {
@@ -115,6 +133,7 @@ async fn run_compact_task_inner(
                 sess.set_total_tokens_full(turn_context.as_ref()).await;
                 let event = EventMsg::Error(ErrorEvent {
                     message: e.to_string(),
+                    connection_lost: false,
                 });
                 sess.send_event(&turn_context, event).await;
                 return;
@@ -130,9 +149,27 @@ async fn run_compact_task_inner(
                     .await;
                     tokio::time::sleep(delay).await;
                     continue;
+                } else if client.get_model() != turn_context.client.get_model() {
+                    // The summarizer is an optimization; a session whose
+                    // summarizer is missing or broken still compacts.
+                    sess.notify_background_event(
+                        turn_context.as_ref(),
+                        format!(
+                            "Could not summarize with {}: {e}. Summarizing with {} instead.",
+                            client.get_model(),
+                            turn_context.client.get_model()
+                        ),
+                    )
+                    .await;
+                    client = turn_context.client.clone();
+                    turn_input = full_turn_input.clone();
+                    truncated_count = 0;
+                    retries = 0;
+                    continue;
                 } else {
                     let event = EventMsg::Error(ErrorEvent {
                         message: e.to_string(),
+                        connection_lost: e.is_connection_lost(),
                     });
                     sess.send_event(&turn_context, event).await;
                     return;
@@ -235,14 +272,48 @@ pub(crate) fn build_compacted_history(
     history
 }
 
+/// Drop the oldest turns of `input`, keeping the last item (the summarization
+/// request), until its estimated size fits `context_window` tokens with room
+/// left for the summary. A turn runs from one user message to the next and
+/// goes as a whole, so no tool call is left without its output or the other
+/// way around. Returns how many items were dropped.
+fn trim_to_context_window(input: &mut Vec<ResponseItem>, context_window: Option<i64>) -> usize {
+    let Some(context_window) = context_window else {
+        return 0;
+    };
+    let budget = usize::try_from(context_window)
+        .unwrap_or(0)
+        .saturating_sub(SUMMARY_RESERVED_TOKENS);
+    let estimated_tokens = |item: &ResponseItem| {
+        serde_json::to_string(item).map_or(0, |json| json.len()) / APPROX_BYTES_PER_TOKEN
+    };
+    let mut total: usize = input.iter().map(estimated_tokens).sum();
+    let mut dropped = 0;
+    let is_user_message =
+        |item: &ResponseItem| matches!(item, ResponseItem::Message { role, .. } if role == "user");
+    while total > budget && input.len() > 1 {
+        // The summarization request is a user message, so the oldest turn
+        // always ends before it.
+        let turn_len = input[1..]
+            .iter()
+            .position(is_user_message)
+            .map_or(input.len() - 1, |next| next + 1);
+        total -= input
+            .drain(..turn_len)
+            .map(|item| estimated_tokens(&item))
+            .sum::<usize>();
+        dropped += turn_len;
+    }
+    dropped
+}
+
 async fn drain_to_completed(
     sess: &Session,
     turn_context: &TurnContext,
+    client: &ModelClient,
     prompt: &Prompt,
 ) -> CodexResult<()> {
-    let mut stream = turn_context
-        .client
-        .clone()
+    let mut stream = client
         .stream_with_task_kind(prompt, TaskKind::Compact)
         .await?;
     loop {
@@ -274,8 +345,78 @@ async fn drain_to_completed(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn history_is_trimmed_to_the_summarizers_window() {
+        let message = |text: String| ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText { text }],
+        };
+        let history = |turns: usize| -> Vec<ResponseItem> {
+            (0..turns)
+                .map(|n| message(format!("turn {n} {}", "x".repeat(4_000))))
+                .chain([message(SUMMARIZATION_PROMPT.to_string())])
+                .collect()
+        };
+
+        let mut input = history(10);
+        assert_eq!(trim_to_context_window(&mut input, None), 0);
+        assert_eq!(input.len(), 11);
+
+        // About 1,000 tokens per turn, 2,000 of the window kept free.
+        let mut input = history(10);
+        assert_eq!(trim_to_context_window(&mut input, Some(6_000)), 7);
+        assert_eq!(input.len(), 4);
+        assert_eq!(
+            input.last(),
+            Some(&message(SUMMARIZATION_PROMPT.to_string()))
+        );
+    }
+
+    #[test]
+    fn trimming_drops_whole_turns() {
+        let message = |role: &str, text: String| ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::InputText { text }],
+        };
+        let mut input: Vec<ResponseItem> = (0..3)
+            .flat_map(|n| {
+                [
+                    message("user", format!("turn {n}")),
+                    ResponseItem::FunctionCall {
+                        id: None,
+                        name: "shell".to_string(),
+                        arguments: "{}".to_string(),
+                        call_id: format!("call-{n}"),
+                    },
+                    ResponseItem::FunctionCallOutput {
+                        call_id: format!("call-{n}"),
+                        output: FunctionCallOutputPayload {
+                            content: "x".repeat(4_000),
+                            success: Some(true),
+                        },
+                    },
+                    message("assistant", format!("done {n}")),
+                ]
+            })
+            .chain([message("user", SUMMARIZATION_PROMPT.to_string())])
+            .collect();
+
+        // About 1,000 tokens per turn, 2,000 of the window kept free: the
+        // oldest turn's four items go together.
+        assert_eq!(trim_to_context_window(&mut input, Some(4_500)), 4);
+        assert_eq!(input.len(), 9);
+        assert_eq!(input[0], message("user", "turn 1".to_string()));
+        assert!(matches!(
+            &input[2],
+            ResponseItem::FunctionCallOutput { call_id, .. } if call_id == "call-1"
+        ));
+    }
+
     #[test]
     fn content_items_to_text_joins_non_empty_segments() {
         let items = vec![
@@ -9,6 +9,8 @@ use crate::config_types::McpServerConfig;
 use crate::config_types::McpServerTransportConfig;
 use crate::config_types::Notice;
 use crate::config_types::Notifications;
+use crate::config_types::OssConfig;
+use crate::config_types::OssConnection;
 use crate::config_types::OtelConfig;
 use crate::config_types::OtelConfigToml;
 use crate::config_types::OtelExporterKind;
@@ -26,8 +28,18 @@ use crate::git_info::resolve_root_git_project_for_trust;
 use crate::model_family::ModelFamily;
 use crate::model_family::derive_default_model_family;
 use crate::model_family::find_family_for_model;
+use crate::model_provider_info::BUILT_IN_OSS_MODEL_PROVIDER_ID;
+use crate::model_provider_info::DEFAULT_OSS_MODEL;
 use crate::model_provider_info::ModelProviderInfo;
+use crate::model_provider_info::base_url_ip;
 use crate::model_provider_info::built_in_model_providers;
+use crate::model_provider_info::create_oss_provider_for_backend;
+use crate::model_provider_info::create_oss_provider_with_base_url;
+use crate::model_provider_info::is_private_address;
+use crate::model_provider_info::loopback_base_url_port;
+use crate::model_provider_info::named_pipe_base_url_path;
+use crate::model_provider_info::parse_oss_base_url;
+use crate::model_provider_info::unix_socket_base_url_path;
 use crate::openai_model_info::get_model_info;
 use crate::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
 use crate::project_doc::LOCAL_PROJECT_DOC_FILENAME;
@@ -37,6 +49,8 @@ use anyhow::Context;
 use codex_app_server_protocol::Tools;
 use codex_app_server_protocol::UserSavedConfig;
 use codex_protocol::config_types::ForcedLoginMethod;
+use codex_protocol::config_types::OssBackend;
+use codex_protocol::config_types::OssSampling;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::config_types::SandboxMode;
@@ -51,6 +65,7 @@ use std::collections::HashMap;
 use std::io::ErrorKind;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use tempfile::NamedTempFile;
 use toml::Value as TomlValue;
@@ -151,6 +166,10 @@ pub struct Config {
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
 
+    /// Background color of the TUI badge marking sessions that use a local
+    /// model.
+    pub tui_local_badge_color: Option<String>,
+
     /// The directory that should be treated as the current working directory
     /// for the session. All relative paths inside the business-logic layer are
     /// resolved against this path.
@@ -258,6 +277,9 @@ pub struct Config {
 
     /// OTEL configuration (exporter type, endpoint, headers, etc.).
     pub otel: crate::config_types::OtelConfig,
+
+    /// Settings for the built-in local open source (`oss`) provider.
+    pub oss: OssConfig,
 }
 
 impl Config {
@@ -266,11 +288,22 @@ impl Config {
         overrides: ConfigOverrides,
     ) -> std::io::Result<Self> {
         let codex_home = find_codex_home()?;
+        // A workspace only picks the local model, so its config is read for
+        // `--oss` sessions alone.
+        let project_dir = match overrides.model_provider.as_deref() {
+            Some(BUILT_IN_OSS_MODEL_PROVIDER_ID) => {
+                Some(std::env::current_dir()?.join(overrides.cwd.clone().unwrap_or_default()))
+            }
+            _ => None,
+        };
 
         let root_value = load_resolved_config(
             &codex_home,
             cli_overrides,
-            crate::config_loader::LoaderOverrides::default(),
+            crate::config_loader::LoaderOverrides {
+                project_dir,
+                ..Default::default()
+            },
         )
         .await?;
 
@@ -317,10 +350,15 @@ fn apply_overlays(
 ) -> TomlValue {
     let LoadedConfigLayers {
         mut base,
+        project_config,
         managed_config,
         managed_preferences,
     } = layers;
 
+    if let Some(project_config) = project_config {
+        merge_toml_values(&mut base, &project_config);
+    }
+
     for (path, value) in cli_overrides.into_iter() {
         apply_toml_override(&mut base, &path, value);
     }
@@ -638,6 +676,123 @@ pub fn set_hide_full_access_warning(codex_home: &Path, acknowledged: bool) -> an
     Ok(())
 }
 
+/// Persist that large local model downloads start without confirmation.
+pub fn set_hide_model_download_confirmation(
+    codex_home: &Path,
+    acknowledged: bool,
+) -> anyhow::Result<()> {
+    let config_path = codex_home.join(CONFIG_TOML_FILE);
+    let mut doc = match std::fs::read_to_string(config_path.clone()) {
+        Ok(s) => s.parse::<DocumentMut>()?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => DocumentMut::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let notices_table = load_or_create_top_level_table(&mut doc, Notice::TABLE_KEY)?;
+
+    notices_table["hide_model_download_confirmation"] = toml_edit::value(acknowledged);
+
+    std::fs::create_dir_all(codex_home)?;
+    let tmp_file = NamedTempFile::new_in(codex_home)?;
+    std::fs::write(tmp_file.path(), doc.to_string())?;
+    tmp_file.persist(config_path)?;
+
+    Ok(())
+}
+
+/// Persist the sampling parameters of the `oss` provider into the `[oss]`
+/// table, removing the ones that are unset so the server defaults apply.
+pub fn set_oss_sampling(codex_home: &Path, sampling: &OssSampling) -> anyhow::Result<()> {
+    let config_path = codex_home.join(CONFIG_TOML_FILE);
+    let mut doc = match std::fs::read_to_string(config_path.clone()) {
+        Ok(s) => s.parse::<DocumentMut>()?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => DocumentMut::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let oss_table = load_or_create_top_level_table(&mut doc, "oss")?;
+    let values = [
+        ("temperature", sampling.temperature.map(toml_edit::value)),
+        ("top_p", sampling.top_p.map(toml_edit::value)),
+        (
+            "max_tokens",
+            sampling
+                .max_tokens
+                .map(|max| toml_edit::value(i64::try_from(max).unwrap_or(i64::MAX))),
+        ),
+    ];
+    for (key, value) in values {
+        match value {
+            Some(value) => oss_table[key] = value,
+            None => {
+                oss_table.remove(key);
+            }
+        }
+    }
+
+    std::fs::create_dir_all(codex_home)?;
+    let tmp_file = NamedTempFile::new_in(codex_home)?;
+    std::fs::write(tmp_file.path(), doc.to_string())?;
+    tmp_file.persist(config_path)?;
+
+    Ok(())
+}
+
+/// Fail on an `oss.http_headers` entry or `oss.user_agent` that cannot be
+/// sent as an HTTP header, rather than silently dropping it from requests.
+fn validate_oss_headers(oss: &crate::config_types::OssConfigToml) -> std::io::Result<()> {
+    let invalid = |message: String| std::io::Error::new(std::io::ErrorKind::InvalidData, message);
+    for (name, value) in oss.http_headers.iter().flatten() {
+        reqwest::header::HeaderName::from_bytes(name.as_bytes()).map_err(|err| {
+            invalid(format!(
+                "oss.http_headers: `{name}` is not a header name: {err}"
+            ))
+        })?;
+        reqwest::header::HeaderValue::from_str(value).map_err(|err| {
+            invalid(format!(
+                "oss.http_headers: the value of `{name}` is invalid: {err}"
+            ))
+        })?;
+    }
+    if let Some(user_agent) = &oss.user_agent {
+        reqwest::header::HeaderValue::from_str(user_agent)
+            .map_err(|err| invalid(format!("oss.user_agent is invalid: {err}")))?;
+    }
+    Ok(())
+}
+
+/// Add `license` to `oss.accepted_licenses` in `$CODEX_HOME/config.toml`,
+/// keeping the rest of the file as it is.
+pub fn add_oss_accepted_license(codex_home: &Path, license: &str) -> anyhow::Result<()> {
+    let config_path = codex_home.join(CONFIG_TOML_FILE);
+    let mut doc = match std::fs::read_to_string(config_path.clone()) {
+        Ok(s) => s.parse::<DocumentMut>()?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => DocumentMut::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let oss_table = load_or_create_top_level_table(&mut doc, "oss")?;
+    let accepted = oss_table
+        .entry("accepted_licenses")
+        .or_insert_with(|| toml_edit::value(toml_edit::Array::new()))
+        .as_array_mut()
+        .ok_or_else(|| anyhow::anyhow!("`oss.accepted_licenses` must be an array"))?;
+    let listed = accepted
+        .iter()
+        .filter_map(toml_edit::Value::as_str)
+        .any(|accepted| accepted.eq_ignore_ascii_case(license));
+    if !listed {
+        accepted.push(license);
+    }
+
+    std::fs::create_dir_all(codex_home)?;
+    let tmp_file = NamedTempFile::new_in(codex_home)?;
+    std::fs::write(tmp_file.path(), doc.to_string())?;
+    tmp_file.persist(config_path)?;
+
+    Ok(())
+}
+
 fn load_or_create_top_level_table<'a>(
     doc: &'a mut DocumentMut,
     key: &str,
@@ -945,6 +1100,9 @@ pub struct ConfigToml {
     /// OTEL configuration.
     pub otel: Option<crate::config_types::OtelConfigToml>,
 
+    /// Settings for the built-in local open source (`oss`) provider.
+    pub oss: Option<crate::config_types::OssConfigToml>,
+
     /// Tracks whether the Windows onboarding screen has been acknowledged.
     pub windows_wsl_setup_acknowledged: Option<bool>,
 
@@ -1044,11 +1202,14 @@ impl ConfigToml {
                 Some(SandboxWorkspaceWrite {
                     writable_roots,
                     network_access,
+                    local_inference_access: _,
                     exclude_tmpdir_env_var,
                     exclude_slash_tmp,
                 }) => SandboxPolicy::WorkspaceWrite {
                     writable_roots: writable_roots.clone(),
                     network_access: *network_access,
+                    local_network_ports: vec![],
+                    local_network_sockets: vec![],
                     exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                     exclude_slash_tmp: *exclude_slash_tmp,
                 },
@@ -1120,6 +1281,10 @@ pub struct ConfigOverrides {
     pub tools_web_search_request: Option<bool>,
     /// Additional directories that should be treated as writable roots for this session.
     pub additional_writable_roots: Vec<PathBuf>,
+    /// Local server expected behind the built-in `oss` provider.
+    pub oss_provider: Option<OssBackend>,
+    /// Base URL of the server behind the built-in `oss` provider.
+    pub oss_base_url: Option<String>,
 }
 
 impl Config {
@@ -1148,6 +1313,8 @@ impl Config {
             show_raw_agent_reasoning,
             tools_web_search_request: override_tools_web_search_request,
             additional_writable_roots,
+            oss_provider,
+            oss_base_url,
         } = overrides;
 
         let active_profile_name = config_profile_key
@@ -1239,11 +1406,214 @@ impl Config {
             || sandbox_mode.is_some()
             || cfg.sandbox_mode.is_some();
 
+        let download_rate_limit = cfg
+            .oss
+            .as_ref()
+            .and_then(|oss| oss.download_rate_limit.as_deref())
+            .map(|limit| {
+                parse_rate_limit(limit).ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!(
+                            "oss.download_rate_limit `{limit}` is not a rate such as \"5MB/s\""
+                        ),
+                    )
+                })
+            })
+            .transpose()?;
+        let oss_base_url = match oss_base_url {
+            Some(base_url) => Some(base_url),
+            None => cfg
+                .oss
+                .as_ref()
+                .and_then(|oss| oss.base_url.as_deref())
+                .map(|base_url| {
+                    parse_oss_base_url(base_url).map_err(|err| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            format!("oss.base_url `{base_url}` is invalid: {err}"),
+                        )
+                    })
+                })
+                .transpose()?,
+        };
+        let oss_unix_socket = oss_base_url.as_deref().and_then(unix_socket_base_url_path);
+        let strict_local = cfg
+            .oss
+            .as_ref()
+            .and_then(|oss| oss.strict_local)
+            .unwrap_or(false);
+        let oss_named_pipe = oss_base_url.as_deref().and_then(named_pipe_base_url_path);
+        let allow_remote = cfg
+            .oss
+            .as_ref()
+            .and_then(|oss| oss.allow_remote)
+            .unwrap_or(false);
+        if let Some(oss) = &cfg.oss {
+            validate_oss_headers(oss)?;
+        }
+        let cloud_review_provider = cfg
+            .oss
+            .as_ref()
+            .and_then(|oss| oss.cloud_review_provider.clone())
+            .unwrap_or_else(|| "openai".to_string());
+        let oss = OssConfig {
+            provider: oss_provider.or(cfg.oss.as_ref().and_then(|oss| oss.provider)),
+            model: cfg.oss.as_ref().and_then(|oss| oss.model.clone()),
+            summary_model: cfg.oss.as_ref().and_then(|oss| oss.summary_model.clone()),
+            summary_context_window: cfg.oss.as_ref().and_then(|oss| oss.summary_context_window),
+            embedding_model: cfg.oss.as_ref().and_then(|oss| oss.embedding_model.clone()),
+            cloud_review_model: cfg
+                .oss
+                .as_ref()
+                .and_then(|oss| oss.cloud_review_model.clone()),
+            cloud_review_provider,
+            base_url: oss_base_url,
+            allow_remote,
+            trusted_lms_sha256: cfg
+                .oss
+                .as_ref()
+                .and_then(|oss| oss.trusted_lms_sha256.clone())
+                .unwrap_or_default(),
+            accepted_licenses: cfg
+                .oss
+                .as_ref()
+                .and_then(|oss| oss.accepted_licenses.clone())
+                .unwrap_or_default(),
+            offline: strict_local
+                || cfg
+                    .oss
+                    .as_ref()
+                    .and_then(|oss| oss.offline)
+                    .unwrap_or(false),
+            strict_local,
+            verbosity: cfg
+                .oss
+                .as_ref()
+                .and_then(|oss| oss.verbosity)
+                .unwrap_or_default(),
+            progress_style: cfg
+                .oss
+                .as_ref()
+                .and_then(|oss| oss.progress_style)
+                .unwrap_or_default(),
+            sampling: cfg
+                .oss
+                .as_ref()
+                .map(|oss| OssSampling {
+                    temperature: oss.temperature,
+                    top_p: oss.top_p,
+                    max_tokens: oss.max_tokens,
+                })
+                .unwrap_or_default(),
+            health_check_interval: cfg
+                .oss
+                .as_ref()
+                .and_then(|oss| oss.health_check_interval_secs)
+                .map(Duration::from_secs),
+            connection: OssConnection {
+                unix_socket: oss_unix_socket,
+                named_pipe: oss_named_pipe,
+                loopback_only: strict_local,
+                private_only: !allow_remote,
+                ..cfg
+                    .oss
+                    .as_ref()
+                    .map(|oss| OssConnection {
+                        http2_prior_knowledge: oss.http2_prior_knowledge.unwrap_or(false),
+                        pool_max_idle_per_host: oss.pool_max_idle_per_host,
+                        pool_idle_timeout: oss.pool_idle_timeout_secs.map(Duration::from_secs),
+                        tcp_keepalive: oss.tcp_keepalive_secs.map(Duration::from_secs),
+                        connect_timeout: oss.connect_timeout_secs.map(Duration::from_secs),
+                        read_timeout: oss.read_timeout_secs.map(Duration::from_secs),
+                        request_timeout: oss.request_timeout_secs.map(Duration::from_secs),
+                        unix_socket: None,
+                        named_pipe: None,
+                        http_headers: oss
+                            .http_headers
+                            .clone()
+                            .unwrap_or_default()
+                            .into_iter()
+                            .collect(),
+                        user_agent: oss.user_agent.clone(),
+                        loopback_only: false,
+                        private_only: false,
+                    })
+                    .unwrap_or_default()
+            },
+            max_concurrent_requests: cfg.oss.as_ref().and_then(|oss| oss.max_concurrent_requests),
+            download_rate_limit,
+            performance_telemetry: cfg
+                .oss
+                .as_ref()
+                .and_then(|oss| oss.performance_telemetry)
+                .unwrap_or_default(),
+            power_watts: cfg.oss.as_ref().and_then(|oss| oss.power_watts),
+            cloud_input_price: cfg.oss.as_ref().and_then(|oss| oss.cloud_input_price),
+            cloud_output_price: cfg.oss.as_ref().and_then(|oss| oss.cloud_output_price),
+            unload_on_exit: cfg
+                .oss
+                .as_ref()
+                .and_then(|oss| oss.unload_on_exit)
+                .unwrap_or(false),
+            debug_dump_sse: cfg.oss.as_ref().and_then(|oss| oss.debug_dump_sse.clone()),
+            stall_timeout: cfg
+                .oss
+                .as_ref()
+                .and_then(|oss| oss.stall_timeout_secs)
+                .map(Duration::from_secs),
+        };
+
+        // `--oss` without `-m` uses `oss.model`, then the default local model,
+        // rather than a model configured for another provider.
+        let model = model.or_else(|| {
+            (model_provider.as_deref() == Some(BUILT_IN_OSS_MODEL_PROVIDER_ID)).then(|| {
+                oss.model
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_OSS_MODEL.to_string())
+            })
+        });
+
         let mut model_providers = built_in_model_providers();
+        if let Some(base_url) = &oss.base_url {
+            model_providers.insert(
+                BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string(),
+                create_oss_provider_with_base_url(base_url),
+            );
+        } else if oss.provider.is_some() {
+            model_providers.insert(
+                BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string(),
+                create_oss_provider_for_backend(oss.provider),
+            );
+        }
         // Merge user-defined providers into the built-in list.
         for (key, provider) in cfg.model_providers.into_iter() {
             model_providers.entry(key).or_insert(provider);
         }
+        if oss.cloud_review_model.is_some()
+            && (oss.cloud_review_provider == BUILT_IN_OSS_MODEL_PROVIDER_ID
+                || !model_providers.contains_key(&oss.cloud_review_provider))
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "oss.cloud_review_provider: `{}` is not a cloud model provider",
+                    oss.cloud_review_provider
+                ),
+            ));
+        }
+        for (key, value) in [
+            ("oss.power_watts", oss.power_watts),
+            ("oss.cloud_input_price", oss.cloud_input_price),
+            ("oss.cloud_output_price", oss.cloud_output_price),
+        ] {
+            if value.is_some_and(|value| !value.is_finite() || value < 0.0) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("{key} must be a number of at least 0"),
+                ));
+            }
+        }
 
         let model_provider_id = model_provider
             .or(config_profile.model_provider)
@@ -1258,6 +1628,52 @@ impl Config {
                 )
             })?
             .clone();
+        // Literal addresses are never resolved, so the connection's address
+        // scope (see `OssConnection::private_only`) cannot catch them.
+        if model_provider_id == BUILT_IN_OSS_MODEL_PROVIDER_ID
+            && let Some(base_url) = model_provider.base_url.as_deref()
+            && let Some(ip) = base_url_ip(base_url)
+            && !(ip.is_loopback()
+                || (!oss.strict_local && (oss.allow_remote || is_private_address(ip))))
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                if oss.strict_local {
+                    format!(
+                        "oss.strict_local only allows a server on this machine, and {base_url} is not on it."
+                    )
+                } else {
+                    format!(
+                        "The oss base_url {base_url} is the public address {ip}; prompts are only sent to servers on this machine or a private network. Set `oss.allow_remote = true` to use it anyway."
+                    )
+                },
+            ));
+        }
+
+        // `--oss` sessions can reach their own server, so users need no
+        // hand-written sandbox exceptions for it. Only the macOS sandbox can
+        // confine the exception to loopback; elsewhere it stays opt-in.
+        let local_inference_access = cfg
+            .sandbox_workspace_write
+            .as_ref()
+            .and_then(|settings| settings.local_inference_access)
+            .unwrap_or(
+                cfg!(target_os = "macos") && model_provider_id == BUILT_IN_OSS_MODEL_PROVIDER_ID,
+            );
+        if local_inference_access
+            && let SandboxPolicy::WorkspaceWrite {
+                network_access: false,
+                local_network_ports,
+                local_network_sockets,
+                ..
+            } = &mut sandbox_policy
+            && let Some(base_url) = model_providers
+                .get(BUILT_IN_OSS_MODEL_PROVIDER_ID)
+                .and_then(|provider| provider.base_url.as_deref())
+        {
+            local_network_ports.extend(loopback_base_url_port(base_url));
+            local_network_sockets.extend(unix_socket_base_url_path(base_url));
+        }
 
         let shell_environment_policy = cfg.shell_environment_policy.into();
 
@@ -1409,6 +1825,7 @@ impl Config {
                 .as_ref()
                 .map(|t| t.notifications.clone())
                 .unwrap_or_default(),
+            tui_local_badge_color: cfg.tui.as_ref().and_then(|t| t.local_badge_color.clone()),
             otel: {
                 let t: OtelConfigToml = cfg.otel.unwrap_or_default();
                 let log_user_prompt = t.log_user_prompt.unwrap_or(false);
@@ -1422,6 +1839,7 @@ impl Config {
                     exporter,
                 }
             },
+            oss,
         };
         Ok(config)
     }
@@ -1488,6 +1906,34 @@ fn default_model() -> String {
     OPENAI_DEFAULT_MODEL.to_string()
 }
 
+/// Bytes per second of a rate such as `5MB/s`, `500KiB/s`, or `1000000`;
+/// the `/s` is optional and units are case-insensitive.
+fn parse_rate_limit(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let value = value
+        .strip_suffix("/s")
+        .or_else(|| value.strip_suffix("/S"))
+        .unwrap_or(value)
+        .trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1.0,
+        "k" | "kb" => 1e3,
+        "kib" => 1024.0,
+        "m" | "mb" => 1e6,
+        "mib" => 1024.0 * 1024.0,
+        "g" | "gb" => 1e9,
+        "gib" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    let bytes = (number * multiplier).round();
+    (bytes >= 1.0).then_some(bytes as u64)
+}
+
 fn default_review_model() -> String {
     OPENAI_DEFAULT_REVIEW_MODEL.to_string()
 }
@@ -1531,6 +1977,8 @@ pub fn log_dir(cfg: &Config) -> std::io::Result<PathBuf> {
 mod tests {
     use crate::config_types::HistoryPersistence;
     use crate::config_types::Notifications;
+    use crate::config_types::OssPerformanceTelemetry;
+    use crate::config_types::OssVerbosity;
     use crate::features::Feature;
 
     use super::*;
@@ -1635,6 +2083,8 @@ exclude_slash_tmp = true
             SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![PathBuf::from("/my/workspace")],
                 network_access: false,
+                local_network_ports: vec![],
+                local_network_sockets: vec![],
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
             },
@@ -1663,6 +2113,8 @@ trust_level = "trusted"
             SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![PathBuf::from("/my/workspace")],
                 network_access: false,
+                local_network_ports: vec![],
+                local_network_sockets: vec![],
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
             },
@@ -1750,6 +2202,573 @@ approve_all = true
         Ok(())
     }
 
+    #[test]
+    fn oss_provider_override_wins_over_config_table() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[oss]
+provider = "lmstudio"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        assert_eq!(
+            cfg.oss.as_ref().and_then(|oss| oss.provider),
+            Some(OssBackend::LmStudio)
+        );
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                oss_provider: Some(OssBackend::Ollama),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(config.oss.provider, Some(OssBackend::Ollama));
+
+        Ok(())
+    }
+
+    #[test]
+    fn oss_base_url_override_replaces_built_in_provider_url() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides {
+                model_provider: Some(BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string()),
+                oss_base_url: Some("http://gpubox:1234/v1".to_string()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.model_provider.base_url.as_deref(),
+            Some("http://gpubox:1234/v1")
+        );
+        assert_eq!(
+            config.oss.base_url.as_deref(),
+            Some("http://gpubox:1234/v1")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn local_inference_access_opens_only_the_loopback_oss_port() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let load = |base_url: &str| {
+            let cfg = toml::from_str::<ConfigToml>(
+                r#"
+sandbox_mode = "workspace-write"
+
+[sandbox_workspace_write]
+local_inference_access = true
+"#,
+            )
+            .expect("TOML deserialization should succeed");
+            Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides {
+                    oss_base_url: Some(base_url.to_string()),
+                    ..Default::default()
+                },
+                codex_home.path().to_path_buf(),
+            )
+        };
+
+        let local = load("http://localhost:1234/v1")?;
+        assert_eq!(local.sandbox_policy.local_network_ports(), &[1234]);
+        assert!(!local.sandbox_policy.has_full_network_access());
+
+        let remote = load("http://gpubox:1234/v1")?;
+        assert_eq!(remote.sandbox_policy.local_network_ports(), &[] as &[u16]);
+
+        // Without a port, the scheme's default would open e.g. 80 or 443.
+        let implicit_port = load("http://localhost/v1")?;
+        assert_eq!(
+            implicit_port.sandbox_policy.local_network_ports(),
+            &[] as &[u16]
+        );
+
+        let socket = load("unix:///run/llama.sock")?;
+        assert_eq!(
+            socket.sandbox_policy.local_network_sockets(),
+            &[PathBuf::from("/run/llama.sock")]
+        );
+        assert_eq!(
+            socket.oss.connection.unix_socket,
+            Some(PathBuf::from("/run/llama.sock"))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn oss_sessions_reach_their_server_by_default() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let load = |model_provider: &str, sandbox: &str| {
+            let cfg =
+                toml::from_str::<ConfigToml>(sandbox).expect("TOML deserialization should succeed");
+            Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides {
+                    model_provider: Some(model_provider.to_string()),
+                    oss_provider: Some(OssBackend::LmStudio),
+                    ..Default::default()
+                },
+                codex_home.path().to_path_buf(),
+            )
+        };
+        let workspace_write = r#"sandbox_mode = "workspace-write""#;
+
+        let oss = load(BUILT_IN_OSS_MODEL_PROVIDER_ID, workspace_write)?;
+        let expected: &[u16] = if cfg!(target_os = "macos") {
+            &[1234]
+        } else {
+            &[]
+        };
+        assert_eq!(oss.sandbox_policy.local_network_ports(), expected);
+
+        let openai = load("openai", workspace_write)?;
+        assert_eq!(openai.sandbox_policy.local_network_ports(), &[] as &[u16]);
+
+        let opted_out = load(
+            BUILT_IN_OSS_MODEL_PROVIDER_ID,
+            r#"
+sandbox_mode = "workspace-write"
+
+[sandbox_workspace_write]
+local_inference_access = false
+"#,
+        )?;
+        assert_eq!(
+            opted_out.sandbox_policy.local_network_ports(),
+            &[] as &[u16]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn oss_provider_without_model_uses_oss_model() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let oss_overrides = || ConfigOverrides {
+            model_provider: Some(BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string()),
+            ..Default::default()
+        };
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+model = "gpt-5-codex"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            oss_overrides(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(config.model, DEFAULT_OSS_MODEL);
+
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+model = "gpt-5-codex"
+
+[oss]
+model = "qwen3-coder:30b"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            oss_overrides(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(config.model, "qwen3-coder:30b");
+
+        Ok(())
+    }
+
+    #[test]
+    fn oss_offline_is_read_from_config_table() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert!(!config.oss.offline);
+
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[oss]
+offline = true
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert!(config.oss.offline);
+
+        Ok(())
+    }
+
+    #[test]
+    fn oss_verbosity_is_read_from_config_table() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[oss]
+verbosity = "quiet"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(config.oss.verbosity, OssVerbosity::Quiet);
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_oss_sampling_round_trips_and_clears_unset_values() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        std::fs::write(
+            codex_home.path().join(CONFIG_TOML_FILE),
+            "[oss]\nmodel = \"qwen3:8b\"\ntop_p = 0.9\n",
+        )?;
+        let sampling = OssSampling {
+            temperature: Some(0.2),
+            top_p: None,
+            max_tokens: Some(4096),
+        };
+        set_oss_sampling(codex_home.path(), &sampling)?;
+
+        let contents = std::fs::read_to_string(codex_home.path().join(CONFIG_TOML_FILE))?;
+        let cfg: ConfigToml = toml::from_str(&contents)?;
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(config.oss.sampling, sampling);
+        assert_eq!(config.oss.model.as_deref(), Some("qwen3:8b"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_oss_accepted_license_appends_once() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        std::fs::write(
+            codex_home.path().join(CONFIG_TOML_FILE),
+            "[oss]\nmodel = \"qwen3:8b\"\naccepted_licenses = [\"apache-2.0\"]\n",
+        )?;
+        add_oss_accepted_license(codex_home.path(), "llama3.1")?;
+        add_oss_accepted_license(codex_home.path(), "Apache-2.0")?;
+
+        let contents = std::fs::read_to_string(codex_home.path().join(CONFIG_TOML_FILE))?;
+        let cfg: ConfigToml = toml::from_str(&contents)?;
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(config.oss.accepted_licenses, vec!["apache-2.0", "llama3.1"]);
+        assert_eq!(config.oss.model.as_deref(), Some("qwen3:8b"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn oss_sampling_is_read_from_config_table() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[oss]
+temperature = 0.2
+max_tokens = 4096
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.oss.sampling,
+            OssSampling {
+                temperature: Some(0.2),
+                top_p: None,
+                max_tokens: Some(4096),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn oss_health_check_interval_is_read_from_config_table() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>("[oss]\nhealth_check_interval_secs = 30\n")
+            .expect("TOML deserialization should succeed");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.oss.health_check_interval,
+            Some(Duration::from_secs(30))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn download_rate_limit_accepts_common_units() {
+        let parsed: Vec<Option<u64>> = ["5MB/s", "500 KiB/s", "1.5m", "250000", "fast", "0MB/s"]
+            .iter()
+            .map(|value| parse_rate_limit(value))
+            .collect();
+        assert_eq!(
+            parsed,
+            [
+                Some(5_000_000),
+                Some(512_000),
+                Some(1_500_000),
+                Some(250_000),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_download_rate_limit_is_rejected() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cfg = toml::from_str::<ConfigToml>("[oss]\ndownload_rate_limit = \"fast\"\n")
+            .expect("TOML deserialization should succeed");
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("an invalid rate should be rejected");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn oss_connection_knobs_are_read_from_config_table() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[oss]
+http2_prior_knowledge = true
+pool_max_idle_per_host = 4
+tcp_keepalive_secs = 30
+connect_timeout_secs = 2
+read_timeout_secs = 120
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.oss.connection,
+            OssConnection {
+                http2_prior_knowledge: true,
+                pool_max_idle_per_host: Some(4),
+                pool_idle_timeout: None,
+                tcp_keepalive: Some(Duration::from_secs(30)),
+                connect_timeout: Some(Duration::from_secs(2)),
+                read_timeout: Some(Duration::from_secs(120)),
+                request_timeout: None,
+                unix_socket: None,
+                named_pipe: None,
+                http_headers: BTreeMap::new(),
+                user_agent: None,
+                loopback_only: false,
+                private_only: true,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn public_oss_addresses_need_allow_remote() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let load = |base_url: &str, oss: &str| {
+            let cfg =
+                toml::from_str::<ConfigToml>(oss).expect("TOML deserialization should succeed");
+            Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides {
+                    model_provider: Some(BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string()),
+                    oss_base_url: Some(base_url.to_string()),
+                    ..Default::default()
+                },
+                codex_home.path().to_path_buf(),
+            )
+        };
+
+        let err = load("http://203.0.113.7:11434", "").expect_err("public address");
+        assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+        assert!(err.to_string().contains("oss.allow_remote"), "{err}");
+
+        let private = load("http://192.168.1.20:11434", "")?;
+        assert!(private.oss.connection.private_only);
+        let allowed = load("http://203.0.113.7:11434", "[oss]\nallow_remote = true\n")?;
+        assert!(!allowed.oss.connection.private_only);
+        let strict = load("http://192.168.1.20:11434", "[oss]\nstrict_local = true\n")
+            .expect_err("not this machine");
+        assert_eq!(strict.kind(), std::io::ErrorKind::PermissionDenied);
+        Ok(())
+    }
+
+    #[test]
+    fn strict_local_implies_offline_and_loopback_connections() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[oss]
+strict_local = true
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert!(config.oss.strict_local);
+        assert!(config.oss.offline);
+        assert!(config.oss.connection.loopback_only);
+
+        Ok(())
+    }
+
+    #[test]
+    fn oss_headers_are_read_and_checked() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let load = |toml: &str| {
+            let cfg =
+                toml::from_str::<ConfigToml>(toml).expect("TOML deserialization should succeed");
+            Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+            )
+        };
+
+        let config = load(
+            r#"
+[oss]
+user_agent = "acme-codex/1.0"
+http_headers = { "X-Gateway-Route" = "gpu-pool" }
+"#,
+        )?;
+        assert_eq!(
+            config.oss.connection.http_headers,
+            BTreeMap::from([("X-Gateway-Route".to_string(), "gpu-pool".to_string())])
+        );
+        assert_eq!(
+            config.oss.connection.user_agent.as_deref(),
+            Some("acme-codex/1.0")
+        );
+
+        let err = load(
+            r#"
+[oss]
+http_headers = { "Bad Header" = "x" }
+"#,
+        )
+        .expect_err("header names cannot contain spaces");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        Ok(())
+    }
+
+    #[test]
+    fn performance_telemetry_is_off_unless_opted_in() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let load = |toml: &str| {
+            let cfg =
+                toml::from_str::<ConfigToml>(toml).expect("TOML deserialization should succeed");
+            Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+            )
+        };
+        assert_eq!(
+            load("")?.oss.performance_telemetry,
+            OssPerformanceTelemetry::Off
+        );
+        assert_eq!(
+            load("[oss]\nperformance_telemetry = \"local\"")?
+                .oss
+                .performance_telemetry,
+            OssPerformanceTelemetry::Local
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn energy_estimates_reject_negative_numbers() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let load = |toml: &str| {
+            let cfg =
+                toml::from_str::<ConfigToml>(toml).expect("TOML deserialization should succeed");
+            Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+            )
+        };
+        let config = load("[oss]\npower_watts = 180.0\ncloud_output_price = 10")?;
+        assert_eq!(config.oss.power_watts, Some(180.0));
+        assert_eq!(config.oss.cloud_output_price, Some(10.0));
+        assert_eq!(config.oss.cloud_input_price, None);
+
+        let err = load("[oss]\npower_watts = -5.0").expect_err("negative watts are refused");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        Ok(())
+    }
+
+    #[test]
+    fn models_stay_loaded_on_exit_unless_configured() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let load = |toml: &str| {
+            let cfg =
+                toml::from_str::<ConfigToml>(toml).expect("TOML deserialization should succeed");
+            Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+            )
+        };
+        assert!(!load("")?.oss.unload_on_exit);
+        assert!(load("[oss]\nunload_on_exit = true")?.oss.unload_on_exit);
+
+        Ok(())
+    }
+
     #[test]
     fn profile_legacy_toggles_override_base() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -1864,6 +2883,7 @@ approve_all = true
 
         let overrides = crate::config_loader::LoaderOverrides {
             managed_config_path: Some(managed_path.clone()),
+            project_dir: None,
             #[cfg(target_os = "macos")]
             managed_preferences_base64: None,
         };
@@ -1970,6 +2990,7 @@ approve_all = true
 
         let overrides = crate::config_loader::LoaderOverrides {
             managed_config_path: Some(managed_path),
+            project_dir: None,
             #[cfg(target_os = "macos")]
             managed_preferences_base64: None,
         };
@@ -1990,6 +3011,51 @@ approve_all = true
         Ok(())
     }
 
+    #[tokio::test]
+    async fn project_config_picks_the_local_model_under_cli_overrides() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        let workspace = TempDir::new()?;
+        std::fs::write(
+            codex_home.path().join(CONFIG_TOML_FILE),
+            "model = \"base\"\n[oss]\nmodel = \"gpt-oss:20b\"\nsummary_model = \"llama3.2:3b\"\n",
+        )?;
+        std::fs::create_dir(workspace.path().join(".codex"))?;
+        std::fs::write(
+            workspace.path().join(".codex").join(CONFIG_TOML_FILE),
+            "model = \"project\"\n[oss]\nmodel = \"qwen3-coder:30b\"\n",
+        )?;
+        let load = |cli_overrides| {
+            load_resolved_config(
+                codex_home.path(),
+                cli_overrides,
+                crate::config_loader::LoaderOverrides {
+                    managed_config_path: Some(codex_home.path().join("managed_config.toml")),
+                    project_dir: Some(workspace.path().to_path_buf()),
+                    #[cfg(target_os = "macos")]
+                    managed_preferences_base64: None,
+                },
+            )
+        };
+
+        let cfg: ConfigToml = load(Vec::new()).await?.try_into()?;
+        let oss = cfg.oss.unwrap_or_default();
+        assert_eq!(cfg.model.as_deref(), Some("base"));
+        assert_eq!(oss.model.as_deref(), Some("qwen3-coder:30b"));
+        assert_eq!(oss.summary_model.as_deref(), Some("llama3.2:3b"));
+
+        let cfg: ConfigToml = load(vec![(
+            "oss.model".to_string(),
+            TomlValue::String("mistral".to_string()),
+        )])
+        .await?
+        .try_into()?;
+        assert_eq!(
+            cfg.oss.unwrap_or_default().model.as_deref(),
+            Some("mistral")
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn load_global_mcp_servers_accepts_legacy_ms_field() -> anyhow::Result<()> {
         let codex_home = TempDir::new()?;
@@ -2907,7 +3973,9 @@ model_verbosity = "high"
                 notices: Default::default(),
                 disable_paste_burst: false,
                 tui_notifications: Default::default(),
+                tui_local_badge_color: None,
                 otel: OtelConfig::default(),
+                oss: OssConfig::default(),
             },
             o3_profile_config
         );
@@ -2975,7 +4043,9 @@ model_verbosity = "high"
             notices: Default::default(),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_local_badge_color: None,
             otel: OtelConfig::default(),
+            oss: OssConfig::default(),
         };
 
         assert_eq!(expected_gpt3_profile_config, gpt3_profile_config);
@@ -3058,7 +4128,9 @@ model_verbosity = "high"
             notices: Default::default(),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_local_badge_color: None,
             otel: OtelConfig::default(),
+            oss: OssConfig::default(),
         };
 
         assert_eq!(expected_zdr_profile_config, zdr_profile_config);
@@ -3127,7 +4199,9 @@ model_verbosity = "high"
             notices: Default::default(),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_local_badge_color: None,
             otel: OtelConfig::default(),
+            oss: OssConfig::default(),
         };
 
         assert_eq!(expected_gpt5_profile_config, gpt5_profile_config);
@@ -3,7 +3,10 @@
 // Note this file should generally be restricted to simple struct/enum
 // definitions that do not contain business logic.
 
+use schemars::JsonSchema;
+use schemars::schema::RootSchema;
 use serde::Deserializer;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Duration;
@@ -13,6 +16,9 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde::de::Error as SerdeError;
 
+use codex_protocol::config_types::OssBackend;
+use codex_protocol::config_types::OssSampling;
+
 pub const DEFAULT_OTEL_ENVIRONMENT: &str = "dev";
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
@@ -329,6 +335,284 @@ impl Default for OtelConfig {
     }
 }
 
+// ===== Local OSS provider configuration =====
+
+/// Settings for the built-in `oss` provider, loaded from the `[oss]` table.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+pub struct OssConfigToml {
+    /// Which local server to expect. When unset, the backend is inferred
+    /// from the provider's `base_url`.
+    pub provider: Option<OssBackend>,
+    /// Model to use with `--oss` when `-m` is not given.
+    pub model: Option<String>,
+    /// Smaller local model that writes the summary when the conversation is
+    /// compacted, instead of the session's model.
+    pub summary_model: Option<String>,
+    /// Tokens of context `summary_model` was loaded with. The conversation's
+    /// oldest turns are left out of the summary so that it fits.
+    pub summary_context_window: Option<i64>,
+    /// Local model that turns text into embeddings, e.g. for searching the
+    /// workspace.
+    pub embedding_model: Option<String>,
+    /// Cloud model that reviews each answer of the local model and approves
+    /// or replaces it. Off unless set.
+    pub cloud_review_model: Option<String>,
+    /// Provider, from `model_providers` or the built-in ones, that serves
+    /// `cloud_review_model`; defaults to "openai".
+    pub cloud_review_provider: Option<String>,
+    /// Base URL of the local server, replacing the backend's default
+    /// `http://localhost:<port>/v1`. `unix:///path/to/server.sock` talks to
+    /// a server listening on a Unix domain socket, and on Windows
+    /// `npipe://./pipe/<name>` one listening on a named pipe. `--oss-url`
+    /// overrides it.
+    pub base_url: Option<String>,
+    /// Allow a base URL that resolves to a public address. Without it,
+    /// prompts only go to servers on this machine or a private network.
+    pub allow_remote: Option<bool>,
+    /// SHA-256 digests of the `lms` CLI builds Codex may run. When set, any
+    /// other binary found where LM Studio installs it is refused.
+    pub trusted_lms_sha256: Option<Vec<String>>,
+    /// Licenses of catalog models that may be downloaded without asking,
+    /// e.g. "apache-2.0", or the name of custom terms such as "llama3.1".
+    pub accepted_licenses: Option<Vec<String>>,
+    /// Never reach the network beyond the local server: no catalog
+    /// searches, no downloads, and no update checks.
+    pub offline: Option<bool>,
+    /// Refuse every connection that does not stay on this machine. Implies
+    /// `offline`, requires the server on a loopback address, and makes the
+    /// local provider's HTTP clients refuse any other address.
+    pub strict_local: Option<bool>,
+    /// How much the local provider's startup path prints.
+    pub verbosity: Option<OssVerbosity>,
+    /// How download progress is shown; `plain` prints periodic lines of text
+    /// for screen readers instead of redrawing a progress bar.
+    pub progress_style: Option<OssProgressStyle>,
+    /// Sampling temperature sent to the local server.
+    pub temperature: Option<f64>,
+    /// Nucleus sampling cutoff sent to the local server.
+    pub top_p: Option<f64>,
+    /// Most tokens the local model generates per response.
+    pub max_tokens: Option<u64>,
+    /// Seconds between health checks of the local server in the TUI; `0`
+    /// turns them off.
+    pub health_check_interval_secs: Option<u64>,
+    /// Speak HTTP/2 to the local server from the first byte, for servers
+    /// that accept cleartext HTTP/2 without an upgrade.
+    pub http2_prior_knowledge: Option<bool>,
+    /// Most idle connections to the local server kept open for reuse.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// Seconds an idle connection to the local server stays open.
+    pub pool_idle_timeout_secs: Option<u64>,
+    /// Seconds between TCP keep-alive probes on connections to the local
+    /// server.
+    pub tcp_keepalive_secs: Option<u64>,
+    /// Seconds to wait for a connection to the local server; a server that
+    /// is not listening fails as soon as this runs out.
+    pub connect_timeout_secs: Option<u64>,
+    /// Seconds a request to the local server may wait for the next bytes of
+    /// its response, including while the model loads.
+    pub read_timeout_secs: Option<u64>,
+    /// Seconds a request to the local server may take in all, streaming its
+    /// response included.
+    pub request_timeout_secs: Option<u64>,
+    /// Headers sent with every request to the local server, e.g. for a
+    /// gateway that routes on them.
+    pub http_headers: Option<HashMap<String, String>>,
+    /// User-Agent sent to the local server instead of Codex's own.
+    pub user_agent: Option<String>,
+    /// Most requests in flight to the local server at once; `0` removes the
+    /// limit. Defaults to the server's parallelism.
+    pub max_concurrent_requests: Option<usize>,
+    /// Average speed model downloads may use, e.g. `"5MB/s"` or `"500KiB"`.
+    pub download_rate_limit: Option<String>,
+    /// Record anonymous throughput of the local model; off unless set.
+    pub performance_telemetry: Option<OssPerformanceTelemetry>,
+    /// Watts the machine draws while the local model generates, used to
+    /// estimate a session's energy when the GPU's own draw cannot be read.
+    pub power_watts: Option<f64>,
+    /// US dollars per million input tokens of the cloud model a session's
+    /// cost is compared to.
+    pub cloud_input_price: Option<f64>,
+    /// US dollars per million output tokens of the cloud model a session's
+    /// cost is compared to.
+    pub cloud_output_price: Option<f64>,
+    /// Unload the session's model from the local server when Codex exits.
+    pub unload_on_exit: Option<bool>,
+    /// Append every SSE frame the local server streams to this file, to
+    /// diagnose malformed streams.
+    pub debug_dump_sse: Option<PathBuf>,
+    /// Seconds a response from the local server may go without a new token
+    /// once generation started, even while other frames arrive; `0` disables
+    /// the check.
+    pub stall_timeout_secs: Option<u64>,
+}
+
+impl OssConfigToml {
+    /// JSON schema of the `[oss]` table, generated from this struct so it
+    /// lists every key with its documentation.
+    pub fn json_schema() -> RootSchema {
+        schemars::schema_for!(OssConfigToml)
+    }
+}
+
+/// Effective settings for the built-in `oss` provider.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OssConfig {
+    pub provider: Option<OssBackend>,
+    pub model: Option<String>,
+    /// Set by `oss.summary_model`; `None` summarizes with the session's
+    /// model.
+    pub summary_model: Option<String>,
+    /// Set by `oss.summary_context_window`; `None` uses the window known for
+    /// `summary_model`, if any.
+    pub summary_context_window: Option<i64>,
+    /// Set by `oss.embedding_model`.
+    pub embedding_model: Option<String>,
+    /// Set by `oss.cloud_review_model`.
+    pub cloud_review_model: Option<String>,
+    /// Set by `oss.cloud_review_provider`, "openai" when unset.
+    pub cloud_review_provider: String,
+    /// Base URL set with `--oss-url` or `oss.base_url`, replacing the
+    /// provider's default `http://localhost:<port>/v1`.
+    pub base_url: Option<String>,
+    /// Set by `oss.allow_remote`.
+    pub allow_remote: bool,
+    /// Set by `oss.trusted_lms_sha256`; empty accepts any `lms` that passes
+    /// the ownership and permission checks.
+    pub trusted_lms_sha256: Vec<String>,
+    /// Set by `oss.accepted_licenses`.
+    pub accepted_licenses: Vec<String>,
+    /// Set by `oss.offline` or the global `--offline` flag; always set with
+    /// `strict_local`.
+    pub offline: bool,
+    /// Set by `oss.strict_local`.
+    pub strict_local: bool,
+    /// Set by `oss.verbosity` or the global `--quiet` / `--verbose` flags.
+    pub verbosity: OssVerbosity,
+    /// Set by `oss.progress_style`.
+    pub progress_style: OssProgressStyle,
+    /// Set by `oss.temperature`, `oss.top_p`, and `oss.max_tokens`.
+    pub sampling: OssSampling,
+    /// Set by `oss.health_check_interval_secs`; `None` uses the TUI's
+    /// default and a zero interval disables the checks.
+    pub health_check_interval: Option<Duration>,
+    /// Set by `oss.http2_prior_knowledge`, the `oss.pool_*` knobs,
+    /// `oss.tcp_keepalive_secs`, the `oss.*_timeout_secs` timeouts,
+    /// `oss.http_headers`, and `oss.user_agent`.
+    pub connection: OssConnection,
+    /// Set by `oss.max_concurrent_requests`; `None` uses the server's
+    /// parallelism.
+    pub max_concurrent_requests: Option<usize>,
+    /// Set by `oss.download_rate_limit`, in bytes per second.
+    pub download_rate_limit: Option<u64>,
+    /// Set by `oss.performance_telemetry`.
+    pub performance_telemetry: OssPerformanceTelemetry,
+    /// Set by `oss.power_watts`; `None` estimates energy only from the
+    /// GPU's measured draw.
+    pub power_watts: Option<f64>,
+    /// Set by `oss.cloud_input_price`; `None` uses the default price.
+    pub cloud_input_price: Option<f64>,
+    /// Set by `oss.cloud_output_price`; `None` uses the default price.
+    pub cloud_output_price: Option<f64>,
+    /// Set by `oss.unload_on_exit`.
+    pub unload_on_exit: bool,
+    /// Set by `oss.debug_dump_sse`.
+    pub debug_dump_sse: Option<PathBuf>,
+    /// Set by `oss.stall_timeout_secs`; `None` uses the default and a zero
+    /// timeout disables the check.
+    pub stall_timeout: Option<Duration>,
+}
+
+impl OssConfig {
+    /// Models the session uses for auxiliary calls besides its own, in the
+    /// order they are prepared.
+    pub fn auxiliary_models(&self) -> impl Iterator<Item = &str> {
+        [
+            self.summary_model.as_deref(),
+            self.embedding_model.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+/// How connections to the local server are made and reused. Unset knobs keep
+/// the HTTP client's defaults.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OssConnection {
+    pub http2_prior_knowledge: bool,
+    pub pool_max_idle_per_host: Option<usize>,
+    pub pool_idle_timeout: Option<Duration>,
+    pub tcp_keepalive: Option<Duration>,
+    pub connect_timeout: Option<Duration>,
+    /// Longest wait for the next bytes of a response.
+    pub read_timeout: Option<Duration>,
+    /// Deadline of a whole request, from sending it to the end of the
+    /// response.
+    pub request_timeout: Option<Duration>,
+    /// Unix domain socket every connection is made to, from a `unix://`
+    /// base URL; the host of request URLs is then ignored.
+    pub unix_socket: Option<PathBuf>,
+    /// Windows named pipe every connection is made to, such as
+    /// `\\.\pipe\llama`, from an `npipe://` base URL.
+    pub named_pipe: Option<String>,
+    /// Headers sent with every request, from `oss.http_headers`.
+    pub http_headers: BTreeMap<String, String>,
+    /// Replaces Codex's User-Agent, from `oss.user_agent`.
+    pub user_agent: Option<String>,
+    /// Connect only to this machine, set by `oss.strict_local`: no proxy is
+    /// used, host names must resolve to loopback addresses, and redirects
+    /// elsewhere are refused.
+    pub loopback_only: bool,
+    /// Connect only to this machine or a private network, set unless
+    /// `oss.allow_remote`: host names must resolve to private addresses, and
+    /// redirects to public ones are refused.
+    pub private_only: bool,
+}
+
+/// How much output the local provider's startup path (server probe, model
+/// checks, downloads) writes to stderr.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OssVerbosity {
+    /// Only errors, for scripting.
+    Quiet,
+    /// Download progress and warnings.
+    #[default]
+    Normal,
+    /// Additionally every probe, retry, and request sent to the server.
+    Verbose,
+}
+
+/// How the CLI shows download progress of the local provider
+/// (`oss.progress_style`).
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OssProgressStyle {
+    /// A progress bar on a terminal, `plain` otherwise.
+    #[default]
+    Auto,
+    /// A progress bar redrawn in place.
+    Bar,
+    /// A line of text every few seconds, e.g. "42% downloaded, 6 minutes
+    /// remaining", which screen readers and logs can follow.
+    Plain,
+}
+
+/// Where anonymous performance samples of the local model go
+/// (`oss.performance_telemetry`). Nothing is recorded unless the user opts in.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OssPerformanceTelemetry {
+    #[default]
+    Off,
+    /// Append samples to a file in the OSS state directory and nowhere else.
+    Local,
+    /// Also send them as `codex.local_performance` through the `[otel]`
+    /// exporter.
+    Otel,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(untagged)]
 pub enum Notifications {
@@ -349,6 +633,10 @@ pub struct Tui {
     /// Defaults to `false`.
     #[serde(default)]
     pub notifications: Notifications,
+
+    /// Color of the badge shown in sessions that use a local model: one of
+    /// "magenta", "cyan", "green", or "red".
+    pub local_badge_color: Option<String>,
 }
 
 /// Settings for notices we display to users via the tui and app-server clients
@@ -358,10 +646,13 @@ pub struct Tui {
 pub struct Notice {
     /// Tracks whether the user has acknowledged the full access warning prompt.
     pub hide_full_access_warning: Option<bool>,
+    /// Tracks whether the user asked not to confirm large local model
+    /// downloads again.
+    pub hide_model_download_confirmation: Option<bool>,
 }
 
 impl Notice {
-    /// used by set_hide_full_access_warning until we refactor config updates
+    /// used by the set_hide_* functions until we refactor config updates
     pub(crate) const TABLE_KEY: &'static str = "notice";
 }
 
@@ -371,6 +662,12 @@ pub struct SandboxWorkspaceWrite {
     pub writable_roots: Vec<PathBuf>,
     #[serde(default)]
     pub network_access: bool,
+    /// Keep the local inference server (the `oss` provider's `base_url`)
+    /// reachable when `network_access` is `false`. Only loopback servers and
+    /// Unix sockets are allowed through. Defaults to on for `--oss` sessions
+    /// and off otherwise.
+    #[serde(default)]
+    pub local_inference_access: Option<bool>,
     #[serde(default)]
     pub exclude_tmpdir_env_var: bool,
     #[serde(default)]
@@ -382,6 +679,7 @@ impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings
         Self {
             writable_roots: sandbox_workspace_write.writable_roots,
             network_access: Some(sandbox_workspace_write.network_access),
+            local_inference_access: sandbox_workspace_write.local_inference_access,
             exclude_tmpdir_env_var: Some(sandbox_workspace_write.exclude_tmpdir_env_var),
             exclude_slash_tmp: Some(sandbox_workspace_write.exclude_slash_tmp),
         }
@@ -497,6 +795,22 @@ mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn oss_schema_documents_every_key() {
+        let schema = serde_json::to_value(OssConfigToml::json_schema()).expect("schema");
+        let properties = schema["properties"].as_object().expect("properties");
+        for key in ["provider", "model", "verbosity", "stall_timeout_secs"] {
+            assert!(properties.contains_key(key), "{key} is missing");
+        }
+        let undocumented: Vec<&String> = properties
+            .iter()
+            .filter(|(_, property)| property.get("description").is_none())
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(undocumented, Vec::<&String>::new());
+        assert!(schema.to_string().contains("\"lmstudio\""));
+    }
+
     #[test]
     fn deserialize_stdio_command_server_config() {
         let cfg: McpServerConfig = toml::from_str(
@@ -1,5 +1,12 @@
+use crate::config_types::OssConnection;
+use crate::model_provider_info::base_url_ip;
+use crate::model_provider_info::is_loopback_base_url;
+use crate::model_provider_info::is_private_address;
 use crate::spawn::CODEX_SANDBOX_ENV_VAR;
 use reqwest::header::HeaderValue;
+use std::net::IpAddr;
+use std::net::SocketAddr;
+use std::sync::Arc;
 use std::sync::LazyLock;
 use std::sync::Mutex;
 use std::sync::OnceLock;
@@ -126,6 +133,196 @@ fn sanitize_user_agent(candidate: String, fallback: &str) -> String {
 
 /// Create a reqwest client with default `originator` and `User-Agent` headers set.
 pub fn create_client() -> reqwest::Client {
+    client_builder()
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+/// Like [`create_client`], for the local `oss` server: connections are made
+/// and reused as `connection` says, so a quick series of tool calls does not
+/// pay for a new connection each time. `bearer_token` is sent to servers
+/// that sit behind an authenticating proxy (see `codex oss login`).
+pub fn create_oss_client(
+    connection: &OssConnection,
+    bearer_token: Option<&str>,
+) -> reqwest::Client {
+    let builder = client_builder()
+        // Ask for compressed responses; large model listings from a server
+        // on the LAN shrink several times over.
+        .gzip(true)
+        .zstd(true);
+    let mut builder = apply_oss_connection(builder, connection);
+    if let Some(token) = bearer_token {
+        builder = apply_bearer_token(builder, token);
+    }
+    builder.build().unwrap_or_else(|_| create_client())
+}
+
+/// Apply the `oss` connection knobs to `builder`; unset ones keep reqwest's
+/// defaults.
+pub fn apply_oss_connection(
+    mut builder: reqwest::ClientBuilder,
+    connection: &OssConnection,
+) -> reqwest::ClientBuilder {
+    if connection.http2_prior_knowledge {
+        builder = builder.http2_prior_knowledge();
+    }
+    if let Some(max_idle) = connection.pool_max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(max_idle);
+    }
+    if let Some(idle_timeout) = connection.pool_idle_timeout {
+        builder = builder.pool_idle_timeout(idle_timeout);
+    }
+    if let Some(keepalive) = connection.tcp_keepalive {
+        builder = builder.tcp_keepalive(keepalive);
+    }
+    if let Some(connect_timeout) = connection.connect_timeout {
+        builder = builder.connect_timeout(connect_timeout);
+    }
+    if let Some(read_timeout) = connection.read_timeout {
+        builder = builder.read_timeout(read_timeout);
+    }
+    if let Some(request_timeout) = connection.request_timeout {
+        builder = builder.timeout(request_timeout);
+    }
+    #[cfg(unix)]
+    if let Some(socket) = &connection.unix_socket {
+        builder = builder.unix_socket(socket.clone());
+    }
+    #[cfg(windows)]
+    if let Some(pipe) = &connection.named_pipe {
+        builder = builder.windows_named_pipe(pipe.clone());
+    }
+    if !connection.http_headers.is_empty() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (name, value) in &connection.http_headers {
+            // Config loading already refused invalid headers.
+            if let (Ok(name), Ok(value)) = (
+                reqwest::header::HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+        builder = builder.default_headers(headers);
+    }
+    if let Some(user_agent) = &connection.user_agent {
+        builder = builder.user_agent(user_agent);
+    }
+    let scope = if connection.loopback_only {
+        Some(AddressScope::Loopback)
+    } else if connection.private_only {
+        Some(AddressScope::Private)
+    } else {
+        None
+    };
+    if let Some(scope) = scope {
+        builder = builder
+            .no_proxy()
+            .dns_resolver(Arc::new(ScopedResolver(scope)))
+            .redirect(scoped_redirects(scope));
+    }
+    builder
+}
+
+/// Most redirects followed, as with reqwest's default policy.
+const MAX_REDIRECTS: usize = 10;
+
+/// Addresses a restricted `oss` client may connect to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AddressScope {
+    /// This machine, for [`OssConnection::loopback_only`].
+    Loopback,
+    /// This machine or a private network, for
+    /// [`OssConnection::private_only`].
+    Private,
+}
+
+impl AddressScope {
+    fn allows(self, ip: IpAddr) -> bool {
+        match self {
+            AddressScope::Loopback => ip.is_loopback(),
+            AddressScope::Private => is_private_address(ip),
+        }
+    }
+
+    fn refusal(self, target: &str) -> String {
+        match self {
+            AddressScope::Loopback => {
+                format!("oss.strict_local refuses {target}: it is not on this machine")
+            }
+            AddressScope::Private => format!(
+                "the oss provider refuses {target}: it is not on this machine or a private network; set `oss.allow_remote = true` to allow it"
+            ),
+        }
+    }
+}
+
+/// Resolves host names to the addresses of their [`AddressScope`] only, and
+/// on every connection, so that a restricted client cannot be pointed at
+/// another machine through DNS, even by a name that first resolved to an
+/// allowed address. A name without such an address is refused, as is one
+/// that does not resolve.
+struct ScopedResolver(AddressScope);
+
+impl reqwest::dns::Resolve for ScopedResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let scope = self.0;
+        Box::pin(async move {
+            let host = name.as_str();
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host, 0))
+                .await?
+                .filter(|address| scope.allows(address.ip()))
+                .collect();
+            if addresses.is_empty() {
+                return Err(Box::new(std::io::Error::new(
+                    std::io::ErrorKind::PermissionDenied,
+                    scope.refusal(host),
+                ))
+                    as Box<dyn std::error::Error + Send + Sync>);
+            }
+            Ok(Box::new(addresses.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// Redirects to a host name are checked by the resolver when followed;
+/// those to an IP address, which is not resolved, are checked here.
+fn scoped_redirects(scope: AddressScope) -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(move |attempt| {
+        let url = attempt.url();
+        let host_allowed = match scope {
+            AddressScope::Loopback => is_loopback_base_url(url.as_str()),
+            AddressScope::Private => {
+                url.host_str().is_some()
+                    && base_url_ip(url.as_str()).is_none_or(|ip| scope.allows(ip))
+            }
+        };
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if host_allowed {
+            attempt.follow()
+        } else {
+            let refused = scope.refusal(&format!("the redirect to {url}"));
+            attempt.error(refused)
+        }
+    })
+}
+
+/// Send `Authorization: Bearer <token>` with every request, kept out of
+/// debug output. A token that is not a valid header value is not sent.
+pub fn apply_bearer_token(builder: reqwest::ClientBuilder, token: &str) -> reqwest::ClientBuilder {
+    let Ok(mut value) = reqwest::header::HeaderValue::from_str(&format!("Bearer {token}")) else {
+        tracing::warn!("ignoring a bearer token that is not a valid header value");
+        return builder;
+    };
+    value.set_sensitive(true);
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(reqwest::header::AUTHORIZATION, value);
+    builder.default_headers(headers)
+}
+
+fn client_builder() -> reqwest::ClientBuilder {
     use reqwest::header::HeaderMap;
 
     let mut headers = HeaderMap::new();
@@ -139,8 +336,7 @@ pub fn create_client() -> reqwest::Client {
     if is_sandboxed() {
         builder = builder.no_proxy();
     }
-
-    builder.build().unwrap_or_else(|_| reqwest::Client::new())
+    builder
 }
 
 fn is_sandboxed() -> bool {
@@ -206,6 +402,159 @@ mod tests {
         assert_eq!(ua_header.to_str().unwrap(), expected_ua);
     }
 
+    #[tokio::test]
+    async fn test_create_oss_client_headers() {
+        skip_if_no_network!();
+
+        use wiremock::Mock;
+        use wiremock::MockServer;
+        use wiremock::ResponseTemplate;
+        use wiremock::matchers::method;
+
+        let route = || [("X-Gateway-Route".to_string(), "gpu-pool".to_string())];
+        // (case, connection, bearer token, header expected on the request)
+        let cases = [
+            (
+                "configured header",
+                OssConnection {
+                    http_headers: route().into_iter().collect(),
+                    ..Default::default()
+                },
+                None,
+                ("x-gateway-route", "gpu-pool"),
+            ),
+            (
+                "configured header over HTTP/2",
+                OssConnection {
+                    http2_prior_knowledge: true,
+                    http_headers: route().into_iter().collect(),
+                    ..Default::default()
+                },
+                None,
+                ("x-gateway-route", "gpu-pool"),
+            ),
+            (
+                "configured user agent replaces Codex's",
+                OssConnection {
+                    user_agent: Some("acme-codex/1.0".to_string()),
+                    ..Default::default()
+                },
+                None,
+                ("user-agent", "acme-codex/1.0"),
+            ),
+            (
+                "configured header replaces a Codex default",
+                OssConnection {
+                    http_headers: [("originator".to_string(), "acme".to_string())]
+                        .into_iter()
+                        .collect(),
+                    ..Default::default()
+                },
+                None,
+                ("originator", "acme"),
+            ),
+            (
+                "bearer token",
+                OssConnection::default(),
+                Some("s3cret"),
+                ("authorization", "Bearer s3cret"),
+            ),
+            (
+                "bearer token replaces a configured authorization header",
+                OssConnection {
+                    http_headers: [("Authorization".to_string(), "Basic Zm9v".to_string())]
+                        .into_iter()
+                        .collect(),
+                    ..Default::default()
+                },
+                Some("s3cret"),
+                ("authorization", "Bearer s3cret"),
+            ),
+        ];
+
+        for (case, connection, bearer_token, (name, value)) in cases {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .respond_with(ResponseTemplate::new(200))
+                .mount(&server)
+                .await;
+
+            let client = create_oss_client(&connection, bearer_token);
+            let resp = client
+                .get(format!("{}/v1/models", server.uri()))
+                .send()
+                .await
+                .expect("failed to send request");
+            if connection.http2_prior_knowledge {
+                assert_eq!(resp.version(), reqwest::Version::HTTP_2, "{case}");
+            }
+
+            let requests = server
+                .received_requests()
+                .await
+                .expect("failed to fetch received requests");
+            let sent: Vec<&str> = requests[0]
+                .headers
+                .get_all(name)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .collect();
+            assert_eq!(sent, vec![value], "{case}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scoped_clients_refuse_redirects_to_public_addresses() {
+        skip_if_no_network!();
+
+        use wiremock::Mock;
+        use wiremock::MockServer;
+        use wiremock::ResponseTemplate;
+        use wiremock::matchers::method;
+        use wiremock::matchers::path;
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/away"))
+            .respond_with(
+                ResponseTemplate::new(302).insert_header("location", "http://203.0.113.7/v1"),
+            )
+            .mount(&server)
+            .await;
+
+        let port = server.address().port();
+        for connection in [
+            OssConnection {
+                loopback_only: true,
+                ..Default::default()
+            },
+            OssConnection {
+                private_only: true,
+                ..Default::default()
+            },
+        ] {
+            let client = create_oss_client(&connection, None);
+            let resp = client
+                .get(format!("http://localhost:{port}/v1/models"))
+                .send()
+                .await
+                .expect("localhost is on this machine");
+            assert!(resp.status().is_success());
+
+            let err = client
+                .get(format!("{}/away", server.uri()))
+                .send()
+                .await
+                .expect_err("redirect to a public address");
+            assert!(err.is_redirect(), "{err}");
+        }
+    }
+
     #[test]
     fn test_invalid_suffix_is_sanitized() {
         let prefix = "codex_cli_rs/0.0.0";
@@ -203,6 +203,8 @@ mod tests {
         SandboxPolicy::WorkspaceWrite {
             writable_roots: writable_roots.into_iter().map(PathBuf::from).collect(),
             network_access,
+            local_network_ports: vec![],
+            local_network_sockets: vec![],
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
         }
@@ -6,6 +6,7 @@ use chrono::DateTime;
 use chrono::Utc;
 use codex_async_utils::CancelErr;
 use codex_protocol::ConversationId;
+use codex_protocol::config_types::OssBackend;
 use codex_protocol::protocol::RateLimitSnapshot;
 use reqwest::StatusCode;
 use serde_json;
@@ -115,6 +116,16 @@ pub enum CodexErr {
     #[error("{0}")]
     RetryLimit(RetryLimitReachedError),
 
+    /// The local `oss` server failed too many requests in a row; requests to
+    /// it are paused for a while. The Session loop does not retry this.
+    #[error("{0}")]
+    LocalBackendUnhealthy(LocalBackendUnhealthyError),
+
+    /// The local `oss` server does not have the requested model. Only the
+    /// user can fix this, so the Session loop does not retry it.
+    #[error("{0}")]
+    LocalModelMissing(String),
+
     /// Agent loop died unexpectedly
     #[error("internal error; agent loop died unexpectedly")]
     InternalAgentDied,
@@ -236,6 +247,26 @@ impl std::fmt::Display for RetryLimitReachedError {
     }
 }
 
+#[derive(Debug)]
+pub struct LocalBackendUnhealthyError {
+    pub backend: OssBackend,
+    /// Requests that failed in a row.
+    pub failures: u32,
+    /// Time left until requests are sent to the server again.
+    pub retry_in: Duration,
+}
+
+impl std::fmt::Display for LocalBackendUnhealthyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let secs = self.retry_in.as_secs() + u64::from(self.retry_in.subsec_nanos() > 0);
+        write!(
+            f,
+            "The local {} server looks unhealthy: {} requests in a row failed. Requests to it are paused; try again in {secs}s.",
+            self.backend, self.failures
+        )
+    }
+}
+
 #[derive(Debug)]
 pub struct UsageLimitReachedError {
     pub(crate) plan_type: Option<PlanType>,
@@ -367,7 +398,47 @@ impl std::fmt::Display for EnvVarError {
     }
 }
 
+/// Whether retrying the request or turn that failed may help.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Retriability {
+    /// A transient failure; a retry after a backoff may succeed.
+    Retriable,
+    /// A retry would fail the same way.
+    NonRetriable,
+    /// Only the user can fix the cause, e.g. by downloading a model or
+    /// waiting for a usage limit to reset.
+    NeedsUserAction,
+}
+
 impl CodexErr {
+    /// How the Session loop treats this error when a turn fails with it.
+    pub fn retriability(&self) -> Retriability {
+        match self {
+            CodexErr::TurnAborted
+            | CodexErr::Interrupted
+            | CodexErr::Fatal(_)
+            // Retrying would only wait out the circuit's cooldown.
+            | CodexErr::LocalBackendUnhealthy(_) => Retriability::NonRetriable,
+            CodexErr::EnvVar(_)
+            | CodexErr::ContextWindowExceeded
+            | CodexErr::UsageLimitReached(_)
+            | CodexErr::UsageNotIncluded
+            | CodexErr::LocalModelMissing(_) => Retriability::NeedsUserAction,
+            _ => Retriability::Retriable,
+        }
+    }
+
+    /// Whether the connection to the model server failed or dropped, as
+    /// when a local server crashes or is closed mid-stream.
+    pub fn is_connection_lost(&self) -> bool {
+        matches!(
+            self,
+            CodexErr::Stream(..)
+                | CodexErr::ConnectionFailed(_)
+                | CodexErr::ResponseStreamFailed(_)
+        )
+    }
+
     /// Minimal shim so that existing `e.downcast_ref::<CodexErr>()` checks continue to compile
     /// after replacing `anyhow::Error` in the return signature. This mirrors the behavior of
     /// `anyhow::Error::downcast_ref` but works directly on our concrete enum.
@@ -420,6 +491,29 @@ mod tests {
     use codex_protocol::protocol::RateLimitWindow;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn only_transient_errors_are_retried() {
+        assert_eq!(
+            CodexErr::Stream("connection reset".to_string(), None).retriability(),
+            Retriability::Retriable
+        );
+        assert_eq!(
+            CodexErr::LocalModelMissing("model 'gpt-oss:20b' not found".to_string()).retriability(),
+            Retriability::NeedsUserAction
+        );
+        assert_eq!(
+            CodexErr::TurnAborted.retriability(),
+            Retriability::NonRetriable
+        );
+    }
+
+    #[test]
+    fn dropped_streams_are_lost_connections() {
+        assert!(CodexErr::Stream("connection reset".to_string(), None).is_connection_lost());
+        assert!(!CodexErr::LocalModelMissing("gpt-oss:20b".to_string()).is_connection_lost());
+        assert!(!CodexErr::ContextWindowExceeded.is_connection_lost());
+    }
+
     fn rate_limit_snapshot() -> RateLimitSnapshot {
         let primary_reset_at = Utc
             .with_ymd_and_hms(2024, 1, 1, 1, 0, 0)
@@ -669,4 +763,17 @@ mod tests {
             );
         });
     }
+
+    #[test]
+    fn local_backend_unhealthy_rounds_the_cooldown_up() {
+        let err = LocalBackendUnhealthyError {
+            backend: OssBackend::LmStudio,
+            failures: 3,
+            retry_in: Duration::from_millis(29_400),
+        };
+        assert_eq!(
+            err.to_string(),
+            "The local lmstudio server looks unhealthy: 3 requests in a row failed. Requests to it are paused; try again in 30s."
+        );
+    }
 }
@@ -0,0 +1,210 @@
+//! Faults injected into a chat completions stream, so the idle timeout, the
+//! stall watchdog and the retry of dropped streams can be tested without a
+//! misbehaving server.
+//!
+//! A [`FaultPlan`] wraps the byte stream of a response and re-splits it on
+//! SSE frame boundaries (`\n\n`), so faults are addressed by the index of
+//! the frame, counting from 0, however the server chunked its writes.
+//!
+//! Only compiled for tests and with the `fault-injection` feature. With the
+//! feature, [`inject_faults`] arms a plan for the next chat stream Codex
+//! opens in this process.
+
+use std::collections::HashMap;
+use std::io;
+use std::pin::Pin;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::Stream;
+use futures::StreamExt;
+
+use crate::error::CodexErr;
+use crate::error::Result;
+
+/// A byte stream with its faults applied.
+pub type FaultyStream = Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>;
+
+/// The faults to inject into one stream.
+#[derive(Debug, Clone, Default)]
+pub struct FaultPlan {
+    delays: HashMap<usize, Duration>,
+    replacements: HashMap<usize, Bytes>,
+    disconnect_after: Option<usize>,
+}
+
+impl FaultPlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hold frame `frame` back for `delay` before passing it on.
+    pub fn delay(mut self, frame: usize, delay: Duration) -> Self {
+        self.delays.insert(frame, delay);
+        self
+    }
+
+    /// Send `bytes` in place of frame `frame`.
+    pub fn malformed(mut self, frame: usize, bytes: impl Into<Bytes>) -> Self {
+        self.replacements.insert(frame, bytes.into());
+        self
+    }
+
+    /// End the stream with a connection reset once `frames` frames have been
+    /// passed on, as a server that crashes mid-answer would.
+    pub fn disconnect_after(mut self, frames: usize) -> Self {
+        self.disconnect_after = Some(frames);
+        self
+    }
+
+    pub fn apply<S>(self, stream: S) -> FaultyStream
+    where
+        S: Stream<Item = Result<Bytes>> + Send + Unpin + 'static,
+    {
+        let state = State {
+            inner: stream,
+            buffer: Vec::new(),
+            next_frame: 0,
+            plan: self,
+            done: false,
+        };
+        Box::pin(futures::stream::unfold(state, State::next))
+    }
+}
+
+struct State<S> {
+    inner: S,
+    buffer: Vec<u8>,
+    next_frame: usize,
+    plan: FaultPlan,
+    done: bool,
+}
+
+impl<S> State<S>
+where
+    S: Stream<Item = Result<Bytes>> + Unpin,
+{
+    async fn next(mut self) -> Option<(Result<Bytes>, Self)> {
+        if self.done {
+            return None;
+        }
+        if self.plan.disconnect_after == Some(self.next_frame) {
+            self.done = true;
+            let err = io::Error::new(
+                io::ErrorKind::ConnectionReset,
+                "connection reset by fault injection",
+            );
+            return Some((Err(CodexErr::Io(err)), self));
+        }
+        let frame = loop {
+            if let Some(end) = self.buffer.windows(2).position(|w| w == b"\n\n") {
+                break self.buffer.drain(..end + 2).collect::<Vec<u8>>();
+            }
+            match self.inner.next().await {
+                Some(Ok(chunk)) => self.buffer.extend_from_slice(&chunk),
+                Some(Err(err)) => {
+                    self.done = true;
+                    return Some((Err(err), self));
+                }
+                // A last frame without its blank line is passed on as is.
+                None if !self.buffer.is_empty() => break std::mem::take(&mut self.buffer),
+                None => return None,
+            }
+        };
+
+        let index = self.next_frame;
+        self.next_frame += 1;
+        if let Some(delay) = self.plan.delays.get(&index) {
+            tokio::time::sleep(*delay).await;
+        }
+        let frame = match self.plan.replacements.get(&index) {
+            Some(replacement) => replacement.clone(),
+            None => Bytes::from(frame),
+        };
+        Some((Ok(frame), self))
+    }
+}
+
+#[cfg(feature = "fault-injection")]
+static INJECTED: std::sync::LazyLock<std::sync::Mutex<Option<FaultPlan>>> =
+    std::sync::LazyLock::new(|| std::sync::Mutex::new(None));
+
+/// Apply `plan` to the next chat stream opened in this process.
+#[cfg(feature = "fault-injection")]
+pub fn inject_faults(plan: FaultPlan) {
+    if let Ok(mut injected) = INJECTED.lock() {
+        *injected = Some(plan);
+    }
+}
+
+/// Apply the plan armed with [`inject_faults`], if any, to `stream`.
+#[cfg(feature = "fault-injection")]
+pub(crate) fn apply_injected<S>(stream: S) -> FaultyStream
+where
+    S: Stream<Item = Result<Bytes>> + Send + Unpin + 'static,
+{
+    let plan = INJECTED
+        .lock()
+        .ok()
+        .and_then(|mut injected| injected.take())
+        .unwrap_or_default();
+    plan.apply(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn chunks(chunks: &[&'static str]) -> impl Stream<Item = Result<Bytes>> + Send + Unpin {
+        futures::stream::iter(
+            chunks
+                .iter()
+                .map(|chunk| Ok(Bytes::from_static(chunk.as_bytes())))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    async fn collect(stream: FaultyStream) -> Vec<String> {
+        stream
+            .map(|item| match item {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).to_string(),
+                Err(err) => format!("error: {err}"),
+            })
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn frames_are_counted_across_chunk_boundaries() {
+        let stream = chunks(&["data: a\n", "\ndata: b\n\ndata:", " c\n\n"]);
+        let frames = collect(
+            FaultPlan::new()
+                .malformed(1, "data: {oops\n\n")
+                .apply(stream),
+        )
+        .await;
+        assert_eq!(
+            frames,
+            vec![
+                "data: a\n\n".to_string(),
+                "data: {oops\n\n".to_string(),
+                "data: c\n\n".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn a_disconnect_ends_the_stream_with_an_error() {
+        let stream = chunks(&["data: a\n\ndata: b\n\ndata: c\n\n"]);
+        let frames = collect(FaultPlan::new().disconnect_after(2).apply(stream)).await;
+        assert_eq!(
+            frames,
+            vec![
+                "data: a\n\n".to_string(),
+                "data: b\n\n".to_string(),
+                "error: connection reset by fault injection".to_string(),
+            ]
+        );
+    }
+}
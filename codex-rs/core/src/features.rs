@@ -41,6 +41,8 @@ pub enum Feature {
     WebSearchRequest,
     /// Automatically approve all approval requests from the harness.
     ApproveAll,
+    /// Offer `/compare` to answer one prompt with two local models side by side.
+    LocalModelCompare,
 }
 
 impl Feature {
@@ -244,4 +246,10 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Experimental,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::LocalModelCompare,
+        key: "local_model_compare",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
 ];
@@ -0,0 +1,67 @@
+//! Entry points for the fuzz targets in `codex-rs/fuzz` (feature
+//! `fuzzing`). Local servers are arbitrary third-party programs, so nothing
+//! they stream may panic the parsers below.
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use codex_otel::otel_event_manager::OtelEventManager;
+use tokio::sync::mpsc;
+
+use crate::chat_completions::parse_usage;
+use crate::chat_completions::process_chat_sse;
+use crate::harmony::HarmonyChannels;
+use crate::oss_latency::StreamTimeouts;
+
+/// Feed `data` to the Chat Completions stream parser as a response body.
+///
+/// The first byte picks how the body is chunked and which optional parsing
+/// is on, so one input covers frames split at any point.
+pub fn chat_sse(data: &[u8]) {
+    let Some((&flags, body)) = data.split_first() else {
+        return;
+    };
+    let chunk_len = usize::from(flags >> 2) + 1;
+    let chunks: Vec<crate::error::Result<Bytes>> = body
+        .chunks(chunk_len)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect();
+    let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    else {
+        return;
+    };
+    runtime.block_on(async move {
+        let (tx, mut rx) = mpsc::channel(16);
+        let parse = process_chat_sse(
+            futures::stream::iter(chunks),
+            tx,
+            StreamTimeouts::fixed(Duration::from_secs(60)),
+            OtelEventManager::new(
+                codex_protocol::ConversationId::new(),
+                "fuzz",
+                "fuzz",
+                None,
+                None,
+                None,
+                false,
+                "fuzz".to_string(),
+            ),
+            None,
+            None,
+            None,
+            flags & 1 == 1,
+            (flags & 2 == 2).then(HarmonyChannels::default),
+        );
+        let drain = async { while rx.recv().await.is_some() {} };
+        tokio::join!(parse, drain);
+    });
+}
+
+/// Decode `data` as the `usage` object of a Chat Completions chunk.
+pub fn usage_json(data: &[u8]) {
+    if let Ok(usage) = serde_json::from_slice(data) {
+        let _ = parse_usage(&usage);
+    }
+}
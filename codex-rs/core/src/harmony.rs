@@ -0,0 +1,196 @@
+//! Splitting of gpt-oss output in the harmony format into its channels.
+//!
+//! Servers that do not parse harmony themselves stream the raw channel
+//! markup as content, e.g.
+//! `<|channel|>analysis<|message|>…<|end|><|start|>assistant<|channel|>final<|message|>…`.
+//! The `analysis` channel holds the model's chain of thought and is surfaced
+//! as reasoning; every other channel is part of the answer.
+
+const START: &str = "<|start|>";
+const CHANNEL: &str = "<|channel|>";
+const MESSAGE: &str = "<|message|>";
+const END_TOKENS: [&str; 3] = ["<|end|>", "<|return|>", "<|call|>"];
+
+/// Channel streamed as reasoning.
+const ANALYSIS_CHANNEL: &str = "analysis";
+
+/// A special token is never longer than this; a longer `<|` without a
+/// closing `|>` is plain text.
+const MAX_TOKEN_LEN: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum HarmonySegment {
+    Reasoning(String),
+    Message(String),
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+enum State {
+    /// Outside any harmony message; text is passed through as it is.
+    #[default]
+    Text,
+    /// Between `<|start|>` or `<|channel|>` and `<|message|>`.
+    Header,
+    /// Inside the body of a message.
+    Body { reasoning: bool },
+}
+
+/// Streaming parser for harmony channels. Special tokens may be split across
+/// chunks, so a trailing partial token is held back until the next chunk.
+#[derive(Debug, Default)]
+pub(crate) struct HarmonyChannels {
+    state: State,
+    pending: String,
+    header: String,
+}
+
+impl HarmonyChannels {
+    pub(crate) fn push(&mut self, chunk: &str) -> Vec<HarmonySegment> {
+        self.pending.push_str(chunk);
+        let mut segments = Vec::new();
+        loop {
+            let Some(open) = self.pending.find("<|") else {
+                // A trailing "<" may be the start of a token.
+                let keep = usize::from(self.pending.ends_with('<'));
+                let text: String = self.pending.drain(..self.pending.len() - keep).collect();
+                self.emit(text, &mut segments);
+                break;
+            };
+            let close = self.pending[open..].find("|>").map(|i| open + i + 2);
+            let Some(close) = close.filter(|close| close - open <= MAX_TOKEN_LEN) else {
+                if self.pending.len() - open <= MAX_TOKEN_LEN && close.is_none() {
+                    // Hold back what may be the start of a token.
+                    let text: String = self.pending.drain(..open).collect();
+                    self.emit(text, &mut segments);
+                    break;
+                }
+                let text: String = self.pending.drain(..open + 2).collect();
+                self.emit(text, &mut segments);
+                continue;
+            };
+            let before: String = self.pending.drain(..open).collect();
+            let token: String = self.pending.drain(..close - open).collect();
+            self.emit(before, &mut segments);
+            self.on_token(&token, &mut segments);
+        }
+        segments
+    }
+
+    /// Flush text held back at the end of the stream.
+    pub(crate) fn finish(&mut self) -> Vec<HarmonySegment> {
+        let text = std::mem::take(&mut self.pending);
+        let mut segments = Vec::new();
+        self.emit(text, &mut segments);
+        segments
+    }
+
+    fn on_token(&mut self, token: &str, segments: &mut Vec<HarmonySegment>) {
+        match token {
+            START | CHANNEL => {
+                if self.state != State::Header {
+                    self.header.clear();
+                }
+                self.header.push_str(token);
+                self.state = State::Header;
+            }
+            MESSAGE if self.state == State::Header => {
+                let channel = self
+                    .header
+                    .rsplit(CHANNEL)
+                    .next()
+                    .and_then(|rest| rest.split_whitespace().next())
+                    .unwrap_or_default();
+                self.state = State::Body {
+                    reasoning: channel == ANALYSIS_CHANNEL,
+                };
+                self.header.clear();
+            }
+            token if END_TOKENS.contains(&token) => self.state = State::Text,
+            // `<|constrain|>` and the like only appear in headers; anything
+            // else is text that happens to look like a token.
+            _ => self.emit(token.to_string(), segments),
+        }
+    }
+
+    fn emit(&mut self, text: String, segments: &mut Vec<HarmonySegment>) {
+        if text.is_empty() {
+            return;
+        }
+        let segment = match self.state {
+            State::Header => {
+                self.header.push_str(&text);
+                return;
+            }
+            State::Body { reasoning: true } => HarmonySegment::Reasoning(text),
+            State::Body { reasoning: false } | State::Text => HarmonySegment::Message(text),
+        };
+        match (segments.last_mut(), segment) {
+            (Some(HarmonySegment::Reasoning(last)), HarmonySegment::Reasoning(text))
+            | (Some(HarmonySegment::Message(last)), HarmonySegment::Message(text)) => {
+                last.push_str(&text);
+            }
+            (_, segment) => segments.push(segment),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Reasoning and message text of `chunks`, streamed one at a time.
+    fn parse(chunks: &[&str]) -> (String, String) {
+        let mut parser = HarmonyChannels::default();
+        let mut segments: Vec<HarmonySegment> =
+            chunks.iter().flat_map(|chunk| parser.push(chunk)).collect();
+        segments.extend(parser.finish());
+        let mut reasoning = String::new();
+        let mut message = String::new();
+        for segment in segments {
+            match segment {
+                HarmonySegment::Reasoning(text) => reasoning.push_str(&text),
+                HarmonySegment::Message(text) => message.push_str(&text),
+            }
+        }
+        (reasoning, message)
+    }
+
+    #[test]
+    fn analysis_channel_becomes_reasoning() {
+        assert_eq!(
+            parse(&[
+                "<|channel|>analysis<|message|>User greets.",
+                " Reply briefly.<|end|><|start|>assistant<|channel|>final<|message|>Hello!",
+            ]),
+            (
+                "User greets. Reply briefly.".to_string(),
+                "Hello!".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn tokens_split_across_chunks_are_reassembled() {
+        assert_eq!(
+            parse(&[
+                "<|chan",
+                "nel|>analysis<|mess",
+                "age|>think<",
+                "|end|><|start|>assistant<|channel|>final <|constrain|>text<|message|>done",
+            ]),
+            ("think".to_string(), "done".to_string())
+        );
+    }
+
+    #[test]
+    fn plain_text_passes_through() {
+        assert_eq!(
+            parse(&["a <|b", " is not a token, nor is <|x|> or 1 < 2"]),
+            (
+                String::new(),
+                "a <|b is not a token, nor is <|x|> or 1 < 2".to_string()
+            )
+        );
+    }
+}
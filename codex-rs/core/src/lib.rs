@@ -11,6 +11,7 @@ pub mod bash;
 mod chat_completions;
 mod client;
 mod client_common;
+mod coalescing_sender;
 pub mod codex;
 mod codex_conversation;
 pub use codex_conversation::CodexConversation;
@@ -26,9 +27,14 @@ mod environment_context;
 pub mod error;
 pub mod exec;
 pub mod exec_env;
+#[cfg(any(test, feature = "fault-injection"))]
+pub mod fault_injection;
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing;
 pub mod features;
 mod flags;
 pub mod git_info;
+mod harmony;
 pub mod landlock;
 pub mod mcp;
 mod mcp_connection_manager;
@@ -42,12 +48,24 @@ mod truncate;
 mod unified_exec;
 mod user_instructions;
 pub use model_provider_info::BUILT_IN_OSS_MODEL_PROVIDER_ID;
+pub use model_provider_info::DEFAULT_OSS_MODEL;
+pub use model_provider_info::LOCAL_SOCKET_HTTP_BASE_URL;
 pub use model_provider_info::ModelProviderInfo;
 pub use model_provider_info::WireApi;
+pub use model_provider_info::base_url_ip;
 pub use model_provider_info::built_in_model_providers;
+pub use model_provider_info::create_oss_provider_for_backend;
 pub use model_provider_info::create_oss_provider_with_base_url;
+pub use model_provider_info::is_local_socket_base_url;
+pub use model_provider_info::is_loopback_base_url;
+pub use model_provider_info::is_private_address;
+pub use model_provider_info::loopback_base_url_port;
+pub use model_provider_info::named_pipe_base_url_path;
+pub use model_provider_info::parse_oss_base_url;
+pub use model_provider_info::unix_socket_base_url_path;
 mod conversation_manager;
 mod event_mapping;
+mod request_stages;
 pub mod review_format;
 pub use codex_protocol::protocol::InitialHistory;
 pub use conversation_manager::ConversationManager;
@@ -58,6 +76,13 @@ pub use auth::CodexAuth;
 pub mod default_client;
 pub mod model_family;
 mod openai_model_info;
+mod oss_cache;
+mod oss_circuit;
+pub mod oss_credentials;
+mod oss_latency;
+mod oss_limiter;
+mod oss_semantic_index;
+mod oss_sse_dump;
 pub mod project_doc;
 mod rollout;
 pub(crate) mod safety;
@@ -7,10 +7,12 @@
 
 use crate::CodexAuth;
 use codex_app_server_protocol::AuthMode;
+use codex_protocol::config_types::OssBackend;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::env::VarError;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use crate::error::EnvVarError;
@@ -139,6 +141,25 @@ impl ModelProviderInfo {
     }
 
     pub(crate) fn get_full_url(&self, auth: &Option<CodexAuth>) -> String {
+        let query_string = self.get_query_string();
+        let base_url = self.get_base_url(auth);
+        match self.wire_api {
+            WireApi::Responses => format!("{base_url}/responses{query_string}"),
+            WireApi::Chat => format!("{base_url}/chat/completions{query_string}"),
+        }
+    }
+
+    /// URL of the OpenAI-compatible `/embeddings` endpoint next to the
+    /// chat endpoint.
+    pub(crate) fn get_embeddings_url(&self) -> String {
+        format!(
+            "{}/embeddings{}",
+            self.get_base_url(&None),
+            self.get_query_string()
+        )
+    }
+
+    fn get_base_url(&self, auth: &Option<CodexAuth>) -> String {
         let default_base_url = if matches!(
             auth,
             Some(CodexAuth {
@@ -150,15 +171,13 @@ impl ModelProviderInfo {
         } else {
             "https://api.openai.com/v1"
         };
-        let query_string = self.get_query_string();
-        let base_url = self
-            .base_url
-            .clone()
-            .unwrap_or(default_base_url.to_string());
-
-        match self.wire_api {
-            WireApi::Responses => format!("{base_url}/responses{query_string}"),
-            WireApi::Chat => format!("{base_url}/chat/completions{query_string}"),
+        match self.base_url.as_deref() {
+            // The client connects to the socket whatever the host.
+            Some(base_url) if is_local_socket_base_url(base_url) => {
+                LOCAL_SOCKET_HTTP_BASE_URL.to_string()
+            }
+            Some(base_url) => base_url.to_string(),
+            None => default_base_url.to_string(),
         }
     }
 
@@ -180,7 +199,10 @@ impl ModelProviderInfo {
     /// Apply provider-specific HTTP headers (both static and environment-based)
     /// onto an existing `reqwest::RequestBuilder` and return the updated
     /// builder.
-    fn apply_http_headers(&self, mut builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    pub(crate) fn apply_http_headers(
+        &self,
+        mut builder: reqwest::RequestBuilder,
+    ) -> reqwest::RequestBuilder {
         if let Some(extra) = &self.http_headers {
             for (k, v) in extra {
                 builder = builder.header(k, v);
@@ -248,9 +270,14 @@ impl ModelProviderInfo {
 }
 
 const DEFAULT_OLLAMA_PORT: u32 = 11434;
+const DEFAULT_LMSTUDIO_PORT: u32 = 1234;
 
 pub const BUILT_IN_OSS_MODEL_PROVIDER_ID: &str = "oss";
 
+/// Default OSS model to use when `--oss` is passed without an explicit `-m`
+/// and `oss.model` is not set.
+pub const DEFAULT_OSS_MODEL: &str = "gpt-oss:20b";
+
 /// Built-in default provider list.
 pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
     use ModelProviderInfo as P;
@@ -307,8 +334,19 @@ pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
 }
 
 pub fn create_oss_provider() -> ModelProviderInfo {
+    create_oss_provider_for_backend(None)
+}
+
+/// Build the `oss` provider for an explicitly selected local backend. The
+/// `CODEX_OSS_` environment variables still take precedence; the backend only
+/// picks the default port.
+pub fn create_oss_provider_for_backend(backend: Option<OssBackend>) -> ModelProviderInfo {
     // These CODEX_OSS_ environment variables are experimental: we may
     // switch to reading values from config.toml instead.
+    let default_port = match backend {
+        Some(OssBackend::LmStudio) => DEFAULT_LMSTUDIO_PORT,
+        Some(OssBackend::Ollama | OssBackend::Generic) | None => DEFAULT_OLLAMA_PORT,
+    };
     let codex_oss_base_url = match std::env::var("CODEX_OSS_BASE_URL")
         .ok()
         .filter(|v| !v.trim().is_empty())
@@ -320,7 +358,7 @@ pub fn create_oss_provider() -> ModelProviderInfo {
                 .ok()
                 .filter(|v| !v.trim().is_empty())
                 .and_then(|v| v.parse::<u32>().ok())
-                .unwrap_or(DEFAULT_OLLAMA_PORT)
+                .unwrap_or(default_port)
         ),
     };
 
@@ -344,6 +382,140 @@ pub fn create_oss_provider_with_base_url(base_url: &str) -> ModelProviderInfo {
     }
 }
 
+/// Validate a user-supplied base URL for the `oss` provider (`--oss-url`).
+/// Only `http`/`https` URLs with a host are accepted, on Unix `unix://` URLs
+/// naming an absolute socket path, and on Windows `npipe://` URLs naming a
+/// local pipe. A URL without a path gets `/v1` appended, since the provider
+/// speaks the OpenAI-compatible API.
+pub fn parse_oss_base_url(value: &str) -> Result<String, String> {
+    if value.trim().starts_with("npipe://") {
+        if !cfg!(windows) {
+            return Err("npipe:// URLs are only supported on Windows".to_string());
+        }
+        if named_pipe_base_url_path(value.trim()).is_none() {
+            return Err(
+                "npipe:// URL must name a local pipe, e.g. npipe://./pipe/llama".to_string(),
+            );
+        }
+        return Ok(value.trim().to_string());
+    }
+    if let Some(socket) = unix_socket_base_url_path(value.trim()) {
+        if !cfg!(unix) {
+            return Err("unix:// URLs are only supported on Unix".to_string());
+        }
+        if !socket.is_absolute() {
+            return Err("unix:// URL must name an absolute socket path".to_string());
+        }
+        return Ok(value.trim().to_string());
+    }
+    let mut url = reqwest::Url::parse(value.trim()).map_err(|e| format!("invalid URL: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!(
+            "unsupported scheme `{}`; expected http or https",
+            url.scheme()
+        ));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err("URL must include a host".to_string());
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err("URL must not include a query string or fragment".to_string());
+    }
+    if url.path() == "/" {
+        url.set_path("/v1");
+    }
+    Ok(url.as_str().trim_end_matches('/').to_string())
+}
+
+/// Whether `base_url` points at this machine (`localhost`, a loopback IP,
+/// a Unix domain socket, or a named pipe).
+pub fn is_loopback_base_url(base_url: &str) -> bool {
+    if is_local_socket_base_url(base_url) {
+        return true;
+    }
+    let Ok(url) = reqwest::Url::parse(base_url) else {
+        return false;
+    };
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// The IP address `base_url` names as its host, if it names one rather
+/// than a host name.
+pub fn base_url_ip(base_url: &str) -> Option<std::net::IpAddr> {
+    let url = reqwest::Url::parse(base_url).ok()?;
+    let host = url.host_str()?;
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+/// Loopback, private (RFC 1918 and unique local), link-local, and
+/// carrier-grade NAT addresses, the last being where VPNs such as Tailscale
+/// put machines.
+pub fn is_private_address(ip: std::net::IpAddr) -> bool {
+    match ip {
+        std::net::IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || (first == 100 && (64..128).contains(&second))
+        }
+        std::net::IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_private_address(std::net::IpAddr::V4(ip));
+            }
+            let first = ip.segments()[0];
+            ip.is_loopback() || first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfe80
+        }
+    }
+}
+
+/// The port of `base_url` when it points at this machine, for opening it up
+/// in the sandbox. A URL without an explicit port gives `None`, rather than
+/// opening the scheme's default port (80 or 443) for the sandbox.
+pub fn loopback_base_url_port(base_url: &str) -> Option<u16> {
+    if !is_loopback_base_url(base_url) {
+        return None;
+    }
+    reqwest::Url::parse(base_url).ok()?.port()
+}
+
+/// What requests to a server on a Unix domain socket or a named pipe are
+/// addressed to; it serves the OpenAI-compatible API under `/v1`.
+pub const LOCAL_SOCKET_HTTP_BASE_URL: &str = "http://localhost/v1";
+
+/// The socket of a `unix://` base URL, e.g. `unix:///run/llama.sock`.
+pub fn unix_socket_base_url_path(base_url: &str) -> Option<PathBuf> {
+    let path = base_url.strip_prefix("unix://")?;
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// The pipe of an `npipe://` base URL, e.g. `\\.\pipe\llama` for
+/// `npipe://./pipe/llama`. Docker's `npipe:////./pipe/llama` spelling is
+/// accepted too. Only pipes on this machine (`.`) are.
+pub fn named_pipe_base_url_path(base_url: &str) -> Option<String> {
+    let path = base_url.strip_prefix("npipe://")?.trim_start_matches('/');
+    let name = path
+        .strip_prefix("./pipe/")
+        .or_else(|| path.strip_prefix(".\\pipe\\"))?;
+    (!name.is_empty()).then(|| format!(r"\\.\pipe\{name}"))
+}
+
+/// Whether `base_url` names a Unix domain socket or a named pipe rather than
+/// a TCP address.
+pub fn is_local_socket_base_url(base_url: &str) -> bool {
+    unix_socket_base_url_path(base_url).is_some() || named_pipe_base_url_path(base_url).is_some()
+}
+
 fn matches_azure_responses_base_url(base_url: &str) -> bool {
     let base = base_url.to_ascii_lowercase();
     const AZURE_MARKERS: [&str; 5] = [
@@ -511,4 +683,115 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             );
         }
     }
+
+    #[test]
+    fn test_parse_oss_base_url() {
+        assert_eq!(
+            parse_oss_base_url("http://gpubox:1234/v1/"),
+            Ok("http://gpubox:1234/v1".to_string())
+        );
+        assert_eq!(
+            parse_oss_base_url("http://gpubox:11434"),
+            Ok("http://gpubox:11434/v1".to_string())
+        );
+        assert!(parse_oss_base_url("gpubox:1234").is_err());
+        assert!(parse_oss_base_url("ftp://gpubox/v1").is_err());
+        assert!(parse_oss_base_url("http://gpubox:1234/v1?x=1").is_err());
+        if cfg!(unix) {
+            assert_eq!(
+                parse_oss_base_url("unix:///run/llama.sock"),
+                Ok("unix:///run/llama.sock".to_string())
+            );
+            assert!(parse_oss_base_url("unix://run/llama.sock").is_err());
+        }
+    }
+
+    #[test]
+    fn unix_socket_providers_send_requests_to_the_socket() {
+        let provider = create_oss_provider_with_base_url("unix:///run/llama.sock");
+        assert_eq!(
+            provider.get_full_url(&None),
+            "http://localhost/v1/chat/completions"
+        );
+    }
+
+    #[test]
+    fn test_is_loopback_base_url() {
+        assert!(is_loopback_base_url("http://localhost:1234/v1"));
+        assert!(is_loopback_base_url("http://127.0.0.1:11434/v1"));
+        assert!(is_loopback_base_url("http://[::1]:11434/v1"));
+        assert!(!is_loopback_base_url("http://gpubox:1234/v1"));
+        assert!(!is_loopback_base_url("http://192.168.1.20:1234/v1"));
+        assert!(is_loopback_base_url("unix:///run/llama.sock"));
+        assert_eq!(
+            loopback_base_url_port("http://localhost:1234/v1"),
+            Some(1234)
+        );
+        assert_eq!(loopback_base_url_port("http://127.0.0.1/v1"), None);
+        assert_eq!(loopback_base_url_port("https://localhost/v1"), None);
+        assert_eq!(loopback_base_url_port("http://gpubox:1234/v1"), None);
+        assert_eq!(loopback_base_url_port("unix:///run/llama.sock"), None);
+        assert_eq!(
+            unix_socket_base_url_path("unix:///run/llama.sock"),
+            Some(PathBuf::from("/run/llama.sock"))
+        );
+        assert_eq!(unix_socket_base_url_path("http://localhost:1234/v1"), None);
+    }
+
+    #[test]
+    fn only_local_and_private_addresses_are_private() {
+        for private in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.5",
+            "192.168.1.20",
+            "169.254.10.1",
+            "100.101.102.103",
+            "::1",
+            "fd7a:115c:a1e0::1",
+            "fe80::1",
+            "::ffff:192.168.1.20",
+        ] {
+            let ip: std::net::IpAddr = private.parse().expect("ip");
+            assert!(is_private_address(ip), "{private}");
+        }
+        for public in [
+            "8.8.8.8",
+            "100.128.0.1",
+            "2606:4700::1111",
+            "::ffff:1.1.1.1",
+        ] {
+            let ip: std::net::IpAddr = public.parse().expect("ip");
+            assert!(!is_private_address(ip), "{public}");
+        }
+        assert_eq!(
+            base_url_ip("http://[::1]:11434/v1"),
+            Some("::1".parse().expect("ip"))
+        );
+        assert_eq!(base_url_ip("http://localhost:11434/v1"), None);
+    }
+
+    #[test]
+    fn named_pipe_base_urls_name_local_pipes() {
+        assert_eq!(
+            named_pipe_base_url_path("npipe://./pipe/llama"),
+            Some(r"\\.\pipe\llama".to_string())
+        );
+        assert_eq!(
+            named_pipe_base_url_path("npipe:////./pipe/llama"),
+            Some(r"\\.\pipe\llama".to_string())
+        );
+        assert_eq!(named_pipe_base_url_path("npipe://gpubox/pipe/llama"), None);
+        assert_eq!(named_pipe_base_url_path("npipe://./pipe/"), None);
+        assert!(is_loopback_base_url("npipe://./pipe/llama"));
+        assert_eq!(loopback_base_url_port("npipe://./pipe/llama"), None);
+        assert_eq!(
+            create_oss_provider_with_base_url("npipe://./pipe/llama").get_full_url(&None),
+            "http://localhost/v1/chat/completions"
+        );
+        assert_eq!(
+            parse_oss_base_url("npipe://./pipe/llama").is_ok(),
+            cfg!(windows)
+        );
+    }
 }
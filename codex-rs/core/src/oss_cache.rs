@@ -0,0 +1,283 @@
+//! Cache of the local `oss` server's answers to auxiliary requests such as
+//! compaction and review. With temperature 0 the server answers the same
+//! request the same way, so asking again only costs another slow inference.
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_protocol::models::ResponseItem;
+use tokio::sync::mpsc;
+
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::error::Result;
+
+/// Most answers kept; the oldest is dropped to make room.
+const MAX_ENTRIES: usize = 64;
+
+/// Most bytes of answers kept, counted as their serialized items; the
+/// oldest are dropped to make room, and a larger answer is not kept at all.
+const MAX_BYTES: usize = 8 * 1024 * 1024;
+
+/// How long an answer is served from the cache.
+const TTL: Duration = Duration::from_secs(30 * 60);
+
+static CACHE: LazyLock<Mutex<ResponseCache>> =
+    LazyLock::new(|| Mutex::new(ResponseCache::default()));
+
+/// Identifies a request by the server it went to, its model and a hash of
+/// its body; two servers may serve different models under one name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct CacheKey {
+    base_url: String,
+    model: String,
+    body_hash: u64,
+}
+
+impl CacheKey {
+    /// Key of a request body sent to `base_url`, when the server answers it
+    /// deterministically.
+    pub(crate) fn for_request(base_url: &str, payload: &serde_json::Value) -> Option<Self> {
+        let greedy = payload
+            .get("temperature")
+            .and_then(serde_json::Value::as_f64)
+            .is_some_and(|temperature| temperature == 0.0);
+        if !greedy {
+            return None;
+        }
+        let mut hasher = DefaultHasher::new();
+        payload.to_string().hash(&mut hasher);
+        Some(Self {
+            base_url: base_url.to_string(),
+            model: payload.get("model")?.as_str()?.to_string(),
+            body_hash: hasher.finish(),
+        })
+    }
+}
+
+/// The items of a completed response.
+#[derive(Debug, Clone)]
+pub(crate) struct CachedResponse {
+    items: Vec<ResponseItem>,
+    /// Size of the serialized items, counted against [`MAX_BYTES`].
+    bytes: usize,
+}
+
+impl CachedResponse {
+    fn new(items: Vec<ResponseItem>) -> Self {
+        let bytes = serde_json::to_vec(&items).map_or(usize::MAX, |json| json.len());
+        Self { items, bytes }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ResponseCache {
+    entries: HashMap<CacheKey, (Instant, CachedResponse)>,
+}
+
+impl ResponseCache {
+    fn get(&self, key: &CacheKey, now: Instant) -> Option<CachedResponse> {
+        self.entries
+            .get(key)
+            .filter(|(stored, _)| now.saturating_duration_since(*stored) < TTL)
+            .map(|(_, response)| response.clone())
+    }
+
+    fn insert(&mut self, key: CacheKey, response: CachedResponse, now: Instant) {
+        if response.bytes > MAX_BYTES {
+            return;
+        }
+        self.entries
+            .retain(|_, (stored, _)| now.saturating_duration_since(*stored) < TTL);
+        self.entries.remove(&key);
+        let mut bytes: usize = self.entries.values().map(|(_, cached)| cached.bytes).sum();
+        while self.entries.len() >= MAX_ENTRIES || bytes + response.bytes > MAX_BYTES {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (stored, _))| *stored)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            if let Some((_, removed)) = self.entries.remove(&oldest) {
+                bytes -= removed.bytes;
+            }
+        }
+        self.entries.insert(key, (now, response));
+    }
+}
+
+/// The cached answer to the request `key` identifies, replayed as a stream.
+/// A replay uses no tokens, so it reports no usage; the original's would be
+/// counted again against the session's totals and context.
+pub(crate) fn replay(key: &CacheKey) -> Option<ResponseStream> {
+    let cached = CACHE.lock().ok()?.get(key, Instant::now())?;
+    let (tx_event, rx_event) = mpsc::channel(cached.items.len() + 1);
+    for item in cached.items {
+        let _ = tx_event.try_send(Ok(ResponseEvent::OutputItemDone(item)));
+    }
+    let _ = tx_event.try_send(Ok(ResponseEvent::Completed {
+        response_id: String::new(),
+        token_usage: None,
+    }));
+    Some(ResponseStream { rx_event })
+}
+
+/// Forward the events of `rx_event` to `tx_event` and cache the response
+/// once it completes without an error.
+pub(crate) async fn forward_and_record(
+    key: CacheKey,
+    mut rx_event: mpsc::Receiver<Result<ResponseEvent>>,
+    tx_event: mpsc::Sender<Result<ResponseEvent>>,
+) {
+    let mut items = Vec::new();
+    loop {
+        let event = tokio::select! {
+            // Dropping `rx_event` lets the request notice the interruption.
+            () = tx_event.closed() => return,
+            event = rx_event.recv() => event,
+        };
+        let Some(event) = event else {
+            return;
+        };
+        match &event {
+            Ok(ResponseEvent::OutputItemDone(item)) => items.push(item.clone()),
+            Ok(ResponseEvent::Completed { .. }) => {
+                if let Ok(mut cache) = CACHE.lock() {
+                    let response = CachedResponse::new(std::mem::take(&mut items));
+                    cache.insert(key.clone(), response, Instant::now());
+                }
+            }
+            _ => {}
+        }
+        if tx_event.send(event).await.is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    const BASE_URL: &str = "http://localhost:11434/v1/chat/completions";
+
+    fn key(body_hash: u64) -> CacheKey {
+        CacheKey {
+            base_url: BASE_URL.to_string(),
+            model: "qwen2.5-coder:7b".to_string(),
+            body_hash,
+        }
+    }
+
+    fn answer(text: &str) -> CachedResponse {
+        CachedResponse::new(vec![ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText {
+                text: text.to_string(),
+            }],
+        }])
+    }
+
+    #[test]
+    fn only_greedy_requests_are_cached() {
+        let body = |temperature: serde_json::Value| {
+            json!({
+                "model": "qwen2.5-coder:7b",
+                "messages": [{"role": "user", "content": "Summarize the file."}],
+                "temperature": temperature,
+            })
+        };
+        let greedy = CacheKey::for_request(BASE_URL, &body(json!(0.0)));
+        assert!(greedy.is_some());
+        assert_eq!(greedy, CacheKey::for_request(BASE_URL, &body(json!(0.0))));
+        assert_eq!(CacheKey::for_request(BASE_URL, &body(json!(0.7))), None);
+        assert_eq!(
+            CacheKey::for_request(
+                BASE_URL,
+                &json!({"model": "qwen2.5-coder:7b", "messages": []})
+            ),
+            None
+        );
+        // Another server may serve another model under the same name.
+        assert_ne!(
+            greedy,
+            CacheKey::for_request(
+                "http://gpu-box:11434/v1/chat/completions",
+                &body(json!(0.0))
+            )
+        );
+    }
+
+    #[test]
+    fn answers_expire_and_the_oldest_makes_room() {
+        let start = Instant::now();
+        let mut cache = ResponseCache::default();
+        cache.insert(key(0), answer("first"), start);
+        assert!(cache.get(&key(0), start + TTL / 2).is_some());
+        assert!(cache.get(&key(0), start + TTL).is_none());
+
+        for hash in 1..=MAX_ENTRIES as u64 {
+            cache.insert(
+                key(hash),
+                answer("later"),
+                start + Duration::from_secs(hash),
+            );
+        }
+        let last = start + Duration::from_secs(MAX_ENTRIES as u64);
+        assert_eq!(cache.entries.len(), MAX_ENTRIES);
+        assert!(cache.get(&key(0), last).is_none());
+        assert!(cache.get(&key(1), last).is_some());
+    }
+
+    #[test]
+    fn answers_are_kept_within_the_byte_budget() {
+        let start = Instant::now();
+        let mut cache = ResponseCache::default();
+        // Three answers of a bit over a third of the budget each.
+        let large = "x".repeat(MAX_BYTES / 3);
+        for hash in 0..3 {
+            cache.insert(key(hash), answer(&large), start + Duration::from_secs(hash));
+        }
+        assert!(cache.get(&key(0), start).is_none());
+        assert!(cache.get(&key(1), start).is_some());
+        assert!(cache.get(&key(2), start).is_some());
+
+        cache.insert(key(3), answer(&"x".repeat(MAX_BYTES)), start);
+        assert!(cache.get(&key(3), start).is_none());
+        assert_eq!(cache.entries.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn replays_report_no_token_usage() {
+        let key = key(u64::MAX);
+        CACHE
+            .lock()
+            .expect("cache lock")
+            .insert(key.clone(), answer("cached"), Instant::now());
+
+        let mut stream = replay(&key).expect("cached answer");
+        assert!(matches!(
+            stream.rx_event.recv().await,
+            Some(Ok(ResponseEvent::OutputItemDone(_)))
+        ));
+        assert!(matches!(
+            stream.rx_event.recv().await,
+            Some(Ok(ResponseEvent::Completed {
+                token_usage: None,
+                ..
+            }))
+        ));
+    }
+}
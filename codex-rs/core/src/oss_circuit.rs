@@ -0,0 +1,121 @@
+//! Circuit breaker for the local `oss` server. After several requests in a
+//! row fail, requests stop for a cooldown instead of hammering a server that
+//! is going down and failing every turn with the same error.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_protocol::config_types::OssBackend;
+
+use crate::error::CodexErr;
+use crate::error::LocalBackendUnhealthyError;
+use crate::error::Result;
+
+/// Consecutive failed requests that open the circuit.
+const FAILURE_THRESHOLD: u32 = 3;
+
+/// How long an open circuit rejects requests. The first request after it
+/// probes the server; if that fails too, the circuit opens again.
+const COOLDOWN: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default)]
+struct CircuitState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// Shared by every turn of a session, so failures add up across turns.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OssCircuit {
+    state: Arc<Mutex<CircuitState>>,
+}
+
+impl OssCircuit {
+    /// Fails with [`CodexErr::LocalBackendUnhealthy`] while the circuit is
+    /// open.
+    pub(crate) fn check(&self, backend: OssBackend) -> Result<()> {
+        self.check_at(backend, Instant::now())
+    }
+
+    pub(crate) fn record_success(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = CircuitState::default();
+        }
+    }
+
+    pub(crate) fn record_failure(&self) {
+        self.record_failure_at(Instant::now());
+    }
+
+    fn check_at(&self, backend: OssBackend, now: Instant) -> Result<()> {
+        let Ok(state) = self.state.lock() else {
+            return Ok(());
+        };
+        match state.open_until {
+            Some(open_until) if now < open_until => Err(CodexErr::LocalBackendUnhealthy(
+                LocalBackendUnhealthyError {
+                    backend,
+                    failures: state.consecutive_failures,
+                    retry_in: open_until - now,
+                },
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn record_failure_at(&self, now: Instant) {
+        if let Ok(mut state) = self.state.lock() {
+            state.consecutive_failures += 1;
+            if state.consecutive_failures >= FAILURE_THRESHOLD {
+                state.open_until = Some(now + COOLDOWN);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn opens_after_consecutive_failures_until_the_cooldown_ends() {
+        let circuit = OssCircuit::default();
+        let start = Instant::now();
+        for _ in 1..FAILURE_THRESHOLD {
+            circuit.record_failure_at(start);
+        }
+        assert!(circuit.check_at(OssBackend::LmStudio, start).is_ok());
+
+        circuit.record_failure_at(start);
+        let err = circuit
+            .check_at(OssBackend::LmStudio, start + Duration::from_secs(10))
+            .err();
+        let Some(CodexErr::LocalBackendUnhealthy(err)) = err else {
+            panic!("expected the circuit to be open, got {err:?}");
+        };
+        assert_eq!(err.failures, FAILURE_THRESHOLD);
+        assert_eq!(err.retry_in, Duration::from_secs(20));
+
+        // After the cooldown one request probes the server; another failure
+        // opens the circuit right away.
+        let probe = start + COOLDOWN;
+        assert!(circuit.check_at(OssBackend::LmStudio, probe).is_ok());
+        circuit.record_failure_at(probe);
+        assert!(circuit.check_at(OssBackend::LmStudio, probe).is_err());
+    }
+
+    #[test]
+    fn a_success_closes_the_circuit() {
+        let circuit = OssCircuit::default();
+        let start = Instant::now();
+        for _ in 0..FAILURE_THRESHOLD {
+            circuit.record_failure_at(start);
+        }
+        circuit.record_success();
+        circuit.record_failure_at(start);
+        assert!(circuit.check_at(OssBackend::Ollama, start).is_ok());
+    }
+}
@@ -0,0 +1,324 @@
+//! Tokens for remote `oss` servers (an LM Studio or vLLM box on the LAN
+//! behind an authenticating proxy), set with `codex oss login <url>`.
+//!
+//! Tokens are kept in the OS keyring, the same way MCP OAuth credentials are
+//! (see `codex-rmcp-client`'s `oauth.rs`), keyed by the server's origin, so
+//! they never have to be written into config.toml. When the keyring is not
+//! available we fall back to CODEX_HOME/oss_credentials.json, readable only
+//! by the current user.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use keyring::Entry;
+use tracing::warn;
+
+use crate::model_provider_info::is_loopback_base_url;
+
+const KEYRING_SERVICE: &str = "Codex OSS Credentials";
+const FALLBACK_FILENAME: &str = "oss_credentials.json";
+
+/// Origin -> token.
+type FallbackFile = BTreeMap<String, String>;
+
+trait KeyringStore {
+    fn load(&self, account: &str) -> Result<Option<String>, keyring::Error>;
+    fn save(&self, account: &str, value: &str) -> Result<(), keyring::Error>;
+    fn delete(&self, account: &str) -> Result<bool, keyring::Error>;
+}
+
+struct DefaultKeyringStore;
+
+impl KeyringStore for DefaultKeyringStore {
+    fn load(&self, account: &str) -> Result<Option<String>, keyring::Error> {
+        match Entry::new(KEYRING_SERVICE, account)?.get_password() {
+            Ok(password) => Ok(Some(password)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn save(&self, account: &str, value: &str) -> Result<(), keyring::Error> {
+        Entry::new(KEYRING_SERVICE, account)?.set_password(value)
+    }
+
+    fn delete(&self, account: &str) -> Result<bool, keyring::Error> {
+        match Entry::new(KEYRING_SERVICE, account)?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+/// Where a token was saved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OssTokenStore {
+    Keyring,
+    /// CODEX_HOME/oss_credentials.json, because the keyring was unavailable.
+    File,
+}
+
+/// The key tokens are stored under: the origin of `base_url`, so
+/// `http://gpu-box:1234/v1` and `http://gpu-box:1234` share one token.
+pub fn oss_credentials_key(base_url: &str) -> io::Result<String> {
+    let url = reqwest::Url::parse(base_url).map_err(|err| {
+        io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid server URL `{base_url}`: {err}"),
+        )
+    })?;
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid server URL `{base_url}`: URL must include a host"),
+        ));
+    }
+    Ok(url.origin().ascii_serialization())
+}
+
+/// Save `token` for the server at `base_url`, preferring the OS keyring.
+pub fn save_oss_token(codex_home: &Path, base_url: &str, token: &str) -> io::Result<OssTokenStore> {
+    save_with_keyring(&DefaultKeyringStore, codex_home, base_url, token)
+}
+
+/// The token saved for the server at `base_url`, if any.
+pub fn load_oss_token(codex_home: &Path, base_url: &str) -> io::Result<Option<String>> {
+    load_with_keyring(&DefaultKeyringStore, codex_home, base_url)
+}
+
+/// Forget the token for the server at `base_url`, wherever it was stored.
+/// Returns whether there was one.
+pub fn delete_oss_token(codex_home: &Path, base_url: &str) -> io::Result<bool> {
+    delete_with_keyring(&DefaultKeyringStore, codex_home, base_url)
+}
+
+/// The token to send to the `oss` server at `base_url`. Servers on this
+/// machine are never looked up, so the common local setup does not touch
+/// the keyring at all. Failures are logged rather than returned: a missing
+/// token surfaces as the server's own 401.
+pub fn remote_oss_token(codex_home: &Path, base_url: &str) -> Option<String> {
+    if is_loopback_base_url(base_url) {
+        return None;
+    }
+    load_oss_token(codex_home, base_url).unwrap_or_else(|err| {
+        warn!("failed to read the token for {base_url}: {err}");
+        None
+    })
+}
+
+fn save_with_keyring<K: KeyringStore>(
+    keyring: &K,
+    codex_home: &Path,
+    base_url: &str,
+    token: &str,
+) -> io::Result<OssTokenStore> {
+    let key = oss_credentials_key(base_url)?;
+    match keyring.save(&key, token) {
+        Ok(()) => {
+            // Drop any copy an earlier keyring-less login left behind.
+            remove_from_file(codex_home, &key)?;
+            Ok(OssTokenStore::Keyring)
+        }
+        Err(err) => {
+            warn!(
+                "failed to save the token for {key} to the keyring, falling back to a file: {err}"
+            );
+            let mut store = read_fallback_file(codex_home)?;
+            store.insert(key, token.to_string());
+            write_fallback_file(codex_home, &store)?;
+            Ok(OssTokenStore::File)
+        }
+    }
+}
+
+fn load_with_keyring<K: KeyringStore>(
+    keyring: &K,
+    codex_home: &Path,
+    base_url: &str,
+) -> io::Result<Option<String>> {
+    let key = oss_credentials_key(base_url)?;
+    match keyring.load(&key) {
+        Ok(Some(token)) => return Ok(Some(token)),
+        Ok(None) => {}
+        Err(err) => warn!("failed to read the token for {key} from the keyring: {err}"),
+    }
+    Ok(read_fallback_file(codex_home)?.remove(&key))
+}
+
+fn delete_with_keyring<K: KeyringStore>(
+    keyring: &K,
+    codex_home: &Path,
+    base_url: &str,
+) -> io::Result<bool> {
+    let key = oss_credentials_key(base_url)?;
+    // A keyring that cannot be reached must not keep the file copy around.
+    let from_keyring = keyring.delete(&key).unwrap_or_else(|err| {
+        warn!("failed to delete the token for {key} from the keyring: {err}");
+        false
+    });
+    let from_file = remove_from_file(codex_home, &key)?;
+    Ok(from_keyring || from_file)
+}
+
+fn remove_from_file(codex_home: &Path, key: &str) -> io::Result<bool> {
+    let mut store = read_fallback_file(codex_home)?;
+    let removed = store.remove(key).is_some();
+    if removed {
+        write_fallback_file(codex_home, &store)?;
+    }
+    Ok(removed)
+}
+
+/// Where tokens go when the keyring is unavailable.
+pub fn fallback_file_path(codex_home: &Path) -> PathBuf {
+    codex_home.join(FALLBACK_FILENAME)
+}
+
+fn read_fallback_file(codex_home: &Path) -> io::Result<FallbackFile> {
+    let path = fallback_file_path(codex_home);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(FallbackFile::new()),
+        Err(err) => return Err(err),
+    };
+    serde_json::from_str(&contents).map_err(|err| {
+        io::Error::new(
+            ErrorKind::InvalidData,
+            format!("failed to parse {}: {err}", path.display()),
+        )
+    })
+}
+
+fn write_fallback_file(codex_home: &Path, store: &FallbackFile) -> io::Result<()> {
+    let path = fallback_file_path(codex_home);
+    if store.is_empty() {
+        return match fs::remove_file(&path) {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        };
+    }
+    fs::create_dir_all(codex_home)?;
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path)?;
+    #[cfg(unix)]
+    {
+        // `mode` only applies to newly created files.
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(serde_json::to_string_pretty(store)?.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::cell::RefCell;
+    use tempfile::TempDir;
+
+    #[derive(Default)]
+    struct MockKeyring {
+        entries: RefCell<BTreeMap<String, String>>,
+        unavailable: bool,
+    }
+
+    impl KeyringStore for MockKeyring {
+        fn load(&self, account: &str) -> Result<Option<String>, keyring::Error> {
+            if self.unavailable {
+                return Err(keyring::Error::NoStorageAccess("locked".into()));
+            }
+            Ok(self.entries.borrow().get(account).cloned())
+        }
+
+        fn save(&self, account: &str, value: &str) -> Result<(), keyring::Error> {
+            if self.unavailable {
+                return Err(keyring::Error::NoStorageAccess("locked".into()));
+            }
+            self.entries
+                .borrow_mut()
+                .insert(account.to_string(), value.to_string());
+            Ok(())
+        }
+
+        fn delete(&self, account: &str) -> Result<bool, keyring::Error> {
+            if self.unavailable {
+                return Err(keyring::Error::NoStorageAccess("locked".into()));
+            }
+            Ok(self.entries.borrow_mut().remove(account).is_some())
+        }
+    }
+
+    #[test]
+    fn tokens_are_keyed_by_origin() {
+        assert_eq!(
+            oss_credentials_key("http://gpu-box:1234/v1").ok(),
+            Some("http://gpu-box:1234".to_string())
+        );
+        assert_eq!(
+            oss_credentials_key("https://llm.example.com/v1").ok(),
+            oss_credentials_key("https://llm.example.com:443").ok()
+        );
+        assert!(oss_credentials_key("not a url").is_err());
+    }
+
+    #[test]
+    fn tokens_prefer_the_keyring() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let keyring = MockKeyring::default();
+
+        let store = save_with_keyring(&keyring, home.path(), "http://gpu-box:1234/v1", "s3cret")?;
+
+        assert_eq!(store, OssTokenStore::Keyring);
+        assert!(!fallback_file_path(home.path()).exists());
+        assert_eq!(
+            load_with_keyring(&keyring, home.path(), "http://gpu-box:1234")?,
+            Some("s3cret".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tokens_fall_back_to_a_private_file() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let keyring = MockKeyring {
+            unavailable: true,
+            ..Default::default()
+        };
+
+        let store = save_with_keyring(&keyring, home.path(), "http://gpu-box:1234/v1", "s3cret")?;
+
+        assert_eq!(store, OssTokenStore::File);
+        assert_eq!(
+            load_with_keyring(&keyring, home.path(), "http://gpu-box:1234/v1")?,
+            Some("s3cret".to_string())
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(fallback_file_path(home.path()))?
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        assert!(delete_with_keyring(
+            &keyring,
+            home.path(),
+            "http://gpu-box:1234/v1"
+        )?);
+        assert!(!fallback_file_path(home.path()).exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+//! Stream timeouts for the local `oss` server derived from the speeds it showed
+//! earlier in the session. A prompt the server needs minutes to process gets
+//! minutes before its first token, while a small one that stalls fails fast,
+//! instead of one fixed timeout being too lax for the one and too strict for
+//! the other.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::protocol::RequestTimingEvent;
+use crate::protocol::TokenUsage;
+
+/// Rough characters-per-token ratio of a request body, used to estimate the
+/// prompt's size before the server counted it.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Headroom over the time the observed speeds predict.
+const SAFETY_FACTOR: f64 = 3.0;
+
+/// Never wait less than this for the first token; it also covers the server
+/// loading the model again after evicting it.
+const MIN_FIRST_TOKEN_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Never wait longer than this for the first token, however long the prompt.
+const MAX_FIRST_TOKEN_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Never wait less than this between tokens.
+const MIN_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Tokens whose generation time a gap between two tokens may last.
+const IDLE_TOKENS: f64 = 50.0;
+
+/// How long a stream may wait for its first token, and then between tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct StreamTimeouts {
+    pub(crate) first_token: Duration,
+    pub(crate) idle: Duration,
+}
+
+impl StreamTimeouts {
+    pub(crate) fn fixed(timeout: Duration) -> Self {
+        Self {
+            first_token: timeout,
+            idle: timeout,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ObservedSpeeds {
+    prefill_tokens_per_sec: Option<f64>,
+    generation_tokens_per_sec: Option<f64>,
+}
+
+/// Slowest speeds the server showed this session. The slowest are kept
+/// because a prompt the server had cached looks faster than it would be
+/// uncached, and a timeout must not cut off a legitimate request.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LatencyBudget {
+    speeds: Arc<Mutex<ObservedSpeeds>>,
+}
+
+impl LatencyBudget {
+    /// Record the speeds of a finished request.
+    pub(crate) fn record(&self, timing: &RequestTimingEvent, usage: Option<&TokenUsage>) {
+        let prefill = usage
+            .zip(timing.prefill_ms)
+            .and_then(|(usage, prefill_ms)| {
+                let processed = usage.input_tokens - usage.cached_input_tokens;
+                (processed > 0 && prefill_ms > 0)
+                    .then(|| processed as f64 * 1000.0 / prefill_ms as f64)
+            });
+        let Ok(mut speeds) = self.speeds.lock() else {
+            return;
+        };
+        keep_slowest(&mut speeds.prefill_tokens_per_sec, prefill);
+        keep_slowest(
+            &mut speeds.generation_tokens_per_sec,
+            timing.tokens_per_second(),
+        );
+    }
+
+    /// Timeouts for a request whose body is `request_bytes` long; `fallback`
+    /// is used until the server's speed is known.
+    pub(crate) fn timeouts(&self, request_bytes: usize, fallback: Duration) -> StreamTimeouts {
+        let Ok(speeds) = self.speeds.lock() else {
+            return StreamTimeouts::fixed(fallback);
+        };
+        let prompt_tokens = (request_bytes / CHARS_PER_TOKEN) as f64;
+        let first_token = speeds.prefill_tokens_per_sec.map_or(fallback, |speed| {
+            Duration::from_secs_f64(prompt_tokens / speed * SAFETY_FACTOR)
+                .clamp(MIN_FIRST_TOKEN_TIMEOUT, MAX_FIRST_TOKEN_TIMEOUT)
+        });
+        let idle = speeds.generation_tokens_per_sec.map_or(fallback, |speed| {
+            Duration::from_secs_f64(IDLE_TOKENS / speed * SAFETY_FACTOR)
+                .clamp(MIN_IDLE_TIMEOUT, fallback.max(MIN_IDLE_TIMEOUT))
+        });
+        StreamTimeouts { first_token, idle }
+    }
+}
+
+fn keep_slowest(slowest: &mut Option<f64>, observed: Option<f64>) {
+    if let Some(observed) = observed.filter(|speed| speed.is_finite() && *speed > 0.0) {
+        *slowest = Some(slowest.map_or(observed, |slowest| slowest.min(observed)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const FALLBACK: Duration = Duration::from_secs(300);
+
+    fn timing(prefill_ms: u64, generation_ms: u64, output_tokens: u64) -> RequestTimingEvent {
+        RequestTimingEvent {
+            connect_ms: 5,
+            prefill_ms: Some(prefill_ms),
+            time_to_first_token_ms: Some(5 + prefill_ms),
+            generation_ms: Some(generation_ms),
+            output_tokens,
+            ..Default::default()
+        }
+    }
+
+    fn usage(input_tokens: i64, cached_input_tokens: i64) -> TokenUsage {
+        TokenUsage {
+            input_tokens,
+            cached_input_tokens,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unknown_speeds_use_the_fallback() {
+        assert_eq!(
+            LatencyBudget::default().timeouts(400_000, FALLBACK),
+            StreamTimeouts::fixed(FALLBACK)
+        );
+    }
+
+    #[test]
+    fn long_prompts_get_longer_to_the_first_token() {
+        let budget = LatencyBudget::default();
+        // 2,000 prompt tokens in 10 s: 200 tokens/s. 51 tokens in 5 s: 10/s.
+        budget.record(&timing(10_000, 5_000, 51), Some(&usage(2_000, 0)));
+
+        let small = budget.timeouts(4_000, FALLBACK);
+        assert_eq!(small.first_token, MIN_FIRST_TOKEN_TIMEOUT);
+        // 50 tokens at 10/s, three times over, is below the floor.
+        assert_eq!(small.idle, MIN_IDLE_TIMEOUT);
+
+        // 240,000 bytes are ~60,000 tokens: 300 s at 200/s, three times over.
+        let long = budget.timeouts(240_000, FALLBACK);
+        assert_eq!(long.first_token, Duration::from_secs(900));
+    }
+
+    #[test]
+    fn the_slowest_observed_speed_is_kept() {
+        let budget = LatencyBudget::default();
+        budget.record(&timing(10_000, 5_000, 51), Some(&usage(2_000, 0)));
+        // A cached prompt looks ten times faster; it must not tighten the budget.
+        budget.record(&timing(1_000, 5_000, 51), Some(&usage(2_500, 500)));
+
+        let long = budget.timeouts(240_000, FALLBACK);
+        assert_eq!(long.first_token, Duration::from_secs(900));
+    }
+}
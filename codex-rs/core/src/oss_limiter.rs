@@ -0,0 +1,95 @@
+//! Limit on the requests in flight to the local `oss` server. A server with
+//! one slot answers concurrent requests one after the other anyway; queueing
+//! them here keeps a compaction or review from stalling the turn's request
+//! behind it at the server, where its timeouts would start running.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use codex_protocol::config_types::OssBackend;
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::sync::Semaphore;
+
+/// Parallel requests Ollama serves when `OLLAMA_NUM_PARALLEL` sets them.
+const OLLAMA_NUM_PARALLEL_ENV_VAR: &str = "OLLAMA_NUM_PARALLEL";
+
+/// One limiter per server URL, shared by every session in the process since
+/// they all compete for the same slots.
+static LIMITERS: LazyLock<Mutex<HashMap<String, OssLimiter>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone)]
+pub(crate) struct OssLimiter {
+    permits: Arc<Semaphore>,
+}
+
+impl OssLimiter {
+    /// Limiter for the server at `base_url` allowing `max_in_flight`
+    /// requests at once; `0` turns the limit off. The first limit set for a
+    /// URL in the process sticks.
+    pub(crate) fn for_server(base_url: &str, max_in_flight: usize) -> Option<Self> {
+        if max_in_flight == 0 {
+            return None;
+        }
+        let Ok(mut limiters) = LIMITERS.lock() else {
+            return Some(Self::new(max_in_flight));
+        };
+        Some(
+            limiters
+                .entry(base_url.trim_end_matches('/').to_string())
+                .or_insert_with(|| Self::new(max_in_flight))
+                .clone(),
+        )
+    }
+
+    fn new(max_in_flight: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_in_flight)),
+        }
+    }
+
+    /// Wait for a free slot, held until the permit is dropped.
+    pub(crate) async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        self.permits.clone().acquire_owned().await.ok()
+    }
+}
+
+/// Requests the server runs in parallel unless `oss.max_concurrent_requests`
+/// says otherwise: what `OLLAMA_NUM_PARALLEL` configures for Ollama, and one
+/// slot, the default of a single llama-server or LM Studio model, otherwise.
+pub(crate) fn default_max_in_flight(backend: OssBackend) -> usize {
+    match backend {
+        OssBackend::Ollama => std::env::var(OLLAMA_NUM_PARALLEL_ENV_VAR)
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .filter(|parallel| *parallel > 0)
+            .unwrap_or(1),
+        OssBackend::LmStudio | OssBackend::Generic => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn requests_to_one_server_share_its_slots() {
+        let first = OssLimiter::for_server("http://127.0.0.1:9/v1", 1).expect("limited");
+        // The limit set first sticks; the URL matches without its slash.
+        let second = OssLimiter::for_server("http://127.0.0.1:9/v1/", 4).expect("limited");
+
+        let permit = first.acquire().await;
+        assert!(permit.is_some());
+        assert_eq!(second.permits.available_permits(), 0);
+        drop(permit);
+        assert_eq!(second.permits.available_permits(), 1);
+    }
+
+    #[test]
+    fn zero_turns_the_limit_off() {
+        assert!(OssLimiter::for_server("http://127.0.0.1:10/v1", 0).is_none());
+    }
+}
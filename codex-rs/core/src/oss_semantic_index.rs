@@ -0,0 +1,548 @@
+//! On-disk vector index of a workspace behind the `semantic_search` tool.
+//!
+//! The files git tracks in the workspace, and the untracked ones it does not
+//! ignore, are split into chunks of lines and embedded with
+//! `oss.embedding_model` on the local server. The vectors are kept in
+//! `$CODEX_HOME/semantic_index/<digest of the workspace path>.json`, so code
+//! never leaves the machine to be indexed or searched.
+//!
+//! A [`WorkspaceIndexer`] started with the session builds the index and
+//! keeps it up to date in the background: only files whose contents changed
+//! are embedded again, and files that are gone are dropped. A search only
+//! embeds its query; while the first pass is still running it waits a
+//! little for it, then answers from what is indexed so far.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+use sha1::Digest;
+use sha1::Sha1;
+use tempfile::NamedTempFile;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::client::ModelClient;
+use crate::error::CodexErr;
+use crate::error::Result;
+
+const INDEX_DIR: &str = "semantic_index";
+
+/// Lines per chunk; neighbouring chunks overlap by [`CHUNK_OVERLAP`] lines so
+/// that code near a boundary is found from either side.
+const CHUNK_LINES: usize = 40;
+const CHUNK_OVERLAP: usize = 8;
+
+/// Longest text embedded for one chunk, well within the context of common
+/// embedding models.
+const MAX_CHUNK_BYTES: usize = 4_000;
+
+/// Larger files are usually generated or data and are not indexed.
+const MAX_FILE_BYTES: u64 = 512 * 1024;
+
+/// Chunks embedded per request to the local server.
+const EMBED_BATCH: usize = 32;
+
+/// Files embedded between saves of the index, which also make them
+/// searchable; a crash or restart only loses the files since the last one.
+const CHECKPOINT_FILES: usize = 64;
+
+/// How often the index is brought up to date once it has caught up.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Longest a search waits for the first pass over the workspace before it
+/// answers from the files indexed so far.
+const FIRST_PASS_WAIT: Duration = Duration::from_secs(5);
+
+/// Embedded chunks of a workspace, by path relative to its root.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub(crate) struct SemanticIndex {
+    /// Embedding model the vectors came from; vectors of different models
+    /// cannot be compared.
+    model: String,
+    files: BTreeMap<String, IndexedFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IndexedFile {
+    sha1: String,
+    chunks: Vec<IndexedChunk>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IndexedChunk {
+    start_line: usize,
+    end_line: usize,
+    embedding: Vec<f32>,
+}
+
+/// A chunk matching a query, most similar first.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SearchHit {
+    pub(crate) path: String,
+    pub(crate) start_line: usize,
+    pub(crate) end_line: usize,
+    pub(crate) score: f32,
+}
+
+/// Hits for a query, and whether they come from a complete index.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SearchResults {
+    pub(crate) hits: Vec<SearchHit>,
+    /// False while the first pass over the workspace is still running.
+    pub(crate) complete: bool,
+}
+
+/// Lines `start_line..=end_line` (1-based) of a file and the text embedded
+/// for them.
+#[derive(Debug, Clone, PartialEq)]
+struct Chunk {
+    start_line: usize,
+    end_line: usize,
+    text: String,
+}
+
+/// Where the index of the workspace at `root` is kept.
+pub(crate) fn index_path(codex_home: &Path, root: &Path) -> PathBuf {
+    let name = hex_sha1(root.to_string_lossy().as_bytes());
+    codex_home.join(INDEX_DIR).join(format!("{name}.json"))
+}
+
+impl SemanticIndex {
+    /// The index saved at `path`, or an empty one when there is none yet or
+    /// it was built with another model.
+    pub(crate) fn load(path: &Path, model: &str) -> Self {
+        let saved = std::fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Self>(&bytes).ok());
+        match saved {
+            Some(index) if index.model == model => index,
+            _ => Self {
+                model: model.to_string(),
+                files: BTreeMap::new(),
+            },
+        }
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> io::Result<()> {
+        let dir = path.parent().unwrap_or(Path::new("."));
+        std::fs::create_dir_all(dir)?;
+        let tmp_file = NamedTempFile::new_in(dir)?;
+        serde_json::to_writer(tmp_file.as_file(), self)?;
+        tmp_file.persist(path).map_err(|err| err.error)?;
+        Ok(())
+    }
+
+    /// Embed the files of the workspace at `root` that changed since the
+    /// index was last updated, calling `checkpoint` every
+    /// [`CHECKPOINT_FILES`] files. Returns how many files were embedded.
+    pub(crate) async fn update(
+        &mut self,
+        client: &ModelClient,
+        root: &Path,
+        mut checkpoint: impl FnMut(&Self),
+    ) -> Result<usize> {
+        let paths = workspace_files(root).await?;
+        self.files
+            .retain(|path, _| paths.binary_search(path).is_ok());
+
+        let mut embedded = 0;
+        for path in paths {
+            let Some(text) = read_text_file(&root.join(&path)).await else {
+                self.files.remove(&path);
+                continue;
+            };
+            let sha1 = hex_sha1(text.as_bytes());
+            if self.files.get(&path).is_some_and(|file| file.sha1 == sha1) {
+                continue;
+            }
+            let chunks = chunk_lines(&path, &text);
+            let mut indexed = Vec::with_capacity(chunks.len());
+            for batch in chunks.chunks(EMBED_BATCH) {
+                let inputs: Vec<String> = batch.iter().map(|chunk| chunk.text.clone()).collect();
+                let embeddings = client.embed(&inputs).await?;
+                indexed.extend(batch.iter().zip(embeddings).map(|(chunk, embedding)| {
+                    IndexedChunk {
+                        start_line: chunk.start_line,
+                        end_line: chunk.end_line,
+                        embedding,
+                    }
+                }));
+            }
+            self.files.insert(
+                path,
+                IndexedFile {
+                    sha1,
+                    chunks: indexed,
+                },
+            );
+            embedded += 1;
+            if embedded % CHECKPOINT_FILES == 0 {
+                checkpoint(self);
+            }
+        }
+        Ok(embedded)
+    }
+
+    /// The `limit` chunks most similar to `query`, an embedding from the
+    /// same model.
+    pub(crate) fn search(&self, query: &[f32], limit: usize) -> Vec<SearchHit> {
+        let mut hits: Vec<SearchHit> = self
+            .files
+            .iter()
+            .flat_map(|(path, file)| {
+                file.chunks.iter().map(move |chunk| SearchHit {
+                    path: path.clone(),
+                    start_line: chunk.start_line,
+                    end_line: chunk.end_line,
+                    score: cosine_similarity(query, &chunk.embedding),
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(limit);
+        hits
+    }
+}
+
+/// What the background task last published for searches to use.
+#[derive(Debug, Clone, Default)]
+struct Snapshot {
+    index: Arc<SemanticIndex>,
+    /// Whether a pass over the whole workspace has finished.
+    caught_up: bool,
+    /// Why the last pass failed, e.g. the workspace is not a git repository.
+    error: Option<String>,
+}
+
+/// The index of a session's workspace, kept up to date by a background
+/// task so that a search does not pay for indexing.
+#[derive(Default)]
+pub(crate) struct WorkspaceIndexer {
+    running: std::sync::Mutex<Option<Running>>,
+}
+
+struct Running {
+    root: PathBuf,
+    snapshot: watch::Receiver<Snapshot>,
+    task: JoinHandle<()>,
+}
+
+impl WorkspaceIndexer {
+    /// Start indexing the workspace at `root`, unless it already is. The
+    /// index of a previous workspace is no longer kept up to date.
+    pub(crate) fn start(&self, client: &ModelClient, root: &Path) -> Result<()> {
+        self.snapshot(client, root).map(drop)
+    }
+
+    /// The `limit` chunks of the workspace at `root` most similar to
+    /// `query`.
+    pub(crate) async fn search(
+        &self,
+        client: &ModelClient,
+        root: &Path,
+        query: &str,
+        limit: usize,
+    ) -> Result<SearchResults> {
+        let mut snapshot = self.snapshot(client, root)?;
+        if !snapshot.borrow().caught_up {
+            let _ = tokio::time::timeout(
+                FIRST_PASS_WAIT,
+                snapshot.wait_for(|snapshot| snapshot.caught_up),
+            )
+            .await;
+        }
+        let Snapshot {
+            index,
+            caught_up,
+            error,
+        } = snapshot.borrow().clone();
+        if let Some(error) = error
+            && index.files.is_empty()
+        {
+            return Err(CodexErr::Io(io::Error::other(error)));
+        }
+
+        let query = client.embed(&[query.to_string()]).await?;
+        let query = query.first().map(Vec::as_slice).unwrap_or_default();
+        Ok(SearchResults {
+            hits: index.search(query, limit),
+            complete: caught_up,
+        })
+    }
+
+    fn snapshot(&self, client: &ModelClient, root: &Path) -> Result<watch::Receiver<Snapshot>> {
+        let Some(model) = client.get_embedding_model() else {
+            return Err(CodexErr::UnsupportedOperation(
+                "semantic search needs a local embedding model; set `oss.embedding_model`"
+                    .to_string(),
+            ));
+        };
+        let mut running = self
+            .running
+            .lock()
+            .map_err(|_| CodexErr::Io(io::Error::other("semantic index lock poisoned")))?;
+        if let Some(running) = running.as_ref()
+            && running.root == root
+            && !running.task.is_finished()
+        {
+            return Ok(running.snapshot.clone());
+        }
+        if let Some(previous) = running.take() {
+            previous.task.abort();
+        }
+        let (tx, snapshot) = watch::channel(Snapshot::default());
+        let task = tokio::spawn(keep_up_to_date(
+            client.clone(),
+            root.to_path_buf(),
+            model,
+            tx,
+        ));
+        *running = Some(Running {
+            root: root.to_path_buf(),
+            snapshot: snapshot.clone(),
+            task,
+        });
+        Ok(snapshot)
+    }
+}
+
+impl Drop for WorkspaceIndexer {
+    fn drop(&mut self) {
+        if let Ok(mut running) = self.running.lock()
+            && let Some(running) = running.take()
+        {
+            running.task.abort();
+        }
+    }
+}
+
+/// Bring the index of the workspace at `root` up to date every
+/// [`REFRESH_INTERVAL`], publishing it to `tx` as it grows.
+async fn keep_up_to_date(
+    client: ModelClient,
+    root: PathBuf,
+    model: String,
+    tx: watch::Sender<Snapshot>,
+) {
+    let path = index_path(&client.get_codex_home(), &root);
+    let mut index = SemanticIndex::load(&path, &model);
+    let mut caught_up = false;
+    tx.send_replace(Snapshot {
+        index: Arc::new(index.clone()),
+        caught_up,
+        error: None,
+    });
+    loop {
+        let updated = index
+            .update(&client, &root, |index| {
+                if let Err(err) = index.save(&path) {
+                    tracing::warn!("failed to save the semantic index: {err}");
+                }
+                tx.send_replace(Snapshot {
+                    index: Arc::new(index.clone()),
+                    caught_up,
+                    error: None,
+                });
+            })
+            .await;
+        // Keep what was embedded even when a later file fails.
+        if let Err(err) = index.save(&path) {
+            tracing::warn!("failed to save the semantic index: {err}");
+        }
+        let error = match updated {
+            Ok(0) => None,
+            Ok(embedded) => {
+                tracing::info!("embedded {embedded} changed file(s) of {}", root.display());
+                None
+            }
+            Err(err) => {
+                tracing::warn!("failed to index {}: {err}", root.display());
+                Some(err.to_string())
+            }
+        };
+        caught_up = true;
+        tx.send_replace(Snapshot {
+            index: Arc::new(index.clone()),
+            caught_up,
+            error,
+        });
+        tokio::time::sleep(REFRESH_INTERVAL).await;
+    }
+}
+
+/// Files of the workspace git knows about and does not ignore, relative to
+/// `root`.
+async fn workspace_files(root: &Path) -> io::Result<Vec<String>> {
+    let output = tokio::process::Command::new("git")
+        .args([
+            "ls-files",
+            "-z",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+        ])
+        .current_dir(root)
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "semantic search needs a git repository; `git ls-files` failed in {}: {}",
+            root.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    let mut paths: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+        .collect();
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// Contents of `path` when it is a text file small enough to index.
+async fn read_text_file(path: &Path) -> Option<String> {
+    let metadata = tokio::fs::metadata(path).await.ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_FILE_BYTES {
+        return None;
+    }
+    let bytes = tokio::fs::read(path).await.ok()?;
+    if bytes.contains(&0) {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Overlapping chunks of `text`, each prefixed with `path` so that the file
+/// name counts towards what the chunk is about.
+fn chunk_lines(path: &str, text: &str) -> Vec<Chunk> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = (start + CHUNK_LINES).min(lines.len());
+        let body = lines[start..end].join("\n");
+        if !body.trim().is_empty() {
+            let mut text = format!("{path}\n{body}");
+            if text.len() > MAX_CHUNK_BYTES {
+                let mut cut = MAX_CHUNK_BYTES;
+                while !text.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                text.truncate(cut);
+            }
+            chunks.push(Chunk {
+                start_line: start + 1,
+                end_line: end,
+                text,
+            });
+        }
+        if end == lines.len() {
+            break;
+        }
+        start = end - CHUNK_OVERLAP;
+    }
+    chunks
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+fn hex_sha1(bytes: &[u8]) -> String {
+    Sha1::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn indexed(chunks: &[(usize, Vec<f32>)]) -> IndexedFile {
+        IndexedFile {
+            sha1: String::new(),
+            chunks: chunks
+                .iter()
+                .map(|(start_line, embedding)| IndexedChunk {
+                    start_line: *start_line,
+                    end_line: start_line + 1,
+                    embedding: embedding.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn chunks_overlap_and_name_their_file() {
+        let text: String = (1..=100).map(|n| format!("line {n}\n")).collect();
+        let chunks = chunk_lines("src/lib.rs", &text);
+        let ranges: Vec<(usize, usize)> = chunks
+            .iter()
+            .map(|chunk| (chunk.start_line, chunk.end_line))
+            .collect();
+        assert_eq!(ranges, vec![(1, 40), (33, 72), (65, 100)]);
+        assert!(chunks[0].text.starts_with("src/lib.rs\nline 1\n"));
+    }
+
+    #[test]
+    fn search_ranks_chunks_by_similarity() {
+        let index = SemanticIndex {
+            model: "nomic-embed-text".to_string(),
+            files: BTreeMap::from([
+                ("a.rs".to_string(), indexed(&[(1, vec![1.0, 0.0])])),
+                (
+                    "b.rs".to_string(),
+                    indexed(&[(1, vec![0.0, 1.0]), (33, vec![0.7, 0.7])]),
+                ),
+            ]),
+        };
+        let hits: Vec<(String, usize)> = index
+            .search(&[1.0, 0.1], 2)
+            .into_iter()
+            .map(|hit| (hit.path, hit.start_line))
+            .collect();
+        assert_eq!(
+            hits,
+            vec![("a.rs".to_string(), 1), ("b.rs".to_string(), 33)]
+        );
+    }
+
+    #[test]
+    fn an_index_of_another_model_is_started_over() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = index_path(dir.path(), Path::new("/work/repo"));
+        let index = SemanticIndex {
+            model: "nomic-embed-text".to_string(),
+            files: BTreeMap::from([("a.rs".to_string(), indexed(&[(1, vec![1.0])]))]),
+        };
+        index.save(&path)?;
+
+        assert_eq!(SemanticIndex::load(&path, "nomic-embed-text"), index);
+        assert!(
+            SemanticIndex::load(&path, "mxbai-embed-large")
+                .files
+                .is_empty()
+        );
+        Ok(())
+    }
+}
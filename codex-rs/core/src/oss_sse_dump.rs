@@ -0,0 +1,126 @@
+//! Dump of the SSE frames a local `oss` server streams
+//! (`oss.debug_dump_sse`), for diagnosing servers whose streams are
+//! malformed.
+//!
+//! Frames are appended as they arrive, in SSE syntax, to the configured
+//! file. Control characters other than tabs are escaped, so the file can be
+//! viewed safely and a frame cannot fake the boundary of the next one.
+
+use std::fmt::Write as _;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::Instant;
+
+use codex_protocol::config_types::OssBackend;
+use tracing::warn;
+
+/// Appends the frames of one response to the dump file.
+pub(crate) struct SseDump {
+    file: File,
+    started: Instant,
+}
+
+impl SseDump {
+    /// Open `path` for the response of `model` on `backend`, or `None` when
+    /// it cannot be written, which is logged.
+    pub(crate) fn open(path: &Path, backend: OssBackend, model: &str) -> Option<Self> {
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+        // Frames hold the model's output, which quotes the user's files.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut dump = options
+            .open(path)
+            .inspect_err(|err| warn!("cannot dump SSE frames to {}: {err}", path.display()))
+            .ok()
+            .map(|file| Self {
+                file,
+                started: Instant::now(),
+            })?;
+        dump.write(&format!(
+            ": {} response from {backend} for {}\n\n",
+            chrono::Utc::now().to_rfc3339(),
+            sanitize(model)
+        ));
+        Some(dump)
+    }
+
+    /// Record a frame with its `event` name (empty for the default) and
+    /// `data`.
+    pub(crate) fn frame(&mut self, event: &str, data: &str) {
+        let mut frame = format!(": +{}ms\n", self.started.elapsed().as_millis());
+        if !event.is_empty() && event != "message" {
+            let _ = writeln!(frame, "event: {}", sanitize(event));
+        }
+        for line in data.split('\n') {
+            let _ = writeln!(frame, "data: {}", sanitize(line));
+        }
+        frame.push('\n');
+        self.write(&frame);
+    }
+
+    /// Record that the stream failed or ended with `reason`.
+    pub(crate) fn end(&mut self, reason: &str) {
+        self.write(&format!(
+            ": +{}ms {}\n\n",
+            self.started.elapsed().as_millis(),
+            sanitize(reason)
+        ));
+    }
+
+    fn write(&mut self, text: &str) {
+        if let Err(err) = self.file.write_all(text.as_bytes()) {
+            warn!("dumping SSE frames failed: {err}");
+        }
+    }
+}
+
+/// `text` with control characters other than tabs escaped.
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\t' => c.to_string(),
+            c if c.is_control() => c.escape_unicode().to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn frames_are_appended_with_control_characters_escaped() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("sse.log");
+        let mut dump = SseDump::open(&path, OssBackend::Ollama, "gpt-oss:20b").expect("open");
+        dump.frame("", "{\"content\":\"a\u{1b}[2J\"}\r");
+        dump.frame("", "[DONE]");
+        dump.end("stream closed");
+
+        let contents = std::fs::read_to_string(&path).expect("read");
+        let lines: Vec<&str> = contents
+            .lines()
+            .filter(|line| !line.starts_with(": "))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "",
+                "data: {\"content\":\"a\\u{1b}[2J\"}\\u{d}",
+                "",
+                "data: [DONE]",
+                "",
+                "",
+            ]
+        );
+        assert!(contents.contains("stream closed"));
+    }
+}
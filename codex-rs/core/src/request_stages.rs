@@ -0,0 +1,99 @@
+//! Timestamps of the stages of a request to the local `oss` server before
+//! its response headers arrive.
+//!
+//! The client only hands a request body over once it has a connection and
+//! wrote the headers, so a body that notes when it is first and last read
+//! tells how long connecting took and how long writing the request took.
+
+use std::sync::Arc;
+use std::sync::OnceLock;
+use std::task::Poll;
+use std::time::Instant;
+
+use bytes::Bytes;
+
+/// When the client started and finished reading a [`timed_body`].
+#[derive(Debug, Default)]
+pub(crate) struct BodyProgress {
+    started: OnceLock<Instant>,
+    written: OnceLock<Instant>,
+}
+
+impl BodyProgress {
+    /// When a connection was ready and the body began to be written.
+    pub(crate) fn started(&self) -> Option<Instant> {
+        self.started.get().copied()
+    }
+
+    /// When the whole body was written.
+    pub(crate) fn written(&self) -> Option<Instant> {
+        self.written.get().copied()
+    }
+}
+
+/// A request body of `bytes` that records its [`BodyProgress`]. Callers set
+/// `Content-Length` themselves, so the body is not sent chunked.
+pub(crate) fn timed_body(bytes: Vec<u8>) -> (reqwest::Body, Arc<BodyProgress>) {
+    let progress = Arc::new(BodyProgress::default());
+    let recorder = Arc::clone(&progress);
+    let mut bytes = Some(Bytes::from(bytes));
+    let stream = futures::stream::poll_fn(move |_| {
+        let now = Instant::now();
+        match bytes.take() {
+            Some(chunk) => {
+                let _ = recorder.started.set(now);
+                Poll::Ready(Some(Ok::<_, std::io::Error>(chunk)))
+            }
+            None => {
+                let _ = recorder.written.set(now);
+                Poll::Ready(None)
+            }
+        }
+    });
+    (reqwest::Body::wrap_stream(stream), progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_test_support::skip_if_no_network;
+    use pretty_assertions::assert_eq;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::method;
+
+    #[tokio::test]
+    async fn a_timed_body_is_sent_whole_and_notes_when_it_was_written() {
+        skip_if_no_network!();
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let body = br#"{"model":"gpt-oss:20b"}"#.to_vec();
+        let len = body.len();
+        let (timed, progress) = timed_body(body.clone());
+        let sent = Instant::now();
+        let resp = reqwest::Client::new()
+            .post(format!("{}/v1/chat/completions", server.uri()))
+            .header(reqwest::header::CONTENT_LENGTH, len)
+            .body(timed)
+            .send()
+            .await
+            .expect("failed to send request");
+        assert!(resp.status().is_success());
+
+        let started = progress.started().expect("body was read");
+        assert!(started >= sent);
+        assert!(progress.written().is_some_and(|written| written >= started));
+        let requests = server
+            .received_requests()
+            .await
+            .expect("failed to fetch received requests");
+        assert_eq!(requests[0].body, body);
+        assert!(!requests[0].headers.contains_key("transfer-encoding"));
+    }
+}
@@ -267,6 +267,23 @@ impl RolloutRecorder {
         }))
     }
 
+    /// Model of the last turn of the rollout at `path` that ran on a local
+    /// server, so that resuming a `--oss` session can make sure it is still
+    /// there. `None` when no turn ran locally.
+    pub async fn last_local_model(path: &Path) -> std::io::Result<Option<String>> {
+        let history = Self::get_rollout_history(path).await?;
+        Ok(history
+            .get_rollout_items()
+            .into_iter()
+            .rev()
+            .find_map(|item| match item {
+                RolloutItem::TurnContext(context) => Some(context),
+                _ => None,
+            })
+            .filter(|context| context.oss_backend.is_some())
+            .map(|context| context.model))
+    }
+
     pub(crate) fn get_rollout_path(&self) -> PathBuf {
         self.rollout_path.clone()
     }
@@ -18,18 +18,24 @@ use crate::rollout::list::ConversationsPage;
 use crate::rollout::list::Cursor;
 use crate::rollout::list::get_conversation;
 use crate::rollout::list::get_conversations;
+use crate::rollout::recorder::RolloutRecorder;
 use anyhow::Result;
 use codex_protocol::ConversationId;
+use codex_protocol::config_types::OssBackend;
+use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::CompactedItem;
 use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::InputMessageKind;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SandboxPolicy;
 use codex_protocol::protocol::SessionMeta;
 use codex_protocol::protocol::SessionMetaLine;
 use codex_protocol::protocol::SessionSource;
+use codex_protocol::protocol::TurnContextItem;
 use codex_protocol::protocol::UserMessageEvent;
 
 const NO_SOURCE_FILTER: &[SessionSource] = &[];
@@ -925,3 +931,56 @@ async fn test_source_filter_excludes_non_matching_sessions() {
         path.ends_with("rollout-2025-08-01T10-00-00-00000000-0000-0000-0000-00000000004d.jsonl")
     }));
 }
+
+#[tokio::test]
+async fn last_local_model_is_read_from_the_latest_turn() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("rollout.jsonl");
+    let turn = |model: &str, oss_backend: Option<OssBackend>| RolloutLine {
+        timestamp: "2025-08-01T10:00:00Z".to_string(),
+        item: RolloutItem::TurnContext(TurnContextItem {
+            cwd: temp.path().to_path_buf(),
+            approval_policy: AskForApproval::OnRequest,
+            sandbox_policy: SandboxPolicy::ReadOnly,
+            model: model.to_string(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+            oss_backend,
+        }),
+    };
+    let write = |lines: &[RolloutLine]| {
+        let meta = RolloutLine {
+            timestamp: "2025-08-01T10:00:00Z".to_string(),
+            item: RolloutItem::SessionMeta(SessionMetaLine {
+                meta: SessionMeta {
+                    id: ConversationId::from_string(&Uuid::from_u128(7).to_string()).unwrap(),
+                    ..SessionMeta::default()
+                },
+                git: None,
+            }),
+        };
+        let mut file = File::create(&path).unwrap();
+        for line in std::iter::once(&meta).chain(lines) {
+            writeln!(file, "{}", serde_json::to_string(line).unwrap()).unwrap();
+        }
+    };
+
+    write(&[
+        turn("gpt-oss:20b", Some(OssBackend::Ollama)),
+        turn("qwen3-coder:30b", Some(OssBackend::Ollama)),
+    ]);
+    assert_eq!(
+        RolloutRecorder::last_local_model(&path).await.unwrap(),
+        Some("qwen3-coder:30b".to_string())
+    );
+
+    // The session moved to the cloud after its local turns.
+    write(&[
+        turn("gpt-oss:20b", Some(OssBackend::Ollama)),
+        turn("gpt-5-codex", None),
+    ]);
+    assert_eq!(
+        RolloutRecorder::last_local_model(&path).await.unwrap(),
+        None
+    );
+}
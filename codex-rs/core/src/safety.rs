@@ -187,6 +187,8 @@ mod tests {
         let policy_workspace_only = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
             network_access: false,
+            local_network_ports: vec![],
+            local_network_sockets: vec![],
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
         };
@@ -208,6 +210,8 @@ mod tests {
         let policy_with_parent = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![parent],
             network_access: false,
+            local_network_ports: vec![],
+            local_network_sockets: vec![],
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
         };
@@ -104,10 +104,25 @@ pub(crate) fn create_seatbelt_command_args(
     };
 
     // TODO(mbolin): apply_patch calls must also honor the SandboxPolicy.
+    let mut socket_cli_args: Vec<String> = Vec::new();
     let network_policy = if sandbox_policy.has_full_network_access() {
-        "(allow network-outbound)\n(allow network-inbound)\n(allow system-socket)"
+        "(allow network-outbound)\n(allow network-inbound)\n(allow system-socket)".to_string()
     } else {
-        ""
+        // Seatbelt can only name `localhost` or `*` as the remote host, which
+        // is exactly the loopback-only carve-out we want.
+        let mut local_policy = sandbox_policy
+            .local_network_ports()
+            .iter()
+            .map(|port| format!("(allow network-outbound (remote ip \"localhost:{port}\"))\n"))
+            .collect::<String>();
+        for (index, socket) in sandbox_policy.local_network_sockets().iter().enumerate() {
+            let socket_param = format!("LOCAL_SOCKET_{index}");
+            socket_cli_args.push(format!("-D{socket_param}={}", socket.to_string_lossy()));
+            local_policy.push_str(&format!(
+                "(allow network-outbound (remote unix-socket (path-literal (param \"{socket_param}\"))))\n"
+            ));
+        }
+        local_policy
     };
 
     let full_policy = format!(
@@ -116,6 +131,7 @@ pub(crate) fn create_seatbelt_command_args(
 
     let mut seatbelt_args: Vec<String> = vec!["-p".to_string(), full_policy];
     seatbelt_args.extend(extra_cli_args);
+    seatbelt_args.extend(socket_cli_args);
     seatbelt_args.push("--".to_string());
     seatbelt_args.extend(command);
     seatbelt_args
@@ -156,6 +172,8 @@ mod tests {
         let policy = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![root_with_git, root_without_git],
             network_access: false,
+            local_network_ports: vec![],
+            local_network_sockets: vec![],
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
         };
@@ -231,6 +249,8 @@ mod tests {
         let policy = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
             network_access: false,
+            local_network_ports: vec![],
+            local_network_sockets: vec![],
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
         };
@@ -301,6 +321,36 @@ mod tests {
         assert_eq!(expected_args, args);
     }
 
+    #[test]
+    fn local_network_carve_outs_open_only_the_local_server() {
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            local_network_ports: vec![1234],
+            local_network_sockets: vec![PathBuf::from("/tmp/llama.sock")],
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        };
+
+        let args = create_seatbelt_command_args(
+            vec!["/bin/echo".to_string(), "hello".to_string()],
+            &policy,
+            Path::new("/"),
+        );
+
+        let full_policy = &args[1];
+        assert!(
+            full_policy.contains("(allow network-outbound (remote ip \"localhost:1234\"))"),
+            "{full_policy}"
+        );
+        assert!(full_policy.contains(
+            "(allow network-outbound (remote unix-socket (path-literal (param \"LOCAL_SOCKET_0\"))))"
+        ));
+        assert!(args.contains(&"-DLOCAL_SOCKET_0=/tmp/llama.sock".to_string()));
+        assert!(!full_policy.contains("(allow network-outbound)"));
+        assert!(!full_policy.contains("(allow network-inbound)"));
+    }
+
     struct PopulatedTmp {
         root_with_git: PathBuf,
         root_without_git: PathBuf,
@@ -3,6 +3,9 @@ use std::sync::Arc;
 use crate::AuthManager;
 use crate::RolloutRecorder;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::oss_circuit::OssCircuit;
+use crate::oss_latency::LatencyBudget;
+use crate::oss_semantic_index::WorkspaceIndexer;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_notification::UserNotifier;
@@ -19,4 +22,13 @@ pub(crate) struct SessionServices {
     pub(crate) auth_manager: Arc<AuthManager>,
     pub(crate) otel_event_manager: OtelEventManager,
     pub(crate) tool_approvals: Mutex<ApprovalStore>,
+    /// Shared by the turns' clients so failed requests to the local server
+    /// add up across turns.
+    pub(crate) oss_circuit: OssCircuit,
+    /// Speeds of the local server seen so far, which size the stream
+    /// timeouts of later requests.
+    pub(crate) oss_latency: LatencyBudget,
+    /// Index of the workspace behind `semantic_search`, built in the
+    /// background from the start of the session.
+    pub(crate) semantic_index: WorkspaceIndexer,
 }
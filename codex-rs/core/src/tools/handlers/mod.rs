@@ -5,6 +5,7 @@ mod mcp;
 mod mcp_resource;
 mod plan;
 mod read_file;
+mod semantic_search;
 mod shell;
 mod test_sync;
 mod unified_exec;
@@ -19,6 +20,7 @@ pub use mcp::McpHandler;
 pub use mcp_resource::McpResourceHandler;
 pub use plan::PlanHandler;
 pub use read_file::ReadFileHandler;
+pub use semantic_search::SemanticSearchHandler;
 pub use shell::ShellHandler;
 pub use test_sync::TestSyncHandler;
 pub use unified_exec::UnifiedExecHandler;
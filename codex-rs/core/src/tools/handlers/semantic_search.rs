@@ -0,0 +1,148 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::oss_semantic_index::SearchHit;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct SemanticSearchHandler;
+
+const DEFAULT_LIMIT: usize = 8;
+const MAX_LIMIT: usize = 50;
+
+/// Lines of each matching chunk quoted in the result.
+const PREVIEW_LINES: usize = 6;
+
+/// Appended to the results while the workspace is still being indexed.
+const INCOMPLETE_NOTE: &str =
+    "The workspace is still being indexed; files not indexed yet are missing from these results.";
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[derive(Deserialize)]
+struct SemanticSearchArgs {
+    query: String,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+#[async_trait]
+impl ToolHandler for SemanticSearchHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            payload,
+            turn,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "semantic_search handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: SemanticSearchArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+
+        let query = args.query.trim();
+        if query.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "query must not be empty".to_string(),
+            ));
+        }
+        if args.limit == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "limit must be greater than zero".to_string(),
+            ));
+        }
+
+        let results = session
+            .services
+            .semantic_index
+            .search(&turn.client, &turn.cwd, query, args.limit.min(MAX_LIMIT))
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("semantic search failed: {err}"))
+            })?;
+
+        let mut content: Vec<String> = results
+            .hits
+            .iter()
+            .map(|hit| format_hit(hit, &read_preview(&turn.cwd.join(&hit.path), hit)))
+            .collect();
+        if content.is_empty() {
+            content.push("No matches found.".to_string());
+        }
+        if !results.complete {
+            content.push(INCOMPLETE_NOTE.to_string());
+        }
+        Ok(ToolOutput::Function {
+            content: content.join("\n\n"),
+            success: Some(!results.hits.is_empty()),
+        })
+    }
+}
+
+fn read_preview(path: &std::path::Path, hit: &SearchHit) -> Vec<String> {
+    std::fs::read_to_string(path)
+        .map(|text| {
+            text.lines()
+                .skip(hit.start_line.saturating_sub(1))
+                .take(PREVIEW_LINES.min(hit.end_line + 1 - hit.start_line))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn format_hit(hit: &SearchHit, preview: &[String]) -> String {
+    let mut out = format!(
+        "{}:{}-{} (similarity {:.2})",
+        hit.path, hit.start_line, hit.end_line, hit.score
+    );
+    for line in preview {
+        out.push_str("\n    ");
+        out.push_str(line);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn hits_are_listed_with_their_location_and_first_lines() {
+        let hit = SearchHit {
+            path: "src/retry.rs".to_string(),
+            start_line: 33,
+            end_line: 72,
+            score: 0.8123,
+        };
+        assert_eq!(
+            format_hit(
+                &hit,
+                &["fn backoff(attempt: u64) -> Duration {".to_string()]
+            ),
+            "src/retry.rs:33-72 (similarity 0.81)\n    fn backoff(attempt: u64) -> Duration {"
+        );
+    }
+}
@@ -30,6 +30,8 @@ pub(crate) struct ToolsConfig {
     pub include_view_image_tool: bool,
     pub experimental_unified_exec_tool: bool,
     pub experimental_supported_tools: Vec<String>,
+    /// Offer `semantic_search`, backed by the local embedding model.
+    pub semantic_search: bool,
 }
 
 pub(crate) struct ToolsConfigParams<'a> {
@@ -76,6 +78,7 @@ impl ToolsConfig {
             include_view_image_tool,
             experimental_unified_exec_tool,
             experimental_supported_tools: model_family.experimental_supported_tools.clone(),
+            semantic_search: false,
         }
     }
 }
@@ -366,6 +369,38 @@ fn create_grep_files_tool() -> ToolSpec {
     })
 }
 
+fn create_semantic_search_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "query".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "What the code you are looking for does or is about, in plain words.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "limit".to_string(),
+        JsonSchema::Number {
+            description: Some("Maximum number of passages to return (defaults to 8).".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "semantic_search".to_string(),
+        description: "Finds the passages of the workspace's files closest in meaning to the \
+                      query, with their paths and line ranges. Use it when you do not know \
+                      which words the code uses."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["query".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_read_file_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
@@ -819,6 +854,7 @@ pub(crate) fn build_specs(
     use crate::tools::handlers::McpResourceHandler;
     use crate::tools::handlers::PlanHandler;
     use crate::tools::handlers::ReadFileHandler;
+    use crate::tools::handlers::SemanticSearchHandler;
     use crate::tools::handlers::ShellHandler;
     use crate::tools::handlers::TestSyncHandler;
     use crate::tools::handlers::UnifiedExecHandler;
@@ -910,6 +946,11 @@ pub(crate) fn build_specs(
         builder.register_handler("list_dir", list_dir_handler);
     }
 
+    if config.semantic_search {
+        builder.push_spec(create_semantic_search_tool());
+        builder.register_handler("semantic_search", Arc::new(SemanticSearchHandler));
+    }
+
     if config
         .experimental_supported_tools
         .contains(&"test_sync_tool".to_string())
@@ -1099,6 +1140,25 @@ mod tests {
         assert!(tools.iter().any(|tool| tool_name(&tool.spec) == "list_dir"));
     }
 
+    #[test]
+    fn test_semantic_search_is_offered_with_a_local_embedding_model() {
+        let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
+        let mut config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &Features::with_defaults(),
+        });
+        let offered = |config: &ToolsConfig| {
+            let (tools, _) = build_specs(config, None).build();
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "semantic_search")
+        };
+        assert!(!offered(&config));
+
+        config.semantic_search = true;
+        assert!(offered(&config));
+    }
+
     #[test]
     fn test_build_specs_mcp_tools() {
         let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
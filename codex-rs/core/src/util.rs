@@ -1,6 +1,9 @@
 use std::time::Duration;
 
+use chrono::DateTime;
+use chrono::Utc;
 use rand::Rng;
+use reqwest::header::HeaderMap;
 
 const INITIAL_DELAY_MS: u64 = 200;
 const BACKOFF_FACTOR: f64 = 2.0;
@@ -11,3 +14,115 @@ pub(crate) fn backoff(attempt: u64) -> Duration {
     let jitter = rand::rng().random_range(0.9..1.1);
     Duration::from_millis((base as f64 * jitter) as u64)
 }
+
+/// Longest wait a server's retry hint may impose on the next attempt.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(120);
+
+/// Delay a rate-limited or overloaded server asks for before the next
+/// attempt: `retry-after-ms`, `Retry-After` in seconds or as an HTTP date, or
+/// the `x-ratelimit-reset-*` durations of OpenAI-compatible proxies, capped at
+/// two minutes.
+pub(crate) fn retry_after(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let delay = header("retry-after-ms")
+        .and_then(|ms| ms.trim().parse::<f64>().ok())
+        .map(|ms| Duration::from_secs_f64(ms.max(0.0) / 1000.0))
+        .or_else(|| {
+            let value = header("retry-after")?.trim();
+            match value.parse::<f64>() {
+                Ok(secs) => Some(Duration::from_secs_f64(secs.max(0.0))),
+                Err(_) => {
+                    let at = DateTime::parse_from_rfc2822(value).ok()?;
+                    Some((at.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+                }
+            }
+        })
+        .or_else(|| {
+            ["x-ratelimit-reset-requests", "x-ratelimit-reset-tokens"]
+                .into_iter()
+                .filter_map(|name| header(name).and_then(parse_reset_duration))
+                .max()
+        })?;
+    Some(delay.min(MAX_RETRY_DELAY))
+}
+
+/// Durations like `1s`, `6m0s`, or `250ms`.
+fn parse_reset_duration(value: &str) -> Option<Duration> {
+    let mut rest = value.trim();
+    let mut total = Duration::ZERO;
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let split = rest.find(|c: char| !(c.is_ascii_digit() || c == '.'))?;
+        let (number, tail) = rest.split_at(split);
+        let number: f64 = number.parse().ok()?;
+        let unit_len = tail
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+        let secs = match unit {
+            "h" => number * 3600.0,
+            "m" => number * 60.0,
+            "s" => number,
+            "ms" => number / 1000.0,
+            _ => return None,
+        };
+        total += Duration::from_secs_f64(secs);
+        rest = tail;
+    }
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, value)| {
+                (
+                    reqwest::header::HeaderName::from_static(name),
+                    HeaderValue::from_static(value),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn retry_hints_are_read_in_every_format() {
+        let now = DateTime::parse_from_rfc3339("2015-10-21T07:27:30Z")
+            .expect("valid date")
+            .with_timezone(&Utc);
+        let delays: Vec<Option<Duration>> = [
+            headers(&[("retry-after", "3")]),
+            headers(&[("retry-after", "Wed, 21 Oct 2015 07:28:00 GMT")]),
+            headers(&[("retry-after-ms", "1500"), ("retry-after", "9")]),
+            headers(&[
+                ("x-ratelimit-reset-requests", "1m30s"),
+                ("x-ratelimit-reset-tokens", "250ms"),
+            ]),
+            headers(&[("retry-after", "3600")]),
+            headers(&[("retry-after", "soon")]),
+            headers(&[]),
+        ]
+        .iter()
+        .map(|headers| retry_after(headers, now))
+        .collect();
+        assert_eq!(
+            delays,
+            [
+                Some(Duration::from_secs(3)),
+                Some(Duration::from_secs(30)),
+                Some(Duration::from_millis(1500)),
+                Some(Duration::from_secs(90)),
+                Some(MAX_RETRY_DELAY),
+                None,
+                None,
+            ]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use codex_app_server_protocol::AuthMode;
+use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
 use codex_core::ContentItem;
 use codex_core::LocalShellAction;
 use codex_core::LocalShellExecAction;
@@ -11,9 +12,11 @@ use codex_core::Prompt;
 use codex_core::ReasoningItemContent;
 use codex_core::ResponseItem;
 use codex_core::WireApi;
+use codex_core::config::Config;
 use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
 use codex_otel::otel_event_manager::OtelEventManager;
 use codex_protocol::ConversationId;
+use codex_protocol::config_types::OssSampling;
 use core_test_support::load_default_config_for_test;
 use futures::StreamExt;
 use serde_json::Value;
@@ -29,6 +32,13 @@ fn network_disabled() -> bool {
 }
 
 async fn run_request(input: Vec<ResponseItem>) -> Value {
+    run_request_with_config(input, |_| {}).await
+}
+
+async fn run_request_with_config(
+    input: Vec<ResponseItem>,
+    configure: impl FnOnce(&mut Config),
+) -> Value {
     let server = MockServer::start().await;
 
     let template = ResponseTemplate::new(200)
@@ -68,6 +78,7 @@ async fn run_request(input: Vec<ResponseItem>) -> Value {
     config.model_provider_id = provider.name.clone();
     config.model_provider = provider.clone();
     config.show_raw_agent_reasoning = true;
+    configure(&mut config);
     let effort = config.model_reasoning_effort;
     let summary = config.model_reasoning_summary;
     let config = Arc::new(config);
@@ -359,3 +370,52 @@ async fn suppresses_duplicate_assistant_messages() {
         Value::String("dup".into())
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn sends_oss_sampling_only_to_the_oss_provider() {
+    if network_disabled() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let sampling = OssSampling {
+        temperature: Some(0.2),
+        top_p: None,
+        max_tokens: Some(512),
+    };
+    let body = run_request_with_config(vec![user_message("u1")], |config| {
+        config.model_provider_id = BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+        config.oss.sampling = sampling;
+    })
+    .await;
+    assert_eq!(body["temperature"], 0.2);
+    assert_eq!(body["max_tokens"], 512);
+    assert!(body.get("top_p").is_none());
+
+    let body = run_request_with_config(vec![user_message("u1")], |config| {
+        config.oss.sampling = sampling;
+    })
+    .await;
+    assert!(body.get("temperature").is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn asks_only_the_oss_provider_for_token_usage() {
+    if network_disabled() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let body = run_request_with_config(vec![user_message("u1")], |config| {
+        config.model_provider_id = BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+    })
+    .await;
+    assert_eq!(body["stream_options"]["include_usage"], true);
+
+    let body = run_request(vec![user_message("u1")]).await;
+    assert!(body.get("stream_options").is_none());
+}
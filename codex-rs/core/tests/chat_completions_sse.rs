@@ -1,8 +1,11 @@
 use assert_matches::assert_matches;
+use std::io::Read;
+use std::io::Write;
 use std::sync::Arc;
 use tracing_test::traced_test;
 
 use codex_app_server_protocol::AuthMode;
+use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
 use codex_core::ContentItem;
 use codex_core::ModelClient;
 use codex_core::ModelProviderInfo;
@@ -10,6 +13,7 @@ use codex_core::Prompt;
 use codex_core::ResponseEvent;
 use codex_core::ResponseItem;
 use codex_core::WireApi;
+use codex_core::model_family::find_family_for_model;
 use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
 use codex_otel::otel_event_manager::OtelEventManager;
 use codex_protocol::ConversationId;
@@ -31,6 +35,15 @@ async fn run_stream(sse_body: &str) -> Vec<ResponseEvent> {
 }
 
 async fn run_stream_with_bytes(sse_body: &[u8]) -> Vec<ResponseEvent> {
+    run_stream_for_provider(sse_body, "mock", None).await
+}
+
+/// Events of a stream from `provider_id`, for the default model or `model`.
+async fn run_stream_for_provider(
+    sse_body: &[u8],
+    provider_id: &str,
+    model: Option<&str>,
+) -> Vec<ResponseEvent> {
     let server = MockServer::start().await;
 
     let template = ResponseTemplate::new(200)
@@ -44,9 +57,31 @@ async fn run_stream_with_bytes(sse_body: &[u8]) -> Vec<ResponseEvent> {
         .mount(&server)
         .await;
 
+    let (client, _codex_home) = test_client(format!("{}/v1", server.uri()), provider_id, model);
+    let prompt = hello_prompt();
+
+    let mut stream = match client.stream(&prompt).await {
+        Ok(s) => s,
+        Err(e) => panic!("stream chat failed: {e}"),
+    };
+    let mut events = Vec::new();
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(ev) => events.push(ev),
+            // We still collect the error to exercise telemetry and complete the task.
+            Err(_e) => break,
+        }
+    }
+    events
+}
+
+/// Client for the chat completions endpoint under `base_url`, sending as
+/// `provider_id`. The returned directory holds the config and must outlive
+/// the client.
+fn test_client(base_url: String, provider_id: &str, model: Option<&str>) -> (ModelClient, TempDir) {
     let provider = ModelProviderInfo {
         name: "mock".into(),
-        base_url: Some(format!("{}/v1", server.uri())),
+        base_url: Some(base_url),
         env_key: None,
         env_key_instructions: None,
         wire_api: WireApi::Chat,
@@ -64,9 +99,15 @@ async fn run_stream_with_bytes(sse_body: &[u8]) -> Vec<ResponseEvent> {
         Err(e) => panic!("failed to create TempDir: {e}"),
     };
     let mut config = load_default_config_for_test(&codex_home);
-    config.model_provider_id = provider.name.clone();
+    config.model_provider_id = provider_id.to_string();
     config.model_provider = provider.clone();
     config.show_raw_agent_reasoning = true;
+    if let Some(model) = model {
+        config.model = model.to_string();
+        if let Some(family) = find_family_for_model(model) {
+            config.model_family = family;
+        }
+    }
     let effort = config.model_reasoning_effort;
     let summary = config.model_reasoning_summary;
     let config = Arc::new(config);
@@ -94,6 +135,10 @@ async fn run_stream_with_bytes(sse_body: &[u8]) -> Vec<ResponseEvent> {
         conversation_id,
     );
 
+    (client, codex_home)
+}
+
+fn hello_prompt() -> Prompt {
     let mut prompt = Prompt::default();
     prompt.input = vec![ResponseItem::Message {
         id: None,
@@ -102,20 +147,7 @@ async fn run_stream_with_bytes(sse_body: &[u8]) -> Vec<ResponseEvent> {
             text: "hello".to_string(),
         }],
     }];
-
-    let mut stream = match client.stream(&prompt).await {
-        Ok(s) => s,
-        Err(e) => panic!("stream chat failed: {e}"),
-    };
-    let mut events = Vec::new();
-    while let Some(event) = stream.next().await {
-        match event {
-            Ok(ev) => events.push(ev),
-            // We still collect the error to exercise telemetry and complete the task.
-            Err(_e) => break,
-        }
-    }
-    events
+    prompt
 }
 
 fn assert_message(item: &ResponseItem, expected: &str) {
@@ -183,6 +215,200 @@ async fn streams_text_without_reasoning() {
     assert_matches!(events[2], ResponseEvent::Completed { .. });
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn reports_request_timing_for_the_oss_provider() {
+    if network_disabled() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let sse = concat!(
+        "data: {\"choices\":[{\"delta\":{\"reasoning\":\"hm\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{\"content\":\"hi\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{\"content\":\"!\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{}}]}\n\n",
+        "data: [DONE]\n\n",
+    );
+
+    let events =
+        run_stream_for_provider(sse.as_bytes(), BUILT_IN_OSS_MODEL_PROVIDER_ID, None).await;
+    let timings: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            ResponseEvent::RequestTiming(timing) => Some(*timing),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(timings.len(), 1, "unexpected events: {events:?}");
+    let timing = timings[0];
+    assert_eq!(timing.output_tokens, 3);
+    assert!(timing.time_to_first_token_ms >= Some(timing.connect_ms));
+    assert_matches!(events.last(), Some(ResponseEvent::Completed { .. }));
+
+    // Cloud providers are not timed.
+    let events = run_stream(sse).await;
+    assert!(
+        !events
+            .iter()
+            .any(|event| matches!(event, ResponseEvent::RequestTiming(_))),
+        "unexpected events: {events:?}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn reports_token_usage_sent_after_the_finish_reason() {
+    if network_disabled() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let sse = concat!(
+        "data: {\"choices\":[{\"delta\":{\"content\":\"hi\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}],\"usage\":null}\n\n",
+        "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":1200,\"completion_tokens\":7,\"total_tokens\":1207}}\n\n",
+        "data: [DONE]\n\n",
+    );
+
+    let events =
+        run_stream_for_provider(sse.as_bytes(), BUILT_IN_OSS_MODEL_PROVIDER_ID, None).await;
+    match events.last() {
+        Some(ResponseEvent::Completed {
+            token_usage: Some(usage),
+            ..
+        }) => {
+            assert_eq!(usage.input_tokens, 1200);
+            assert_eq!(usage.output_tokens, 7);
+            assert_eq!(usage.total_tokens, 1207);
+        }
+        other => panic!("expected Completed with usage, got {other:?}"),
+    }
+    let timing = events.iter().find_map(|event| match event {
+        ResponseEvent::RequestTiming(timing) => Some(*timing),
+        _ => None,
+    });
+    assert_eq!(timing.map(|timing| timing.output_tokens), Some(7));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn splits_raw_harmony_channels_of_gpt_oss() {
+    if network_disabled() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let sse = concat!(
+        "data: {\"choices\":[{\"delta\":{\"content\":\"<|channel|>analysis<|message|>Greet\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{\"content\":\" back.<|end|><|start|>assistant\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{\"content\":\"<|channel|>final<|message|>Hi!\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n",
+    );
+
+    let events = run_stream_for_provider(
+        sse.as_bytes(),
+        BUILT_IN_OSS_MODEL_PROVIDER_ID,
+        Some("gpt-oss:20b"),
+    )
+    .await;
+    let mut reasoning = String::new();
+    let mut text = String::new();
+    for event in &events {
+        match event {
+            ResponseEvent::ReasoningContentDelta(delta) => reasoning.push_str(delta),
+            ResponseEvent::OutputTextDelta(delta) => text.push_str(delta),
+            _ => {}
+        }
+    }
+    assert_eq!(reasoning, "Greet back.");
+    assert_eq!(text, "Hi!");
+    let done: Vec<&ResponseItem> = events
+        .iter()
+        .filter_map(|event| match event {
+            ResponseEvent::OutputItemDone(item) => Some(item),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(done.len(), 2, "unexpected events: {events:?}");
+    assert_reasoning(done[0], "Greet back.");
+    assert_message(done[1], "Hi!");
+}
+
+/// Dropping the stream, as interrupting a turn does, closes the connection so
+/// the server stops generating.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn dropping_the_stream_closes_the_connection() {
+    if network_disabled() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    // A server that streams one token and then keeps generating, reporting
+    // when the client hangs up.
+    let listener = match std::net::TcpListener::bind("127.0.0.1:0") {
+        Ok(listener) => listener,
+        Err(e) => panic!("failed to bind: {e}"),
+    };
+    let base_url = match listener.local_addr() {
+        Ok(addr) => format!("http://{addr}/v1"),
+        Err(e) => panic!("no local address: {e}"),
+    };
+    let (closed_tx, closed_rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let Ok((mut socket, _)) = listener.accept() else {
+            return;
+        };
+        let mut request = Vec::new();
+        let mut buf = [0u8; 4096];
+        while !String::from_utf8_lossy(&request).contains("\"role\"") {
+            match socket.read(&mut buf) {
+                Ok(0) | Err(_) => return,
+                Ok(n) => request.extend_from_slice(&buf[..n]),
+            }
+        }
+        let chunk = "data: {\"choices\":[{\"delta\":{\"content\":\"hi\"}}]}\n\n";
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ntransfer-encoding: chunked\r\n\r\n{:x}\r\n{chunk}\r\n",
+            chunk.len()
+        );
+        if socket.write_all(response.as_bytes()).is_err() {
+            return;
+        }
+        // Everything after the request is discarded; only the hang-up counts.
+        loop {
+            match socket.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+        }
+        let _ = closed_tx.send(());
+    });
+
+    let (client, _codex_home) = test_client(base_url, BUILT_IN_OSS_MODEL_PROVIDER_ID, None);
+    let mut stream = match client.stream(&hello_prompt()).await {
+        Ok(s) => s,
+        Err(e) => panic!("stream chat failed: {e}"),
+    };
+    assert_matches!(
+        stream.next().await,
+        Some(Ok(ResponseEvent::OutputTextDelta(_)))
+    );
+    drop(stream);
+
+    // Well before the idle timeout would end the request.
+    let closed = tokio::task::spawn_blocking(move || {
+        closed_rx.recv_timeout(std::time::Duration::from_secs(3))
+    })
+    .await;
+    assert_matches!(closed, Ok(Ok(())));
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn streams_reasoning_from_string_delta() {
     if network_disabled() {
@@ -495,6 +495,8 @@ fn scenarios() -> Vec<ScenarioSpec> {
     let workspace_write = |network_access| SandboxPolicy::WorkspaceWrite {
         writable_roots: vec![],
         network_access,
+        local_network_ports: vec![],
+        local_network_sockets: vec![],
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
     };
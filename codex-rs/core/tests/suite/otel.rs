@@ -1,3 +1,6 @@
+use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
+use codex_core::WireApi;
+use codex_protocol::config_types::OssBackend;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::Op;
@@ -17,6 +20,10 @@ use core_test_support::test_codex::test_codex;
 use core_test_support::wait_for_event_with_timeout;
 use std::time::Duration;
 use tracing_test::traced_test;
+use wiremock::Mock;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
 
 use core_test_support::responses::ev_local_shell_call;
 
@@ -464,6 +471,67 @@ async fn process_sse_emits_completed_telemetry() {
     });
 }
 
+#[tokio::test]
+#[traced_test]
+async fn chat_completions_of_the_oss_provider_emit_local_inference_telemetry() {
+    let server = start_mock_server().await;
+
+    let sse = concat!(
+        "data: {\"choices\":[{\"delta\":{\"content\":\"hi\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n",
+        "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":3,\"completion_tokens\":1,\"total_tokens\":4}}\n\n",
+        "data: [DONE]\n\n",
+    );
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_raw(sse, "text/event-stream"),
+        )
+        .mount(&server)
+        .await;
+
+    let TestCodex { codex, .. } = test_codex()
+        .with_config(|config| {
+            config.model_provider_id = BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+            config.model_provider.wire_api = WireApi::Chat;
+            config.oss.provider = Some(OssBackend::LmStudio);
+        })
+        .build(&server)
+        .await
+        .unwrap();
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await
+        .unwrap();
+
+    wait_for_event_with_timeout(
+        &codex,
+        |ev| matches!(ev, EventMsg::TaskComplete(_)),
+        Duration::from_secs(5),
+    )
+    .await;
+
+    logs_assert(|lines: &[&str]| {
+        lines
+            .iter()
+            .find(|line| {
+                line.contains("codex.local_inference")
+                    && line.contains("backend=lmstudio")
+                    && line.contains("success=true")
+                    && line.contains("output_tokens=1")
+            })
+            .map(|_| Ok(()))
+            .unwrap_or(Err("missing codex.local_inference telemetry".to_string()))
+    });
+}
+
 #[tokio::test]
 #[traced_test]
 async fn handle_response_item_records_tool_result_for_custom_tool_call() {
@@ -454,12 +454,15 @@ async fn overrides_turn_context_but_keeps_cached_prefix_and_key_constant() {
             sandbox_policy: Some(SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![writable.path().to_path_buf()],
                 network_access: true,
+                local_network_ports: vec![],
+                local_network_sockets: vec![],
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
             }),
             model: Some("o3".to_string()),
             effort: Some(Some(ReasoningEffort::High)),
             summary: Some(ReasoningSummary::Detailed),
+            oss_sampling: None,
         })
         .await
         .unwrap();
@@ -587,6 +590,8 @@ async fn per_turn_overrides_keep_cached_prefix_and_key_constant() {
             sandbox_policy: SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![writable.path().to_path_buf()],
                 network_access: true,
+                local_network_ports: vec![],
+                local_network_sockets: vec![],
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
             },
@@ -21,6 +21,21 @@ pub struct Cli {
     #[arg(long = "oss", default_value_t = false)]
     pub oss: bool,
 
+    /// Local server to expect with `--oss`, overriding detection from the
+    /// provider's base URL (config key: `oss.provider`).
+    #[arg(long = "oss-provider", value_enum, requires = "oss")]
+    pub oss_provider: Option<codex_common::OssBackendCliArg>,
+
+    /// Base URL of the server to use with `--oss` for this invocation, e.g.
+    /// `http://gpubox:1234/v1`. A warning is printed when it is not loopback.
+    #[arg(long = "oss-url", value_name = "URL", requires = "oss", value_parser = codex_core::parse_oss_base_url)]
+    pub oss_url: Option<String>,
+
+    /// Download the model with `--oss` when it is missing. Without this flag
+    /// a missing model fails the run before it starts.
+    #[arg(long = "oss-download", requires = "oss", default_value_t = false)]
+    pub oss_download: bool,
+
     /// Select the sandbox policy to use when executing model-generated shell
     /// commands.
     #[arg(long = "sandbox", short = 's', value_enum)]
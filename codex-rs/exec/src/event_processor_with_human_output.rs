@@ -4,6 +4,7 @@ use codex_core::config::Config;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::CloudReviewEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
@@ -157,7 +158,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
     fn process_event(&mut self, event: Event) -> CodexStatus {
         let Event { id: _, msg } = event;
         match msg {
-            EventMsg::Error(ErrorEvent { message }) => {
+            EventMsg::Error(ErrorEvent { message, .. }) => {
                 let prefix = "ERROR:".style(self.red);
                 ts_msg!(self, "{prefix} {message}");
             }
@@ -208,6 +209,26 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     message,
                 );
             }
+            EventMsg::CloudReview(CloudReviewEvent {
+                draft_model,
+                reviewer_model,
+                revised_message,
+            }) => match revised_message {
+                Some(message) => ts_msg!(
+                    self,
+                    "{}\n{}",
+                    format!("codex (revised by {reviewer_model}, drafted by {draft_model})")
+                        .style(self.italic)
+                        .style(self.magenta),
+                    message,
+                ),
+                None => ts_msg!(
+                    self,
+                    "{}",
+                    format!("approved by {reviewer_model} (drafted by {draft_model})")
+                        .style(self.dimmed)
+                ),
+            },
             EventMsg::ExecCommandBegin(ExecCommandBeginEvent { command, cwd, .. }) => {
                 eprint!(
                     "{}\n{} in {}",
@@ -511,6 +532,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             },
             EventMsg::ShutdownComplete => return CodexStatus::Shutdown,
             EventMsg::ConversationPath(_) => {}
+            EventMsg::RequestTiming(_) => {}
             EventMsg::UserMessage(_) => {}
             EventMsg::EnteredReviewMode(_) => {}
             EventMsg::ExitedReviewMode(_) => {}
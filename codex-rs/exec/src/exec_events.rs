@@ -31,6 +31,18 @@ pub enum ThreadEvent {
     /// Represents an unrecoverable error emitted directly by the event stream.
     #[serde(rename = "error")]
     Error(ThreadErrorEvent),
+    /// Progress of a local model download with `--oss`, emitted before the
+    /// thread starts.
+    #[serde(rename = "model_download.progress")]
+    ModelDownloadProgress(codex_protocol::oss::ModelDownloadProgress),
+    /// Something happened to the local server or its models with `--oss`.
+    #[serde(rename = "local_server.event")]
+    LocalServerEvent(LocalServerEvent),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+pub struct LocalServerEvent {
+    pub event: codex_protocol::oss::OssEvent,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
@@ -18,6 +18,7 @@ use codex_core::NewConversation;
 use codex_core::auth::enforce_login_restrictions;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
+use codex_core::config_types::OssVerbosity;
 use codex_core::features::Feature;
 use codex_core::git_info::get_git_repo_root;
 use codex_core::protocol::AskForApproval;
@@ -26,17 +27,26 @@ use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
 use codex_core::protocol::SessionSource;
 use codex_core::protocol::TaskCompleteEvent;
-use codex_ollama::DEFAULT_OSS_MODEL;
+#[cfg(feature = "oss")]
+use codex_core::protocol::TokenCountEvent;
+#[cfg(feature = "oss")]
+use codex_core::protocol::TokenUsage;
 use codex_protocol::config_types::SandboxMode;
+#[cfg(feature = "oss")]
+use codex_protocol::oss::ModelDownloadProgress;
+use codex_protocol::oss::OssEvent;
 use codex_protocol::user_input::UserInput;
 use event_processor_with_human_output::EventProcessorWithHumanOutput;
 use event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
+use exec_events::LocalServerEvent;
+use exec_events::ThreadEvent;
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use serde_json::Value;
 use std::io::IsTerminal;
 use std::io::Read;
 use std::path::PathBuf;
 use supports_color::Stream;
+use tokio::sync::broadcast;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
@@ -59,6 +69,10 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         images,
         model: model_cli_arg,
         oss,
+        oss_provider,
+        oss_url,
+        #[cfg_attr(not(feature = "oss"), allow(unused_variables))]
+        oss_download,
         config_profile,
         full_auto,
         dangerously_bypass_approvals_and_sandbox,
@@ -147,16 +161,10 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         sandbox_mode_cli_arg.map(Into::<SandboxMode>::into)
     };
 
-    // When using `--oss`, let the bootstrapper pick the model (defaulting to
-    // gpt-oss:20b) and ensure it is present locally. Also, force the built‑in
-    // `oss` model provider.
-    let model = if let Some(model) = model_cli_arg {
-        Some(model)
-    } else if oss {
-        Some(DEFAULT_OSS_MODEL.to_owned())
-    } else {
-        None // No model specified, will use the default.
-    };
+    // When using `--oss` without `-m`, the model comes from `oss.model` (or the
+    // default local model); see `Config::load_from_base_config_with_overrides`.
+    // Also force the built‑in `oss` model provider.
+    let model = model_cli_arg;
 
     let model_provider = if oss {
         Some(BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string())
@@ -181,6 +189,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         show_raw_agent_reasoning: oss.then_some(true),
         tools_web_search_request: None,
         additional_writable_roots: Vec::new(),
+        oss_provider: oss_provider.map(Into::into),
+        oss_base_url: oss_url,
     };
     // Parse `-c` overrides.
     let cli_kv_overrides = match config_overrides.parse_overrides() {
@@ -191,7 +201,43 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     };
 
-    let config = Config::load_with_cli_overrides(cli_kv_overrides, overrides).await?;
+    let config =
+        Config::load_with_cli_overrides(cli_kv_overrides.clone(), overrides.clone()).await?;
+    // A resumed `--oss` session continues on the model it ran on, unless `-m`
+    // picked one, so that the checks below prepare that model rather than
+    // the first turn failing against a server without it.
+    let config = match &command {
+        Some(ExecCommand::Resume(args)) if oss && overrides.model.is_none() => {
+            let recorded = match resolve_resume_path(&config, args).await? {
+                Some(path) => codex_core::RolloutRecorder::last_local_model(&path)
+                    .await
+                    .unwrap_or_else(|err| {
+                        error!("could not read the model of the resumed session: {err}");
+                        None
+                    }),
+                None => None,
+            };
+            match recorded {
+                Some(model) if model != config.model => {
+                    if config.oss.verbosity != OssVerbosity::Quiet {
+                        eprintln!(
+                            "Resuming on {model}, the model this session ran on; pass -m to use another."
+                        );
+                    }
+                    Config::load_with_cli_overrides(
+                        cli_kv_overrides,
+                        ConfigOverrides {
+                            model: Some(model),
+                            ..overrides
+                        },
+                    )
+                    .await?
+                }
+                _ => config,
+            }
+        }
+        _ => config,
+    };
     let approve_all_enabled = config.features.enabled(Feature::ApproveAll);
 
     if let Err(err) = enforce_login_restrictions(&config).await {
@@ -232,10 +278,47 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         )),
     };
 
+    #[cfg(not(feature = "oss"))]
+    if oss || config.oss.offline {
+        anyhow::bail!("codex was built without local backend support");
+    }
+    #[cfg(not(feature = "oss"))]
+    let mut oss_events = None;
+    #[cfg(feature = "oss")]
+    codex_ollama::ensure_offline_provider(&config)?;
+    #[cfg(feature = "oss")]
+    let mut oss_events = oss.then(codex_ollama::subscribe_oss_events);
+    #[cfg(feature = "oss")]
     if oss {
-        codex_ollama::ensure_oss_ready(&config)
-            .await
-            .map_err(|e| anyhow::anyhow!("OSS setup failed: {e}"))?;
+        codex_ollama::ensure_local_base_url(&config).await?;
+        if let Some(warning) = codex_ollama::remote_oss_url_warning(&config) {
+            eprintln!("Warning: {warning}");
+        }
+        // Check everything the run needs before doing any work. Each failure
+        // class gets its own exit code so CI can branch on it.
+        let mut capabilities = vec!["tools".to_string()];
+        if !images.is_empty() {
+            capabilities.push("vision".to_string());
+        }
+        let requirements = codex_ollama::PreflightRequirements {
+            allow_download: oss_download,
+            capabilities,
+        };
+        let ready = if json_mode {
+            let mut reporter = codex_ollama::JsonProgressReporter::new(
+                config.model.clone(),
+                print_download_progress,
+            );
+            codex_ollama::preflight_oss(&config, &requirements, &mut reporter).await
+        } else {
+            let mut reporter = codex_ollama::CliProgressReporter::from_oss_config(&config.oss);
+            codex_ollama::preflight_oss(&config, &requirements, &mut reporter).await
+        };
+        report_oss_events(&mut oss_events, json_mode, config.oss.verbosity);
+        if let Err(err) = ready {
+            eprintln!("OSS setup failed: {err}");
+            std::process::exit(err.exit_code());
+        }
     }
 
     let default_cwd = config.cwd.to_path_buf();
@@ -361,10 +444,37 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     // Run the loop until the task is complete.
     // Track whether a fatal error was reported by the server so we can
     // exit with a non-zero status for automation-friendly signaling.
-    let mut error_seen = false;
+    let mut last_error: Option<String> = None;
+    #[cfg(feature = "oss")]
+    let performance_recorder = oss
+        .then(|| codex_ollama::PerformanceRecorder::from_config(&config))
+        .flatten();
+    #[cfg(feature = "oss")]
+    let energy_meter = oss.then(|| codex_ollama::EnergyMeter::from_config(&config));
+    #[cfg(feature = "oss")]
+    let mut token_usage = TokenUsage::default();
     while let Some(event) = rx.recv().await {
-        if matches!(event.msg, EventMsg::Error(_)) {
-            error_seen = true;
+        if let EventMsg::Error(error) = &event.msg {
+            last_error = Some(error.message.clone());
+        }
+        #[cfg(feature = "oss")]
+        {
+            if let (Some(recorder), EventMsg::RequestTiming(timing)) =
+                (&performance_recorder, &event.msg)
+            {
+                recorder.record(&config.model, timing).await;
+            }
+            match &event.msg {
+                EventMsg::RequestTiming(timing) => {
+                    if let Some(meter) = &energy_meter {
+                        meter.record(timing);
+                    }
+                }
+                EventMsg::TokenCount(TokenCountEvent {
+                    info: Some(info), ..
+                }) => token_usage = info.total_token_usage.clone(),
+                _ => {}
+            }
         }
         // Auto-approve requests when the approve_all feature is enabled.
         if approve_all_enabled {
@@ -394,6 +504,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
                 _ => {}
             }
         }
+        report_oss_events(&mut oss_events, json_mode, config.oss.verbosity);
         let shutdown: CodexStatus = event_processor.process_event(event);
         match shutdown {
             CodexStatus::Running => continue,
@@ -406,13 +517,91 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     }
     event_processor.print_final_output();
-    if error_seen {
+    #[cfg(feature = "oss")]
+    if let Some(energy) = energy_meter.and_then(|meter| meter.finish(&token_usage))
+        && !json_mode
+        && config.oss.verbosity != OssVerbosity::Quiet
+    {
+        eprintln!("{}", codex_ollama::format_session_energy(&energy));
+    }
+    #[cfg(feature = "oss")]
+    if oss {
+        codex_ollama::shutdown_local_provider(&config).await;
+        report_oss_events(&mut oss_events, json_mode, config.oss.verbosity);
+    }
+    if let Some(message) = last_error {
+        #[cfg(feature = "oss")]
+        if oss {
+            std::process::exit(codex_ollama::OssError::GenerationFailed(message).exit_code());
+        }
         std::process::exit(1);
     }
 
     Ok(())
 }
 
+/// Write one `model_download.progress` line of the `--json` stream.
+#[cfg(feature = "oss")]
+#[allow(clippy::print_stdout)]
+fn print_download_progress(progress: &ModelDownloadProgress) -> std::io::Result<()> {
+    let event = ThreadEvent::ModelDownloadProgress(progress.clone());
+    let line = serde_json::to_string(&event).map_err(std::io::Error::other)?;
+    println!("{line}");
+    Ok(())
+}
+
+/// Report the [`OssEvent`]s published since the last call: as
+/// `local_server.event` lines with `--json`, otherwise on stderr. Download
+/// progress is left out, as the preflight reporter already shows it.
+#[allow(clippy::print_stdout)]
+fn report_oss_events(
+    events: &mut Option<broadcast::Receiver<OssEvent>>,
+    json_mode: bool,
+    verbosity: OssVerbosity,
+) {
+    let Some(events) = events else {
+        return;
+    };
+    loop {
+        let event = match events.try_recv() {
+            Ok(OssEvent::DownloadProgress(_)) => continue,
+            Ok(event) => event,
+            Err(broadcast::error::TryRecvError::Lagged(_)) => continue,
+            Err(_) => return,
+        };
+        if json_mode {
+            let line = ThreadEvent::LocalServerEvent(LocalServerEvent { event });
+            match serde_json::to_string(&line) {
+                Ok(line) => println!("{line}"),
+                Err(err) => error!("failed to serialize local server event: {err}"),
+            }
+        } else if let Some(message) = oss_event_message(&event, verbosity) {
+            eprintln!("{message}");
+        }
+    }
+}
+
+/// Line shown for `event` without `--json`. Downloads are already shown by
+/// the progress reporter, and detection only when verbose.
+fn oss_event_message(event: &OssEvent, verbosity: OssVerbosity) -> Option<String> {
+    if verbosity == OssVerbosity::Quiet {
+        return None;
+    }
+    match event {
+        OssEvent::ServerDetected { backend, base_url } => (verbosity == OssVerbosity::Verbose)
+            .then(|| format!("Found the {backend} server at {base_url}")),
+        OssEvent::ServerStarted { backend, base_url } => {
+            Some(format!("Started the {backend} server at {base_url}"))
+        }
+        OssEvent::ModelLoaded { model } => Some(format!("Loaded {model}")),
+        OssEvent::ModelUnloaded { model } => Some(format!("Unloaded {model}")),
+        OssEvent::BackendUnhealthy { message, .. } => Some(format!("Warning: {message}")),
+        OssEvent::DownloadStarted { .. }
+        | OssEvent::DownloadProgress(_)
+        | OssEvent::DownloadFinished { .. } => None,
+    }
+}
+
 async fn resolve_resume_path(
     config: &Config,
     args: &crate::cli::ResumeArgs,
@@ -20,6 +20,7 @@ use codex_exec::exec_events::CommandExecutionStatus;
 use codex_exec::exec_events::ItemCompletedEvent;
 use codex_exec::exec_events::ItemStartedEvent;
 use codex_exec::exec_events::ItemUpdatedEvent;
+use codex_exec::exec_events::LocalServerEvent;
 use codex_exec::exec_events::McpToolCallItem;
 use codex_exec::exec_events::McpToolCallStatus;
 use codex_exec::exec_events::PatchApplyStatus;
@@ -434,6 +435,7 @@ fn error_event_produces_error() {
         "e1",
         EventMsg::Error(codex_core::protocol::ErrorEvent {
             message: "boom".to_string(),
+            connection_lost: false,
         }),
     ));
     assert_eq!(
@@ -469,6 +471,7 @@ fn error_followed_by_task_complete_produces_turn_failed() {
         "e1",
         EventMsg::Error(ErrorEvent {
             message: "boom".to_string(),
+            connection_lost: false,
         }),
     );
     assert_eq!(
@@ -815,3 +818,46 @@ fn task_complete_produces_turn_completed_with_usage() {
         })]
     );
 }
+
+#[test]
+fn model_download_progress_serializes_with_type_tag() {
+    let event = ThreadEvent::ModelDownloadProgress(codex_protocol::oss::ModelDownloadProgress {
+        model: "gpt-oss:20b".to_string(),
+        phase: codex_protocol::oss::DownloadPhase::Downloading,
+        status: None,
+        completed_bytes: Some(10),
+        total_bytes: Some(100),
+        bytes_per_second: Some(5),
+    });
+    assert_eq!(
+        serde_json::to_value(&event).expect("serialize"),
+        serde_json::json!({
+            "type": "model_download.progress",
+            "model": "gpt-oss:20b",
+            "phase": "downloading",
+            "status": null,
+            "completed_bytes": 10,
+            "total_bytes": 100,
+            "bytes_per_second": 5,
+        })
+    );
+}
+
+#[test]
+fn local_server_event_serializes_with_type_tag() {
+    let event = ThreadEvent::LocalServerEvent(LocalServerEvent {
+        event: codex_protocol::oss::OssEvent::ModelLoaded {
+            model: "gpt-oss:20b".to_string(),
+        },
+    });
+    assert_eq!(
+        serde_json::to_value(&event).expect("serialize"),
+        serde_json::json!({
+            "type": "local_server.event",
+            "event": {
+                "type": "model_loaded",
+                "model": "gpt-oss:20b",
+            },
+        })
+    );
+}
@@ -3,6 +3,9 @@ mod apply_patch;
 mod approve_all;
 mod auth_env;
 mod originator;
+#[cfg(feature = "oss")]
+mod oss;
+mod oss_docker;
 mod output_schema;
 mod resume;
 mod sandbox;
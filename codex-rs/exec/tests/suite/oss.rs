@@ -0,0 +1,72 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use codex_ollama::test_util::FakeLocalServer;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex_exec::test_codex_exec;
+use pretty_assertions::assert_eq;
+
+/// A whole `--oss` run against LM Studio: preflight finds the model, the turn
+/// streams the local model's reply, and the model is loaded on first use.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn oss_run_answers_with_the_local_model() {
+    skip_if_no_network!();
+
+    let test = test_codex_exec();
+    let server = FakeLocalServer::start(&["qwen3-8b"])
+        .await
+        .with_reply(&["Hi", " there"]);
+    let last_message = test.cwd_path().join("last-message.txt");
+
+    test.cmd()
+        .arg("--skip-git-repo-check")
+        .arg("--oss")
+        .arg("--oss-provider")
+        .arg("lmstudio")
+        .arg("--oss-url")
+        .arg(server.base_url())
+        .arg("-m")
+        .arg("qwen3-8b")
+        .arg("-o")
+        .arg(&last_message)
+        .arg("say hi")
+        .assert()
+        .success();
+
+    assert_eq!(
+        std::fs::read_to_string(&last_message).expect("read last message"),
+        "Hi there"
+    );
+    assert_eq!(server.loaded_models(), vec!["qwen3-8b".to_string()]);
+}
+
+/// A model the server does not have fails preflight with its own exit code
+/// before any turn runs.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn oss_run_fails_fast_when_the_model_is_missing() {
+    skip_if_no_network!();
+
+    let test = test_codex_exec();
+    let server = FakeLocalServer::start(&["qwen3-8b"]).await;
+
+    test.cmd()
+        .arg("--skip-git-repo-check")
+        .arg("--oss")
+        .arg("--oss-provider")
+        .arg("lmstudio")
+        .arg("--oss-url")
+        .arg(server.base_url())
+        .arg("-m")
+        .arg("gpt-oss:20b")
+        .arg("say hi")
+        .assert()
+        .code(11);
+
+    let chats = server
+        .received_requests()
+        .await
+        .into_iter()
+        .filter(|request| request.url.path() == "/v1/chat/completions")
+        .count();
+    assert_eq!(chats, 0);
+}
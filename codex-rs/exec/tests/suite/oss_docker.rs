@@ -0,0 +1,151 @@
+//! End-to-end `--oss` runs against a real Ollama server in a Docker
+//! container, built with `--features docker-tests`:
+//!
+//! ```shell
+//! cargo test -p codex-exec --features docker-tests oss_docker
+//! ```
+//!
+//! Each test starts its own container and pulls a tiny model into it, so
+//! the first run downloads the image and the model. Set
+//! `CODEX_DOCKER_TEST_IMAGE` or `CODEX_DOCKER_TEST_MODEL` to use others; the
+//! model must support tool calls.
+#![cfg(all(feature = "docker-tests", not(target_os = "windows")))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use std::process::Command;
+use std::time::Duration;
+use std::time::Instant;
+
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex_exec::test_codex_exec;
+
+const DEFAULT_IMAGE: &str = "ollama/ollama:latest";
+const DEFAULT_MODEL: &str = "qwen2.5:0.5b";
+const OLLAMA_PORT: &str = "11434/tcp";
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// An Ollama container, removed when dropped.
+struct OllamaContainer {
+    id: String,
+    host_root: String,
+}
+
+impl OllamaContainer {
+    fn start() -> Self {
+        let image =
+            std::env::var("CODEX_DOCKER_TEST_IMAGE").unwrap_or_else(|_| DEFAULT_IMAGE.to_string());
+        let id = docker(&["run", "--detach", "--publish", "127.0.0.1::11434", &image]);
+        // `docker port` prints one line per address, e.g. "127.0.0.1:49153".
+        let address = docker(&["port", &id, OLLAMA_PORT]);
+        let address = address.lines().next().expect("published port").to_string();
+        let container = Self {
+            id,
+            host_root: format!("http://{address}"),
+        };
+        container.wait_until_ready();
+        container
+    }
+
+    fn base_url(&self) -> String {
+        format!("{}/v1", self.host_root)
+    }
+
+    fn wait_until_ready(&self) {
+        let started = Instant::now();
+        while started.elapsed() < STARTUP_TIMEOUT {
+            let ready = Command::new("docker")
+                .args(["exec", &self.id, "ollama", "list"])
+                .output()
+                .is_ok_and(|output| output.status.success());
+            if ready {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        }
+        panic!("ollama in container {} did not start", self.id);
+    }
+}
+
+impl Drop for OllamaContainer {
+    fn drop(&mut self) {
+        let _ = Command::new("docker")
+            .args(["rm", "--force", &self.id])
+            .output();
+    }
+}
+
+/// Run `docker` with `args`, returning its trimmed stdout.
+fn docker(args: &[&str]) -> String {
+    let output = Command::new("docker")
+        .args(args)
+        .output()
+        .expect("run docker");
+    assert!(
+        output.status.success(),
+        "`docker {}` failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn model() -> String {
+    std::env::var("CODEX_DOCKER_TEST_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string())
+}
+
+/// Run `codex exec --oss` with `provider` against `container`, returning the
+/// last message.
+fn run_exec(container: &OllamaContainer, provider: &str, download: bool) -> String {
+    let test = test_codex_exec();
+    let last_message = test.cwd_path().join("last-message.txt");
+    let mut cmd = test.cmd();
+    cmd.arg("--skip-git-repo-check")
+        .arg("--oss")
+        .arg("--oss-provider")
+        .arg(provider)
+        .arg("--oss-url")
+        .arg(container.base_url())
+        .arg("-m")
+        .arg(model());
+    if download {
+        cmd.arg("--oss-download");
+    }
+    cmd.arg("-o")
+        .arg(&last_message)
+        .arg("Reply with the single word: pong")
+        .timeout(Duration::from_secs(600))
+        .assert()
+        .success();
+    std::fs::read_to_string(&last_message).expect("read last message")
+}
+
+/// Preflight pulls the missing model, the turn streams a real reply, and the
+/// model shows up among the server's models afterwards.
+#[test]
+fn ollama_run_pulls_the_model_and_answers() {
+    skip_if_no_network!();
+
+    let container = OllamaContainer::start();
+    let reply = run_exec(&container, "ollama", true);
+
+    assert!(!reply.trim().is_empty(), "empty reply from {}", model());
+    let models = docker(&["exec", &container.id, "ollama", "list"]);
+    assert!(
+        models.contains(&model()),
+        "{} not pulled: {models}",
+        model()
+    );
+}
+
+/// The same server driven through its OpenAI-compatible API alone, as
+/// `oss.provider = "generic"` does for llama-server and vLLM.
+#[test]
+fn generic_run_answers_through_the_openai_api() {
+    skip_if_no_network!();
+
+    let container = OllamaContainer::start();
+    docker(&["exec", &container.id, "ollama", "pull", &model()]);
+    let reply = run_exec(&container, "generic", false);
+
+    assert!(!reply.trim().is_empty(), "empty reply from {}", model());
+}
@@ -32,6 +32,10 @@ pub(crate) fn apply_sandbox_policy_to_current_thread(
     cwd: &Path,
 ) -> Result<()> {
     if !sandbox_policy.has_full_network_access() {
+        // `local_network_ports` and `local_network_sockets` are not honored
+        // here: seccomp cannot see where a socket connects to, and Landlock
+        // matches TCP ports but not addresses, so opening a port to the
+        // local server would open it to every host.
         install_network_seccomp_filter_on_current_thread()?;
     }
 
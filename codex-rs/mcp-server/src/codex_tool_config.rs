@@ -159,6 +159,8 @@ impl CodexToolCallParam {
             show_raw_agent_reasoning: None,
             tools_web_search_request: None,
             additional_writable_roots: Vec::new(),
+            oss_provider: None,
+            oss_base_url: None,
         };
 
         let cli_overrides = cli_overrides
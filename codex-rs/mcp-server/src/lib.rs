@@ -24,6 +24,8 @@ mod codex_tool_config;
 mod codex_tool_runner;
 mod error_code;
 mod exec_approval;
+#[cfg(feature = "oss")]
+mod local_model_tools;
 pub(crate) mod message_processor;
 mod outgoing_message;
 mod patch_approval;
@@ -36,6 +38,8 @@ pub use crate::codex_tool_config::CodexToolCallParam;
 pub use crate::codex_tool_config::CodexToolCallReplyParam;
 pub use crate::exec_approval::ExecApprovalElicitRequestParams;
 pub use crate::exec_approval::ExecApprovalResponse;
+#[cfg(feature = "oss")]
+pub use crate::local_model_tools::ModelDownloadElicitRequestParams;
 pub use crate::patch_approval::PatchApprovalElicitRequestParams;
 pub use crate::patch_approval::PatchApprovalResponse;
 
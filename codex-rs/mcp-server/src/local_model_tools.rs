@@ -0,0 +1,376 @@
+//! Tools that manage the models of the local `oss` server, so that an agent
+//! or another MCP client can provision the model a task needs. Downloads only
+//! start once the client approves them through an elicitation.
+
+use std::io;
+use std::sync::Arc;
+
+use codex_core::config::Config;
+use codex_core::protocol::ReviewDecision;
+use codex_core::protocol_config_types::OssBackend;
+use codex_ollama::OllamaClient;
+use codex_ollama::PullEvent;
+use codex_ollama::PullProgressReporter;
+use codex_ollama::RegistryClient;
+use codex_ollama::contains_model;
+use codex_ollama::format_size;
+use mcp_types::CallToolResult;
+use mcp_types::ContentBlock;
+use mcp_types::ElicitRequest;
+use mcp_types::ElicitRequestParamsRequestedSchema;
+use mcp_types::ModelContextProtocolRequest;
+use mcp_types::RequestId;
+use mcp_types::TextContent;
+use mcp_types::Tool;
+use mcp_types::ToolAnnotations;
+use mcp_types::ToolInputSchema;
+use schemars::JsonSchema;
+use schemars::r#gen::SchemaSettings;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::exec_approval::ExecApprovalResponse;
+use crate::outgoing_message::OutgoingMessageSender;
+
+pub(crate) const LIST_TOOL: &str = "local-models-list";
+pub(crate) const PULL_TOOL: &str = "local-models-pull";
+pub(crate) const LOAD_TOOL: &str = "local-models-load";
+pub(crate) const UNLOAD_TOOL: &str = "local-models-unload";
+pub(crate) const STATUS_TOOL: &str = "local-models-status";
+
+/// Arguments of the tools that act on one model.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LocalModelParam {
+    /// Model name as the local server knows it, e.g. "gpt-oss:20b".
+    pub model: String,
+}
+
+/// Arguments of `local-models-unload`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LocalModelUnloadParam {
+    /// Model to unload; all loaded models when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// Arguments of the tools that take none.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LocalModelNoParam {}
+
+/// Conforms to [`mcp_types::ElicitRequestParams`] so that it can be used as
+/// the `params` field of an [`ElicitRequest`] asking to approve a download.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ModelDownloadElicitRequestParams {
+    pub message: String,
+
+    #[serde(rename = "requestedSchema")]
+    pub requested_schema: ElicitRequestParamsRequestedSchema,
+
+    pub codex_elicitation: String,
+    pub codex_mcp_tool_call_id: String,
+    pub codex_model: String,
+    /// Bytes the download needs, when the registry reports it.
+    pub codex_download_bytes: Option<u64>,
+}
+
+pub(crate) fn is_local_model_tool(name: &str) -> bool {
+    [LIST_TOOL, PULL_TOOL, LOAD_TOOL, UNLOAD_TOOL, STATUS_TOOL].contains(&name)
+}
+
+/// Definitions of the local model tools, for `tools/list`.
+pub(crate) fn create_local_model_tools() -> Vec<Tool> {
+    vec![
+        tool::<LocalModelNoParam>(
+            LIST_TOOL,
+            "List Local Models",
+            "List the models the local oss server has downloaded, with their size and whether they are loaded.",
+            true,
+        ),
+        tool::<LocalModelParam>(
+            PULL_TOOL,
+            "Download Local Model",
+            "Download a model to the local oss server (Ollama only). The client is asked to approve the download first.",
+            false,
+        ),
+        tool::<LocalModelParam>(
+            LOAD_TOOL,
+            "Load Local Model",
+            "Load a downloaded model into memory so that its first request does not wait for it.",
+            false,
+        ),
+        tool::<LocalModelUnloadParam>(
+            UNLOAD_TOOL,
+            "Unload Local Model",
+            "Free the memory of a loaded model, or of all loaded models.",
+            false,
+        ),
+        tool::<LocalModelNoParam>(
+            STATUS_TOOL,
+            "Local Server Status",
+            "Report whether the local oss server runs, its version, and which models are loaded.",
+            true,
+        ),
+    ]
+}
+
+fn tool<T: JsonSchema>(name: &str, title: &str, description: &str, read_only: bool) -> Tool {
+    let schema = SchemaSettings::draft2019_09()
+        .with(|s| {
+            s.inline_subschemas = true;
+            s.option_add_null_type = false;
+        })
+        .into_generator()
+        .into_root_schema_for::<T>();
+
+    #[expect(clippy::expect_used)]
+    let schema_value =
+        serde_json::to_value(&schema).expect("local model tool schema should serialise to JSON");
+
+    let input_schema =
+        serde_json::from_value::<ToolInputSchema>(schema_value).unwrap_or_else(|e| {
+            panic!("failed to create Tool from schema: {e}");
+        });
+
+    Tool {
+        name: name.to_string(),
+        title: Some(title.to_string()),
+        input_schema,
+        output_schema: None,
+        description: Some(description.to_string()),
+        annotations: Some(ToolAnnotations {
+            destructive_hint: None,
+            idempotent_hint: Some(true),
+            open_world_hint: None,
+            read_only_hint: Some(read_only),
+            title: None,
+        }),
+    }
+}
+
+/// Run the local model tool `name` and return its result. Failures are
+/// reported as error results, never as protocol errors.
+pub(crate) async fn handle_local_model_tool(
+    id: RequestId,
+    name: &str,
+    arguments: Option<serde_json::Value>,
+    config: Arc<Config>,
+    outgoing: Arc<OutgoingMessageSender>,
+) -> CallToolResult {
+    let arguments = arguments.unwrap_or_else(|| json!({}));
+    let result = match name {
+        LIST_TOOL => codex_ollama::list_local_models(&config)
+            .await
+            .and_then(to_json),
+        STATUS_TOOL => status(&config).await,
+        PULL_TOOL => match parse::<LocalModelParam>(arguments) {
+            Ok(LocalModelParam { model }) => pull(&config, &model, id, &outgoing).await,
+            Err(err) => Err(err),
+        },
+        LOAD_TOOL => match parse::<LocalModelParam>(arguments) {
+            Ok(LocalModelParam { model }) => codex_ollama::load_model(&config, &model)
+                .await
+                .map(|()| json!({"loaded": model})),
+            Err(err) => Err(err),
+        },
+        UNLOAD_TOOL => match parse::<LocalModelUnloadParam>(arguments) {
+            Ok(LocalModelUnloadParam { model }) => {
+                codex_ollama::unload_models(&config, model.as_deref())
+                    .await
+                    .map(|unloaded| json!({"unloaded": unloaded}))
+            }
+            Err(err) => Err(err),
+        },
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Unknown tool '{name}'"),
+        )),
+    };
+    match result {
+        Ok(value) => CallToolResult {
+            content: vec![text(value.to_string())],
+            is_error: None,
+            structured_content: Some(value),
+        },
+        Err(err) => CallToolResult {
+            content: vec![text(err.to_string())],
+            is_error: Some(true),
+            structured_content: None,
+        },
+    }
+}
+
+async fn status(config: &Config) -> io::Result<serde_json::Value> {
+    let client = OllamaClient::from_oss_provider(config)?;
+    let backend = config.oss.provider.unwrap_or(OssBackend::Ollama);
+    let status =
+        codex_ollama::runtime_status(&client, backend, &codex_ollama::oss_state_dir(config))
+            .await?;
+    to_json(status)
+}
+
+async fn pull(
+    config: &Config,
+    model: &str,
+    id: RequestId,
+    outgoing: &OutgoingMessageSender,
+) -> io::Result<serde_json::Value> {
+    if !matches!(config.oss.provider, Some(OssBackend::Ollama) | None) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Only Ollama can download models on request; download it in the server's own app.",
+        ));
+    }
+    let client = OllamaClient::try_from_oss_provider(config).await?;
+    if contains_model(&client.fetch_models().await?, model) {
+        return Ok(json!({"model": model, "downloaded": false}));
+    }
+    if config.oss.offline {
+        return Err(codex_ollama::offline_error("Downloading models"));
+    }
+    let size = RegistryClient::new().fetch_download_size(model).await.ok();
+    if !approve_download(model, size, id, outgoing).await {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("The download of '{model}' was not approved."),
+        ));
+    }
+    client
+        .pull_with_reporter(model, &mut SilentReporter)
+        .await?;
+    Ok(json!({"model": model, "downloaded": true}))
+}
+
+/// Ask the client whether `model` may be downloaded. Anything but an
+/// approval, including no answer, declines.
+async fn approve_download(
+    model: &str,
+    size: Option<u64>,
+    id: RequestId,
+    outgoing: &OutgoingMessageSender,
+) -> bool {
+    let params = ModelDownloadElicitRequestParams {
+        message: download_message(model, size),
+        requested_schema: ElicitRequestParamsRequestedSchema {
+            r#type: "object".to_string(),
+            properties: json!({}),
+            required: None,
+        },
+        codex_elicitation: "model-download-approval".to_string(),
+        codex_mcp_tool_call_id: request_id_string(&id),
+        codex_model: model.to_string(),
+        codex_download_bytes: size,
+    };
+    let Ok(params) = serde_json::to_value(&params) else {
+        return false;
+    };
+    let on_response = outgoing
+        .send_request(ElicitRequest::METHOD, Some(params))
+        .await;
+    let Ok(value) = on_response.await else {
+        return false;
+    };
+    matches!(
+        serde_json::from_value::<ExecApprovalResponse>(value),
+        Ok(ExecApprovalResponse {
+            decision: ReviewDecision::Approved | ReviewDecision::ApprovedForSession,
+        })
+    )
+}
+
+fn download_message(model: &str, size: Option<u64>) -> String {
+    match size {
+        Some(bytes) => format!(
+            "Allow Codex to download the local model `{model}` ({})?",
+            format_size(bytes)
+        ),
+        None => format!("Allow Codex to download the local model `{model}`?"),
+    }
+}
+
+fn request_id_string(id: &RequestId) -> String {
+    match id {
+        RequestId::String(id) => id.clone(),
+        RequestId::Integer(id) => id.to_string(),
+    }
+}
+
+/// Downloads run without a terminal to show their progress on.
+struct SilentReporter;
+
+impl PullProgressReporter for SilentReporter {
+    fn on_event(&mut self, _event: &PullEvent) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn parse<T: serde::de::DeserializeOwned>(arguments: serde_json::Value) -> io::Result<T> {
+    serde_json::from_value(arguments).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid arguments: {err}"),
+        )
+    })
+}
+
+fn to_json(value: impl Serialize) -> io::Result<serde_json::Value> {
+    serde_json::to_value(value).map_err(io::Error::other)
+}
+
+fn text(text: String) -> ContentBlock {
+    ContentBlock::TextContent(TextContent {
+        r#type: "text".to_string(),
+        text,
+        annotations: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn only_downloads_need_an_argument_and_change_no_state_unasked() {
+        let tools = create_local_model_tools();
+        let summary: Vec<_> = tools
+            .iter()
+            .map(|tool| {
+                (
+                    tool.name.as_str(),
+                    tool.input_schema.required.clone().unwrap_or_default(),
+                    tool.annotations
+                        .as_ref()
+                        .and_then(|annotations| annotations.read_only_hint),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (LIST_TOOL, vec![], Some(true)),
+                (PULL_TOOL, vec!["model".to_string()], Some(false)),
+                (LOAD_TOOL, vec!["model".to_string()], Some(false)),
+                (UNLOAD_TOOL, vec![], Some(false)),
+                (STATUS_TOOL, vec![], Some(true)),
+            ]
+        );
+        assert!(tools.iter().all(|tool| is_local_model_tool(&tool.name)));
+        assert!(!is_local_model_tool("codex"));
+    }
+
+    #[test]
+    fn download_approvals_name_the_model_and_its_size() {
+        assert_eq!(
+            download_message("gpt-oss:20b", Some(13_780_000_000)),
+            format!(
+                "Allow Codex to download the local model `gpt-oss:20b` ({})?",
+                format_size(13_780_000_000)
+            )
+        );
+        assert_eq!(
+            download_message("qwen3:8b", None),
+            "Allow Codex to download the local model `qwen3:8b`?"
+        );
+    }
+}
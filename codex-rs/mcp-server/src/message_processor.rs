@@ -6,6 +6,12 @@ use crate::codex_tool_config::CodexToolCallReplyParam;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_param;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_reply_param;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+#[cfg(feature = "oss")]
+use crate::local_model_tools::create_local_model_tools;
+#[cfg(feature = "oss")]
+use crate::local_model_tools::handle_local_model_tool;
+#[cfg(feature = "oss")]
+use crate::local_model_tools::is_local_model_tool;
 use crate::outgoing_message::OutgoingMessageSender;
 use codex_protocol::ConversationId;
 use codex_protocol::protocol::SessionSource;
@@ -40,6 +46,7 @@ pub(crate) struct MessageProcessor {
     outgoing: Arc<OutgoingMessageSender>,
     initialized: bool,
     codex_linux_sandbox_exe: Option<PathBuf>,
+    config: Arc<Config>,
     conversation_manager: Arc<ConversationManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
 }
@@ -60,6 +67,7 @@ impl MessageProcessor {
             outgoing,
             initialized: false,
             codex_linux_sandbox_exe,
+            config,
             conversation_manager,
             running_requests_id_to_codex_uuid: Arc::new(Mutex::new(HashMap::new())),
         }
@@ -298,11 +306,15 @@ impl MessageProcessor {
         params: <mcp_types::ListToolsRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::trace!("tools/list -> {params:?}");
+        #[cfg_attr(not(feature = "oss"), allow(unused_mut))]
+        let mut tools = vec![
+            create_tool_for_codex_tool_call_param(),
+            create_tool_for_codex_tool_call_reply_param(),
+        ];
+        #[cfg(feature = "oss")]
+        tools.extend(create_local_model_tools());
         let result = ListToolsResult {
-            tools: vec![
-                create_tool_for_codex_tool_call_param(),
-                create_tool_for_codex_tool_call_reply_param(),
-            ],
+            tools,
             next_cursor: None,
         };
 
@@ -324,6 +336,10 @@ impl MessageProcessor {
                 self.handle_tool_call_codex_session_reply(id, arguments)
                     .await
             }
+            #[cfg(feature = "oss")]
+            name if is_local_model_tool(name) => {
+                self.handle_tool_call_local_model(id, name.to_string(), arguments)
+            }
             _ => {
                 let result = CallToolResult {
                     content: vec![ContentBlock::TextContent(TextContent {
@@ -339,6 +355,25 @@ impl MessageProcessor {
             }
         }
     }
+    #[cfg(feature = "oss")]
+    fn handle_tool_call_local_model(
+        &self,
+        id: RequestId,
+        name: String,
+        arguments: Option<serde_json::Value>,
+    ) {
+        let config = self.config.clone();
+        let outgoing = self.outgoing.clone();
+        // A download waits for the client to approve it, and the approval
+        // arrives through this loop, so the tool must not block it.
+        task::spawn(async move {
+            let result =
+                handle_local_model_tool(id.clone(), &name, arguments, config, outgoing.clone())
+                    .await;
+            outgoing.send_response(id, result).await;
+        });
+    }
+
     async fn handle_tool_call_codex(&self, id: RequestId, arguments: Option<serde_json::Value>) {
         let (initial_prompt, config): (String, Config) = match arguments {
             Some(json_val) => match serde_json::from_value::<CodexToolCallParam>(json_val) {
@@ -0,0 +1,116 @@
+//! Which HTTP APIs a server exposes, so code paths are picked by what the
+//! server answers rather than by the backend it is configured as: the
+//! OpenAI-compatible `/v1` serves inference, while LM Studio's `/api/v0`
+//! and Ollama's native `/api` carry model management and richer metadata.
+//!
+//! The answer is probed once per host root and kept for the process.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use crate::OllamaClient;
+
+/// The APIs a server answered on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ApiSurfaces {
+    /// The OpenAI-compatible API, `GET /v1/models`.
+    pub openai: bool,
+    /// LM Studio's REST API, `GET /api/v0/models`.
+    pub lmstudio_v0: bool,
+    /// Ollama's native API, `GET /api/version`.
+    pub ollama: bool,
+}
+
+impl ApiSurfaces {
+    fn any(&self) -> bool {
+        self.openai || self.lmstudio_v0 || self.ollama
+    }
+}
+
+static SURFACES: LazyLock<Mutex<HashMap<String, ApiSurfaces>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn cache_key(host_root: &str) -> String {
+    host_root.trim_end_matches('/').to_string()
+}
+
+impl OllamaClient {
+    /// The APIs the server exposes, probed on first use. A server that
+    /// answers on none of them is not cached, so it is probed again once it
+    /// is up.
+    pub async fn api_surfaces(&self) -> ApiSurfaces {
+        let key = cache_key(self.host_root());
+        if let Some(cached) = SURFACES
+            .lock()
+            .ok()
+            .and_then(|surfaces| surfaces.get(&key).copied())
+        {
+            return cached;
+        }
+        let (openai, lmstudio_v0, ollama) = tokio::join!(
+            self.answers_get("/v1/models"),
+            self.answers_get("/api/v0/models"),
+            self.answers_get("/api/version"),
+        );
+        let surfaces = ApiSurfaces {
+            openai,
+            lmstudio_v0,
+            ollama,
+        };
+        self.log(format_args!("{key} exposes {surfaces:?}"));
+        if surfaces.any()
+            && let Ok(mut cached) = SURFACES.lock()
+        {
+            cached.insert(key, surfaces);
+        }
+        surfaces
+    }
+}
+
+/// Drop what is known about the server at `host_root`, e.g. after starting
+/// it, since a different version may now answer there.
+pub(crate) fn forget_api_surfaces(host_root: &str) {
+    if let Ok(mut cached) = SURFACES.lock() {
+        cached.remove(&cache_key(host_root));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn surfaces_are_probed_once_per_server() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping surfaces_are_probed_once_per_server",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        for path in ["/v1/models", "/api/v0/models"] {
+            wiremock::Mock::given(wiremock::matchers::method("GET"))
+                .and(wiremock::matchers::path(path))
+                .respond_with(
+                    wiremock::ResponseTemplate::new(200)
+                        .set_body_json(serde_json::json!({"data": []})),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = OllamaClient::from_host_root(server.uri());
+        let expected = ApiSurfaces {
+            openai: true,
+            lmstudio_v0: true,
+            ollama: false,
+        };
+        assert_eq!(client.api_surfaces().await, expected);
+        assert_eq!(client.api_surfaces().await, expected);
+    }
+}
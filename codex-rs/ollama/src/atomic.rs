@@ -0,0 +1,135 @@
+//! Atomic writes of the files Codex keeps for the local provider.
+//!
+//! A file is written to a temporary sibling, flushed to disk, and renamed
+//! over the destination, so an interrupted write (a crash, Ctrl-C, a full
+//! disk) leaves the previous file or none, never a half-written one that
+//! reads as valid. Temporary files a killed process left behind are removed
+//! by [`remove_orphaned_temp_files`].
+
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::server::process_alive;
+
+const TEMP_SUFFIX: &str = ".tmp";
+
+/// Write `contents` to `path` atomically, creating its directory.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    std::fs::create_dir_all(dir)?;
+    let temp = temp_path(path)?;
+    let result = write_and_rename(&temp, path, contents.as_ref());
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result?;
+    sync_dir(dir)
+}
+
+fn write_and_rename(temp: &Path, path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut file = File::create(temp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    std::fs::rename(temp, path)
+}
+
+/// Remove temporary files in `dir` left behind by writes of processes that
+/// are no longer running. Returns how many were removed.
+pub fn remove_orphaned_temp_files(dir: &Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .and_then(temp_file_owner)
+                .is_some_and(|pid| !process_alive(pid))
+        })
+        .filter(|entry| std::fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+
+/// `.<name>.<pid>.tmp` next to `path`, so that writes by concurrent
+/// processes do not collide and orphans can be told from live writes.
+fn temp_path(path: &Path) -> io::Result<PathBuf> {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} has no file name", path.display()),
+            )
+        })?;
+    Ok(path.with_file_name(format!(".{name}.{}{TEMP_SUFFIX}", std::process::id())))
+}
+
+/// Pid of the process writing the temporary file `file_name`, if it is one.
+fn temp_file_owner(file_name: &str) -> Option<u32> {
+    let stem = file_name.strip_prefix('.')?.strip_suffix(TEMP_SUFFIX)?;
+    let (_, pid) = stem.rsplit_once('.')?;
+    pid.parse().ok()
+}
+
+/// Persist the rename itself; directories cannot be opened for syncing on
+/// Windows, where the rename is durable once it returns.
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn writes_replace_the_file_and_leave_no_temp_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("nested").join("model-usage.json");
+        write_atomic(&path, "first").expect("write");
+        write_atomic(&path, "second").expect("write");
+
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), "second");
+        let names: Vec<_> = std::fs::read_dir(path.parent().expect("parent"))
+            .expect("read dir")
+            .map(|entry| entry.expect("entry").file_name())
+            .collect();
+        assert_eq!(names, vec!["model-usage.json"]);
+    }
+
+    #[test]
+    fn only_temp_files_of_exited_processes_are_removed() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        // No real process will ever have this pid.
+        let orphan = dir
+            .path()
+            .join(format!(".model-usage.json.{}.tmp", u32::MAX));
+        let live = dir
+            .path()
+            .join(format!(".model-usage.json.{}.tmp", std::process::id()));
+        let unrelated = dir.path().join("notes.tmp");
+        for path in [&orphan, &live, &unrelated] {
+            std::fs::write(path, "partial").expect("write");
+        }
+
+        assert_eq!(remove_orphaned_temp_files(dir.path()), 1);
+        assert!(!orphan.exists());
+        assert!(live.exists());
+        assert!(unrelated.exists());
+    }
+}
@@ -0,0 +1,158 @@
+//! Append-only record of what the OSS path fetched and executed, kept with
+//! the other OSS state as `audit.jsonl` (see [`crate::paths`]).
+//!
+//! Each line is one [`AuditEntry`]: a model download with the layers and
+//! bytes it transferred, a model load, or a program Codex ran, such as
+//! `ollama serve` or `lms load` (see [`crate::command`]). Entries are only ever appended, so the file
+//! doubles as a history to review.
+
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+const AUDIT_FILE: &str = "audit.jsonl";
+
+/// One line of the audit log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub action: AuditAction,
+    /// Why the action failed; `None` when it succeeded.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum AuditAction {
+    /// A model pulled by the server at `host_root`.
+    Download {
+        model: String,
+        host_root: String,
+        /// Digests of the layers transferred, e.g. "sha256:b112e727…".
+        layers: Vec<String>,
+        bytes: u64,
+    },
+    /// A model loaded into the memory of the server at `host_root`.
+    Load { model: String, host_root: String },
+    /// A program Codex ran.
+    Command {
+        program: PathBuf,
+        args: Vec<String>,
+        /// Pid of a program left running in the background.
+        pid: Option<u32>,
+        exit_code: Option<i32>,
+    },
+}
+
+/// Path of the audit log for the OSS state in `state_dir`.
+pub fn audit_log_path(state_dir: &Path) -> PathBuf {
+    state_dir.join(AUDIT_FILE)
+}
+
+/// Every entry in the audit log at `path`, oldest first. Lines that do not
+/// parse, e.g. one cut short by a crash, are skipped.
+pub fn read_audit_log(path: &Path) -> io::Result<Vec<AuditEntry>> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Append `entry` to the audit log at `path`. Failing to write it is logged
+/// rather than failing the action it records.
+pub(crate) fn record(path: &Path, entry: &AuditEntry) {
+    if let Err(err) = append(path, entry) {
+        tracing::warn!("failed to write the audit log {}: {err}", path.display());
+    }
+}
+
+fn append(path: &Path, entry: &AuditEntry) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(entry).map_err(io::Error::other)?;
+    line.push('\n');
+    // One write per line keeps concurrent sessions from interleaving.
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn entry(action: AuditAction) -> AuditEntry {
+        let at = DateTime::parse_from_rfc3339("2025-10-01T12:00:00Z")
+            .expect("timestamp")
+            .with_timezone(&Utc);
+        AuditEntry {
+            started_at: at,
+            finished_at: at,
+            action,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn entries_are_appended_one_per_line() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = audit_log_path(&dir.path().join("oss"));
+        let download = entry(AuditAction::Download {
+            model: "gpt-oss:20b".to_string(),
+            host_root: "http://localhost:11434".to_string(),
+            layers: vec!["sha256:aa".to_string()],
+            bytes: 42,
+        });
+        let load = entry(AuditAction::Load {
+            model: "gpt-oss:20b".to_string(),
+            host_root: "http://localhost:11434".to_string(),
+        });
+        record(&path, &download);
+        record(&path, &load);
+
+        let contents = std::fs::read_to_string(&path).expect("read");
+        assert_eq!(contents.lines().count(), 2);
+        let first: serde_json::Value =
+            serde_json::from_str(contents.lines().next().expect("line")).expect("json");
+        assert_eq!(first["action"], "download");
+        assert_eq!(first["bytes"], 42);
+        assert_eq!(read_audit_log(&path).expect("read"), vec![download, load]);
+    }
+
+    #[test]
+    fn a_missing_log_is_empty_and_torn_lines_are_skipped() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = audit_log_path(dir.path());
+        assert_eq!(read_audit_log(&path).expect("read"), Vec::new());
+
+        let command = entry(AuditAction::Command {
+            program: PathBuf::from("/usr/local/bin/lms"),
+            args: vec!["load".to_string(), "qwen3-8b".to_string()],
+            pid: None,
+            exit_code: Some(0),
+        });
+        record(&path, &command);
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(b"{\"started_at\":"))
+            .expect("append torn line");
+        assert_eq!(read_audit_log(&path).expect("read"), vec![command]);
+    }
+}
@@ -0,0 +1,136 @@
+//! The operations Codex performs on a local model server, behind a trait so
+//! that callers, downstream crates, and tests can substitute another
+//! implementation for [`OllamaClient`].
+//! [`crate::ensure_oss_ready_with_backend`], [`crate::pull_model_with_backend`]
+//! and [`crate::load_model_with_backend`] run the startup, download and load
+//! flows against any implementation.
+
+use codex_protocol::oss::OssEvent;
+use std::io;
+
+use crate::GenerateOptions;
+use crate::ModelAnswer;
+use crate::OllamaClient;
+use crate::PullProgressReporter;
+use crate::contains_model;
+use crate::events::publish;
+use crate::load::load_lmstudio;
+use crate::unload::generic_unload_unsupported;
+use crate::unload::unload_lmstudio;
+
+/// A local server that serves models, such as Ollama or LM Studio.
+#[async_trait::async_trait]
+pub trait LocalModelBackend: Send + Sync {
+    /// Check that the server answers.
+    async fn probe(&self) -> io::Result<()>;
+
+    /// Names of the models the server can serve.
+    async fn list_models(&self) -> io::Result<Vec<String>>;
+
+    /// Make `model` available, downloading it when the server can pull
+    /// models. Fails with [`io::ErrorKind::NotFound`] when it cannot.
+    async fn ensure_model(
+        &self,
+        model: &str,
+        reporter: &mut (dyn PullProgressReporter + Send),
+    ) -> io::Result<()>;
+
+    /// A complete answer of `model` to `prompt`, without tools.
+    async fn chat(
+        &self,
+        model: &str,
+        prompt: &str,
+        max_tokens: Option<u64>,
+    ) -> io::Result<ModelAnswer>;
+
+    /// One embedding vector for each of `inputs`, in order.
+    async fn embed(&self, model: &str, inputs: &[String]) -> io::Result<Vec<Vec<f32>>>;
+
+    /// Load `model` into the server's memory ahead of a request. Returns
+    /// whether it is loaded afterwards; servers that only load models on
+    /// demand leave it alone.
+    async fn load(&self, model: &str) -> io::Result<bool>;
+
+    /// Evict `model` from the server's memory. Fails with
+    /// [`io::ErrorKind::Unsupported`] when the server cannot.
+    async fn unload(&self, model: &str) -> io::Result<()>;
+}
+
+#[async_trait::async_trait]
+impl LocalModelBackend for OllamaClient {
+    async fn probe(&self) -> io::Result<()> {
+        self.probe_server().await
+    }
+
+    async fn list_models(&self) -> io::Result<Vec<String>> {
+        if self.uses_openai_compat() {
+            self.fetch_openai_model_ids().await
+        } else {
+            self.fetch_models().await
+        }
+    }
+
+    async fn ensure_model(
+        &self,
+        model: &str,
+        reporter: &mut (dyn PullProgressReporter + Send),
+    ) -> io::Result<()> {
+        if contains_model(&self.list_models().await?, model) {
+            return Ok(());
+        }
+        // Only Ollama can download models on request.
+        if self.uses_openai_compat() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("model '{model}' is not available on the server"),
+            ));
+        }
+        self.pull_with_reporter(model, reporter).await
+    }
+
+    async fn chat(
+        &self,
+        model: &str,
+        prompt: &str,
+        max_tokens: Option<u64>,
+    ) -> io::Result<ModelAnswer> {
+        self.answer(model, prompt, max_tokens).await
+    }
+
+    async fn embed(&self, model: &str, inputs: &[String]) -> io::Result<Vec<Vec<f32>>> {
+        self.embeddings(model, inputs).await
+    }
+
+    async fn load(&self, model: &str) -> io::Result<bool> {
+        if self.is_lmstudio() {
+            load_lmstudio(self, model).await
+        } else if self.uses_openai_compat() {
+            Ok(false)
+        } else {
+            // Ollama loads a model for any request, so a single token is
+            // generated.
+            let options = GenerateOptions {
+                num_predict: Some(1),
+                ..Default::default()
+            };
+            self.generate_with_stats(model, "Hi", &options)
+                .await
+                .map(|_| true)
+        }
+    }
+
+    async fn unload(&self, model: &str) -> io::Result<()> {
+        if self.is_lmstudio() {
+            // LM Studio has no unload endpoint.
+            unload_lmstudio(self, Some(model)).await?;
+        } else if self.uses_openai_compat() {
+            return Err(generic_unload_unsupported());
+        } else {
+            self.unload_model(model).await?;
+        }
+        publish(OssEvent::ModelUnloaded {
+            model: model.to_string(),
+        });
+        Ok(())
+    }
+}
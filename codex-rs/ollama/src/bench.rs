@@ -0,0 +1,107 @@
+//! Throughput benchmark for a local model, used by `codex oss bench`.
+
+use codex_protocol::oss::BenchRun;
+use std::io;
+
+use crate::OllamaClient;
+use crate::generate::GenerateOptions;
+use crate::generate::GenerationStats;
+
+/// Prompt sizes, in approximate tokens, exercised by default.
+pub const DEFAULT_BENCH_CONTEXT_SIZES: &[u32] = &[512, 2048, 8192];
+
+/// Tokens to generate for each benchmark run by default.
+pub const DEFAULT_BENCH_MAX_TOKENS: u32 = 128;
+
+/// Extra room in the context window beyond the prompt so generation is not
+/// truncated.
+const CONTEXT_HEADROOM_TOKENS: u32 = 512;
+
+/// Rough characters-per-token ratio for English prose and code.
+const CHARS_PER_TOKEN: usize = 4;
+
+const FILLER: &str = "The build system compiles each crate in the workspace, \
+runs the unit tests, and reports any warnings emitted by the linter. ";
+
+const INSTRUCTION: &str = "\n\nSummarize the text above in one paragraph.";
+
+/// Result of benchmarking a single context size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchResult {
+    /// Requested prompt size, in approximate tokens.
+    pub context_size: u32,
+    pub stats: GenerationStats,
+}
+
+impl From<&BenchResult> for BenchRun {
+    fn from(result: &BenchResult) -> Self {
+        let stats = &result.stats;
+        Self {
+            context_size: result.context_size,
+            prompt_tokens: stats.prompt_tokens,
+            completion_tokens: stats.completion_tokens,
+            prefill_tokens_per_sec: stats.prefill_tokens_per_sec(),
+            generation_tokens_per_sec: stats.generation_tokens_per_sec(),
+            time_to_first_token_ms: stats
+                .time_to_first_token
+                .map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX)),
+        }
+    }
+}
+
+/// A deterministic prompt of roughly `approx_tokens` tokens.
+pub fn bench_prompt(approx_tokens: u32) -> String {
+    let target_chars = approx_tokens as usize * CHARS_PER_TOKEN;
+    let repeats = target_chars.div_ceil(FILLER.len()).max(1);
+    let mut prompt = FILLER.repeat(repeats);
+    prompt.push_str(INSTRUCTION);
+    prompt
+}
+
+/// Benchmark `model` once per entry in `context_sizes`. The model is loaded
+/// with a short request first so load time does not skew the first result.
+pub async fn run_benchmark(
+    client: &OllamaClient,
+    model: &str,
+    context_sizes: &[u32],
+    max_tokens: u32,
+) -> io::Result<Vec<BenchResult>> {
+    let warmup = GenerateOptions {
+        num_predict: Some(1),
+        ..Default::default()
+    };
+    client.generate_with_stats(model, "Hi", &warmup).await?;
+
+    let mut results = Vec::with_capacity(context_sizes.len());
+    for &context_size in context_sizes {
+        let options = GenerateOptions {
+            num_predict: Some(max_tokens),
+            num_ctx: Some(context_size + max_tokens + CONTEXT_HEADROOM_TOKENS),
+            keep_alive: None,
+        };
+        let stats = client
+            .generate_with_stats(model, &bench_prompt(context_size), &options)
+            .await?;
+        results.push(BenchResult {
+            context_size,
+            stats,
+        });
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bench_prompt_scales_with_requested_size() {
+        let small = bench_prompt(512);
+        let large = bench_prompt(8192);
+        assert!(small.len() >= 512 * CHARS_PER_TOKEN);
+        assert!(large.len() >= 8192 * CHARS_PER_TOKEN);
+        assert!(large.len() > small.len() * 10);
+        assert!(small.ends_with(INSTRUCTION));
+        assert_eq!(bench_prompt(512), small);
+    }
+}
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+/// Locate the `ollama` executable. `PATH` is consulted first, followed by the
+/// locations used by the official installers on each platform.
+#[cfg(feature = "ollama")]
+pub fn find_ollama_binary() -> Option<PathBuf> {
+    if let Ok(path) = which::which("ollama") {
+        return Some(path);
+    }
+    install_locations().into_iter().find(|path| path.is_file())
+}
+
+/// A build without Ollama support never runs it.
+#[cfg(not(feature = "ollama"))]
+pub fn find_ollama_binary() -> Option<PathBuf> {
+    None
+}
+
+#[cfg(all(feature = "ollama", target_os = "macos"))]
+fn install_locations() -> Vec<PathBuf> {
+    vec![
+        PathBuf::from("/Applications/Ollama.app/Contents/Resources/ollama"),
+        PathBuf::from("/opt/homebrew/bin/ollama"),
+        PathBuf::from("/usr/local/bin/ollama"),
+    ]
+}
+
+#[cfg(all(feature = "ollama", target_os = "windows"))]
+fn install_locations() -> Vec<PathBuf> {
+    std::env::var_os("LOCALAPPDATA")
+        .map(|local| {
+            vec![
+                PathBuf::from(local)
+                    .join("Programs")
+                    .join("Ollama")
+                    .join("ollama.exe"),
+            ]
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(all(
+    feature = "ollama",
+    not(any(target_os = "macos", target_os = "windows"))
+))]
+fn install_locations() -> Vec<PathBuf> {
+    vec![
+        PathBuf::from("/usr/local/bin/ollama"),
+        PathBuf::from("/usr/bin/ollama"),
+    ]
+}
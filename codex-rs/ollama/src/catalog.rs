@@ -0,0 +1,417 @@
+//! Search of downloadable GGUF models on Hugging Face, the catalog LM Studio
+//! browses and that Ollama can pull from directly via `hf.co/<repo>:<quant>`.
+
+use codex_core::config::Config;
+use codex_core::config_types::OssConnection;
+use codex_core::default_client::apply_oss_connection;
+use codex_protocol::oss::CatalogModel;
+use codex_protocol::oss::CatalogVariant;
+use codex_protocol::oss::ModelLicense;
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::io;
+
+use crate::client::http_client;
+use crate::client::http_client_builder;
+use crate::offline_error;
+
+/// Public Hugging Face endpoint used when no other catalog is configured.
+pub const DEFAULT_CATALOG_URL: &str = "https://huggingface.co";
+
+/// Prefix Ollama uses to pull models straight from Hugging Face.
+const HF_PULL_PREFIX: &str = "hf.co/";
+
+/// Client for the model catalog.
+pub struct CatalogClient {
+    client: reqwest::Client,
+    base_url: String,
+    /// Refuse every request (`oss.offline`).
+    offline: bool,
+}
+
+impl CatalogClient {
+    /// Catalog at `base_url`, e.g. [`DEFAULT_CATALOG_URL`].
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let client = http_client();
+        Self {
+            client,
+            base_url: base_url.into(),
+            offline: false,
+        }
+    }
+
+    /// Catalog at [`DEFAULT_CATALOG_URL`], reached with the `oss` connection
+    /// settings that apply to any host (`oss.http_headers`, `oss.user_agent`
+    /// and the timeouts). Every request is refused in offline mode.
+    pub fn from_oss_config(config: &Config) -> io::Result<Self> {
+        // The socket, HTTP/2 and address settings describe the local server,
+        // not the catalog on the internet.
+        let connection = OssConnection {
+            http2_prior_knowledge: false,
+            unix_socket: None,
+            named_pipe: None,
+            loopback_only: false,
+            private_only: false,
+            ..config.oss.connection.clone()
+        };
+        let client = apply_oss_connection(http_client_builder(), &connection)
+            .build()
+            .map_err(io::Error::other)?;
+        Ok(Self {
+            client,
+            base_url: DEFAULT_CATALOG_URL.to_string(),
+            offline: config.oss.offline,
+        })
+    }
+
+    /// Most downloaded GGUF repositories matching `query`, with their
+    /// quantized variants and file sizes.
+    pub async fn search(&self, query: &str, limit: usize) -> io::Result<Vec<CatalogModel>> {
+        let url = format!("{}/api/models", self.base_url.trim_end_matches('/'));
+        let limit = limit.to_string();
+        let resp = self
+            .get(url)?
+            .query(&[
+                ("search", query),
+                ("filter", "gguf"),
+                ("sort", "downloads"),
+                ("direction", "-1"),
+                ("limit", limit.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "catalog search failed: HTTP {}",
+                resp.status()
+            )));
+        }
+        let results: JsonValue = resp.json().await.map_err(io::Error::other)?;
+        let repos = repos_from_search(&results);
+
+        let models =
+            futures::future::join_all(repos.into_iter().map(|(repo, downloads)| async move {
+                // A repository whose file listing cannot be fetched is still worth
+                // showing; it just has no variants.
+                let info = self.fetch_repo_info(&repo).await.unwrap_or(JsonValue::Null);
+                catalog_model_from_repo(&self.base_url, &repo, downloads, &info)
+            }))
+            .await;
+        Ok(models)
+    }
+
+    /// License declared on the model card of `repo`, e.g.
+    /// "unsloth/gpt-oss-20b-GGUF"; `None` when the card declares none.
+    pub async fn fetch_license(&self, repo: &str) -> io::Result<Option<ModelLicense>> {
+        let info = self.fetch_repo_info(repo).await?;
+        Ok(license_from_repo_info(&self.base_url, repo, &info))
+    }
+
+    async fn fetch_repo_info(&self, repo: &str) -> io::Result<JsonValue> {
+        let url = format!("{}/api/models/{repo}", self.base_url.trim_end_matches('/'));
+        let resp = self
+            .get(url)?
+            .query(&[("blobs", "true")])
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "failed to fetch {repo}: HTTP {}",
+                resp.status()
+            )));
+        }
+        resp.json().await.map_err(io::Error::other)
+    }
+
+    fn get(&self, url: String) -> io::Result<reqwest::RequestBuilder> {
+        if self.offline {
+            return Err(offline_error("Looking up the model catalog"));
+        }
+        Ok(self.client.get(url))
+    }
+}
+
+impl Default for CatalogClient {
+    fn default() -> Self {
+        Self::new(DEFAULT_CATALOG_URL)
+    }
+}
+
+/// Repository ids and download counts from a `/api/models` search response.
+fn repos_from_search(results: &JsonValue) -> Vec<(String, Option<u64>)> {
+    results
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let id = item.get("id").and_then(JsonValue::as_str)?;
+                    let downloads = item.get("downloads").and_then(JsonValue::as_u64);
+                    Some((id.to_string(), downloads))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Build a catalog entry from a `/api/models/<repo>?blobs=true` response.
+/// Split weights (`-00001-of-00002.gguf`) are combined into one variant.
+fn catalog_model_from_repo(
+    base_url: &str,
+    repo: &str,
+    downloads: Option<u64>,
+    info: &JsonValue,
+) -> CatalogModel {
+    let mut variants: BTreeMap<String, CatalogVariant> = BTreeMap::new();
+    let siblings = info
+        .get("siblings")
+        .and_then(JsonValue::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for sibling in siblings {
+        let Some(file) = sibling.get("rfilename").and_then(JsonValue::as_str) else {
+            continue;
+        };
+        let Some(quant) = quant_from_filename(file) else {
+            continue;
+        };
+        let size = sibling.get("size").and_then(JsonValue::as_u64);
+        variants
+            .entry(quant.clone())
+            .and_modify(|variant| {
+                variant.size_bytes = match (variant.size_bytes, size) {
+                    (Some(total), Some(size)) => Some(total + size),
+                    _ => None,
+                };
+            })
+            .or_insert_with(|| CatalogVariant {
+                quant,
+                file: file.to_string(),
+                size_bytes: size,
+            });
+    }
+    let mut variants: Vec<CatalogVariant> = variants.into_values().collect();
+    variants.sort_by_key(|variant| variant.size_bytes.unwrap_or(u64::MAX));
+
+    CatalogModel {
+        name: format!("{HF_PULL_PREFIX}{repo}"),
+        repo: repo.to_string(),
+        downloads,
+        last_modified: info
+            .get("lastModified")
+            .and_then(JsonValue::as_str)
+            .map(str::to_string),
+        license: license_from_repo_info(base_url, repo, info),
+        variants,
+    }
+}
+
+/// License from the model card metadata (`cardData.license`, with
+/// `license_name` and `license_link` for custom terms), falling back to the
+/// `license:` tag. Terms without a link of their own are on the model page.
+fn license_from_repo_info(base_url: &str, repo: &str, info: &JsonValue) -> Option<ModelLicense> {
+    let card = info.get("cardData");
+    let card_str = |key: &str| {
+        card.and_then(|card| card.get(key))
+            .and_then(|value| match value {
+                // Some cards list several licenses; the first is the primary one.
+                JsonValue::Array(values) => values.first(),
+                value => Some(value),
+            })
+            .and_then(JsonValue::as_str)
+            .map(str::to_string)
+    };
+    let id = card_str("license").or_else(|| {
+        info.get("tags")
+            .and_then(JsonValue::as_array)?
+            .iter()
+            .filter_map(JsonValue::as_str)
+            .find_map(|tag| tag.strip_prefix("license:"))
+            .map(str::to_string)
+    })?;
+    let repo_url = format!("{}/{repo}", base_url.trim_end_matches('/'));
+    let url = match card_str("license_link") {
+        Some(link) if link.starts_with("http://") || link.starts_with("https://") => link,
+        Some(link) => format!("{repo_url}/blob/main/{}", link.trim_start_matches('/')),
+        None => repo_url,
+    };
+    Some(ModelLicense {
+        id,
+        name: card_str("license_name"),
+        url: Some(url),
+    })
+}
+
+/// Quantization level encoded in a GGUF file name, e.g.
+/// "gpt-oss-20b-Q4_K_M.gguf" -> "Q4_K_M". Projector files are skipped.
+fn quant_from_filename(file: &str) -> Option<String> {
+    let name = file.rsplit('/').next().unwrap_or(file);
+    let stem = name.strip_suffix(".gguf")?;
+    if stem.to_ascii_lowercase().contains("mmproj") {
+        return None;
+    }
+    stem.rsplit(['-', '.'])
+        .map(str::to_ascii_uppercase)
+        .find(|segment| is_quant_label(segment))
+}
+
+fn is_quant_label(segment: &str) -> bool {
+    const FLOAT_LABELS: [&str; 4] = ["F16", "F32", "BF16", "MXFP4"];
+    if FLOAT_LABELS.contains(&segment) {
+        return true;
+    }
+    let rest = segment
+        .strip_prefix("IQ")
+        .or_else(|| segment.strip_prefix('Q'));
+    rest.is_some_and(|rest| rest.chars().next().is_some_and(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_quant_from_filename() {
+        assert_eq!(
+            quant_from_filename("gpt-oss-20b-Q4_K_M.gguf").as_deref(),
+            Some("Q4_K_M")
+        );
+        assert_eq!(
+            quant_from_filename("Q8_0/model.q8_0-00001-of-00002.gguf").as_deref(),
+            Some("Q8_0")
+        );
+        assert_eq!(
+            quant_from_filename("gpt-oss-20b-UD-IQ4_XS.gguf").as_deref(),
+            Some("IQ4_XS")
+        );
+        assert_eq!(
+            quant_from_filename("gpt-oss-20b-F16.gguf").as_deref(),
+            Some("F16")
+        );
+        assert_eq!(quant_from_filename("mmproj-model-f16.gguf"), None);
+        assert_eq!(quant_from_filename("README.md"), None);
+    }
+
+    #[test]
+    fn test_catalog_model_from_repo_merges_shards() {
+        let info = serde_json::json!({
+            "id": "unsloth/gpt-oss-20b-GGUF",
+            "lastModified": "2025-08-20T10:00:00.000Z",
+            "siblings": [
+                {"rfilename": "README.md", "size": 100},
+                {"rfilename": "gpt-oss-20b-Q8_0-00001-of-00002.gguf", "size": 6_000},
+                {"rfilename": "gpt-oss-20b-Q8_0-00002-of-00002.gguf", "size": 5_000},
+                {"rfilename": "gpt-oss-20b-Q4_K_M.gguf", "size": 7_000}
+            ]
+        });
+        let model = catalog_model_from_repo(
+            DEFAULT_CATALOG_URL,
+            "unsloth/gpt-oss-20b-GGUF",
+            Some(42),
+            &info,
+        );
+        assert_eq!(
+            model,
+            CatalogModel {
+                name: "hf.co/unsloth/gpt-oss-20b-GGUF".to_string(),
+                repo: "unsloth/gpt-oss-20b-GGUF".to_string(),
+                downloads: Some(42),
+                last_modified: Some("2025-08-20T10:00:00.000Z".to_string()),
+                license: None,
+                variants: vec![
+                    CatalogVariant {
+                        quant: "Q4_K_M".to_string(),
+                        file: "gpt-oss-20b-Q4_K_M.gguf".to_string(),
+                        size_bytes: Some(7_000),
+                    },
+                    CatalogVariant {
+                        quant: "Q8_0".to_string(),
+                        file: "gpt-oss-20b-Q8_0-00001-of-00002.gguf".to_string(),
+                        size_bytes: Some(11_000),
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_license_from_repo_info() {
+        let repo = "org/model-GGUF";
+        let custom = serde_json::json!({
+            "cardData": {
+                "license": "other",
+                "license_name": "llama3.1",
+                "license_link": "LICENSE"
+            },
+            "tags": ["gguf", "license:other"]
+        });
+        assert_eq!(
+            license_from_repo_info(DEFAULT_CATALOG_URL, repo, &custom),
+            Some(ModelLicense {
+                id: "other".to_string(),
+                name: Some("llama3.1".to_string()),
+                url: Some("https://huggingface.co/org/model-GGUF/blob/main/LICENSE".to_string()),
+            })
+        );
+
+        let tagged = serde_json::json!({"tags": ["gguf", "license:apache-2.0"]});
+        assert_eq!(
+            license_from_repo_info(DEFAULT_CATALOG_URL, repo, &tagged),
+            Some(ModelLicense {
+                id: "apache-2.0".to_string(),
+                name: None,
+                url: Some("https://huggingface.co/org/model-GGUF".to_string()),
+            })
+        );
+        assert_eq!(
+            license_from_repo_info(DEFAULT_CATALOG_URL, repo, &serde_json::json!({})),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_happy_path() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_search_happy_path",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/models"))
+            .and(wiremock::matchers::query_param("search", "gpt-oss"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(
+                serde_json::json!([{"id": "org/gpt-oss-GGUF", "downloads": 7}]).to_string(),
+                "application/json",
+            ))
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/models/org/gpt-oss-GGUF"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_raw(
+                    serde_json::json!({
+                        "siblings": [{"rfilename": "gpt-oss-Q4_K_M.gguf", "size": 12}]
+                    })
+                    .to_string(),
+                    "application/json",
+                ),
+            )
+            .mount(&server)
+            .await;
+
+        let catalog = CatalogClient::new(server.uri());
+        let models = catalog.search("gpt-oss", 5).await.expect("search");
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].name, "hf.co/org/gpt-oss-GGUF");
+        assert_eq!(models[0].downloads, Some(7));
+        assert_eq!(models[0].variants[0].quant, "Q4_K_M");
+        assert_eq!(models[0].variants[0].size_bytes, Some(12));
+    }
+}
@@ -1,27 +1,117 @@
 use bytes::BytesMut;
 use futures::StreamExt;
 use futures::stream::BoxStream;
+use reqwest::Method;
 use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use tracing::Instrument;
 
+use crate::CatalogClient;
+use crate::audit;
+use crate::audit::AuditAction;
+use crate::audit::AuditEntry;
+use crate::audit::audit_log_path;
+use crate::command::print_only;
+use crate::download_lock::DownloadLock;
+use crate::download_lock::download_lock_dir;
+use crate::events::publish;
+use crate::generate::GenerateOptions;
+use crate::generate::GenerationStats;
+use crate::generate::ModelAnswer;
+use crate::generate::answer_from_chat_completion;
+use crate::generate::apply_final_generate_message;
+use crate::guard::ensure_loopback_base_url;
+use crate::license::ensure_license_accepted;
+use crate::models::LoadedModel;
+use crate::models::LocalModel;
+use crate::models::ModelDetails;
+use crate::models::context_length_from_show;
+use crate::models::context_window_from_lmstudio;
+use crate::models::loaded_model_ids_from_lmstudio;
+use crate::models::loaded_model_names_from_ps;
+use crate::models::loaded_models_from_ps;
+use crate::models::local_models_from_lmstudio;
+use crate::models::local_models_from_tags;
+use crate::models::model_details_from_show;
+use crate::models::model_digests_from_tags;
+use crate::models::model_ids_from_openai_models;
+use crate::models::parameters_from_show;
+use crate::oss_state_dir;
 use crate::parser::pull_events_from_value;
+use crate::pull::JsonProgressReporter;
 use crate::pull::PullEvent;
 use crate::pull::PullProgressReporter;
+use crate::throttle::DownloadThrottle;
 use crate::url::base_url_to_host_root;
 use crate::url::is_openai_compatible_base_url;
 use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
 use codex_core::ModelProviderInfo;
 use codex_core::WireApi;
 use codex_core::config::Config;
+use codex_core::config_types::OssConnection;
+use codex_core::config_types::OssVerbosity;
+use codex_core::default_client::apply_bearer_token;
+use codex_core::default_client::apply_oss_connection;
+use codex_core::oss_credentials::remote_oss_token;
+use codex_core::protocol_config_types::OssBackend;
+use codex_protocol::oss::ModelLicense;
+use codex_protocol::oss::OssEvent;
+
+/// Where Ollama listens unless told otherwise.
+const DEFAULT_OLLAMA_HOST_ROOT: &str = "http://localhost:11434";
 
 const OLLAMA_CONNECTION_ERROR: &str = "No running Ollama server detected. Start it with: `ollama serve` (after installing). Install instructions: https://github.com/ollama/ollama?tab=readme-ov-file#ollama";
 
+/// HTTP client for the local server and the model registries. Responses may
+/// come gzip or zstd compressed, which shrinks the large `/v1/models` and
+/// `/api/tags` listings of a server reached over Wi-Fi or a LAN.
+pub(crate) fn http_client() -> reqwest::Client {
+    http_client_builder()
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+pub(crate) fn http_client_builder() -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .user_agent(codex_core::default_client::get_codex_user_agent())
+        .connect_timeout(std::time::Duration::from_secs(5))
+        .gzip(true)
+        .zstd(true)
+}
+
 /// Client for interacting with a local Ollama instance.
 pub struct OllamaClient {
     client: reqwest::Client,
     host_root: String,
     uses_openai_compat: bool,
+    /// The server is LM Studio (`oss.provider = "lmstudio"`), whose `lms`
+    /// CLI can load and unload models.
+    lmstudio: bool,
+    /// Echo every request and retry to stderr (`oss.verbosity = "verbose"`).
+    verbose: bool,
+    /// Bytes per second pulls may average (`oss.download_rate_limit`).
+    download_rate_limit: Option<u64>,
+    /// Where downloads and commands are recorded; see [`crate::audit`].
+    audit_log: Option<PathBuf>,
+    /// Builds of `lms` this client may run (`oss.trusted_lms_sha256`).
+    trusted_lms_sha256: Vec<String>,
+    /// Where pulls take their cross-process lock; see
+    /// [`crate::download_lock`].
+    download_lock_dir: Option<PathBuf>,
+    /// Licenses catalog models may be pulled under
+    /// (`oss.accepted_licenses`); `None` pulls without checking.
+    accepted_licenses: Option<Vec<String>>,
+    /// Where licenses of catalog models are looked up.
+    catalog: CatalogClient,
+    /// Licenses already looked up, by model, so a pull fetches each once.
+    licenses: std::sync::Mutex<HashMap<String, Option<ModelLicense>>>,
+    /// Refuse pulls, which download from the internet (`oss.strict_local`).
+    strict_local: bool,
 }
 
 impl OllamaClient {
@@ -29,6 +119,14 @@ impl OllamaClient {
     /// and verify that a local Ollama server is reachable. If no server is
     /// detected, returns an error with helpful installation/run instructions.
     pub async fn try_from_oss_provider(config: &Config) -> io::Result<Self> {
+        let client = Self::from_oss_provider(config)?;
+        client.probe_server().await?;
+        Ok(client)
+    }
+
+    /// Construct a client for the built‑in open‑source ("oss") model provider
+    /// without contacting the server.
+    pub fn from_oss_provider(config: &Config) -> io::Result<Self> {
         // Note that we must look up the provider from the Config to ensure that
         // any overrides the user has in their config.toml are taken into
         // account.
@@ -42,17 +140,57 @@ impl OllamaClient {
                 )
             })?;
 
-        Self::try_from_provider(provider).await
+        let mut client = Self::from_provider(provider);
+        // An explicitly selected backend overrides detection from the base_url.
+        if let Some(backend) = config.oss.provider {
+            client.uses_openai_compat = backend != OssBackend::Ollama;
+            client.lmstudio = backend == OssBackend::LmStudio;
+        }
+        // Without a selected backend the startup path treats the server as
+        // Ollama.
+        let backend = config.oss.provider.unwrap_or(OssBackend::Ollama);
+        if !crate::backend_compiled_in(backend) {
+            return Err(crate::backend_not_compiled_in(backend));
+        }
+        client.verbose = config.oss.verbosity == OssVerbosity::Verbose;
+        client.download_rate_limit = config.oss.download_rate_limit;
+        client.audit_log = Some(audit_log_path(&oss_state_dir(config)));
+        client.trusted_lms_sha256 = config.oss.trusted_lms_sha256.clone();
+        client.download_lock_dir = Some(download_lock_dir(&oss_state_dir(config)));
+        client.accepted_licenses = Some(config.oss.accepted_licenses.clone());
+        client.catalog = CatalogClient::from_oss_config(config)?;
+        if config.oss.strict_local {
+            ensure_loopback_base_url(&client.host_root)?;
+            client.strict_local = true;
+        }
+        // The request deadline is meant for model requests; a pull may
+        // rightly take hours.
+        let connection = OssConnection {
+            request_timeout: None,
+            ..config.oss.connection.clone()
+        };
+        let mut builder = apply_oss_connection(http_client_builder(), &connection);
+        if let Some(base_url) = provider.base_url.as_deref()
+            && let Some(token) = remote_oss_token(&config.codex_home, base_url)
+        {
+            builder = apply_bearer_token(builder, &token);
+        }
+        // A client that failed to build must not be swapped for one that
+        // drops the token or the connection settings.
+        client.client = builder.build().map_err(io::Error::other)?;
+        Ok(client)
     }
 
     #[cfg(test)]
     async fn try_from_provider_with_base_url(base_url: &str) -> io::Result<Self> {
         let provider = codex_core::create_oss_provider_with_base_url(base_url);
-        Self::try_from_provider(&provider).await
+        let client = Self::from_provider(&provider);
+        client.probe_server().await?;
+        Ok(client)
     }
 
-    /// Build a client from a provider definition and verify the server is reachable.
-    async fn try_from_provider(provider: &ModelProviderInfo) -> io::Result<Self> {
+    /// Build a client from a provider definition.
+    fn from_provider(provider: &ModelProviderInfo) -> Self {
         #![expect(clippy::expect_used)]
         let base_url = provider
             .base_url
@@ -62,30 +200,102 @@ impl OllamaClient {
             || matches!(provider.wire_api, WireApi::Chat)
                 && is_openai_compatible_base_url(base_url);
         let host_root = base_url_to_host_root(base_url);
-        let client = reqwest::Client::builder()
-            .connect_timeout(std::time::Duration::from_secs(5))
-            .build()
-            .unwrap_or_else(|_| reqwest::Client::new());
-        let client = Self {
+        let client = http_client();
+        Self {
             client,
             host_root,
             uses_openai_compat,
-        };
-        client.probe_server().await?;
-        Ok(client)
+            lmstudio: false,
+            verbose: false,
+            download_rate_limit: None,
+            audit_log: None,
+            trusted_lms_sha256: Vec::new(),
+            download_lock_dir: None,
+            accepted_licenses: None,
+            catalog: CatalogClient::default(),
+            licenses: Default::default(),
+            strict_local: false,
+        }
+    }
+
+    /// Root URL of the Ollama server, e.g. "http://localhost:11434".
+    pub fn host_root(&self) -> &str {
+        &self.host_root
+    }
+
+    /// Audit log this client records to, when built from the `oss` provider.
+    pub(crate) fn audit_log(&self) -> Option<&Path> {
+        self.audit_log.as_deref()
+    }
+
+    /// The `lms` CLI, if installed and it passes [`crate::lms_trust`]'s
+    /// checks.
+    pub(crate) fn trusted_lms_cli(&self) -> io::Result<Option<PathBuf>> {
+        crate::lms_trust::trusted_lms_cli(&self.trusted_lms_sha256)
+    }
+
+    /// License of catalog model `model`, looked up once per client; see
+    /// [`crate::license::model_license`].
+    pub async fn model_license(&self, model: &str) -> io::Result<Option<ModelLicense>> {
+        if let Some(license) = self
+            .licenses
+            .lock()
+            .ok()
+            .and_then(|licenses| licenses.get(model).cloned())
+        {
+            return Ok(license);
+        }
+        let license = crate::license::model_license(&self.catalog, model).await?;
+        if let Ok(mut licenses) = self.licenses.lock() {
+            licenses.insert(model.to_string(), license.clone());
+        }
+        Ok(license)
+    }
+
+    /// Let pulls go ahead under `license` for the rest of this client's
+    /// life, e.g. after the user accepted it.
+    pub fn accept_license(&mut self, license: &str) {
+        if let Some(accepted) = &mut self.accepted_licenses {
+            accepted.push(license.to_string());
+        }
+    }
+
+    /// Build a request for `path` on the server, echoing it when verbose.
+    fn request(&self, method: Method, path: &str) -> TracedRequest {
+        let url = format!("{}{path}", self.host_root.trim_end_matches('/'));
+        self.log(format_args!("{method} {url}"));
+        TracedRequest {
+            builder: self.client.request(method, url),
+            model: None,
+        }
+    }
+
+    /// Print a line about what the client is doing when verbose, and trace
+    /// it otherwise; the TUI, which owns the terminal, forces
+    /// `oss.verbosity` to quiet.
+    pub(crate) fn log(&self, message: std::fmt::Arguments<'_>) {
+        if self.verbose {
+            eprintln!("[oss] {message}");
+        } else {
+            tracing::debug!("{message}");
+        }
     }
 
     /// Probe whether the server is reachable by hitting the appropriate health endpoint.
-    async fn probe_server(&self) -> io::Result<()> {
-        let url = if self.uses_openai_compat {
-            format!("{}/v1/models", self.host_root.trim_end_matches('/'))
+    pub async fn probe_server(&self) -> io::Result<()> {
+        let path = if self.uses_openai_compat {
+            "/v1/models"
         } else {
-            format!("{}/api/tags", self.host_root.trim_end_matches('/'))
+            "/api/tags"
         };
-        let resp = self.client.get(url).send().await.map_err(|err| {
-            tracing::warn!("Failed to connect to Ollama server: {err:?}");
-            io::Error::other(OLLAMA_CONNECTION_ERROR)
-        })?;
+        let resp = self
+            .request(Method::GET, path)
+            .send()
+            .await
+            .map_err(|err| {
+                tracing::warn!("Failed to connect to Ollama server: {err:?}");
+                io::Error::other(OLLAMA_CONNECTION_ERROR)
+            })?;
         if resp.status().is_success() {
             Ok(())
         } else {
@@ -98,12 +308,54 @@ impl OllamaClient {
         }
     }
 
+    /// Whether `GET path` succeeds.
+    pub(crate) async fn answers_get(&self, path: &str) -> bool {
+        self.request(Method::GET, path)
+            .send()
+            .await
+            .is_ok_and(|resp| resp.status().is_success())
+    }
+
+    /// Poll the server until it responds or `timeout` elapses. Useful right
+    /// after starting a server process.
+    pub async fn wait_until_reachable(&self, timeout: std::time::Duration) -> io::Result<()> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            match self.probe_server().await {
+                Ok(()) => return Ok(()),
+                Err(err) if tokio::time::Instant::now() >= deadline => return Err(err),
+                Err(_) => {
+                    self.log(format_args!("server not reachable yet; retrying in 250ms"));
+                    tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+                }
+            }
+        }
+    }
+
+    /// Return the version string reported by the server, e.g. "0.11.4".
+    pub async fn fetch_version(&self) -> io::Result<String> {
+        let resp = self
+            .request(Method::GET, "/api/version")
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "failed to query server version: HTTP {}",
+                resp.status()
+            )));
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        val.get("version")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| io::Error::other("server did not report a version"))
+    }
+
     /// Return the list of model names known to the local Ollama instance.
     pub async fn fetch_models(&self) -> io::Result<Vec<String>> {
-        let tags_url = format!("{}/api/tags", self.host_root.trim_end_matches('/'));
         let resp = self
-            .client
-            .get(tags_url)
+            .request(Method::GET, "/api/tags")
             .send()
             .await
             .map_err(io::Error::other)?;
@@ -124,16 +376,320 @@ impl OllamaClient {
         Ok(names)
     }
 
+    /// Return the model ids listed by the OpenAI-compatible `/v1/models`
+    /// endpoint. Works against any OpenAI-compatible server.
+    pub async fn fetch_openai_model_ids(&self) -> io::Result<Vec<String>> {
+        let resp = self
+            .request(Method::GET, "/v1/models")
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "failed to list models: HTTP {}",
+                resp.status()
+            )));
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        Ok(model_ids_from_openai_models(&val))
+    }
+
+    /// Return metadata for every model known to the local Ollama instance,
+    /// including whether it is currently loaded and its context length.
+    pub async fn fetch_local_models(&self) -> io::Result<Vec<LocalModel>> {
+        let resp = self
+            .request(Method::GET, "/api/tags")
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "failed to list models: HTTP {}",
+                resp.status()
+            )));
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        let mut models = local_models_from_tags(&val);
+
+        // Older servers do not implement `/api/ps`; treat that as "nothing loaded".
+        let loaded = self.fetch_loaded_model_names().await.unwrap_or_else(|err| {
+            tracing::debug!("Failed to query loaded models: {err}");
+            Vec::new()
+        });
+        let context_lengths = futures::future::join_all(
+            models
+                .iter()
+                .map(|model| self.fetch_context_length(&model.name)),
+        )
+        .await;
+        for (model, context_length) in models.iter_mut().zip(context_lengths) {
+            model.loaded = loaded.contains(&model.name);
+            model.context_length = context_length;
+        }
+        Ok(models)
+    }
+
+    /// Return the manifest digest of every local model, keyed by model name.
+    pub async fn fetch_model_digests(&self) -> io::Result<Vec<(String, String)>> {
+        let resp = self
+            .request(Method::GET, "/api/tags")
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "failed to list models: HTTP {}",
+                resp.status()
+            )));
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        Ok(model_digests_from_tags(&val))
+    }
+
+    /// Return the models currently loaded into memory with their footprint.
+    pub async fn fetch_loaded_models(&self) -> io::Result<Vec<LoadedModel>> {
+        let resp = self
+            .request(Method::GET, "/api/ps")
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "failed to query loaded models: HTTP {}",
+                resp.status()
+            )));
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        Ok(loaded_models_from_ps(&val))
+    }
+
+    /// Return the names of the models currently loaded into memory.
+    pub async fn fetch_loaded_model_names(&self) -> io::Result<Vec<String>> {
+        let resp = self
+            .request(Method::GET, "/api/ps")
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "failed to query loaded models: HTTP {}",
+                resp.status()
+            )));
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        Ok(loaded_model_names_from_ps(&val))
+    }
+
+    /// Return the ids of the models LM Studio currently has loaded, from its
+    /// `/api/v0/models` REST endpoint.
+    pub async fn fetch_lmstudio_loaded_models(&self) -> io::Result<Vec<String>> {
+        let resp = self
+            .request(Method::GET, "/api/v0/models")
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "failed to query loaded models: HTTP {}",
+                resp.status()
+            )));
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        Ok(loaded_model_ids_from_lmstudio(&val))
+    }
+
+    /// Every model LM Studio has downloaded, with the metadata its
+    /// `/api/v0/models` REST endpoint reports.
+    pub async fn fetch_lmstudio_models(&self) -> io::Result<Vec<LocalModel>> {
+        let resp = self
+            .request(Method::GET, "/api/v0/models")
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "failed to list models: HTTP {}",
+                resp.status()
+            )));
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        Ok(local_models_from_lmstudio(&val))
+    }
+
+    /// Context window LM Studio runs `model` with, from its `/api/v0/models`
+    /// REST endpoint.
+    pub async fn fetch_lmstudio_context_window(&self, model: &str) -> io::Result<Option<u64>> {
+        let resp = self
+            .request(Method::GET, "/api/v0/models")
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "failed to query models: HTTP {}",
+                resp.status()
+            )));
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        Ok(context_window_from_lmstudio(&val, model))
+    }
+
+    /// Load `model` if needed and restart its idle timer, without generating
+    /// anything: Ollama answers a request without a prompt once the model is
+    /// in memory.
+    pub async fn touch_model(&self, model: &str) -> io::Result<()> {
+        let resp = self
+            .request(Method::POST, "/api/generate")
+            .json(&serde_json::json!({"model": model}))
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        let status = resp.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!(
+                "failed to keep model '{model}' loaded: HTTP {status}"
+            )))
+        }
+    }
+
+    /// Evict `model` from memory right away by asking for a zero keep-alive.
+    /// Unloading a model that is not loaded succeeds.
+    pub async fn unload_model(&self, model: &str) -> io::Result<()> {
+        let resp = self
+            .request(Method::POST, "/api/generate")
+            .json(&serde_json::json!({"model": model, "keep_alive": 0}))
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        let status = resp.status();
+        if status.is_success() {
+            Ok(())
+        } else if status == reqwest::StatusCode::NOT_FOUND {
+            Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("model '{model}' not found"),
+            ))
+        } else {
+            Err(io::Error::other(format!(
+                "failed to unload model '{model}': HTTP {status}"
+            )))
+        }
+    }
+
+    /// Delete a model from the local Ollama instance, freeing its disk space.
+    pub async fn delete_model(&self, model: &str) -> io::Result<()> {
+        let resp = self
+            .request(Method::DELETE, "/api/delete")
+            .json(&serde_json::json!({"model": model}))
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        let status = resp.status();
+        if status.is_success() {
+            Ok(())
+        } else if status == reqwest::StatusCode::NOT_FOUND {
+            Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("model '{model}' not found"),
+            ))
+        } else {
+            Err(io::Error::other(format!(
+                "failed to delete model '{model}': HTTP {status}"
+            )))
+        }
+    }
+
+    /// Return full metadata for `model`, combining `/api/show` with the size
+    /// from `/api/tags` and the load state from `/api/ps`.
+    pub async fn fetch_model_details(&self, model: &str) -> io::Result<ModelDetails> {
+        let resp = self
+            .request(Method::POST, "/api/show")
+            .json(&serde_json::json!({"model": model}))
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        let status = resp.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("model '{model}' not found"),
+            ));
+        } else if !status.is_success() {
+            return Err(io::Error::other(format!(
+                "failed to show model '{model}': HTTP {status}"
+            )));
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        let mut details = model_details_from_show(model, &val);
+
+        if let Ok(resp) = self.request(Method::GET, "/api/tags").send().await
+            && let Ok(val) = resp.json::<JsonValue>().await
+        {
+            details.size_bytes = local_models_from_tags(&val)
+                .into_iter()
+                .find(|m| m.name == model)
+                .and_then(|m| m.size_bytes);
+        }
+        details.loaded = self
+            .fetch_loaded_model_names()
+            .await
+            .map(|names| names.iter().any(|name| name == model))
+            .unwrap_or(false);
+        Ok(details)
+    }
+
+    /// Return the Modelfile parameters of `model`, such as its sampling
+    /// settings.
+    pub async fn fetch_model_parameters(
+        &self,
+        model: &str,
+    ) -> io::Result<BTreeMap<String, Vec<String>>> {
+        let resp = self
+            .request(Method::POST, "/api/show")
+            .json(&serde_json::json!({"model": model}))
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        let status = resp.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("model '{model}' not found"),
+            ));
+        } else if !status.is_success() {
+            return Err(io::Error::other(format!(
+                "failed to show model '{model}': HTTP {status}"
+            )));
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        Ok(parameters_from_show(&val))
+    }
+
+    /// Look up the maximum context length for `model`, if the server reports one.
+    async fn fetch_context_length(&self, model: &str) -> Option<u64> {
+        let resp = self
+            .request(Method::POST, "/api/show")
+            .json(&serde_json::json!({"model": model}))
+            .send()
+            .await
+            .ok()?;
+        if !resp.status().is_success() {
+            return None;
+        }
+        let val = resp.json::<JsonValue>().await.ok()?;
+        context_length_from_show(&val)
+    }
+
     /// Start a model pull and emit streaming events. The returned stream ends when
     /// a Success event is observed or the server closes the connection.
     pub async fn pull_model_stream(
         &self,
         model: &str,
     ) -> io::Result<BoxStream<'static, PullEvent>> {
-        let url = format!("{}/api/pull", self.host_root.trim_end_matches('/'));
         let resp = self
-            .client
-            .post(url)
+            .request(Method::POST, "/api/pull")
             .json(&serde_json::json!({"model": model, "stream": true}))
             .send()
             .await
@@ -183,58 +739,598 @@ impl OllamaClient {
         Ok(Box::pin(s))
     }
 
-    /// High-level helper to pull a model and drive a progress reporter.
-    pub async fn pull_with_reporter(
+    /// High-level helper to pull a model and drive a progress reporter. With
+    /// a download rate limit, a pull that gets ahead of it is paused and
+    /// resumed. A catalog model whose license is not accepted is refused; see
+    /// [`crate::license`]. Under `oss.strict_local` every pull is refused;
+    /// in print-only mode (see [`crate::set_print_only`]) the request is
+    /// printed instead and taken to have succeeded.
+    pub async fn pull_with_reporter<R: PullProgressReporter + ?Sized>(
         &self,
         model: &str,
-        reporter: &mut dyn PullProgressReporter,
+        reporter: &mut R,
     ) -> io::Result<()> {
-        reporter.on_event(&PullEvent::Status(format!("Pulling model {model}...")))?;
-        let mut stream = self.pull_model_stream(model).await?;
-        while let Some(event) = stream.next().await {
-            reporter.on_event(&event)?;
-            match event {
-                PullEvent::Success => {
-                    return Ok(());
-                }
-                PullEvent::Error(err) => {
-                    // Empirically, ollama returns a 200 OK response even when
-                    // the output stream includes an error message. Verify with:
-                    //
-                    // `curl -i http://localhost:11434/api/pull -d '{ "model": "foobarbaz" }'`
-                    //
-                    // As such, we have to check the event stream, not the
-                    // HTTP response status, to determine whether to return Err.
-                    return Err(io::Error::other(format!("Pull failed: {err}")));
+        if self.strict_local {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("Downloading {model} is disabled by oss.strict_local."),
+            ));
+        }
+        if print_only() {
+            println!("POST {}/api/pull {model}", self.host_root);
+            return reporter.on_event(&PullEvent::Success);
+        }
+        if let Some(accepted) = &self.accepted_licenses {
+            ensure_license_accepted(self, accepted, model).await?;
+        }
+        // Held until the pull ends, so other sessions wait for this one.
+        let _lock = match &self.download_lock_dir {
+            Some(lock_dir) => match DownloadLock::try_acquire(lock_dir, model)? {
+                Some(lock) => Some(lock),
+                None => {
+                    reporter.on_event(&PullEvent::Status(format!(
+                        "Waiting for another Codex session to finish downloading {model}..."
+                    )))?;
+                    let lock = DownloadLock::acquire(lock_dir, model).await?;
+                    if self
+                        .fetch_models()
+                        .await
+                        .is_ok_and(|models| models.iter().any(|name| name == model))
+                    {
+                        reporter.on_event(&PullEvent::Success)?;
+                        return Ok(());
+                    }
+                    // The other download failed or was cancelled; retry it.
+                    Some(lock)
                 }
-                PullEvent::ChunkProgress { .. } | PullEvent::Status(_) => {
-                    continue;
+            },
+            None => None,
+        };
+        reporter.on_event(&PullEvent::Status(format!("Pulling model {model}...")))?;
+        publish(OssEvent::DownloadStarted {
+            model: model.to_string(),
+        });
+        let started_at = chrono::Utc::now();
+        let mut bus = JsonProgressReporter::new(model, |progress| {
+            publish(OssEvent::DownloadProgress(progress.clone()));
+            Ok(())
+        });
+        let started = std::time::Instant::now();
+        let mut downloaded = BTreeMap::new();
+        let mut throttle = self
+            .download_rate_limit
+            .map(|bytes_per_second| DownloadThrottle::new(bytes_per_second, started));
+        let result = async {
+            loop {
+                let mut stream = self.pull_model_stream(model).await?;
+                let mut pause = None;
+                while let Some(event) = stream.next().await {
+                    reporter.on_event(&event)?;
+                    bus.on_event(&event)?;
+                    match event {
+                        PullEvent::Success => {
+                            return Ok(());
+                        }
+                        PullEvent::Error(err) => {
+                            // Empirically, ollama returns a 200 OK response even when
+                            // the output stream includes an error message. Verify with:
+                            //
+                            // `curl -i http://localhost:11434/api/pull -d '{ "model": "foobarbaz" }'`
+                            //
+                            // As such, we have to check the event stream, not the
+                            // HTTP response status, to determine whether to return Err.
+                            return Err(io::Error::other(format!("Pull failed: {err}")));
+                        }
+                        PullEvent::ChunkProgress {
+                            digest,
+                            completed: Some(completed),
+                            ..
+                        } => {
+                            if let Some(throttle) = throttle.as_mut() {
+                                throttle.record(&digest, completed);
+                                pause = throttle.pause_at(std::time::Instant::now());
+                            }
+                            downloaded.insert(digest, completed);
+                            if pause.is_some() {
+                                break;
+                            }
+                        }
+                        PullEvent::ChunkProgress { .. } | PullEvent::Status(_) => {
+                            continue;
+                        }
+                    }
                 }
+                let Some(pause) = pause else {
+                    return Err(io::Error::other(
+                        "Pull stream ended unexpectedly without success.",
+                    ));
+                };
+                // Dropping the stream stops Ollama's download; pulling again
+                // resumes it.
+                drop(stream);
+                self.log(format_args!(
+                    "pausing the download for {}s to stay under the rate limit",
+                    pause.as_secs().max(1)
+                ));
+                tokio::time::sleep(pause).await;
             }
         }
-        Err(io::Error::other(
-            "Pull stream ended unexpectedly without success.",
-        ))
-    }
-
-    /// Low-level constructor given a raw host root, e.g. "http://localhost:11434".
-    #[cfg(test)]
-    fn from_host_root(host_root: impl Into<String>) -> Self {
-        let client = reqwest::Client::builder()
-            .connect_timeout(std::time::Duration::from_secs(5))
-            .build()
-            .unwrap_or_else(|_| reqwest::Client::new());
-        Self {
-            client,
-            host_root: host_root.into(),
-            uses_openai_compat: false,
+        .await;
+        let error = result.as_ref().err().map(ToString::to_string);
+        codex_otel::otel_event_manager::local_model_download(
+            "ollama",
+            model,
+            downloaded.values().sum(),
+            started.elapsed(),
+            error.as_deref(),
+        );
+        if let Some(audit_log) = &self.audit_log {
+            audit::record(
+                audit_log,
+                &AuditEntry {
+                    started_at,
+                    finished_at: chrono::Utc::now(),
+                    action: AuditAction::Download {
+                        model: model.to_string(),
+                        host_root: self.host_root.clone(),
+                        layers: downloaded.keys().cloned().collect(),
+                        bytes: downloaded.values().sum(),
+                    },
+                    error: error.clone(),
+                },
+            );
         }
+        publish(OssEvent::DownloadFinished {
+            model: model.to_string(),
+            error,
+        });
+        result
+    }
+
+    /// Run a streaming completion via `/api/generate`, discarding the text and
+    /// returning the timing statistics reported by the server together with
+    /// the observed time to first token.
+    pub async fn generate_with_stats(
+        &self,
+        model: &str,
+        prompt: &str,
+        options: &GenerateOptions,
+    ) -> io::Result<GenerationStats> {
+        let mut body = serde_json::json!({
+            "model": model,
+            "prompt": prompt,
+            "stream": true,
+            "options": options.to_json(),
+        });
+        if let Some(keep_alive) = &options.keep_alive {
+            body["keep_alive"] = keep_alive.clone().into();
+        }
+        let started = std::time::Instant::now();
+        let resp = self
+            .request(Method::POST, "/api/generate")
+            .json(&body)
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "generate failed: HTTP {}",
+                resp.status()
+            )));
+        }
+
+        let mut stats = GenerationStats::default();
+        let mut stream = resp.bytes_stream();
+        let mut buf = BytesMut::new();
+        while let Some(chunk) = stream.next().await {
+            buf.extend_from_slice(&chunk.map_err(io::Error::other)?);
+            while let Some(pos) = buf.iter().position(|b| *b == b'\n') {
+                let line = buf.split_to(pos + 1);
+                let Ok(value) = serde_json::from_slice::<JsonValue>(&line) else {
+                    continue;
+                };
+                if let Some(err_msg) = value.get("error").and_then(|e| e.as_str()) {
+                    return Err(io::Error::other(format!("generate failed: {err_msg}")));
+                }
+                if stats.time_to_first_token.is_none()
+                    && value
+                        .get("response")
+                        .and_then(|r| r.as_str())
+                        .is_some_and(|r| !r.is_empty())
+                {
+                    stats.time_to_first_token = Some(started.elapsed());
+                }
+                if value.get("done").and_then(JsonValue::as_bool) == Some(true) {
+                    apply_final_generate_message(&mut stats, &value);
+                    stats.total_duration = started.elapsed();
+                    return Ok(stats);
+                }
+            }
+        }
+        Err(io::Error::other(
+            "generate stream ended unexpectedly without completion.",
+        ))
+    }
+
+    /// Ask `model` for a complete answer to `prompt` via the OpenAI-compatible
+    /// `/v1/chat/completions` endpoint, which every supported backend serves.
+    /// No tools are offered, so the answer is text only.
+    pub async fn answer(
+        &self,
+        model: &str,
+        prompt: &str,
+        max_tokens: Option<u64>,
+    ) -> io::Result<ModelAnswer> {
+        let mut body = serde_json::json!({
+            "model": model,
+            "messages": [{"role": "user", "content": prompt}],
+            "stream": false,
+        });
+        if let Some(max_tokens) = max_tokens {
+            body["max_tokens"] = max_tokens.into();
+        }
+        let (value, elapsed) = self.chat_completion(model, &body).await?;
+        Ok(answer_from_chat_completion(&value, elapsed))
+    }
+
+    /// Embed each of `inputs` with `model` via the OpenAI-compatible
+    /// `/v1/embeddings` endpoint, returning the vectors in input order.
+    pub async fn embeddings(&self, model: &str, inputs: &[String]) -> io::Result<Vec<Vec<f32>>> {
+        let resp = self
+            .request(Method::POST, "/v1/embeddings")
+            .json(&serde_json::json!({"model": model, "input": inputs}))
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(io::Error::other(format!(
+                "embedding with {model} failed: HTTP {status} {}",
+                body.trim()
+            )));
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        let mut data: Vec<(u64, Vec<f32>)> = val
+            .get("data")
+            .and_then(JsonValue::as_array)
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .map(|(position, item)| {
+                let index = item
+                    .get("index")
+                    .and_then(JsonValue::as_u64)
+                    .unwrap_or(position as u64);
+                let embedding = item
+                    .get("embedding")
+                    .and_then(JsonValue::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(JsonValue::as_f64)
+                    .map(|value| value as f32)
+                    .collect();
+                (index, embedding)
+            })
+            .collect();
+        if data.len() != inputs.len() {
+            return Err(io::Error::other(format!(
+                "embedding with {model} returned {} vectors for {} inputs",
+                data.len(),
+                inputs.len()
+            )));
+        }
+        data.sort_by_key(|(index, _)| *index);
+        Ok(data.into_iter().map(|(_, embedding)| embedding).collect())
+    }
+
+    /// Send a non-streaming `/v1/chat/completions` request for `model` and
+    /// return the response body with the time the request took.
+    pub(crate) async fn chat_completion(
+        &self,
+        model: &str,
+        body: &JsonValue,
+    ) -> io::Result<(JsonValue, std::time::Duration)> {
+        let started = std::time::Instant::now();
+        let resp = self
+            .request(Method::POST, "/v1/chat/completions")
+            .json(body)
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(io::Error::other(format!(
+                "{model} failed: HTTP {status} {}",
+                body.trim()
+            )));
+        }
+        let value = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        Ok((value, started.elapsed()))
+    }
+
+    /// Send a streaming Chat Completions request and return the payload of
+    /// every `data:` line of the response, including the final `[DONE]`.
+    pub(crate) async fn chat_completion_events(
+        &self,
+        model: &str,
+        body: &JsonValue,
+    ) -> io::Result<Vec<String>> {
+        let resp = self
+            .request(Method::POST, "/v1/chat/completions")
+            .json(body)
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(io::Error::other(format!(
+                "{model} failed: HTTP {status} {}",
+                body.trim()
+            )));
+        }
+        let text = resp.text().await.map_err(io::Error::other)?;
+        Ok(text
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(|data| data.trim().to_string())
+            .filter(|data| !data.is_empty())
+            .collect())
+    }
+
+    /// Configure a client for any server, without a Codex [`Config`].
+    pub fn builder() -> OllamaClientBuilder {
+        OllamaClientBuilder::default()
+    }
+
+    /// Low-level constructor given a raw host root, e.g. "http://localhost:11434".
+    /// The server is not contacted.
+    pub fn from_host_root(host_root: impl Into<String>) -> Self {
+        let client = http_client();
+        Self {
+            client,
+            host_root: host_root.into(),
+            uses_openai_compat: false,
+            lmstudio: false,
+            verbose: false,
+            download_rate_limit: None,
+            audit_log: None,
+            trusted_lms_sha256: Vec::new(),
+            download_lock_dir: None,
+            accepted_licenses: None,
+            catalog: CatalogClient::default(),
+            licenses: Default::default(),
+            strict_local: false,
+        }
+    }
+
+    /// Whether the server is spoken to through its OpenAI-compatible API
+    /// rather than Ollama's native one.
+    pub(crate) fn uses_openai_compat(&self) -> bool {
+        self.uses_openai_compat
+    }
+
+    /// Whether the server is LM Studio, so its `lms` CLI may be used.
+    pub(crate) fn is_lmstudio(&self) -> bool {
+        self.lmstudio
+    }
+
+    /// Talk to the server through its OpenAI-compatible API instead of the
+    /// native Ollama one.
+    pub(crate) fn with_openai_compat(mut self, uses_openai_compat: bool) -> Self {
+        self.uses_openai_compat = uses_openai_compat;
+        self
+    }
+
+    /// Echo each request to stderr, as `oss.verbosity = "verbose"` does.
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
     }
 }
 
+/// A request to the local server. Each one runs in an `oss_request` span
+/// carrying its method, URL, and model, and logs its status, latency, and
+/// size at debug level, so `RUST_LOG=codex_ollama=debug` gives a timeline of
+/// the traffic with the server.
+struct TracedRequest {
+    builder: reqwest::RequestBuilder,
+    model: Option<String>,
+}
+
+impl TracedRequest {
+    fn json(mut self, body: &JsonValue) -> Self {
+        self.model = body
+            .get("model")
+            .and_then(JsonValue::as_str)
+            .map(str::to_string);
+        self.builder = self.builder.json(body);
+        self
+    }
+
+    async fn send(self) -> reqwest::Result<reqwest::Response> {
+        let (client, request) = self.builder.build_split();
+        let request = request?;
+        let request_bytes = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .map_or(0, <[u8]>::len);
+        let span = tracing::debug_span!(
+            "oss_request",
+            method = %request.method(),
+            url = %request.url(),
+            model = self.model.as_deref().unwrap_or_default(),
+        );
+        async move {
+            let started = std::time::Instant::now();
+            let result = client.execute(request).await;
+            let elapsed_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+            match &result {
+                // Streamed bodies have no length; their size is unknown here.
+                Ok(resp) => tracing::debug!(
+                    status = resp.status().as_u16(),
+                    elapsed_ms,
+                    request_bytes,
+                    response_bytes = resp.content_length(),
+                    "response"
+                ),
+                Err(err) => tracing::debug!(elapsed_ms, request_bytes, "request failed: {err}"),
+            }
+            result
+        }
+        .instrument(span)
+        .await
+    }
+}
+
+/// Builder of an [`OllamaClient`] for library consumers that have no Codex
+/// [`Config`], e.g. `OllamaClient::builder().base_url("http://gpu-box:1234/v1")`.
+/// The server is not contacted.
+#[derive(Debug, Clone, Default)]
+pub struct OllamaClientBuilder {
+    base_url: Option<String>,
+    openai_compatible: Option<bool>,
+    connection: OssConnection,
+    headers: Vec<(String, String)>,
+    bearer_token: Option<String>,
+    proxy: Option<String>,
+    verbose: bool,
+    download_rate_limit: Option<u64>,
+}
+
+impl OllamaClientBuilder {
+    /// Base URL of the server; one ending in `/v1` is spoken to through its
+    /// OpenAI-compatible API. Defaults to Ollama's `http://localhost:11434`.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Use the OpenAI-compatible API (LM Studio, llama.cpp, vLLM) or
+    /// Ollama's native one, whatever the base URL looks like.
+    pub fn openai_compatible(mut self, openai_compatible: bool) -> Self {
+        self.openai_compatible = Some(openai_compatible);
+        self
+    }
+
+    /// Longest wait for a connection to the server.
+    pub fn connect_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.connection.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Longest wait for the next bytes of a response.
+    pub fn read_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.connection.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Deadline of a whole request, response included. Model downloads can
+    /// take hours, so leave this unset for clients that pull models.
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.connection.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Send `name: value` with every request.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Authenticate every request with `Authorization: Bearer <token>`, as
+    /// servers behind an authenticating proxy expect.
+    pub fn bearer_auth(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Send all requests through the proxy at `url`. Without one, the usual
+    /// proxy environment variables apply.
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Echo each request and retry to stderr.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Average bytes per second model downloads may use.
+    pub fn download_rate_limit(mut self, bytes_per_second: u64) -> Self {
+        self.download_rate_limit = Some(bytes_per_second);
+        self
+    }
+
+    /// Build the client. Fails on an invalid header or proxy URL.
+    pub fn build(self) -> io::Result<OllamaClient> {
+        let base_url = self
+            .base_url
+            .unwrap_or_else(|| DEFAULT_OLLAMA_HOST_ROOT.to_string());
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .map_err(|err| invalid_input(format!("invalid header name '{name}': {err}")))?;
+            let value = reqwest::header::HeaderValue::from_str(value)
+                .map_err(|err| invalid_input(format!("invalid value of header {name}: {err}")))?;
+            headers.append(name, value);
+        }
+        if let Some(token) = &self.bearer_token {
+            let mut value = reqwest::header::HeaderValue::from_str(&format!("Bearer {token}"))
+                .map_err(|err| invalid_input(format!("invalid bearer token: {err}")))?;
+            value.set_sensitive(true);
+            headers.insert(reqwest::header::AUTHORIZATION, value);
+        }
+        let connection = OssConnection {
+            unix_socket: self
+                .connection
+                .unix_socket
+                .or_else(|| codex_core::unix_socket_base_url_path(&base_url)),
+            named_pipe: self
+                .connection
+                .named_pipe
+                .or_else(|| codex_core::named_pipe_base_url_path(&base_url)),
+            ..self.connection
+        };
+        let mut builder =
+            apply_oss_connection(http_client_builder(), &connection).default_headers(headers);
+        if let Some(proxy) = &self.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|err| invalid_input(format!("invalid proxy URL '{proxy}': {err}")))?;
+            builder = builder.proxy(proxy);
+        }
+        Ok(OllamaClient {
+            client: builder.build().map_err(io::Error::other)?,
+            host_root: base_url_to_host_root(&base_url),
+            uses_openai_compat: self
+                .openai_compatible
+                .unwrap_or_else(|| is_openai_compatible_base_url(&base_url)),
+            lmstudio: false,
+            verbose: self.verbose,
+            download_rate_limit: self.download_rate_limit,
+            audit_log: None,
+            trusted_lms_sha256: Vec::new(),
+            download_lock_dir: None,
+            accepted_licenses: None,
+            catalog: CatalogClient::default(),
+            licenses: Default::default(),
+            strict_local: false,
+        })
+    }
+}
+
+fn invalid_input(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::OssError;
 
     // Happy-path tests using a mock HTTP server; skip if sandbox network is disabled.
     #[tokio::test]
@@ -268,6 +1364,430 @@ mod tests {
         assert!(models.contains(&"mistral".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_fetch_openai_model_ids_accepts_compressed_listing() {
+        use std::io::Write;
+
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_fetch_openai_model_ids_accepts_compressed_listing",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let listing = serde_json::json!({
+            "object": "list",
+            "data": [ {"id": "qwen2.5-coder-7b"}, {"id": "gpt-oss-20b"} ]
+        });
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(listing.to_string().as_bytes())
+            .expect("compress listing");
+        let compressed = encoder.finish().expect("finish gzip stream");
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/v1/models"))
+            .and(wiremock::matchers::header_regex(
+                "accept-encoding",
+                "gzip.*zstd|zstd.*gzip",
+            ))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .insert_header("content-encoding", "gzip")
+                    .set_body_raw(compressed, "application/json"),
+            )
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::from_host_root(server.uri());
+        let ids = client
+            .fetch_openai_model_ids()
+            .await
+            .expect("fetch model ids");
+        assert_eq!(ids, vec!["qwen2.5-coder-7b", "gpt-oss-20b"]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_local_models_merges_loaded_state_and_context_length() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_fetch_local_models_merges_loaded_state_and_context_length",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/tags"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_raw(
+                    serde_json::json!({
+                        "models": [
+                            {"name": "gpt-oss:20b", "size": 100, "details": {"quantization_level": "MXFP4"}},
+                            {"name": "mistral"}
+                        ]
+                    })
+                    .to_string(),
+                    "application/json",
+                ),
+            )
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/ps"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(
+                serde_json::json!({"models": [{"name": "gpt-oss:20b"}]}).to_string(),
+                "application/json",
+            ))
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/show"))
+            .and(wiremock::matchers::body_partial_json(
+                serde_json::json!({"model": "gpt-oss:20b"}),
+            ))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(
+                serde_json::json!({"model_info": {"gptoss.context_length": 131072}}).to_string(),
+                "application/json",
+            ))
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::from_host_root(server.uri());
+        let models = client
+            .fetch_local_models()
+            .await
+            .expect("fetch local models");
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].name, "gpt-oss:20b");
+        assert!(models[0].loaded);
+        assert_eq!(models[0].context_length, Some(131072));
+        assert_eq!(models[0].quantization.as_deref(), Some("MXFP4"));
+        assert_eq!(models[1].name, "mistral");
+        assert!(!models[1].loaded);
+        assert_eq!(models[1].context_length, None);
+    }
+
+    #[tokio::test]
+    async fn test_embeddings_are_returned_in_input_order() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_embeddings_are_returned_in_input_order",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/embeddings"))
+            .and(wiremock::matchers::body_json(
+                serde_json::json!({"model": "nomic-embed-text", "input": ["a", "b"]}),
+            ))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_raw(
+                    serde_json::json!({"data": [
+                        {"index": 1, "embedding": [0.5, 0.25]},
+                        {"index": 0, "embedding": [1.0, 0.0]},
+                    ]})
+                    .to_string(),
+                    "application/json",
+                ),
+            )
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::from_host_root(server.uri());
+        let inputs = vec!["a".to_string(), "b".to_string()];
+        let embeddings = client
+            .embeddings("nomic-embed-text", &inputs)
+            .await
+            .expect("embeddings");
+        assert_eq!(embeddings, vec![vec![1.0, 0.0], vec![0.5, 0.25]]);
+    }
+
+    #[tokio::test]
+    async fn test_touch_model_sends_no_prompt() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_touch_model_sends_no_prompt",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/generate"))
+            .and(wiremock::matchers::body_json(
+                serde_json::json!({"model": "gpt-oss:20b"}),
+            ))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_raw(
+                    serde_json::json!({"model": "gpt-oss:20b", "response": "", "done": true})
+                        .to_string(),
+                    "application/json",
+                ),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::from_host_root(server.uri());
+        client
+            .touch_model("gpt-oss:20b")
+            .await
+            .expect("touch should succeed");
+    }
+
+    #[test]
+    fn test_builder_reads_the_api_from_the_base_url() {
+        let native = OllamaClient::builder().build().expect("build");
+        assert_eq!(native.host_root(), "http://localhost:11434");
+        assert!(!native.uses_openai_compat());
+
+        let compat = OllamaClient::builder()
+            .base_url("http://gpu-box:1234/v1")
+            .build()
+            .expect("build");
+        assert_eq!(compat.host_root(), "http://gpu-box:1234");
+        assert!(compat.uses_openai_compat());
+
+        let forced = OllamaClient::builder()
+            .base_url("http://gpu-box:8080")
+            .openai_compatible(true)
+            .build()
+            .expect("build");
+        assert!(forced.uses_openai_compat());
+
+        let err = OllamaClient::builder()
+            .header("bad header", "value")
+            .build()
+            .err()
+            .expect("invalid header name");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[tokio::test]
+    async fn test_builder_sends_headers_and_auth() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_builder_sends_headers_and_auth",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/tags"))
+            .and(wiremock::matchers::header("authorization", "Bearer secret"))
+            .and(wiremock::matchers::header("x-team", "ml"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(
+                serde_json::json!({"models": [ {"name": "gpt-oss:20b"} ]}).to_string(),
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::builder()
+            .base_url(server.uri())
+            .header("X-Team", "ml")
+            .bearer_auth("secret")
+            .timeout(std::time::Duration::from_secs(5))
+            .build()
+            .expect("build");
+        let models = client.fetch_models().await.expect("fetch models");
+        assert_eq!(models, vec!["gpt-oss:20b".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_unload_model_sends_zero_keep_alive() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_unload_model_sends_zero_keep_alive",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/generate"))
+            .and(wiremock::matchers::body_partial_json(
+                serde_json::json!({"model": "gpt-oss:20b", "keep_alive": 0}),
+            ))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(
+                serde_json::json!({"model": "gpt-oss:20b", "done": true, "done_reason": "unload"})
+                    .to_string(),
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/generate"))
+            .respond_with(wiremock::ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::from_host_root(server.uri());
+        client
+            .unload_model("gpt-oss:20b")
+            .await
+            .expect("unload gpt-oss:20b");
+        let err = client
+            .unload_model("missing")
+            .await
+            .expect_err("missing model should fail");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_pull_publishes_download_events() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_pull_publishes_download_events",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/pull"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(
+                "{\"status\":\"pulling manifest\"}\n{\"status\":\"success\"}\n",
+                "application/x-ndjson",
+            ))
+            .mount(&server)
+            .await;
+
+        let mut events = crate::subscribe_oss_events();
+        let client = OllamaClient::from_host_root(server.uri());
+        let mut reporter = crate::CliProgressReporter::with_verbosity(OssVerbosity::Quiet);
+        client
+            .pull_with_reporter("events-test", &mut reporter)
+            .await
+            .expect("pull");
+
+        // Other tests publish on the same bus concurrently.
+        let mut seen = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            match event {
+                OssEvent::DownloadStarted { model } if model == "events-test" => {
+                    seen.push("started".to_string());
+                }
+                OssEvent::DownloadProgress(progress) if progress.model == "events-test" => {
+                    seen.push(format!("{:?}", progress.phase));
+                }
+                OssEvent::DownloadFinished { model, error } if model == "events-test" => {
+                    assert_eq!(error, None);
+                    seen.push("finished".to_string());
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(
+            seen,
+            vec!["started", "Manifest", "Done", "Done", "finished"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_model_maps_not_found() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_delete_model_maps_not_found",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("DELETE"))
+            .and(wiremock::matchers::path("/api/delete"))
+            .and(wiremock::matchers::body_partial_json(
+                serde_json::json!({"model": "mistral"}),
+            ))
+            .respond_with(wiremock::ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("DELETE"))
+            .and(wiremock::matchers::path("/api/delete"))
+            .respond_with(wiremock::ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::from_host_root(server.uri());
+        client
+            .delete_model("mistral")
+            .await
+            .expect("delete mistral");
+        let err = client
+            .delete_model("missing")
+            .await
+            .expect_err("missing model should fail");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_generate_with_stats_reads_final_message() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_generate_with_stats_reads_final_message",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        let body = [
+            serde_json::json!({"response": "", "done": false}),
+            serde_json::json!({"response": "Hello", "done": false}),
+            serde_json::json!({
+                "response": "",
+                "done": true,
+                "prompt_eval_count": 10,
+                "prompt_eval_duration": 1_000_000_000u64,
+                "eval_count": 2,
+                "eval_duration": 500_000_000u64
+            }),
+        ]
+        .iter()
+        .map(|v| format!("{v}\n"))
+        .collect::<String>();
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/generate"))
+            .and(wiremock::matchers::body_partial_json(
+                serde_json::json!({"model": "mistral", "options": {"num_predict": 2}}),
+            ))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_raw(body, "application/x-ndjson"),
+            )
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::from_host_root(server.uri());
+        let options = GenerateOptions {
+            num_predict: Some(2),
+            ..Default::default()
+        };
+        let stats = client
+            .generate_with_stats("mistral", "Hi", &options)
+            .await
+            .expect("generate");
+        assert_eq!(stats.prompt_tokens, 10);
+        assert_eq!(stats.completion_tokens, 2);
+        assert_eq!(stats.prefill_tokens_per_sec(), Some(10.0));
+        assert_eq!(stats.generation_tokens_per_sec(), Some(4.0));
+        assert!(stats.time_to_first_token.is_some());
+    }
+
     #[tokio::test]
     async fn test_probe_server_happy_path_openai_compat_and_native() {
         if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
@@ -346,4 +1866,109 @@ mod tests {
             .expect("expected error");
         assert_eq!(OLLAMA_CONNECTION_ERROR, err.to_string());
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_socket_base_urls_talk_to_the_socket() {
+        use tokio::io::AsyncReadExt;
+        use tokio::io::AsyncWriteExt;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let socket = dir.path().join("server.sock");
+        let listener = tokio::net::UnixListener::bind(&socket).expect("bind socket");
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.expect("accept");
+            let mut request = vec![0; 4096];
+            let read = stream.read(&mut request).await.expect("read request");
+            let body = r#"{"data":[{"id":"qwen3-8b"}]}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream
+                .write_all(response.as_bytes())
+                .await
+                .expect("write response");
+            String::from_utf8_lossy(&request[..read]).to_string()
+        });
+
+        let client = OllamaClient::builder()
+            .base_url(format!("unix://{}", socket.display()))
+            .build()
+            .expect("build");
+        let models = client.fetch_openai_model_ids().await.expect("list models");
+
+        assert_eq!(models, vec!["qwen3-8b".to_string()]);
+        let request = server.await.expect("server");
+        assert!(request.starts_with("GET /v1/models HTTP/1.1"), "{request}");
+    }
+
+    #[cfg(windows)]
+    #[tokio::test]
+    async fn named_pipe_base_urls_talk_to_the_pipe() {
+        use tokio::io::AsyncReadExt;
+        use tokio::io::AsyncWriteExt;
+
+        let name = format!("codex-ollama-test-{}", std::process::id());
+        let mut pipe = tokio::net::windows::named_pipe::ServerOptions::new()
+            .first_pipe_instance(true)
+            .create(format!(r"\\.\pipe\{name}"))
+            .expect("create pipe");
+        let server = tokio::spawn(async move {
+            pipe.connect().await.expect("connect");
+            let mut request = vec![0; 4096];
+            let read = pipe.read(&mut request).await.expect("read request");
+            let body = r#"{"data":[{"id":"qwen3-8b"}]}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            pipe.write_all(response.as_bytes())
+                .await
+                .expect("write response");
+            String::from_utf8_lossy(&request[..read]).to_string()
+        });
+
+        let client = OllamaClient::builder()
+            .base_url(format!("npipe://./pipe/{name}"))
+            .build()
+            .expect("build");
+        let models = client.fetch_openai_model_ids().await.expect("list models");
+
+        assert_eq!(models, vec!["qwen3-8b".to_string()]);
+        let request = server.await.expect("server");
+        assert!(request.starts_with("GET /v1/models HTTP/1.1"), "{request}");
+    }
+
+    #[tokio::test]
+    async fn licenses_are_looked_up_once_per_client() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping licenses_are_looked_up_once_per_client",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/models/org/gated-GGUF"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(
+                serde_json::json!({"cardData": {"license": "other"}}).to_string(),
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut client = OllamaClient::from_host_root("http://localhost:11434");
+        client.catalog = CatalogClient::new(server.uri());
+        let model = "hf.co/org/gated-GGUF:Q4_K_M";
+        for _ in 0..2 {
+            let err = ensure_license_accepted(&client, &[], model)
+                .await
+                .expect_err("the license is not accepted");
+            assert!(matches!(err, OssError::LicenseNotAccepted { .. }), "{err}");
+        }
+    }
 }
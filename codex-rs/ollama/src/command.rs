@@ -0,0 +1,246 @@
+//! The one place the OSS path runs other programs, such as `lms load` or
+//! `ollama serve`. Every invocation is logged with its full argv and
+//! recorded in the audit log (see [`crate::audit`]).
+//!
+//! In print-only mode (`codex oss --dry-run ...`) the commands are printed
+//! to stdout instead of run, and treated as if they succeeded; so are model
+//! pulls (see [`crate::OllamaClient::pull_with_reporter`]). Under a
+//! Codex sandbox (`CODEX_SANDBOX` is set, e.g. when the agent itself runs
+//! `codex oss serve start`), starting a background server is refused: it
+//! would outlive the sandboxed command that asked for it.
+//!
+//! Read-only probes of the host (`sysctl`, `vm_stat`, `reg query`,
+//! `tasklist`) do not change anything and are run directly.
+
+use chrono::Utc;
+#[cfg(feature = "ollama")]
+use codex_core::spawn::CODEX_SANDBOX_ENV_VAR;
+#[cfg(feature = "ollama")]
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+use std::process::ExitStatus;
+use std::process::Output;
+use std::process::Stdio;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use crate::audit;
+use crate::audit::AuditAction;
+use crate::audit::AuditEntry;
+
+static PRINT_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Print external commands instead of running them, for the rest of the
+/// process.
+pub fn set_print_only(print_only: bool) {
+    PRINT_ONLY.store(print_only, Ordering::Relaxed);
+}
+
+/// Whether external commands are printed instead of run.
+pub fn print_only() -> bool {
+    PRINT_ONLY.load(Ordering::Relaxed)
+}
+
+/// `program` and `args` as one line a shell would run the same way.
+pub(crate) fn format_argv(program: &Path, args: &[&str]) -> String {
+    std::iter::once(program.to_string_lossy().as_ref())
+        .chain(args.iter().copied())
+        .map(quote)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Run `program` with `args` to completion, recording the invocation in the
+/// audit log at `audit_log` when there is one.
+pub(crate) async fn run_command(
+    audit_log: Option<&Path>,
+    program: &Path,
+    args: &[&str],
+) -> io::Result<Output> {
+    run_command_with(print_only(), audit_log, program, args).await
+}
+
+async fn run_command_with(
+    print_only: bool,
+    audit_log: Option<&Path>,
+    program: &Path,
+    args: &[&str],
+) -> io::Result<Output> {
+    let argv = format_argv(program, args);
+    if print_only {
+        println!("{argv}");
+        return Ok(Output {
+            status: ExitStatus::default(),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        });
+    }
+    tracing::info!("running {argv}");
+    let started_at = Utc::now();
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .await;
+    if let Some(audit_log) = audit_log {
+        let error = match &output {
+            Ok(output) if !output.status.success() => {
+                Some(String::from_utf8_lossy(&output.stderr).trim().to_string())
+            }
+            Ok(_) => None,
+            Err(err) => Some(err.to_string()),
+        };
+        audit::record(
+            audit_log,
+            &AuditEntry {
+                started_at,
+                finished_at: Utc::now(),
+                action: AuditAction::Command {
+                    program: program.to_path_buf(),
+                    args: args.iter().map(ToString::to_string).collect(),
+                    pid: None,
+                    exit_code: output.as_ref().ok().and_then(|output| output.status.code()),
+                },
+                error,
+            },
+        );
+    }
+    output
+}
+
+/// Start `program` in the background, detached from this process group,
+/// with its output appended to the file at `log`. Returns its pid, or `None` when the
+/// command was only printed.
+// The program is intentionally left running after Codex exits; it is reaped
+// by whoever stops it (or init) rather than waited on here.
+#[cfg(feature = "ollama")]
+#[allow(clippy::zombie_processes)]
+pub(crate) fn spawn_background(
+    audit_log: &Path,
+    program: &Path,
+    args: &[&str],
+    env: &[(&'static str, String)],
+    log: &Path,
+) -> io::Result<Option<u32>> {
+    let argv = format_argv(program, args);
+    if print_only() {
+        let env: Vec<String> = env
+            .iter()
+            .map(|(key, value)| format!("{key}={}", quote(value)))
+            .collect();
+        println!("{}", [env.join(" "), argv].join(" ").trim_start());
+        return Ok(None);
+    }
+    ensure_background_allowed(std::env::var_os(CODEX_SANDBOX_ENV_VAR).is_some(), &argv)?;
+    tracing::info!("starting {argv} in the background");
+    if let Some(parent) = log.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let log = OpenOptions::new().create(true).append(true).open(log)?;
+
+    let mut command = std::process::Command::new(program);
+    command
+        .args(args)
+        .envs(env.iter().map(|(key, value)| (*key, value)))
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+    // Detach from our process group so Ctrl-C in the terminal that ran
+    // `codex oss serve start` does not take the server down with it.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    let started_at = Utc::now();
+    let child = command.spawn();
+    audit::record(
+        audit_log,
+        &AuditEntry {
+            started_at,
+            finished_at: Utc::now(),
+            action: AuditAction::Command {
+                program: program.to_path_buf(),
+                args: args.iter().map(ToString::to_string).collect(),
+                pid: child.as_ref().ok().map(std::process::Child::id),
+                exit_code: None,
+            },
+            error: child.as_ref().err().map(ToString::to_string),
+        },
+    );
+    Ok(Some(child?.id()))
+}
+
+#[cfg(feature = "ollama")]
+fn ensure_background_allowed(sandboxed: bool, argv: &str) -> io::Result<()> {
+    if sandboxed {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "refusing to start `{argv}` from inside the Codex sandbox; start the server outside of it"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn argv_is_printed_for_a_shell() {
+        assert_eq!(
+            format_argv(Path::new("/usr/local/bin/lms"), &["load", "qwen/qwen3-8b"]),
+            "/usr/local/bin/lms load qwen/qwen3-8b"
+        );
+        assert_eq!(
+            format_argv(
+                Path::new("/Applications/LM Studio.app/lms"),
+                &["load", "it's", ""]
+            ),
+            r"'/Applications/LM Studio.app/lms' load 'it'\''s' ''"
+        );
+    }
+
+    #[tokio::test]
+    async fn print_only_commands_are_not_run_or_recorded() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let audit_log = audit::audit_log_path(dir.path());
+
+        let output = run_command_with(
+            true,
+            Some(&audit_log),
+            &dir.path().join("missing-lms"),
+            &["server", "start"],
+        )
+        .await
+        .expect("printed");
+
+        assert!(output.status.success());
+        assert!(!audit_log.exists());
+    }
+
+    #[cfg(feature = "ollama")]
+    #[test]
+    fn background_servers_are_refused_inside_the_sandbox() {
+        let err = ensure_background_allowed(true, "ollama serve").expect_err("sandboxed");
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(ensure_background_allowed(false, "ollama serve").is_ok());
+    }
+}
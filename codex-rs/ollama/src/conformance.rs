@@ -0,0 +1,544 @@
+//! Checks run by `codex oss conformance` against any OpenAI-compatible
+//! server, turning "my server doesn't work with Codex" into the list of
+//! features it is missing.
+//!
+//! The chat checks send what Codex sends over the Chat Completions API: a
+//! streamed request, with tools, asking for usage in a last chunk. Each
+//! response is judged the way Codex parses it, so a pass here means Codex
+//! sees the text, the tool call, or the token counts.
+
+use std::io;
+
+use codex_protocol::oss::CheckStatus;
+use codex_protocol::oss::ConformanceReport;
+use codex_protocol::oss::ReadinessCheck;
+use serde_json::Value as JsonValue;
+use serde_json::json;
+
+use crate::OllamaClient;
+
+/// Most tokens a model may generate for one check.
+const CONFORMANCE_MAX_TOKENS: u64 = 256;
+
+const CHAT_CHECKS: [&str; 3] = ["streaming", "usage", "tool_calls"];
+
+fn new_check(
+    name: &str,
+    status: CheckStatus,
+    detail: impl Into<String>,
+    remediation: Option<&str>,
+) -> ReadinessCheck {
+    ReadinessCheck {
+        name: name.to_string(),
+        status,
+        detail: detail.into(),
+        remediation: remediation.map(ToString::to_string),
+    }
+}
+
+/// Run every check against the server `client` talks to, using `model`, or
+/// else the first model the server lists. A check whose request fails
+/// counts as failed, so the report always covers every check.
+pub async fn run_conformance(client: &OllamaClient, model: Option<&str>) -> ConformanceReport {
+    let base_url = format!("{}/v1", client.host_root().trim_end_matches('/'));
+    let (models, model) = check_models(client.fetch_openai_model_ids().await, model);
+    let mut checks = vec![models];
+    let Some(model) = model else {
+        checks.extend(CHAT_CHECKS.iter().map(|name| {
+            new_check(
+                name,
+                CheckStatus::Skipped,
+                "no model to run against",
+                Some("Pass the model to test with `-m`."),
+            )
+        }));
+        return ConformanceReport {
+            base_url,
+            model: None,
+            checks,
+        };
+    };
+
+    match client
+        .chat_completion_events(&model, &text_request(&model))
+        .await
+    {
+        Ok(events) => {
+            checks.push(check_streaming(&events));
+            checks.push(check_usage(&events));
+        }
+        Err(err) => {
+            checks.push(request_failed("streaming", &err));
+            checks.push(new_check(
+                "usage",
+                CheckStatus::Skipped,
+                "the streamed request failed",
+                None,
+            ));
+        }
+    }
+    checks.push(
+        match client
+            .chat_completion_events(&model, &tool_request(&model))
+            .await
+        {
+            Ok(events) => check_tool_calls(&events),
+            Err(err) => request_failed("tool_calls", &err),
+        },
+    );
+
+    ConformanceReport {
+        base_url,
+        model: Some(model),
+        checks,
+    }
+}
+
+fn request_failed(name: &str, err: &io::Error) -> ReadinessCheck {
+    new_check(
+        name,
+        CheckStatus::Fail,
+        format!("POST /v1/chat/completions failed: {err}"),
+        Some(
+            "Serve `POST /v1/chat/completions` and accept `stream`, `stream_options`, and `tools`.",
+        ),
+    )
+}
+
+/// The `models` check and the model the chat checks should use.
+fn check_models(
+    listed: io::Result<Vec<String>>,
+    requested: Option<&str>,
+) -> (ReadinessCheck, Option<String>) {
+    let requested = requested.map(ToString::to_string);
+    match listed {
+        Err(err) => (
+            new_check(
+                "models",
+                CheckStatus::Fail,
+                format!("GET /v1/models failed: {err}"),
+                Some(
+                    "Point `--base-url` at the server's OpenAI-compatible API, usually ending in `/v1`.",
+                ),
+            ),
+            requested,
+        ),
+        Ok(ids) if ids.is_empty() => (
+            new_check(
+                "models",
+                CheckStatus::Fail,
+                "GET /v1/models lists no models",
+                Some("Download or load a model on the server."),
+            ),
+            requested,
+        ),
+        Ok(ids) => match requested {
+            Some(model) if !ids.contains(&model) => (
+                new_check(
+                    "models",
+                    CheckStatus::Warn,
+                    format!("'{model}' is not among the {} listed models", ids.len()),
+                    Some("Codex finds models by the ids `/v1/models` lists; pick one of those."),
+                ),
+                Some(model),
+            ),
+            Some(model) => (
+                new_check(
+                    "models",
+                    CheckStatus::Ok,
+                    format!("{} models listed", ids.len()),
+                    None,
+                ),
+                Some(model),
+            ),
+            None => (
+                new_check(
+                    "models",
+                    CheckStatus::Ok,
+                    format!("{} models listed, testing '{}'", ids.len(), ids[0]),
+                    None,
+                ),
+                ids.into_iter().next(),
+            ),
+        },
+    }
+}
+
+fn text_request(model: &str) -> JsonValue {
+    json!({
+        "model": model,
+        "messages": [
+            {"role": "user", "content": "Reply with the single word: ready"},
+        ],
+        "stream": true,
+        "stream_options": {"include_usage": true},
+        "temperature": 0,
+        "max_tokens": CONFORMANCE_MAX_TOKENS,
+    })
+}
+
+fn tool_request(model: &str) -> JsonValue {
+    json!({
+        "model": model,
+        "messages": [
+            {"role": "system", "content": "Do what the user asks by calling the `shell` tool; do not answer in prose."},
+            {"role": "user", "content": "List the files in the current directory."},
+        ],
+        "stream": true,
+        "stream_options": {"include_usage": true},
+        "temperature": 0,
+        "max_tokens": CONFORMANCE_MAX_TOKENS,
+        "tools": [{
+            "type": "function",
+            "function": {
+                "name": "shell",
+                "description": "Runs a shell command and returns its output.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "command": {"type": "array", "items": {"type": "string"}},
+                    },
+                    "required": ["command"],
+                    "additionalProperties": false,
+                },
+            },
+        }],
+    })
+}
+
+/// The JSON chunks of a stream, without the final `[DONE]`.
+fn parse_chunks(events: &[String]) -> Result<Vec<JsonValue>, String> {
+    events
+        .iter()
+        .filter(|data| data.as_str() != "[DONE]")
+        .map(|data| {
+            serde_json::from_str(data).map_err(|err| format!("a chunk is not valid JSON: {err}"))
+        })
+        .collect()
+}
+
+fn finish_reason(chunks: &[JsonValue]) -> Option<&str> {
+    chunks
+        .iter()
+        .filter_map(|chunk| chunk.pointer("/choices/0/finish_reason"))
+        .find_map(JsonValue::as_str)
+}
+
+fn check_streaming(events: &[String]) -> ReadinessCheck {
+    const NAME: &str = "streaming";
+    const REMEDIATION: &str = "Stream `\"stream\": true` responses as server-sent `data:` chunks with `choices[0].delta`, ending with a `finish_reason` and `data: [DONE]`.";
+    if events.is_empty() {
+        return new_check(
+            NAME,
+            CheckStatus::Fail,
+            "the response has no `data:` lines; Codex only streams",
+            Some(REMEDIATION),
+        );
+    }
+    let chunks = match parse_chunks(events) {
+        Ok(chunks) => chunks,
+        Err(err) => return new_check(NAME, CheckStatus::Fail, err, Some(REMEDIATION)),
+    };
+    let text: String = chunks
+        .iter()
+        .filter_map(|chunk| chunk.pointer("/choices/0/delta/content"))
+        .filter_map(JsonValue::as_str)
+        .collect();
+    if text.is_empty() {
+        return new_check(
+            NAME,
+            CheckStatus::Fail,
+            "no chunk has text in `choices[0].delta.content`",
+            Some(REMEDIATION),
+        );
+    }
+    let Some(reason) = finish_reason(&chunks) else {
+        return new_check(
+            NAME,
+            CheckStatus::Fail,
+            "no chunk sets `finish_reason`, so Codex never sees the answer end",
+            Some(REMEDIATION),
+        );
+    };
+    if events.last().map(String::as_str) != Some("[DONE]") {
+        return new_check(
+            NAME,
+            CheckStatus::Warn,
+            "the stream does not end with `data: [DONE]`",
+            Some(REMEDIATION),
+        );
+    }
+    new_check(
+        NAME,
+        CheckStatus::Ok,
+        format!(
+            "{} chunks, finish_reason \"{reason}\", ended by [DONE]",
+            chunks.len()
+        ),
+        None,
+    )
+}
+
+fn check_usage(events: &[String]) -> ReadinessCheck {
+    const NAME: &str = "usage";
+    let usage = parse_chunks(events).ok().and_then(|chunks| {
+        chunks
+            .into_iter()
+            .filter_map(|mut chunk| chunk.get_mut("usage").map(JsonValue::take))
+            .rfind(|usage| !usage.is_null())
+    });
+    let Some(usage) = usage else {
+        return new_check(
+            NAME,
+            CheckStatus::Warn,
+            "no chunk reports `usage`; Codex cannot show token counts or context left",
+            Some("Honor `stream_options.include_usage` with a last chunk carrying `usage`."),
+        );
+    };
+    let count = |field: &str| usage.get(field).and_then(JsonValue::as_u64);
+    match (count("prompt_tokens"), count("completion_tokens")) {
+        (Some(prompt), Some(completion)) => new_check(
+            NAME,
+            CheckStatus::Ok,
+            format!("{prompt} prompt and {completion} completion tokens"),
+            None,
+        ),
+        _ => new_check(
+            NAME,
+            CheckStatus::Warn,
+            "`usage` lacks `prompt_tokens` or `completion_tokens`",
+            Some("Report `prompt_tokens` and `completion_tokens` as integers in `usage`."),
+        ),
+    }
+}
+
+fn check_tool_calls(events: &[String]) -> ReadinessCheck {
+    const NAME: &str = "tool_calls";
+    const REMEDIATION: &str = "Stream tool calls as `choices[0].delta.tool_calls` fragments and finish them with `finish_reason: \"tool_calls\"`; a model without tool support cannot drive Codex.";
+    let chunks = match parse_chunks(events) {
+        Ok(chunks) => chunks,
+        Err(err) => return new_check(NAME, CheckStatus::Fail, err, Some(REMEDIATION)),
+    };
+    // Fragments are assembled the way Codex assembles them: the first name
+    // seen, and every `arguments` piece in order.
+    let mut name = None;
+    let mut arguments = String::new();
+    for call in chunks
+        .iter()
+        .filter_map(|chunk| chunk.pointer("/choices/0/delta/tool_calls/0/function"))
+    {
+        if name.is_none() {
+            name = call.get("name").and_then(JsonValue::as_str);
+        }
+        if let Some(fragment) = call.get("arguments").and_then(JsonValue::as_str) {
+            arguments.push_str(fragment);
+        }
+    }
+    let Some(name) = name else {
+        return new_check(
+            NAME,
+            CheckStatus::Fail,
+            "the model answered without calling the tool",
+            Some(REMEDIATION),
+        );
+    };
+    if let Err(err) = serde_json::from_str::<JsonValue>(&arguments) {
+        return new_check(
+            NAME,
+            CheckStatus::Fail,
+            format!("the arguments of `{name}` are not valid JSON: {err}"),
+            Some(REMEDIATION),
+        );
+    }
+    match finish_reason(&chunks) {
+        Some("tool_calls") => new_check(
+            NAME,
+            CheckStatus::Ok,
+            format!("called `{name}` with valid JSON arguments"),
+            None,
+        ),
+        other => new_check(
+            NAME,
+            CheckStatus::Fail,
+            format!(
+                "called `{name}` but finished with {}, so Codex drops the call",
+                other.map_or_else(|| "no finish_reason".to_string(), |r| format!("\"{r}\""))
+            ),
+            Some(REMEDIATION),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn events(chunks: &[JsonValue]) -> Vec<String> {
+        chunks
+            .iter()
+            .map(JsonValue::to_string)
+            .chain(["[DONE]".to_string()])
+            .collect()
+    }
+
+    fn delta(delta: JsonValue, finish_reason: Option<&str>) -> JsonValue {
+        json!({"choices": [{"index": 0, "delta": delta, "finish_reason": finish_reason}]})
+    }
+
+    #[test]
+    fn models_check_picks_the_first_listed_model() {
+        let (check, model) = check_models(Ok(vec!["qwen3-8b".into(), "gpt-oss-20b".into()]), None);
+        assert_eq!(check.status, CheckStatus::Ok);
+        assert_eq!(model.as_deref(), Some("qwen3-8b"));
+
+        let (check, model) = check_models(Ok(vec!["qwen3-8b".into()]), Some("llama3"));
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert_eq!(model.as_deref(), Some("llama3"));
+
+        let (check, model) = check_models(Err(io::Error::other("connection refused")), None);
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert_eq!(model, None);
+    }
+
+    #[test]
+    fn streamed_text_with_usage_passes() {
+        let events = events(&[
+            delta(json!({"role": "assistant", "content": "rea"}), None),
+            delta(json!({"content": "dy"}), Some("stop")),
+            json!({"choices": [], "usage": {"prompt_tokens": 12, "completion_tokens": 2}}),
+        ]);
+        assert_eq!(check_streaming(&events).status, CheckStatus::Ok);
+        assert_eq!(
+            check_usage(&events).detail,
+            "12 prompt and 2 completion tokens"
+        );
+    }
+
+    #[test]
+    fn gaps_in_streaming_and_usage_are_reported() {
+        let unfinished = events(&[delta(json!({"content": "ready"}), None)]);
+        assert_eq!(check_streaming(&unfinished).status, CheckStatus::Fail);
+        assert_eq!(check_usage(&unfinished).status, CheckStatus::Warn);
+
+        let not_streamed: Vec<String> = Vec::new();
+        assert_eq!(check_streaming(&not_streamed).status, CheckStatus::Fail);
+
+        let mut no_done = events(&[delta(json!({"content": "ready"}), Some("stop"))]);
+        no_done.pop();
+        assert_eq!(check_streaming(&no_done).status, CheckStatus::Warn);
+    }
+
+    #[test]
+    fn tool_call_fragments_are_assembled() {
+        let events = events(&[
+            delta(
+                json!({"tool_calls": [{"index": 0, "id": "call_1", "function": {"name": "shell", "arguments": "{\"command\": "}}]}),
+                None,
+            ),
+            delta(
+                json!({"tool_calls": [{"index": 0, "function": {"arguments": "[\"ls\"]}"}}]}),
+                None,
+            ),
+            delta(json!({}), Some("tool_calls")),
+        ]);
+        assert_eq!(
+            check_tool_calls(&events),
+            new_check(
+                "tool_calls",
+                CheckStatus::Ok,
+                "called `shell` with valid JSON arguments",
+                None,
+            )
+        );
+    }
+
+    #[test]
+    fn tool_calls_that_codex_would_drop_fail() {
+        let stopped = events(&[
+            delta(
+                json!({"tool_calls": [{"index": 0, "function": {"name": "shell", "arguments": "{\"command\": [\"ls\"]}"}}]}),
+                None,
+            ),
+            delta(json!({}), Some("stop")),
+        ]);
+        let check = check_tool_calls(&stopped);
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert_eq!(
+            check.detail,
+            "called `shell` but finished with \"stop\", so Codex drops the call"
+        );
+
+        let prose = events(&[delta(json!({"content": "Run `ls`."}), Some("stop"))]);
+        assert_eq!(check_tool_calls(&prose).status, CheckStatus::Fail);
+    }
+
+    #[tokio::test]
+    async fn runs_every_check_against_a_server() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping runs_every_check_against_a_server",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let sse = |chunks: &[JsonValue]| {
+            events(chunks)
+                .iter()
+                .map(|data| format!("data: {data}\n\n"))
+                .collect::<String>()
+        };
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/v1/models"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .set_body_json(json!({"object": "list", "data": [{"id": "qwen3-8b"}]})),
+            )
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .and(wiremock::matchers::body_string_contains("\"tools\""))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(
+                sse(&[delta(
+                    json!({"tool_calls": [{"index": 0, "function": {"name": "shell", "arguments": "{\"command\": [\"ls\"]}"}}]}),
+                    Some("tool_calls"),
+                )]),
+                "text/event-stream",
+            ))
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(
+                sse(&[delta(json!({"content": "ready"}), Some("stop"))]),
+                "text/event-stream",
+            ))
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::builder()
+            .base_url(format!("{}/v1", server.uri()))
+            .build()
+            .expect("client");
+        let report = run_conformance(&client, None).await;
+        let statuses: Vec<(&str, CheckStatus)> = report
+            .checks
+            .iter()
+            .map(|check| (check.name.as_str(), check.status))
+            .collect();
+        assert_eq!(report.model.as_deref(), Some("qwen3-8b"));
+        assert_eq!(
+            statuses,
+            vec![
+                ("models", CheckStatus::Ok),
+                ("streaming", CheckStatus::Ok),
+                ("usage", CheckStatus::Warn),
+                ("tool_calls", CheckStatus::Ok),
+            ]
+        );
+        assert!(report.passed());
+    }
+}
@@ -0,0 +1,66 @@
+//! The context window a local model runs with, which is often much smaller
+//! than the most the model supports.
+
+use std::collections::BTreeMap;
+
+use codex_core::config::Config;
+use codex_core::protocol_config_types::OssBackend;
+
+use crate::OllamaClient;
+
+/// Context window of `config.model` on the server behind the `oss` provider.
+/// On Ollama, the window the loaded model was allocated wins over a
+/// `num_ctx` set in its Modelfile, which wins over the most the model
+/// supports. `None` when the server does not tell.
+pub async fn detect_context_window(config: &Config) -> Option<u64> {
+    let client = OllamaClient::from_oss_provider(config).ok()?;
+    let model = config.model.as_str();
+    match config.oss.provider.unwrap_or(OssBackend::Ollama) {
+        OssBackend::Ollama => {
+            let loaded = client
+                .fetch_loaded_models()
+                .await
+                .ok()
+                .and_then(|models| models.into_iter().find(|m| m.name == model))
+                .and_then(|m| m.context_length);
+            if loaded.is_some() {
+                return loaded;
+            }
+            let configured = client
+                .fetch_model_parameters(model)
+                .await
+                .ok()
+                .and_then(|parameters| num_ctx(&parameters));
+            if configured.is_some() {
+                return configured;
+            }
+            client.fetch_model_details(model).await.ok()?.context_length
+        }
+        OssBackend::LmStudio | OssBackend::Generic if client.api_surfaces().await.lmstudio_v0 => {
+            client
+                .fetch_lmstudio_context_window(model)
+                .await
+                .ok()
+                .flatten()
+        }
+        OssBackend::LmStudio | OssBackend::Generic => None,
+    }
+}
+
+fn num_ctx(parameters: &BTreeMap<String, Vec<String>>) -> Option<u64> {
+    parameters.get("num_ctx")?.first()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_num_ctx_reads_the_modelfile_parameter() {
+        let mut parameters = BTreeMap::new();
+        assert_eq!(num_ctx(&parameters), None);
+        parameters.insert("num_ctx".to_string(), vec!["8192".to_string()]);
+        assert_eq!(num_ctx(&parameters), Some(8192));
+    }
+}
@@ -0,0 +1,153 @@
+//! Detection of local model servers installed or running on this machine.
+
+#[cfg(any(feature = "ollama", feature = "lmstudio"))]
+use codex_core::protocol_config_types::OssBackend;
+pub use codex_protocol::oss::DetectedBackend;
+use std::path::PathBuf;
+#[cfg(any(feature = "ollama", feature = "lmstudio"))]
+use std::time::Duration;
+
+#[cfg(any(feature = "ollama", feature = "lmstudio"))]
+use crate::OllamaClient;
+#[cfg(feature = "ollama")]
+use crate::find_ollama_binary;
+#[cfg(feature = "lmstudio")]
+use crate::lmstudio_install::app_candidates;
+#[cfg(feature = "lmstudio")]
+use crate::lmstudio_install::bundled_cli;
+#[cfg(feature = "lmstudio")]
+use crate::lmstudio_install::detect_lmstudio_install;
+
+#[cfg(any(feature = "ollama", feature = "lmstudio"))]
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Look for Ollama and LM Studio on their default ports and install paths.
+/// Backends this build has no support for are left out.
+pub async fn detect_backends() -> Vec<DetectedBackend> {
+    let (ollama, lmstudio) = tokio::join!(detect_ollama(), detect_lmstudio());
+    ollama.into_iter().chain(lmstudio).collect()
+}
+
+#[cfg(feature = "ollama")]
+async fn detect_ollama() -> Option<DetectedBackend> {
+    let host_root = "http://localhost:11434".to_string();
+    Some(DetectedBackend {
+        backend: OssBackend::Ollama,
+        install_path: find_ollama_binary(),
+        running: is_running(&host_root, false).await,
+        host_root,
+    })
+}
+
+#[cfg(not(feature = "ollama"))]
+async fn detect_ollama() -> Option<DetectedBackend> {
+    None
+}
+
+#[cfg(feature = "lmstudio")]
+async fn detect_lmstudio() -> Option<DetectedBackend> {
+    let host_root = "http://localhost:1234".to_string();
+    Some(DetectedBackend {
+        backend: OssBackend::LmStudio,
+        install_path: find_lmstudio(),
+        running: is_running(&host_root, true).await,
+        host_root,
+    })
+}
+
+#[cfg(not(feature = "lmstudio"))]
+async fn detect_lmstudio() -> Option<DetectedBackend> {
+    None
+}
+
+#[cfg(any(feature = "ollama", feature = "lmstudio"))]
+async fn is_running(host_root: &str, openai_compatible: bool) -> bool {
+    let client = OllamaClient::from_host_root(host_root).with_openai_compat(openai_compatible);
+    matches!(
+        tokio::time::timeout(PROBE_TIMEOUT, client.probe_server()).await,
+        Ok(Ok(()))
+    )
+}
+
+/// Locate LM Studio's `lms` CLI or, failing that, the desktop app.
+#[cfg(feature = "lmstudio")]
+fn find_lmstudio() -> Option<PathBuf> {
+    detect_lmstudio_install().and_then(|install| install.cli.or(install.app))
+}
+
+/// Locate LM Studio's `lms` CLI on `PATH` or where LM Studio installs it.
+/// The path is run as the program itself, never through a shell, so
+/// install paths with spaces such as `C:\Program Files\LM Studio` work.
+#[cfg(feature = "lmstudio")]
+pub(crate) fn find_lms_cli() -> Option<PathBuf> {
+    if let Ok(path) = which::which("lms") {
+        return Some(path);
+    }
+    lms_install_locations()
+        .into_iter()
+        .find(|path| path.is_file())
+}
+
+/// A build without LM Studio support never runs it.
+#[cfg(not(feature = "lmstudio"))]
+pub(crate) fn find_lms_cli() -> Option<PathBuf> {
+    None
+}
+
+#[cfg(all(feature = "lmstudio", not(target_os = "windows")))]
+fn lms_install_locations() -> Vec<PathBuf> {
+    let home = dirs::home_dir().map(|home| {
+        [
+            home.join(".lmstudio").join("bin").join("lms"),
+            home.join(".cache")
+                .join("lm-studio")
+                .join("bin")
+                .join("lms"),
+        ]
+    });
+    // Before `lms bootstrap` runs, the CLI is only inside the app bundle.
+    let bundled = app_candidates()
+        .into_iter()
+        .map(|(app, _)| bundled_cli(&app));
+    home.into_iter().flatten().chain(bundled).collect()
+}
+
+#[cfg(all(feature = "lmstudio", target_os = "windows"))]
+fn lms_install_locations() -> Vec<PathBuf> {
+    let installs: Vec<PathBuf> = app_candidates().into_iter().map(|(app, _)| app).collect();
+    windows_lms_locations(dirs::home_dir().as_deref(), &installs)
+}
+
+/// Where `lms.exe` is on Windows: the user's `.lmstudio\bin`, where LM
+/// Studio bootstraps the CLI, then the copy bundled with each install.
+#[cfg(all(feature = "lmstudio", any(target_os = "windows", test)))]
+fn windows_lms_locations(home: Option<&std::path::Path>, installs: &[PathBuf]) -> Vec<PathBuf> {
+    home.map(|home| home.join(".lmstudio").join("bin").join("lms.exe"))
+        .into_iter()
+        .chain(installs.iter().map(|install| bundled_cli(install)))
+        .collect()
+}
+
+#[cfg(all(test, feature = "lmstudio"))]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn the_bootstrapped_cli_comes_before_the_bundled_ones() {
+        let home = PathBuf::from("home");
+        let install = PathBuf::from("LM Studio");
+        assert_eq!(
+            windows_lms_locations(Some(&home), &[install.clone()]),
+            vec![
+                home.join(".lmstudio").join("bin").join("lms.exe"),
+                install
+                    .join("resources")
+                    .join("app")
+                    .join(".webpack")
+                    .join("lms.exe"),
+            ]
+        );
+        assert_eq!(windows_lms_locations(None, &[]), Vec::<PathBuf>::new());
+    }
+}
@@ -0,0 +1,117 @@
+//! Cross-process lock on a model download, so that two Codex sessions
+//! asking for the same missing model do not both pull it. The second one
+//! waits for the first to finish and then uses the model it downloaded.
+//!
+//! Each model has a lock file in the OSS state directory's `locks`
+//! (see [`crate::paths`]), held with an
+//! advisory exclusive lock for as long as the download runs. The lock is
+//! released when the holder exits, however it exits, so a crashed session
+//! never leaves a model locked.
+
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::fs::TryLockError;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+const LOCK_DIR: &str = "locks";
+
+/// Directory of the download locks for the OSS state in `state_dir`.
+pub(crate) fn download_lock_dir(state_dir: &Path) -> PathBuf {
+    state_dir.join(LOCK_DIR)
+}
+
+/// Held while this process downloads a model; dropping it lets the next
+/// session in.
+#[derive(Debug)]
+pub(crate) struct DownloadLock {
+    _file: File,
+}
+
+impl DownloadLock {
+    /// Take the lock on `model`, or `None` when another process holds it.
+    pub(crate) fn try_acquire(lock_dir: &Path, model: &str) -> io::Result<Option<Self>> {
+        let file = open_lock_file(lock_dir, model)?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(Self { _file: file })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(err)) => Err(err),
+        }
+    }
+
+    /// Wait until the lock on `model` is free and take it.
+    pub(crate) async fn acquire(lock_dir: &Path, model: &str) -> io::Result<Self> {
+        let file = open_lock_file(lock_dir, model)?;
+        tokio::task::spawn_blocking(move || {
+            file.lock()?;
+            Ok(Self { _file: file })
+        })
+        .await
+        .map_err(io::Error::other)?
+    }
+}
+
+/// Path of the lock file for `model`. Characters that are not safe in a
+/// file name are replaced; two names that map to the same file merely wait
+/// for each other.
+pub(crate) fn lock_file_path(lock_dir: &Path, model: &str) -> PathBuf {
+    let name: String = model
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    lock_dir.join(format!("{name}.lock"))
+}
+
+fn open_lock_file(lock_dir: &Path, model: &str) -> io::Result<File> {
+    std::fs::create_dir_all(lock_dir)?;
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_file_path(lock_dir, model))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn lock_files_are_named_after_the_model() {
+        let dir = Path::new("locks");
+        assert_eq!(
+            lock_file_path(dir, "hf.co/unsloth/Qwen3-8B-GGUF:Q4_K_M"),
+            dir.join("hf.co_unsloth_Qwen3-8B-GGUF_Q4_K_M.lock")
+        );
+    }
+
+    #[tokio::test]
+    async fn a_second_download_of_a_model_waits_for_the_first() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let first = DownloadLock::try_acquire(dir.path(), "gpt-oss:20b")
+            .expect("lock")
+            .expect("free");
+
+        assert!(
+            DownloadLock::try_acquire(dir.path(), "gpt-oss:20b")
+                .expect("lock")
+                .is_none()
+        );
+        let other = DownloadLock::try_acquire(dir.path(), "qwen3:8b").expect("lock");
+        assert!(other.is_some());
+
+        let waiter = tokio::spawn({
+            let dir = dir.path().to_path_buf();
+            async move { DownloadLock::acquire(&dir, "gpt-oss:20b").await }
+        });
+        drop(first);
+        waiter.await.expect("join").expect("acquired after release");
+    }
+}
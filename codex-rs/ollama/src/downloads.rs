@@ -0,0 +1,444 @@
+//! Model downloads that outlive the request that started them.
+//!
+//! The [`DownloadManager`] pulls one model at a time, in the order the
+//! downloads were started, and lets any frontend in the process list,
+//! pause, resume, and cancel them. Pausing or cancelling drops the pull,
+//! which stops the server's download; pulling again resumes it from the
+//! layers already on disk.
+//!
+//! Downloads Codex starts on its own, e.g. while preparing `--oss`, go
+//! through [`DownloadManager::global`], so they can be managed too.
+
+use std::io;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use codex_protocol::oss::DownloadState;
+use codex_protocol::oss::DownloadStatus;
+use codex_protocol::oss::ModelDownloadProgress;
+use codex_protocol::oss::OssEvent;
+use tokio::sync::broadcast;
+use tokio::sync::watch;
+
+use crate::JsonProgressReporter;
+use crate::OllamaClient;
+use crate::PullEvent;
+use crate::PullProgressReporter;
+use crate::events::publish;
+
+const EVENT_CAPACITY: usize = 256;
+
+static GLOBAL: LazyLock<DownloadManager> = LazyLock::new(DownloadManager::new);
+
+/// Handle to a queue of model downloads.
+#[derive(Clone, Default)]
+pub struct DownloadManager {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    next_id: u64,
+    /// Pending downloads, oldest first.
+    downloads: Vec<Download>,
+    worker_running: bool,
+}
+
+struct Download {
+    id: u64,
+    model: String,
+    client: Arc<OllamaClient>,
+    state: DownloadState,
+    progress: Option<ModelDownloadProgress>,
+    control: watch::Sender<Control>,
+    /// The server's pull events, for [`DownloadManager::download`].
+    events: broadcast::Sender<PullEvent>,
+    /// Set once the download ends, to its error when it failed.
+    done: watch::Sender<Option<Result<(), String>>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Run,
+    Pause,
+    Cancel,
+}
+
+struct Job {
+    id: u64,
+    model: String,
+    client: Arc<OllamaClient>,
+    control: watch::Receiver<Control>,
+    events: broadcast::Sender<PullEvent>,
+}
+
+/// Records the progress of the running download and passes its events on.
+struct Tracker<F: FnMut(&ModelDownloadProgress) -> io::Result<()>> {
+    progress: JsonProgressReporter<F>,
+    events: broadcast::Sender<PullEvent>,
+}
+
+impl<F: FnMut(&ModelDownloadProgress) -> io::Result<()>> PullProgressReporter for Tracker<F> {
+    fn on_event(&mut self, event: &PullEvent) -> io::Result<()> {
+        self.progress.on_event(event)?;
+        // Nobody may be listening.
+        let _ = self.events.send(event.clone());
+        Ok(())
+    }
+}
+
+impl DownloadManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The manager shared by everything in this process.
+    pub fn global() -> &'static Self {
+        &GLOBAL
+    }
+
+    /// Queue a download of `model` with `client`, returning its id. A model
+    /// already pending on the same server is not queued twice; its id is
+    /// returned instead.
+    pub fn start(&self, client: OllamaClient, model: &str) -> u64 {
+        let Ok(mut state) = self.state.lock() else {
+            return 0;
+        };
+        if let Some(pending) = state.downloads.iter().find(|pending| {
+            pending.model == model && pending.client.host_root() == client.host_root()
+        }) {
+            return pending.id;
+        }
+        state.next_id += 1;
+        let id = state.next_id;
+        state.downloads.push(Download {
+            id,
+            model: model.to_string(),
+            client: Arc::new(client),
+            state: DownloadState::Queued,
+            progress: None,
+            control: watch::Sender::new(Control::Run),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+            done: watch::Sender::new(None),
+        });
+        if !state.worker_running {
+            state.worker_running = true;
+            tokio::spawn(run_queue(self.state.clone()));
+        }
+        id
+    }
+
+    /// Every pending download, oldest first.
+    pub fn status(&self) -> Vec<DownloadStatus> {
+        let Ok(state) = self.state.lock() else {
+            return Vec::new();
+        };
+        state
+            .downloads
+            .iter()
+            .map(|download| DownloadStatus {
+                id: download.id,
+                model: download.model.clone(),
+                host_root: download.client.host_root().to_string(),
+                state: download.state,
+                progress: download.progress.clone(),
+            })
+            .collect()
+    }
+
+    /// Stop download `id` and drop it from the queue. Returns whether it
+    /// was pending.
+    pub fn cancel(&self, id: u64) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return false;
+        };
+        let Some(index) = state.downloads.iter().position(|d| d.id == id) else {
+            return false;
+        };
+        if state.downloads[index].state == DownloadState::Running {
+            // The worker notices, stops the pull, and finishes it.
+            state.downloads[index].control.send_replace(Control::Cancel);
+        } else {
+            finish_cancelled(state.downloads.remove(index));
+        }
+        true
+    }
+
+    /// Stop download `id` until it is resumed, letting the downloads queued
+    /// after it go first. Returns whether it was pending.
+    pub fn pause(&self, id: u64) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return false;
+        };
+        let Some(download) = state.downloads.iter_mut().find(|d| d.id == id) else {
+            return false;
+        };
+        match download.state {
+            DownloadState::Running => {
+                download.control.send_replace(Control::Pause);
+            }
+            DownloadState::Queued | DownloadState::Paused => download.state = DownloadState::Paused,
+        }
+        true
+    }
+
+    /// Queue paused download `id` again. Returns whether it was pending.
+    pub fn resume(&self, id: u64) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return false;
+        };
+        let Some(download) = state.downloads.iter_mut().find(|d| d.id == id) else {
+            return false;
+        };
+        if download.state == DownloadState::Paused {
+            download.state = DownloadState::Queued;
+        }
+        download.control.send_replace(Control::Run);
+        if !state.worker_running {
+            state.worker_running = true;
+            tokio::spawn(run_queue(self.state.clone()));
+        }
+        true
+    }
+
+    /// Download `model` with `client` through the queue, reporting the
+    /// server's progress to `reporter`, and wait for it to end. Joins the
+    /// pending download of the same model when there is one.
+    pub async fn download<R: PullProgressReporter + ?Sized>(
+        &self,
+        client: OllamaClient,
+        model: &str,
+        reporter: &mut R,
+    ) -> io::Result<()> {
+        let id = self.start(client, model);
+        let (mut events, mut done) = self.subscribe(id)?;
+        loop {
+            tokio::select! {
+                biased;
+                event = events.recv() => match event {
+                    Ok(event) => reporter.on_event(&event)?,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = done.wait_for(Option::is_some) => break,
+            }
+        }
+        // Events sent just before the end, e.g. the final success.
+        while let Ok(event) = events.try_recv() {
+            reporter.on_event(&event)?;
+        }
+        outcome(&mut done).await
+    }
+
+    /// Wait for download `id` to end. Fails with [`io::ErrorKind::NotFound`]
+    /// when it is not pending, and with its error when it failed or was
+    /// cancelled.
+    pub async fn wait(&self, id: u64) -> io::Result<()> {
+        let (_, mut done) = self.subscribe(id)?;
+        outcome(&mut done).await
+    }
+
+    fn subscribe(
+        &self,
+        id: u64,
+    ) -> io::Result<(
+        broadcast::Receiver<PullEvent>,
+        watch::Receiver<Option<Result<(), String>>>,
+    )> {
+        self.state
+            .lock()
+            .ok()
+            .and_then(|state| {
+                state
+                    .downloads
+                    .iter()
+                    .find(|d| d.id == id)
+                    .map(|d| (d.events.subscribe(), d.done.subscribe()))
+            })
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, format!("no pending download {id}"))
+            })
+    }
+}
+
+async fn outcome(done: &mut watch::Receiver<Option<Result<(), String>>>) -> io::Result<()> {
+    let result = done
+        .wait_for(Option::is_some)
+        .await
+        .map_err(|_| io::Error::other("the download manager went away"))?
+        .clone();
+    match result {
+        Some(Err(message)) => Err(io::Error::other(message)),
+        Some(Ok(())) | None => Ok(()),
+    }
+}
+
+/// Pull the queued downloads one after the other until none is left.
+async fn run_queue(state: Arc<Mutex<State>>) {
+    while let Some(mut job) = next_job(&state) {
+        let progress = JsonProgressReporter::new(job.model.clone(), {
+            let state = state.clone();
+            let id = job.id;
+            move |progress| {
+                if let Ok(mut state) = state.lock()
+                    && let Some(download) = state.downloads.iter_mut().find(|d| d.id == id)
+                {
+                    download.progress = Some(progress.clone());
+                }
+                Ok(())
+            }
+        });
+        let mut reporter = Tracker {
+            progress,
+            events: job.events.clone(),
+        };
+        let stopped = tokio::select! {
+            result = job.client.pull_with_reporter(&job.model, &mut reporter) => Err(result),
+            control = job.control.wait_for(|control| *control != Control::Run) => {
+                Ok(control.map_or(Control::Cancel, |control| *control))
+            }
+        };
+
+        let Ok(mut state) = state.lock() else {
+            return;
+        };
+        let Some(index) = state.downloads.iter().position(|d| d.id == job.id) else {
+            continue;
+        };
+        match stopped {
+            Ok(Control::Pause) => state.downloads[index].state = DownloadState::Paused,
+            Ok(Control::Run | Control::Cancel) => finish_cancelled(state.downloads.remove(index)),
+            Err(result) => {
+                let download = state.downloads.remove(index);
+                download
+                    .done
+                    .send_replace(Some(result.map_err(|err| err.to_string())));
+            }
+        }
+    }
+}
+
+/// Mark the oldest queued download running and hand it to the worker, or
+/// stop the worker when none is queued.
+fn next_job(state: &Mutex<State>) -> Option<Job> {
+    let mut state = state.lock().ok()?;
+    let Some(download) = state
+        .downloads
+        .iter_mut()
+        .find(|d| d.state == DownloadState::Queued)
+    else {
+        state.worker_running = false;
+        return None;
+    };
+    download.state = DownloadState::Running;
+    download.control.send_replace(Control::Run);
+    Some(Job {
+        id: download.id,
+        model: download.model.clone(),
+        client: download.client.clone(),
+        control: download.control.subscribe(),
+        events: download.events.clone(),
+    })
+}
+
+fn finish_cancelled(download: Download) {
+    let error = "download cancelled".to_string();
+    publish(OssEvent::DownloadFinished {
+        model: download.model,
+        error: Some(error.clone()),
+    });
+    download.done.send_replace(Some(Err(error)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    async fn wait_until(manager: &DownloadManager, id: u64, wanted: DownloadState) {
+        for _ in 0..500 {
+            if manager
+                .status()
+                .iter()
+                .any(|download| download.id == id && download.state == wanted)
+            {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("download {id} never became {wanted:?}");
+    }
+
+    fn states(manager: &DownloadManager) -> Vec<(String, DownloadState)> {
+        manager
+            .status()
+            .into_iter()
+            .map(|download| (download.model, download.state))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn downloads_run_in_order_and_can_be_paused_and_cancelled() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping downloads_run_in_order_and_can_be_paused_and_cancelled",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/pull"))
+            .and(wiremock::matchers::body_partial_json(
+                serde_json::json!({"model": "slow"}),
+            ))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .set_delay(Duration::from_secs(30))
+                    .set_body_raw("{\"status\":\"success\"}\n", "application/x-ndjson"),
+            )
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/pull"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(
+                "{\"status\":\"pulling manifest\"}\n{\"status\":\"success\"}\n",
+                "application/x-ndjson",
+            ))
+            .mount(&server)
+            .await;
+
+        let manager = DownloadManager::new();
+        let slow = manager.start(OllamaClient::from_host_root(server.uri()), "slow");
+        let fast = manager.start(OllamaClient::from_host_root(server.uri()), "fast");
+        assert_eq!(
+            manager.start(OllamaClient::from_host_root(server.uri()), "fast"),
+            fast
+        );
+        wait_until(&manager, slow, DownloadState::Running).await;
+        assert!(manager.pause(fast));
+        assert_eq!(
+            states(&manager),
+            vec![
+                ("slow".to_string(), DownloadState::Running),
+                ("fast".to_string(), DownloadState::Paused),
+            ]
+        );
+
+        assert!(manager.cancel(slow));
+        let err = manager.wait(slow).await.expect_err("cancelled");
+        assert_eq!(err.to_string(), "download cancelled");
+        assert_eq!(
+            states(&manager),
+            vec![("fast".to_string(), DownloadState::Paused)]
+        );
+
+        assert!(manager.resume(fast));
+        manager.wait(fast).await.expect("fast download");
+        assert_eq!(states(&manager), Vec::new());
+        assert!(!manager.cancel(fast));
+    }
+}
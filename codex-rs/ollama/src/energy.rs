@@ -0,0 +1,470 @@
+//! Estimates of the energy a session with the local model used, and of what
+//! its tokens would have cost from a cloud model.
+//!
+//! While the session runs, the GPU's power draw is read once a second with
+//! `nvidia-smi` when it is installed. Each request's energy is the average
+//! draw during the request times how long the server took to answer it; on
+//! machines whose GPU cannot be read, `oss.power_watts` stands in for the
+//! draw. Only the GPU is measured, so the figures leave out the rest of the
+//! machine. When the session ends, its totals are appended to a ledger in
+//! the OSS state directory, which `codex oss status` sums up.
+
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_core::config::Config;
+use codex_core::protocol::RequestTimingEvent;
+use codex_core::protocol::TokenUsage;
+use codex_protocol::oss::EnergyTotals;
+use codex_protocol::oss::PowerSource;
+use codex_protocol::oss::SessionEnergy;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::BufReader;
+
+use crate::oss_state_dir;
+
+const ENERGY_FILE: &str = "energy.jsonl";
+
+/// US dollars per million input tokens when `oss.cloud_input_price` is
+/// unset: the list price of gpt-5.
+pub const DEFAULT_CLOUD_INPUT_PRICE: f64 = 1.25;
+
+/// US dollars per million output tokens when `oss.cloud_output_price` is
+/// unset: the list price of gpt-5.
+pub const DEFAULT_CLOUD_OUTPUT_PRICE: f64 = 10.0;
+
+/// How often `nvidia-smi` reports the GPU's draw.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Power readings older than this cannot belong to a request still to be
+/// recorded and are dropped.
+const SAMPLE_RETENTION: Duration = Duration::from_secs(30 * 60);
+
+const MS_PER_HOUR: f64 = 3_600_000.0;
+
+/// File the sessions are appended to, one JSON object per line.
+pub fn energy_log_path(state_dir: &Path) -> PathBuf {
+    state_dir.join(ENERGY_FILE)
+}
+
+/// Accounts for the requests of one session with the local model.
+#[derive(Clone)]
+pub struct EnergyMeter {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    path: PathBuf,
+    model: String,
+    power_watts: Option<f64>,
+    input_price: f64,
+    output_price: f64,
+    sampler: Option<PowerSampler>,
+    totals: Mutex<MeterTotals>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct MeterTotals {
+    busy_ms: u64,
+    generation_ms: u64,
+    /// Tokens generated after the first one of each request, which
+    /// `generation_ms` was spent on.
+    timed_tokens: u64,
+    streamed_tokens: u64,
+    /// Busy time during which the GPU's draw was read, and the energy used
+    /// in it.
+    measured_ms: u64,
+    measured_wh: f64,
+}
+
+impl MeterTotals {
+    fn add(&mut self, timing: &RequestTimingEvent, watts: Option<f64>) {
+        self.busy_ms += timing.total_ms;
+        self.streamed_tokens += timing.output_tokens;
+        if let Some(generation_ms) = timing.generation_ms.filter(|ms| *ms > 0) {
+            self.generation_ms += generation_ms;
+            self.timed_tokens += timing.output_tokens.saturating_sub(1);
+        }
+        if let Some(watts) = watts {
+            self.measured_ms += timing.total_ms;
+            self.measured_wh += watts * timing.total_ms as f64 / MS_PER_HOUR;
+        }
+    }
+}
+
+impl EnergyMeter {
+    /// A meter for the session `config` starts, reading the GPU's draw when
+    /// it can.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                path: energy_log_path(&oss_state_dir(config)),
+                model: config.model.clone(),
+                power_watts: config.oss.power_watts,
+                input_price: config
+                    .oss
+                    .cloud_input_price
+                    .unwrap_or(DEFAULT_CLOUD_INPUT_PRICE),
+                output_price: config
+                    .oss
+                    .cloud_output_price
+                    .unwrap_or(DEFAULT_CLOUD_OUTPUT_PRICE),
+                sampler: PowerSampler::spawn(),
+                totals: Mutex::new(MeterTotals::default()),
+            }),
+        }
+    }
+
+    /// Account for the request `timing` reports, which just ended.
+    pub fn record(&self, timing: &RequestTimingEvent) {
+        let watts = self
+            .inner
+            .sampler
+            .as_ref()
+            .and_then(|sampler| sampler.average_since(Duration::from_millis(timing.total_ms)));
+        if let Ok(mut totals) = self.inner.totals.lock() {
+            totals.add(timing, watts);
+        }
+    }
+
+    /// Estimate the session so far, with `usage` the tokens the server
+    /// reported, and append it to the ledger. `None` when no request ran.
+    pub fn finish(&self, usage: &TokenUsage) -> Option<SessionEnergy> {
+        let totals = *self.inner.totals.lock().ok()?;
+        let session = estimate(
+            &self.inner.model,
+            &totals,
+            usage,
+            self.inner.power_watts,
+            (self.inner.input_price, self.inner.output_price),
+        )?;
+        if let Err(err) = append_session(&self.inner.path, &session) {
+            tracing::warn!("recording the session's energy failed: {err}");
+        }
+        Some(session)
+    }
+}
+
+fn estimate(
+    model: &str,
+    totals: &MeterTotals,
+    usage: &TokenUsage,
+    power_watts: Option<f64>,
+    (input_price, output_price): (f64, f64),
+) -> Option<SessionEnergy> {
+    if totals.busy_ms == 0 {
+        return None;
+    }
+    let input_tokens = u64::try_from(usage.input_tokens).unwrap_or(0);
+    // Not every local server reports usage; then count the streamed tokens.
+    let output_tokens = u64::try_from(usage.output_tokens)
+        .ok()
+        .filter(|tokens| *tokens > 0)
+        .unwrap_or(totals.streamed_tokens);
+    let (average_watts, power_source) = if totals.measured_ms > 0 {
+        (
+            Some(totals.measured_wh * MS_PER_HOUR / totals.measured_ms as f64),
+            Some(PowerSource::Measured),
+        )
+    } else {
+        (power_watts, power_watts.map(|_| PowerSource::Configured))
+    };
+    Some(SessionEnergy {
+        model: model.to_string(),
+        ended_at: chrono::Utc::now().to_rfc3339(),
+        input_tokens,
+        output_tokens,
+        busy_ms: totals.busy_ms,
+        tokens_per_second: (totals.generation_ms > 0)
+            .then(|| totals.timed_tokens as f64 * 1000.0 / totals.generation_ms as f64),
+        average_watts,
+        power_source,
+        energy_wh: average_watts.map(|watts| watts * totals.busy_ms as f64 / MS_PER_HOUR),
+        cloud_cost_usd: (input_tokens as f64 * input_price + output_tokens as f64 * output_price)
+            / 1_000_000.0,
+    })
+}
+
+fn append_session(path: &Path, session: &SessionEnergy) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut line = serde_json::to_string(session).map_err(io::Error::other)?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+/// Totals of the sessions recorded in `state_dir`. Lines that do not parse
+/// are skipped.
+pub fn energy_totals(state_dir: &Path) -> EnergyTotals {
+    let contents = std::fs::read_to_string(energy_log_path(state_dir)).unwrap_or_default();
+    let mut totals = EnergyTotals::default();
+    for session in contents
+        .lines()
+        .filter_map(|line| serde_json::from_str::<SessionEnergy>(line).ok())
+    {
+        totals.sessions += 1;
+        totals.input_tokens += session.input_tokens;
+        totals.output_tokens += session.output_tokens;
+        match session.energy_wh {
+            Some(energy_wh) => totals.energy_wh += energy_wh,
+            None => totals.sessions_without_energy += 1,
+        }
+        totals.cloud_cost_usd += session.cloud_cost_usd;
+        totals.last_session = Some(session);
+    }
+    totals
+}
+
+/// One-line summary of `session`, shown when it ends.
+pub fn format_session_energy(session: &SessionEnergy) -> String {
+    let speed = session
+        .tokens_per_second
+        .map(|tps| format!(" at {tps:.1} tokens/s"))
+        .unwrap_or_default();
+    let energy = match (
+        session.energy_wh,
+        session.average_watts,
+        session.power_source,
+    ) {
+        (Some(energy_wh), Some(watts), Some(PowerSource::Measured)) => {
+            format!("about {energy_wh:.1} Wh (GPU measured at {watts:.0} W)")
+        }
+        (Some(energy_wh), Some(watts), _) => {
+            format!("about {energy_wh:.1} Wh (assuming {watts:.0} W)")
+        }
+        _ => "energy not measured (set oss.power_watts)".to_string(),
+    };
+    format!(
+        "Local inference: {} output tokens{speed}, {energy}; ${:.2} at cloud prices",
+        session.output_tokens, session.cloud_cost_usd
+    )
+}
+
+/// Reads the GPU's total draw from a long-running `nvidia-smi`.
+struct PowerSampler {
+    samples: Arc<Mutex<VecDeque<(Instant, f64)>>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl PowerSampler {
+    /// `None` without `nvidia-smi` or outside a Tokio runtime.
+    fn spawn() -> Option<Self> {
+        let program = which::which("nvidia-smi").ok()?;
+        let runtime = tokio::runtime::Handle::try_current().ok()?;
+        let samples = Arc::new(Mutex::new(VecDeque::new()));
+        let task = runtime.spawn(run_sampler(program, Arc::clone(&samples)));
+        Some(Self { samples, task })
+    }
+
+    /// Average draw over the last `window`, or `None` without a reading in
+    /// it. A request shorter than the sampling interval uses the latest
+    /// reading.
+    fn average_since(&self, window: Duration) -> Option<f64> {
+        let samples = self.samples.lock().ok()?;
+        let since = Instant::now().checked_sub(window.max(SAMPLE_INTERVAL * 2))?;
+        let readings: Vec<f64> = samples
+            .iter()
+            .filter(|(at, _)| *at >= since)
+            .map(|(_, watts)| *watts)
+            .collect();
+        (!readings.is_empty()).then(|| readings.iter().sum::<f64>() / readings.len() as f64)
+    }
+}
+
+impl Drop for PowerSampler {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn run_sampler(program: PathBuf, samples: Arc<Mutex<VecDeque<(Instant, f64)>>>) {
+    let child = tokio::process::Command::new(program)
+        .args([
+            "--query-gpu=index,power.draw",
+            "--format=csv,noheader,nounits",
+            "-lms",
+            &SAMPLE_INTERVAL.as_millis().to_string(),
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(err) => {
+            tracing::debug!("not measuring GPU power: {err}");
+            return;
+        }
+    };
+    let Some(stdout) = child.stdout.take() else {
+        return;
+    };
+    let mut lines = BufReader::new(stdout).lines();
+    let mut reading = GpuReading::default();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if let Some(watts) = reading.push(&line)
+            && let Ok(mut samples) = samples.lock()
+        {
+            let now = Instant::now();
+            samples.push_back((now, watts));
+            while samples
+                .front()
+                .is_some_and(|(at, _)| now.duration_since(*at) > SAMPLE_RETENTION)
+            {
+                samples.pop_front();
+            }
+        }
+    }
+}
+
+/// Sums the per-GPU lines of one `nvidia-smi` report. Each report starts
+/// with GPU 0, so a line for it completes the previous report.
+#[derive(Debug, Default)]
+struct GpuReading {
+    watts: Option<f64>,
+}
+
+impl GpuReading {
+    /// Add `line` ("<index>, <watts>"), returning the total of the report it
+    /// completed, if any. GPUs that do not report their draw ("[N/A]") are
+    /// left out.
+    fn push(&mut self, line: &str) -> Option<f64> {
+        let (index, watts) = line.split_once(',')?;
+        let completed = if index.trim() == "0" {
+            self.watts.take()
+        } else {
+            None
+        };
+        if let Ok(watts) = watts.trim().parse::<f64>() {
+            *self.watts.get_or_insert(0.0) += watts;
+        }
+        completed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn timing(total_ms: u64, generation_ms: u64, output_tokens: u64) -> RequestTimingEvent {
+        RequestTimingEvent {
+            generation_ms: Some(generation_ms),
+            output_tokens,
+            total_ms,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reports_from_several_gpus_are_summed() {
+        let mut reading = GpuReading::default();
+        assert_eq!(reading.push("0, 120.50"), None);
+        assert_eq!(reading.push("1, 80.25"), None);
+        assert_eq!(reading.push("2, [N/A]"), None);
+        assert_eq!(reading.push("0, 100.00"), Some(200.75));
+        assert_eq!(reading.push("1, 50.00"), None);
+        assert_eq!(reading.push("0, 90.00"), Some(150.0));
+    }
+
+    #[test]
+    fn measured_draw_is_preferred_over_the_configured_one() {
+        let mut totals = MeterTotals::default();
+        totals.add(&timing(3_600_000, 1_800_000, 1001), Some(200.0));
+        totals.add(&timing(3_600_000, 1_800_000, 1001), Some(100.0));
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            ..Default::default()
+        };
+
+        let session = estimate("gpt-oss:20b", &totals, &usage, Some(50.0), (1.25, 10.0))
+            .expect("requests ran");
+        assert_eq!(session.power_source, Some(PowerSource::Measured));
+        assert_eq!(session.average_watts, Some(150.0));
+        assert_eq!(session.energy_wh, Some(300.0));
+        assert_eq!(session.cloud_cost_usd, 2.25);
+        assert_eq!(
+            session.tokens_per_second,
+            Some(2000.0 * 1000.0 / 3_600_000.0)
+        );
+
+        let mut unmeasured = MeterTotals::default();
+        unmeasured.add(&timing(1_800_000, 1_000, 11), None);
+        let session = estimate(
+            "gpt-oss:20b",
+            &unmeasured,
+            &TokenUsage::default(),
+            Some(50.0),
+            (1.25, 10.0),
+        )
+        .expect("requests ran");
+        assert_eq!(session.power_source, Some(PowerSource::Configured));
+        assert_eq!(session.energy_wh, Some(25.0));
+        // Without usage from the server, the streamed tokens are counted.
+        assert_eq!(session.output_tokens, 11);
+
+        assert_eq!(
+            estimate(
+                "gpt-oss:20b",
+                &MeterTotals::default(),
+                &usage,
+                None,
+                (1.25, 10.0)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn ledger_totals_add_up_the_sessions() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let session = |energy_wh| SessionEnergy {
+            model: "gpt-oss:20b".to_string(),
+            ended_at: "2026-10-14T12:00:00+00:00".to_string(),
+            input_tokens: 1000,
+            output_tokens: 200,
+            busy_ms: 60_000,
+            tokens_per_second: Some(40.0),
+            average_watts: energy_wh.map(|_| 120.0),
+            power_source: energy_wh.map(|_| PowerSource::Measured),
+            energy_wh,
+            cloud_cost_usd: 0.5,
+        };
+        let path = energy_log_path(dir.path());
+        append_session(&path, &session(Some(2.0))).expect("append");
+        append_session(&path, &session(None)).expect("append");
+
+        assert_eq!(
+            energy_totals(dir.path()),
+            EnergyTotals {
+                sessions: 2,
+                input_tokens: 2000,
+                output_tokens: 400,
+                energy_wh: 2.0,
+                sessions_without_energy: 1,
+                cloud_cost_usd: 1.0,
+                last_session: Some(session(None)),
+            }
+        );
+        assert_eq!(
+            format_session_energy(&session(Some(2.0))),
+            "Local inference: 200 output tokens at 40.0 tokens/s, about 2.0 Wh (GPU measured at 120 W); $0.50 at cloud prices"
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Portable manifests of a local provider setup: `codex oss export` writes the
+//! backend, models, and their sampling parameters so that `codex oss import`
+//! can provision the same models on another machine.
+
+use codex_core::config::Config;
+use codex_core::protocol_config_types::OssBackend;
+use codex_protocol::oss::ExportedModel;
+use codex_protocol::oss::OSS_ENVIRONMENT_MANIFEST_VERSION;
+use codex_protocol::oss::OssEnvironmentManifest;
+use codex_protocol::oss::OssImportOutcome;
+use std::io;
+
+use crate::OllamaClient;
+use crate::PullProgressReporter;
+use crate::offline_error;
+use crate::registry::digests_match;
+
+/// Describe the models available on the server behind the `oss` provider.
+pub async fn export_environment(config: &Config) -> io::Result<OssEnvironmentManifest> {
+    let client = OllamaClient::try_from_oss_provider(config).await?;
+    Ok(OssEnvironmentManifest {
+        version: OSS_ENVIRONMENT_MANIFEST_VERSION,
+        provider: config.oss.provider,
+        default_model: config.model.clone(),
+        models: available_models(&client, config.oss.provider).await?,
+    })
+}
+
+/// Download the models of `manifest` that are missing from the server behind
+/// the `oss` provider. Only Ollama can download models; on other backends
+/// missing models are reported as unavailable.
+pub async fn import_environment(
+    config: &Config,
+    manifest: &OssEnvironmentManifest,
+    reporter: &mut dyn PullProgressReporter,
+) -> io::Result<OssImportOutcome> {
+    if manifest.version > OSS_ENVIRONMENT_MANIFEST_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "manifest version {} is newer than this Codex supports ({OSS_ENVIRONMENT_MANIFEST_VERSION}); upgrade Codex and try again.",
+                manifest.version
+            ),
+        ));
+    }
+    let backend = config.oss.provider;
+    let client = OllamaClient::try_from_oss_provider(config).await?;
+    let local = available_models(&client, backend).await?;
+    let can_pull = matches!(backend, Some(OssBackend::Ollama) | None);
+    let missing: Vec<&ExportedModel> = manifest
+        .models
+        .iter()
+        .filter(|model| !local.iter().any(|l| l.name == model.name))
+        .collect();
+    if can_pull && !missing.is_empty() && config.oss.offline {
+        return Err(offline_error("Downloading models"));
+    }
+
+    let mut outcome = OssImportOutcome {
+        present: Vec::new(),
+        pulled: Vec::new(),
+        unavailable: Vec::new(),
+        mismatched: Vec::new(),
+    };
+    for wanted in &manifest.models {
+        if let Some(found) = local.iter().find(|l| l.name == wanted.name) {
+            outcome.present.push(wanted.name.clone());
+            if !matches_manifest(wanted, found) {
+                outcome.mismatched.push(wanted.name.clone());
+            }
+        } else if can_pull {
+            client.pull_with_reporter(&wanted.name, reporter).await?;
+            outcome.pulled.push(wanted.name.clone());
+            let digest = client
+                .fetch_model_digests()
+                .await?
+                .into_iter()
+                .find(|(name, _)| name == &wanted.name)
+                .map(|(_, digest)| digest);
+            let pulled = ExportedModel {
+                name: wanted.name.clone(),
+                digest,
+                parameters: client.fetch_model_parameters(&wanted.name).await?,
+            };
+            if !matches_manifest(wanted, &pulled) {
+                outcome.mismatched.push(wanted.name.clone());
+            }
+        } else {
+            outcome.unavailable.push(wanted.name.clone());
+        }
+    }
+    Ok(outcome)
+}
+
+async fn available_models(
+    client: &OllamaClient,
+    backend: Option<OssBackend>,
+) -> io::Result<Vec<ExportedModel>> {
+    let mut models = match backend {
+        Some(OssBackend::LmStudio | OssBackend::Generic) => client
+            .fetch_openai_model_ids()
+            .await?
+            .into_iter()
+            .map(|name| ExportedModel {
+                name,
+                digest: None,
+                parameters: Default::default(),
+            })
+            .collect(),
+        Some(OssBackend::Ollama) | None => {
+            let mut models = Vec::new();
+            for (name, digest) in client.fetch_model_digests().await? {
+                let parameters = client.fetch_model_parameters(&name).await?;
+                models.push(ExportedModel {
+                    name,
+                    digest: Some(digest),
+                    parameters,
+                });
+            }
+            models
+        }
+    };
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(models)
+}
+
+/// Whether `local` is the revision and configuration recorded in the
+/// manifest. Values the manifest does not record are not compared.
+fn matches_manifest(wanted: &ExportedModel, local: &ExportedModel) -> bool {
+    let digest_matches = match (&wanted.digest, &local.digest) {
+        (Some(wanted), Some(local)) => digests_match(wanted, local),
+        _ => true,
+    };
+    digest_matches && (wanted.parameters.is_empty() || wanted.parameters == local.parameters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn model(digest: Option<&str>, parameters: &[(&str, &str)]) -> ExportedModel {
+        let mut map: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (name, value) in parameters {
+            map.entry(name.to_string())
+                .or_default()
+                .push(value.to_string());
+        }
+        ExportedModel {
+            name: "gpt-oss:20b".to_string(),
+            digest: digest.map(str::to_string),
+            parameters: map,
+        }
+    }
+
+    #[test]
+    fn test_matches_manifest_compares_recorded_values_only() {
+        let exported = model(Some("sha256:ABC"), &[("temperature", "0.7")]);
+        assert!(matches_manifest(
+            &exported,
+            &model(Some("abc"), &[("temperature", "0.7")])
+        ));
+        assert!(!matches_manifest(
+            &exported,
+            &model(Some("def"), &[("temperature", "0.7")])
+        ));
+        assert!(!matches_manifest(
+            &exported,
+            &model(Some("abc"), &[("temperature", "1.0")])
+        ));
+        assert!(matches_manifest(
+            &model(None, &[]),
+            &model(Some("def"), &[("temperature", "1.0")])
+        ));
+    }
+}
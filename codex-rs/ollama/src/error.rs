@@ -0,0 +1,133 @@
+//! Typed failures of the `--oss` startup path, so callers such as
+//! `codex exec` can tell the causes apart.
+
+use codex_core::error::Retriability;
+use std::io;
+
+/// Why preparing or using the local OSS provider failed.
+#[derive(Debug, thiserror::Error)]
+pub enum OssError {
+    /// The local server did not answer.
+    #[error("{0}")]
+    ServerUnreachable(String),
+
+    /// The model is not available locally and cannot be downloaded, either
+    /// because the backend cannot pull models or downloads are disabled.
+    #[error("{0}")]
+    ModelMissing(String),
+
+    /// The model lacks a capability the run needs, such as tool calling.
+    #[error("{0}")]
+    ModelUnsupported(String),
+
+    /// Downloading the model failed part way.
+    #[error("failed to download model '{model}': {source}")]
+    DownloadFailed {
+        model: String,
+        #[source]
+        source: io::Error,
+    },
+
+    /// The model's license is not listed in `oss.accepted_licenses`, so it
+    /// is not downloaded.
+    #[error(
+        "'{model}' is published under the {license} license, which is not listed in `oss.accepted_licenses`"
+    )]
+    LicenseNotAccepted {
+        model: String,
+        license: String,
+        /// Where the terms can be read.
+        url: Option<String>,
+    },
+
+    /// The server accepted the request but the model failed to respond.
+    #[error("generation failed: {0}")]
+    GenerationFailed(String),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+impl OssError {
+    /// Process exit code used by `codex exec --oss` for this failure, so
+    /// scripts can branch on the cause. Unclassified failures exit with 1.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            OssError::ServerUnreachable(_) => 10,
+            OssError::ModelMissing(_) => 11,
+            OssError::DownloadFailed { .. } => 12,
+            OssError::GenerationFailed(_) => 13,
+            OssError::ModelUnsupported(_) => 14,
+            OssError::LicenseNotAccepted { .. } => 15,
+            OssError::Io(_) => 1,
+        }
+    }
+
+    /// Whether trying again, after the backoff the core retry loop uses, may
+    /// succeed. A missing or unsuitable model is surfaced right away.
+    pub fn retriability(&self) -> Retriability {
+        match self {
+            OssError::ServerUnreachable(_)
+            | OssError::DownloadFailed { .. }
+            | OssError::GenerationFailed(_) => Retriability::Retriable,
+            OssError::ModelMissing(_)
+            | OssError::ModelUnsupported(_)
+            | OssError::LicenseNotAccepted { .. } => Retriability::NeedsUserAction,
+            OssError::Io(_) => Retriability::NonRetriable,
+        }
+    }
+}
+
+impl From<OssError> for io::Error {
+    fn from(err: OssError) -> Self {
+        match err {
+            OssError::Io(err) => err,
+            OssError::ModelMissing(message) => io::Error::new(io::ErrorKind::NotFound, message),
+            err @ OssError::LicenseNotAccepted { .. } => {
+                io::Error::new(io::ErrorKind::PermissionDenied, err.to_string())
+            }
+            other => io::Error::other(other.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let codes = [
+            OssError::ServerUnreachable(String::new()).exit_code(),
+            OssError::ModelMissing(String::new()).exit_code(),
+            OssError::DownloadFailed {
+                model: String::new(),
+                source: io::Error::other("x"),
+            }
+            .exit_code(),
+            OssError::GenerationFailed(String::new()).exit_code(),
+            OssError::ModelUnsupported(String::new()).exit_code(),
+            OssError::LicenseNotAccepted {
+                model: String::new(),
+                license: String::new(),
+                url: None,
+            }
+            .exit_code(),
+        ];
+        assert_eq!(codes, [10, 11, 12, 13, 14, 15]);
+        assert_eq!(OssError::Io(io::Error::other("x")).exit_code(), 1);
+    }
+
+    #[test]
+    fn test_only_a_missing_model_needs_the_user() {
+        assert_eq!(
+            OssError::ServerUnreachable(String::new()).retriability(),
+            Retriability::Retriable
+        );
+        assert_eq!(
+            OssError::ModelMissing(String::new()).retriability(),
+            Retriability::NeedsUserAction
+        );
+    }
+}
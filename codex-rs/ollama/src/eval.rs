@@ -0,0 +1,511 @@
+//! Coding-agent tasks run against a local model by `codex oss eval`, so a
+//! model can be judged usable with Codex before a session depends on it.
+//!
+//! Each task asks for one thing Codex relies on — a well-formed tool call, a
+//! patch in the `apply_patch` format, or a unified diff — and is scored by
+//! checking the answer mechanically: the call's arguments, or the file the
+//! patch or diff produces when applied to the task's fixture.
+
+use codex_apply_patch::APPLY_PATCH_TOOL_INSTRUCTIONS;
+use codex_apply_patch::Hunk;
+use codex_protocol::oss::EvalCategory;
+use codex_protocol::oss::EvalReport;
+use codex_protocol::oss::EvalTaskResult;
+use serde_json::Value as JsonValue;
+use serde_json::json;
+
+use crate::OllamaClient;
+
+/// Most tokens a model may generate for one task.
+const EVAL_MAX_TOKENS: u64 = 1024;
+
+const TOOL_CALL_INSTRUCTIONS: &str = "You are a coding agent working in a git repository. \
+Do what the user asks by calling the `shell` tool; do not answer in prose.";
+
+const DIFF_INSTRUCTIONS: &str = "You are a coding agent. Answer only with a unified diff, as \
+produced by `git diff`, that makes the requested change. Do not explain it.";
+
+/// A file a patch or diff task edits.
+struct Fixture {
+    path: &'static str,
+    contents: &'static str,
+}
+
+const GREET_PY: Fixture = Fixture {
+    path: "src/greet.py",
+    contents: "def greet(name):\n    return \"Hello, \" + name\n\n\nprint(greet(\"world\"))\n",
+};
+
+const CONFIG_TOML: Fixture = Fixture {
+    path: "config.toml",
+    contents: "[server]\nhost = \"127.0.0.1\"\nport = 8080\n\n[log]\nlevel = \"info\"\n",
+};
+
+enum Check {
+    /// The model calls `shell` with a command containing each string.
+    ToolCall {
+        command_contains: &'static [&'static str],
+    },
+    /// Applying the model's answer to `fixture` yields contents with each of
+    /// `contains` and none of `absent`.
+    Edit {
+        fixture: Fixture,
+        contains: &'static [&'static str],
+        absent: &'static [&'static str],
+    },
+}
+
+struct EvalTask {
+    id: &'static str,
+    category: EvalCategory,
+    request: &'static str,
+    check: Check,
+}
+
+/// The bundled suite, run in order.
+const EVAL_TASKS: &[EvalTask] = &[
+    EvalTask {
+        id: "tool_call_git_log",
+        category: EvalCategory::ToolCall,
+        request: "Show the five most recent commits.",
+        check: Check::ToolCall {
+            command_contains: &["git", "log", "5"],
+        },
+    },
+    EvalTask {
+        id: "tool_call_search",
+        category: EvalCategory::ToolCall,
+        request: "Find every line containing TODO in the files under src/.",
+        check: Check::ToolCall {
+            command_contains: &["TODO", "src"],
+        },
+    },
+    EvalTask {
+        id: "patch_rename_function",
+        category: EvalCategory::Patch,
+        request: "Rename the function `greet` to `welcome`, including where it is called.",
+        check: Check::Edit {
+            fixture: GREET_PY,
+            contains: &["def welcome(name):", "print(welcome(\"world\"))"],
+            absent: &["greet"],
+        },
+    },
+    EvalTask {
+        id: "patch_change_string",
+        category: EvalCategory::Patch,
+        request: "Change the greeting from \"Hello, \" to \"Hi, \".",
+        check: Check::Edit {
+            fixture: GREET_PY,
+            contains: &["return \"Hi, \" + name"],
+            absent: &["Hello"],
+        },
+    },
+    EvalTask {
+        id: "diff_change_value",
+        category: EvalCategory::Diff,
+        request: "Change the server port from 8080 to 9090.",
+        check: Check::Edit {
+            fixture: CONFIG_TOML,
+            contains: &["port = 9090", "host = \"127.0.0.1\""],
+            absent: &["8080"],
+        },
+    },
+    EvalTask {
+        id: "diff_add_line",
+        category: EvalCategory::Diff,
+        request: "Add `timeout = 30` to the [server] table, after the port.",
+        check: Check::Edit {
+            fixture: CONFIG_TOML,
+            contains: &["port = 8080\ntimeout = 30\n", "level = \"info\""],
+            absent: &[],
+        },
+    },
+];
+
+/// Run the bundled suite against `model`, one task after the other. A task
+/// whose request fails counts as failed, so the report always covers the
+/// whole suite.
+pub async fn run_eval(client: &OllamaClient, model: &str) -> EvalReport {
+    let mut tasks = Vec::with_capacity(EVAL_TASKS.len());
+    for task in EVAL_TASKS {
+        let body = request_body(task, model);
+        let (outcome, elapsed_ms) = match client.chat_completion(model, &body).await {
+            Ok((response, elapsed)) => (
+                score(task, &response),
+                u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+            ),
+            Err(err) => (Err(format!("request failed: {err}")), 0),
+        };
+        tasks.push(EvalTaskResult {
+            id: task.id.to_string(),
+            category: task.category,
+            passed: outcome.is_ok(),
+            failure: outcome.err(),
+            elapsed_ms,
+        });
+    }
+    EvalReport {
+        model: model.to_string(),
+        tasks,
+    }
+}
+
+fn request_body(task: &EvalTask, model: &str) -> JsonValue {
+    let (instructions, user) = match &task.check {
+        Check::ToolCall { .. } => (TOOL_CALL_INSTRUCTIONS.to_string(), task.request.to_string()),
+        Check::Edit { fixture, .. } => {
+            let instructions = match task.category {
+                EvalCategory::Patch => format!(
+                    "You are a coding agent. Answer only with a patch that makes the requested change; it is applied with `apply_patch`.\n\n{APPLY_PATCH_TOOL_INSTRUCTIONS}"
+                ),
+                EvalCategory::ToolCall | EvalCategory::Diff => DIFF_INSTRUCTIONS.to_string(),
+            };
+            let user = format!(
+                "{}\n\nContents of `{}`:\n```\n{}```",
+                task.request, fixture.path, fixture.contents
+            );
+            (instructions, user)
+        }
+    };
+    let mut body = json!({
+        "model": model,
+        "messages": [
+            {"role": "system", "content": instructions},
+            {"role": "user", "content": user},
+        ],
+        "stream": false,
+        "temperature": 0,
+        "max_tokens": EVAL_MAX_TOKENS,
+    });
+    if matches!(task.check, Check::ToolCall { .. }) {
+        body["tools"] = json!([shell_tool()]);
+    }
+    body
+}
+
+/// The `shell` tool as Codex offers it over Chat Completions.
+fn shell_tool() -> JsonValue {
+    json!({
+        "type": "function",
+        "function": {
+            "name": "shell",
+            "description": "Runs a shell command and returns its output.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "command": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "The command to execute",
+                    },
+                    "workdir": {
+                        "type": "string",
+                        "description": "The working directory to execute the command in",
+                    },
+                },
+                "required": ["command"],
+                "additionalProperties": false,
+            },
+        },
+    })
+}
+
+/// `Ok` when the answer in `response` passes `task`, otherwise why not.
+fn score(task: &EvalTask, response: &JsonValue) -> Result<(), String> {
+    let message = response
+        .pointer("/choices/0/message")
+        .ok_or_else(|| "the response has no message".to_string())?;
+    match &task.check {
+        Check::ToolCall { command_contains } => score_tool_call(message, command_contains),
+        Check::Edit {
+            fixture,
+            contains,
+            absent,
+        } => {
+            let answer = message
+                .get("content")
+                .and_then(JsonValue::as_str)
+                .unwrap_or_default();
+            let edited = match task.category {
+                EvalCategory::Patch => apply_patch_answer(answer, fixture)?,
+                EvalCategory::ToolCall | EvalCategory::Diff => apply_diff_answer(answer, fixture)?,
+            };
+            check_contents(&edited, contains, absent)
+        }
+    }
+}
+
+fn score_tool_call(message: &JsonValue, command_contains: &[&str]) -> Result<(), String> {
+    let call = message
+        .pointer("/tool_calls/0/function")
+        .ok_or_else(|| "the model answered without calling a tool".to_string())?;
+    let name = call
+        .get("name")
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default();
+    if name != "shell" {
+        return Err(format!("the model called `{name}` instead of `shell`"));
+    }
+    // Arguments arrive as a JSON string; a model that emits malformed JSON
+    // here cannot drive Codex's tools.
+    let arguments = call
+        .get("arguments")
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default();
+    let arguments: JsonValue = serde_json::from_str(arguments)
+        .map_err(|err| format!("the tool arguments are not valid JSON: {err}"))?;
+    let command: Vec<&str> = arguments
+        .get("command")
+        .and_then(JsonValue::as_array)
+        .ok_or_else(|| "the tool arguments have no `command` array".to_string())?
+        .iter()
+        .map(|arg| arg.as_str().ok_or("the `command` array holds a non-string"))
+        .collect::<Result<_, _>>()?;
+    let command = command.join(" ");
+    match command_contains
+        .iter()
+        .find(|needle| !command.contains(**needle))
+    {
+        Some(missing) => Err(format!("`{command}` does not contain `{missing}`")),
+        None => Ok(()),
+    }
+}
+
+/// Contents of `fixture` after applying the `apply_patch` patch in `answer`.
+fn apply_patch_answer(answer: &str, fixture: &Fixture) -> Result<String, String> {
+    let start = answer
+        .find("*** Begin Patch")
+        .ok_or_else(|| "the answer has no `*** Begin Patch`".to_string())?;
+    let end = answer[start..]
+        .find("*** End Patch")
+        .map(|end| start + end + "*** End Patch".len())
+        .ok_or_else(|| "the answer has no `*** End Patch`".to_string())?;
+    let patch = codex_apply_patch::parse_patch(&answer[start..end])
+        .map_err(|err| format!("the patch does not parse: {err}"))?;
+    let [
+        Hunk::UpdateFile {
+            path,
+            move_path: None,
+            chunks,
+        },
+    ] = patch.hunks.as_slice()
+    else {
+        return Err(format!("expected a single update of `{}`", fixture.path));
+    };
+    if !path.ends_with(fixture.path) {
+        return Err(format!(
+            "the patch updates `{}` instead of `{}`",
+            path.display(),
+            fixture.path
+        ));
+    }
+    let mut lines = fixture_lines(fixture);
+    let mut from = 0;
+    for chunk in chunks {
+        if let Some(context) = &chunk.change_context {
+            from = find_block(&lines, std::slice::from_ref(context), from)
+                .map(|index| index + 1)
+                .ok_or_else(|| format!("the context `{context}` is not in the file"))?;
+        }
+        from = replace_block(&mut lines, &chunk.old_lines, &chunk.new_lines, from)?;
+    }
+    Ok(join_lines(&lines))
+}
+
+/// Contents of `fixture` after applying the unified diff in `answer`.
+fn apply_diff_answer(answer: &str, fixture: &Fixture) -> Result<String, String> {
+    let target = answer
+        .lines()
+        .find_map(|line| line.strip_prefix("+++ "))
+        .ok_or_else(|| "the answer has no `+++` file header".to_string())?;
+    if !target.trim().ends_with(fixture.path) {
+        return Err(format!(
+            "the diff changes `{}` instead of `{}`",
+            target.trim(),
+            fixture.path
+        ));
+    }
+    let mut hunks: Vec<(Vec<String>, Vec<String>)> = Vec::new();
+    let mut in_hunk = false;
+    for line in answer.lines() {
+        if line.starts_with("@@") {
+            hunks.push((Vec::new(), Vec::new()));
+            in_hunk = true;
+            continue;
+        }
+        let Some((old, new)) = hunks.last_mut().filter(|_| in_hunk) else {
+            continue;
+        };
+        if line.starts_with("```") || line.starts_with("diff ") || line.starts_with("--- ") {
+            in_hunk = false;
+        } else if let Some(removed) = line.strip_prefix('-') {
+            old.push(removed.to_string());
+        } else if let Some(added) = line.strip_prefix('+') {
+            new.push(added.to_string());
+        } else if !line.starts_with('\\') {
+            // Context; some models drop the leading space of blank lines.
+            let context = line.strip_prefix(' ').unwrap_or(line).to_string();
+            old.push(context.clone());
+            new.push(context);
+        }
+    }
+    if hunks.is_empty() {
+        return Err("the diff has no hunks".to_string());
+    }
+    let mut lines = fixture_lines(fixture);
+    let mut from = 0;
+    for (old, new) in hunks {
+        from = replace_block(&mut lines, &old, &new, from)?;
+    }
+    Ok(join_lines(&lines))
+}
+
+fn fixture_lines(fixture: &Fixture) -> Vec<String> {
+    fixture.contents.lines().map(str::to_string).collect()
+}
+
+fn join_lines(lines: &[String]) -> String {
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    contents
+}
+
+/// First index at or after `from` where `block` starts, ignoring trailing
+/// whitespace.
+fn find_block(lines: &[String], block: &[String], from: usize) -> Option<usize> {
+    if block.is_empty() {
+        return Some(lines.len());
+    }
+    (from..=lines.len().saturating_sub(block.len())).find(|&start| {
+        lines[start..start + block.len()]
+            .iter()
+            .zip(block)
+            .all(|(line, expected)| line.trim_end() == expected.trim_end())
+    })
+}
+
+/// Replace `old` with `new` at or after `from` and return the index after
+/// the replacement. An empty `old` appends `new` to the file.
+fn replace_block(
+    lines: &mut Vec<String>,
+    old: &[String],
+    new: &[String],
+    from: usize,
+) -> Result<usize, String> {
+    let start = find_block(lines, old, from).ok_or_else(|| {
+        format!(
+            "the lines to replace are not in the file:\n{}",
+            old.join("\n")
+        )
+    })?;
+    lines.splice(start..start + old.len(), new.iter().cloned());
+    Ok(start + new.len())
+}
+
+fn check_contents(contents: &str, contains: &[&str], absent: &[&str]) -> Result<(), String> {
+    if let Some(missing) = contains.iter().find(|needle| !contents.contains(**needle)) {
+        return Err(format!("the edited file lacks `{missing}`"));
+    }
+    if let Some(left) = absent.iter().find(|needle| contents.contains(**needle)) {
+        return Err(format!("the edited file still contains `{left}`"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn task(id: &str) -> &'static EvalTask {
+        EVAL_TASKS
+            .iter()
+            .find(|task| task.id == id)
+            .expect("task exists")
+    }
+
+    fn text_response(content: &str) -> JsonValue {
+        json!({"choices": [{"message": {"role": "assistant", "content": content}}]})
+    }
+
+    fn tool_call_response(name: &str, arguments: &str) -> JsonValue {
+        json!({"choices": [{"message": {
+            "role": "assistant",
+            "tool_calls": [{"type": "function", "function": {"name": name, "arguments": arguments}}],
+        }}]})
+    }
+
+    #[test]
+    fn tool_calls_are_checked_for_name_arguments_and_command() {
+        let task = task("tool_call_git_log");
+        assert_eq!(
+            score(
+                task,
+                &tool_call_response("shell", r#"{"command": ["git", "log", "-5"]}"#)
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            score(
+                task,
+                &tool_call_response("shell", r#"{"command": ["bash", "-lc", "git log -n 5"]}"#)
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            score(task, &text_response("Run `git log -5`.")),
+            Err("the model answered without calling a tool".to_string())
+        );
+        assert!(
+            score(
+                task,
+                &tool_call_response("shell", r#"{"command": "git log"#)
+            )
+            .is_err_and(|err| err.starts_with("the tool arguments are not valid JSON"))
+        );
+        assert_eq!(
+            score(
+                task,
+                &tool_call_response("shell", r#"{"command": ["git", "status"]}"#)
+            ),
+            Err("`git status` does not contain `log`".to_string())
+        );
+    }
+
+    #[test]
+    fn patches_are_applied_to_the_fixture() {
+        let answer = "Here you go:\n```\n*** Begin Patch\n*** Update File: src/greet.py\n@@\n-def greet(name):\n+def welcome(name):\n     return \"Hello, \" + name\n@@\n-print(greet(\"world\"))\n+print(welcome(\"world\"))\n*** End Patch\n```";
+        assert_eq!(
+            score(task("patch_rename_function"), &text_response(answer)),
+            Ok(())
+        );
+
+        // Only the definition was renamed.
+        let partial = "*** Begin Patch\n*** Update File: src/greet.py\n@@\n-def greet(name):\n+def welcome(name):\n*** End Patch";
+        assert_eq!(
+            score(task("patch_rename_function"), &text_response(partial)),
+            Err("the edited file lacks `print(welcome(\"world\"))`".to_string())
+        );
+
+        assert_eq!(
+            score(
+                task("patch_change_string"),
+                &text_response("-    return \"Hello, \" + name\n+    return \"Hi, \" + name")
+            ),
+            Err("the answer has no `*** Begin Patch`".to_string())
+        );
+    }
+
+    #[test]
+    fn diffs_are_applied_to_the_fixture() {
+        let answer = "```diff\ndiff --git a/config.toml b/config.toml\n--- a/config.toml\n+++ b/config.toml\n@@ -1,3 +1,4 @@\n [server]\n host = \"127.0.0.1\"\n port = 8080\n+timeout = 30\n```";
+        assert_eq!(score(task("diff_add_line"), &text_response(answer)), Ok(()));
+
+        let stale =
+            "--- a/config.toml\n+++ b/config.toml\n@@ -3 +3 @@\n-port = 8000\n+port = 9090\n";
+        assert!(
+            score(task("diff_change_value"), &text_response(stale))
+                .is_err_and(|err| err.starts_with("the lines to replace are not in the file"))
+        );
+    }
+}
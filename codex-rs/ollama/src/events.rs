@@ -0,0 +1,55 @@
+//! Process-wide bus of [`OssEvent`]s.
+//!
+//! Starting the server, downloading, loading, and unloading models publish
+//! what they did here, wherever they were called from, so the TUI, `codex
+//! exec`, and the app server report the same events without each caller
+//! threading a callback through.
+
+use std::sync::LazyLock;
+
+use codex_protocol::oss::OssEvent;
+use tokio::sync::broadcast;
+
+/// Events a subscriber may fall behind by before it misses some.
+const CAPACITY: usize = 256;
+
+static EVENTS: LazyLock<broadcast::Sender<OssEvent>> =
+    LazyLock::new(|| broadcast::channel(CAPACITY).0);
+
+/// Receive every event published from now on. A receiver that falls more
+/// than a few hundred events behind gets `RecvError::Lagged` and skips ahead.
+pub fn subscribe_oss_events() -> broadcast::Receiver<OssEvent> {
+    EVENTS.subscribe()
+}
+
+pub(crate) fn publish(event: OssEvent) {
+    // Sending only fails when nobody is subscribed.
+    let _ = EVENTS.send(event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn subscribers_receive_events_published_after_they_subscribe() {
+        publish(OssEvent::ModelLoaded {
+            model: "before-subscribing".to_string(),
+        });
+        let mut events = subscribe_oss_events();
+        let loaded = OssEvent::ModelLoaded {
+            model: "bus-test".to_string(),
+        };
+        publish(loaded.clone());
+
+        // Other tests publish on the same bus concurrently.
+        let received = loop {
+            let event = events.recv().await.expect("event");
+            if matches!(&event, OssEvent::ModelLoaded { model } if model.starts_with("b")) {
+                break event;
+            }
+        };
+        assert_eq!(received, loaded);
+    }
+}
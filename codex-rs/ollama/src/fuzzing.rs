@@ -0,0 +1,27 @@
+//! Entry points for the fuzz targets in `codex-rs/fuzz` (feature
+//! `fuzzing`).
+
+use serde_json::Value as JsonValue;
+
+use crate::models;
+use crate::parser::pull_events_from_value;
+
+/// Decode `data` with every reader of model listings, model metadata, and
+/// pull progress, as if a local server had answered with it.
+pub fn models_json(data: &[u8]) {
+    let Ok(value) = serde_json::from_slice::<JsonValue>(data) else {
+        return;
+    };
+    let _ = models::local_models_from_tags(&value);
+    let _ = models::model_digests_from_tags(&value);
+    let _ = models::loaded_model_names_from_ps(&value);
+    let _ = models::loaded_models_from_ps(&value);
+    let _ = models::loaded_model_ids_from_lmstudio(&value);
+    let _ = models::local_models_from_lmstudio(&value);
+    let _ = models::context_window_from_lmstudio(&value, "gpt-oss-20b");
+    let _ = models::model_ids_from_openai_models(&value);
+    let _ = models::context_length_from_show(&value);
+    let _ = models::parameters_from_show(&value);
+    let _ = models::model_details_from_show("gpt-oss:20b", &value);
+    let _ = pull_events_from_value(&value);
+}
@@ -0,0 +1,169 @@
+use serde_json::Value as JsonValue;
+use std::time::Duration;
+
+/// Model options forwarded with a generate request. Unset fields fall back
+/// to the model's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GenerateOptions {
+    /// Maximum number of tokens to generate.
+    pub num_predict: Option<u32>,
+    /// Context window to allocate, in tokens.
+    pub num_ctx: Option<u32>,
+    /// How long the server should keep the model loaded afterwards, e.g.
+    /// "30m". Sent as a top-level request field rather than a model option.
+    pub keep_alive: Option<String>,
+}
+
+impl GenerateOptions {
+    pub(crate) fn to_json(&self) -> JsonValue {
+        let mut options = serde_json::Map::new();
+        if let Some(num_predict) = self.num_predict {
+            options.insert("num_predict".to_string(), num_predict.into());
+        }
+        if let Some(num_ctx) = self.num_ctx {
+            options.insert("num_ctx".to_string(), num_ctx.into());
+        }
+        JsonValue::Object(options)
+    }
+}
+
+/// Timing and token statistics for a single `/api/generate` call.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GenerationStats {
+    /// Number of prompt tokens evaluated (prefill).
+    pub prompt_tokens: u64,
+    /// Time spent evaluating the prompt.
+    pub prompt_eval_duration: Duration,
+    /// Number of tokens generated.
+    pub completion_tokens: u64,
+    /// Time spent generating tokens.
+    pub eval_duration: Duration,
+    /// Time spent loading the model into memory (zero when already loaded).
+    pub load_duration: Duration,
+    /// Wall-clock time from sending the request until the first token arrived.
+    pub time_to_first_token: Option<Duration>,
+    /// Wall-clock time for the whole request.
+    pub total_duration: Duration,
+}
+
+/// Complete answer of a model to a single prompt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelAnswer {
+    pub text: String,
+    /// Tokens generated, when the server reports usage.
+    pub completion_tokens: Option<u64>,
+    /// Wall-clock time for the whole request.
+    pub elapsed: Duration,
+}
+
+impl ModelAnswer {
+    /// Average speed over the whole request, including prompt processing.
+    pub fn tokens_per_sec(&self) -> Option<f64> {
+        tokens_per_sec(self.completion_tokens?, self.elapsed)
+    }
+}
+
+impl GenerationStats {
+    /// Prompt processing speed in tokens per second.
+    pub fn prefill_tokens_per_sec(&self) -> Option<f64> {
+        tokens_per_sec(self.prompt_tokens, self.prompt_eval_duration)
+    }
+
+    /// Generation speed in tokens per second.
+    pub fn generation_tokens_per_sec(&self) -> Option<f64> {
+        tokens_per_sec(self.completion_tokens, self.eval_duration)
+    }
+}
+
+fn tokens_per_sec(tokens: u64, duration: Duration) -> Option<f64> {
+    let secs = duration.as_secs_f64();
+    (tokens > 0 && secs > 0.0).then(|| tokens as f64 / secs)
+}
+
+/// Answer from a non-streaming `/v1/chat/completions` response body.
+pub(crate) fn answer_from_chat_completion(value: &JsonValue, elapsed: Duration) -> ModelAnswer {
+    let text = value
+        .pointer("/choices/0/message/content")
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default()
+        .to_string();
+    ModelAnswer {
+        text,
+        completion_tokens: value
+            .pointer("/usage/completion_tokens")
+            .and_then(JsonValue::as_u64),
+        elapsed,
+    }
+}
+
+/// Fill in the server-reported counters from the final (`"done": true`)
+/// message of a generate stream. Durations are reported in nanoseconds.
+pub(crate) fn apply_final_generate_message(stats: &mut GenerationStats, value: &JsonValue) {
+    let count = |key: &str| value.get(key).and_then(JsonValue::as_u64).unwrap_or(0);
+    stats.prompt_tokens = count("prompt_eval_count");
+    stats.prompt_eval_duration = Duration::from_nanos(count("prompt_eval_duration"));
+    stats.completion_tokens = count("eval_count");
+    stats.eval_duration = Duration::from_nanos(count("eval_duration"));
+    stats.load_duration = Duration::from_nanos(count("load_duration"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_apply_final_generate_message() {
+        let mut stats = GenerationStats::default();
+        apply_final_generate_message(
+            &mut stats,
+            &serde_json::json!({
+                "done": true,
+                "prompt_eval_count": 512,
+                "prompt_eval_duration": 500_000_000u64,
+                "eval_count": 128,
+                "eval_duration": 2_000_000_000u64,
+                "load_duration": 1_000_000u64
+            }),
+        );
+        assert_eq!(stats.prompt_tokens, 512);
+        assert_eq!(stats.completion_tokens, 128);
+        assert_eq!(stats.load_duration, Duration::from_millis(1));
+        assert_eq!(stats.prefill_tokens_per_sec(), Some(1024.0));
+        assert_eq!(stats.generation_tokens_per_sec(), Some(64.0));
+    }
+
+    #[test]
+    fn test_answer_from_chat_completion() {
+        let answer = answer_from_chat_completion(
+            &serde_json::json!({
+                "choices": [{"message": {"role": "assistant", "content": "Use a HashMap."}}],
+                "usage": {"prompt_tokens": 20, "completion_tokens": 40}
+            }),
+            Duration::from_secs(2),
+        );
+        assert_eq!(answer.text, "Use a HashMap.");
+        assert_eq!(answer.tokens_per_sec(), Some(20.0));
+    }
+
+    #[test]
+    fn test_generate_options_to_json_omits_unset() {
+        assert_eq!(GenerateOptions::default().to_json(), serde_json::json!({}));
+        let options = GenerateOptions {
+            num_predict: Some(128),
+            num_ctx: Some(4096),
+            keep_alive: Some("30m".to_string()),
+        };
+        assert_eq!(
+            options.to_json(),
+            serde_json::json!({"num_predict": 128, "num_ctx": 4096})
+        );
+    }
+
+    #[test]
+    fn test_tokens_per_sec_handles_zero() {
+        let stats = GenerationStats::default();
+        assert_eq!(stats.prefill_tokens_per_sec(), None);
+        assert_eq!(stats.generation_tokens_per_sec(), None);
+    }
+}
@@ -0,0 +1,130 @@
+//! Refuse to send prompts to a "local" server that is really on the
+//! internet.
+//!
+//! A config edit, made by hand or by a prompt-injected tool call, could point
+//! the `oss` provider at an attacker's host and quietly ship every prompt and
+//! file there. Unless `oss.allow_remote = true`, the provider's base URL must
+//! resolve to a loopback or private address. With `oss.strict_local = true`
+//! it must be on this machine, whatever `oss.allow_remote` says.
+//!
+//! This is the early check with a readable error. The HTTP client repeats it
+//! for every connection and redirect, so a name that later resolves somewhere
+//! else is refused too.
+
+use std::io;
+use std::net::IpAddr;
+
+use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
+use codex_core::config::Config;
+use codex_core::is_private_address;
+
+/// Fail when the `oss` provider's base URL resolves to a public address and
+/// `oss.allow_remote` is not set, or is not on this machine and
+/// `oss.strict_local` is set.
+pub async fn ensure_local_base_url(config: &Config) -> io::Result<()> {
+    let Some(base_url) = config
+        .model_providers
+        .get(BUILT_IN_OSS_MODEL_PROVIDER_ID)
+        .and_then(|provider| provider.base_url.as_deref())
+    else {
+        return Ok(());
+    };
+    if config.oss.strict_local {
+        return ensure_loopback_base_url(base_url);
+    }
+    if config.oss.allow_remote {
+        return Ok(());
+    }
+    check_base_url(base_url).await
+}
+
+/// Fail unless `base_url` is on this machine: a loopback address,
+/// `localhost`, or a local socket.
+pub(crate) fn ensure_loopback_base_url(base_url: &str) -> io::Result<()> {
+    if codex_core::is_loopback_base_url(base_url) {
+        return Ok(());
+    }
+    Err(io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!(
+            "oss.strict_local only allows a server on this machine, and {base_url} is not on it."
+        ),
+    ))
+}
+
+async fn check_base_url(base_url: &str) -> io::Result<()> {
+    if codex_core::is_local_socket_base_url(base_url) {
+        return Ok(());
+    }
+    let refuse = |reason: String| {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{reason}; prompts are only sent to servers on this machine or a private network. Set `oss.allow_remote = true` to use it anyway."
+            ),
+        ))
+    };
+    let url = match reqwest::Url::parse(base_url) {
+        Ok(url) => url,
+        Err(err) => return refuse(format!("The oss base_url {base_url} is invalid ({err})")),
+    };
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        return refuse(format!(
+            "The oss base_url {base_url} names no host and port"
+        ));
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addresses: Vec<IpAddr> = match host.parse::<IpAddr>() {
+        Ok(ip) => vec![ip],
+        // A name that cannot be checked is not let through.
+        Err(_) => match tokio::net::lookup_host((host, port)).await {
+            Ok(addresses) => addresses.map(|address| address.ip()).collect(),
+            Err(err) => {
+                return refuse(format!(
+                    "The oss base_url {base_url} could not be resolved ({err})"
+                ));
+            }
+        },
+    };
+    match addresses.iter().find(|ip| !is_private_address(**ip)) {
+        Some(public) => refuse(format!(
+            "The oss base_url {base_url} resolves to the public address {public}"
+        )),
+        None if addresses.is_empty() => refuse(format!(
+            "The oss base_url {base_url} resolves to no address"
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn public_base_urls_are_refused() {
+        assert!(check_base_url("http://localhost:1234/v1").await.is_ok());
+        assert!(check_base_url("http://192.168.1.20:1234/v1").await.is_ok());
+        assert!(check_base_url("unix:///run/llama.sock").await.is_ok());
+
+        let err = check_base_url("http://203.0.113.7:1234/v1")
+            .await
+            .expect_err("public address");
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(err.to_string().contains("oss.allow_remote"), "{err}");
+
+        // What cannot be checked is refused rather than let through.
+        for unchecked in ["not a url", "http://codex-test.invalid:1234/v1"] {
+            let err = check_base_url(unchecked).await.expect_err(unchecked);
+            assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        }
+    }
+
+    #[test]
+    fn strict_local_refuses_private_addresses() {
+        assert!(ensure_loopback_base_url("http://127.0.0.1:11434").is_ok());
+        assert!(ensure_loopback_base_url("http://localhost:1234/v1").is_ok());
+        let err = ensure_loopback_base_url("http://192.168.1.20:1234/v1").expect_err("LAN");
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    }
+}
@@ -0,0 +1,206 @@
+//! Best-effort probes of the host machine used by the readiness checks.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Directory where Ollama stores model weights. Honors `OLLAMA_MODELS` and
+/// otherwise falls back to the default `~/.ollama/models`.
+pub(crate) fn models_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("OLLAMA_MODELS").filter(|v| !v.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    dirs::home_dir().map(|home| home.join(".ollama").join("models"))
+}
+
+/// Free disk space where Ollama stores model weights, if it can be
+/// determined.
+pub fn available_model_disk_bytes() -> Option<u64> {
+    available_disk_bytes(&models_dir()?)
+}
+
+/// Free space available to unprivileged users on the filesystem containing
+/// `path`, walking up to the nearest existing ancestor.
+#[cfg(unix)]
+// The `statvfs` field widths differ across platforms, so `u64::from` is a
+// no-op on some targets and a widening conversion on others.
+#[allow(clippy::useless_conversion)]
+pub(crate) fn available_disk_bytes(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let c_path = CString::new(existing.as_os_str().as_bytes()).ok()?;
+    // SAFETY: `statvfs` only writes into the zeroed struct we own, and
+    // `c_path` is a valid NUL-terminated string for the duration of the call.
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    let rc = unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) };
+    if rc != 0 {
+        return None;
+    }
+    Some(u64::from(stat.f_bavail).saturating_mul(u64::from(stat.f_frsize)))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn available_disk_bytes(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Total physical memory of the machine, if it can be determined.
+#[cfg(target_os = "linux")]
+pub(crate) fn total_memory_bytes() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    parse_meminfo_total(&meminfo)
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn total_memory_bytes() -> Option<u64> {
+    let output = std::process::Command::new("sysctl")
+        .args(["-n", "hw.memsize"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub(crate) fn total_memory_bytes() -> Option<u64> {
+    None
+}
+
+/// Total memory of the machine's NVIDIA GPUs, read with `nvidia-smi`, if
+/// it has any.
+pub(crate) fn gpu_memory_bytes() -> Option<u64> {
+    let output = std::process::Command::new("nvidia-smi")
+        .args(["--query-gpu=memory.total", "--format=csv,noheader,nounits"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_gpu_memory(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Sum of the per-GPU totals `nvidia-smi` reports, one per line in MiB.
+fn parse_gpu_memory(output: &str) -> Option<u64> {
+    let mib: Vec<u64> = output
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect();
+    (!mib.is_empty()).then(|| mib.iter().sum::<u64>().saturating_mul(1024 * 1024))
+}
+
+/// Physical memory of the machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostMemory {
+    pub total_bytes: u64,
+    /// Memory that can be given to new allocations without swapping.
+    pub available_bytes: u64,
+}
+
+/// Current memory of the machine, if it can be determined.
+#[cfg(target_os = "linux")]
+pub fn host_memory() -> Option<HostMemory> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    Some(HostMemory {
+        total_bytes: parse_meminfo_total(&meminfo)?,
+        available_bytes: parse_meminfo_field(&meminfo, "MemAvailable:")?,
+    })
+}
+
+#[cfg(target_os = "macos")]
+pub fn host_memory() -> Option<HostMemory> {
+    let output = std::process::Command::new("vm_stat").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(HostMemory {
+        total_bytes: total_memory_bytes()?,
+        available_bytes: parse_vm_stat_available(&String::from_utf8_lossy(&output.stdout))?,
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn host_memory() -> Option<HostMemory> {
+    None
+}
+
+/// Parse the `MemTotal` line of `/proc/meminfo` (reported in KiB).
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_meminfo_total(meminfo: &str) -> Option<u64> {
+    parse_meminfo_field(meminfo, "MemTotal:")
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_meminfo_field(meminfo: &str, field: &str) -> Option<u64> {
+    meminfo.lines().find_map(|line| {
+        let rest = line.strip_prefix(field)?;
+        let kib = rest
+            .trim()
+            .trim_end_matches("kB")
+            .trim()
+            .parse::<u64>()
+            .ok()?;
+        Some(kib.saturating_mul(1024))
+    })
+}
+
+/// Free, inactive, and speculative pages reported by macOS `vm_stat`, which
+/// the kernel hands out before it starts compressing or swapping.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_vm_stat_available(vm_stat: &str) -> Option<u64> {
+    let page_size = vm_stat
+        .lines()
+        .next()?
+        .split("page size of ")
+        .nth(1)?
+        .split_whitespace()
+        .next()?
+        .parse::<u64>()
+        .ok()?;
+    let pages = |label: &str| -> u64 {
+        vm_stat
+            .lines()
+            .find_map(|line| line.strip_prefix(label))
+            .and_then(|rest| rest.trim().trim_end_matches('.').parse::<u64>().ok())
+            .unwrap_or(0)
+    };
+    let available = pages("Pages free:") + pages("Pages inactive:") + pages("Pages speculative:");
+    Some(available.saturating_mul(page_size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_meminfo_total() {
+        let meminfo = "MemTotal:       16314060 kB\nMemFree:         1234 kB\n";
+        assert_eq!(parse_meminfo_total(meminfo), Some(16314060 * 1024));
+        assert_eq!(parse_meminfo_total("MemFree: 1 kB\n"), None);
+        assert_eq!(
+            parse_meminfo_field("MemAvailable:    8000 kB\n", "MemAvailable:"),
+            Some(8000 * 1024)
+        );
+    }
+
+    #[test]
+    fn test_parse_vm_stat_available() {
+        let vm_stat = "Mach Virtual Memory Statistics: (page size of 16384 bytes)\n\
+            Pages free:                               10.\n\
+            Pages active:                             99.\n\
+            Pages inactive:                           20.\n\
+            Pages speculative:                         2.\n";
+        assert_eq!(parse_vm_stat_available(vm_stat), Some(32 * 16384));
+    }
+
+    #[test]
+    fn test_parse_gpu_memory_sums_the_gpus() {
+        assert_eq!(
+            parse_gpu_memory("24564\n24564\n"),
+            Some(2 * 24564 * 1024 * 1024)
+        );
+        assert_eq!(parse_gpu_memory("No devices were found\n"), None);
+    }
+}
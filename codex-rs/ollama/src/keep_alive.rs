@@ -0,0 +1,78 @@
+//! Keeping the session's model loaded while Codex runs something other than
+//! the model, such as a long build or test suite.
+//!
+//! Servers unload a model that received no request for a while (five
+//! minutes by default for Ollama, an hour for models LM Studio loaded on
+//! demand), so the turn after a long command would otherwise wait for the
+//! model to load again.
+
+use codex_core::config::Config;
+use codex_core::protocol_config_types::OssBackend;
+use std::io;
+use std::time::Duration;
+
+use crate::OllamaClient;
+
+/// Ollama loads a model for a request without a prompt and restarts its
+/// idle timer, without evaluating anything.
+const OLLAMA_INTERVAL: Duration = Duration::from_secs(2 * 60);
+
+/// LM Studio only counts real completions, which replace its prompt cache,
+/// so it is reminded rarely, well within its idle time.
+const LMSTUDIO_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// How often `backend` needs a request to keep a model loaded, or `None`
+/// when Codex does not know its idle policy.
+fn keep_alive_interval(backend: OssBackend) -> Option<Duration> {
+    match backend {
+        OssBackend::Ollama => Some(OLLAMA_INTERVAL),
+        OssBackend::LmStudio => Some(LMSTUDIO_INTERVAL),
+        OssBackend::Generic => None,
+    }
+}
+
+/// Send `model` the lightest request that counts as use on the server
+/// behind the `oss` provider.
+pub async fn keep_model_alive(config: &Config, model: &str) -> io::Result<()> {
+    let client = OllamaClient::from_oss_provider(config)?;
+    match config.oss.provider.unwrap_or(OssBackend::Ollama) {
+        OssBackend::Ollama => client.touch_model(model).await,
+        OssBackend::LmStudio => client.answer(model, "Hi", Some(1)).await.map(|_| ()),
+        OssBackend::Generic => Ok(()),
+    }
+}
+
+/// Keep the session's model loaded until the returned future is dropped.
+/// The first request is only sent after a full interval, so commands that
+/// finish quickly cost nothing.
+pub async fn keep_model_warm(config: Config) {
+    let Some(interval) = keep_alive_interval(config.oss.provider.unwrap_or(OssBackend::Ollama))
+    else {
+        return;
+    };
+    loop {
+        tokio::time::sleep(interval).await;
+        if let Err(err) = keep_model_alive(&config, &config.model).await {
+            tracing::warn!("keeping {} loaded failed: {err}", config.model);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn models_are_kept_alive_well_within_the_default_idle_times() {
+        assert_eq!(
+            keep_alive_interval(OssBackend::Ollama),
+            Some(Duration::from_secs(120))
+        );
+        assert!(
+            keep_alive_interval(OssBackend::LmStudio)
+                .is_some_and(|interval| interval < Duration::from_secs(60 * 60))
+        );
+        assert_eq!(keep_alive_interval(OssBackend::Generic), None);
+    }
+}
@@ -1,37 +1,328 @@
+mod api;
+mod atomic;
+mod audit;
+mod backend;
+mod bench;
+mod binary;
+mod catalog;
 mod client;
+mod command;
+mod conformance;
+mod context;
+mod detect;
+mod download_lock;
+mod downloads;
+mod energy;
+mod environment;
+mod error;
+mod eval;
+mod events;
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing;
+mod generate;
+mod guard;
+mod host;
+mod keep_alive;
+mod license;
+mod lms;
+mod lms_trust;
+mod lmstudio_install;
+mod load;
+mod logs;
+mod messages;
+mod model_id;
+mod models;
 mod parser;
+mod paths;
+mod performance;
+mod preflight;
 mod pull;
+mod readiness;
+mod recommend;
+mod registry;
+mod server;
+mod shutdown;
+mod status;
+mod supervisor;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+mod throttle;
+mod unload;
 mod url;
+mod usage;
+mod verify;
 
+pub use api::ApiSurfaces;
+pub use atomic::remove_orphaned_temp_files;
+pub use atomic::write_atomic;
+pub use audit::AuditAction;
+pub use audit::AuditEntry;
+pub use audit::audit_log_path;
+pub use audit::read_audit_log;
+pub use backend::LocalModelBackend;
+pub use bench::BenchResult;
+pub use bench::DEFAULT_BENCH_CONTEXT_SIZES;
+pub use bench::DEFAULT_BENCH_MAX_TOKENS;
+pub use bench::run_benchmark;
+pub use binary::find_ollama_binary;
+pub use catalog::CatalogClient;
+pub use catalog::DEFAULT_CATALOG_URL;
 pub use client::OllamaClient;
+pub use client::OllamaClientBuilder;
 use codex_core::config::Config;
+use codex_core::config_types::OssVerbosity;
+use codex_core::protocol_config_types::OssBackend;
+use codex_protocol::oss::OssEvent;
+pub use command::print_only;
+pub use command::set_print_only;
+pub use conformance::run_conformance;
+pub use context::detect_context_window;
+pub use detect::DetectedBackend;
+pub use detect::detect_backends;
+pub use downloads::DownloadManager;
+pub use energy::DEFAULT_CLOUD_INPUT_PRICE;
+pub use energy::DEFAULT_CLOUD_OUTPUT_PRICE;
+pub use energy::EnergyMeter;
+pub use energy::energy_log_path;
+pub use energy::energy_totals;
+pub use energy::format_session_energy;
+pub use environment::export_environment;
+pub use environment::import_environment;
+pub use error::OssError;
+pub use eval::run_eval;
+pub use events::subscribe_oss_events;
+pub use generate::GenerateOptions;
+pub use generate::GenerationStats;
+pub use generate::ModelAnswer;
+pub use guard::ensure_local_base_url;
+pub use host::HostMemory;
+pub use host::available_model_disk_bytes;
+pub use host::host_memory;
+pub use keep_alive::keep_model_alive;
+pub use keep_alive::keep_model_warm;
+pub use license::catalog_repo;
+use license::ensure_license_accepted;
+pub use license::license_accepted;
+pub use license::model_license;
+pub use lmstudio_install::LmStudioInstall;
+pub use lmstudio_install::LmStudioInstallKind;
+pub use lmstudio_install::detect_lmstudio_install;
+pub use load::load_model;
+pub use load::load_model_with_backend;
+pub use logs::LogFilter;
+pub use logs::LogLevel;
+pub use logs::follow_log;
+pub use logs::read_last_lines;
+pub use logs::server_log_candidates;
+pub use messages::MessageId;
+pub use messages::message;
+pub use model_id::ModelId;
+pub use model_id::contains_model;
+pub use models::LoadedModel;
+pub use models::LocalModel;
+pub use models::ModelDetails;
+pub use models::format_size;
+pub use paths::clean_oss_cache;
+pub use paths::oss_cache_dir;
+pub use paths::oss_state_dir;
+pub use performance::PerformanceRecorder;
+pub use performance::performance_log_path;
+pub use preflight::PreflightRequirements;
+pub use preflight::preflight_oss;
 pub use pull::CliProgressReporter;
+pub use pull::JsonProgressReporter;
 pub use pull::PullEvent;
 pub use pull::PullProgressReporter;
 pub use pull::TuiProgressReporter;
+pub use readiness::CheckStatus;
+pub use readiness::OssReadiness;
+pub use readiness::ReadinessCheck;
+pub use readiness::check_oss_readiness;
+pub use recommend::HardwareProfile;
+pub use recommend::ModelRecommendation;
+pub use recommend::ModelRecommendations;
+pub use recommend::WorkspaceProfile;
+pub use recommend::profile_workspace;
+pub use recommend::rank_models;
+pub use recommend::recommend_models;
+pub use recommend::record_workspace_model;
+pub use recommend::workspace_model;
+pub use registry::RegistryClient;
+pub use registry::check_model_updates;
+pub use server::ServeOptions;
+pub use server::managed_server_pid;
+pub use server::managed_server_uptime;
+pub use server::server_log_path;
+pub use server::spawn_server;
+pub use server::start_oss_server;
+pub use server::stop_managed_server;
+pub use shutdown::shutdown_local_provider;
+pub use status::runtime_status;
+pub use status::server_status;
+pub use supervisor::ServerLifecycleEvent;
+pub use supervisor::supervise_managed_server;
+pub use unload::unload_models;
+pub use url::base_url_to_host_root;
+pub use usage::model_last_used;
+pub use usage::record_model_use;
+pub use verify::ollama_models_dir;
+pub use verify::remove_damaged_blobs;
+pub use verify::verify_models;
 
-/// Default OSS model to use when `--oss` is passed without an explicit `-m`.
-pub const DEFAULT_OSS_MODEL: &str = "gpt-oss:20b";
+/// Warning to show when `--oss-url` points at another machine, since prompts
+/// and file contents will leave this host. Suppressed by `--quiet`.
+pub fn remote_oss_url_warning(config: &Config) -> Option<String> {
+    if config.oss.verbosity == OssVerbosity::Quiet {
+        return None;
+    }
+    let base_url = config.oss.base_url.as_deref()?;
+    (!codex_core::is_loopback_base_url(base_url)).then(|| {
+        format!(
+            "--oss-url points at {base_url}, which is not on this machine; prompts and file contents will be sent to that server."
+        )
+    })
+}
+
+/// Error returned when `action` would need the network but `--offline` is set.
+pub fn offline_error(action: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::PermissionDenied,
+        format!("{action} is disabled in offline mode (--offline / oss.offline)."),
+    )
+}
+
+/// In offline mode, only a provider served from this machine may be used.
+pub fn ensure_offline_provider(config: &Config) -> std::io::Result<()> {
+    if !config.oss.offline {
+        return Ok(());
+    }
+    let is_local = config
+        .model_provider
+        .base_url
+        .as_deref()
+        .is_some_and(codex_core::is_loopback_base_url);
+    if is_local {
+        Ok(())
+    } else {
+        Err(offline_error(&format!(
+            "Using the remote provider '{}'",
+            config.model_provider.name
+        )))
+    }
+}
+
+pub use codex_core::DEFAULT_OSS_MODEL;
+
+/// Whether this build can drive `backend`. Ollama and LM Studio support are
+/// the `ollama` and `lmstudio` cargo features; generic OpenAI-compatible
+/// servers only need HTTP and are always supported.
+pub fn backend_compiled_in(backend: OssBackend) -> bool {
+    match backend {
+        #[cfg(feature = "ollama")]
+        OssBackend::Ollama => true,
+        #[cfg(feature = "lmstudio")]
+        OssBackend::LmStudio => true,
+        OssBackend::Generic => true,
+        #[allow(unreachable_patterns)]
+        _ => false,
+    }
+}
+
+/// Error for a backend [`backend_compiled_in`] rules out.
+pub(crate) fn backend_not_compiled_in(backend: OssBackend) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!(
+            "This build of Codex was built without {backend} support; rebuild it with the `{backend}` feature or use `--oss-provider generic`."
+        ),
+    )
+}
+
+/// Models the local server has, sorted by name. Servers other than Ollama
+/// and LM Studio only report model ids.
+pub async fn list_local_models(config: &Config) -> std::io::Result<Vec<LocalModel>> {
+    let client = OllamaClient::try_from_oss_provider(config).await?;
+    let mut models = match config.oss.provider {
+        // LM Studio's own API also says what is loaded and how much context
+        // each model supports; other servers only list ids.
+        Some(OssBackend::LmStudio | OssBackend::Generic)
+            if client.api_surfaces().await.lmstudio_v0 =>
+        {
+            client.fetch_lmstudio_models().await?
+        }
+        Some(OssBackend::LmStudio | OssBackend::Generic) => client
+            .fetch_openai_model_ids()
+            .await?
+            .into_iter()
+            .map(|name| LocalModel {
+                name,
+                size_bytes: None,
+                quantization: None,
+                parameter_size: None,
+                context_length: None,
+                loaded: false,
+            })
+            .collect(),
+        Some(OssBackend::Ollama) | None => client.fetch_local_models().await?,
+    };
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(models)
+}
 
 /// Prepare the local OSS environment when `--oss` is selected.
 ///
-/// - Ensures a local Ollama server is reachable.
-/// - Checks if the model exists locally and pulls it if missing.
-pub async fn ensure_oss_ready(config: &Config) -> std::io::Result<()> {
+/// Output follows `oss.verbosity`: quiet prints nothing (errors are returned
+/// to the caller), verbose also echoes each probe and request.
+///
+/// - Ensures a local server is reachable.
+/// - For Ollama, checks if the model exists locally and pulls it if missing
+///   (unless offline mode is on, in which case a missing model is an error).
+/// - For other OpenAI-compatible backends, which cannot pull on demand,
+///   verifies the model is already available.
+pub async fn ensure_oss_ready(config: &Config) -> Result<(), OssError> {
+    let mut reporter = CliProgressReporter::from_oss_config(&config.oss);
+    ensure_oss_ready_with_reporter(config, &mut reporter).await
+}
+
+/// [`ensure_oss_ready`] with download progress sent to `reporter`, e.g. a
+/// [`JsonProgressReporter`] in `--json` flows.
+pub async fn ensure_oss_ready_with_reporter(
+    config: &Config,
+    reporter: &mut dyn PullProgressReporter,
+) -> Result<(), OssError> {
     // Only download when the requested model is the default OSS model (or when -m is not provided).
     let model = config.model.as_ref();
 
+    // Writes a killed session left unfinished.
+    remove_orphaned_temp_files(&oss_state_dir(config));
+    if let Err(err) = record_model_use(&oss_state_dir(config), model) {
+        tracing::warn!("Failed to record use of model {model}: {err}");
+    }
+
+    if let Some(backend @ (OssBackend::LmStudio | OssBackend::Generic)) = config.oss.provider {
+        return ensure_openai_compatible_ready(config, backend).await;
+    }
+
     // Verify local Ollama is reachable.
-    let ollama_client = crate::OllamaClient::try_from_oss_provider(config).await?;
+    let ollama_client = crate::OllamaClient::from_oss_provider(config)?;
+    ollama_client.log(format_args!(
+        "probing Ollama at {}",
+        ollama_client.host_root()
+    ));
+    ollama_client
+        .probe_server()
+        .await
+        .map_err(|err| OssError::ServerUnreachable(err.to_string()))?;
+    events::publish(OssEvent::ServerDetected {
+        backend: OssBackend::Ollama,
+        base_url: ollama_client.host_root().to_string(),
+    });
 
-    // If the model is not present locally, pull it.
+    // Pull the models that are not present locally.
     match ollama_client.fetch_models().await {
         Ok(models) => {
-            if !models.iter().any(|m| m == model) {
-                let mut reporter = crate::CliProgressReporter::new();
-                ollama_client
-                    .pull_with_reporter(model, &mut reporter)
-                    .await?;
+            for model in session_models(config) {
+                ensure_ollama_model(config, &ollama_client, &models, model, reporter).await?;
             }
         }
         Err(err) => {
@@ -42,3 +333,271 @@ pub async fn ensure_oss_ready(config: &Config) -> std::io::Result<()> {
 
     Ok(())
 }
+
+/// [`ensure_oss_ready_with_reporter`] against `server` instead of a client
+/// built from `config`, e.g. a fake in tests: check that it answers and has
+/// the session's models, pulling the missing ones where it can.
+pub async fn ensure_oss_ready_with_backend(
+    config: &Config,
+    server: &dyn LocalModelBackend,
+    reporter: &mut (dyn PullProgressReporter + Send),
+) -> Result<(), OssError> {
+    server
+        .probe()
+        .await
+        .map_err(|err| OssError::ServerUnreachable(err.to_string()))?;
+    let models = server.list_models().await?;
+    for model in session_models(config) {
+        if contains_model(&models, model) {
+            continue;
+        }
+        if config.oss.offline {
+            return Err(OssError::ModelMissing(format!(
+                "Model '{model}' is not downloaded and offline mode disables downloads. Run `codex models pull {model}` while online first."
+            )));
+        }
+        pull_model_with_backend(server, model, reporter).await?;
+    }
+    Ok(())
+}
+
+/// Download `model` onto `server`, unless it already has it. Servers that
+/// cannot pull models report it as [`OssError::ModelMissing`].
+pub async fn pull_model_with_backend(
+    server: &dyn LocalModelBackend,
+    model: &str,
+    reporter: &mut (dyn PullProgressReporter + Send),
+) -> Result<(), OssError> {
+    server
+        .ensure_model(model, reporter)
+        .await
+        .map_err(|source| match source.kind() {
+            std::io::ErrorKind::NotFound => OssError::ModelMissing(format!(
+                "Model '{model}' is not available on the server, which cannot download it. Download or load it there first, then select it with -m."
+            )),
+            _ => OssError::DownloadFailed {
+                model: model.to_string(),
+                source,
+            },
+        })
+}
+
+/// The session's model followed by those for auxiliary calls (see
+/// `oss.summary_model` and `oss.embedding_model`), each once.
+fn session_models(config: &Config) -> Vec<&str> {
+    let mut models = vec![config.model.as_str()];
+    for model in config.oss.auxiliary_models() {
+        if !models.contains(&model) {
+            models.push(model);
+        }
+    }
+    models
+}
+
+/// Pull `model` unless it is among the downloaded `models`.
+async fn ensure_ollama_model(
+    config: &Config,
+    ollama_client: &crate::OllamaClient,
+    models: &[String],
+    model: &str,
+    reporter: &mut dyn PullProgressReporter,
+) -> Result<(), OssError> {
+    if contains_model(models, model) {
+        ollama_client.log(format_args!("model '{model}' is already downloaded"));
+        return Ok(());
+    }
+    if config.oss.offline {
+        return Err(OssError::ModelMissing(format!(
+            "Model '{model}' is not downloaded and offline mode disables downloads. Run `codex models pull {model}` while online first."
+        )));
+    }
+    ollama_client.log(format_args!("model '{model}' is missing; pulling it"));
+    // Checked here for a structured error; the pull reuses the lookup.
+    let download_client = crate::OllamaClient::from_oss_provider(config)?;
+    ensure_license_accepted(&download_client, &config.oss.accepted_licenses, model).await?;
+    DownloadManager::global()
+        .download(download_client, model, reporter)
+        .await
+        .map_err(|source| OssError::DownloadFailed {
+            model: model.to_string(),
+            source,
+        })?;
+    Ok(())
+}
+
+async fn ensure_openai_compatible_ready(
+    config: &Config,
+    backend: OssBackend,
+) -> Result<(), OssError> {
+    let client = crate::OllamaClient::from_oss_provider(config)?;
+    client.log(format_args!("probing {backend} at {}", client.host_root()));
+    for model in session_models(config) {
+        ensure_model_served(&client, backend, client.host_root(), model).await?;
+        client.log(format_args!("model '{model}' is available"));
+    }
+    Ok(())
+}
+
+/// Check that `server`, reached at `host_root`, answers and serves `model`.
+/// Such servers cannot download models on request.
+async fn ensure_model_served(
+    server: &dyn LocalModelBackend,
+    backend: OssBackend,
+    host_root: &str,
+    model: &str,
+) -> Result<(), OssError> {
+    server.probe().await.map_err(|_| {
+        OssError::ServerUnreachable(format!(
+            "No {backend} server reachable at {host_root}. Start it, or pick another backend with --oss-provider."
+        ))
+    })?;
+    events::publish(OssEvent::ServerDetected {
+        backend,
+        base_url: host_root.to_string(),
+    });
+    let models = server.list_models().await?;
+    if contains_model(&models, model) {
+        Ok(())
+    } else {
+        Err(OssError::ModelMissing(format!(
+            "Model '{model}' is not available on the {backend} server. Download or load it there first, then select it with -m."
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use std::io;
+
+    /// A server that lists `models`, or does not answer when `None`, and
+    /// records the models it was asked to pull.
+    struct FakeBackend {
+        models: Option<Vec<String>>,
+        pulled: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl LocalModelBackend for FakeBackend {
+        async fn probe(&self) -> io::Result<()> {
+            match self.models {
+                Some(_) => Ok(()),
+                None => Err(io::Error::from(io::ErrorKind::ConnectionRefused)),
+            }
+        }
+
+        async fn list_models(&self) -> io::Result<Vec<String>> {
+            Ok(self.models.clone().unwrap_or_default())
+        }
+
+        async fn ensure_model(
+            &self,
+            model: &str,
+            _reporter: &mut (dyn PullProgressReporter + Send),
+        ) -> io::Result<()> {
+            if let Ok(mut pulled) = self.pulled.lock() {
+                pulled.push(model.to_string());
+            }
+            Ok(())
+        }
+
+        async fn chat(
+            &self,
+            _model: &str,
+            _prompt: &str,
+            _max_tokens: Option<u64>,
+        ) -> io::Result<ModelAnswer> {
+            Ok(ModelAnswer {
+                text: String::new(),
+                completion_tokens: None,
+                elapsed: std::time::Duration::ZERO,
+            })
+        }
+
+        async fn embed(&self, _model: &str, inputs: &[String]) -> io::Result<Vec<Vec<f32>>> {
+            Ok(vec![Vec::new(); inputs.len()])
+        }
+
+        async fn load(&self, _model: &str) -> io::Result<bool> {
+            Ok(true)
+        }
+
+        async fn unload(&self, _model: &str) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn fake_backend(models: Option<&[&str]>) -> FakeBackend {
+        FakeBackend {
+            models: models.map(|models| models.iter().map(ToString::to_string).collect()),
+            pulled: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    async fn check(models: Option<&[&str]>) -> Result<(), OssError> {
+        let server = fake_backend(models);
+        ensure_model_served(
+            &server,
+            OssBackend::LmStudio,
+            "http://localhost:1234",
+            "qwen3-coder",
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn a_served_model_is_ready_and_a_missing_one_is_reported() {
+        assert_matches!(check(Some(&["qwen3-coder"])).await, Ok(()));
+        assert_matches!(
+            check(Some(&["gpt-oss-20b"])).await,
+            Err(OssError::ModelMissing(_))
+        );
+        assert_matches!(check(None).await, Err(OssError::ServerUnreachable(_)));
+    }
+
+    #[tokio::test]
+    async fn only_missing_session_models_are_pulled_through_the_backend() -> io::Result<()> {
+        let codex_home = tempfile::tempdir()?;
+        let mut config = Config::load_from_base_config_with_overrides(
+            codex_core::config::ConfigToml::default(),
+            codex_core::config::ConfigOverrides {
+                model: Some("qwen3-coder".to_string()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+        config.oss.embedding_model = Some("nomic-embed-text".to_string());
+        let server = fake_backend(Some(&["nomic-embed-text"]));
+        let mut reporter = CliProgressReporter::with_verbosity(OssVerbosity::Quiet);
+
+        ensure_oss_ready_with_backend(&config, &server, &mut reporter)
+            .await
+            .map_err(io::Error::other)?;
+        assert_eq!(
+            *server.pulled.lock().expect("pulled"),
+            vec!["qwen3-coder".to_string()]
+        );
+
+        config.oss.offline = true;
+        let server = fake_backend(Some(&["nomic-embed-text"]));
+        assert_matches!(
+            ensure_oss_ready_with_backend(&config, &server, &mut reporter).await,
+            Err(OssError::ModelMissing(_))
+        );
+        assert!(server.pulled.lock().expect("pulled").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn generic_servers_need_no_backend_feature() {
+        assert!(backend_compiled_in(OssBackend::Generic));
+        assert_eq!(
+            backend_compiled_in(OssBackend::LmStudio),
+            cfg!(feature = "lmstudio")
+        );
+        let err = backend_not_compiled_in(OssBackend::LmStudio);
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+        assert!(err.to_string().contains("without lmstudio support"));
+    }
+}
@@ -0,0 +1,111 @@
+//! Licenses of models pulled from the catalog (`hf.co/<repo>` names). Some
+//! GGUF repositories carry non-commercial or otherwise restricted terms, so a
+//! model is only downloaded once its license is listed in
+//! `oss.accepted_licenses`. `codex models pull` asks and records the answer;
+//! every other download path refuses an unlisted license.
+//!
+//! Models from the Ollama library have no catalog entry and are not gated.
+
+use codex_protocol::oss::ModelLicense;
+use std::io;
+
+use crate::CatalogClient;
+use crate::OllamaClient;
+use crate::OssError;
+
+/// Prefixes Ollama accepts for models pulled straight from Hugging Face.
+const CATALOG_PREFIXES: [&str; 2] = ["hf.co/", "huggingface.co/"];
+
+/// Catalog repository of `model`, e.g. "unsloth/gpt-oss-20b-GGUF" for
+/// "hf.co/unsloth/gpt-oss-20b-GGUF:Q4_K_M"; `None` for other models.
+pub fn catalog_repo(model: &str) -> Option<&str> {
+    let repo = CATALOG_PREFIXES
+        .iter()
+        .find_map(|prefix| model.strip_prefix(prefix))?;
+    Some(repo.split_once(':').map_or(repo, |(repo, _)| repo))
+}
+
+/// License of `model` from the catalog; `None` when it is not a catalog
+/// model or its card declares no license.
+pub async fn model_license(
+    catalog: &CatalogClient,
+    model: &str,
+) -> io::Result<Option<ModelLicense>> {
+    match catalog_repo(model) {
+        Some(repo) => catalog.fetch_license(repo).await,
+        None => Ok(None),
+    }
+}
+
+/// Whether `accepted` (`oss.accepted_licenses`) lists `license`. Case is
+/// ignored, since model cards are not consistent about it.
+pub fn license_accepted(accepted: &[String], license: &ModelLicense) -> bool {
+    accepted
+        .iter()
+        .any(|accepted| accepted.trim().eq_ignore_ascii_case(license.key()))
+}
+
+/// Fail with [`OssError::LicenseNotAccepted`] unless `model` may be
+/// downloaded under `accepted`. The license is looked up through `client`,
+/// which remembers it for the pull that follows.
+pub(crate) async fn ensure_license_accepted(
+    client: &OllamaClient,
+    accepted: &[String],
+    model: &str,
+) -> Result<(), OssError> {
+    let Some(license) = client.model_license(model).await.map_err(|err| {
+        io::Error::new(
+            err.kind(),
+            format!("could not look up the license of '{model}': {err}"),
+        )
+    })?
+    else {
+        return Ok(());
+    };
+    if license_accepted(accepted, &license) {
+        return Ok(());
+    }
+    Err(OssError::LicenseNotAccepted {
+        model: model.to_string(),
+        license: license.key().to_string(),
+        url: license.url,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn catalog_models_are_recognized_by_prefix() {
+        assert_eq!(
+            catalog_repo("hf.co/unsloth/gpt-oss-20b-GGUF:Q4_K_M"),
+            Some("unsloth/gpt-oss-20b-GGUF")
+        );
+        assert_eq!(
+            catalog_repo("huggingface.co/org/model-GGUF"),
+            Some("org/model-GGUF")
+        );
+        assert_eq!(catalog_repo("gpt-oss:20b"), None);
+    }
+
+    #[test]
+    fn custom_terms_are_accepted_by_name() {
+        let custom = ModelLicense {
+            id: "other".to_string(),
+            name: Some("llama3.1".to_string()),
+            url: None,
+        };
+        assert!(license_accepted(&["Llama3.1".to_string()], &custom));
+        assert!(!license_accepted(&["other".to_string()], &custom));
+
+        let apache = ModelLicense {
+            id: "apache-2.0".to_string(),
+            name: None,
+            url: None,
+        };
+        assert!(license_accepted(&["apache-2.0".to_string()], &apache));
+        assert!(!license_accepted(&[], &apache));
+    }
+}
@@ -0,0 +1,45 @@
+//! The arguments Codex runs LM Studio's `lms` CLI with. Kept in one place,
+//! with snapshots, as they are the interface to a program Codex does not
+//! control.
+
+/// Load `model` into the server's memory.
+pub(crate) fn load_args(model: &str) -> Vec<&str> {
+    vec!["load", model]
+}
+
+/// Unload `model`, or every loaded model when `None`.
+pub(crate) fn unload_args(model: Option<&str>) -> Vec<&str> {
+    match model {
+        Some(model) => vec!["unload", model],
+        None => vec!["unload", "--all"],
+    }
+}
+
+pub(crate) fn server_start_args() -> Vec<&'static str> {
+    vec!["server", "start"]
+}
+
+pub(crate) fn server_stop_args() -> Vec<&'static str> {
+    vec!["server", "stop"]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lms_invocations_match_their_snapshots() {
+        let invocations = [
+            load_args("qwen/qwen3-8b"),
+            unload_args(Some("qwen/qwen3-8b")),
+            unload_args(None),
+            server_start_args(),
+            server_stop_args(),
+        ];
+        let lines: Vec<String> = invocations
+            .iter()
+            .map(|args| format!("lms {}", args.join(" ")))
+            .collect();
+        insta::assert_snapshot!(lines.join("\n"));
+    }
+}
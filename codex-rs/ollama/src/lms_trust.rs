@@ -0,0 +1,180 @@
+//! Checks on LM Studio's `lms` CLI before Codex runs it. The CLI is found at
+//! guessable paths under the home directory, so whatever sits there is
+//! looked at first: it must be a regular file that only its owner (the
+//! user or root) can change, neither group- nor world-writable and in a
+//! directory that is neither, and, when `oss.trusted_lms_sha256` is set, one
+//! of the listed builds.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::detect::find_lms_cli;
+use crate::verify::sha256_file;
+
+/// The `lms` CLI, checked with [`verify_lms_binary`]; `None` when it is not
+/// installed.
+pub(crate) fn trusted_lms_cli(trusted_sha256: &[String]) -> io::Result<Option<PathBuf>> {
+    find_lms_cli()
+        .map(|path| verify_lms_binary(&path, trusted_sha256))
+        .transpose()
+}
+
+/// Resolve `path` to the file it names and check that it is safe to run.
+/// Returns the resolved absolute path, which is what gets executed.
+pub(crate) fn verify_lms_binary(path: &Path, trusted_sha256: &[String]) -> io::Result<PathBuf> {
+    let resolved = std::fs::canonicalize(path)?;
+    let metadata = std::fs::metadata(&resolved)?;
+    if !metadata.is_file() {
+        return Err(untrusted(&resolved, "it is not a regular file"));
+    }
+    #[cfg(unix)]
+    {
+        check_unix_permissions(&resolved, &metadata)?;
+    }
+    if !trusted_sha256.is_empty() {
+        let digest = sha256_file(&resolved)?;
+        if !trusted_sha256
+            .iter()
+            .any(|trusted| normalize_digest(trusted) == digest)
+        {
+            return Err(untrusted(
+                &resolved,
+                &format!("its sha256 {digest} is not listed in `oss.trusted_lms_sha256`"),
+            ));
+        }
+    }
+    tracing::info!("using the lms CLI at {}", resolved.display());
+    Ok(resolved)
+}
+
+#[cfg(unix)]
+fn check_unix_permissions(path: &Path, metadata: &std::fs::Metadata) -> io::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    let euid = unsafe { libc::geteuid() };
+    if metadata.uid() != 0 && metadata.uid() != euid {
+        return Err(untrusted(
+            path,
+            &format!("it is owned by uid {}, not you or root", metadata.uid()),
+        ));
+    }
+    if let Some(writable) = writable_by_others(metadata.mode()) {
+        return Err(untrusted(path, &format!("it is {writable}")));
+    }
+    // Whoever can write the directory can swap the file.
+    if let Some(parent) = path.parent() {
+        let parent_metadata = std::fs::metadata(parent)?;
+        // A sticky directory such as /tmp only lets owners replace files.
+        let sticky = parent_metadata.mode() & 0o1000 != 0;
+        if let Some(writable) = writable_by_others(parent_metadata.mode())
+            && !sticky
+        {
+            return Err(untrusted(
+                path,
+                &format!("its directory {} is {writable}", parent.display()),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// How a file with `mode` can be changed by users other than its owner.
+#[cfg(unix)]
+fn writable_by_others(mode: u32) -> Option<&'static str> {
+    if mode & 0o002 != 0 {
+        Some("world-writable")
+    } else if mode & 0o020 != 0 {
+        Some("group-writable")
+    } else {
+        None
+    }
+}
+
+/// Digests may be written as in `shasum` output or with Ollama's `sha256:`
+/// prefix.
+fn normalize_digest(digest: &str) -> String {
+    digest
+        .trim()
+        .trim_start_matches("sha256:")
+        .to_ascii_lowercase()
+}
+
+fn untrusted(path: &Path, reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!(
+            "refusing to run the lms CLI at {}: {reason}",
+            path.display()
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use sha2::Digest;
+    use sha2::Sha256;
+
+    fn write_lms(dir: &Path) -> PathBuf {
+        let path = dir.join("lms");
+        std::fs::write(&path, b"#!/bin/sh\n").expect("write lms");
+        // Independent of the umask, which may leave the file group-writable.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).expect("chmod");
+        }
+        path
+    }
+
+    #[test]
+    fn listed_builds_are_accepted_and_others_refused() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let lms = write_lms(dir.path());
+        let digest = format!("{:x}", Sha256::digest(b"#!/bin/sh\n"));
+
+        let resolved = verify_lms_binary(&lms, &[]).expect("no list given");
+        assert!(resolved.is_absolute());
+        verify_lms_binary(&lms, &[format!("sha256:{}", digest.to_uppercase())])
+            .expect("listed build");
+        let err = verify_lms_binary(&lms, &["0".repeat(64)]).expect_err("unlisted build");
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(err.to_string().contains(&digest), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn world_writable_binaries_are_refused() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let lms = write_lms(dir.path());
+        std::fs::set_permissions(&lms, std::fs::Permissions::from_mode(0o777)).expect("chmod");
+
+        let err = verify_lms_binary(&lms, &[]).expect_err("world-writable");
+        assert!(err.to_string().contains("world-writable"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn group_writable_binaries_are_refused() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let lms = write_lms(dir.path());
+        std::fs::set_permissions(&lms, std::fs::Permissions::from_mode(0o775)).expect("chmod");
+
+        let err = verify_lms_binary(&lms, &[]).expect_err("group-writable");
+        assert!(err.to_string().contains("it is group-writable"), "{err}");
+    }
+
+    #[test]
+    fn directories_are_refused() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let err = verify_lms_binary(dir.path(), &[]).expect_err("directory");
+        assert!(err.to_string().contains("not a regular file"), "{err}");
+    }
+}
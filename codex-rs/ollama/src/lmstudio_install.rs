@@ -0,0 +1,381 @@
+//! How LM Studio is installed on this machine: which kind of install, its
+//! version, and where its `lms` CLI is, for `codex oss doctor`.
+//!
+//! Besides the regular installers, LM Studio comes as a winget package, as
+//! a portable zip that can be unpacked anywhere, and on macOS as an app
+//! bundle whose CLI ships inside it until `lms bootstrap` copies it out.
+
+use std::fmt;
+#[cfg(feature = "lmstudio")]
+use std::path::Path;
+use std::path::PathBuf;
+
+#[cfg(feature = "lmstudio")]
+use crate::detect::find_lms_cli;
+
+/// An install of LM Studio.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LmStudioInstall {
+    pub kind: LmStudioInstallKind,
+    /// Directory or bundle of the app; `None` when only the CLI was found.
+    pub app: Option<PathBuf>,
+    /// Version of the app, when it records one.
+    pub version: Option<String>,
+    /// The `lms` CLI Codex would run.
+    pub cli: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LmStudioInstallKind {
+    /// Installed by LM Studio's own installer.
+    Installer,
+    Winget,
+    /// Unpacked from the portable zip.
+    Portable,
+    /// `LM Studio.app` on macOS.
+    AppBundle,
+    /// Only the `lms` CLI, e.g. on a headless machine.
+    CliOnly,
+}
+
+impl fmt::Display for LmStudioInstallKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Installer => "installer",
+            Self::Winget => "winget",
+            Self::Portable => "portable",
+            Self::AppBundle => "app bundle",
+            Self::CliOnly => "CLI only",
+        })
+    }
+}
+
+/// Find LM Studio, preferring an install of the app over a lone CLI.
+#[cfg(feature = "lmstudio")]
+pub fn detect_lmstudio_install() -> Option<LmStudioInstall> {
+    let cli = find_lms_cli();
+    // A CLI run from inside an app directory identifies that install, e.g.
+    // a portable one put on `PATH`.
+    let cli_app = cli.as_deref().and_then(app_of_bundled_cli);
+    let app = cli_app
+        .map(|app| {
+            let kind = app_candidates()
+                .into_iter()
+                .find(|(candidate, _)| *candidate == app)
+                .map_or_else(|| kind_of_app(&app), |(_, kind)| kind);
+            (app, kind)
+        })
+        .or_else(|| app_candidates().into_iter().find(|(app, _)| app.exists()));
+    match app {
+        Some((app, kind)) => Some(LmStudioInstall {
+            kind,
+            version: app_version(&app),
+            cli: cli.or_else(|| Some(bundled_cli(&app)).filter(|cli| cli.is_file())),
+            app: Some(app),
+        }),
+        None => cli.map(|cli| LmStudioInstall {
+            kind: LmStudioInstallKind::CliOnly,
+            app: None,
+            version: None,
+            cli: Some(cli),
+        }),
+    }
+}
+
+/// A build without LM Studio support never looks for it.
+#[cfg(not(feature = "lmstudio"))]
+pub fn detect_lmstudio_install() -> Option<LmStudioInstall> {
+    None
+}
+
+/// The `lms` CLI bundled with the app at `app`.
+#[cfg(feature = "lmstudio")]
+pub(crate) fn bundled_cli(app: &Path) -> PathBuf {
+    if app.extension().is_some_and(|ext| ext == "app") {
+        app.join("Contents")
+            .join("Resources")
+            .join("app")
+            .join(".webpack")
+            .join("lms")
+    } else {
+        app.join("resources")
+            .join("app")
+            .join(".webpack")
+            .join("lms.exe")
+    }
+}
+
+/// The app directory or bundle a bundled CLI at `cli` belongs to.
+#[cfg(feature = "lmstudio")]
+fn app_of_bundled_cli(cli: &Path) -> Option<PathBuf> {
+    let resources = cli.parent()?.parent()?.parent()?;
+    let app = if resources.file_name()? == "Resources" {
+        resources.parent()?.parent()?
+    } else {
+        resources.parent()?
+    };
+    (bundled_cli(app) == cli).then(|| app.to_path_buf())
+}
+
+/// Kind of an install at `app` that is not at one of the known locations.
+#[cfg(feature = "lmstudio")]
+fn kind_of_app(app: &Path) -> LmStudioInstallKind {
+    if app.extension().is_some_and(|ext| ext == "app") {
+        LmStudioInstallKind::AppBundle
+    } else if is_winget_package(app) {
+        LmStudioInstallKind::Winget
+    } else {
+        LmStudioInstallKind::Portable
+    }
+}
+
+/// Whether `app` is under winget's package directory,
+/// `%LOCALAPPDATA%\Microsoft\WinGet\Packages`.
+#[cfg(feature = "lmstudio")]
+fn is_winget_package(app: &Path) -> bool {
+    let components: Vec<String> = app
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_ascii_lowercase())
+        .collect();
+    components
+        .windows(2)
+        .any(|pair| pair[0] == "winget" && pair[1] == "packages")
+}
+
+/// Version recorded by the app at `app`: in `Info.plist` for a bundle,
+/// else in the `package.json` of its resources.
+#[cfg(feature = "lmstudio")]
+fn app_version(app: &Path) -> Option<String> {
+    if app.extension().is_some_and(|ext| ext == "app") {
+        let plist = std::fs::read_to_string(app.join("Contents").join("Info.plist")).ok()?;
+        return plist_string(&plist, "CFBundleShortVersionString");
+    }
+    let package =
+        std::fs::read_to_string(app.join("resources").join("app").join("package.json")).ok()?;
+    let package: serde_json::Value = serde_json::from_str(&package).ok()?;
+    package
+        .get("version")
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_string)
+}
+
+/// The string value of `key` in an XML property list.
+#[cfg(feature = "lmstudio")]
+fn plist_string(plist: &str, key: &str) -> Option<String> {
+    let after_key = &plist[plist.find(&format!("<key>{key}</key>"))?..];
+    let start = after_key.find("<string>")? + "<string>".len();
+    let end = start + after_key[start..].find("</string>")?;
+    Some(after_key[start..end].trim().to_string())
+}
+
+/// Where each kind of install puts the app on this platform, most common
+/// first.
+#[cfg(all(feature = "lmstudio", target_os = "macos"))]
+pub(crate) fn app_candidates() -> Vec<(PathBuf, LmStudioInstallKind)> {
+    let mut candidates = vec![(
+        PathBuf::from("/Applications/LM Studio.app"),
+        LmStudioInstallKind::AppBundle,
+    )];
+    if let Some(home) = dirs::home_dir() {
+        candidates.push((
+            home.join("Applications").join("LM Studio.app"),
+            LmStudioInstallKind::AppBundle,
+        ));
+    }
+    candidates
+}
+
+#[cfg(all(feature = "lmstudio", target_os = "windows"))]
+pub(crate) fn app_candidates() -> Vec<(PathBuf, LmStudioInstallKind)> {
+    let mut candidates: Vec<(PathBuf, LmStudioInstallKind)> = registry_install_locations()
+        .into_iter()
+        .map(|app| {
+            let kind = if is_winget_package(&app) {
+                LmStudioInstallKind::Winget
+            } else {
+                LmStudioInstallKind::Installer
+            };
+            (app, kind)
+        })
+        .collect();
+    // The default per-user and machine-wide install directories, for
+    // installs the registry does not list.
+    if let Some(local) = std::env::var_os("LOCALAPPDATA").map(PathBuf::from) {
+        candidates.push((
+            local.join("Programs").join("LM Studio"),
+            LmStudioInstallKind::Installer,
+        ));
+        candidates.extend(winget_packages(
+            &local.join("Microsoft").join("WinGet").join("Packages"),
+        ));
+    }
+    if let Some(programs) = std::env::var_os("ProgramFiles") {
+        candidates.push((
+            PathBuf::from(programs).join("LM Studio"),
+            LmStudioInstallKind::Installer,
+        ));
+    }
+    candidates
+}
+
+#[cfg(all(
+    feature = "lmstudio",
+    not(any(target_os = "macos", target_os = "windows"))
+))]
+pub(crate) fn app_candidates() -> Vec<(PathBuf, LmStudioInstallKind)> {
+    Vec::new()
+}
+
+/// LM Studio packages winget unpacked under `packages`, e.g.
+/// `ElementLabs.LMStudio_Microsoft.Winget.Source_8wekyb3d8bbwe`.
+#[cfg(all(feature = "lmstudio", target_os = "windows"))]
+fn winget_packages(packages: &Path) -> Vec<(PathBuf, LmStudioInstallKind)> {
+    let Ok(entries) = std::fs::read_dir(packages) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with("ElementLabs.LMStudio")
+        })
+        .map(|entry| (entry.path(), LmStudioInstallKind::Winget))
+        .collect()
+}
+
+/// Install directories of LM Studio listed under the uninstall keys of the
+/// current user and of the machine.
+#[cfg(all(feature = "lmstudio", target_os = "windows"))]
+fn registry_install_locations() -> Vec<PathBuf> {
+    [
+        r"HKCU\Software\Microsoft\Windows\CurrentVersion\Uninstall",
+        r"HKLM\Software\Microsoft\Windows\CurrentVersion\Uninstall",
+    ]
+    .into_iter()
+    .filter_map(|key| {
+        std::process::Command::new("reg")
+            .args(["query", key, "/s"])
+            .output()
+            .ok()
+    })
+    .filter(|output| output.status.success())
+    .flat_map(|output| lmstudio_install_locations(&String::from_utf8_lossy(&output.stdout)))
+    .collect()
+}
+
+/// Install directories of LM Studio in the output of `reg query <key> /s`,
+/// which prints each program's key followed by its values, e.g.
+/// `    InstallLocation    REG_SZ    C:\Users\me\AppData\Local\Programs\LM Studio`.
+#[cfg(all(feature = "lmstudio", any(target_os = "windows", test)))]
+fn lmstudio_install_locations(reg_output: &str) -> Vec<PathBuf> {
+    let mut locations = Vec::new();
+    let mut is_lmstudio = false;
+    let mut location: Option<PathBuf> = None;
+    for line in reg_output.lines() {
+        if line.starts_with("HKEY_") {
+            if is_lmstudio && let Some(location) = location.take() {
+                locations.push(location);
+            }
+            is_lmstudio = false;
+            location = None;
+            continue;
+        }
+        let Some((name, rest)) = line.trim_start().split_once("    REG_") else {
+            continue;
+        };
+        let data = rest.split_once("    ").map_or("", |(_, data)| data).trim();
+        match name {
+            "DisplayName" => is_lmstudio = data.starts_with("LM Studio"),
+            "InstallLocation" if !data.is_empty() => {
+                location = Some(PathBuf::from(data.trim_matches('"')));
+            }
+            _ => {}
+        }
+    }
+    if is_lmstudio && let Some(location) = location {
+        locations.push(location);
+    }
+    locations
+}
+
+#[cfg(all(test, feature = "lmstudio"))]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn lmstudio_installs_are_read_from_the_uninstall_keys() {
+        let output = concat!(
+            "\r\n",
+            "HKEY_CURRENT_USER\\Software\\Microsoft\\Windows\\CurrentVersion\\Uninstall\\Ollama\r\n",
+            "    DisplayName    REG_SZ    Ollama\r\n",
+            "    InstallLocation    REG_SZ    C:\\Users\\me\\AppData\\Local\\Programs\\Ollama\r\n",
+            "\r\n",
+            "HKEY_CURRENT_USER\\Software\\Microsoft\\Windows\\CurrentVersion\\Uninstall\\0a1b2c3d-lm-studio\r\n",
+            "    DisplayName    REG_SZ    LM Studio 0.3.30\r\n",
+            "    DisplayVersion    REG_SZ    0.3.30\r\n",
+            "    InstallLocation    REG_SZ    \"D:\\Apps\\LM Studio\"\r\n",
+            "\r\n",
+            "HKEY_CURRENT_USER\\Software\\Microsoft\\Windows\\CurrentVersion\\Uninstall\\Empty\r\n",
+            "    DisplayName    REG_SZ    LM Studio\r\n",
+            "    InstallLocation    REG_SZ    \r\n",
+        );
+        assert_eq!(
+            lmstudio_install_locations(output),
+            vec![PathBuf::from("D:\\Apps\\LM Studio")]
+        );
+    }
+
+    #[test]
+    fn a_bundled_cli_leads_back_to_its_app() {
+        let bundle = PathBuf::from("/Applications/LM Studio.app");
+        assert_eq!(app_of_bundled_cli(&bundled_cli(&bundle)), Some(bundle));
+        let portable = PathBuf::from("tools").join("LM Studio");
+        assert_eq!(
+            app_of_bundled_cli(&bundled_cli(&portable)),
+            Some(portable.clone())
+        );
+        assert_eq!(
+            app_of_bundled_cli(
+                &PathBuf::from("home")
+                    .join(".lmstudio")
+                    .join("bin")
+                    .join("lms")
+            ),
+            None
+        );
+        assert_eq!(kind_of_app(&portable), LmStudioInstallKind::Portable);
+        let winget = PathBuf::from("AppData")
+            .join("Local")
+            .join("Microsoft")
+            .join("WinGet")
+            .join("Packages")
+            .join("ElementLabs.LMStudio_Microsoft.Winget.Source_8wekyb3d8bbwe");
+        assert_eq!(kind_of_app(&winget), LmStudioInstallKind::Winget);
+    }
+
+    #[test]
+    fn versions_are_read_from_the_app() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let bundle = dir.path().join("LM Studio.app");
+        std::fs::create_dir_all(bundle.join("Contents")).expect("bundle");
+        std::fs::write(
+            bundle.join("Contents").join("Info.plist"),
+            "<plist><dict>\n\t<key>CFBundleName</key>\n\t<string>LM Studio</string>\n\t<key>CFBundleShortVersionString</key>\n\t<string>0.3.30</string>\n</dict></plist>",
+        )
+        .expect("plist");
+        assert_eq!(app_version(&bundle), Some("0.3.30".to_string()));
+
+        let portable = dir.path().join("portable");
+        std::fs::create_dir_all(portable.join("resources").join("app")).expect("resources");
+        std::fs::write(
+            portable.join("resources").join("app").join("package.json"),
+            r#"{"name": "lm-studio", "version": "0.3.29"}"#,
+        )
+        .expect("package.json");
+        assert_eq!(app_version(&portable), Some("0.3.29".to_string()));
+        assert_eq!(app_version(&dir.path().join("missing")), None);
+    }
+}
@@ -0,0 +1,86 @@
+//! Loading models into the memory of the local server ahead of a request.
+
+use chrono::Utc;
+use codex_core::config::Config;
+use codex_protocol::oss::OssEvent;
+use std::io;
+
+use crate::LocalModelBackend;
+use crate::OllamaClient;
+use crate::audit;
+use crate::audit::AuditAction;
+use crate::audit::AuditEntry;
+use crate::command::run_command;
+use crate::events::publish;
+use crate::lms;
+
+/// Load `model` on the server behind the `oss` provider, so the next request
+/// does not wait for it.
+///
+/// Ollama loads a model for any request, so a single token is generated. LM
+/// Studio is asked through its `lms` CLI when it is installed; without it,
+/// and on generic servers, the model is left to load on demand.
+pub async fn load_model(config: &Config, model: &str) -> io::Result<()> {
+    let client = OllamaClient::from_oss_provider(config)?;
+    let started_at = Utc::now();
+    let loaded = load_model_with_backend(&client, model).await;
+    if let (Some(audit_log), Ok(true) | Err(_)) = (client.audit_log(), &loaded) {
+        audit::record(
+            audit_log,
+            &AuditEntry {
+                started_at,
+                finished_at: Utc::now(),
+                action: AuditAction::Load {
+                    model: model.to_string(),
+                    host_root: client.host_root().to_string(),
+                },
+                error: loaded.as_ref().err().map(ToString::to_string),
+            },
+        );
+    }
+    loaded.map(drop)
+}
+
+/// [`load_model`] on any [`LocalModelBackend`], such as a fake in tests.
+/// Returns whether `model` is loaded afterwards.
+pub async fn load_model_with_backend(
+    server: &dyn LocalModelBackend,
+    model: &str,
+) -> io::Result<bool> {
+    let loaded = server.load(model).await?;
+    if loaded {
+        publish(OssEvent::ModelLoaded {
+            model: model.to_string(),
+        });
+    }
+    Ok(loaded)
+}
+
+/// Whether `model` is loaded afterwards; without `lms` it is left alone.
+pub(crate) async fn load_lmstudio(client: &OllamaClient, model: &str) -> io::Result<bool> {
+    if client
+        .fetch_lmstudio_loaded_models()
+        .await?
+        .iter()
+        .any(|loaded| loaded == model)
+    {
+        return Ok(true);
+    }
+    let Some(lms) = client.trusted_lms_cli()? else {
+        return Ok(false);
+    };
+    let args = lms::load_args(model);
+    client.log(format_args!(
+        "running `{} {}`",
+        lms.display(),
+        args.join(" ")
+    ));
+    let output = run_command(client.audit_log(), &lms, &args).await?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "`lms load {model}` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(true)
+}
@@ -0,0 +1,225 @@
+//! Reading the local server's log, which is where "the model produced
+//! nothing" failures are usually explained.
+
+use codex_core::protocol_config_types::OssBackend;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::server::server_log_path;
+
+/// How much of the end of the file is scanned for the initial lines.
+const TAIL_WINDOW_BYTES: u64 = 256 * 1024;
+
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Severity of a server log line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// Parse a level name as written by Ollama (`level=WARN`), LM Studio
+    /// (`[ERROR]`), or a user on the command line (`warn`).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "debug" | "trace" => Some(Self::Debug),
+            "info" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" | "fatal" | "panic" => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
+/// Level of a single line, if it states one.
+fn line_level(line: &str) -> Option<LogLevel> {
+    if let Some(rest) = line
+        .split_whitespace()
+        .find_map(|f| f.strip_prefix("level="))
+    {
+        return LogLevel::parse(rest.trim_matches('"'));
+    }
+    line.split('[')
+        .skip(1)
+        .filter_map(|chunk| chunk.split(']').next())
+        .find_map(LogLevel::parse)
+}
+
+/// Filters log lines by minimum level. Lines that do not state a level, such
+/// as the output of the inference engine, take the level of the line before.
+#[derive(Debug, Clone)]
+pub struct LogFilter {
+    min_level: LogLevel,
+    current: LogLevel,
+}
+
+impl LogFilter {
+    pub fn new(min_level: LogLevel) -> Self {
+        Self {
+            min_level,
+            current: LogLevel::Info,
+        }
+    }
+
+    pub fn accepts(&mut self, line: &str) -> bool {
+        if let Some(level) = line_level(line) {
+            self.current = level;
+        }
+        self.current >= self.min_level
+    }
+}
+
+/// Where the server behind `backend` writes its log, most recently written
+/// first. Only paths that exist are returned.
+pub fn server_log_candidates(state_dir: &Path, backend: Option<OssBackend>) -> Vec<PathBuf> {
+    let mut candidates = vec![server_log_path(state_dir)];
+    if let Some(home) = dirs::home_dir() {
+        match backend {
+            Some(OssBackend::LmStudio) => {
+                candidates.extend(newest_log_in(&home.join(".lmstudio").join("server-logs")));
+            }
+            Some(OssBackend::Generic) => {}
+            Some(OssBackend::Ollama) | None => {
+                candidates.push(home.join(".ollama").join("logs").join("server.log"));
+                if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+                    candidates.push(PathBuf::from(local).join("Ollama").join("server.log"));
+                }
+            }
+        }
+    }
+    let mut existing: Vec<(PathBuf, std::time::SystemTime)> = candidates
+        .into_iter()
+        .filter_map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect();
+    existing.sort_by(|a, b| b.1.cmp(&a.1));
+    existing.into_iter().map(|(path, _)| path).collect()
+}
+
+/// LM Studio rotates its server log daily into `YYYY-MM/` folders.
+fn newest_log_in(dir: &Path) -> Option<PathBuf> {
+    let mut newest: Option<(PathBuf, std::time::SystemTime)> = None;
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            if path.extension().is_none_or(|ext| ext != "log") {
+                continue;
+            }
+            let Ok(modified) = entry.metadata().and_then(|m| m.modified()) else {
+                continue;
+            };
+            if newest.as_ref().is_none_or(|(_, best)| modified > *best) {
+                newest = Some((path, modified));
+            }
+        }
+    }
+    newest.map(|(path, _)| path)
+}
+
+/// The last `count` lines of the file at `path`, and the offset at which
+/// following should resume.
+pub fn read_last_lines(path: &Path, count: usize) -> io::Result<(Vec<String>, u64)> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(TAIL_WINDOW_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+    let text = String::from_utf8_lossy(&buf);
+    let mut lines: Vec<&str> = text.lines().collect();
+    // The first line is likely cut in half when reading from the middle.
+    if start > 0 && !lines.is_empty() {
+        lines.remove(0);
+    }
+    let skip = lines.len().saturating_sub(count);
+    let lines = lines[skip..].iter().map(|line| line.to_string()).collect();
+    Ok((lines, start + buf.len() as u64))
+}
+
+/// Call `on_line` for every line appended to `path` after `offset`, until
+/// the returned future is dropped. A truncated (rotated) file is read again
+/// from the start.
+pub async fn follow_log(
+    path: &Path,
+    mut offset: u64,
+    mut on_line: impl FnMut(&str),
+) -> io::Result<()> {
+    let mut partial = String::new();
+    loop {
+        let len = std::fs::metadata(path)?.len();
+        if len < offset {
+            offset = 0;
+            partial.clear();
+        }
+        if len > offset {
+            let mut file = std::fs::File::open(path)?;
+            file.seek(SeekFrom::Start(offset))?;
+            let mut buf = Vec::new();
+            file.read_to_end(&mut buf)?;
+            offset += buf.len() as u64;
+            partial.push_str(&String::from_utf8_lossy(&buf));
+            while let Some(pos) = partial.find('\n') {
+                let line: String = partial.drain(..=pos).collect();
+                on_line(line.trim_end_matches(['\r', '\n']));
+            }
+        }
+        tokio::time::sleep(FOLLOW_POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_line_level_understands_ollama_and_lmstudio_formats() {
+        assert_eq!(
+            line_level(r#"time=2025-10-01T10:00:00Z level=WARN source=server.go:1 msg="low vram""#),
+            Some(LogLevel::Warn)
+        );
+        assert_eq!(
+            line_level("[2025-10-01 10:00:00][ERROR] Model failed to load"),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(line_level("llama_model_loader: loaded meta data"), None);
+    }
+
+    #[test]
+    fn test_filter_carries_level_to_unleveled_lines() {
+        let mut filter = LogFilter::new(LogLevel::Warn);
+        assert!(!filter.accepts("time=t level=INFO msg=starting"));
+        assert!(!filter.accepts("llama_model_loader: loaded meta data"));
+        assert!(filter.accepts("time=t level=ERROR msg=\"llama runner terminated\""));
+        assert!(filter.accepts("  exit status 2"));
+    }
+
+    #[test]
+    fn test_read_last_lines() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("server.log");
+        std::fs::write(&path, "one\ntwo\nthree\n").expect("write log");
+        let (lines, offset) = read_last_lines(&path, 2).expect("read log");
+        assert_eq!(lines, vec!["two", "three"]);
+        assert_eq!(offset, 14);
+    }
+}
@@ -0,0 +1,266 @@
+//! User-facing text of the OSS path (download prompts, `codex oss doctor`
+//! results, and the TUI's error cards), looked up by [`MessageId`] so it
+//! can be translated.
+//!
+//! English is built in. A translation is a JSON object mapping message ids
+//! to text, saved as `$CODEX_HOME/locales/<language>.json`, e.g. `de.json`
+//! or `pt-BR.json`; the language comes from `LC_ALL`, `LC_MESSAGES`, or
+//! `LANG`, and otherwise from the OS. Placeholders such as `{model}` are
+//! filled in from the message's arguments. Messages missing from the
+//! translation, or whose translation drops a placeholder, stay in English,
+//! so a partial translation is still usable.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+macro_rules! messages {
+    ($($variant:ident = $id:literal => $english:literal,)*) => {
+        /// A user-facing message of the OSS path.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum MessageId {
+            $($variant,)*
+        }
+
+        impl MessageId {
+            /// Every message, for translators.
+            pub const ALL: &'static [MessageId] = &[$(MessageId::$variant,)*];
+
+            /// Key of the message in translation files.
+            pub fn id(self) -> &'static str {
+                match self {
+                    $(MessageId::$variant => $id,)*
+                }
+            }
+
+            /// The built-in English text.
+            pub fn english(self) -> &'static str {
+                match self {
+                    $(MessageId::$variant => $english,)*
+                }
+            }
+        }
+    };
+}
+
+messages! {
+    DoctorOllamaFound = "doctor.ollama-found" => "Found ollama at {path}",
+    DoctorOllamaMissing = "doctor.ollama-missing" => "ollama executable not found on PATH",
+    DoctorInstallOllama = "doctor.install-ollama" => "Install Ollama: https://github.com/ollama/ollama?tab=readme-ov-file#ollama",
+    DoctorRestoreProvider = "doctor.restore-provider" => "Restore the built-in `oss` provider in config.toml.",
+    DoctorServerReachable = "doctor.server-reachable" => "Server reachable at {host}",
+    DoctorServerUnreachable = "doctor.server-unreachable" => "No Ollama server reachable at {host}",
+    DoctorStartOllama = "doctor.start-ollama" => "Start the server with `ollama serve`.",
+    DoctorServerVersion = "doctor.server-version" => "Ollama {version}",
+    DoctorVersionUnknown = "doctor.version-unknown" => "Could not determine server version: {error}",
+    DoctorUpgradeOllama = "doctor.upgrade-ollama" => "Upgrade Ollama to a recent release.",
+    DoctorModelAvailable = "doctor.model-available" => "Model '{model}' is available locally{size}",
+    DoctorModelMissing = "doctor.model-missing" => "Model '{model}' is not downloaded yet",
+    DoctorPullModel = "doctor.pull-model" => "Download it with `codex models pull {model}`.",
+    DoctorModelsUnlisted = "doctor.models-unlisted" => "Could not list local models: {error}",
+    DoctorSkippedUnreachable = "doctor.skipped-unreachable" => "Server unreachable",
+    DoctorLmStudioMissing = "doctor.lmstudio-missing" => "LM Studio not found",
+    DoctorInstallLmStudio = "doctor.install-lmstudio" => "Install LM Studio: https://lmstudio.ai",
+    DoctorLmsFound = "doctor.lms-found" => "{install}, lms CLI at {path}",
+    DoctorLmsRefused = "doctor.lms-refused" => "{install}, {error}",
+    DoctorFixLms = "doctor.fix-lms" => "Reinstall the lms CLI (npx lmstudio install-cli) or fix the permissions of {path}",
+    DoctorLmsMissing = "doctor.lms-missing" => "{install}, lms CLI not found",
+    DoctorInstallLms = "doctor.install-lms" => "Install the lms CLI: npx lmstudio install-cli",
+    DoctorModelsDirUnknown = "doctor.models-dir-unknown" => "Could not determine the model directory",
+    DoctorDiskLow = "doctor.disk-low" => "Only {free} free for {path}",
+    DoctorFreeDisk = "doctor.free-disk" => "Free at least {size} of disk space before downloading a model.",
+    DoctorDiskFree = "doctor.disk-free" => "{free} free for {path}",
+    DoctorDiskUnknown = "doctor.disk-unknown" => "Free disk space unavailable on this platform",
+    DoctorMemoryUnknown = "doctor.memory-unknown" => "Total memory unavailable on this platform",
+    DoctorMemoryLow = "doctor.memory-low" => "{total} of memory; the model needs about {required}",
+    DoctorSmallerModel = "doctor.smaller-model" => "Choose a smaller model or quantization with `-m`.",
+    DoctorMemory = "doctor.memory" => "{total} of memory",
+    ProgressPercent = "progress.percent" => "{percent}% downloaded",
+    ProgressPercentRemaining = "progress.percent-remaining" => "{percent}% downloaded, {remaining} remaining",
+    ProgressUnderMinute = "progress.under-minute" => "less than a minute",
+    ProgressOneMinute = "progress.one-minute" => "1 minute",
+    ProgressMinutes = "progress.minutes" => "{minutes} minutes",
+    ProgressHours = "progress.hours" => "about {hours} hours",
+    LicenseInfo = "license.info" => "'{model}' is published under the {license} license.",
+    LicenseTerms = "license.terms" => "Terms: {url}",
+    PromptAcceptLicense = "prompt.accept-license" => "Accept the {license} license and remember it in oss.accepted_licenses?",
+    PromptDownloadModel = "prompt.download-model" => "Download model '{model}'?",
+    PromptDownloadNow = "prompt.download-now" => "Download '{model}' now?",
+    CardServerNotResponding = "card.server-not-responding" => "The local {backend} server is not responding",
+    CardModelNotFound = "card.model-not-found" => "Model '{model}' was not found on the {backend} server",
+    CardModelUnsupported = "card.model-unsupported" => "{model} cannot drive the agent",
+    CardPickToolModel = "card.pick-tool-model" => "Pick a model that supports tool calling with /model",
+    CardDownloadFailed = "card.download-failed" => "Downloading {model} failed",
+    CardCheckNetwork = "card.check-network" => "Check the network connection and try again; the download resumes where it stopped",
+    CardCheckDisk = "card.check-disk" => "Check that the disk holding the models has enough free space",
+    CardLicenseNotAccepted = "card.license-not-accepted" => "{model} needs its {license} license accepted",
+    CardReadLicense = "card.read-license" => "Read the terms at {url}",
+    CardAcceptLicense = "card.accept-license" => "Accept them with `codex models pull {model}`, or add \"{license}\" to oss.accepted_licenses",
+    CardModelFailed = "card.model-failed" => "{model} failed to respond",
+    CardProviderFailed = "card.provider-failed" => "The local provider failed",
+    CardRunDoctor = "card.run-doctor" => "Run `codex oss doctor` to check the setup",
+    CardFreeMemoryAmount = "card.free-memory-amount" => "Free {size} of memory by closing other applications",
+    CardFreeMemory = "card.free-memory" => "Free memory by closing other applications",
+    CardUnloadModels = "card.unload-models" => "Unload other models with `codex oss unload`",
+    CardSmallerModel = "card.smaller-model" => "Switch to a smaller model or quantization with /model",
+    CardOutOfMemory = "card.out-of-memory" => "Not enough memory to load {model}",
+    CardServerFailing = "card.server-failing" => "The local {backend} server keeps failing",
+    CardRestartServer = "card.restart-server" => "Restart the {backend} server; requests resume once the pause ends",
+    CardStartOllama = "card.start-ollama" => "Start it with `ollama serve`, or `codex oss serve start`",
+    CardStartLmStudio = "card.start-lmstudio" => "Start it with `lms server start`",
+    CardStartGeneric = "card.start-generic" => "Start the server that --oss-url points at",
+    CardCheckOssUrl = "card.check-oss-url" => "Check --oss-url if the server listens on another address",
+    CardDidYouMean = "card.did-you-mean" => "Did you mean {models}?",
+    CardPullOllama = "card.pull-ollama" => "Download it with `codex models pull {model}`",
+    CardPullLmStudio = "card.pull-lmstudio" => "Download it with `lms get {model}`, then `lms load {model}`",
+    CardCheckModelList = "card.check-model-list" => "Check the model names the server lists under /v1/models",
+    CardPickModel = "card.pick-model" => "Pick an available model with /model",
+    CardServerLog = "card.server-log" => "See the server log with `codex oss logs`",
+}
+
+/// Translations for the user's language, loaded once.
+static CATALOG: LazyLock<HashMap<String, String>> = LazyLock::new(load_catalog);
+
+/// `id` in the user's language with `args` filled in, e.g.
+/// `message(MessageId::DoctorPullModel, &[("model", "qwen3:8b")])`.
+pub fn message(id: MessageId, args: &[(&str, &str)]) -> String {
+    render(id, CATALOG.get(id.id()).map(String::as_str), args)
+}
+
+fn render(id: MessageId, translation: Option<&str>, args: &[(&str, &str)]) -> String {
+    let template = translation
+        .filter(|translation| keeps_placeholders(id.english(), translation))
+        .unwrap_or(id.english());
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+/// Whether `translation` has every placeholder of `english`, so that no
+/// argument is silently lost.
+fn keeps_placeholders(english: &str, translation: &str) -> bool {
+    english
+        .split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}'))
+        .all(|(name, _)| translation.contains(&format!("{{{name}}}")))
+}
+
+fn load_catalog() -> HashMap<String, String> {
+    let Some(language) = user_language() else {
+        return HashMap::new();
+    };
+    let Ok(codex_home) = codex_core::config::find_codex_home() else {
+        return HashMap::new();
+    };
+    read_catalog(&codex_home.join("locales"), &language)
+}
+
+/// The language to translate to, as a BCP 47 tag such as `pt-BR`; `None`
+/// for English, which needs no catalog.
+fn user_language() -> Option<String> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .or_else(sys_locale::get_locale)?;
+    normalize_locale(&locale)
+}
+
+/// `de_DE.UTF-8` -> `de-DE`; `C`, `POSIX`, and English -> `None`.
+fn normalize_locale(locale: &str) -> Option<String> {
+    let tag = locale
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .replace('_', "-");
+    let language = tag.split('-').next().unwrap_or_default();
+    if language.is_empty() || language.eq_ignore_ascii_case("en") || tag == "C" || tag == "POSIX" {
+        return None;
+    }
+    Some(tag)
+}
+
+/// The catalog for `language` in `dir`, trying the full tag (`pt-BR.json`)
+/// before the bare language (`pt.json`). Unreadable catalogs are ignored.
+fn read_catalog(dir: &Path, language: &str) -> HashMap<String, String> {
+    let bare = language.split('-').next().unwrap_or(language);
+    [language, bare]
+        .into_iter()
+        .find_map(|name| {
+            let contents = std::fs::read_to_string(dir.join(format!("{name}.json"))).ok()?;
+            match serde_json::from_str(&contents) {
+                Ok(catalog) => Some(catalog),
+                Err(err) => {
+                    tracing::warn!("ignoring the translations in {name}.json: {err}");
+                    None
+                }
+            }
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+
+    #[test]
+    fn message_ids_are_unique() {
+        let ids: HashSet<&str> = MessageId::ALL.iter().map(|id| id.id()).collect();
+        assert_eq!(ids.len(), MessageId::ALL.len());
+    }
+
+    #[test]
+    fn translations_fill_in_arguments_and_fall_back_to_english() {
+        let args = [("model", "qwen3:8b")];
+        assert_eq!(
+            render(MessageId::DoctorPullModel, None, &args),
+            "Download it with `codex models pull qwen3:8b`."
+        );
+        assert_eq!(
+            render(
+                MessageId::DoctorPullModel,
+                Some("Lade es mit `codex models pull {model}` herunter."),
+                &args
+            ),
+            "Lade es mit `codex models pull qwen3:8b` herunter."
+        );
+        // A translation that loses the model name is not used.
+        assert_eq!(
+            render(MessageId::DoctorPullModel, Some("Lade es herunter."), &args),
+            "Download it with `codex models pull qwen3:8b`."
+        );
+    }
+
+    #[test]
+    fn locales_map_to_catalog_names() {
+        assert_eq!(normalize_locale("de_DE.UTF-8"), Some("de-DE".to_string()));
+        assert_eq!(normalize_locale("pt-BR"), Some("pt-BR".to_string()));
+        assert_eq!(normalize_locale("en_US.UTF-8"), None);
+        assert_eq!(normalize_locale("C"), None);
+    }
+
+    #[test]
+    fn the_regional_catalog_is_preferred() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("pt.json"), r#"{"card.pick-model": "pt"}"#)
+            .expect("pt.json");
+        assert_eq!(
+            read_catalog(dir.path(), "pt-BR").get("card.pick-model"),
+            Some(&"pt".to_string())
+        );
+        std::fs::write(
+            dir.path().join("pt-BR.json"),
+            r#"{"card.pick-model": "pt-BR"}"#,
+        )
+        .expect("pt-BR.json");
+        assert_eq!(
+            read_catalog(dir.path(), "pt-BR").get("card.pick-model"),
+            Some(&"pt-BR".to_string())
+        );
+        assert!(read_catalog(dir.path(), "fr").is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+//! Model ids as local servers spell them, normalized for comparison.
+//!
+//! The same model goes by several names: Ollama lists `gpt-oss` as
+//! `gpt-oss:latest` and accepts `library/gpt-oss` or
+//! `registry.ollama.ai/library/gpt-oss`; LM Studio lists `qwen/qwen3-8b`
+//! where users type `qwen3-8b`, and may add an `@q4_k_m` quantization.
+//! Comparing such names as strings made a present model look missing, and
+//! so downloaded again.
+
+use std::fmt;
+
+const DEFAULT_TAG: &str = "latest";
+const DEFAULT_REGISTRY: &str = "registry.ollama.ai";
+const DEFAULT_NAMESPACE: &str = "library";
+const REVISION_PREFIX: &str = "@sha256:";
+
+/// A parsed model id: `[publisher/]name[:tag][@quant|@sha256:revision]`.
+/// Every part is lowercased, as both servers match names case-insensitively.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ModelId {
+    /// Registry and namespace, or LM Studio's publisher; `None` for
+    /// Ollama's default `registry.ollama.ai/library`.
+    pub publisher: Option<String>,
+    pub name: String,
+    /// Ollama tag; `latest` when the id names none.
+    pub tag: String,
+    /// LM Studio quantization suffix, e.g. `q4_k_m`.
+    pub quant: Option<String>,
+    /// Content digest pinning one download, e.g. `sha256:8934d96d…`.
+    pub revision: Option<String>,
+}
+
+impl ModelId {
+    /// Parse `raw`, never failing: whatever is not recognized as a
+    /// publisher, tag, quantization, or revision is the name.
+    pub fn parse(raw: &str) -> Self {
+        let raw = raw.trim().to_lowercase();
+        let (rest, revision, quant) = match raw.split_once(REVISION_PREFIX) {
+            Some((rest, digest)) => (rest, Some(format!("sha256:{digest}")), None),
+            None => match raw.rsplit_once('@') {
+                Some((rest, quant)) if !quant.is_empty() => (rest, None, Some(quant.to_string())),
+                _ => (raw.as_str(), None, None),
+            },
+        };
+        // A ':' before the last '/' is a registry port, not a tag.
+        let (path, tag) = match rest.rsplit_once(':') {
+            Some((path, tag)) if !tag.is_empty() && !tag.contains('/') => (path, tag),
+            _ => (rest.trim_end_matches(':'), DEFAULT_TAG),
+        };
+        let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        if segments.len() > 1 && segments[0] == DEFAULT_REGISTRY {
+            segments.remove(0);
+        }
+        if segments.len() > 1 && segments[0] == DEFAULT_NAMESPACE {
+            segments.remove(0);
+        }
+        let name = segments.pop().unwrap_or_default().to_string();
+        Self {
+            publisher: (!segments.is_empty()).then(|| segments.join("/")),
+            name,
+            tag: tag.to_string(),
+            quant,
+            revision,
+        }
+    }
+
+    /// Whether `self` and `other` name the same model. Parts one side leaves
+    /// out (publisher, quantization, revision) match anything.
+    pub fn matches(&self, other: &ModelId) -> bool {
+        fn agree(a: &Option<String>, b: &Option<String>) -> bool {
+            match (a, b) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            }
+        }
+        self.name == other.name
+            && self.tag == other.tag
+            && agree(&self.publisher, &other.publisher)
+            && agree(&self.quant, &other.quant)
+            && agree(&self.revision, &other.revision)
+    }
+}
+
+impl fmt::Display for ModelId {
+    /// The shortest spelling that parses back to the same id.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(publisher) = &self.publisher {
+            write!(f, "{publisher}/")?;
+        }
+        f.write_str(&self.name)?;
+        if self.tag != DEFAULT_TAG {
+            write!(f, ":{}", self.tag)?;
+        }
+        if let Some(revision) = &self.revision {
+            write!(f, "@{revision}")?;
+        } else if let Some(quant) = &self.quant {
+            write!(f, "@{quant}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether `model` is among the ids a server listed, however either spells it.
+pub fn contains_model(models: &[String], model: &str) -> bool {
+    let wanted = ModelId::parse(model);
+    models
+        .iter()
+        .any(|listed| ModelId::parse(listed).matches(&wanted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    fn id(publisher: Option<&str>, name: &str, tag: &str) -> ModelId {
+        ModelId {
+            publisher: publisher.map(str::to_string),
+            name: name.to_string(),
+            tag: tag.to_string(),
+            quant: None,
+            revision: None,
+        }
+    }
+
+    #[test]
+    fn spellings_of_the_same_model_parse_alike() {
+        for raw in [
+            "gpt-oss",
+            "gpt-oss:latest",
+            "GPT-OSS",
+            " library/gpt-oss ",
+            "registry.ollama.ai/library/gpt-oss:latest",
+        ] {
+            assert_eq!(ModelId::parse(raw), id(None, "gpt-oss", "latest"), "{raw}");
+        }
+        assert_eq!(
+            ModelId::parse("hf.co/unsloth/Qwen3-8B-GGUF:Q4_K_M"),
+            id(Some("hf.co/unsloth"), "qwen3-8b-gguf", "q4_k_m")
+        );
+        assert_eq!(
+            ModelId::parse("localhost:5000/team/coder:7b"),
+            id(Some("localhost:5000/team"), "coder", "7b")
+        );
+    }
+
+    #[test]
+    fn quantizations_and_revisions_are_split_off() {
+        let quantized = ModelId::parse("qwen/qwen3-8b@q4_k_m");
+        assert_eq!(quantized.publisher.as_deref(), Some("qwen"));
+        assert_eq!(quantized.quant.as_deref(), Some("q4_k_m"));
+        let pinned = ModelId::parse("gpt-oss:20b@sha256:8934d96d");
+        assert_eq!(pinned.tag, "20b");
+        assert_eq!(pinned.revision.as_deref(), Some("sha256:8934d96d"));
+        assert_eq!(pinned.quant, None);
+    }
+
+    #[test]
+    fn listed_models_match_however_they_are_spelled() {
+        let listed = vec!["gpt-oss:latest".to_string(), "qwen/qwen3-8b".to_string()];
+        assert!(contains_model(&listed, "gpt-oss"));
+        assert!(contains_model(&listed, "qwen3-8b"));
+        assert!(contains_model(&listed, "qwen3-8b@q4_k_m"));
+        assert!(!contains_model(&listed, "gpt-oss:20b"));
+        assert!(!contains_model(&listed, "mistral/qwen3-8b"));
+    }
+
+    fn segment() -> impl Strategy<Value = String> {
+        "[a-z][a-z0-9._-]{0,10}"
+    }
+
+    prop_compose! {
+        fn model_id()(
+            publisher in proptest::option::of("[a-z][a-z0-9-]{0,8}(/[a-z][a-z0-9-]{0,8})?"),
+            name in segment(),
+            tag in proptest::option::of(segment()),
+            quant in proptest::option::of("q[2-8]_k(_[msl])?"),
+            revision in proptest::option::of("[0-9a-f]{8,64}"),
+        ) -> ModelId {
+            let publisher = publisher
+                .filter(|p| p.split('/').next() != Some(DEFAULT_NAMESPACE));
+            ModelId {
+                publisher,
+                name,
+                tag: tag.unwrap_or_else(|| DEFAULT_TAG.to_string()),
+                quant: quant.filter(|_| revision.is_none()),
+                revision: revision.map(|digest| format!("sha256:{digest}")),
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn displayed_ids_parse_back_unchanged(id in model_id()) {
+            prop_assert_eq!(ModelId::parse(&id.to_string()), id);
+        }
+
+        #[test]
+        fn case_whitespace_and_default_parts_do_not_change_the_id(
+            id in model_id(),
+            pad in "[ \t]{0,3}",
+        ) {
+            let mut spelled = id.to_string().to_uppercase();
+            if id.publisher.is_none() {
+                spelled = format!("{DEFAULT_REGISTRY}/{DEFAULT_NAMESPACE}/{spelled}");
+            }
+            if id.tag == DEFAULT_TAG && id.quant.is_none() && id.revision.is_none() {
+                spelled.push_str(":latest");
+            }
+            prop_assert_eq!(ModelId::parse(&format!("{pad}{spelled}{pad}")), id);
+        }
+
+        #[test]
+        fn an_id_matches_itself_without_its_optional_parts(id in model_id()) {
+            let bare = ModelId {
+                publisher: None,
+                quant: None,
+                revision: None,
+                ..id.clone()
+            };
+            prop_assert!(id.matches(&id));
+            prop_assert!(bare.matches(&id));
+            prop_assert!(id.matches(&bare));
+        }
+
+        #[test]
+        fn parsing_never_panics(raw in "\\PC{0,40}") {
+            let _ = ModelId::parse(&raw);
+        }
+    }
+}
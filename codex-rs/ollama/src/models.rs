@@ -0,0 +1,511 @@
+pub use codex_protocol::oss::LoadedModel;
+pub use codex_protocol::oss::LocalModel;
+pub use codex_protocol::oss::ModelDetails;
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+
+/// Format a byte count as a human-readable size, e.g. "12.83 GB".
+pub fn format_size(bytes: u64) -> String {
+    const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+    const MB: f64 = 1024.0 * 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= GB {
+        format!("{:.2} GB", bytes / GB)
+    } else {
+        format!("{:.1} MB", bytes / MB)
+    }
+}
+
+/// Parse the `/api/tags` response into a list of models. Fields that are only
+/// available from other endpoints (`context_length`, `loaded`) are left unset.
+pub(crate) fn local_models_from_tags(value: &JsonValue) -> Vec<LocalModel> {
+    value
+        .get("models")
+        .and_then(|m| m.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| {
+                    let name = v.get("name").and_then(|n| n.as_str())?;
+                    let details = v.get("details");
+                    let detail = |key: &str| {
+                        details
+                            .and_then(|d| d.get(key))
+                            .and_then(|s| s.as_str())
+                            .filter(|s| !s.is_empty())
+                            .map(str::to_string)
+                    };
+                    Some(LocalModel {
+                        name: name.to_string(),
+                        size_bytes: v.get("size").and_then(JsonValue::as_u64),
+                        quantization: detail("quantization_level"),
+                        parameter_size: detail("parameter_size"),
+                        context_length: None,
+                        loaded: false,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse the `/api/tags` response into `(name, manifest digest)` pairs.
+pub(crate) fn model_digests_from_tags(value: &JsonValue) -> Vec<(String, String)> {
+    value
+        .get("models")
+        .and_then(|m| m.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| {
+                    let name = v.get("name").and_then(|n| n.as_str())?;
+                    let digest = v.get("digest").and_then(|d| d.as_str())?;
+                    Some((name.to_string(), digest.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse the `/api/ps` response into the names of the models currently loaded.
+pub(crate) fn loaded_model_names_from_ps(value: &JsonValue) -> Vec<String> {
+    value
+        .get("models")
+        .and_then(|m| m.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.get("name").and_then(|n| n.as_str()))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse LM Studio's `/api/v0/models` response into the ids of the models
+/// currently loaded.
+pub(crate) fn loaded_model_ids_from_lmstudio(value: &JsonValue) -> Vec<String> {
+    value
+        .get("data")
+        .and_then(|m| m.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter(|v| v.get("state").and_then(|s| s.as_str()) == Some("loaded"))
+                .filter_map(|v| v.get("id").and_then(|n| n.as_str()))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse LM Studio's `/api/v0/models` response into a list of models. It
+/// reports no sizes on disk or parameter counts.
+pub(crate) fn local_models_from_lmstudio(value: &JsonValue) -> Vec<LocalModel> {
+    value
+        .get("data")
+        .and_then(|m| m.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| {
+                    let id = v.get("id").and_then(|n| n.as_str())?;
+                    Some(LocalModel {
+                        name: id.to_string(),
+                        size_bytes: None,
+                        quantization: v
+                            .get("quantization")
+                            .and_then(|q| q.as_str())
+                            .filter(|q| !q.is_empty())
+                            .map(str::to_string),
+                        parameter_size: None,
+                        context_length: v.get("max_context_length").and_then(JsonValue::as_u64),
+                        loaded: v.get("state").and_then(|s| s.as_str()) == Some("loaded"),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Context window of `model` in LM Studio's `/api/v0/models` response: the
+/// one it was loaded with, otherwise the most it supports.
+pub(crate) fn context_window_from_lmstudio(value: &JsonValue, model: &str) -> Option<u64> {
+    let entry = value
+        .get("data")
+        .and_then(|m| m.as_array())?
+        .iter()
+        .find(|v| v.get("id").and_then(|n| n.as_str()) == Some(model))?;
+    entry
+        .get("loaded_context_length")
+        .and_then(JsonValue::as_u64)
+        .or_else(|| entry.get("max_context_length").and_then(JsonValue::as_u64))
+}
+
+/// Parse the `/api/ps` response into the models currently loaded, with their
+/// memory footprint.
+pub(crate) fn loaded_models_from_ps(value: &JsonValue) -> Vec<LoadedModel> {
+    value
+        .get("models")
+        .and_then(|m| m.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| {
+                    let name = v.get("name").and_then(|n| n.as_str())?;
+                    Some(LoadedModel {
+                        name: name.to_string(),
+                        size_bytes: v.get("size").and_then(JsonValue::as_u64),
+                        vram_bytes: v.get("size_vram").and_then(JsonValue::as_u64),
+                        context_length: v.get("context_length").and_then(JsonValue::as_u64),
+                        expires_at: v
+                            .get("expires_at")
+                            .and_then(|e| e.as_str())
+                            .map(str::to_string),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse an OpenAI-compatible `/v1/models` response into model ids.
+pub(crate) fn model_ids_from_openai_models(value: &JsonValue) -> Vec<String> {
+    value
+        .get("data")
+        .and_then(|m| m.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.get("id").and_then(|n| n.as_str()))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Extract the context length from an `/api/show` response. Ollama reports it
+/// under an architecture-specific key such as `llama.context_length`.
+pub(crate) fn context_length_from_show(value: &JsonValue) -> Option<u64> {
+    value
+        .get("model_info")
+        .and_then(|info| info.as_object())
+        .and_then(|info| {
+            info.iter()
+                .find(|(key, _)| key.ends_with(".context_length"))
+                .and_then(|(_, v)| v.as_u64())
+        })
+}
+
+/// Extract the Modelfile parameters from an `/api/show` response, which
+/// lists them one per line as `name value`, e.g. `temperature 0.7`.
+pub(crate) fn parameters_from_show(value: &JsonValue) -> BTreeMap<String, Vec<String>> {
+    let mut parameters: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let text = value
+        .get("parameters")
+        .and_then(|p| p.as_str())
+        .unwrap_or_default();
+    for line in text.lines() {
+        let Some((name, value)) = line.trim().split_once(char::is_whitespace) else {
+            continue;
+        };
+        parameters
+            .entry(name.to_string())
+            .or_default()
+            .push(value.trim().to_string());
+    }
+    parameters
+}
+
+/// Parse an `/api/show` response. Size, load state, and last-used time come
+/// from other sources and are left unset.
+pub(crate) fn model_details_from_show(name: &str, value: &JsonValue) -> ModelDetails {
+    let details = value.get("details");
+    let detail = |key: &str| {
+        details
+            .and_then(|d| d.get(key))
+            .and_then(|s| s.as_str())
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    let architecture = value
+        .get("model_info")
+        .and_then(|info| info.get("general.architecture"))
+        .and_then(|a| a.as_str())
+        .map(str::to_string)
+        .or_else(|| detail("family"));
+    // The modelfile generated by Ollama references the weights blob with a
+    // `FROM /path/to/blob` line.
+    let file_path = value
+        .get("modelfile")
+        .and_then(|m| m.as_str())
+        .and_then(|modelfile| {
+            modelfile
+                .lines()
+                .find_map(|line| line.strip_prefix("FROM "))
+                .map(|path| path.trim().to_string())
+        });
+    let capabilities = value
+        .get("capabilities")
+        .and_then(|c| c.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    ModelDetails {
+        name: name.to_string(),
+        architecture,
+        quantization: detail("quantization_level"),
+        parameter_size: detail("parameter_size"),
+        file_path,
+        size_bytes: None,
+        context_length: context_length_from_show(value),
+        capabilities,
+        loaded: false,
+        last_used: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_format_size_uses_gb_and_mb() {
+        assert_eq!(format_size(13_780_173_839), "12.83 GB");
+        assert_eq!(format_size(512 * 1024 * 1024), "512.0 MB");
+    }
+
+    #[test]
+    fn test_loaded_model_ids_from_lmstudio() {
+        let v = serde_json::json!({
+            "data": [
+                {"id": "openai/gpt-oss-20b", "state": "loaded"},
+                {"id": "qwen/qwen3-8b", "state": "not-loaded"}
+            ]
+        });
+        assert_eq!(
+            loaded_model_ids_from_lmstudio(&v),
+            vec!["openai/gpt-oss-20b".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_local_models_from_lmstudio() {
+        let v = serde_json::json!({
+            "data": [
+                {
+                    "id": "openai/gpt-oss-20b",
+                    "type": "llm",
+                    "arch": "gpt-oss",
+                    "quantization": "MXFP4",
+                    "state": "loaded",
+                    "max_context_length": 131072
+                },
+                {"id": "qwen/qwen3-8b", "state": "not-loaded"}
+            ]
+        });
+        assert_eq!(
+            local_models_from_lmstudio(&v),
+            vec![
+                LocalModel {
+                    name: "openai/gpt-oss-20b".to_string(),
+                    size_bytes: None,
+                    quantization: Some("MXFP4".to_string()),
+                    parameter_size: None,
+                    context_length: Some(131072),
+                    loaded: true,
+                },
+                LocalModel {
+                    name: "qwen/qwen3-8b".to_string(),
+                    size_bytes: None,
+                    quantization: None,
+                    parameter_size: None,
+                    context_length: None,
+                    loaded: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_context_window_from_lmstudio_prefers_loaded_length() {
+        let v = serde_json::json!({
+            "data": [
+                {"id": "openai/gpt-oss-20b", "loaded_context_length": 8192, "max_context_length": 131072},
+                {"id": "qwen/qwen3-8b", "max_context_length": 32768}
+            ]
+        });
+        assert_eq!(
+            context_window_from_lmstudio(&v, "openai/gpt-oss-20b"),
+            Some(8192)
+        );
+        assert_eq!(
+            context_window_from_lmstudio(&v, "qwen/qwen3-8b"),
+            Some(32768)
+        );
+        assert_eq!(context_window_from_lmstudio(&v, "missing"), None);
+    }
+
+    #[test]
+    fn test_local_models_from_tags() {
+        let v = serde_json::json!({
+            "models": [
+                {
+                    "name": "gpt-oss:20b",
+                    "size": 13780173839u64,
+                    "details": {
+                        "format": "gguf",
+                        "family": "gptoss",
+                        "parameter_size": "20.9B",
+                        "quantization_level": "MXFP4"
+                    }
+                },
+                {"name": "mistral"},
+                {"size": 1}
+            ]
+        });
+        assert_eq!(
+            local_models_from_tags(&v),
+            vec![
+                LocalModel {
+                    name: "gpt-oss:20b".to_string(),
+                    size_bytes: Some(13780173839),
+                    quantization: Some("MXFP4".to_string()),
+                    parameter_size: Some("20.9B".to_string()),
+                    context_length: None,
+                    loaded: false,
+                },
+                LocalModel {
+                    name: "mistral".to_string(),
+                    size_bytes: None,
+                    quantization: None,
+                    parameter_size: None,
+                    context_length: None,
+                    loaded: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_model_digests_from_tags_skips_entries_without_digest() {
+        let value = serde_json::json!({
+            "models": [
+                {"name": "gpt-oss:20b", "digest": "aa11"},
+                {"name": "mistral"}
+            ]
+        });
+        assert_eq!(
+            model_digests_from_tags(&value),
+            vec![("gpt-oss:20b".to_string(), "aa11".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_loaded_model_names_from_ps() {
+        let v = serde_json::json!({"models": [{"name": "gpt-oss:20b", "size_vram": 1}]});
+        assert_eq!(loaded_model_names_from_ps(&v), vec!["gpt-oss:20b"]);
+        assert!(loaded_model_names_from_ps(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_loaded_models_from_ps() {
+        let v = serde_json::json!({
+            "models": [{
+                "name": "gpt-oss:20b",
+                "size": 14000000000u64,
+                "size_vram": 12000000000u64,
+                "context_length": 8192,
+                "expires_at": "2025-10-14T12:05:00Z"
+            }]
+        });
+        assert_eq!(
+            loaded_models_from_ps(&v),
+            vec![LoadedModel {
+                name: "gpt-oss:20b".to_string(),
+                size_bytes: Some(14_000_000_000),
+                vram_bytes: Some(12_000_000_000),
+                context_length: Some(8192),
+                expires_at: Some("2025-10-14T12:05:00Z".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_model_ids_from_openai_models() {
+        let v = serde_json::json!({
+            "object": "list",
+            "data": [
+                {"id": "openai/gpt-oss-20b", "object": "model"},
+                {"object": "model"}
+            ]
+        });
+        assert_eq!(model_ids_from_openai_models(&v), vec!["openai/gpt-oss-20b"]);
+    }
+
+    #[test]
+    fn test_model_details_from_show() {
+        let v = serde_json::json!({
+            "modelfile": "# Modelfile generated by \"ollama show\"\nFROM /home/me/.ollama/models/blobs/sha256-abc\nTEMPLATE x\n",
+            "details": {
+                "family": "gptoss",
+                "parameter_size": "20.9B",
+                "quantization_level": "MXFP4"
+            },
+            "model_info": {
+                "general.architecture": "gptoss",
+                "gptoss.context_length": 131072
+            },
+            "capabilities": ["completion", "tools", "thinking"]
+        });
+        assert_eq!(
+            model_details_from_show("gpt-oss:20b", &v),
+            ModelDetails {
+                name: "gpt-oss:20b".to_string(),
+                architecture: Some("gptoss".to_string()),
+                quantization: Some("MXFP4".to_string()),
+                parameter_size: Some("20.9B".to_string()),
+                file_path: Some("/home/me/.ollama/models/blobs/sha256-abc".to_string()),
+                size_bytes: None,
+                context_length: Some(131072),
+                capabilities: vec![
+                    "completion".to_string(),
+                    "tools".to_string(),
+                    "thinking".to_string()
+                ],
+                loaded: false,
+                last_used: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parameters_from_show() {
+        let v = serde_json::json!({
+            "parameters": "temperature                    0.7\nstop                           \"<|end|>\"\nstop                           \"<|return|>\""
+        });
+        let parameters = parameters_from_show(&v);
+        assert_eq!(
+            parameters.get("temperature"),
+            Some(&vec!["0.7".to_string()])
+        );
+        assert_eq!(
+            parameters.get("stop"),
+            Some(&vec![
+                "\"<|end|>\"".to_string(),
+                "\"<|return|>\"".to_string()
+            ])
+        );
+        assert!(parameters_from_show(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_context_length_from_show() {
+        let v = serde_json::json!({
+            "model_info": {
+                "general.architecture": "gptoss",
+                "gptoss.context_length": 131072
+            }
+        });
+        assert_eq!(context_length_from_show(&v), Some(131072));
+        assert_eq!(context_length_from_show(&serde_json::json!({})), None);
+    }
+}
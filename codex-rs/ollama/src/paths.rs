@@ -0,0 +1,180 @@
+//! Where the local OSS provider keeps what it writes.
+//!
+//! State that must survive a restart goes to the platform's state
+//! directory, "codex/oss" under:
+//!
+//! - Linux: `$XDG_STATE_HOME` (default `~/.local/state`)
+//! - macOS: `~/Library/Application Support`
+//! - Windows: `%LOCALAPPDATA%`
+//!
+//! It holds the audit log (`audit.jsonl`), model usage records
+//! (`model-usage.json`), performance samples (`performance.jsonl`), the pid
+//! and log of a server Codex started (`ollama-serve.pid`,
+//! `ollama-serve.log`), and download locks (`locks/`).
+//!
+//! What can be fetched again, such as the model names used for shell
+//! completion, goes to the cache directory, "codex/oss" under
+//! `$XDG_CACHE_HOME` (default `~/.cache`) on Linux, `~/Library/Caches` on
+//! macOS, and `%LOCALAPPDATA%\codex\oss\cache` on Windows. `codex oss
+//! clean-cache` empties it.
+//!
+//! With `CODEX_HOME` pointing somewhere other than `~/.codex`, everything
+//! stays in `$CODEX_HOME/oss` (the cache in `$CODEX_HOME/oss/cache`) so that
+//! separate Codex homes do not share state.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::config::Config;
+
+const APP_DIR: &str = "codex";
+const OSS_DIR: &str = "oss";
+const CACHE_DIR: &str = "cache";
+
+/// Directory holding state for the local OSS provider, such as servers
+/// started by Codex and model usage records. State left in `~/.codex/oss`
+/// by older versions is moved here the first time it is looked up.
+pub fn oss_state_dir(config: &Config) -> PathBuf {
+    let legacy = config.codex_home.join(OSS_DIR);
+    let Some(dir) = platform_dirs(&config.codex_home).map(|(state, _)| state) else {
+        return legacy;
+    };
+    migrate_legacy_state(&legacy, &dir)
+}
+
+/// Directory holding caches of the local OSS provider; anything in it can
+/// be deleted at any time.
+pub fn oss_cache_dir(config: &Config) -> PathBuf {
+    match platform_dirs(&config.codex_home) {
+        Some((_, cache)) => cache,
+        None => config.codex_home.join(OSS_DIR).join(CACHE_DIR),
+    }
+}
+
+/// Delete everything in the cache directory. Returns the bytes freed.
+pub fn clean_oss_cache(config: &Config) -> io::Result<u64> {
+    let dir = oss_cache_dir(config);
+    let freed = dir_size(&dir)?;
+    match std::fs::remove_dir_all(&dir) {
+        Ok(()) => Ok(freed),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(0),
+        Err(err) => Err(err),
+    }
+}
+
+/// The state and cache directories for the default Codex home, or `None`
+/// when `codex_home` was moved with `CODEX_HOME` (or the platform
+/// directories are unknown).
+fn platform_dirs(codex_home: &Path) -> Option<(PathBuf, PathBuf)> {
+    let default_home = dirs::home_dir()?.join(".codex");
+    if codex_home != default_home {
+        return None;
+    }
+    let state_base = dirs::state_dir().or_else(dirs::data_local_dir)?;
+    let cache_base = dirs::cache_dir()?;
+    Some(layout(&state_base, &cache_base))
+}
+
+/// Where state and cache go given the platform's base directories. On
+/// Windows both bases are `%LOCALAPPDATA%`, so the cache is nested in the
+/// state directory to keep `clean-cache` away from the state.
+fn layout(state_base: &Path, cache_base: &Path) -> (PathBuf, PathBuf) {
+    let state = state_base.join(APP_DIR).join(OSS_DIR);
+    let cache = cache_base.join(APP_DIR).join(OSS_DIR);
+    let cache = if cache == state {
+        state.join(CACHE_DIR)
+    } else {
+        cache
+    };
+    (state, cache)
+}
+
+/// Move `legacy` to `dir` unless `dir` already exists. When the move fails,
+/// e.g. across file systems, `legacy` stays in use.
+fn migrate_legacy_state(legacy: &Path, dir: &Path) -> PathBuf {
+    if dir.exists() || !legacy.is_dir() {
+        return dir.to_path_buf();
+    }
+    let moved = dir
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::rename(legacy, dir));
+    match moved {
+        Ok(()) => dir.to_path_buf(),
+        // Another process may have moved it first.
+        Err(_) if dir.exists() => dir.to_path_buf(),
+        Err(err) => {
+            tracing::warn!(
+                "could not move {} to {}: {err}",
+                legacy.display(),
+                dir.display()
+            );
+            legacy.to_path_buf()
+        }
+    }
+}
+
+fn dir_size(dir: &Path) -> io::Result<u64> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err),
+    };
+    let mut size = 0;
+    for entry in entries {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn caches_are_kept_apart_from_state() {
+        let home = Path::new("home");
+        let (state, cache) = layout(&home.join(".local/state"), &home.join(".cache"));
+        assert_eq!(state, home.join(".local/state").join("codex").join("oss"));
+        assert_eq!(cache, home.join(".cache").join("codex").join("oss"));
+
+        let local_app_data = Path::new("AppData").join("Local");
+        let (state, cache) = layout(&local_app_data, &local_app_data);
+        assert_eq!(cache, state.join("cache"));
+    }
+
+    #[test]
+    fn legacy_state_is_moved_once() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let legacy = dir.path().join(".codex").join("oss");
+        std::fs::create_dir_all(&legacy).expect("legacy dir");
+        std::fs::write(legacy.join("audit.jsonl"), "{}\n").expect("audit log");
+        let state = dir.path().join("state").join("codex").join("oss");
+
+        assert_eq!(migrate_legacy_state(&legacy, &state), state);
+        assert!(state.join("audit.jsonl").is_file());
+        assert!(!legacy.exists());
+
+        std::fs::create_dir_all(&legacy).expect("recreated legacy dir");
+        assert_eq!(migrate_legacy_state(&legacy, &state), state);
+        assert!(legacy.exists());
+    }
+
+    #[test]
+    fn dir_size_counts_nested_files() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir_all(dir.path().join("nested")).expect("nested dir");
+        std::fs::write(dir.path().join("a"), [0u8; 3]).expect("a");
+        std::fs::write(dir.path().join("nested").join("b"), [0u8; 4]).expect("b");
+        assert_eq!(dir_size(dir.path()).expect("size"), 7);
+        assert_eq!(dir_size(&dir.path().join("missing")).expect("size"), 0);
+    }
+}
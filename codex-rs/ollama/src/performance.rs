@@ -0,0 +1,225 @@
+//! Opt-in performance telemetry of the local model
+//! (`oss.performance_telemetry`).
+//!
+//! Each sample records how fast a request ran, which model and quantization
+//! served it, and a coarse class of the machine. Prompts, output, paths, and
+//! anything that identifies the user or the conversation are never recorded.
+//! Samples always go to a local file first, so users can see exactly what
+//! would be shared before choosing `otel`.
+
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use codex_core::config::Config;
+use codex_core::config_types::OssPerformanceTelemetry;
+use codex_core::protocol::RequestTimingEvent;
+use codex_core::protocol_config_types::OssBackend;
+use codex_protocol::oss::HardwareClass;
+use codex_protocol::oss::PerformanceSample;
+
+use crate::OllamaClient;
+use crate::host::total_memory_bytes;
+use crate::oss_state_dir;
+
+const PERFORMANCE_FILE: &str = "performance.jsonl";
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+/// File the samples are appended to, one JSON object per line.
+pub fn performance_log_path(state_dir: &Path) -> PathBuf {
+    state_dir.join(PERFORMANCE_FILE)
+}
+
+/// Records [`PerformanceSample`]s of a session's requests to the local model.
+#[derive(Clone)]
+pub struct PerformanceRecorder {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    telemetry: OssPerformanceTelemetry,
+    path: PathBuf,
+    backend: OssBackend,
+    client: OllamaClient,
+    hardware: HardwareClass,
+    /// Quantization and parameter size of each model, looked up once.
+    models: Mutex<HashMap<String, (Option<String>, Option<String>)>>,
+}
+
+impl PerformanceRecorder {
+    /// A recorder for `config`, or `None` unless the user opted in.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if config.oss.performance_telemetry == OssPerformanceTelemetry::Off {
+            return None;
+        }
+        let client = OllamaClient::from_oss_provider(config)
+            .inspect_err(|err| tracing::warn!("not recording local performance: {err}"))
+            .ok()?;
+        let telemetry = match config.oss.performance_telemetry {
+            OssPerformanceTelemetry::Otel if config.oss.strict_local => {
+                tracing::warn!(
+                    "oss.strict_local keeps performance samples on this machine; not exporting them"
+                );
+                OssPerformanceTelemetry::Local
+            }
+            telemetry => telemetry,
+        };
+        Some(Self {
+            inner: Arc::new(Inner {
+                telemetry,
+                path: performance_log_path(&oss_state_dir(config)),
+                backend: config.oss.provider.unwrap_or(OssBackend::Ollama),
+                client,
+                hardware: hardware_class(),
+                models: Mutex::new(HashMap::new()),
+            }),
+        })
+    }
+
+    /// Record the request `timing` reports, when it generated enough tokens
+    /// to measure its speed. Failures are logged, never surfaced.
+    pub async fn record(&self, model: &str, timing: &RequestTimingEvent) {
+        let Some(tokens_per_second) = timing.tokens_per_second() else {
+            return;
+        };
+        let (quantization, parameter_size) = self.model_info(model).await;
+        let sample = PerformanceSample {
+            codex_version: env!("CARGO_PKG_VERSION").to_string(),
+            backend: self.inner.backend,
+            model: model.to_string(),
+            quantization,
+            parameter_size,
+            hardware: self.inner.hardware.clone(),
+            time_to_first_token_ms: timing.time_to_first_token_ms,
+            prefill_ms: timing.prefill_ms,
+            output_tokens: timing.output_tokens,
+            tokens_per_second,
+        };
+        if let Err(err) = append_sample(&self.inner.path, &sample) {
+            tracing::warn!("recording local performance failed: {err}");
+        }
+        if self.inner.telemetry == OssPerformanceTelemetry::Otel {
+            codex_otel::otel_event_manager::local_performance(&sample);
+        }
+    }
+
+    async fn model_info(&self, model: &str) -> (Option<String>, Option<String>) {
+        if let Some(info) = self
+            .inner
+            .models
+            .lock()
+            .ok()
+            .and_then(|models| models.get(model).cloned())
+        {
+            return info;
+        }
+        // Only Ollama describes a model's quantization.
+        let info = match self.inner.backend {
+            OssBackend::Ollama => self
+                .inner
+                .client
+                .fetch_model_details(model)
+                .await
+                .map(|details| (details.quantization, details.parameter_size))
+                .unwrap_or_default(),
+            OssBackend::LmStudio | OssBackend::Generic => (None, None),
+        };
+        if let Ok(mut models) = self.inner.models.lock() {
+            models.insert(model.to_string(), info.clone());
+        }
+        info
+    }
+}
+
+fn append_sample(path: &Path, sample: &PerformanceSample) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut line = serde_json::to_string(sample).map_err(io::Error::other)?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+fn hardware_class() -> HardwareClass {
+    HardwareClass {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        cpu_cores: std::thread::available_parallelism()
+            .ok()
+            .and_then(|cores| u32::try_from(cores.get()).ok()),
+        memory_class: total_memory_bytes().map(memory_class),
+    }
+}
+
+/// Range of `total_bytes` of memory, rounded so that machines only group
+/// with others of the same size.
+fn memory_class(total_bytes: u64) -> String {
+    // Machines report a little less than their nominal memory.
+    let gib = (total_bytes + GIB / 2) / GIB;
+    match gib {
+        0..8 => "<8 GiB",
+        8..16 => "8-16 GiB",
+        16..32 => "16-32 GiB",
+        32..64 => "32-64 GiB",
+        64..128 => "64-128 GiB",
+        _ => "128+ GiB",
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn memory_is_reported_as_a_range() {
+        assert_eq!(memory_class(4 * GIB), "<8 GiB");
+        // 16 GB machines report slightly less than 16 GiB.
+        assert_eq!(memory_class(16 * GIB - 300 * 1024 * 1024), "16-32 GiB");
+        assert_eq!(memory_class(36 * GIB), "32-64 GiB");
+        assert_eq!(memory_class(512 * GIB), "128+ GiB");
+    }
+
+    #[test]
+    fn samples_are_appended_as_json_lines() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = performance_log_path(dir.path());
+        let sample = PerformanceSample {
+            codex_version: "0.0.0".to_string(),
+            backend: OssBackend::Ollama,
+            model: "gpt-oss:20b".to_string(),
+            quantization: Some("MXFP4".to_string()),
+            parameter_size: Some("20.9B".to_string()),
+            hardware: HardwareClass {
+                os: "macos".to_string(),
+                arch: "aarch64".to_string(),
+                cpu_cores: Some(12),
+                memory_class: Some("32-64 GiB".to_string()),
+            },
+            time_to_first_token_ms: Some(850),
+            prefill_ms: Some(600),
+            output_tokens: 120,
+            tokens_per_second: 42.5,
+        };
+        append_sample(&path, &sample).expect("append");
+        append_sample(&path, &sample).expect("append");
+
+        let contents = std::fs::read_to_string(&path).expect("read");
+        let lines: Vec<PerformanceSample> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("parse"))
+            .collect();
+        assert_eq!(lines, vec![sample.clone(), sample]);
+    }
+}
@@ -0,0 +1,141 @@
+//! Strict checks run before a headless `codex exec --oss` starts, so that a
+//! run fails up front instead of partway through with side effects.
+
+use codex_core::config::Config;
+use codex_core::protocol_config_types::OssBackend;
+use codex_protocol::oss::OssEvent;
+
+use crate::DownloadManager;
+use crate::OllamaClient;
+use crate::OssError;
+use crate::PullProgressReporter;
+use crate::contains_model;
+use crate::events::publish;
+use crate::license::ensure_license_accepted;
+use crate::oss_state_dir;
+use crate::record_model_use;
+
+/// What a headless run needs from the local provider.
+#[derive(Debug, Clone, Default)]
+pub struct PreflightRequirements {
+    /// Download the model when it is missing instead of failing.
+    pub allow_download: bool,
+    /// Capabilities the model must report in Ollama's `/api/show`, e.g.
+    /// "tools" or "vision".
+    pub capabilities: Vec<String>,
+}
+
+/// Verify the server is up, the model is present (downloading it only when
+/// allowed), and the model supports the required capabilities.
+pub async fn preflight_oss(
+    config: &Config,
+    requirements: &PreflightRequirements,
+    reporter: &mut (dyn PullProgressReporter + Send),
+) -> Result<(), OssError> {
+    let model = config.model.as_str();
+    let backend = config.oss.provider.unwrap_or(OssBackend::Ollama);
+    let client = OllamaClient::from_oss_provider(config)?;
+    client.log(format_args!("probing {backend} at {}", client.host_root()));
+    client.probe_server().await.map_err(|_| {
+        OssError::ServerUnreachable(format!(
+            "No {backend} server reachable at {}.",
+            client.host_root()
+        ))
+    })?;
+    publish(OssEvent::ServerDetected {
+        backend,
+        base_url: client.host_root().to_string(),
+    });
+
+    if backend != OssBackend::Ollama {
+        let models = client.fetch_openai_model_ids().await.map_err(|err| {
+            OssError::ServerUnreachable(format!(
+                "Failed to list models on the {backend} server: {err}"
+            ))
+        })?;
+        if !contains_model(&models, model) {
+            return Err(OssError::ModelMissing(format!(
+                "Model '{model}' is not available on the {backend} server. Download or load it there first."
+            )));
+        }
+        // OpenAI-compatible servers do not report model capabilities.
+        return Ok(());
+    }
+
+    let models = client.fetch_models().await.map_err(|err| {
+        OssError::ServerUnreachable(format!("Failed to list local models: {err}"))
+    })?;
+    if !contains_model(&models, model) {
+        if config.oss.offline {
+            return Err(OssError::ModelMissing(format!(
+                "Model '{model}' is not downloaded and offline mode disables downloads."
+            )));
+        }
+        if !requirements.allow_download {
+            return Err(OssError::ModelMissing(format!(
+                "Model '{model}' is not downloaded. Run `codex models pull {model}` first, or pass --oss-download."
+            )));
+        }
+        client.log(format_args!("model '{model}' is missing; pulling it"));
+        // Checked here for a structured error; the pull reuses the lookup.
+        let download_client = OllamaClient::from_oss_provider(config)?;
+        ensure_license_accepted(&download_client, &config.oss.accepted_licenses, model).await?;
+        DownloadManager::global()
+            .download(download_client, model, reporter)
+            .await
+            .map_err(|source| OssError::DownloadFailed {
+                model: model.to_string(),
+                source,
+            })?;
+    }
+
+    if !requirements.capabilities.is_empty() {
+        let details = client.fetch_model_details(model).await?;
+        let missing = missing_capabilities(&details.capabilities, &requirements.capabilities);
+        if !missing.is_empty() {
+            return Err(OssError::ModelUnsupported(format!(
+                "Model '{model}' does not support {}, which this run needs. Choose another model with -m.",
+                missing.join(", ")
+            )));
+        }
+    }
+
+    if let Err(err) = record_model_use(&oss_state_dir(config), model) {
+        tracing::warn!("Failed to record use of model {model}: {err}");
+    }
+    Ok(())
+}
+
+/// Required capabilities the model does not report. Servers too old to
+/// report any capabilities are given the benefit of the doubt.
+fn missing_capabilities(reported: &[String], required: &[String]) -> Vec<String> {
+    if reported.is_empty() {
+        return Vec::new();
+    }
+    required
+        .iter()
+        .filter(|capability| !reported.contains(capability))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_missing_capabilities() {
+        let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let required = strings(&["tools", "vision"]);
+        assert_eq!(
+            missing_capabilities(&strings(&["completion", "tools"]), &required),
+            strings(&["vision"])
+        );
+        assert_eq!(
+            missing_capabilities(&strings(&["completion", "tools", "vision"]), &required),
+            Vec::<String>::new()
+        );
+        assert_eq!(missing_capabilities(&[], &required), Vec::<String>::new());
+    }
+}
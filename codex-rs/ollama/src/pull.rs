@@ -1,6 +1,17 @@
+use codex_core::config_types::OssConfig;
+use codex_core::config_types::OssProgressStyle;
+use codex_core::config_types::OssVerbosity;
+use codex_protocol::oss::DownloadPhase;
+use codex_protocol::oss::ModelDownloadProgress;
 use std::collections::HashMap;
 use std::io;
+use std::io::IsTerminal;
 use std::io::Write;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::messages::MessageId;
+use crate::messages::message;
 
 /// Events emitted while pulling a model from Ollama.
 #[derive(Debug, Clone)]
@@ -26,8 +37,11 @@ pub trait PullProgressReporter {
     fn on_event(&mut self, event: &PullEvent) -> io::Result<()>;
 }
 
-/// A minimal CLI reporter that writes inline progress to stderr.
+/// A minimal CLI reporter that writes inline progress to stderr, or
+/// [`PlainProgress`] lines when the progress style is plain.
 pub struct CliProgressReporter {
+    verbosity: OssVerbosity,
+    plain: Option<PlainProgress>,
     printed_header: bool,
     last_line_len: usize,
     last_completed_sum: u64,
@@ -43,7 +57,31 @@ impl Default for CliProgressReporter {
 
 impl CliProgressReporter {
     pub fn new() -> Self {
+        Self::with_verbosity(OssVerbosity::Normal)
+    }
+
+    /// Reporter that prints nothing when quiet and every status line,
+    /// including manifest messages, when verbose.
+    pub fn with_verbosity(verbosity: OssVerbosity) -> Self {
+        Self::with_style(verbosity, OssProgressStyle::Bar)
+    }
+
+    /// Reporter following `oss.verbosity` and `oss.progress_style`.
+    pub fn from_oss_config(oss: &OssConfig) -> Self {
+        Self::with_style(oss.verbosity, oss.progress_style)
+    }
+
+    /// Reporter drawing progress in `style`; `auto` is plain unless stderr
+    /// is a terminal.
+    pub fn with_style(verbosity: OssVerbosity, style: OssProgressStyle) -> Self {
+        let plain = match style {
+            OssProgressStyle::Auto => !std::io::stderr().is_terminal(),
+            OssProgressStyle::Bar => false,
+            OssProgressStyle::Plain => true,
+        };
         Self {
+            verbosity,
+            plain: plain.then(PlainProgress::default),
             printed_header: false,
             last_line_len: 0,
             last_completed_sum: 0,
@@ -55,11 +93,22 @@ impl CliProgressReporter {
 
 impl PullProgressReporter for CliProgressReporter {
     fn on_event(&mut self, event: &PullEvent) -> io::Result<()> {
+        if self.verbosity == OssVerbosity::Quiet {
+            return Ok(());
+        }
         let mut out = std::io::stderr();
         match event {
             PullEvent::Status(status) => {
                 // Avoid noisy manifest messages; otherwise show status inline.
-                if status.eq_ignore_ascii_case("pulling manifest") {
+                if status.eq_ignore_ascii_case("pulling manifest")
+                    && self.verbosity != OssVerbosity::Verbose
+                {
+                    return Ok(());
+                }
+                if let Some(plain) = &mut self.plain {
+                    if let Some(line) = plain.on_status(status) {
+                        writeln!(out, "{line}")?;
+                    }
                     return Ok(());
                 }
                 let pad = self.last_line_len.saturating_sub(status.len());
@@ -73,31 +122,25 @@ impl PullProgressReporter for CliProgressReporter {
                 total,
                 completed,
             } => {
-                if let Some(t) = *total {
-                    self.totals_by_digest
-                        .entry(digest.clone())
-                        .or_insert((0, 0))
-                        .0 = t;
-                }
-                if let Some(c) = *completed {
-                    self.totals_by_digest
-                        .entry(digest.clone())
-                        .or_insert((0, 0))
-                        .1 = c;
-                }
-
-                let (sum_total, sum_completed) = self
-                    .totals_by_digest
-                    .values()
-                    .fold((0u64, 0u64), |acc, (t, c)| (acc.0 + *t, acc.1 + *c));
+                let (sum_total, sum_completed) =
+                    record_layer_progress(&mut self.totals_by_digest, digest, *total, *completed);
                 if sum_total > 0 {
                     if !self.printed_header {
                         let gb = (sum_total as f64) / (1024.0 * 1024.0 * 1024.0);
                         let header = format!("Downloading model: total {gb:.2} GB\n");
-                        out.write_all(b"\r\x1b[2K")?;
+                        if self.plain.is_none() {
+                            out.write_all(b"\r\x1b[2K")?;
+                        }
                         out.write_all(header.as_bytes())?;
                         self.printed_header = true;
                     }
+                    if let Some(plain) = &mut self.plain {
+                        if let Some(line) = plain.on_bytes(sum_completed, sum_total, Instant::now())
+                        {
+                            writeln!(out, "{line}")?;
+                        }
+                        return Ok(());
+                    }
                     let now = std::time::Instant::now();
                     let dt = now
                         .duration_since(self.last_instant)
@@ -127,6 +170,8 @@ impl PullProgressReporter for CliProgressReporter {
                 // here or the error will be printed twice.
                 Ok(())
             }
+            // Plain lines already end in a newline.
+            PullEvent::Success if self.plain.is_some() => Ok(()),
             PullEvent::Success => {
                 out.write_all(b"\n")?;
                 out.flush()
@@ -135,6 +180,208 @@ impl PullProgressReporter for CliProgressReporter {
     }
 }
 
+/// Minimum time between two lines of plain progress.
+const PLAIN_PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Progress as a line of text every [`PLAIN_PROGRESS_INTERVAL`], e.g. "42%
+/// downloaded, 6 minutes remaining", for screen readers and logs that cannot
+/// follow a progress bar redrawn with carriage returns.
+#[derive(Debug, Default)]
+struct PlainProgress {
+    /// When the first bytes were reported and how many there were, since a
+    /// resumed download starts partway through.
+    started: Option<(Instant, u64)>,
+    last_line_at: Option<Instant>,
+    last_status: Option<String>,
+}
+
+impl PlainProgress {
+    /// The line for `completed` of `total` bytes at `now`, when one is due.
+    fn on_bytes(&mut self, completed: u64, total: u64, now: Instant) -> Option<String> {
+        let (started_at, started_bytes) = *self.started.get_or_insert((now, completed));
+        if self
+            .last_line_at
+            .is_some_and(|at| now.duration_since(at) < PLAIN_PROGRESS_INTERVAL)
+        {
+            return None;
+        }
+        self.last_line_at = Some(now);
+        let percent = (completed.saturating_mul(100) / total.max(1)).min(100);
+        let elapsed = now.duration_since(started_at).as_secs_f64();
+        let downloaded = completed.saturating_sub(started_bytes);
+        let remaining = (elapsed > 0.0 && downloaded > 0).then(|| {
+            let left = total.saturating_sub(completed) as f64;
+            Duration::from_secs_f64(left * elapsed / downloaded as f64)
+        });
+        let percent = percent.to_string();
+        Some(match remaining {
+            Some(remaining) => message(
+                MessageId::ProgressPercentRemaining,
+                &[
+                    ("percent", &percent),
+                    ("remaining", &format_remaining(remaining)),
+                ],
+            ),
+            None => message(MessageId::ProgressPercent, &[("percent", &percent)]),
+        })
+    }
+
+    /// `status` unless it repeats the previous one.
+    fn on_status(&mut self, status: &str) -> Option<String> {
+        if self.last_status.as_deref() == Some(status) {
+            return None;
+        }
+        self.last_status = Some(status.to_string());
+        Some(status.to_string())
+    }
+}
+
+/// Remaining time in words, rounded up to whole minutes and, past an hour
+/// and a half, to hours.
+fn format_remaining(remaining: Duration) -> String {
+    let minutes = remaining.as_secs().div_ceil(60);
+    match minutes {
+        0 => message(MessageId::ProgressUnderMinute, &[]),
+        1 => message(MessageId::ProgressOneMinute, &[]),
+        2..90 => message(
+            MessageId::ProgressMinutes,
+            &[("minutes", &minutes.to_string())],
+        ),
+        _ => message(
+            MessageId::ProgressHours,
+            &[("hours", &((minutes + 30) / 60).to_string())],
+        ),
+    }
+}
+
+/// Track per-layer progress and return the (total, completed) bytes summed
+/// over all layers seen so far.
+fn record_layer_progress(
+    totals_by_digest: &mut HashMap<String, (u64, u64)>,
+    digest: &str,
+    total: Option<u64>,
+    completed: Option<u64>,
+) -> (u64, u64) {
+    let entry = totals_by_digest.entry(digest.to_string()).or_insert((0, 0));
+    if let Some(t) = total {
+        entry.0 = t;
+    }
+    if let Some(c) = completed {
+        entry.1 = c;
+    }
+    totals_by_digest
+        .values()
+        .fold((0u64, 0u64), |acc, (t, c)| (acc.0 + *t, acc.1 + *c))
+}
+
+/// Minimum time between two `downloading` events from [`JsonProgressReporter`].
+const JSON_PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Reporter for `--json` flows: turns pull events into
+/// [`ModelDownloadProgress`] records and hands each one to `emit`, e.g. to
+/// print it as one line of JSON. Byte progress is throttled; phase changes
+/// are always emitted.
+pub struct JsonProgressReporter<F: FnMut(&ModelDownloadProgress) -> io::Result<()>> {
+    model: String,
+    emit: F,
+    phase: DownloadPhase,
+    totals_by_digest: HashMap<String, (u64, u64)>,
+    last_emit: Option<(std::time::Instant, u64)>,
+}
+
+impl<F: FnMut(&ModelDownloadProgress) -> io::Result<()>> JsonProgressReporter<F> {
+    pub fn new(model: impl Into<String>, emit: F) -> Self {
+        Self {
+            model: model.into(),
+            emit,
+            phase: DownloadPhase::Manifest,
+            totals_by_digest: HashMap::new(),
+            last_emit: None,
+        }
+    }
+
+    fn progress(&self, status: Option<String>) -> ModelDownloadProgress {
+        let (total, completed) = self
+            .totals_by_digest
+            .values()
+            .fold((0u64, 0u64), |acc, (t, c)| (acc.0 + *t, acc.1 + *c));
+        ModelDownloadProgress {
+            model: self.model.clone(),
+            phase: self.phase,
+            status,
+            completed_bytes: (total > 0).then_some(completed),
+            total_bytes: (total > 0).then_some(total),
+            bytes_per_second: None,
+        }
+    }
+}
+
+impl<F: FnMut(&ModelDownloadProgress) -> io::Result<()>> PullProgressReporter
+    for JsonProgressReporter<F>
+{
+    fn on_event(&mut self, event: &PullEvent) -> io::Result<()> {
+        match event {
+            PullEvent::Status(status) => {
+                self.phase = phase_for_status(status).unwrap_or(self.phase);
+                let progress = self.progress(Some(status.clone()));
+                (self.emit)(&progress)
+            }
+            PullEvent::ChunkProgress {
+                digest,
+                total,
+                completed,
+            } => {
+                let (_, sum_completed) =
+                    record_layer_progress(&mut self.totals_by_digest, digest, *total, *completed);
+                let now = std::time::Instant::now();
+                let speed = match self.last_emit {
+                    Some((at, _)) if now.duration_since(at) < JSON_PROGRESS_INTERVAL => {
+                        return Ok(());
+                    }
+                    Some((at, bytes)) => {
+                        let secs = now.duration_since(at).as_secs_f64().max(0.001);
+                        Some((sum_completed.saturating_sub(bytes) as f64 / secs) as u64)
+                    }
+                    None => None,
+                };
+                self.last_emit = Some((now, sum_completed));
+                self.phase = DownloadPhase::Downloading;
+                let mut progress = self.progress(None);
+                progress.bytes_per_second = speed;
+                (self.emit)(&progress)
+            }
+            PullEvent::Success => {
+                self.phase = DownloadPhase::Done;
+                let progress = self.progress(None);
+                (self.emit)(&progress)
+            }
+            PullEvent::Error(message) => {
+                self.phase = DownloadPhase::Failed;
+                let progress = self.progress(Some(message.clone()));
+                (self.emit)(&progress)
+            }
+        }
+    }
+}
+
+/// Phase implied by one of Ollama's pull status messages.
+fn phase_for_status(status: &str) -> Option<DownloadPhase> {
+    let status = status.to_ascii_lowercase();
+    if status == "pulling manifest" || status.starts_with("pulling model") {
+        Some(DownloadPhase::Manifest)
+    } else if status.starts_with("pulling ") {
+        Some(DownloadPhase::Downloading)
+    } else if status.starts_with("verifying") {
+        Some(DownloadPhase::Verifying)
+    } else if status.starts_with("writing") || status.starts_with("removing") {
+        Some(DownloadPhase::Writing)
+    } else if status == "success" {
+        Some(DownloadPhase::Done)
+    } else {
+        None
+    }
+}
+
 /// For now the TUI reporter delegates to the CLI reporter. This keeps UI and
 /// CLI behavior aligned until a dedicated TUI integration is implemented.
 #[derive(Default)]
@@ -145,3 +392,89 @@ impl PullProgressReporter for TuiProgressReporter {
         self.0.on_event(event)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_json_reporter_emits_phases_and_throttles_bytes() {
+        let mut events = Vec::new();
+        let mut reporter = JsonProgressReporter::new("gpt-oss:20b", |progress| {
+            events.push(progress.clone());
+            Ok(())
+        });
+        let chunk = |completed| PullEvent::ChunkProgress {
+            digest: "sha256:aa".to_string(),
+            total: Some(100),
+            completed: Some(completed),
+        };
+        for event in [
+            PullEvent::Status("pulling manifest".to_string()),
+            chunk(10),
+            // Arrives within the throttle interval and is dropped.
+            chunk(20),
+            PullEvent::Status("verifying sha256 digest".to_string()),
+            PullEvent::Success,
+        ] {
+            reporter.on_event(&event).expect("report");
+        }
+        drop(reporter);
+
+        let summary: Vec<(DownloadPhase, Option<u64>, Option<u64>)> = events
+            .iter()
+            .map(|e| (e.phase, e.completed_bytes, e.total_bytes))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (DownloadPhase::Manifest, None, None),
+                (DownloadPhase::Downloading, Some(10), Some(100)),
+                (DownloadPhase::Verifying, Some(20), Some(100)),
+                (DownloadPhase::Done, Some(20), Some(100)),
+            ]
+        );
+    }
+
+    #[test]
+    fn plain_progress_prints_a_line_every_interval() {
+        let mut plain = PlainProgress::default();
+        let start = Instant::now();
+        let gib = 1024 * 1024 * 1024;
+
+        assert_eq!(
+            plain.on_bytes(0, 10 * gib, start),
+            Some("0% downloaded".to_string())
+        );
+        assert_eq!(
+            plain.on_bytes(gib, 10 * gib, start + Duration::from_secs(5)),
+            None
+        );
+        // 42% after 4 minutes leaves about 6 minutes at the same speed.
+        assert_eq!(
+            plain.on_bytes(
+                42 * gib / 10,
+                10 * gib,
+                start + Duration::from_secs(4 * 60 + 20)
+            ),
+            Some("42% downloaded, 6 minutes remaining".to_string())
+        );
+        assert_eq!(
+            plain.on_status("verifying sha256 digest"),
+            Some("verifying sha256 digest".to_string())
+        );
+        assert_eq!(plain.on_status("verifying sha256 digest"), None);
+    }
+
+    #[test]
+    fn remaining_time_is_rounded_up() {
+        assert_eq!(format_remaining(Duration::ZERO), "less than a minute");
+        assert_eq!(format_remaining(Duration::from_secs(30)), "1 minute");
+        assert_eq!(format_remaining(Duration::from_secs(61)), "2 minutes");
+        assert_eq!(
+            format_remaining(Duration::from_secs(3 * 3600)),
+            "about 3 hours"
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use crate::LmStudioInstall;
+use crate::OllamaClient;
+use crate::binary::find_ollama_binary;
+use crate::detect_lmstudio_install;
+use crate::host::available_disk_bytes;
+use crate::host::models_dir;
+use crate::host::total_memory_bytes;
+use crate::lms_trust::verify_lms_binary;
+use crate::messages::MessageId;
+use crate::messages::message;
+use crate::models::format_size;
+use codex_core::config::Config;
+use codex_core::protocol_config_types::OssBackend;
+pub use codex_protocol::oss::CheckStatus;
+pub use codex_protocol::oss::OssReadiness;
+pub use codex_protocol::oss::ReadinessCheck;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+/// Free disk space we want available before downloading a model whose size
+/// is not yet known (the default `gpt-oss:20b` is ~13 GiB).
+const MIN_FREE_DISK_FOR_DOWNLOAD_BYTES: u64 = 20 * GIB;
+
+/// Physical memory recommended when the model size is not yet known.
+const MIN_RECOMMENDED_MEMORY_BYTES: u64 = 16 * GIB;
+
+fn new_check(
+    name: &str,
+    status: CheckStatus,
+    detail: impl Into<String>,
+    remediation: Option<String>,
+) -> ReadinessCheck {
+    ReadinessCheck {
+        name: name.to_string(),
+        status,
+        detail: detail.into(),
+        remediation,
+    }
+}
+
+/// Run every step of the `--oss` readiness pipeline without changing any
+/// state: nothing is downloaded or started, problems are only reported.
+pub async fn check_oss_readiness(config: &Config) -> OssReadiness {
+    let model = config.model.clone();
+    let mut checks = Vec::new();
+
+    checks.push(if config.oss.provider == Some(OssBackend::LmStudio) {
+        lmstudio_binary_check(detect_lmstudio_install(), &config.oss.trusted_lms_sha256)
+    } else {
+        match find_ollama_binary() {
+            Some(path) => new_check(
+                "binary",
+                CheckStatus::Ok,
+                message(
+                    MessageId::DoctorOllamaFound,
+                    &[("path", &path.display().to_string())],
+                ),
+                None,
+            ),
+            // A missing binary is not fatal: the server may run in a container
+            // or on another machine.
+            None => new_check(
+                "binary",
+                CheckStatus::Warn,
+                message(MessageId::DoctorOllamaMissing, &[]),
+                Some(message(MessageId::DoctorInstallOllama, &[])),
+            ),
+        }
+    });
+
+    let client = match OllamaClient::from_oss_provider(config) {
+        Ok(client) => client,
+        Err(err) => {
+            checks.push(new_check(
+                "server",
+                CheckStatus::Fail,
+                err.to_string(),
+                Some(message(MessageId::DoctorRestoreProvider, &[])),
+            ));
+            return OssReadiness {
+                host_root: String::new(),
+                model,
+                checks,
+            };
+        }
+    };
+    let host_root = client.host_root().to_string();
+
+    let reachable = client.probe_server().await.is_ok();
+    checks.push(if reachable {
+        new_check(
+            "server",
+            CheckStatus::Ok,
+            message(MessageId::DoctorServerReachable, &[("host", &host_root)]),
+            None,
+        )
+    } else {
+        new_check(
+            "server",
+            CheckStatus::Fail,
+            message(MessageId::DoctorServerUnreachable, &[("host", &host_root)]),
+            Some(message(MessageId::DoctorStartOllama, &[])),
+        )
+    });
+
+    let mut model_size = None;
+    let mut model_present = false;
+    if reachable {
+        checks.push(match client.fetch_version().await {
+            Ok(version) => new_check(
+                "version",
+                CheckStatus::Ok,
+                message(MessageId::DoctorServerVersion, &[("version", &version)]),
+                None,
+            ),
+            Err(err) => new_check(
+                "version",
+                CheckStatus::Warn,
+                message(
+                    MessageId::DoctorVersionUnknown,
+                    &[("error", &err.to_string())],
+                ),
+                Some(message(MessageId::DoctorUpgradeOllama, &[])),
+            ),
+        });
+
+        checks.push(match client.fetch_local_models().await {
+            Ok(models) => match models.iter().find(|m| m.name == model) {
+                Some(found) => {
+                    model_present = true;
+                    model_size = found.size_bytes;
+                    let size = found
+                        .size_bytes
+                        .map(|bytes| format!(" ({})", format_size(bytes)))
+                        .unwrap_or_default();
+                    new_check(
+                        "model",
+                        CheckStatus::Ok,
+                        message(
+                            MessageId::DoctorModelAvailable,
+                            &[("model", &model), ("size", &size)],
+                        ),
+                        None,
+                    )
+                }
+                None => new_check(
+                    "model",
+                    CheckStatus::Warn,
+                    message(MessageId::DoctorModelMissing, &[("model", &model)]),
+                    Some(message(MessageId::DoctorPullModel, &[("model", &model)])),
+                ),
+            },
+            Err(err) => new_check(
+                "model",
+                CheckStatus::Warn,
+                message(
+                    MessageId::DoctorModelsUnlisted,
+                    &[("error", &err.to_string())],
+                ),
+                None,
+            ),
+        });
+    } else {
+        for name in ["version", "model"] {
+            checks.push(new_check(
+                name,
+                CheckStatus::Skipped,
+                message(MessageId::DoctorSkippedUnreachable, &[]),
+                None,
+            ));
+        }
+    }
+
+    checks.push(disk_check(model_present));
+    checks.push(memory_check(model_size));
+
+    OssReadiness {
+        host_root,
+        model,
+        checks,
+    }
+}
+
+/// Report how LM Studio is installed and which `lms` CLI Codex runs.
+fn lmstudio_binary_check(
+    install: Option<LmStudioInstall>,
+    trusted_lms_sha256: &[String],
+) -> ReadinessCheck {
+    let Some(install) = install else {
+        return new_check(
+            "binary",
+            CheckStatus::Warn,
+            message(MessageId::DoctorLmStudioMissing, &[]),
+            Some(message(MessageId::DoctorInstallLmStudio, &[])),
+        );
+    };
+    let mut detail = format!("LM Studio ({})", install.kind);
+    if let Some(version) = &install.version {
+        detail.push_str(&format!(" {version}"));
+    }
+    if let Some(app) = &install.app {
+        detail.push_str(&format!(" at {}", app.display()));
+    }
+    match &install.cli {
+        Some(cli) => match verify_lms_binary(cli, trusted_lms_sha256) {
+            Ok(resolved) => new_check(
+                "binary",
+                CheckStatus::Ok,
+                message(
+                    MessageId::DoctorLmsFound,
+                    &[
+                        ("install", &detail),
+                        ("path", &resolved.display().to_string()),
+                    ],
+                ),
+                None,
+            ),
+            Err(err) => new_check(
+                "binary",
+                CheckStatus::Fail,
+                message(
+                    MessageId::DoctorLmsRefused,
+                    &[("install", &detail), ("error", &err.to_string())],
+                ),
+                Some(message(
+                    MessageId::DoctorFixLms,
+                    &[("path", &cli.display().to_string())],
+                )),
+            ),
+        },
+        // Without the CLI Codex cannot start the server or load models, but
+        // one started from the app still works.
+        None => new_check(
+            "binary",
+            CheckStatus::Warn,
+            message(MessageId::DoctorLmsMissing, &[("install", &detail)]),
+            Some(message(MessageId::DoctorInstallLms, &[])),
+        ),
+    }
+}
+
+fn disk_check(model_present: bool) -> ReadinessCheck {
+    let Some(dir) = models_dir() else {
+        return new_check(
+            "disk",
+            CheckStatus::Skipped,
+            message(MessageId::DoctorModelsDirUnknown, &[]),
+            None,
+        );
+    };
+    let path = dir.display().to_string();
+    match available_disk_bytes(&dir) {
+        Some(free) if !model_present && free < MIN_FREE_DISK_FOR_DOWNLOAD_BYTES => new_check(
+            "disk",
+            CheckStatus::Warn,
+            message(
+                MessageId::DoctorDiskLow,
+                &[("free", &format_size(free)), ("path", &path)],
+            ),
+            Some(message(
+                MessageId::DoctorFreeDisk,
+                &[("size", &format_size(MIN_FREE_DISK_FOR_DOWNLOAD_BYTES))],
+            )),
+        ),
+        Some(free) => new_check(
+            "disk",
+            CheckStatus::Ok,
+            message(
+                MessageId::DoctorDiskFree,
+                &[("free", &format_size(free)), ("path", &path)],
+            ),
+            None,
+        ),
+        None => new_check(
+            "disk",
+            CheckStatus::Skipped,
+            message(MessageId::DoctorDiskUnknown, &[]),
+            None,
+        ),
+    }
+}
+
+fn memory_check(model_size: Option<u64>) -> ReadinessCheck {
+    let Some(total) = total_memory_bytes() else {
+        return new_check(
+            "memory",
+            CheckStatus::Skipped,
+            message(MessageId::DoctorMemoryUnknown, &[]),
+            None,
+        );
+    };
+    let required = model_size.unwrap_or(MIN_RECOMMENDED_MEMORY_BYTES);
+    if total < required {
+        new_check(
+            "memory",
+            CheckStatus::Warn,
+            message(
+                MessageId::DoctorMemoryLow,
+                &[
+                    ("total", &format_size(total)),
+                    ("required", &format_size(required)),
+                ],
+            ),
+            Some(message(MessageId::DoctorSmallerModel, &[])),
+        )
+    } else {
+        new_check(
+            "memory",
+            CheckStatus::Ok,
+            message(MessageId::DoctorMemory, &[("total", &format_size(total))]),
+            None,
+        )
+    }
+}
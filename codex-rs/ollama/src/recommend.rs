@@ -0,0 +1,637 @@
+//! Recommending a local model for a workspace when `--oss` starts without
+//! one. The models the server has, and for Ollama a few worth downloading,
+//! are ranked by how well they suit the machine and the code: a model must
+//! fit in the memory it runs from, a repository that is mostly code favors
+//! a model tuned for code, and a small repository favors a small, fast model
+//! where a large one is worth a bigger model's reach.
+//!
+//! The pick is remembered per workspace in the OSS state directory, so the
+//! question is asked once.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::config::Config;
+use codex_core::protocol_config_types::OssBackend;
+
+use crate::LocalModel;
+use crate::atomic::write_atomic;
+use crate::host::gpu_memory_bytes;
+use crate::host::total_memory_bytes;
+use crate::list_local_models;
+use crate::model_id::ModelId;
+use crate::models::format_size;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+const WORKSPACE_MODELS_FILE: &str = "workspace-models.json";
+
+/// Files looked at when profiling a workspace; the rest of a huge one does
+/// not change the picture.
+const MAX_PROFILED_FILES: usize = 50_000;
+
+/// Models offered at most, so that each has a number key.
+const MAX_RECOMMENDATIONS: usize = 6;
+
+/// Memory a model needs beyond its weights for the context cache, as a
+/// fraction of their size.
+const CONTEXT_HEADROOM: f64 = 0.2;
+
+/// On machines without a GPU, larger models answer too slowly to work with.
+const CPU_ONLY_BUDGET_BYTES: u64 = 8 * GIB;
+
+/// Share of a workspace's text that must be code for code models to be
+/// preferred.
+const CODE_HEAVY_SHARE: f64 = 0.5;
+
+/// What a model is tuned for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModelFocus {
+    Code,
+    General,
+}
+
+/// A model of the Ollama library offered for download, with the size of
+/// its default quantization.
+struct SuggestedModel {
+    name: &'static str,
+    size_bytes: u64,
+    focus: ModelFocus,
+}
+
+const SUGGESTED_MODELS: [SuggestedModel; 8] = [
+    SuggestedModel {
+        name: "qwen2.5-coder:7b",
+        size_bytes: 5 * GIB,
+        focus: ModelFocus::Code,
+    },
+    SuggestedModel {
+        name: "qwen2.5-coder:14b",
+        size_bytes: 9 * GIB,
+        focus: ModelFocus::Code,
+    },
+    SuggestedModel {
+        name: "devstral:24b",
+        size_bytes: 14 * GIB,
+        focus: ModelFocus::Code,
+    },
+    SuggestedModel {
+        name: "qwen3-coder:30b",
+        size_bytes: 19 * GIB,
+        focus: ModelFocus::Code,
+    },
+    SuggestedModel {
+        name: "qwen3:4b",
+        size_bytes: 3 * GIB,
+        focus: ModelFocus::General,
+    },
+    SuggestedModel {
+        name: "qwen3:8b",
+        size_bytes: 5 * GIB,
+        focus: ModelFocus::General,
+    },
+    SuggestedModel {
+        name: "gpt-oss:20b",
+        size_bytes: 14 * GIB,
+        focus: ModelFocus::General,
+    },
+    SuggestedModel {
+        name: "gpt-oss:120b",
+        size_bytes: 65 * GIB,
+        focus: ModelFocus::General,
+    },
+];
+
+/// Languages by file extension. Prose counts toward a workspace's text but
+/// not its code.
+const LANGUAGES: [(&str, &[&str]); 24] = [
+    ("Rust", &["rs"]),
+    ("Python", &["py", "pyi"]),
+    ("TypeScript", &["ts", "tsx", "mts", "cts"]),
+    ("JavaScript", &["js", "jsx", "mjs", "cjs"]),
+    ("Go", &["go"]),
+    ("Java", &["java"]),
+    ("Kotlin", &["kt", "kts"]),
+    ("Swift", &["swift"]),
+    ("C", &["c", "h"]),
+    ("C++", &["cc", "cpp", "cxx", "hpp", "hh", "hxx"]),
+    ("C#", &["cs"]),
+    ("Ruby", &["rb"]),
+    ("PHP", &["php"]),
+    ("Scala", &["scala"]),
+    ("Shell", &["sh", "bash", "zsh"]),
+    ("Lua", &["lua"]),
+    ("Zig", &["zig"]),
+    ("Elixir", &["ex", "exs"]),
+    ("Haskell", &["hs"]),
+    ("OCaml", &["ml", "mli"]),
+    ("Dart", &["dart"]),
+    ("SQL", &["sql"]),
+    ("Markdown", &["md", "mdx"]),
+    ("Text", &["txt", "rst", "adoc"]),
+];
+
+const PROSE_LANGUAGES: [&str; 2] = ["Markdown", "Text"];
+
+/// How much a workspace holds, and in which languages.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkspaceProfile {
+    pub files: usize,
+    pub bytes: u64,
+    /// Bytes per language, largest first.
+    pub languages: Vec<(&'static str, u64)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WorkspaceSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl WorkspaceProfile {
+    /// Share of the workspace's text that is code rather than prose.
+    fn code_share(&self) -> f64 {
+        let (code, text) = self
+            .languages
+            .iter()
+            .fold((0, 0), |(code, text), (language, bytes)| {
+                if PROSE_LANGUAGES.contains(language) {
+                    (code, text + bytes)
+                } else {
+                    (code + bytes, text + bytes)
+                }
+            });
+        if text == 0 {
+            0.0
+        } else {
+            code as f64 / text as f64
+        }
+    }
+
+    fn size(&self) -> WorkspaceSize {
+        if self.files > 5_000 || self.bytes > 100 * 1024 * 1024 {
+            WorkspaceSize::Large
+        } else if self.files < 200 && self.bytes < 5 * 1024 * 1024 {
+            WorkspaceSize::Small
+        } else {
+            WorkspaceSize::Medium
+        }
+    }
+
+    /// E.g. "mostly Rust (82%), 1240 files, 36.0 MB".
+    pub fn describe(&self) -> String {
+        let text: u64 = self.languages.iter().map(|(_, bytes)| bytes).sum();
+        let files = format!("{} files, {}", self.files, format_size(self.bytes));
+        match self.languages.first() {
+            Some((language, bytes)) if text > 0 => {
+                let share = (*bytes as f64 / text as f64 * 100.0).round();
+                format!("mostly {language} ({share}%), {files}")
+            }
+            _ => files,
+        }
+    }
+}
+
+/// Memory the machine can run a model from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HardwareProfile {
+    pub memory_bytes: Option<u64>,
+    /// Total memory of the NVIDIA GPUs, when there are any.
+    pub gpu_memory_bytes: Option<u64>,
+    /// Apple silicon, whose GPU runs models from the machine's memory.
+    pub unified_memory: bool,
+}
+
+impl HardwareProfile {
+    pub fn detect() -> Self {
+        Self {
+            memory_bytes: total_memory_bytes(),
+            gpu_memory_bytes: gpu_memory_bytes(),
+            unified_memory: cfg!(all(target_os = "macos", target_arch = "aarch64")),
+        }
+    }
+
+    /// Size of the largest model that runs at a usable speed, if known.
+    fn model_budget(&self) -> Option<u64> {
+        if let Some(gpu) = self.gpu_memory_bytes {
+            return Some(gpu);
+        }
+        let memory = self.memory_bytes?;
+        if self.unified_memory {
+            // macOS lets the GPU use about three quarters of the memory.
+            Some(memory / 4 * 3)
+        } else {
+            Some((memory / 2).min(CPU_ONLY_BUDGET_BYTES))
+        }
+    }
+
+    /// E.g. "24.00 GB of GPU memory".
+    pub fn describe(&self) -> String {
+        match (self.gpu_memory_bytes, self.memory_bytes) {
+            (Some(gpu), _) => format!("{} of GPU memory", format_size(gpu)),
+            (None, Some(memory)) if self.unified_memory => {
+                format!("{} of unified memory", format_size(memory))
+            }
+            (None, Some(memory)) => format!("{} of memory and no GPU", format_size(memory)),
+            (None, None) => "unknown memory".to_string(),
+        }
+    }
+}
+
+/// A model offered for the workspace, with why.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelRecommendation {
+    pub model: String,
+    pub size_bytes: Option<u64>,
+    /// Whether the server has it; the others are downloaded at startup.
+    pub installed: bool,
+    /// E.g. "installed · tuned for code · fits in 24.00 GB of GPU memory".
+    pub reason: String,
+}
+
+/// Models ranked for a workspace, best first, and what they were ranked on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelRecommendations {
+    pub workspace: WorkspaceProfile,
+    pub hardware: HardwareProfile,
+    pub models: Vec<ModelRecommendation>,
+}
+
+/// Rank the models for `config.cwd`. Fails when the server cannot list its
+/// models. Downloads are only suggested for Ollama, which pulls library
+/// models by name, and not while `oss.offline` is set.
+pub async fn recommend_models(config: &Config) -> io::Result<ModelRecommendations> {
+    let installed = list_local_models(config).await?;
+    let cwd = config.cwd.clone();
+    let (workspace, hardware) =
+        tokio::task::spawn_blocking(move || (profile_workspace(&cwd), HardwareProfile::detect()))
+            .await
+            .map_err(io::Error::other)?;
+    let suggest_downloads =
+        matches!(config.oss.provider, None | Some(OssBackend::Ollama)) && !config.oss.offline;
+    let models = rank_models(&workspace, &hardware, &installed, suggest_downloads);
+    Ok(ModelRecommendations {
+        workspace,
+        hardware,
+        models,
+    })
+}
+
+/// Size and languages of the files under `root`: those git tracks or does
+/// not ignore, or outside a repository, those not in hidden directories.
+pub fn profile_workspace(root: &Path) -> WorkspaceProfile {
+    let files = git_files(root).unwrap_or_else(|| walk_files(root));
+    let mut profile = WorkspaceProfile::default();
+    let mut languages: BTreeMap<&'static str, u64> = BTreeMap::new();
+    for path in files.iter().take(MAX_PROFILED_FILES) {
+        let Ok(metadata) = std::fs::metadata(root.join(path)) else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        profile.files += 1;
+        profile.bytes += metadata.len();
+        if let Some(language) = language_of(path) {
+            *languages.entry(language).or_default() += metadata.len();
+        }
+    }
+    profile.languages = languages.into_iter().collect();
+    profile
+        .languages
+        .sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    profile
+}
+
+fn git_files(root: &Path) -> Option<Vec<PathBuf>> {
+    let output = std::process::Command::new("git")
+        .args([
+            "ls-files",
+            "-z",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+        ])
+        .current_dir(root)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .collect(),
+    )
+}
+
+fn walk_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![PathBuf::new()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(root.join(&dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = dir.join(entry.file_name());
+            match entry.file_type() {
+                Ok(kind) if kind.is_dir() => dirs.push(path),
+                Ok(kind) if kind.is_file() => files.push(path),
+                _ => {}
+            }
+            if files.len() >= MAX_PROFILED_FILES {
+                return files;
+            }
+        }
+    }
+    files
+}
+
+fn language_of(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|(_, extensions)| extensions.contains(&extension.as_str()))
+        .map(|(language, _)| *language)
+}
+
+/// Tell code models from general ones by name, for installed models Codex
+/// knows nothing else about.
+fn focus_of(model: &str) -> ModelFocus {
+    let name = ModelId::parse(model).name;
+    if ["coder", "code", "devstral", "codestral"]
+        .iter()
+        .any(|marker| name.contains(marker))
+    {
+        ModelFocus::Code
+    } else {
+        ModelFocus::General
+    }
+}
+
+/// Rank `installed` and, with `suggest_downloads`, the suggested models the
+/// server lacks. Models too large for the machine are left out unless none
+/// fits, in which case only the smallest is offered.
+pub fn rank_models(
+    workspace: &WorkspaceProfile,
+    hardware: &HardwareProfile,
+    installed: &[LocalModel],
+    suggest_downloads: bool,
+) -> Vec<ModelRecommendation> {
+    struct Candidate {
+        model: String,
+        size_bytes: Option<u64>,
+        installed: bool,
+        focus: ModelFocus,
+    }
+
+    let mut candidates: Vec<Candidate> = installed
+        .iter()
+        // Embedding models cannot chat.
+        .filter(|model| !model.name.contains("embed"))
+        .map(|model| Candidate {
+            model: model.name.clone(),
+            size_bytes: model.size_bytes,
+            installed: true,
+            focus: focus_of(&model.name),
+        })
+        .collect();
+    if suggest_downloads {
+        for suggested in &SUGGESTED_MODELS {
+            let id = ModelId::parse(suggested.name);
+            if !installed
+                .iter()
+                .any(|model| ModelId::parse(&model.name).matches(&id))
+            {
+                candidates.push(Candidate {
+                    model: suggested.name.to_string(),
+                    size_bytes: Some(suggested.size_bytes),
+                    installed: false,
+                    focus: suggested.focus,
+                });
+            }
+        }
+    }
+
+    let budget = hardware.model_budget();
+    let fits = |size: Option<u64>| match (size, budget) {
+        (Some(size), Some(budget)) => size as f64 * (1.0 + CONTEXT_HEADROOM) <= budget as f64,
+        _ => true,
+    };
+    if !candidates
+        .iter()
+        .any(|candidate| fits(candidate.size_bytes))
+    {
+        candidates.sort_by(|a, b| {
+            let size = |candidate: &Candidate| candidate.size_bytes.unwrap_or(u64::MAX);
+            size(a).cmp(&size(b)).then(a.model.cmp(&b.model))
+        });
+        candidates.truncate(1);
+    } else {
+        candidates.retain(|candidate| fits(candidate.size_bytes));
+    }
+
+    // The size a model for this repository would ideally have: small and
+    // fast for a small one, as large as fits for a large one.
+    let target = match workspace.size() {
+        WorkspaceSize::Small => 6 * GIB,
+        WorkspaceSize::Medium => 14 * GIB,
+        WorkspaceSize::Large => budget.unwrap_or(u64::MAX),
+    };
+    let target = budget.map_or(target, |budget| {
+        target.min((budget as f64 / (1.0 + CONTEXT_HEADROOM)) as u64)
+    });
+    let code_heavy = workspace.code_share() >= CODE_HEAVY_SHARE;
+    let score = |candidate: &Candidate| {
+        let mut score = match candidate.size_bytes {
+            Some(size) => -(size as f64 / target as f64).log2().abs(),
+            None => -1.0,
+        };
+        if code_heavy == (candidate.focus == ModelFocus::Code) {
+            score += 1.0;
+        }
+        if candidate.installed {
+            score += 0.75;
+        }
+        score
+    };
+
+    let mut ranked: Vec<(f64, Candidate)> = candidates
+        .into_iter()
+        .map(|candidate| (score(&candidate), candidate))
+        .collect();
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.model.cmp(&b.1.model)));
+    ranked.truncate(MAX_RECOMMENDATIONS);
+
+    ranked
+        .into_iter()
+        .map(|(_, candidate)| {
+            let mut reasons = vec![match candidate.size_bytes {
+                Some(size) if !candidate.installed => format!("downloads {}", format_size(size)),
+                _ => "installed".to_string(),
+            }];
+            if code_heavy && candidate.focus == ModelFocus::Code {
+                reasons.push("tuned for code".to_string());
+            }
+            match (candidate.size_bytes, budget) {
+                (Some(size), Some(_)) if fits(Some(size)) => {
+                    reasons.push(format!("fits in {}", hardware.describe()));
+                }
+                (Some(_), Some(_)) => {
+                    reasons.push(format!("may not fit in {}", hardware.describe()))
+                }
+                _ => {}
+            }
+            ModelRecommendation {
+                model: candidate.model,
+                size_bytes: candidate.size_bytes,
+                installed: candidate.installed,
+                reason: reasons.join(" · "),
+            }
+        })
+        .collect()
+}
+
+fn read_workspace_models(state_dir: &Path) -> BTreeMap<String, String> {
+    std::fs::read_to_string(state_dir.join(WORKSPACE_MODELS_FILE))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// The model picked for `workspace` before, if any.
+pub fn workspace_model(state_dir: &Path, workspace: &Path) -> Option<String> {
+    read_workspace_models(state_dir).remove(&workspace.to_string_lossy().into_owned())
+}
+
+/// Remember `model` as the one picked for `workspace`.
+pub fn record_workspace_model(state_dir: &Path, workspace: &Path, model: &str) -> io::Result<()> {
+    let mut models = read_workspace_models(state_dir);
+    models.insert(workspace.to_string_lossy().into_owned(), model.to_string());
+    let contents = serde_json::to_string_pretty(&models).map_err(io::Error::other)?;
+    write_atomic(&state_dir.join(WORKSPACE_MODELS_FILE), contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn local(name: &str, size_gib: u64) -> LocalModel {
+        LocalModel {
+            name: name.to_string(),
+            size_bytes: Some(size_gib * GIB),
+            quantization: None,
+            parameter_size: None,
+            context_length: None,
+            loaded: false,
+        }
+    }
+
+    fn names(ranked: &[ModelRecommendation]) -> Vec<&str> {
+        ranked.iter().map(|r| r.model.as_str()).collect()
+    }
+
+    const GPU_24_GIB: HardwareProfile = HardwareProfile {
+        memory_bytes: Some(64 * GIB),
+        gpu_memory_bytes: Some(24 * GIB),
+        unified_memory: false,
+    };
+
+    #[test]
+    fn a_large_rust_repository_gets_the_largest_code_model_that_fits() {
+        let workspace = WorkspaceProfile {
+            files: 12_000,
+            bytes: 400 * 1024 * 1024,
+            languages: vec![("Rust", 300 * 1024 * 1024), ("Markdown", 10 * 1024 * 1024)],
+        };
+        let ranked = rank_models(
+            &workspace,
+            &GPU_24_GIB,
+            &[local("gpt-oss:20b", 14), local("nomic-embed-text", 1)],
+            true,
+        );
+        assert_eq!(ranked[0].model, "qwen3-coder:30b");
+        assert_eq!(
+            ranked[0].reason,
+            "downloads 19.00 GB · tuned for code · fits in 24.00 GB of GPU memory"
+        );
+        assert!(!names(&ranked).contains(&"gpt-oss:120b"));
+        assert!(!names(&ranked).contains(&"nomic-embed-text"));
+    }
+
+    #[test]
+    fn a_small_docs_repository_prefers_an_installed_general_model() {
+        let workspace = WorkspaceProfile {
+            files: 40,
+            bytes: 600 * 1024,
+            languages: vec![("Markdown", 500 * 1024), ("Python", 20 * 1024)],
+        };
+        let ranked = rank_models(
+            &workspace,
+            &GPU_24_GIB,
+            &[local("qwen3:8b", 5), local("qwen2.5-coder:14b", 9)],
+            false,
+        );
+        assert_eq!(names(&ranked), vec!["qwen3:8b", "qwen2.5-coder:14b"]);
+        assert_eq!(
+            ranked[0].reason,
+            "installed · fits in 24.00 GB of GPU memory"
+        );
+    }
+
+    #[test]
+    fn only_the_smallest_model_is_offered_when_none_fits() {
+        let hardware = HardwareProfile {
+            memory_bytes: Some(8 * GIB),
+            gpu_memory_bytes: None,
+            unified_memory: false,
+        };
+        let ranked = rank_models(
+            &WorkspaceProfile::default(),
+            &hardware,
+            &[local("gpt-oss:20b", 14), local("devstral:24b", 14)],
+            false,
+        );
+        assert_eq!(names(&ranked), vec!["devstral:24b"]);
+        assert_eq!(
+            ranked[0].reason,
+            "installed · may not fit in 8.00 GB of memory and no GPU"
+        );
+    }
+
+    #[test]
+    fn workspaces_are_profiled_by_language() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir(dir.path().join("src")).expect("mkdir");
+        std::fs::create_dir(dir.path().join(".cache")).expect("mkdir");
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() {}\n".repeat(10)).expect("write");
+        std::fs::write(dir.path().join("README.md"), "# Demo\n").expect("write");
+        std::fs::write(dir.path().join(".cache/blob"), "x".repeat(1000)).expect("write");
+
+        let profile = profile_workspace(dir.path());
+        assert_eq!(profile.files, 2);
+        assert_eq!(profile.languages, vec![("Rust", 130), ("Markdown", 7)]);
+        assert_eq!(profile.describe(), "mostly Rust (95%), 2 files, 0.0 MB");
+    }
+
+    #[test]
+    fn workspace_picks_round_trip() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let workspace = Path::new("/src/parser");
+        assert_eq!(workspace_model(dir.path(), workspace), None);
+        record_workspace_model(dir.path(), workspace, "qwen3-coder:30b").expect("record");
+        assert_eq!(
+            workspace_model(dir.path(), workspace),
+            Some("qwen3-coder:30b".to_string())
+        );
+    }
+}
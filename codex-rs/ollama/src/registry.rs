@@ -0,0 +1,382 @@
+//! Update checks against the registry a model was pulled from.
+//!
+//! Ollama identifies a local model by the sha256 of its manifest, so a model
+//! is out of date when the registry now serves a manifest with a different
+//! digest. Pulling the model again fetches the new revision. The manifest
+//! also lists the size of every layer, which tells how much a pull downloads.
+
+use codex_protocol::oss::ModelUpdateStatus;
+use sha2::Digest;
+use sha2::Sha256;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::OllamaClient;
+use crate::client::http_client;
+
+const DEFAULT_REGISTRY: &str = "registry.ollama.ai";
+const DEFAULT_NAMESPACE: &str = "library";
+const DEFAULT_TAG: &str = "latest";
+const MANIFEST_MEDIA_TYPE: &str = "application/vnd.docker.distribution.manifest.v2+json";
+
+/// A model name split into the parts used to address its manifest, e.g.
+/// "gpt-oss:20b" or "hf.co/unsloth/gpt-oss-20b-GGUF:Q4_K_M".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ModelReference {
+    registry: String,
+    namespace: String,
+    repository: String,
+    tag: String,
+}
+
+impl ModelReference {
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        let (path, tag) = match name.rsplit_once(':') {
+            Some((path, tag)) if !tag.contains('/') => (path, tag),
+            _ => (name, DEFAULT_TAG),
+        };
+        let parts: Vec<&str> = path.split('/').collect();
+        let (registry, namespace, repository) = match parts.as_slice() {
+            [repository] => (DEFAULT_REGISTRY, DEFAULT_NAMESPACE, *repository),
+            [namespace, repository] => (DEFAULT_REGISTRY, *namespace, *repository),
+            [registry, namespace, repository] => (*registry, *namespace, *repository),
+            _ => return None,
+        };
+        if [registry, namespace, repository, tag]
+            .iter()
+            .any(|part| part.is_empty())
+        {
+            return None;
+        }
+        Some(Self {
+            registry: registry.to_string(),
+            namespace: namespace.to_string(),
+            repository: repository.to_string(),
+            tag: tag.to_string(),
+        })
+    }
+
+    /// Reference for a manifest Ollama stores at
+    /// `manifests/<registry>/<namespace>/<repository>/<tag>`.
+    pub(crate) fn from_manifest_parts(
+        registry: &str,
+        namespace: &str,
+        repository: &str,
+        tag: &str,
+    ) -> Self {
+        Self {
+            registry: registry.to_string(),
+            namespace: namespace.to_string(),
+            repository: repository.to_string(),
+            tag: tag.to_string(),
+        }
+    }
+
+    /// Name as Ollama lists it, without the default registry and namespace.
+    pub(crate) fn name(&self) -> String {
+        let Self {
+            registry,
+            namespace,
+            repository,
+            tag,
+        } = self;
+        if registry != DEFAULT_REGISTRY {
+            format!("{registry}/{namespace}/{repository}:{tag}")
+        } else if namespace != DEFAULT_NAMESPACE {
+            format!("{namespace}/{repository}:{tag}")
+        } else {
+            format!("{repository}:{tag}")
+        }
+    }
+
+    /// Where Ollama stores this model's manifest under `models_dir`.
+    pub(crate) fn manifest_path(&self, models_dir: &Path) -> PathBuf {
+        models_dir
+            .join("manifests")
+            .join(&self.registry)
+            .join(&self.namespace)
+            .join(&self.repository)
+            .join(&self.tag)
+    }
+
+    fn manifest_url(&self, registry_base_url: Option<&str>) -> String {
+        let base = registry_base_url
+            .map(|base| base.trim_end_matches('/').to_string())
+            .unwrap_or_else(|| format!("https://{}", self.registry));
+        format!(
+            "{base}/v2/{}/{}/manifests/{}",
+            self.namespace, self.repository, self.tag
+        )
+    }
+}
+
+/// Client for model registries.
+pub struct RegistryClient {
+    client: reqwest::Client,
+    /// Replaces `https://<registry>` for every model, for tests and mirrors.
+    base_url_override: Option<String>,
+}
+
+impl RegistryClient {
+    pub fn new() -> Self {
+        let client = http_client();
+        Self {
+            client,
+            base_url_override: None,
+        }
+    }
+
+    /// Send every manifest request to `base_url` instead of the model's registry.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url_override = Some(base_url.into());
+        self
+    }
+
+    /// Digest of the manifest the registry currently serves for `model`.
+    pub async fn fetch_manifest_digest(&self, model: &str) -> io::Result<String> {
+        let body = self.fetch_manifest(model).await?;
+        Ok(format!("{:x}", Sha256::digest(&body)))
+    }
+
+    /// Bytes a pull of `model` downloads when none of its layers are present.
+    pub async fn fetch_download_size(&self, model: &str) -> io::Result<u64> {
+        let body = self.fetch_manifest(model).await?;
+        let manifest: serde_json::Value = serde_json::from_slice(&body)?;
+        manifest_size(&manifest).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("the manifest of '{model}' lists no layer sizes"),
+            )
+        })
+    }
+
+    async fn fetch_manifest(&self, model: &str) -> io::Result<bytes::Bytes> {
+        let reference = ModelReference::parse(model).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("'{model}' is not a registry model name"),
+            )
+        })?;
+        let resp = self
+            .client
+            .get(reference.manifest_url(self.base_url_override.as_deref()))
+            .header(reqwest::header::ACCEPT, MANIFEST_MEDIA_TYPE)
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("'{model}' is no longer published"),
+            ));
+        }
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "registry returned HTTP {}",
+                resp.status()
+            )));
+        }
+        resp.bytes().await.map_err(io::Error::other)
+    }
+}
+
+/// Total size of the config and layers of a manifest.
+fn manifest_size(manifest: &serde_json::Value) -> Option<u64> {
+    let layers = manifest.get("layers")?.as_array()?;
+    let config = manifest.get("config").into_iter();
+    config
+        .chain(layers)
+        .map(|blob| blob.get("size").and_then(serde_json::Value::as_u64))
+        .sum()
+}
+
+impl Default for RegistryClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compare each local model (or only `models`, when non-empty) with the
+/// revision its registry currently serves.
+pub async fn check_model_updates(
+    client: &OllamaClient,
+    registry: &RegistryClient,
+    models: &[String],
+) -> io::Result<Vec<ModelUpdateStatus>> {
+    let mut local = client.fetch_model_digests().await?;
+    if !models.is_empty() {
+        local.retain(|(name, _)| models.contains(name));
+        for model in models {
+            if !local.iter().any(|(name, _)| name == model) {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("no local model named '{model}'"),
+                ));
+            }
+        }
+    }
+
+    let statuses =
+        futures::future::join_all(local.into_iter().map(|(model, local_digest)| async move {
+            match registry.fetch_manifest_digest(&model).await {
+                Ok(remote_digest) => ModelUpdateStatus {
+                    update_available: !digests_match(&local_digest, &remote_digest),
+                    model,
+                    local_digest: Some(local_digest),
+                    remote_digest: Some(remote_digest),
+                    error: None,
+                },
+                Err(err) => ModelUpdateStatus {
+                    model,
+                    local_digest: Some(local_digest),
+                    remote_digest: None,
+                    update_available: false,
+                    error: Some(err.to_string()),
+                },
+            }
+        }))
+        .await;
+    Ok(statuses)
+}
+
+/// Ollama reports digests with or without the `sha256:` prefix.
+pub(crate) fn digests_match(a: &str, b: &str) -> bool {
+    let strip = |d: &str| d.trim_start_matches("sha256:").to_ascii_lowercase();
+    strip(a) == strip(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_model_reference() {
+        let reference = |registry: &str, namespace: &str, repository: &str, tag: &str| {
+            Some(ModelReference {
+                registry: registry.to_string(),
+                namespace: namespace.to_string(),
+                repository: repository.to_string(),
+                tag: tag.to_string(),
+            })
+        };
+        assert_eq!(
+            ModelReference::parse("gpt-oss:20b"),
+            reference("registry.ollama.ai", "library", "gpt-oss", "20b")
+        );
+        assert_eq!(
+            ModelReference::parse("mistral"),
+            reference("registry.ollama.ai", "library", "mistral", "latest")
+        );
+        assert_eq!(
+            ModelReference::parse("hf.co/unsloth/gpt-oss-20b-GGUF:Q4_K_M"),
+            reference("hf.co", "unsloth", "gpt-oss-20b-GGUF", "Q4_K_M")
+        );
+        assert_eq!(ModelReference::parse("a/b/c/d"), None);
+        assert_eq!(ModelReference::parse("gpt-oss:"), None);
+    }
+
+    #[test]
+    fn test_name_omits_defaults() {
+        for name in [
+            "gpt-oss:20b",
+            "hf.co/unsloth/gpt-oss-20b-GGUF:Q4_K_M",
+            "me/tuned:v1",
+        ] {
+            let reference = ModelReference::parse(name).expect("reference");
+            assert_eq!(reference.name(), name);
+        }
+    }
+
+    #[test]
+    fn test_manifest_url() {
+        let reference = ModelReference::parse("gpt-oss:20b").expect("reference");
+        assert_eq!(
+            reference.manifest_url(None),
+            "https://registry.ollama.ai/v2/library/gpt-oss/manifests/20b"
+        );
+        assert_eq!(
+            reference.manifest_url(Some("http://127.0.0.1:9000/")),
+            "http://127.0.0.1:9000/v2/library/gpt-oss/manifests/20b"
+        );
+    }
+
+    #[test]
+    fn test_manifest_size_sums_config_and_layers() {
+        let manifest = serde_json::json!({
+            "config": {"digest": "sha256:a", "size": 489},
+            "layers": [
+                {"digest": "sha256:b", "size": 13_780_154_624_u64},
+                {"digest": "sha256:c", "size": 7_240},
+            ]
+        });
+        assert_eq!(manifest_size(&manifest), Some(13_780_162_353));
+        assert_eq!(
+            manifest_size(&serde_json::json!({"schemaVersion": 2})),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_model_updates_compares_manifest_digests() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_check_model_updates_compares_manifest_digests",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let manifest = r#"{"schemaVersion":2}"#;
+        let current = format!("{:x}", Sha256::digest(manifest.as_bytes()));
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/tags"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_raw(
+                    serde_json::json!({
+                        "models": [
+                            {"name": "gpt-oss:20b", "digest": current},
+                            {"name": "mistral:latest", "digest": "0123"}
+                        ]
+                    })
+                    .to_string(),
+                    "application/json",
+                ),
+            )
+            .mount(&server)
+            .await;
+        for repository in ["gpt-oss", "mistral"] {
+            wiremock::Mock::given(wiremock::matchers::method("GET"))
+                .and(wiremock::matchers::path(format!(
+                    "/v2/library/{repository}/manifests/{}",
+                    if repository == "gpt-oss" {
+                        "20b"
+                    } else {
+                        "latest"
+                    }
+                )))
+                .respond_with(
+                    wiremock::ResponseTemplate::new(200)
+                        .set_body_raw(manifest, MANIFEST_MEDIA_TYPE),
+                )
+                .mount(&server)
+                .await;
+        }
+
+        let client = OllamaClient::from_host_root(server.uri());
+        let registry = RegistryClient::new().with_base_url(server.uri());
+        let statuses = check_model_updates(&client, &registry, &[])
+            .await
+            .expect("check updates");
+        let outdated: Vec<(&str, bool)> = statuses
+            .iter()
+            .map(|status| (status.model.as_str(), status.update_available))
+            .collect();
+        assert_eq!(
+            outdated,
+            vec![("gpt-oss:20b", false), ("mistral:latest", true)]
+        );
+    }
+}
@@ -0,0 +1,484 @@
+//! Lifecycle management for an `ollama serve` process started by Codex.
+//!
+//! Ollama does not ship a daemon manager of its own, so servers started via
+//! `codex oss serve start` are detached child processes whose pid is recorded
+//! in the Codex state directory. Only servers started this way can be stopped,
+//! and only while the recorded pid still runs Ollama: after a reboot it may
+//! belong to any process.
+
+use codex_core::config::Config;
+use codex_core::protocol_config_types::OssBackend;
+use codex_protocol::oss::OssEvent;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+#[cfg(not(target_os = "linux"))]
+use std::process::Command;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use crate::OllamaClient;
+use crate::api::forget_api_surfaces;
+use crate::audit::audit_log_path;
+use crate::command::format_argv;
+use crate::command::print_only;
+use crate::command::run_command;
+#[cfg(feature = "ollama")]
+use crate::command::spawn_background;
+use crate::events::publish;
+use crate::find_ollama_binary;
+use crate::lms;
+use crate::lms_trust::trusted_lms_cli;
+use crate::oss_state_dir;
+
+const PID_FILE: &str = "ollama-serve.pid";
+const LOG_FILE: &str = "ollama-serve.log";
+
+/// How long to wait for a stopped server to exit before giving up.
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait for a started server to answer.
+const START_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Pid of the last `ollama serve` this process started, or 0.
+static OLLAMA_STARTED_HERE: AtomicU32 = AtomicU32::new(0);
+
+/// Set once this process ran `lms server start`.
+static LMSTUDIO_STARTED_HERE: AtomicBool = AtomicBool::new(false);
+
+/// Options forwarded to `ollama serve`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServeOptions {
+    /// Port to listen on; defaults to Ollama's own default (11434).
+    pub port: Option<u16>,
+    /// Allow cross-origin requests from any origin.
+    pub cors: bool,
+}
+
+impl ServeOptions {
+    /// Environment variables that configure `ollama serve` for these options.
+    #[cfg(feature = "ollama")]
+    pub(crate) fn env(&self) -> Vec<(&'static str, String)> {
+        let mut env = Vec::new();
+        if let Some(port) = self.port {
+            env.push(("OLLAMA_HOST", format!("127.0.0.1:{port}")));
+        }
+        if self.cors {
+            env.push(("OLLAMA_ORIGINS", "*".to_string()));
+        }
+        env
+    }
+}
+
+/// Path of the log file that captures output of a Codex-managed server.
+pub fn server_log_path(state_dir: &Path) -> PathBuf {
+    state_dir.join(LOG_FILE)
+}
+
+/// Start `ollama serve` in the background and record its pid in `state_dir`.
+/// Returns `None` when the command was only printed (see
+/// [`crate::set_print_only`]).
+#[cfg(feature = "ollama")]
+pub fn spawn_server(
+    binary: &Path,
+    options: &ServeOptions,
+    state_dir: &Path,
+) -> io::Result<Option<u32>> {
+    let Some(pid) = spawn_background(
+        &audit_log_path(state_dir),
+        binary,
+        &["serve"],
+        &options.env(),
+        &server_log_path(state_dir),
+    )?
+    else {
+        return Ok(None);
+    };
+    std::fs::write(state_dir.join(PID_FILE), pid.to_string())?;
+    Ok(Some(pid))
+}
+
+/// A build without Ollama support never starts it.
+#[cfg(not(feature = "ollama"))]
+pub fn spawn_server(
+    _binary: &Path,
+    _options: &ServeOptions,
+    _state_dir: &Path,
+) -> io::Result<Option<u32>> {
+    Err(crate::backend_not_compiled_in(OssBackend::Ollama))
+}
+
+/// Start the server behind the `oss` provider and wait until it answers.
+///
+/// Ollama is started with `ollama serve` on the configured port and LM Studio
+/// with `lms server start`. Remote and generic servers cannot be started.
+pub async fn start_oss_server(config: &Config) -> io::Result<()> {
+    let client = OllamaClient::from_oss_provider(config)?;
+    if !codex_core::is_loopback_base_url(client.host_root()) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "The server at {} is not on this machine; start it there and retry.",
+                client.host_root()
+            ),
+        ));
+    }
+    if config.oss.connection.unix_socket.is_some() || config.oss.connection.named_pipe.is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Codex cannot start a server on a Unix domain socket or a named pipe; start it yourself and retry.",
+        ));
+    }
+    match config.oss.provider {
+        Some(OssBackend::Generic) => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Codex cannot start a generic OpenAI-compatible server; start it yourself and retry.",
+            ));
+        }
+        Some(OssBackend::LmStudio) => {
+            let lms = client.trusted_lms_cli()?.ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "LM Studio's `lms` CLI was not found. Install it with `npx lmstudio install-cli`.",
+                )
+            })?;
+            let args = lms::server_start_args();
+            client.log(format_args!(
+                "running `{} {}`",
+                lms.display(),
+                args.join(" ")
+            ));
+            let output = run_command(client.audit_log(), &lms, &args).await?;
+            if !output.status.success() {
+                return Err(io::Error::other(format!(
+                    "`lms server start` failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            LMSTUDIO_STARTED_HERE.store(true, Ordering::Relaxed);
+        }
+        Some(OssBackend::Ollama) | None => {
+            let binary = find_ollama_binary().ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "ollama executable not found. Install it from https://github.com/ollama/ollama?tab=readme-ov-file#ollama",
+                )
+            })?;
+            let options = ServeOptions {
+                port: host_root_port(client.host_root()),
+                cors: false,
+            };
+            client.log(format_args!("spawning `{} serve`", binary.display()));
+            if let Some(pid) = spawn_server(&binary, &options, &oss_state_dir(config))? {
+                OLLAMA_STARTED_HERE.store(pid, Ordering::Relaxed);
+            }
+        }
+    }
+    if print_only() {
+        return Ok(());
+    }
+    client.wait_until_reachable(START_TIMEOUT).await?;
+    forget_api_surfaces(client.host_root());
+    publish(OssEvent::ServerStarted {
+        backend: config.oss.provider.unwrap_or(OssBackend::Ollama),
+        base_url: client.host_root().to_string(),
+    });
+    Ok(())
+}
+
+/// Port of a host root such as "http://localhost:11434".
+fn host_root_port(host_root: &str) -> Option<u16> {
+    let (_, port) = host_root.trim_end_matches('/').rsplit_once(':')?;
+    port.parse().ok()
+}
+
+/// Pid of the running server previously started by Codex, if any. Stale pid
+/// files, left behind by a crashed server or naming a process that is not
+/// Ollama, are removed.
+pub fn managed_server_pid(state_dir: &Path) -> Option<u32> {
+    let pid = recorded_pid(state_dir)?;
+    if process_alive(pid) && runs_ollama(pid) {
+        Some(pid)
+    } else {
+        forget_server(state_dir);
+        None
+    }
+}
+
+/// Pid recorded for the server Codex started, whether or not it still runs.
+pub(crate) fn recorded_pid(state_dir: &Path) -> Option<u32> {
+    std::fs::read_to_string(state_dir.join(PID_FILE))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Remove the record of the server Codex started.
+pub(crate) fn forget_server(state_dir: &Path) {
+    let _ = std::fs::remove_file(state_dir.join(PID_FILE));
+}
+
+/// How long the server started by Codex has been running, measured from when
+/// its pid was recorded.
+pub fn managed_server_uptime(state_dir: &Path) -> Option<Duration> {
+    managed_server_pid(state_dir)?;
+    let started = std::fs::metadata(state_dir.join(PID_FILE))
+        .and_then(|meta| meta.modified())
+        .ok()?;
+    started.elapsed().ok()
+}
+
+/// Stop the server previously started by Codex. Returns the pid that was
+/// stopped, or `None` when no managed server was running.
+pub async fn stop_managed_server(state_dir: &Path) -> io::Result<Option<u32>> {
+    let Some(pid) = managed_server_pid(state_dir) else {
+        return Ok(None);
+    };
+    let audit_log = audit_log_path(state_dir);
+    if print_only() {
+        terminate(&audit_log, pid).await?;
+        return Ok(Some(pid));
+    }
+    // Forget the server before it exits, so a session supervising it does
+    // not take the stop for a crash and start it again.
+    forget_server(state_dir);
+    let restore = || std::fs::write(state_dir.join(PID_FILE), pid.to_string());
+    if let Err(err) = terminate(&audit_log, pid).await {
+        restore()?;
+        return Err(err);
+    }
+
+    let deadline = tokio::time::Instant::now() + STOP_TIMEOUT;
+    while process_alive(pid) {
+        if tokio::time::Instant::now() >= deadline {
+            restore()?;
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("server (pid {pid}) did not exit within {STOP_TIMEOUT:?}"),
+            ));
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    Ok(Some(pid))
+}
+
+/// Stop the server behind the `oss` provider if this process started it,
+/// as opposed to `codex oss serve start` or the user. Returns whether a
+/// server was stopped.
+pub(crate) async fn stop_server_started_here(config: &Config) -> io::Result<bool> {
+    match config.oss.provider {
+        Some(OssBackend::Generic) => Ok(false),
+        Some(OssBackend::LmStudio) => {
+            if !LMSTUDIO_STARTED_HERE.swap(false, Ordering::Relaxed) {
+                return Ok(false);
+            }
+            let Some(lms) = trusted_lms_cli(&config.oss.trusted_lms_sha256)? else {
+                return Ok(false);
+            };
+            let audit_log = audit_log_path(&oss_state_dir(config));
+            let output = run_command(Some(&audit_log), &lms, &lms::server_stop_args()).await?;
+            if !output.status.success() {
+                return Err(io::Error::other(format!(
+                    "`lms server stop` failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            Ok(true)
+        }
+        Some(OssBackend::Ollama) | None => {
+            let state_dir = oss_state_dir(config);
+            let started = OLLAMA_STARTED_HERE.load(Ordering::Relaxed);
+            if started == 0 || recorded_pid(&state_dir) != Some(started) {
+                return Ok(false);
+            }
+            Ok(stop_managed_server(&state_dir).await?.is_some())
+        }
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 performs error checking only and sends nothing.
+    unsafe { libc::kill(pid, 0) == 0 }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    Command::new("tasklist")
+        .args(["/FI", format!("PID eq {pid}").as_str(), "/NH"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+        .unwrap_or(false)
+}
+
+/// Whether `pid` runs an Ollama binary, so that a pid the system handed to
+/// another process since the server exited is never signalled.
+fn runs_ollama(pid: u32) -> bool {
+    process_name(pid).is_some_and(|name| name.to_ascii_lowercase().starts_with("ollama"))
+}
+
+#[cfg(target_os = "linux")]
+fn process_name(pid: u32) -> Option<String> {
+    let comm = std::fs::read_to_string(format!("/proc/{pid}/comm")).ok()?;
+    Some(comm.trim().to_string())
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn process_name(pid: u32) -> Option<String> {
+    let output = Command::new("ps")
+        .args(["-p", pid.to_string().as_str(), "-o", "comm="])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // macOS prints the executable's full path.
+    let comm = String::from_utf8_lossy(&output.stdout);
+    Path::new(comm.trim())
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+#[cfg(not(unix))]
+fn process_name(pid: u32) -> Option<String> {
+    let output = Command::new("tasklist")
+        .args(["/FI", format!("PID eq {pid}").as_str(), "/FO", "CSV", "/NH"])
+        .output()
+        .ok()?;
+    // e.g. "ollama.exe","1234","Console","1","25,132 K"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let name = stdout.trim().strip_prefix('"')?.split('"').next()?;
+    Some(name.to_string())
+}
+
+/// The program and arguments that stop `pid`: SIGTERM on Unix, and on
+/// Windows `taskkill`, which also ends the runners the server started.
+fn terminate_argv(pid: u32) -> (&'static str, Vec<String>) {
+    let pid = pid.to_string();
+    if cfg!(unix) {
+        ("kill", vec!["-TERM".to_string(), pid])
+    } else {
+        let args = ["/PID", pid.as_str(), "/T", "/F"];
+        ("taskkill", args.iter().map(ToString::to_string).collect())
+    }
+}
+
+/// Stop `pid` through [`run_command`], so the stop is logged and audited
+/// like every other command the OSS path runs.
+async fn terminate(audit_log: &Path, pid: u32) -> io::Result<()> {
+    let (program, args) = terminate_argv(pid);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = run_command(Some(audit_log), Path::new(program), &args).await?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "`{}` failed: {}",
+            format_argv(Path::new(program), &args),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[cfg(feature = "ollama")]
+    #[test]
+    fn serve_options_env() {
+        assert!(ServeOptions::default().env().is_empty());
+        let options = ServeOptions {
+            port: Some(11500),
+            cors: true,
+        };
+        assert_eq!(
+            options.env(),
+            vec![
+                ("OLLAMA_HOST", "127.0.0.1:11500".to_string()),
+                ("OLLAMA_ORIGINS", "*".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn host_root_port_is_parsed() {
+        assert_eq!(host_root_port("http://localhost:11500/"), Some(11500));
+        assert_eq!(host_root_port("http://localhost"), None);
+    }
+
+    #[tokio::test]
+    async fn servers_started_elsewhere_are_not_stopped() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let mut config = Config::load_from_base_config_with_overrides(
+            codex_core::config::ConfigToml::default(),
+            codex_core::config::ConfigOverrides::default(),
+            dir.path().to_path_buf(),
+        )
+        .expect("config");
+        config.oss.provider = Some(OssBackend::Ollama);
+        // A live server that `codex oss serve start` recorded.
+        let state_dir = oss_state_dir(&config);
+        std::fs::create_dir_all(&state_dir).expect("state dir");
+        std::fs::write(state_dir.join(PID_FILE), std::process::id().to_string())
+            .expect("write pid");
+
+        assert!(!stop_server_started_here(&config).await.expect("stop"));
+        assert_eq!(recorded_pid(&state_dir), Some(std::process::id()));
+    }
+
+    #[test]
+    fn stale_pid_file_is_removed() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        // No real process will ever have this pid.
+        std::fs::write(dir.path().join(PID_FILE), u32::MAX.to_string()).expect("write pid");
+        assert_eq!(managed_server_pid(dir.path()), None);
+        assert!(!dir.path().join(PID_FILE).exists());
+    }
+
+    #[test]
+    fn a_reused_pid_is_not_taken_for_the_server() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        // A live process, but the test binary rather than Ollama.
+        std::fs::write(dir.path().join(PID_FILE), std::process::id().to_string())
+            .expect("write pid");
+        assert!(!runs_ollama(std::process::id()));
+        assert_eq!(managed_server_pid(dir.path()), None);
+        assert!(!dir.path().join(PID_FILE).exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stopping_a_server_is_audited() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let audit_log = audit_log_path(dir.path());
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .expect("spawn sleep");
+        let pid = child.id();
+
+        terminate(&audit_log, pid).await.expect("terminate");
+        child.wait().expect("wait");
+
+        let entries = crate::audit::read_audit_log(&audit_log).expect("read audit log");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].action,
+            crate::audit::AuditAction::Command {
+                program: PathBuf::from("kill"),
+                args: vec!["-TERM".to_string(), pid.to_string()],
+                pid: None,
+                exit_code: Some(0),
+            }
+        );
+    }
+}
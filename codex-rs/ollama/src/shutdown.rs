@@ -0,0 +1,32 @@
+//! Leaving the local provider tidy when a session ends.
+
+use codex_core::config::Config;
+use std::time::Duration;
+
+use crate::server::stop_server_started_here;
+use crate::unload::unload_models;
+
+/// Longest Codex waits on the local server while exiting.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Stop the server this process started, or otherwise unload the session's
+/// model when `oss.unload_on_exit` is set. Failures are logged; exiting
+/// never waits longer than a few seconds on the server.
+pub async fn shutdown_local_provider(config: &Config) {
+    let tidy = async {
+        match stop_server_started_here(config).await {
+            // Stopping the server freed its models too.
+            Ok(true) => return,
+            Ok(false) => {}
+            Err(err) => tracing::warn!("stopping the local server failed: {err}"),
+        }
+        if config.oss.unload_on_exit
+            && let Err(err) = unload_models(config, Some(&config.model)).await
+        {
+            tracing::warn!("unloading {} failed: {err}", config.model);
+        }
+    };
+    if tokio::time::timeout(SHUTDOWN_TIMEOUT, tidy).await.is_err() {
+        tracing::warn!("gave up on the local server after {SHUTDOWN_TIMEOUT:?} while exiting");
+    }
+}
@@ -0,0 +1,74 @@
+//! Runtime status of the local server: its version, the models it holds in
+//! memory, and how long it has been up.
+
+use std::io;
+use std::path::Path;
+
+use codex_core::protocol_config_types::OssBackend;
+use codex_protocol::oss::LoadedModel;
+use codex_protocol::oss::OssRuntimeStatus;
+use codex_protocol::oss::ServerStatus;
+
+use crate::OllamaClient;
+use crate::server::managed_server_pid;
+use crate::server::managed_server_uptime;
+
+/// Whether the Ollama server answers and which version it runs.
+pub async fn server_status(client: &OllamaClient, state_dir: &Path) -> ServerStatus {
+    let version = client.fetch_version().await.ok();
+    ServerStatus {
+        host_root: client.host_root().to_string(),
+        running: version.is_some(),
+        version,
+        managed_pid: managed_server_pid(state_dir),
+    }
+}
+
+/// Status of the server `client` talks to. Only Ollama reports its version
+/// and the memory of loaded models; LM Studio reports which models are
+/// loaded, and other servers neither.
+pub async fn runtime_status(
+    client: &OllamaClient,
+    backend: OssBackend,
+    state_dir: &Path,
+) -> io::Result<OssRuntimeStatus> {
+    let server = match backend {
+        OssBackend::Ollama => server_status(client, state_dir).await,
+        OssBackend::LmStudio | OssBackend::Generic => ServerStatus {
+            host_root: client.host_root().to_string(),
+            running: client.probe_server().await.is_ok(),
+            version: None,
+            managed_pid: None,
+        },
+    };
+    let loaded_models = match backend {
+        _ if !server.running => Vec::new(),
+        OssBackend::Ollama => client.fetch_loaded_models().await?,
+        OssBackend::LmStudio | OssBackend::Generic if client.api_surfaces().await.lmstudio_v0 => {
+            client
+                .fetch_lmstudio_loaded_models()
+                .await?
+                .into_iter()
+                .map(|name| LoadedModel {
+                    name,
+                    size_bytes: None,
+                    vram_bytes: None,
+                    context_length: None,
+                    expires_at: None,
+                })
+                .collect()
+        }
+        OssBackend::LmStudio | OssBackend::Generic => Vec::new(),
+    };
+    let uptime_secs = match backend {
+        OssBackend::Ollama => managed_server_uptime(state_dir).map(|uptime| uptime.as_secs()),
+        OssBackend::LmStudio | OssBackend::Generic => None,
+    };
+    Ok(OssRuntimeStatus {
+        server,
+        uptime_secs,
+        // None of the supported servers report requests in flight.
+        active_requests: None,
+        loaded_models,
+    })
+}
@@ -0,0 +1,209 @@
+//! Supervision of an `ollama serve` process started by Codex.
+//!
+//! A server Codex started records its pid in the state directory. While a
+//! session runs, the supervisor watches that pid; when the process is gone
+//! but its pid file is still there, the server crashed rather than being
+//! stopped, so it is started again and the session's model reloaded.
+//! Servers started by someone else, including LM Studio's, are left alone.
+
+use codex_core::config::Config;
+use codex_core::protocol_config_types::OssBackend;
+use codex_protocol::oss::OssEvent;
+use std::path::Path;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::events::publish;
+use crate::load::load_model;
+use crate::oss_state_dir;
+use crate::server::forget_server;
+use crate::server::process_alive;
+use crate::server::recorded_pid;
+use crate::server::start_oss_server;
+
+/// How often the recorded pid is checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Restarts attempted after a crash before giving up.
+const MAX_RESTARTS: u32 = 3;
+
+/// Delay before the first restart; each further attempt doubles it.
+const BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// A server that stayed up this long after a restart gets a fresh budget of
+/// restarts the next time it crashes.
+const STABLE_AFTER: Duration = Duration::from_secs(5 * 60);
+
+/// What the supervisor saw happen to the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerLifecycleEvent {
+    /// The server process exited without being stopped.
+    Exited { pid: u32 },
+    /// The server is started again after `delay`.
+    Restarting {
+        attempt: u32,
+        max_attempts: u32,
+        delay: Duration,
+    },
+    /// The server answers again and the session's model is loaded.
+    Restarted { pid: Option<u32> },
+    /// Restarts ran out; the server stays down.
+    GaveUp { attempts: u32, error: String },
+}
+
+/// Restarts left for a server that crashed, and how long to wait for each.
+#[derive(Debug, Default)]
+struct RestartBudget {
+    used: u32,
+    last_restart: Option<Instant>,
+}
+
+impl RestartBudget {
+    /// Delay before the next restart, or `None` once the budget is spent.
+    fn next_delay(&mut self, now: Instant) -> Option<Duration> {
+        if self
+            .last_restart
+            .is_some_and(|last| now.saturating_duration_since(last) >= STABLE_AFTER)
+        {
+            self.used = 0;
+        }
+        if self.used >= MAX_RESTARTS {
+            return None;
+        }
+        let delay = BASE_DELAY * 2u32.pow(self.used);
+        self.used += 1;
+        self.last_restart = Some(now);
+        Some(delay)
+    }
+}
+
+/// Watch the server Codex started for `config` and restart it with backoff
+/// when it crashes, reporting each step to `on_event`. Returns once restarts
+/// ran out.
+pub async fn supervise_managed_server(
+    config: Config,
+    mut on_event: impl FnMut(ServerLifecycleEvent) + Send,
+) {
+    let state_dir = oss_state_dir(&config);
+    let mut budget = RestartBudget::default();
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        let Some(pid) = crashed_pid(&state_dir) else {
+            continue;
+        };
+        tracing::warn!("the local server (pid {pid}) exited unexpectedly");
+        publish(OssEvent::BackendUnhealthy {
+            backend: OssBackend::Ollama,
+            message: format!("the local server (pid {pid}) exited unexpectedly"),
+        });
+        on_event(ServerLifecycleEvent::Exited { pid });
+        let mut last_error = None;
+        loop {
+            let Some(delay) = budget.next_delay(Instant::now()) else {
+                let error = last_error.unwrap_or_else(|| "it kept exiting".to_string());
+                publish(OssEvent::BackendUnhealthy {
+                    backend: OssBackend::Ollama,
+                    message: format!(
+                        "the local server stays down after {MAX_RESTARTS} restarts: {error}"
+                    ),
+                });
+                on_event(ServerLifecycleEvent::GaveUp {
+                    attempts: MAX_RESTARTS,
+                    error,
+                });
+                return;
+            };
+            on_event(ServerLifecycleEvent::Restarting {
+                attempt: budget.used,
+                max_attempts: MAX_RESTARTS,
+                delay,
+            });
+            tokio::time::sleep(delay).await;
+            match start_oss_server(&config).await {
+                Ok(()) => {
+                    // Replay what the session did when it started.
+                    if let Err(err) = load_model(&config, &config.model).await {
+                        tracing::warn!("reloading {} failed: {err}", config.model);
+                    }
+                    on_event(ServerLifecycleEvent::Restarted {
+                        pid: recorded_pid(&state_dir),
+                    });
+                    break;
+                }
+                Err(err) => {
+                    tracing::warn!("restarting the local server failed: {err}");
+                    last_error = Some(err.to_string());
+                }
+            }
+        }
+    }
+}
+
+/// Pid of a server Codex started that is no longer running. Clears its pid
+/// file so the crash is reported once.
+fn crashed_pid(state_dir: &Path) -> Option<u32> {
+    let pid = recorded_pid(state_dir)?;
+    if process_alive(pid) {
+        return None;
+    }
+    forget_server(state_dir);
+    Some(pid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn restarts_back_off_and_run_out() {
+        let start = Instant::now();
+        let mut budget = RestartBudget::default();
+        let delays: Vec<_> = (0..=MAX_RESTARTS)
+            .map(|_| budget.next_delay(start))
+            .collect();
+        assert_eq!(
+            delays,
+            vec![
+                Some(Duration::from_secs(1)),
+                Some(Duration::from_secs(2)),
+                Some(Duration::from_secs(4)),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn a_server_that_stayed_up_gets_its_restarts_back() {
+        let start = Instant::now();
+        let mut budget = RestartBudget::default();
+        for _ in 0..MAX_RESTARTS {
+            budget.next_delay(start);
+        }
+        assert_eq!(budget.next_delay(start + STABLE_AFTER / 2), None);
+        assert_eq!(
+            budget.next_delay(start + STABLE_AFTER),
+            Some(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn only_a_recorded_server_that_is_gone_counts_as_crashed() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        assert_eq!(crashed_pid(dir.path()), None);
+
+        std::fs::write(
+            dir.path().join("ollama-serve.pid"),
+            std::process::id().to_string(),
+        )
+        .expect("write pid");
+        assert_eq!(crashed_pid(dir.path()), None);
+
+        // No real process will ever have this pid.
+        std::fs::write(dir.path().join("ollama-serve.pid"), u32::MAX.to_string())
+            .expect("write pid");
+        assert_eq!(crashed_pid(dir.path()), Some(u32::MAX));
+        assert_eq!(crashed_pid(dir.path()), None);
+    }
+}
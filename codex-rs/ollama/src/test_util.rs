@@ -0,0 +1,369 @@
+//! Mock local servers for tests of the `oss` integration (feature
+//! `test-util`).
+//!
+//! [`FakeLocalServer`] answers on a [`wiremock::MockServer`] the way Ollama
+//! and LM Studio do, keeping state across requests, so end-to-end tests of
+//! whole `codex exec --oss` runs do not have to copy the JSON of either.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use serde_json::json;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::Request;
+use wiremock::Respond;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+const CHAT_COMPLETIONS: &str = "/v1/chat/completions";
+
+/// Server-sent events of a chat completion by `model` made of `deltas`,
+/// ending with a stop chunk and `[DONE]`.
+fn chat_stream_body(model: &str, deltas: &[&str]) -> String {
+    let chunk = |delta: serde_json::Value, finish_reason: Option<&str>| {
+        json!({
+            "id": "chatcmpl-test",
+            "object": "chat.completion.chunk",
+            "model": model,
+            "choices": [{"index": 0, "delta": delta, "finish_reason": finish_reason}],
+        })
+    };
+    let mut body = String::new();
+    for delta in deltas {
+        let event = chunk(json!({"role": "assistant", "content": delta}), None);
+        body.push_str(&format!("data: {event}\n\n"));
+    }
+    let stop = chunk(json!({}), Some("stop"));
+    body.push_str(&format!("data: {stop}\n\ndata: [DONE]\n\n"));
+    body
+}
+
+/// A local server that remembers which models it has and which are loaded.
+///
+/// It answers LM Studio's `/v1` and `/api/v0` listings and, for loading,
+/// unloading, and downloading, the Ollama `/api` endpoints Codex drives.
+/// Chat completions stream [`FakeLocalServer::with_reply`]'s text and load
+/// the model on first use, as LM Studio's just-in-time loading does.
+pub struct FakeLocalServer {
+    server: MockServer,
+    state: Arc<Mutex<FakeState>>,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    /// Downloaded models, with whether each is loaded.
+    models: Vec<(String, bool)>,
+    /// Models `/api/pull` can download.
+    pullable: Vec<String>,
+    reply: Vec<String>,
+}
+
+impl FakeState {
+    fn has(&self, model: &str) -> bool {
+        self.models.iter().any(|(id, _)| id == model)
+    }
+
+    fn set_loaded(&mut self, model: &str, loaded: bool) {
+        for (id, state) in &mut self.models {
+            if id == model {
+                *state = loaded;
+            }
+        }
+    }
+}
+
+impl FakeLocalServer {
+    /// Start a server that has downloaded `models`, none of them loaded.
+    pub async fn start(models: &[&str]) -> Self {
+        let state = Arc::new(Mutex::new(FakeState {
+            models: models.iter().map(|id| (id.to_string(), false)).collect(),
+            pullable: Vec::new(),
+            reply: vec!["Hello".to_string(), " from the local model.".to_string()],
+        }));
+        let server = MockServer::start().await;
+        for (verb, route, endpoint) in [
+            ("GET", "/v1/models", Endpoint::OpenAiModels),
+            ("GET", "/api/v0/models", Endpoint::LmStudioModels),
+            ("GET", "/api/tags", Endpoint::Tags),
+            ("GET", "/api/ps", Endpoint::Ps),
+            ("GET", "/api/version", Endpoint::Version),
+            ("POST", CHAT_COMPLETIONS, Endpoint::Chat),
+            ("POST", "/api/generate", Endpoint::Generate),
+            ("POST", "/api/pull", Endpoint::Pull),
+        ] {
+            Mock::given(method(verb))
+                .and(path(route))
+                .respond_with(FakeResponder {
+                    endpoint,
+                    state: Arc::clone(&state),
+                })
+                .mount(&server)
+                .await;
+        }
+        Self { server, state }
+    }
+
+    /// Stream `deltas` in reply to every chat completion.
+    pub fn with_reply(self, deltas: &[&str]) -> Self {
+        if let Ok(mut state) = self.state.lock() {
+            state.reply = deltas.iter().map(|delta| delta.to_string()).collect();
+        }
+        self
+    }
+
+    /// Let `/api/pull` download `model`, reporting progress as it goes.
+    pub fn with_pullable(self, model: &str) -> Self {
+        if let Ok(mut state) = self.state.lock() {
+            state.pullable.push(model.to_string());
+        }
+        self
+    }
+
+    /// Root URL of the server, e.g. `http://127.0.0.1:53211`.
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// Base URL of its OpenAI-compatible API, as `--oss-url` takes it.
+    pub fn base_url(&self) -> String {
+        format!("{}/v1", self.server.uri())
+    }
+
+    /// Models currently loaded.
+    pub fn loaded_models(&self) -> Vec<String> {
+        self.state
+            .lock()
+            .map(|state| {
+                state
+                    .models
+                    .iter()
+                    .filter(|(_, loaded)| *loaded)
+                    .map(|(id, _)| id.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Every request the server received, in order.
+    pub async fn received_requests(&self) -> Vec<Request> {
+        self.server.received_requests().await.unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Endpoint {
+    OpenAiModels,
+    LmStudioModels,
+    Tags,
+    Ps,
+    Version,
+    Chat,
+    Generate,
+    Pull,
+}
+
+struct FakeResponder {
+    endpoint: Endpoint,
+    state: Arc<Mutex<FakeState>>,
+}
+
+impl Respond for FakeResponder {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let Ok(mut state) = self.state.lock() else {
+            return ResponseTemplate::new(500);
+        };
+        let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap_or_default();
+        let model = body
+            .get("model")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        match self.endpoint {
+            Endpoint::OpenAiModels => json_response(
+                200,
+                &json!({
+                    "object": "list",
+                    "data": state
+                        .models
+                        .iter()
+                        .map(|(id, _)| json!({"id": id, "object": "model"}))
+                        .collect::<Vec<_>>()
+                }),
+            ),
+            Endpoint::LmStudioModels => json_response(
+                200,
+                &json!({
+                    "object": "list",
+                    "data": state
+                        .models
+                        .iter()
+                        .map(|(id, loaded)| {
+                            json!({
+                                "id": id,
+                                "object": "model",
+                                "type": "llm",
+                                "state": if *loaded { "loaded" } else { "not-loaded" },
+                                "max_context_length": 32768,
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                }),
+            ),
+            Endpoint::Tags => json_response(
+                200,
+                &json!({
+                    "models": state
+                        .models
+                        .iter()
+                        .map(|(id, _)| json!({"name": id}))
+                        .collect::<Vec<_>>()
+                }),
+            ),
+            Endpoint::Ps => json_response(
+                200,
+                &json!({
+                    "models": state
+                        .models
+                        .iter()
+                        .filter(|(_, loaded)| *loaded)
+                        .map(|(id, _)| json!({"name": id}))
+                        .collect::<Vec<_>>()
+                }),
+            ),
+            Endpoint::Version => json_response(200, &json!({"version": "0.0.0-fake"})),
+            Endpoint::Chat if state.has(&model) => {
+                state.set_loaded(&model, true);
+                if body.get("stream").and_then(serde_json::Value::as_bool) == Some(true) {
+                    let deltas: Vec<&str> = state.reply.iter().map(String::as_str).collect();
+                    sse_response(chat_stream_body(&model, &deltas))
+                } else {
+                    json_response(
+                        200,
+                        &json!({
+                            "object": "chat.completion",
+                            "model": model,
+                            "choices": [{
+                                "index": 0,
+                                "message": {"role": "assistant", "content": state.reply.concat()},
+                                "finish_reason": "stop",
+                            }],
+                            "usage": {"completion_tokens": state.reply.len()},
+                        }),
+                    )
+                }
+            }
+            Endpoint::Chat => json_response(
+                404,
+                &json!({"error": {"message": format!("model '{model}' not found")}}),
+            ),
+            Endpoint::Generate if state.has(&model) => {
+                // `keep_alive: 0` is how Ollama is asked to unload a model.
+                let unload = body.get("keep_alive").and_then(serde_json::Value::as_i64) == Some(0);
+                state.set_loaded(&model, !unload);
+                json_response(200, &json!({"model": model, "response": "", "done": true}))
+            }
+            Endpoint::Generate => json_response(
+                404,
+                &json!({"error": format!("model \"{model}\" not found, try pulling it first")}),
+            ),
+            Endpoint::Pull if state.pullable.contains(&model) => {
+                if !state.has(&model) {
+                    state.models.push((model, false));
+                }
+                let total = 4096;
+                let lines = [
+                    json!({"status": "pulling manifest"}),
+                    json!({"status": "downloading", "digest": "sha256:fake", "total": total, "completed": total / 2}),
+                    json!({"status": "downloading", "digest": "sha256:fake", "total": total, "completed": total}),
+                    json!({"status": "verifying sha256 digest"}),
+                    json!({"status": "success"}),
+                ];
+                let body: String = lines.iter().map(|line| format!("{line}\n")).collect();
+                ResponseTemplate::new(200).set_body_raw(body, "application/x-ndjson")
+            }
+            Endpoint::Pull => json_response(
+                200,
+                &json!({"error": "pull model manifest: file does not exist"}),
+            ),
+        }
+    }
+}
+
+fn json_response(status: u16, body: &serde_json::Value) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_raw(body.to_string(), "application/json")
+}
+
+fn sse_response(body: String) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "text/event-stream")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OllamaClient;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn the_fake_server_loads_unloads_and_downloads_models() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping the_fake_server_loads_unloads_and_downloads_models",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = FakeLocalServer::start(&["qwen3-8b"])
+            .await
+            .with_pullable("gpt-oss:20b");
+        let client = OllamaClient::from_host_root(server.uri());
+
+        let answer = client
+            .answer("qwen3-8b", "Hi", Some(8))
+            .await
+            .expect("answer");
+        assert_eq!(answer.text, "Hello from the local model.");
+        assert_eq!(server.loaded_models(), vec!["qwen3-8b".to_string()]);
+        assert_eq!(
+            client
+                .fetch_lmstudio_loaded_models()
+                .await
+                .expect("loaded models"),
+            vec!["qwen3-8b".to_string()]
+        );
+
+        client.unload_model("qwen3-8b").await.expect("unload");
+        assert_eq!(server.loaded_models(), Vec::<String>::new());
+
+        let mut reporter = crate::CliProgressReporter::new();
+        client
+            .pull_with_reporter("gpt-oss:20b", &mut reporter)
+            .await
+            .expect("pull");
+        assert_eq!(
+            client.fetch_models().await.expect("fetch models"),
+            vec!["qwen3-8b".to_string(), "gpt-oss:20b".to_string()]
+        );
+        assert!(
+            client
+                .pull_with_reporter("missing", &mut reporter)
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn chat_streams_end_with_a_stop_chunk_and_done() {
+        let body = chat_stream_body("gpt-oss:20b", &["Hel", "lo"]);
+        let frames: Vec<&str> = body
+            .split("\n\n")
+            .filter(|frame| !frame.is_empty())
+            .collect();
+        assert_eq!(frames.len(), 4);
+        assert!(frames[0].contains(r#""content":"Hel""#));
+        assert!(frames[2].contains(r#""finish_reason":"stop""#));
+        assert_eq!(frames[3], "data: [DONE]");
+    }
+}
@@ -0,0 +1,87 @@
+//! Average-rate limit for model downloads (`oss.download_rate_limit`).
+//!
+//! Ollama downloads a model itself and has no rate limit of its own, so the
+//! client paces it from the outside: when a pull gets ahead of the allowed
+//! rate, the pull is dropped, which stops the download, and restarted once
+//! the average is back under the limit. Ollama resumes from the partial
+//! files it keeps, so no bytes are fetched twice.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+
+/// How far ahead of the allowed rate a download may run before it is paused.
+/// Short bursts are fine; what matters is the average.
+const MAX_AHEAD: Duration = Duration::from_secs(2);
+
+#[derive(Debug)]
+pub(crate) struct DownloadThrottle {
+    bytes_per_second: u64,
+    started: Instant,
+    /// Bytes of each layer present when the layer was first reported, which
+    /// an earlier pull downloaded.
+    resumed_at: HashMap<String, u64>,
+    completed: HashMap<String, u64>,
+}
+
+impl DownloadThrottle {
+    pub(crate) fn new(bytes_per_second: u64, started: Instant) -> Self {
+        Self {
+            bytes_per_second: bytes_per_second.max(1),
+            started,
+            resumed_at: HashMap::new(),
+            completed: HashMap::new(),
+        }
+    }
+
+    /// Record that `completed` bytes of the layer `digest` are downloaded.
+    pub(crate) fn record(&mut self, digest: &str, completed: u64) {
+        self.resumed_at
+            .entry(digest.to_string())
+            .or_insert(completed);
+        self.completed.insert(digest.to_string(), completed);
+    }
+
+    /// How long to pause the download to bring its average rate back under
+    /// the limit, once it ran too far ahead.
+    pub(crate) fn pause_at(&self, now: Instant) -> Option<Duration> {
+        let downloaded: u64 = self
+            .completed
+            .iter()
+            .map(|(digest, completed)| {
+                completed.saturating_sub(self.resumed_at.get(digest).copied().unwrap_or(0))
+            })
+            .sum();
+        let allowed = Duration::from_secs_f64(downloaded as f64 / self.bytes_per_second as f64);
+        let elapsed = now.saturating_duration_since(self.started);
+        (allowed > elapsed + MAX_AHEAD).then(|| allowed - elapsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const MB: u64 = 1_000_000;
+
+    #[test]
+    fn a_download_ahead_of_the_limit_pauses_until_it_is_back_on_average() {
+        let start = Instant::now();
+        let mut throttle = DownloadThrottle::new(MB, start);
+        // 500 MB were downloaded before; only what this pull adds counts.
+        throttle.record("sha256:a", 500 * MB);
+        throttle.record("sha256:a", 501 * MB);
+        assert_eq!(throttle.pause_at(start + Duration::from_secs(1)), None);
+
+        // 10 MB in 1 s at 1 MB/s: 9 s ahead.
+        throttle.record("sha256:a", 505 * MB);
+        throttle.record("sha256:b", 0);
+        throttle.record("sha256:b", 5 * MB);
+        assert_eq!(
+            throttle.pause_at(start + Duration::from_secs(1)),
+            Some(Duration::from_secs(9))
+        );
+        assert_eq!(throttle.pause_at(start + Duration::from_secs(9)), None);
+    }
+}
@@ -0,0 +1,83 @@
+//! Freeing the memory held by models loaded on the local server.
+
+use codex_core::config::Config;
+use codex_core::protocol_config_types::OssBackend;
+use codex_protocol::oss::OssEvent;
+use std::io;
+
+use crate::OllamaClient;
+use crate::command::run_command;
+use crate::events::publish;
+use crate::lms;
+
+/// Unload `model`, or every loaded model when `None`, from the server behind
+/// the `oss` provider. Returns the models that were unloaded.
+///
+/// Ollama is asked through its REST API; LM Studio has no unload endpoint, so
+/// its `lms` CLI is used instead.
+pub async fn unload_models(config: &Config, model: Option<&str>) -> io::Result<Vec<String>> {
+    let client = OllamaClient::from_oss_provider(config)?;
+    let unloaded = match config.oss.provider {
+        Some(OssBackend::LmStudio) => unload_lmstudio(&client, model).await?,
+        Some(OssBackend::Generic) => return Err(generic_unload_unsupported()),
+        Some(OssBackend::Ollama) | None => {
+            client.probe_server().await?;
+            let targets = match model {
+                Some(model) => vec![model.to_string()],
+                None => client.fetch_loaded_model_names().await?,
+            };
+            for target in &targets {
+                client.unload_model(target).await?;
+            }
+            targets
+        }
+    };
+    for model in &unloaded {
+        publish(OssEvent::ModelUnloaded {
+            model: model.clone(),
+        });
+    }
+    Ok(unloaded)
+}
+
+pub(crate) fn generic_unload_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Generic OpenAI-compatible servers cannot unload models; use the server's own tools.",
+    )
+}
+
+pub(crate) async fn unload_lmstudio(
+    client: &OllamaClient,
+    model: Option<&str>,
+) -> io::Result<Vec<String>> {
+    let lms = client.trusted_lms_cli()?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "LM Studio's `lms` CLI was not found. Install it with `npx lmstudio install-cli`.",
+        )
+    })?;
+    let targets = match model {
+        Some(model) => vec![model.to_string()],
+        None => client.fetch_lmstudio_loaded_models().await?,
+    };
+    if targets.is_empty() {
+        return Ok(targets);
+    }
+
+    let args = lms::unload_args(model);
+    client.log(format_args!(
+        "running `{} {}`",
+        lms.display(),
+        args.join(" ")
+    ));
+    let output = run_command(client.audit_log(), &lms, &args).await?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "`lms {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(targets)
+}
@@ -1,11 +1,21 @@
 /// Identify whether a base_url points at an OpenAI-compatible root (".../v1").
+/// Servers on a Unix domain socket or a named pipe are always spoken to
+/// through it.
 pub(crate) fn is_openai_compatible_base_url(base_url: &str) -> bool {
     base_url.trim_end_matches('/').ends_with("/v1")
+        || codex_core::is_local_socket_base_url(base_url)
 }
 
 /// Convert a provider base_url into the native Ollama host root.
 /// For example, "http://localhost:11434/v1" -> "http://localhost:11434".
+/// A server on a Unix domain socket or a named pipe is addressed as
+/// `http://localhost`; the client connects to it whatever the host.
 pub fn base_url_to_host_root(base_url: &str) -> String {
+    if codex_core::is_local_socket_base_url(base_url) {
+        return codex_core::LOCAL_SOCKET_HTTP_BASE_URL
+            .trim_end_matches("/v1")
+            .to_string();
+    }
     let trimmed = base_url.trim_end_matches('/');
     if trimmed.ends_with("/v1") {
         trimmed
@@ -35,5 +45,9 @@ mod tests {
             base_url_to_host_root("http://localhost:11434/"),
             "http://localhost:11434"
         );
+        assert_eq!(
+            base_url_to_host_root("unix:///run/llama.sock"),
+            "http://localhost"
+        );
     }
 }
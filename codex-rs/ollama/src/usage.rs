@@ -0,0 +1,59 @@
+//! Codex's own record of when each local model was last used, kept alongside
+//! the other OSS state (see [`crate::paths`]).
+
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use crate::atomic::write_atomic;
+
+const USAGE_FILE: &str = "model-usage.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct UsageRecords {
+    #[serde(default)]
+    last_used: BTreeMap<String, DateTime<Utc>>,
+}
+
+fn read_records(state_dir: &Path) -> UsageRecords {
+    std::fs::read_to_string(state_dir.join(USAGE_FILE))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Record that `model` was used by a session starting now.
+pub fn record_model_use(state_dir: &Path, model: &str) -> io::Result<()> {
+    let mut records = read_records(state_dir);
+    records.last_used.insert(model.to_string(), Utc::now());
+    let contents = serde_json::to_string_pretty(&records).map_err(io::Error::other)?;
+    write_atomic(&state_dir.join(USAGE_FILE), contents)
+}
+
+/// When `model` was last used by Codex, if ever.
+pub fn model_last_used(state_dir: &Path, model: &str) -> Option<DateTime<Utc>> {
+    read_records(state_dir).last_used.get(model).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn records_round_trip_per_model() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        assert_eq!(model_last_used(dir.path(), "gpt-oss:20b"), None);
+
+        record_model_use(dir.path(), "gpt-oss:20b").expect("record");
+        let first = model_last_used(dir.path(), "gpt-oss:20b").expect("recorded");
+        record_model_use(dir.path(), "mistral").expect("record");
+
+        assert_eq!(model_last_used(dir.path(), "gpt-oss:20b"), Some(first));
+        assert!(model_last_used(dir.path(), "mistral").is_some());
+    }
+}
@@ -0,0 +1,347 @@
+//! Integrity checks of the models Ollama keeps on disk.
+//!
+//! A model is a manifest under `<models>/manifests/<registry>/<namespace>/
+//! <repository>/<tag>` that lists content-addressed blobs stored as
+//! `<models>/blobs/sha256-<hex>`. A download cut short, e.g. by a full disk,
+//! leaves a blob missing, truncated, or only as a `-partial` file.
+
+use codex_protocol::oss::BlobProblem;
+use codex_protocol::oss::BlobProblemKind;
+use codex_protocol::oss::ModelIntegrityReport;
+use serde_json::Value as JsonValue;
+use sha2::Digest;
+use sha2::Sha256;
+use std::io;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::registry::ModelReference;
+
+/// Directory where Ollama stores models: `$OLLAMA_MODELS`, else
+/// `~/.ollama/models`.
+pub fn ollama_models_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("OLLAMA_MODELS") {
+        return Some(PathBuf::from(dir));
+    }
+    dirs::home_dir().map(|home| home.join(".ollama").join("models"))
+}
+
+/// Verify `models` (or every model with a manifest, when empty) against the
+/// blobs in `models_dir`. Hashes every blob, so this reads the whole model
+/// from disk.
+pub fn verify_models(
+    models_dir: &Path,
+    models: &[String],
+) -> io::Result<Vec<ModelIntegrityReport>> {
+    let references = if models.is_empty() {
+        manifest_references(models_dir)?
+    } else {
+        models
+            .iter()
+            .map(|model| {
+                ModelReference::parse(model).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("'{model}' is not a valid model name"),
+                    )
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?
+    };
+    let mut reports = references
+        .iter()
+        .map(|reference| verify_model(models_dir, reference))
+        .collect::<io::Result<Vec<_>>>()?;
+    reports.sort_by(|a, b| a.model.cmp(&b.model));
+    Ok(reports)
+}
+
+/// Delete the damaged blobs of `report`, including leftover partial
+/// downloads, so that pulling the model again fetches them.
+pub fn remove_damaged_blobs(models_dir: &Path, report: &ModelIntegrityReport) -> io::Result<()> {
+    for problem in &report.problems {
+        if problem.kind == BlobProblemKind::InvalidManifest {
+            continue;
+        }
+        match std::fs::remove_file(&problem.path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+        for partial in partial_downloads(models_dir, &problem.digest) {
+            std::fs::remove_file(partial)?;
+        }
+    }
+    Ok(())
+}
+
+fn verify_model(models_dir: &Path, reference: &ModelReference) -> io::Result<ModelIntegrityReport> {
+    let model = reference.name();
+    let manifest_path = reference.manifest_path(models_dir);
+    let manifest = match std::fs::read(&manifest_path) {
+        Ok(manifest) => manifest,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no local model named '{model}'"),
+            ));
+        }
+        Err(err) => return Err(err),
+    };
+    let mut report = ModelIntegrityReport {
+        model,
+        manifest_path: manifest_path.display().to_string(),
+        problems: Vec::new(),
+    };
+    let Some(blobs) = serde_json::from_slice::<JsonValue>(&manifest)
+        .ok()
+        .and_then(|manifest| manifest_blobs(&manifest))
+    else {
+        report.problems.push(BlobProblem {
+            digest: String::new(),
+            path: report.manifest_path.clone(),
+            kind: BlobProblemKind::InvalidManifest,
+            expected_size: None,
+            actual_size: Some(manifest.len() as u64),
+        });
+        return Ok(report);
+    };
+    for (digest, expected_size) in blobs {
+        if let Some(problem) = check_blob(models_dir, &digest, expected_size)? {
+            report.problems.push(problem);
+        }
+    }
+    Ok(report)
+}
+
+/// Digests and sizes of the config and layers listed in a manifest.
+fn manifest_blobs(manifest: &JsonValue) -> Option<Vec<(String, Option<u64>)>> {
+    let layers = manifest.get("layers")?.as_array()?;
+    let blob = |value: &JsonValue| {
+        let digest = value.get("digest")?.as_str()?.to_string();
+        Some((digest, value.get("size").and_then(JsonValue::as_u64)))
+    };
+    manifest
+        .get("config")
+        .into_iter()
+        .chain(layers)
+        .map(blob)
+        .collect()
+}
+
+fn check_blob(
+    models_dir: &Path,
+    digest: &str,
+    expected_size: Option<u64>,
+) -> io::Result<Option<BlobProblem>> {
+    let path = blob_path(models_dir, digest);
+    let problem = |kind, actual_size| BlobProblem {
+        digest: digest.to_string(),
+        path: path.display().to_string(),
+        kind,
+        expected_size,
+        actual_size,
+    };
+    let actual_size = match std::fs::metadata(&path) {
+        Ok(metadata) => metadata.len(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            let kind = if partial_downloads(models_dir, digest).is_empty() {
+                BlobProblemKind::Missing
+            } else {
+                BlobProblemKind::Incomplete
+            };
+            return Ok(Some(problem(kind, None)));
+        }
+        Err(err) => return Err(err),
+    };
+    if let Some(expected) = expected_size
+        && actual_size != expected
+    {
+        let kind = if actual_size < expected {
+            BlobProblemKind::Incomplete
+        } else {
+            BlobProblemKind::ChecksumMismatch
+        };
+        return Ok(Some(problem(kind, Some(actual_size))));
+    }
+    let expected_hex = digest.trim_start_matches("sha256:").to_ascii_lowercase();
+    if sha256_file(&path)? != expected_hex {
+        return Ok(Some(problem(
+            BlobProblemKind::ChecksumMismatch,
+            Some(actual_size),
+        )));
+    }
+    Ok(None)
+}
+
+/// `sha256:<hex>` is stored as `blobs/sha256-<hex>`.
+fn blob_path(models_dir: &Path, digest: &str) -> PathBuf {
+    models_dir.join("blobs").join(digest.replace(':', "-"))
+}
+
+/// Files Ollama writes while a blob is still downloading:
+/// `sha256-<hex>-partial` and its numbered chunks.
+fn partial_downloads(models_dir: &Path, digest: &str) -> Vec<PathBuf> {
+    let prefix = format!("{}-partial", digest.replace(':', "-"));
+    let Ok(entries) = std::fs::read_dir(models_dir.join("blobs")) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&prefix))
+        })
+        .collect()
+}
+
+/// Every model with a manifest under `models_dir`.
+fn manifest_references(models_dir: &Path) -> io::Result<Vec<ModelReference>> {
+    let root = models_dir.join("manifests");
+    let mut references = Vec::new();
+    for registry in subdirs(&root)? {
+        for namespace in subdirs(&registry)? {
+            for repository in subdirs(&namespace)? {
+                for tag in std::fs::read_dir(&repository)?.flatten() {
+                    if !tag.path().is_file() {
+                        continue;
+                    }
+                    let name = |path: &Path| {
+                        path.file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                            .unwrap_or_default()
+                    };
+                    references.push(ModelReference::from_manifest_parts(
+                        &name(&registry),
+                        &name(&namespace),
+                        &name(&repository),
+                        &name(&tag.path()),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(references)
+}
+
+fn subdirs(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    match std::fs::read_dir(dir) {
+        Ok(entries) => Ok(entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+pub(crate) fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Write a blob with `contents` and return its digest and size.
+    fn write_blob(models_dir: &Path, contents: &[u8]) -> (String, u64) {
+        let digest = format!("sha256:{:x}", Sha256::digest(contents));
+        std::fs::create_dir_all(models_dir.join("blobs")).expect("blobs dir");
+        std::fs::write(blob_path(models_dir, &digest), contents).expect("write blob");
+        (digest, contents.len() as u64)
+    }
+
+    fn write_manifest(models_dir: &Path, name: &str, blobs: &[(String, u64)]) {
+        let reference = ModelReference::parse(name).expect("reference");
+        let path = reference.manifest_path(models_dir);
+        std::fs::create_dir_all(path.parent().expect("parent")).expect("manifest dir");
+        let layer =
+            |(digest, size): &(String, u64)| serde_json::json!({"digest": digest, "size": size});
+        let manifest = serde_json::json!({
+            "schemaVersion": 2,
+            "config": layer(&blobs[0]),
+            "layers": blobs[1..].iter().map(layer).collect::<Vec<_>>(),
+        });
+        std::fs::write(path, manifest.to_string()).expect("write manifest");
+    }
+
+    fn problem_kinds(report: &ModelIntegrityReport) -> Vec<BlobProblemKind> {
+        report.problems.iter().map(|p| p.kind).collect()
+    }
+
+    #[test]
+    fn test_verify_models_reports_damaged_blobs() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let models_dir = dir.path();
+
+        let config = write_blob(models_dir, b"{}");
+        let weights = write_blob(models_dir, b"weights");
+        write_manifest(models_dir, "gpt-oss:20b", &[config.clone(), weights]);
+
+        // Truncated by a full disk.
+        let truncated = write_blob(models_dir, b"truncated weights");
+        std::fs::write(blob_path(models_dir, &truncated.0), b"trunc").expect("truncate");
+        // Corrupted in place.
+        let corrupted = write_blob(models_dir, b"good bytes");
+        std::fs::write(blob_path(models_dir, &corrupted.0), b"bad bytes!").expect("corrupt");
+        // Interrupted before the blob was renamed into place.
+        let partial = write_blob(models_dir, b"partial weights");
+        let partial_path = blob_path(models_dir, &partial.0);
+        std::fs::rename(&partial_path, format!("{}-partial", partial_path.display()))
+            .expect("rename");
+        write_manifest(
+            models_dir,
+            "mistral",
+            &[config, truncated, corrupted, partial],
+        );
+
+        let reports = verify_models(models_dir, &[]).expect("verify");
+        let summary: Vec<(&str, Vec<BlobProblemKind>)> = reports
+            .iter()
+            .map(|report| (report.model.as_str(), problem_kinds(report)))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("gpt-oss:20b", vec![]),
+                (
+                    "mistral:latest",
+                    vec![
+                        BlobProblemKind::Incomplete,
+                        BlobProblemKind::ChecksumMismatch,
+                        BlobProblemKind::Incomplete,
+                    ]
+                ),
+            ]
+        );
+
+        remove_damaged_blobs(models_dir, &reports[1]).expect("remove");
+        let reports = verify_models(models_dir, &["mistral".to_string()]).expect("verify");
+        assert_eq!(
+            problem_kinds(&reports[0]),
+            vec![BlobProblemKind::Missing; 3]
+        );
+    }
+
+    #[test]
+    fn test_verify_unknown_model_is_not_found() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let err = verify_models(dir.path(), &["nope:1b".to_string()]).expect_err("missing");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -5,7 +5,9 @@ use codex_protocol::ConversationId;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::oss::PerformanceSample;
 use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::RequestTimingEvent;
 use codex_protocol::protocol::ReviewDecision;
 use codex_protocol::protocol::SandboxPolicy;
 use codex_protocol::user_input::UserInput;
@@ -28,6 +30,23 @@ pub enum ToolDecisionSource {
     User,
 }
 
+/// Why a request to a local `--oss` server failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum LocalInferenceError {
+    /// The server could not be reached.
+    Connection,
+    /// The server answered with an error status.
+    Status,
+    /// The response stream broke off.
+    Stream,
+    /// The server stopped sending tokens.
+    IdleTimeout,
+    /// The server kept the stream open but sent no new tokens.
+    Stalled,
+}
+
 #[derive(Debug, Clone)]
 pub struct OtelEventMetadata {
     conversation_id: ConversationId,
@@ -464,6 +483,106 @@ impl OtelEventManager {
             output = %output,
         );
     }
+
+    /// A request to the local `--oss` server on `backend` finished.
+    pub fn local_inference(&self, backend: &str, timing: &RequestTimingEvent) {
+        tracing::event!(
+            tracing::Level::INFO,
+            event.name = "codex.local_inference",
+            event.timestamp = %timestamp(),
+            conversation.id = %self.metadata.conversation_id,
+            app.version = %self.metadata.app_version,
+            terminal.type = %self.metadata.terminal_type,
+            model = %self.metadata.model,
+            slug = %self.metadata.slug,
+            backend = %backend,
+            success = %"true",
+            connection_ms = timing.connection_ms,
+            tls = timing.tls,
+            request_write_ms = timing.request_write_ms,
+            connect_ms = timing.connect_ms,
+            prefill_ms = timing.prefill_ms,
+            ttfb_ms = timing.time_to_first_token_ms,
+            generation_ms = timing.generation_ms,
+            output_tokens = timing.output_tokens,
+            tokens_per_second = timing.tokens_per_second(),
+            total_ms = timing.total_ms,
+        );
+    }
+
+    /// A request to the local `--oss` server on `backend` failed.
+    pub fn local_inference_failed<T>(
+        &self,
+        backend: &str,
+        error_class: LocalInferenceError,
+        status_code: Option<u16>,
+        error: &T,
+    ) where
+        T: Display,
+    {
+        tracing::event!(
+            tracing::Level::INFO,
+            event.name = "codex.local_inference",
+            event.timestamp = %timestamp(),
+            conversation.id = %self.metadata.conversation_id,
+            app.version = %self.metadata.app_version,
+            terminal.type = %self.metadata.terminal_type,
+            model = %self.metadata.model,
+            slug = %self.metadata.slug,
+            backend = %backend,
+            success = %"false",
+            error.class = %error_class,
+            http.response.status_code = status_code,
+            error.message = %error,
+        );
+    }
+}
+
+/// A model download from `backend` finished or failed after `bytes` were
+/// transferred. Downloads happen outside of conversations, so only the app
+/// metadata is attached.
+pub fn local_model_download(
+    backend: &str,
+    model: &str,
+    bytes: u64,
+    duration: Duration,
+    error: Option<&str>,
+) {
+    tracing::event!(
+        tracing::Level::INFO,
+        event.name = "codex.local_model_download",
+        event.timestamp = %timestamp(),
+        app.version = %env!("CARGO_PKG_VERSION"),
+        backend = %backend,
+        model = %model,
+        bytes = bytes,
+        duration_ms = %duration.as_millis(),
+        success = %error.is_none(),
+        error.message = error,
+    );
+}
+
+/// An opted-in performance sample of the local model. Only the sample and
+/// the app version are attached, never conversation or user metadata.
+pub fn local_performance(sample: &PerformanceSample) {
+    tracing::event!(
+        tracing::Level::INFO,
+        event.name = "codex.local_performance",
+        event.timestamp = %timestamp(),
+        app.version = %sample.codex_version,
+        backend = %sample.backend,
+        model = %sample.model,
+        quantization = sample.quantization.as_deref(),
+        parameter_size = sample.parameter_size.as_deref(),
+        os = %sample.hardware.os,
+        arch = %sample.hardware.arch,
+        cpu_cores = sample.hardware.cpu_cores,
+        memory_class = sample.hardware.memory_class.as_deref(),
+        ttfb_ms = sample.time_to_first_token_ms,
+        prefill_ms = sample.prefill_ms,
+        output_tokens = sample.output_tokens,
+        tokens_per_second = sample.tokens_per_second,
+    );
 }
 
 fn timestamp() -> String {
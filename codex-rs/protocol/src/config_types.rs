@@ -85,3 +85,29 @@ pub enum ForcedLoginMethod {
     Chatgpt,
     Api,
 }
+
+/// Local server that backs the built-in open source ("oss") provider.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Serialize, Display, JsonSchema, TS)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum OssBackend {
+    /// Ollama's native API: models are listed and pulled on demand.
+    Ollama,
+    /// LM Studio's OpenAI-compatible server (default port 1234).
+    LmStudio,
+    /// Any other OpenAI-compatible server; models must already be available.
+    Generic,
+}
+
+/// Sampling parameters sent with each request to the local `oss` server.
+/// Unset parameters are left to the server's defaults.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default, Serialize, JsonSchema, TS)]
+pub struct OssSampling {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    /// Most tokens generated per response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+}
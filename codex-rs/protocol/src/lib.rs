@@ -6,6 +6,7 @@ pub mod items;
 pub mod message_history;
 pub mod models;
 pub mod num_format;
+pub mod oss;
 pub mod parse_command;
 pub mod plan_tool;
 pub mod protocol;
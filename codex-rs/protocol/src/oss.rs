@@ -0,0 +1,719 @@
+//! Machine-readable output of the `codex models` and `codex oss` commands.
+//!
+//! These types are what `--json` emits, what the app server sends, and what
+//! `codex_ollama` returns, so external tooling can rely on their shape. Add
+//! fields rather than renaming or removing existing ones; the tests below pin
+//! the wire format of the most used ones.
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use ts_rs::TS;
+
+use crate::config_types::OssBackend;
+
+/// A model available on the local server (`codex models list`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct LocalModel {
+    /// Model name as understood by the server, e.g. "gpt-oss:20b".
+    pub name: String,
+    /// Size of the model weights on disk, in bytes.
+    pub size_bytes: Option<u64>,
+    /// Quantization level reported by the server, e.g. "Q4_K_M".
+    pub quantization: Option<String>,
+    /// Parameter count reported by the server, e.g. "20.9B".
+    pub parameter_size: Option<String>,
+    /// Maximum context length supported by the model, in tokens.
+    pub context_length: Option<u64>,
+    /// Whether the model is currently loaded into memory.
+    pub loaded: bool,
+}
+
+/// Full metadata for a single local model (`codex models show`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ModelDetails {
+    pub name: String,
+    /// Model architecture, e.g. "gptoss" or "llama".
+    pub architecture: Option<String>,
+    pub quantization: Option<String>,
+    pub parameter_size: Option<String>,
+    /// Path of the weights file on disk.
+    pub file_path: Option<String>,
+    pub size_bytes: Option<u64>,
+    pub context_length: Option<u64>,
+    /// Capabilities reported by the server, e.g. "completion", "tools".
+    pub capabilities: Vec<String>,
+    pub loaded: bool,
+    /// When Codex last started a session with this model, as RFC 3339.
+    pub last_used: Option<String>,
+}
+
+/// A downloadable model found by `codex models search`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct CatalogModel {
+    /// Name to pass to `codex models pull`, e.g. "hf.co/unsloth/gpt-oss-20b-GGUF".
+    pub name: String,
+    /// Repository the model is published in, e.g. "unsloth/gpt-oss-20b-GGUF".
+    pub repo: String,
+    pub downloads: Option<u64>,
+    /// When the repository was last updated, as RFC 3339.
+    pub last_modified: Option<String>,
+    /// License declared on the model card, if any.
+    pub license: Option<ModelLicense>,
+    /// Quantized variants available for download.
+    pub variants: Vec<CatalogVariant>,
+}
+
+/// License a catalog model is published under. Downloading it requires the
+/// license to be accepted, once, in `oss.accepted_licenses`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ModelLicense {
+    /// Identifier from the model card, e.g. "apache-2.0", or "other" for
+    /// custom terms.
+    pub id: String,
+    /// Name of custom terms, e.g. "llama3.1" or "gemma".
+    pub name: Option<String>,
+    /// Where the terms can be read.
+    pub url: Option<String>,
+}
+
+impl ModelLicense {
+    /// What `oss.accepted_licenses` lists to accept this license: the name
+    /// of custom terms, the identifier otherwise.
+    pub fn key(&self) -> &str {
+        match &self.name {
+            Some(name) if self.id == "other" => name,
+            _ => &self.id,
+        }
+    }
+}
+
+/// One quantized variant of a [`CatalogModel`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct CatalogVariant {
+    /// Quantization level, e.g. "Q4_K_M"; pass it to `codex models pull --quant`.
+    pub quant: String,
+    /// File holding the weights within the repository.
+    pub file: String,
+    pub size_bytes: Option<u64>,
+}
+
+/// Update check for one local model (`codex models upgrade --check`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ModelUpdateStatus {
+    pub model: String,
+    /// Manifest digest of the local copy.
+    pub local_digest: Option<String>,
+    /// Manifest digest the registry currently serves.
+    pub remote_digest: Option<String>,
+    /// True when the registry serves a newer revision than the local copy.
+    pub update_available: bool,
+    /// Why the registry could not be checked, e.g. for a model created locally.
+    pub error: Option<String>,
+}
+
+/// Integrity check of one downloaded model (`codex models verify`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ModelIntegrityReport {
+    pub model: String,
+    /// Manifest listing the blobs the model is made of.
+    pub manifest_path: String,
+    /// Blobs that are missing or do not match the manifest; empty when the
+    /// model is intact.
+    pub problems: Vec<BlobProblem>,
+}
+
+/// A blob of a local model that failed verification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct BlobProblem {
+    /// Digest the manifest expects, e.g. "sha256:...".
+    pub digest: String,
+    pub path: String,
+    pub kind: BlobProblemKind,
+    pub expected_size: Option<u64>,
+    /// Size on disk, when the blob exists.
+    pub actual_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum BlobProblemKind {
+    /// The blob is not on disk at all.
+    Missing,
+    /// The blob is shorter than expected or only a partial download exists.
+    Incomplete,
+    /// The blob's contents do not hash to the expected digest.
+    ChecksumMismatch,
+    /// The manifest itself could not be read or parsed.
+    InvalidManifest,
+}
+
+/// One line of the newline-delimited progress stream written while a model
+/// downloads with `--json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ModelDownloadProgress {
+    pub model: String,
+    pub phase: DownloadPhase,
+    /// Status text reported by the server, or the error message when
+    /// `phase` is `failed`.
+    pub status: Option<String>,
+    /// Bytes downloaded so far, summed over all layers.
+    pub completed_bytes: Option<u64>,
+    pub total_bytes: Option<u64>,
+    /// Download speed since the previous event.
+    pub bytes_per_second: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadPhase {
+    /// Resolving the model's manifest.
+    Manifest,
+    Downloading,
+    /// Checking the downloaded layers against their digests.
+    Verifying,
+    /// Writing the manifest and cleaning up unused layers.
+    Writing,
+    Done,
+    Failed,
+}
+
+/// A pending download of `codex_ollama`'s download manager.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct DownloadStatus {
+    /// Identifies the download to pause, resume, or cancel.
+    pub id: u64,
+    pub model: String,
+    /// Root URL of the server the model downloads to.
+    pub host_root: String,
+    pub state: DownloadState,
+    /// The latest progress; `None` until the server reports any.
+    pub progress: Option<ModelDownloadProgress>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadState {
+    /// Waiting for the downloads started before it.
+    Queued,
+    Running,
+    /// Stopped until resumed.
+    Paused,
+}
+
+/// Something that happened to the local server or its models, published by
+/// `codex_ollama` for frontends to show however they like.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OssEvent {
+    /// A server answered at `base_url`.
+    ServerDetected {
+        backend: OssBackend,
+        base_url: String,
+    },
+    /// Codex started the server and it answers.
+    ServerStarted {
+        backend: OssBackend,
+        base_url: String,
+    },
+    DownloadStarted {
+        model: String,
+    },
+    DownloadProgress(ModelDownloadProgress),
+    /// The download ended; `error` says why when it failed.
+    DownloadFinished {
+        model: String,
+        error: Option<String>,
+    },
+    ModelLoaded {
+        model: String,
+    },
+    ModelUnloaded {
+        model: String,
+    },
+    /// The server went away or stopped answering.
+    BackendUnhealthy {
+        backend: OssBackend,
+        message: String,
+    },
+}
+
+/// Result of `codex models pull`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ModelPullOutcome {
+    pub model: String,
+    /// True when the model was already present and nothing was downloaded.
+    pub already_present: bool,
+}
+
+/// Result of `codex models rm`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ModelRemoveOutcome {
+    pub model: String,
+    /// False when no model with that name existed.
+    pub removed: bool,
+    /// Disk space freed, in bytes.
+    pub size_bytes: Option<u64>,
+}
+
+/// Result of `codex oss unload`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ModelUnloadOutcome {
+    /// Models evicted from memory; empty when nothing was loaded.
+    pub unloaded: Vec<String>,
+}
+
+/// Outcome of a single readiness check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+    Skipped,
+}
+
+/// One step of the OSS readiness pipeline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ReadinessCheck {
+    /// Short identifier for the check, e.g. "server".
+    pub name: String,
+    pub status: CheckStatus,
+    /// Human-readable description of what was found.
+    pub detail: String,
+    /// What the user can do to fix a failing or degraded check.
+    pub remediation: Option<String>,
+}
+
+/// Report produced by `codex oss doctor`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct OssReadiness {
+    /// Root URL of the server that was probed.
+    pub host_root: String,
+    /// Model the session would use.
+    pub model: String,
+    pub checks: Vec<ReadinessCheck>,
+}
+
+impl OssReadiness {
+    /// True when no check failed outright.
+    pub fn is_ready(&self) -> bool {
+        !self
+            .checks
+            .iter()
+            .any(|check| check.status == CheckStatus::Fail)
+    }
+
+    /// Remediation steps for every check that did not pass, in pipeline order.
+    pub fn remediation_steps(&self) -> Vec<&str> {
+        self.checks
+            .iter()
+            .filter(|check| matches!(check.status, CheckStatus::Warn | CheckStatus::Fail))
+            .filter_map(|check| check.remediation.as_deref())
+            .collect()
+    }
+}
+
+/// One context size measured by `codex oss bench`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct BenchRun {
+    /// Requested prompt size, in approximate tokens.
+    pub context_size: u32,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub prefill_tokens_per_sec: Option<f64>,
+    pub generation_tokens_per_sec: Option<f64>,
+    pub time_to_first_token_ms: Option<u64>,
+}
+
+/// Result of `codex oss warmup`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct WarmupReport {
+    pub model: String,
+    /// Time the server spent loading the model; zero when it was already loaded.
+    pub load_ms: u64,
+    /// Time from sending the request until the first generated token.
+    pub time_to_first_token_ms: Option<u64>,
+    /// Wall-clock time for the whole warm-up request.
+    pub total_ms: u64,
+}
+
+/// Result of `codex oss serve` actions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ServerStatus {
+    /// Root URL of the server, e.g. "http://localhost:11434".
+    pub host_root: String,
+    /// Whether the server answered.
+    pub running: bool,
+    /// Version reported by the server.
+    pub version: Option<String>,
+    /// Pid of the server when it was started by Codex.
+    pub managed_pid: Option<u32>,
+}
+
+/// What was found for one local backend (`codex oss setup`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct DetectedBackend {
+    pub backend: OssBackend,
+    /// Executable or application bundle of the backend, when installed.
+    pub install_path: Option<PathBuf>,
+    /// Whether a server answered on the backend's default port.
+    pub running: bool,
+    /// Root URL that was probed, e.g. "http://localhost:11434".
+    pub host_root: String,
+}
+
+impl DetectedBackend {
+    pub fn is_available(&self) -> bool {
+        self.running || self.install_path.is_some()
+    }
+}
+
+/// A model currently loaded into memory on the local server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct LoadedModel {
+    pub name: String,
+    /// Total memory used by the loaded model, in bytes.
+    pub size_bytes: Option<u64>,
+    /// Portion of `size_bytes` resident in GPU memory.
+    pub vram_bytes: Option<u64>,
+    /// Context window the model was loaded with, in tokens.
+    pub context_length: Option<u64>,
+    /// When the server will unload the model if it stays idle, as RFC 3339.
+    pub expires_at: Option<String>,
+}
+
+/// Runtime status reported by `codex oss status`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct OssRuntimeStatus {
+    pub server: ServerStatus,
+    /// Seconds since the server started, when known.
+    pub uptime_secs: Option<u64>,
+    /// Requests currently being processed, when the backend reports it.
+    pub active_requests: Option<u64>,
+    pub loaded_models: Vec<LoadedModel>,
+}
+
+/// Version of the [`OssEnvironmentManifest`] format written by `codex oss export`.
+pub const OSS_ENVIRONMENT_MANIFEST_VERSION: u32 = 1;
+
+/// Portable description of a local provider setup, written by `codex oss
+/// export` and replayed on another machine by `codex oss import`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct OssEnvironmentManifest {
+    /// Format version, see [`OSS_ENVIRONMENT_MANIFEST_VERSION`].
+    pub version: u32,
+    /// Backend the models were exported from, when configured.
+    pub provider: Option<OssBackend>,
+    /// Model `codex --oss` uses by default.
+    pub default_model: String,
+    pub models: Vec<ExportedModel>,
+}
+
+/// One model of an [`OssEnvironmentManifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ExportedModel {
+    pub name: String,
+    /// Manifest digest of the exported revision, when the backend reports one.
+    pub digest: Option<String>,
+    /// Sampling parameters baked into the model, e.g. "temperature" or
+    /// "stop". A parameter may be set more than once.
+    #[serde(default)]
+    pub parameters: BTreeMap<String, Vec<String>>,
+}
+
+/// What `codex oss import` did with each model of a manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct OssImportOutcome {
+    /// Models that were already present.
+    pub present: Vec<String>,
+    /// Models that were downloaded.
+    pub pulled: Vec<String>,
+    /// Models that are missing and could not be downloaded on this backend.
+    pub unavailable: Vec<String>,
+    /// Models whose local revision or sampling parameters differ from the
+    /// manifest.
+    pub mismatched: Vec<String>,
+}
+
+/// Skill of a coding agent exercised by a `codex oss eval` task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum EvalCategory {
+    /// Calling a tool with well-formed arguments.
+    ToolCall,
+    /// Writing a patch in the `apply_patch` format.
+    Patch,
+    /// Writing a unified diff.
+    Diff,
+}
+
+/// One task scored by `codex oss eval`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct EvalTaskResult {
+    /// Short identifier for the task, e.g. "patch_rename_function".
+    pub id: String,
+    pub category: EvalCategory,
+    pub passed: bool,
+    /// Why the task failed.
+    pub failure: Option<String>,
+    /// Wall-clock time for the model's answer.
+    pub elapsed_ms: u64,
+}
+
+/// Report produced by `codex oss eval`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct EvalReport {
+    pub model: String,
+    pub tasks: Vec<EvalTaskResult>,
+}
+
+impl EvalReport {
+    /// Share of the tasks that passed, in percent.
+    pub fn score_percent(&self) -> u32 {
+        let passed = self.tasks.iter().filter(|task| task.passed).count();
+        match self.tasks.len() {
+            0 => 0,
+            total => (passed * 100 / total) as u32,
+        }
+    }
+}
+
+/// Report produced by `codex oss conformance`: which parts of the
+/// OpenAI-compatible API that Codex relies on a server implements.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ConformanceReport {
+    pub base_url: String,
+    /// Model the chat checks ran against; `None` when none could be found.
+    pub model: Option<String>,
+    pub checks: Vec<ReadinessCheck>,
+}
+
+impl ConformanceReport {
+    /// True when no check failed outright; warnings mark features Codex can
+    /// do without.
+    pub fn passed(&self) -> bool {
+        !self
+            .checks
+            .iter()
+            .any(|check| check.status == CheckStatus::Fail)
+    }
+
+    /// Remediation steps for every check that did not pass.
+    pub fn remediation_steps(&self) -> Vec<&str> {
+        self.checks
+            .iter()
+            .filter(|check| matches!(check.status, CheckStatus::Warn | CheckStatus::Fail))
+            .filter_map(|check| check.remediation.as_deref())
+            .collect()
+    }
+}
+
+/// Coarse description of the machine a [`PerformanceSample`] was taken on,
+/// precise enough to group results but not to identify the machine.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct HardwareClass {
+    /// Operating system, e.g. "macos" or "linux".
+    pub os: String,
+    /// CPU architecture, e.g. "aarch64" or "x86_64".
+    pub arch: String,
+    /// Logical CPU cores.
+    pub cpu_cores: Option<u32>,
+    /// Range of the machine's physical memory, e.g. "16-32 GiB".
+    pub memory_class: Option<String>,
+}
+
+/// Anonymous throughput of one request to the local model, recorded when
+/// `oss.performance_telemetry` is on. Never contains prompts or output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct PerformanceSample {
+    pub codex_version: String,
+    pub backend: OssBackend,
+    pub model: String,
+    pub quantization: Option<String>,
+    pub parameter_size: Option<String>,
+    pub hardware: HardwareClass,
+    pub time_to_first_token_ms: Option<u64>,
+    pub prefill_ms: Option<u64>,
+    pub output_tokens: u64,
+    pub tokens_per_second: f64,
+}
+
+/// Where the power figure of a [`SessionEnergy`] came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSource {
+    /// Read from the GPU while the server generated.
+    Measured,
+    /// `oss.power_watts`, as the GPU's draw could not be read.
+    Configured,
+}
+
+/// Estimated energy use of one session with the local model, and what its
+/// tokens would have cost from a cloud model, recorded when it ends.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct SessionEnergy {
+    pub model: String,
+    /// When the session ended, in RFC 3339.
+    pub ended_at: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Time the server spent answering the session's requests.
+    pub busy_ms: u64,
+    /// Generation speed over all of the session's requests.
+    pub tokens_per_second: Option<f64>,
+    /// Power drawn while the server was busy; `None` when it could not be
+    /// measured and `oss.power_watts` is unset.
+    pub average_watts: Option<f64>,
+    pub power_source: Option<PowerSource>,
+    /// Estimated energy in watt-hours, when `average_watts` is known.
+    pub energy_wh: Option<f64>,
+    /// What the session's tokens cost at `oss.cloud_input_price` and
+    /// `oss.cloud_output_price`, in US dollars.
+    pub cloud_cost_usd: f64,
+}
+
+/// Totals of the recorded [`SessionEnergy`]s, shown by `codex oss status`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct EnergyTotals {
+    pub sessions: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Energy of the sessions with an estimate, in watt-hours.
+    pub energy_wh: f64,
+    /// Sessions whose energy could not be estimated.
+    pub sessions_without_energy: u64,
+    pub cloud_cost_usd: f64,
+    pub last_session: Option<SessionEnergy>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(status: CheckStatus, remediation: Option<&str>) -> ReadinessCheck {
+        ReadinessCheck {
+            name: "x".to_string(),
+            status,
+            detail: "detail".to_string(),
+            remediation: remediation.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn readiness_fails_only_on_failed_checks() {
+        let mut report = OssReadiness {
+            host_root: "http://localhost:11434".to_string(),
+            model: "gpt-oss:20b".to_string(),
+            checks: vec![
+                check(CheckStatus::Ok, None),
+                check(CheckStatus::Warn, Some("warned")),
+                check(CheckStatus::Skipped, Some("skipped")),
+            ],
+        };
+        assert!(report.is_ready());
+        assert_eq!(report.remediation_steps(), vec!["warned"]);
+
+        report.checks.push(check(CheckStatus::Fail, Some("failed")));
+        assert!(!report.is_ready());
+        assert_eq!(report.remediation_steps(), vec!["warned", "failed"]);
+    }
+
+    #[test]
+    fn check_status_serializes_lowercase() {
+        assert_eq!(
+            serde_json::to_value(check(CheckStatus::Skipped, None)).expect("serialize"),
+            serde_json::json!({
+                "name": "x",
+                "status": "skipped",
+                "detail": "detail",
+                "remediation": null
+            })
+        );
+    }
+
+    #[test]
+    fn wire_format_is_stable() {
+        let model = LocalModel {
+            name: "gpt-oss:20b".to_string(),
+            size_bytes: Some(13_000_000_000),
+            quantization: Some("MXFP4".to_string()),
+            parameter_size: Some("20.9B".to_string()),
+            context_length: Some(131_072),
+            loaded: true,
+        };
+        assert_eq!(
+            serde_json::to_value(&model).expect("serialize"),
+            serde_json::json!({
+                "name": "gpt-oss:20b",
+                "size_bytes": 13_000_000_000u64,
+                "quantization": "MXFP4",
+                "parameter_size": "20.9B",
+                "context_length": 131_072,
+                "loaded": true,
+            })
+        );
+
+        let progress = OssEvent::DownloadProgress(ModelDownloadProgress {
+            model: "gpt-oss:20b".to_string(),
+            phase: DownloadPhase::Verifying,
+            status: Some("verifying sha256 digest".to_string()),
+            completed_bytes: Some(10),
+            total_bytes: Some(10),
+            bytes_per_second: None,
+        });
+        assert_eq!(
+            serde_json::to_value(&progress).expect("serialize"),
+            serde_json::json!({
+                "type": "download_progress",
+                "model": "gpt-oss:20b",
+                "phase": "verifying",
+                "status": "verifying sha256 digest",
+                "completed_bytes": 10,
+                "total_bytes": 10,
+                "bytes_per_second": null,
+            })
+        );
+
+        let detected = DetectedBackend {
+            backend: OssBackend::LmStudio,
+            install_path: None,
+            running: true,
+            host_root: "http://localhost:1234".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&detected).expect("serialize"),
+            serde_json::json!({
+                "backend": "lmstudio",
+                "install_path": null,
+                "running": true,
+                "host_root": "http://localhost:1234",
+            })
+        );
+    }
+
+    #[test]
+    fn events_round_trip() {
+        let events = vec![
+            OssEvent::ServerStarted {
+                backend: OssBackend::Ollama,
+                base_url: "http://localhost:11434".to_string(),
+            },
+            OssEvent::DownloadFinished {
+                model: "gpt-oss:20b".to_string(),
+                error: Some("disk full".to_string()),
+            },
+            OssEvent::BackendUnhealthy {
+                backend: OssBackend::Ollama,
+                message: "exited".to_string(),
+            },
+        ];
+        for event in events {
+            let json = serde_json::to_string(&event).expect("serialize");
+            let parsed: OssEvent = serde_json::from_str(&json).expect("deserialize");
+            assert_eq!(parsed, event);
+        }
+    }
+}
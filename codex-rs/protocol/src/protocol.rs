@@ -11,6 +11,8 @@ use std::str::FromStr;
 use std::time::Duration;
 
 use crate::ConversationId;
+use crate::config_types::OssBackend;
+use crate::config_types::OssSampling;
 use crate::config_types::ReasoningEffort as ReasoningEffortConfig;
 use crate::config_types::ReasoningSummary as ReasoningSummaryConfig;
 use crate::custom_prompts::CustomPrompt;
@@ -130,6 +132,10 @@ pub enum Op {
         /// Updated reasoning summary preference (honored only for reasoning-capable models).
         #[serde(skip_serializing_if = "Option::is_none")]
         summary: Option<ReasoningSummaryConfig>,
+
+        /// Updated sampling parameters for the local `oss` provider.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        oss_sampling: Option<OssSampling>,
     },
 
     /// Approve a command execution
@@ -251,6 +257,17 @@ pub enum SandboxPolicy {
         #[serde(default)]
         network_access: bool,
 
+        /// Loopback ports that stay reachable when `network_access` is
+        /// `false`, so commands can still use the local inference server
+        /// (e.g. for embeddings).
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        local_network_ports: Vec<u16>,
+
+        /// Unix domain sockets that stay connectable when `network_access`
+        /// is `false`, e.g. a local inference server listening on one.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        local_network_sockets: Vec<PathBuf>,
+
         /// When set to `true`, will NOT include the per-user `TMPDIR`
         /// environment variable among the default writable roots. Defaults to
         /// `false`.
@@ -316,6 +333,8 @@ impl SandboxPolicy {
         SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
             network_access: false,
+            local_network_ports: vec![],
+            local_network_sockets: vec![],
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
         }
@@ -342,6 +361,32 @@ impl SandboxPolicy {
         }
     }
 
+    /// Loopback ports reachable without full network access. Empty when the
+    /// network is fully open or fully closed.
+    pub fn local_network_ports(&self) -> &[u16] {
+        match self {
+            SandboxPolicy::WorkspaceWrite {
+                network_access: false,
+                local_network_ports,
+                ..
+            } => local_network_ports,
+            _ => &[],
+        }
+    }
+
+    /// Unix domain sockets connectable without full network access. Empty
+    /// when the network is fully open or fully closed.
+    pub fn local_network_sockets(&self) -> &[PathBuf] {
+        match self {
+            SandboxPolicy::WorkspaceWrite {
+                network_access: false,
+                local_network_sockets,
+                ..
+            } => local_network_sockets,
+            _ => &[],
+        }
+    }
+
     /// Returns the list of writable roots (tailored to the current working
     /// directory) together with subpaths that should remain read‑only under
     /// each writable root.
@@ -354,6 +399,8 @@ impl SandboxPolicy {
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
                 network_access: _,
+                local_network_ports: _,
+                local_network_sockets: _,
             } => {
                 // Start from explicitly configured writable roots.
                 let mut roots: Vec<PathBuf> = writable_roots.clone();
@@ -433,9 +480,17 @@ pub enum EventMsg {
     /// Optional means unknown — UIs should not display when `None`.
     TokenCount(TokenCountEvent),
 
+    /// Latency of one model request against the local `--oss` server, as
+    /// measured by the client.
+    RequestTiming(RequestTimingEvent),
+
     /// Agent text output message
     AgentMessage(AgentMessageEvent),
 
+    /// A cloud model reviewed the answer the local `--oss` model gave for the
+    /// turn (`oss.cloud_review_model`).
+    CloudReview(CloudReviewEvent),
+
     /// User/system input message (what was sent to the model)
     UserMessage(UserMessageEvent),
 
@@ -549,6 +604,10 @@ pub struct ExitedReviewModeEvent {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ErrorEvent {
     pub message: String,
+    /// Whether the turn failed because the connection to the model server
+    /// was lost, e.g. because it crashed or closed the stream mid-response.
+    #[serde(default)]
+    pub connection_lost: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -644,6 +703,46 @@ pub struct TokenCountEvent {
     pub rate_limits: Option<RateLimitSnapshot>,
 }
 
+/// Where the time of one streamed model request went.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct RequestTimingEvent {
+    /// From sending the request until a connection to the server was ready:
+    /// the TCP connect and, when `tls` is set, the TLS handshake. Near zero
+    /// when a pooled connection was reused.
+    #[serde(default)]
+    pub connection_ms: Option<u64>,
+    /// Whether the request went over https, so `connection_ms` includes a
+    /// TLS handshake unless the connection was reused.
+    #[serde(default)]
+    pub tls: bool,
+    /// From the connection being ready until the request body was written.
+    #[serde(default)]
+    pub request_write_ms: Option<u64>,
+    /// From sending the request until the response headers arrived.
+    pub connect_ms: u64,
+    /// From the response headers until the first generated token, which the
+    /// server spends processing the prompt.
+    pub prefill_ms: Option<u64>,
+    /// From sending the request until the first generated token.
+    pub time_to_first_token_ms: Option<u64>,
+    /// From the first generated token until the stream ended.
+    pub generation_ms: Option<u64>,
+    /// Streamed chunks, one per token as local servers send them.
+    pub output_tokens: u64,
+    /// From sending the request until the stream ended.
+    #[serde(default)]
+    pub total_ms: u64,
+}
+
+impl RequestTimingEvent {
+    /// Generation speed, when any token was generated after the first.
+    pub fn tokens_per_second(&self) -> Option<f64> {
+        let generation_ms = self.generation_ms.filter(|ms| *ms > 0)?;
+        (self.output_tokens > 1)
+            .then(|| (self.output_tokens - 1) as f64 * 1000.0 / generation_ms as f64)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
 pub struct RateLimitSnapshot {
     pub primary: Option<RateLimitWindow>,
@@ -770,6 +869,17 @@ pub struct AgentMessageEvent {
     pub message: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct CloudReviewEvent {
+    /// Local model that drafted the answer.
+    pub draft_model: String,
+    /// Cloud model that reviewed it.
+    pub reviewer_model: String,
+    /// The reviewer's answer, replacing the draft; `None` when it approved
+    /// the draft as it was.
+    pub revised_message: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum InputMessageKind {
@@ -1049,6 +1159,9 @@ pub struct TurnContextItem {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub effort: Option<ReasoningEffortConfig>,
     pub summary: ReasoningSummaryConfig,
+    /// Backend of the local server that ran the turn, for `--oss` sessions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oss_backend: Option<OssBackend>,
 }
 
 #[derive(Serialize, Deserialize, Clone, JsonSchema)]
@@ -1445,4 +1558,29 @@ mod tests {
         assert_eq!(deserialized, event);
         Ok(())
     }
+
+    #[test]
+    fn turn_context_records_the_local_backend() -> Result<()> {
+        let old_line = json!({
+            "cwd": "/tmp",
+            "approval_policy": "never",
+            "sandbox_policy": { "mode": "danger-full-access" },
+            "model": "gpt-5",
+            "summary": "auto",
+        });
+        let item: TurnContextItem = serde_json::from_value(old_line.clone())?;
+        assert_eq!(item.oss_backend, None);
+        assert_eq!(serde_json::to_value(&item)?, old_line);
+
+        let item = TurnContextItem {
+            model: "gpt-oss:20b".to_string(),
+            oss_backend: Some(OssBackend::LmStudio),
+            ..item
+        };
+        let value = serde_json::to_value(&item)?;
+        assert_eq!(value["oss_backend"], "lmstudio");
+        let item: TurnContextItem = serde_json::from_value(value)?;
+        assert_eq!(item.oss_backend, Some(OssBackend::LmStudio));
+        Ok(())
+    }
 }
@@ -7,6 +7,10 @@ use crate::diff_render::DiffSummary;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::file_search::FileSearchManager;
 use crate::history_cell::HistoryCell;
+#[cfg(feature = "oss")]
+use crate::oss_health::ReconnectProgress;
+#[cfg(feature = "oss")]
+use crate::oss_server_prompt::ServerRecovery;
 use crate::pager_overlay::Overlay;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::resume_picker::ResumeSelection;
@@ -15,11 +19,19 @@ use crate::tui::TuiEvent;
 use crate::updates::UpdateAction;
 use codex_ansi_escape::ansi_escape_line;
 use codex_core::AuthManager;
+#[cfg(feature = "oss")]
+use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
 use codex_core::ConversationManager;
 use codex_core::config::Config;
 use codex_core::config::persist_model_selection;
 use codex_core::config::set_hide_full_access_warning;
+#[cfg(feature = "oss")]
+use codex_core::config::set_hide_model_download_confirmation;
+use codex_core::config::set_oss_sampling;
 use codex_core::model_family::find_family_for_model;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
 use codex_core::protocol::SessionSource;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
@@ -38,6 +50,8 @@ use std::sync::atomic::Ordering;
 use std::thread;
 use std::time::Duration;
 use tokio::select;
+#[cfg(feature = "oss")]
+use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::sync::mpsc::unbounded_channel;
 
 #[cfg(not(debug_assertions))]
@@ -79,8 +93,39 @@ pub(crate) struct App {
     pub(crate) feedback: codex_feedback::CodexFeedback,
     /// Set when the user confirms an update; propagated on exit.
     pub(crate) pending_update_action: Option<UpdateAction>,
+    /// Configuration for the default cloud provider, offered in `--oss`
+    /// sessions when the local server stops responding.
+    #[cfg(feature = "oss")]
+    cloud_config: Option<Config>,
+    /// Health and memory checks of the local server, stopped when leaving
+    /// `--oss`.
+    #[cfg(feature = "oss")]
+    local_server_monitors: Vec<tokio::task::JoinHandle<()>>,
+    /// Configuration of the `--oss` session to switch to once its model,
+    /// downloading in the background, is ready, when the session started on
+    /// the cloud provider meanwhile.
+    #[cfg(feature = "oss")]
+    local_config: Option<Config>,
+    /// Wait for the local server after it dropped mid-turn, while one runs.
+    #[cfg(feature = "oss")]
+    local_reconnect: Option<tokio::task::JoinHandle<()>>,
+    /// Records the local model's throughput when
+    /// `oss.performance_telemetry` is on.
+    #[cfg(feature = "oss")]
+    performance_recorder: Option<codex_ollama::PerformanceRecorder>,
+    /// Estimates the energy of the session with the local model.
+    #[cfg(feature = "oss")]
+    energy_meter: Option<codex_ollama::EnergyMeter>,
+    /// Keeps the local model loaded while commands run.
+    #[cfg(feature = "oss")]
+    local_keep_alive: Option<tokio::task::JoinHandle<()>>,
 }
 
+/// How long exiting waits for a turn running against the local model to be
+/// cancelled.
+#[cfg(feature = "oss")]
+const LOCAL_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
 impl App {
     #[allow(clippy::too_many_arguments)]
     pub async fn run(
@@ -92,8 +137,14 @@ impl App {
         initial_images: Vec<PathBuf>,
         resume_selection: ResumeSelection,
         feedback: codex_feedback::CodexFeedback,
+        cloud_config: Option<Config>,
+        background_download: Option<Config>,
     ) -> Result<AppExitInfo> {
         use tokio_stream::StreamExt;
+        // Without local backend support there is no `--oss` session to fall
+        // back from or download for.
+        #[cfg(not(feature = "oss"))]
+        let _ = (cloud_config, background_download);
         let (app_event_tx, mut app_event_rx) = unbounded_channel();
         let app_event_tx = AppEventSender::new(app_event_tx);
 
@@ -147,6 +198,34 @@ impl App {
             }
         };
 
+        #[cfg(feature = "oss")]
+        let is_oss_session = config.model_provider_id == BUILT_IN_OSS_MODEL_PROVIDER_ID;
+        #[cfg(feature = "oss")]
+        let local_server_monitors = if is_oss_session {
+            crate::oss_models::spawn_detect_context_window(config.clone(), app_event_tx.clone());
+            spawn_local_server_monitors(&config, &app_event_tx)
+        } else {
+            Vec::new()
+        };
+        #[cfg(feature = "oss")]
+        let performance_recorder = is_oss_session
+            .then(|| codex_ollama::PerformanceRecorder::from_config(&config))
+            .flatten();
+        #[cfg(feature = "oss")]
+        let energy_meter = is_oss_session.then(|| codex_ollama::EnergyMeter::from_config(&config));
+        #[cfg(feature = "oss")]
+        let downloading_model = background_download
+            .as_ref()
+            .map(|local_config| local_config.model.clone());
+        #[cfg(feature = "oss")]
+        let local_config = background_download.and_then(|local_config| {
+            crate::oss_models::spawn_background_download(
+                local_config.clone(),
+                app_event_tx.clone(),
+            );
+            (!is_oss_session).then_some(local_config)
+        });
+
         let file_search = FileSearchManager::new(config.cwd.clone(), app_event_tx.clone());
         #[cfg(not(debug_assertions))]
         let upgrade_version = crate::updates::get_upgrade_version(&config);
@@ -168,8 +247,30 @@ impl App {
             backtrack: BacktrackState::default(),
             feedback: feedback.clone(),
             pending_update_action: None,
+            #[cfg(feature = "oss")]
+            cloud_config,
+            #[cfg(feature = "oss")]
+            local_server_monitors,
+            #[cfg(feature = "oss")]
+            local_config,
+            #[cfg(feature = "oss")]
+            local_reconnect: None,
+            #[cfg(feature = "oss")]
+            performance_recorder,
+            #[cfg(feature = "oss")]
+            energy_meter,
+            #[cfg(feature = "oss")]
+            local_keep_alive: None,
         };
 
+        #[cfg(feature = "oss")]
+        if let Some(model) = downloading_model {
+            app.chat_widget.add_info_message(
+                format!("Downloading {model} in the background"),
+                Some("You can switch to it when the download finishes.".to_string()),
+            );
+        }
+
         #[cfg(not(debug_assertions))]
         if let Some(latest_version) = upgrade_version {
             app.handle_event(
@@ -196,13 +297,62 @@ impl App {
             }
         } {}
         tui.terminal.clear()?;
+        #[cfg(feature = "oss")]
+        if app.config.model_provider_id == BUILT_IN_OSS_MODEL_PROVIDER_ID {
+            app.shut_down_local_session(&mut app_event_rx).await;
+        }
+        #[cfg(not(feature = "oss"))]
+        let local_energy = None;
+        #[cfg(feature = "oss")]
+        let local_energy = app
+            .energy_meter
+            .as_ref()
+            .and_then(|meter| meter.finish(&app.token_usage()));
         Ok(AppExitInfo {
             token_usage: app.token_usage(),
             conversation_id: app.chat_widget.conversation_id(),
+            local_energy,
             update_action: app.pending_update_action,
         })
     }
 
+    /// Cancel a turn still running against the local model and tidy up the
+    /// local server, rather than exiting while it is mid-generation.
+    #[cfg(feature = "oss")]
+    async fn shut_down_local_session(&mut self, app_event_rx: &mut UnboundedReceiver<AppEvent>) {
+        for monitor in self
+            .local_server_monitors
+            .drain(..)
+            .chain(self.local_reconnect.take())
+            .chain(self.local_keep_alive.take())
+        {
+            monitor.abort();
+        }
+        if self.chat_widget.is_task_running() {
+            // Aborting the turn drops its response stream; the local servers
+            // stop generating once the connection closes.
+            self.chat_widget.submit_op(Op::Shutdown);
+            let shut_down = async {
+                while let Some(event) = app_event_rx.recv().await {
+                    if let AppEvent::CodexEvent(Event {
+                        msg: EventMsg::ShutdownComplete,
+                        ..
+                    }) = event
+                    {
+                        break;
+                    }
+                }
+            };
+            if tokio::time::timeout(LOCAL_DRAIN_TIMEOUT, shut_down)
+                .await
+                .is_err()
+            {
+                tracing::warn!("the running turn did not stop within {LOCAL_DRAIN_TIMEOUT:?}");
+            }
+        }
+        codex_ollama::shutdown_local_provider(&self.config).await;
+    }
+
     pub(crate) async fn handle_tui_event(
         &mut self,
         tui: &mut tui::Tui,
@@ -319,6 +469,16 @@ impl App {
             AppEvent::ExitRequest => {
                 return Ok(false);
             }
+            // Interrupting while the local server is awaited stops waiting;
+            // there is no turn running to interrupt.
+            #[cfg(feature = "oss")]
+            AppEvent::CodexOp(Op::Interrupt) if self.chat_widget.is_reconnecting_local_server() => {
+                self.chat_widget.cancel_local_reconnect();
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::CodexOp(Op::Interrupt) if self.chat_widget.is_comparing_local_models() => {
+                self.chat_widget.cancel_local_compare();
+            }
             AppEvent::CodexOp(op) => self.chat_widget.submit_op(op),
             AppEvent::DiffResult(text) => {
                 // Clear the in-progress state in the bottom pane
@@ -336,6 +496,14 @@ impl App {
                 ));
                 tui.frame_requester().schedule_frame();
             }
+            AppEvent::ShowRequestLatency(lines) => {
+                let _ = tui.enter_alt_screen();
+                self.overlay = Some(Overlay::new_static_with_lines(
+                    lines,
+                    "L A T E N C Y".to_string(),
+                ));
+                tui.frame_requester().schedule_frame();
+            }
             AppEvent::StartFileSearch(query) => {
                 if !query.is_empty() {
                     self.file_search.on_user_query(query);
@@ -422,6 +590,293 @@ impl App {
             AppEvent::OpenApprovalsPopup => {
                 self.chat_widget.open_approvals_popup();
             }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalModelsLoaded(result) => match result {
+                Ok(models) => self.chat_widget.open_local_model_popup(models),
+                Err(err) => self
+                    .chat_widget
+                    .add_error_message(format!("Failed to list local models: {err}")),
+            },
+            #[cfg(feature = "oss")]
+            AppEvent::LocalCompareModelsLoaded(result) => match result {
+                Ok(models) => self.chat_widget.on_local_compare_models_loaded(models),
+                Err(err) => self
+                    .chat_widget
+                    .add_error_message(format!("Failed to list local models: {err}")),
+            },
+            #[cfg(feature = "oss")]
+            AppEvent::SelectLocalCompareModel(model) => {
+                self.chat_widget.on_local_compare_model_selected(model);
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalCompareFinished(answers) => {
+                self.chat_widget.on_local_compare_finished(answers);
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::SwitchLocalModel(model) => {
+                crate::oss_models::spawn_switch_local_model(
+                    self.config.clone(),
+                    model,
+                    self.app_event_tx.clone(),
+                );
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::ConfirmLocalModelDownload(estimate) => {
+                self.chat_widget
+                    .open_local_model_download_confirmation(estimate);
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::PersistModelDownloadConfirmationHidden => {
+                self.config.notices.hide_model_download_confirmation = Some(true);
+                if let Err(err) =
+                    set_hide_model_download_confirmation(&self.config.codex_home, true)
+                {
+                    tracing::error!(
+                        error = %err,
+                        "failed to persist model download confirmation preference"
+                    );
+                    self.chat_widget.add_error_message(format!(
+                        "Failed to save model download confirmation preference: {err}"
+                    ));
+                }
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::PrepareLocalModel(model) => {
+                self.chat_widget.on_local_model_preparing(&model);
+                crate::oss_models::spawn_prepare_local_model(
+                    self.config.clone(),
+                    model,
+                    self.app_event_tx.clone(),
+                );
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalModelDownloadProgress(progress) => {
+                self.chat_widget.on_local_model_download_progress(&progress);
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalModelReady(model) => {
+                self.chat_widget.on_local_model_prepared();
+                let mut switched = self.config.clone();
+                switched.model = model.clone();
+                crate::oss_models::spawn_detect_context_window(switched, self.app_event_tx.clone());
+                self.chat_widget.submit_op(Op::OverrideTurnContext {
+                    cwd: None,
+                    approval_policy: None,
+                    sandbox_policy: None,
+                    model: Some(model.clone()),
+                    effort: None,
+                    summary: None,
+                    oss_sampling: None,
+                });
+                self.app_event_tx.send(AppEvent::UpdateModel(model.clone()));
+                match codex_core::config_edit::persist_overrides(
+                    &self.config.codex_home,
+                    None,
+                    &[(&["oss", "model"], model.as_str())],
+                )
+                .await
+                {
+                    Ok(()) => self
+                        .chat_widget
+                        .add_info_message(format!("Model changed to {model}"), None),
+                    Err(err) => {
+                        tracing::error!(error = %err, "failed to persist oss.model");
+                        self.chat_widget.add_error_message(format!(
+                            "Switched to {model}, but failed to save it as the default: {err}"
+                        ));
+                    }
+                }
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalServerStatus(status) => {
+                self.chat_widget.set_local_server_status(status);
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::RecordLocalPerformance(timing) => {
+                if let Some(meter) = &self.energy_meter {
+                    meter.record(&timing);
+                }
+                if let Some(recorder) = self.performance_recorder.clone() {
+                    let model = self.config.model.clone();
+                    tokio::spawn(async move { recorder.record(&model, &timing).await });
+                }
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::KeepLocalModelLoaded(keep) => {
+                if let Some(keep_alive) = self.local_keep_alive.take() {
+                    keep_alive.abort();
+                }
+                if keep && self.config.model_provider_id == BUILT_IN_OSS_MODEL_PROVIDER_ID {
+                    self.local_keep_alive = Some(tokio::spawn(codex_ollama::keep_model_warm(
+                        self.config.clone(),
+                    )));
+                }
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalServerLifecycle(event) => {
+                self.chat_widget.on_local_server_lifecycle(event);
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalModelLoaded(model) => {
+                // The window a model is loaded with can differ from the one
+                // detected before it was loaded.
+                if model == self.config.model {
+                    crate::oss_models::spawn_detect_context_window(
+                        self.config.clone(),
+                        self.app_event_tx.clone(),
+                    );
+                }
+                self.chat_widget.on_local_model_loaded(&model);
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalModelUnloaded(model) => {
+                self.chat_widget.on_local_model_unloaded(&model);
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalModelEjected { model, result } => {
+                self.chat_widget.on_local_model_ejected(&model, result);
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalContextWindow(window) => {
+                self.chat_widget.set_local_context_window(window);
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalMemoryUsage(usage) => {
+                self.chat_widget.set_local_memory_usage(usage);
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalServerReport(result) => {
+                self.chat_widget.on_local_server_report(result);
+            }
+            AppEvent::UpdateOssSampling(sampling) => {
+                self.chat_widget.submit_op(Op::OverrideTurnContext {
+                    cwd: None,
+                    approval_policy: None,
+                    sandbox_policy: None,
+                    model: None,
+                    effort: None,
+                    summary: None,
+                    oss_sampling: Some(sampling),
+                });
+                self.chat_widget.set_oss_sampling(sampling);
+                self.config.oss.sampling = sampling;
+                match set_oss_sampling(&self.config.codex_home, &sampling) {
+                    Ok(()) => self.chat_widget.add_info_message(
+                        "Sampling parameters updated".to_string(),
+                        Some("They apply from the next turn.".to_string()),
+                    ),
+                    Err(err) => {
+                        tracing::error!(error = %err, "failed to persist oss sampling");
+                        self.chat_widget.add_error_message(format!(
+                            "Sampling parameters apply to this session, but saving them failed: {err}"
+                        ));
+                    }
+                }
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::RecoverLocalServer(ServerRecovery::SwitchToCloud) => {
+                match self.cloud_config.take() {
+                    Some(config) => {
+                        for monitor in self
+                            .local_server_monitors
+                            .drain(..)
+                            .chain(self.local_reconnect.take())
+                            .chain(self.local_keep_alive.take())
+                        {
+                            monitor.abort();
+                        }
+                        self.performance_recorder = None;
+                        if let Some(meter) = self.energy_meter.take() {
+                            meter.finish(&self.token_usage());
+                        }
+                        self.config = config;
+                        self.app_event_tx.send(AppEvent::NewSession);
+                    }
+                    None => self.chat_widget.add_error_message(
+                        "No cloud provider is configured; restart Codex without --oss to use one."
+                            .to_string(),
+                    ),
+                }
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::RecoverLocalServer(choice) => {
+                let start = choice == ServerRecovery::StartServer;
+                if start {
+                    self.chat_widget
+                        .add_info_message("Starting the local server…".to_string(), None);
+                }
+                crate::oss_health::spawn_recovery(
+                    self.config.clone(),
+                    start,
+                    self.app_event_tx.clone(),
+                );
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalModelDownloaded { model, result } => {
+                self.chat_widget.on_local_model_downloaded(model, result);
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::SwitchToDownloadedModel(model) => {
+                if self.config.model_provider_id == BUILT_IN_OSS_MODEL_PROVIDER_ID {
+                    self.app_event_tx.send(AppEvent::SwitchLocalModel(model));
+                } else if let Some(config) = self.local_config.take() {
+                    self.local_server_monitors =
+                        spawn_local_server_monitors(&config, &self.app_event_tx);
+                    self.performance_recorder =
+                        codex_ollama::PerformanceRecorder::from_config(&config);
+                    self.energy_meter = Some(codex_ollama::EnergyMeter::from_config(&config));
+                    crate::oss_models::spawn_detect_context_window(
+                        config.clone(),
+                        self.app_event_tx.clone(),
+                    );
+                    self.config = config;
+                    self.app_event_tx.send(AppEvent::NewSession);
+                } else {
+                    self.chat_widget.add_error_message(format!(
+                        "Cannot switch to {model}; restart Codex with --oss to use it."
+                    ));
+                }
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalServerRecovery(result) => {
+                self.chat_widget.on_local_server_recovery(result);
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::PreloadLocalModel => {
+                crate::oss_health::spawn_preload(self.config.clone());
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::ReconnectLocalServer => {
+                if let Some(previous) = self.local_reconnect.take() {
+                    previous.abort();
+                }
+                self.local_reconnect = Some(crate::oss_health::spawn_reconnect(
+                    self.config.clone(),
+                    self.app_event_tx.clone(),
+                ));
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::CancelLocalReconnect => {
+                if let Some(reconnect) = self.local_reconnect.take() {
+                    reconnect.abort();
+                }
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalServerReconnect(progress) => {
+                if matches!(
+                    progress,
+                    ReconnectProgress::Reconnected | ReconnectProgress::Failed(_)
+                ) {
+                    self.local_reconnect = None;
+                }
+                self.chat_widget.on_local_server_reconnect(progress);
+            }
+            #[cfg(feature = "oss")]
+            AppEvent::LocalModelFailed { model, card } => {
+                tracing::warn!("failed to load {model}: {card:?}");
+                self.chat_widget.on_local_model_prepared();
+                self.chat_widget.add_oss_error_card(card);
+            }
             AppEvent::OpenReviewBranchPicker(cwd) => {
                 self.chat_widget.show_review_branch_picker(&cwd).await;
             }
@@ -524,6 +979,26 @@ impl App {
     }
 }
 
+/// Health and memory checks of the local server behind `config`,
+/// supervision of one Codex started, and its events.
+#[cfg(feature = "oss")]
+fn spawn_local_server_monitors(
+    config: &Config,
+    app_event_tx: &AppEventSender,
+) -> Vec<tokio::task::JoinHandle<()>> {
+    [
+        crate::oss_health::spawn_health_monitor(config, app_event_tx.clone()),
+        crate::oss_memory::spawn_memory_monitor(config, app_event_tx.clone()),
+        crate::oss_health::spawn_supervisor(config, app_event_tx.clone()),
+        Some(crate::oss_health::spawn_event_listener(
+            app_event_tx.clone(),
+        )),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -573,6 +1048,20 @@ mod tests {
             backtrack: BacktrackState::default(),
             feedback: codex_feedback::CodexFeedback::new(),
             pending_update_action: None,
+            #[cfg(feature = "oss")]
+            cloud_config: None,
+            #[cfg(feature = "oss")]
+            local_server_monitors: Vec::new(),
+            #[cfg(feature = "oss")]
+            local_config: None,
+            #[cfg(feature = "oss")]
+            local_reconnect: None,
+            #[cfg(feature = "oss")]
+            performance_recorder: None,
+            #[cfg(feature = "oss")]
+            energy_meter: None,
+            #[cfg(feature = "oss")]
+            local_keep_alive: None,
         }
     }
 
@@ -4,13 +4,39 @@ use codex_common::approval_presets::ApprovalPreset;
 use codex_common::model_presets::ModelPreset;
 use codex_core::protocol::ConversationPathResponseEvent;
 use codex_core::protocol::Event;
+#[cfg(feature = "oss")]
+use codex_core::protocol::RequestTimingEvent;
 use codex_file_search::FileMatch;
+#[cfg(feature = "oss")]
+use codex_ollama::LocalModel;
+#[cfg(feature = "oss")]
+use codex_ollama::ServerLifecycleEvent;
+#[cfg(feature = "oss")]
+use codex_protocol::oss::ModelDownloadProgress;
+use ratatui::text::Line;
 
 use crate::bottom_pane::ApprovalRequest;
 use crate::history_cell::HistoryCell;
+#[cfg(feature = "oss")]
+use crate::oss_compare::ComparedAnswer;
+#[cfg(feature = "oss")]
+use crate::oss_error_card::OssErrorCard;
+#[cfg(feature = "oss")]
+use crate::oss_health::LocalServerReport;
+#[cfg(feature = "oss")]
+use crate::oss_health::LocalServerStatus;
+#[cfg(feature = "oss")]
+use crate::oss_health::ReconnectProgress;
+#[cfg(feature = "oss")]
+use crate::oss_memory::MemoryUsage;
+#[cfg(feature = "oss")]
+use crate::oss_models::DownloadEstimate;
+#[cfg(feature = "oss")]
+use crate::oss_server_prompt::ServerRecovery;
 
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
+use codex_core::protocol_config_types::OssSampling;
 use codex_core::protocol_config_types::ReasoningEffort;
 
 #[allow(clippy::large_enum_variant)]
@@ -44,6 +70,21 @@ pub(crate) enum AppEvent {
     /// Result of computing a `/diff` command.
     DiffResult(String),
 
+    /// Open the `/latency` overlay with these rows.
+    ShowRequestLatency(Vec<Line<'static>>),
+
+    /// Models on the local server to pick from for `/compare`.
+    #[cfg(feature = "oss")]
+    LocalCompareModelsLoaded(Result<Vec<String>, String>),
+
+    /// A model was picked in the `/compare` picker.
+    #[cfg(feature = "oss")]
+    SelectLocalCompareModel(String),
+
+    /// Answers of both models compared by `/compare`.
+    #[cfg(feature = "oss")]
+    LocalCompareFinished(Vec<ComparedAnswer>),
+
     InsertHistoryCell(Box<dyn HistoryCell>),
 
     StartCommitAnimation,
@@ -101,4 +142,131 @@ pub(crate) enum AppEvent {
 
     /// Open the approval popup.
     FullScreenApprovalRequest(ApprovalRequest),
+
+    /// Result of listing the models on the local `--oss` server.
+    #[cfg(feature = "oss")]
+    LocalModelsLoaded(Result<Vec<LocalModel>, String>),
+
+    /// Download (if needed) and load a local model, then switch to it.
+    #[cfg(feature = "oss")]
+    SwitchLocalModel(String),
+
+    /// Ask before [`AppEvent::SwitchLocalModel`] starts a large download.
+    #[cfg(feature = "oss")]
+    ConfirmLocalModelDownload(DownloadEstimate),
+
+    /// Download (if needed) and load a local model once any download is
+    /// confirmed.
+    #[cfg(feature = "oss")]
+    PrepareLocalModel(String),
+
+    /// Persist the choice to start large local model downloads without
+    /// confirmation.
+    #[cfg(feature = "oss")]
+    PersistModelDownloadConfirmationHidden,
+
+    /// Progress of a local model download started by [`AppEvent::SwitchLocalModel`].
+    #[cfg(feature = "oss")]
+    LocalModelDownloadProgress(ModelDownloadProgress),
+
+    /// The local model is downloaded and loaded; switch the session to it.
+    #[cfg(feature = "oss")]
+    LocalModelReady(String),
+
+    /// Preparing the local model failed.
+    #[cfg(feature = "oss")]
+    LocalModelFailed {
+        model: String,
+        card: OssErrorCard,
+    },
+
+    /// The local server finished loading the model into memory.
+    #[cfg(feature = "oss")]
+    LocalModelLoaded(String),
+
+    /// A model was unloaded from the local server, by `/eject` or otherwise.
+    #[cfg(feature = "oss")]
+    LocalModelUnloaded(String),
+
+    /// `/eject` unloaded the model from the local server, or failed to.
+    #[cfg(feature = "oss")]
+    LocalModelEjected {
+        model: String,
+        result: Result<(), String>,
+    },
+
+    /// Context window the local server runs the session's model with, when
+    /// it reports one.
+    #[cfg(feature = "oss")]
+    LocalContextWindow(Option<u64>),
+
+    /// Memory used by the local model and left on the machine.
+    #[cfg(feature = "oss")]
+    LocalMemoryUsage(MemoryUsage),
+
+    /// Status of the local server requested with `/oss-status`.
+    #[cfg(feature = "oss")]
+    LocalServerReport(Result<LocalServerReport, String>),
+
+    /// Apply new sampling parameters for the local model from the next turn
+    /// and save them to `config.toml`.
+    UpdateOssSampling(OssSampling),
+
+    /// A local model downloading in the background finished or failed.
+    #[cfg(feature = "oss")]
+    LocalModelDownloaded {
+        model: String,
+        result: Result<(), OssErrorCard>,
+    },
+
+    /// Switch the session to a model downloaded in the background.
+    #[cfg(feature = "oss")]
+    SwitchToDownloadedModel(String),
+
+    /// Health of the local server, sent by its monitor whenever it changes.
+    #[cfg(feature = "oss")]
+    LocalServerStatus(LocalServerStatus),
+
+    /// Act on the user's choice after the local server stopped responding.
+    #[cfg(feature = "oss")]
+    RecoverLocalServer(ServerRecovery),
+
+    /// Health of the local server after [`AppEvent::RecoverLocalServer`], or
+    /// why starting it failed.
+    #[cfg(feature = "oss")]
+    LocalServerRecovery(Result<LocalServerStatus, String>),
+
+    /// The local server dropped in the middle of a turn; wait for it to come
+    /// back and reload the model.
+    #[cfg(feature = "oss")]
+    ReconnectLocalServer,
+
+    /// Stop waiting for the local server started by
+    /// [`AppEvent::ReconnectLocalServer`].
+    #[cfg(feature = "oss")]
+    CancelLocalReconnect,
+
+    /// Progress of [`AppEvent::ReconnectLocalServer`].
+    #[cfg(feature = "oss")]
+    LocalServerReconnect(ReconnectProgress),
+
+    /// The supervisor of the local server Codex started saw it crash,
+    /// restart, or stay down.
+    #[cfg(feature = "oss")]
+    LocalServerLifecycle(ServerLifecycleEvent),
+
+    /// A request to the local model finished; account for its energy, and
+    /// record its throughput when `oss.performance_telemetry` is on.
+    #[cfg(feature = "oss")]
+    RecordLocalPerformance(RequestTimingEvent),
+
+    /// The local server came back while idle; load the session's model
+    /// before the next message needs it.
+    #[cfg(feature = "oss")]
+    PreloadLocalModel,
+
+    /// Commands started (`true`) or all finished (`false`); keep the local
+    /// model loaded meanwhile.
+    #[cfg(feature = "oss")]
+    KeepLocalModelLoaded(bool),
 }
@@ -19,13 +19,16 @@ use super::chat_composer_history::ChatComposerHistory;
 use super::command_popup::CommandItem;
 use super::command_popup::CommandPopup;
 use super::file_search_popup::FileSearchPopup;
+use super::footer::ContextUsage;
 use super::footer::FooterMode;
 use super::footer::FooterProps;
+use super::footer::OssServerBadge;
 use super::footer::esc_hint_mode;
 use super::footer::footer_height;
 use super::footer::render_footer;
 use super::footer::reset_mode_after_activity;
 use super::footer::toggle_shortcut_mode;
+use super::local_mode_badge::LocalModeBadge;
 use super::paste_burst::CharDecision;
 use super::paste_burst::PasteBurst;
 use crate::bottom_pane::paste_burst::FlushResult;
@@ -35,6 +38,7 @@ use crate::bottom_pane::prompt_args::parse_slash_name;
 use crate::bottom_pane::prompt_args::prompt_argument_names;
 use crate::bottom_pane::prompt_args::prompt_command_with_arg_placeholders;
 use crate::bottom_pane::prompt_args::prompt_has_numeric_placeholders;
+use crate::generation_meter::GenerationSpeed;
 use crate::slash_command::SlashCommand;
 use crate::slash_command::built_in_slash_commands;
 use crate::style::user_message_style;
@@ -109,6 +113,10 @@ pub(crate) struct ChatComposer {
     footer_mode: FooterMode,
     footer_hint_override: Option<Vec<(String, String)>>,
     context_window_percent: Option<i64>,
+    oss_server: Option<OssServerBadge>,
+    generation_speed: Option<GenerationSpeed>,
+    context_usage: Option<ContextUsage>,
+    local_mode: Option<LocalModeBadge>,
 }
 
 /// Popup state – at most one can be visible at any time.
@@ -152,6 +160,10 @@ impl ChatComposer {
             footer_mode: FooterMode::ShortcutSummary,
             footer_hint_override: None,
             context_window_percent: None,
+            oss_server: None,
+            generation_speed: None,
+            context_usage: None,
+            local_mode: None,
         };
         // Apply configuration via the setter to keep side-effects centralized.
         this.set_disable_paste_burst(disable_paste_burst);
@@ -1381,6 +1393,9 @@ impl ChatComposer {
             use_shift_enter_hint: self.use_shift_enter_hint,
             is_task_running: self.is_task_running,
             context_window_percent: self.context_window_percent,
+            oss_server: self.oss_server,
+            generation_speed: self.generation_speed,
+            context_usage: self.context_usage,
         }
     }
 
@@ -1517,6 +1532,22 @@ impl ChatComposer {
         }
     }
 
+    pub(crate) fn set_oss_server(&mut self, badge: Option<OssServerBadge>) {
+        self.oss_server = badge;
+    }
+
+    pub(crate) fn set_generation_speed(&mut self, speed: Option<GenerationSpeed>) {
+        self.generation_speed = speed;
+    }
+
+    pub(crate) fn set_context_usage(&mut self, usage: Option<ContextUsage>) {
+        self.context_usage = usage;
+    }
+
+    pub(crate) fn set_local_mode(&mut self, badge: Option<LocalModeBadge>) {
+        self.local_mode = badge;
+    }
+
     pub(crate) fn set_esc_backtrack_hint(&mut self, show: bool) {
         self.esc_backtrack_hint = show;
         if show {
@@ -1581,6 +1612,21 @@ impl WidgetRef for ChatComposer {
         block_rect.y = composer_rect.y.saturating_sub(1);
         block_rect.height = composer_rect.height.saturating_add(1);
         Block::default().style(style).render_ref(block_rect, buf);
+        // The badge sits right-aligned in the padding row above the input.
+        if let Some(badge) = &self.local_mode
+            && composer_rect.y > area.y
+        {
+            let max_width = usize::from(composer_rect.width.saturating_sub(2));
+            let line = badge.line(max_width);
+            let width = line.width() as u16;
+            let badge_rect = Rect {
+                x: composer_rect.right().saturating_sub(width + 1),
+                y: block_rect.y,
+                width,
+                height: 1,
+            };
+            line.render_ref(badge_rect, buf);
+        }
         buf.set_span(
             composer_rect.x,
             composer_rect.y,
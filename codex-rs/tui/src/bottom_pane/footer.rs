@@ -1,7 +1,9 @@
+use crate::generation_meter::GenerationSpeed;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
 use crate::render::line_utils::prefix_lines;
 use crate::ui_consts::FOOTER_INDENT_COLS;
+use codex_core::protocol_config_types::OssBackend;
 use crossterm::event::KeyCode;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
@@ -18,6 +20,53 @@ pub(crate) struct FooterProps {
     pub(crate) use_shift_enter_hint: bool,
     pub(crate) is_task_running: bool,
     pub(crate) context_window_percent: Option<i64>,
+    pub(crate) oss_server: Option<OssServerBadge>,
+    pub(crate) generation_speed: Option<GenerationSpeed>,
+    /// Replaces the percentage of context left in `--oss` sessions.
+    pub(crate) context_usage: Option<ContextUsage>,
+}
+
+/// Tokens in the context of a local model, against the context window the
+/// server runs it with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct ContextUsage {
+    pub(crate) used_tokens: u64,
+    pub(crate) window_tokens: u64,
+    /// Token count at which the conversation is compacted, when enabled.
+    pub(crate) compact_at_tokens: Option<u64>,
+}
+
+impl ContextUsage {
+    /// Within a tenth of the point where the conversation is compacted or,
+    /// without auto-compaction, of the end of the window.
+    fn is_nearly_full(self) -> bool {
+        let limit = self
+            .compact_at_tokens
+            .unwrap_or(self.window_tokens)
+            .min(self.window_tokens);
+        self.used_tokens.saturating_mul(10) >= limit.saturating_mul(9)
+    }
+}
+
+/// State of the local `--oss` server, shown at the start of the footer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(not(feature = "oss"), allow(dead_code))]
+pub(crate) struct OssServerBadge {
+    pub(crate) backend: OssBackend,
+    pub(crate) state: OssServerState,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(not(feature = "oss"), allow(dead_code))]
+pub(crate) enum OssServerState {
+    Unreachable,
+    /// Reachable, but failed several requests in a row; requests to it are
+    /// paused for a while.
+    Unhealthy,
+    /// Reachable, but the session's model is not loaded; the next request
+    /// loads it.
+    Idle,
+    ModelLoaded,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -81,7 +130,7 @@ fn footer_lines(props: FooterProps) -> Vec<Line<'static>> {
             is_task_running: props.is_task_running,
         })],
         FooterMode::ShortcutSummary => {
-            let mut line = context_window_line(props.context_window_percent);
+            let mut line = status_line(props);
             line.push_span(" · ".dim());
             line.extend(vec![
                 key_hint::plain(KeyCode::Char('?')).into(),
@@ -94,7 +143,7 @@ fn footer_lines(props: FooterProps) -> Vec<Line<'static>> {
             esc_backtrack_hint: props.esc_backtrack_hint,
         }),
         FooterMode::EscHint => vec![esc_hint_line(props.esc_backtrack_hint)],
-        FooterMode::ContextOnly => vec![context_window_line(props.context_window_percent)],
+        FooterMode::ContextOnly => vec![status_line(props)],
     }
 }
 
@@ -221,6 +270,86 @@ fn build_columns(entries: Vec<Line<'static>>) -> Vec<Line<'static>> {
         .collect()
 }
 
+/// The OSS server badge and generation speed, when known, followed by the
+/// context indicator or, in `--oss` sessions, the context gauge.
+fn status_line(props: FooterProps) -> Line<'static> {
+    let mut line = Line::from("");
+    if let Some(badge) = props.oss_server {
+        line.extend(oss_server_line(badge).spans);
+        line.push_span(" · ".dim());
+    }
+    if let Some(speed) = props.generation_speed {
+        line.extend(generation_speed_line(speed).spans);
+        line.push_span(" · ".dim());
+    }
+    match props.context_usage {
+        Some(usage) => line.extend(context_gauge_line(usage).spans),
+        None => line.extend(context_window_line(props.context_window_percent).spans),
+    }
+    line
+}
+
+/// Width of the context gauge, in cells.
+const CONTEXT_GAUGE_WIDTH: u64 = 8;
+
+/// e.g. "context ███░░░░░ 3.1k / 8.2k".
+fn context_gauge_line(usage: ContextUsage) -> Line<'static> {
+    let window = usage.window_tokens.max(1);
+    let filled = (usage.used_tokens.min(window) * CONTEXT_GAUGE_WIDTH + window / 2) / window;
+    let gauge = format!(
+        "{}{}",
+        "█".repeat(filled as usize),
+        "░".repeat((CONTEXT_GAUGE_WIDTH - filled) as usize)
+    );
+    let counts = format!(
+        " {} / {}",
+        format_tokens(usage.used_tokens),
+        format_tokens(usage.window_tokens)
+    );
+    let mut line = Line::from(vec!["context ".dim()]);
+    if usage.is_nearly_full() {
+        line.push_span(gauge.red());
+        line.push_span(counts.dim());
+        let warning = if usage.compact_at_tokens.is_some() {
+            " · compacting soon"
+        } else {
+            " · context almost full"
+        };
+        line.push_span(warning.red());
+    } else {
+        line.push_span(gauge.dim());
+        line.push_span(counts.dim());
+    }
+    line
+}
+
+/// e.g. "950", "8.2k", "131k".
+fn format_tokens(tokens: u64) -> String {
+    match tokens {
+        0..1_000 => tokens.to_string(),
+        1_000..100_000 => format!("{:.1}k", tokens as f64 / 1_000.0),
+        _ => format!("{}k", tokens / 1_000),
+    }
+}
+
+fn generation_speed_line(speed: GenerationSpeed) -> Line<'static> {
+    let mut text = format!("{:.1} tok/s", speed.tokens_per_second);
+    if let Some(ttft) = speed.time_to_first_token {
+        text.push_str(&format!(" · {:.1}s to first token", ttft.as_secs_f64()));
+    }
+    Line::from(text).dim()
+}
+
+fn oss_server_line(badge: OssServerBadge) -> Line<'static> {
+    let backend = Span::from(format!("{} ", badge.backend)).dim();
+    match badge.state {
+        OssServerState::Unreachable => Line::from(vec![backend, "✗ unreachable".red()]),
+        OssServerState::Unhealthy => Line::from(vec![backend, "✗ unhealthy".red()]),
+        OssServerState::Idle => Line::from(vec![backend, "○ idle".dim()]),
+        OssServerState::ModelLoaded => Line::from(vec![backend, "● ".green(), "loaded".dim()]),
+    }
+}
+
 fn context_window_line(percent: Option<i64>) -> Line<'static> {
     let percent = percent.unwrap_or(100).clamp(0, 100);
     Line::from(vec![Span::from(format!("{percent}% context left")).dim()])
@@ -400,6 +529,9 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                oss_server: None,
+                context_usage: None,
+                generation_speed: None,
             },
         );
 
@@ -411,6 +543,9 @@ mod tests {
                 use_shift_enter_hint: true,
                 is_task_running: false,
                 context_window_percent: None,
+                oss_server: None,
+                context_usage: None,
+                generation_speed: None,
             },
         );
 
@@ -422,6 +557,9 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                oss_server: None,
+                context_usage: None,
+                generation_speed: None,
             },
         );
 
@@ -433,6 +571,9 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: true,
                 context_window_percent: None,
+                oss_server: None,
+                context_usage: None,
+                generation_speed: None,
             },
         );
 
@@ -444,6 +585,9 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                oss_server: None,
+                context_usage: None,
+                generation_speed: None,
             },
         );
 
@@ -455,6 +599,9 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                oss_server: None,
+                context_usage: None,
+                generation_speed: None,
             },
         );
 
@@ -466,7 +613,84 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: true,
                 context_window_percent: Some(72),
+                oss_server: None,
+                context_usage: None,
+                generation_speed: None,
             },
         );
+
+        snapshot_footer(
+            "footer_oss_server_loaded",
+            FooterProps {
+                mode: FooterMode::ShortcutSummary,
+                esc_backtrack_hint: false,
+                use_shift_enter_hint: false,
+                is_task_running: false,
+                context_window_percent: None,
+                oss_server: Some(OssServerBadge {
+                    backend: OssBackend::Ollama,
+                    state: OssServerState::ModelLoaded,
+                }),
+                context_usage: None,
+                generation_speed: None,
+            },
+        );
+
+        snapshot_footer(
+            "footer_generation_speed",
+            FooterProps {
+                mode: FooterMode::ContextOnly,
+                esc_backtrack_hint: false,
+                use_shift_enter_hint: false,
+                is_task_running: true,
+                context_window_percent: Some(90),
+                oss_server: None,
+                context_usage: None,
+                generation_speed: Some(GenerationSpeed {
+                    tokens_per_second: 42.3,
+                    time_to_first_token: Some(std::time::Duration::from_millis(1_400)),
+                }),
+            },
+        );
+
+        snapshot_footer(
+            "footer_context_gauge_compacting_soon",
+            FooterProps {
+                mode: FooterMode::ContextOnly,
+                esc_backtrack_hint: false,
+                use_shift_enter_hint: false,
+                is_task_running: true,
+                context_window_percent: Some(0),
+                oss_server: None,
+                context_usage: Some(ContextUsage {
+                    used_tokens: 6_900,
+                    window_tokens: 8_192,
+                    compact_at_tokens: Some(7_372),
+                }),
+                generation_speed: None,
+            },
+        );
+    }
+
+    #[test]
+    fn context_gauge_fills_with_usage() {
+        let usage = |used_tokens| ContextUsage {
+            used_tokens,
+            window_tokens: 8_192,
+            compact_at_tokens: None,
+        };
+        let text = |usage| -> String {
+            context_gauge_line(usage)
+                .spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect()
+        };
+        assert_eq!(text(usage(0)), "context ░░░░░░░░ 0 / 8.2k");
+        assert_eq!(text(usage(3_100)), "context ███░░░░░ 3.1k / 8.2k");
+        assert_eq!(
+            text(usage(8_000)),
+            "context ████████ 8.0k / 8.2k · context almost full"
+        );
     }
 }
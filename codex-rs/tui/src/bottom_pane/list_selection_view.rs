@@ -1,3 +1,4 @@
+use codex_common::fuzzy_match::fuzzy_match;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyModifiers;
@@ -51,6 +52,9 @@ pub(crate) struct SelectionViewParams {
     pub items: Vec<SelectionItem>,
     pub is_searchable: bool,
     pub search_placeholder: Option<String>,
+    /// Match the search query as a subsequence and rank items by how well
+    /// they match, instead of keeping items containing the query in order.
+    pub fuzzy_search: bool,
     pub header: Box<dyn Renderable>,
 }
 
@@ -63,6 +67,7 @@ impl Default for SelectionViewParams {
             items: Vec::new(),
             is_searchable: false,
             search_placeholder: None,
+            fuzzy_search: false,
             header: Box::new(()),
         }
     }
@@ -77,6 +82,7 @@ pub(crate) struct ListSelectionView {
     is_searchable: bool,
     search_query: String,
     search_placeholder: Option<String>,
+    fuzzy_search: bool,
     filtered_indices: Vec<usize>,
     last_selected_actual_idx: Option<usize>,
     header: Box<dyn Renderable>,
@@ -107,6 +113,7 @@ impl ListSelectionView {
             } else {
                 None
             },
+            fuzzy_search: params.fuzzy_search,
             filtered_indices: Vec::new(),
             last_selected_actual_idx: None,
             header,
@@ -134,7 +141,20 @@ impl ListSelectionView {
                     .flatten()
             });
 
-        if self.is_searchable && !self.search_query.is_empty() {
+        if self.is_searchable && self.fuzzy_search && !self.search_query.is_empty() {
+            let mut scored: Vec<(usize, i32)> = self
+                .items
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, item)| {
+                    let value = item.search_value.as_ref()?;
+                    fuzzy_match(value, &self.search_query).map(|(_, score)| (idx, score))
+                })
+                .collect();
+            // Stable, so equally good matches keep their order.
+            scored.sort_by_key(|(_, score)| *score);
+            self.filtered_indices = scored.into_iter().map(|(idx, _)| idx).collect();
+        } else if self.is_searchable && !self.search_query.is_empty() {
             let query_lower = self.search_query.to_lowercase();
             self.filtered_indices = self
                 .items
@@ -535,4 +555,42 @@ mod tests {
             "expected search query line to include rendered query, got {lines:?}"
         );
     }
+
+    #[test]
+    fn fuzzy_search_ranks_closest_matches_first() {
+        let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let items = ["qwen3-coder:30b", "gpt-oss:20b", "qwen3:8b"]
+            .into_iter()
+            .map(|name| SelectionItem {
+                name: name.to_string(),
+                search_value: Some(name.to_string()),
+                ..Default::default()
+            })
+            .collect();
+        let mut view = ListSelectionView::new(
+            SelectionViewParams {
+                items,
+                is_searchable: true,
+                fuzzy_search: true,
+                ..Default::default()
+            },
+            tx,
+        );
+        let names = |view: &ListSelectionView| -> Vec<String> {
+            view.filtered_indices
+                .iter()
+                .map(|idx| view.items[*idx].name.clone())
+                .collect()
+        };
+
+        view.set_search_query("q8".to_string());
+        assert_eq!(names(&view), vec!["qwen3:8b"]);
+
+        view.set_search_query("oss".to_string());
+        assert_eq!(names(&view), vec!["gpt-oss:20b"]);
+
+        view.set_search_query("qw3".to_string());
+        assert_eq!(names(&view), vec!["qwen3-coder:30b", "qwen3:8b"]);
+    }
 }
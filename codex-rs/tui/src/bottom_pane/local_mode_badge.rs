@@ -0,0 +1,109 @@
+//! Badge on the composer marking sessions that use a local model, so code
+//! meant for a local model is not sent to the cloud provider by mistake.
+
+use ratatui::style::Color;
+use ratatui::style::Style;
+use ratatui::style::Stylize as _;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use unicode_width::UnicodeWidthStr;
+
+const LABEL: &str = " LOCAL ";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LocalModeBadge {
+    model: String,
+    /// Where the server listens, e.g. "localhost:11434".
+    host: String,
+    color: Color,
+}
+
+impl LocalModeBadge {
+    /// `color` is the `tui.local_badge_color` setting; anything but one of
+    /// the style guide's ANSI colors falls back to magenta.
+    pub(crate) fn new(model: String, host: String, color: Option<&str>) -> Self {
+        let color = match color.map(str::to_ascii_lowercase).as_deref() {
+            Some("cyan") => Color::Cyan,
+            Some("green") => Color::Green,
+            Some("red") => Color::Red,
+            _ => Color::Magenta,
+        };
+        Self { model, host, color }
+    }
+
+    /// The label followed by the model and host, dropping the host and then
+    /// the model when they do not fit in `max_width`.
+    pub(crate) fn line(&self, max_width: usize) -> Line<'static> {
+        // Reversed, so the label text takes the terminal's background color.
+        let label = Span::styled(LABEL, Style::default().fg(self.color).reversed().bold());
+        let with_host = format!(" {} @ {}", self.model, self.host);
+        let model_only = format!(" {}", self.model);
+        let detail = [with_host, model_only]
+            .into_iter()
+            .find(|detail| LABEL.len() + detail.width() <= max_width);
+        match detail {
+            Some(detail) => Line::from(vec![
+                label,
+                Span::styled(detail, Style::default().fg(self.color)),
+            ]),
+            None => Line::from(label),
+        }
+    }
+}
+
+/// Host and port of `base_url`, e.g. "localhost:11434" for
+/// "http://localhost:11434/v1".
+pub(crate) fn display_host(base_url: &str) -> String {
+    let without_scheme = base_url
+        .split_once("://")
+        .map_or(base_url, |(_, rest)| rest);
+    without_scheme
+        .split('/')
+        .next()
+        .unwrap_or(without_scheme)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn text(line: Line<'static>) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn drops_details_that_do_not_fit() {
+        let badge = LocalModeBadge::new(
+            "gpt-oss:20b".to_string(),
+            "localhost:11434".to_string(),
+            None,
+        );
+        assert_eq!(
+            text(badge.line(80)),
+            " LOCAL  gpt-oss:20b @ localhost:11434"
+        );
+        assert_eq!(text(badge.line(20)), " LOCAL  gpt-oss:20b");
+        assert_eq!(text(badge.line(10)), " LOCAL ");
+    }
+
+    #[test]
+    fn color_falls_back_to_magenta() {
+        let badge = |color| LocalModeBadge::new(String::new(), String::new(), Some(color)).color;
+        assert_eq!(badge("cyan"), Color::Cyan);
+        assert_eq!(badge("Green"), Color::Green);
+        assert_eq!(badge("#8a2be2"), Color::Magenta);
+        assert_eq!(badge("yellow"), Color::Magenta);
+        assert_eq!(badge("not a color"), Color::Magenta);
+    }
+
+    #[test]
+    fn host_keeps_only_the_authority() {
+        assert_eq!(display_host("http://localhost:11434/v1"), "localhost:11434");
+        assert_eq!(display_host("127.0.0.1:1234"), "127.0.0.1:1234");
+    }
+}
@@ -25,8 +25,16 @@ pub mod custom_prompt_view;
 mod file_search_popup;
 mod footer;
 mod list_selection_view;
+mod local_mode_badge;
+mod oss_sampling_view;
 mod prompt_args;
+pub(crate) use footer::ContextUsage;
+pub(crate) use footer::OssServerBadge;
+pub(crate) use footer::OssServerState;
 pub(crate) use list_selection_view::SelectionViewParams;
+pub(crate) use local_mode_badge::LocalModeBadge;
+pub(crate) use local_mode_badge::display_host;
+pub(crate) use oss_sampling_view::OssSamplingView;
 mod feedback_view;
 mod paste_burst;
 pub mod popup_consts;
@@ -45,6 +53,11 @@ pub(crate) use chat_composer::ChatComposer;
 pub(crate) use chat_composer::InputResult;
 use codex_protocol::custom_prompts::CustomPrompt;
 
+use crate::generation_meter::GenerationSpeed;
+#[cfg(feature = "oss")]
+use crate::oss_memory::MemoryUsage;
+#[cfg(feature = "oss")]
+use crate::oss_memory::MemoryWidget;
 use crate::status_indicator_widget::StatusIndicatorWidget;
 pub(crate) use list_selection_view::SelectionAction;
 pub(crate) use list_selection_view::SelectionItem;
@@ -70,6 +83,9 @@ pub(crate) struct BottomPane {
     status: Option<StatusIndicatorWidget>,
     /// Queued user messages to show under the status indicator.
     queued_user_messages: Vec<String>,
+    /// Memory used by the local model, in `--oss` sessions.
+    #[cfg(feature = "oss")]
+    memory: Option<MemoryWidget>,
     context_window_percent: Option<i64>,
 }
 
@@ -102,6 +118,8 @@ impl BottomPane {
             ctrl_c_quit_hint: false,
             status: None,
             queued_user_messages: Vec::new(),
+            #[cfg(feature = "oss")]
+            memory: None,
             esc_backtrack_hint: false,
             context_window_percent: None,
         }
@@ -127,11 +145,10 @@ impl BottomPane {
         // Base height depends on whether a modal/overlay is active.
         let base = match self.active_view().as_ref() {
             Some(view) => view.desired_height(width),
-            None => self.composer.desired_height(width).saturating_add(
-                self.status
-                    .as_ref()
-                    .map_or(0, |status| status.desired_height(width)),
-            ),
+            None => self
+                .composer
+                .desired_height(width)
+                .saturating_add(self.above_composer_height(width)),
         };
         // Account for bottom padding rows. Top spacing is handled in layout().
         base.saturating_add(Self::BOTTOM_PAD_LINES)
@@ -156,9 +173,7 @@ impl BottomPane {
             Some(_) => [Rect::ZERO, area],
             None => {
                 let status_height = self
-                    .status
-                    .as_ref()
-                    .map_or(0, |status| status.desired_height(area.width))
+                    .above_composer_height(area.width)
                     .min(area.height.saturating_sub(1));
 
                 Layout::vertical([Constraint::Max(status_height), Constraint::Min(1)]).areas(area)
@@ -166,6 +181,29 @@ impl BottomPane {
         }
     }
 
+    /// Height of the status indicator and memory widget shown above the
+    /// composer.
+    fn above_composer_height(&self, width: u16) -> u16 {
+        let status = self
+            .status
+            .as_ref()
+            .map_or(0, |status| status.desired_height(width));
+        status.saturating_add(self.memory_height(width))
+    }
+
+    #[cfg(feature = "oss")]
+    fn memory_height(&self, width: u16) -> u16 {
+        self.memory
+            .as_ref()
+            .map_or(0, |memory| memory.desired_height(width))
+    }
+
+    /// A build without local backend support has no memory widget.
+    #[cfg(not(feature = "oss"))]
+    fn memory_height(&self, _width: u16) -> u16 {
+        0
+    }
+
     pub fn cursor_pos(&self, area: Rect) -> Option<(u16, u16)> {
         // Hide the cursor whenever an overlay view is active (e.g. the
         // status indicator shown while a task is running, or approval modal).
@@ -367,6 +405,44 @@ impl BottomPane {
         self.request_redraw();
     }
 
+    #[cfg_attr(not(feature = "oss"), allow(dead_code))]
+    pub(crate) fn set_oss_server(&mut self, badge: Option<OssServerBadge>) {
+        self.composer.set_oss_server(badge);
+        self.request_redraw();
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn set_memory_usage(&mut self, usage: MemoryUsage) {
+        self.memory.get_or_insert_default().set_usage(usage);
+        self.request_redraw();
+    }
+
+    /// Expand or collapse the memory widget. Returns false when there is none.
+    #[cfg(feature = "oss")]
+    pub(crate) fn toggle_memory_widget(&mut self) -> bool {
+        let Some(memory) = self.memory.as_mut() else {
+            return false;
+        };
+        memory.toggle_expanded();
+        self.request_redraw();
+        true
+    }
+
+    pub(crate) fn set_generation_speed(&mut self, speed: Option<GenerationSpeed>) {
+        self.composer.set_generation_speed(speed);
+        self.request_redraw();
+    }
+
+    pub(crate) fn set_context_usage(&mut self, usage: Option<ContextUsage>) {
+        self.composer.set_context_usage(usage);
+        self.request_redraw();
+    }
+
+    pub(crate) fn set_local_mode(&mut self, badge: Option<LocalModeBadge>) {
+        self.composer.set_local_mode(badge);
+        self.request_redraw();
+    }
+
     /// Show a generic list selection view with the provided items.
     pub(crate) fn show_selection_view(&mut self, params: list_selection_view::SelectionViewParams) {
         let view = list_selection_view::ListSelectionView::new(params, self.app_event_tx.clone());
@@ -514,10 +590,20 @@ impl WidgetRef for &BottomPane {
             view.render(content, buf);
         } else {
             // No active modal:
-            // If a status indicator is active, render it above the composer.
+            // If a status indicator is active, render it above the composer,
+            // followed by the memory widget of `--oss` sessions.
+            let memory_height = self.memory_height(status_area.width);
+            #[cfg_attr(not(feature = "oss"), allow(unused_variables))]
+            let [status_area, memory_area] =
+                Layout::vertical([Constraint::Min(0), Constraint::Max(memory_height)])
+                    .areas(status_area);
             if let Some(status) = &self.status {
                 status.render_ref(status_area, buf);
             }
+            #[cfg(feature = "oss")]
+            if let Some(memory) = &self.memory {
+                memory.render_ref(memory_area, buf);
+            }
 
             // Render the composer in the remaining area.
             self.composer.render_ref(content, buf);
@@ -0,0 +1,339 @@
+//! Form for the sampling parameters of the local `--oss` model, opened with
+//! `/sampling`. Saved values apply from the next turn and are written to the
+//! `[oss]` table of `config.toml`.
+
+use codex_core::protocol_config_types::OssSampling;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Block;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Widget;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::key_hint;
+use crate::render::Insets;
+use crate::render::RectExt as _;
+use crate::render::renderable::Renderable;
+use crate::style::user_message_style;
+
+use super::CancellationEvent;
+use super::bottom_pane_view::BottomPaneView;
+
+/// Width of the label column, so the values line up.
+const LABEL_WIDTH: usize = 13;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Temperature,
+    TopP,
+    MaxTokens,
+}
+
+const FIELDS: [Field; 3] = [Field::Temperature, Field::TopP, Field::MaxTokens];
+
+impl Field {
+    fn label(self) -> &'static str {
+        match self {
+            Field::Temperature => "Temperature",
+            Field::TopP => "Top P",
+            Field::MaxTokens => "Max tokens",
+        }
+    }
+
+    fn value(self, sampling: &OssSampling) -> Option<String> {
+        match self {
+            Field::Temperature => sampling.temperature.map(|value| value.to_string()),
+            Field::TopP => sampling.top_p.map(|value| value.to_string()),
+            Field::MaxTokens => sampling.max_tokens.map(|value| value.to_string()),
+        }
+    }
+
+    /// Step the value up or down. An unset value starts from a common
+    /// default rather than the server's, which is not reported.
+    fn adjust(self, sampling: &mut OssSampling, up: bool) {
+        match self {
+            Field::Temperature => {
+                sampling.temperature = Some(step(sampling.temperature, 0.7, 0.1, 0.0, 2.0, up));
+            }
+            Field::TopP => {
+                sampling.top_p = Some(step(sampling.top_p, 0.9, 0.05, 0.05, 1.0, up));
+            }
+            Field::MaxTokens => {
+                let next = match sampling.max_tokens {
+                    None => 4096,
+                    Some(current) if up => current.saturating_mul(2),
+                    Some(current) => current / 2,
+                };
+                sampling.max_tokens = Some(next.clamp(256, 131_072));
+            }
+        }
+    }
+
+    fn clear(self, sampling: &mut OssSampling) {
+        match self {
+            Field::Temperature => sampling.temperature = None,
+            Field::TopP => sampling.top_p = None,
+            Field::MaxTokens => sampling.max_tokens = None,
+        }
+    }
+}
+
+/// `current` moved by `delta` within `min..=max`, rounded to hundredths so
+/// repeated steps do not accumulate floating point noise in `config.toml`.
+fn step(current: Option<f64>, start: f64, delta: f64, min: f64, max: f64, up: bool) -> f64 {
+    let next = match current {
+        None => start,
+        Some(current) if up => current + delta,
+        Some(current) => current - delta,
+    };
+    (next.clamp(min, max) * 100.0).round() / 100.0
+}
+
+pub(crate) struct OssSamplingView {
+    model: String,
+    sampling: OssSampling,
+    selected: usize,
+    app_event_tx: AppEventSender,
+    complete: bool,
+}
+
+impl OssSamplingView {
+    pub(crate) fn new(model: String, sampling: OssSampling, app_event_tx: AppEventSender) -> Self {
+        Self {
+            model,
+            sampling,
+            selected: 0,
+            app_event_tx,
+            complete: false,
+        }
+    }
+
+    fn selected_field(&self) -> Field {
+        FIELDS[self.selected]
+    }
+
+    fn save(&mut self) {
+        self.app_event_tx
+            .send(AppEvent::UpdateOssSampling(self.sampling));
+        self.complete = true;
+    }
+
+    fn field_line(&self, index: usize, field: Field) -> Line<'static> {
+        let is_selected = index == self.selected;
+        let prefix = if is_selected { "› " } else { "  " };
+        let label = format!("{prefix}{:<LABEL_WIDTH$}", field.label());
+        let value: Span<'static> = match field.value(&self.sampling) {
+            Some(value) => value.into(),
+            None => "server default".dim(),
+        };
+        let label: Span<'static> = if is_selected {
+            label.cyan().bold()
+        } else {
+            label.into()
+        };
+        Line::from(vec![label, value])
+    }
+
+    fn hint_line() -> Line<'static> {
+        Line::from(vec![
+            key_hint::plain(KeyCode::Left).into(),
+            "/".into(),
+            key_hint::plain(KeyCode::Right).into(),
+            " adjust · ".into(),
+            key_hint::plain(KeyCode::Backspace).into(),
+            " server default · ".into(),
+            key_hint::plain(KeyCode::Enter).into(),
+            " save · ".into(),
+            key_hint::plain(KeyCode::Esc).into(),
+            " cancel".into(),
+        ])
+    }
+}
+
+impl BottomPaneView for OssSamplingView {
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.checked_sub(1).unwrap_or(FIELDS.len() - 1);
+            }
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => {
+                self.selected = (self.selected + 1) % FIELDS.len();
+            }
+            KeyCode::Left | KeyCode::Char('-') => {
+                self.selected_field().adjust(&mut self.sampling, false);
+            }
+            KeyCode::Right | KeyCode::Char('+') => {
+                self.selected_field().adjust(&mut self.sampling, true);
+            }
+            KeyCode::Backspace | KeyCode::Delete => {
+                self.selected_field().clear(&mut self.sampling);
+            }
+            KeyCode::Enter => self.save(),
+            KeyCode::Esc => {
+                self.on_ctrl_c();
+            }
+            _ => {}
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    fn on_ctrl_c(&mut self) -> CancellationEvent {
+        self.complete = true;
+        CancellationEvent::Handled
+    }
+}
+
+impl Renderable for OssSamplingView {
+    fn desired_height(&self, _width: u16) -> u16 {
+        // Title, subtitle, blank line, one row per field, the vertical
+        // padding, and the hint line.
+        3 + FIELDS.len() as u16 + 2 + 1
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 || area.width == 0 {
+            return;
+        }
+
+        let [content_area, footer_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(area);
+
+        Block::default()
+            .style(user_message_style())
+            .render(content_area, buf);
+
+        let [header_area, _, rows_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Length(FIELDS.len() as u16),
+        ])
+        .areas(content_area.inset(Insets::vh(1, 2)));
+        Paragraph::new(vec![
+            Line::from("Sampling parameters".bold()),
+            Line::from(format!("Applies from the next turn with {}", self.model).dim()),
+        ])
+        .render(header_area, buf);
+
+        // Like the selection list, the "›" marker sits in the left padding.
+        let rows_area = Rect {
+            x: rows_area.x.saturating_sub(2),
+            width: rows_area.width + 2,
+            ..rows_area
+        };
+        let rows: Vec<Line<'static>> = FIELDS
+            .into_iter()
+            .enumerate()
+            .map(|(index, field)| self.field_line(index, field))
+            .collect();
+        Paragraph::new(rows).render(rows_area, buf);
+
+        let hint_area = Rect {
+            x: footer_area.x + 2,
+            y: footer_area.y,
+            width: footer_area.width.saturating_sub(2),
+            height: footer_area.height,
+        };
+        Self::hint_line().dim().render(hint_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc::UnboundedReceiver;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn make_view(sampling: OssSampling) -> (OssSamplingView, UnboundedReceiver<AppEvent>) {
+        let (tx_raw, rx) = unbounded_channel::<AppEvent>();
+        let view = OssSamplingView::new(
+            "gpt-oss:20b".to_string(),
+            sampling,
+            AppEventSender::new(tx_raw),
+        );
+        (view, rx)
+    }
+
+    fn press(view: &mut OssSamplingView, code: KeyCode) {
+        view.handle_key_event(KeyEvent::from(code));
+    }
+
+    fn render_lines(view: &OssSamplingView) -> String {
+        let width = 80;
+        let area = Rect::new(0, 0, width, view.desired_height(width));
+        let mut buf = Buffer::empty(area);
+        view.render(area, &mut buf);
+        (0..area.height)
+            .map(|row| {
+                (0..area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn renders_current_values() {
+        let (view, _rx) = make_view(OssSampling {
+            temperature: Some(0.2),
+            top_p: None,
+            max_tokens: Some(4096),
+        });
+        assert_snapshot!("oss_sampling_view", render_lines(&view));
+    }
+
+    #[test]
+    fn adjusting_and_saving_sends_the_new_values() {
+        let (mut view, mut rx) = make_view(OssSampling {
+            temperature: Some(0.2),
+            top_p: None,
+            max_tokens: Some(4096),
+        });
+        press(&mut view, KeyCode::Right);
+        press(&mut view, KeyCode::Down);
+        press(&mut view, KeyCode::Left);
+        press(&mut view, KeyCode::Down);
+        press(&mut view, KeyCode::Backspace);
+        press(&mut view, KeyCode::Enter);
+
+        assert!(view.is_complete());
+        match rx.try_recv() {
+            Ok(AppEvent::UpdateOssSampling(sampling)) => assert_eq!(
+                sampling,
+                OssSampling {
+                    temperature: Some(0.3),
+                    top_p: Some(0.9),
+                    max_tokens: None,
+                }
+            ),
+            other => panic!("expected UpdateOssSampling, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn steps_stay_within_range() {
+        assert_eq!(step(Some(1.95), 0.7, 0.1, 0.0, 2.0, true), 2.0);
+        assert_eq!(step(Some(0.05), 0.7, 0.1, 0.0, 2.0, false), 0.0);
+        let mut sampling = OssSampling {
+            max_tokens: Some(256),
+            ..Default::default()
+        };
+        Field::MaxTokens.adjust(&mut sampling, false);
+        assert_eq!(sampling.max_tokens, Some(256));
+    }
+}
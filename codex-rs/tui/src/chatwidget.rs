@@ -2,9 +2,13 @@ use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
+use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
 use codex_core::config::Config;
 use codex_core::config_types::Notifications;
+#[cfg(feature = "oss")]
+use codex_core::features::Feature;
 use codex_core::git_info::current_branch_name;
 use codex_core::git_info::local_git_branches;
 use codex_core::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
@@ -16,6 +20,7 @@ use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::CloudReviewEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
@@ -42,7 +47,13 @@ use codex_core::protocol::UserMessageEvent;
 use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
+#[cfg(feature = "oss")]
+use codex_ollama::LocalModel;
+#[cfg(feature = "oss")]
+use codex_ollama::ServerLifecycleEvent;
 use codex_protocol::ConversationId;
+#[cfg(feature = "oss")]
+use codex_protocol::oss::ModelDownloadProgress;
 use codex_protocol::parse_command::ParsedCommand;
 use codex_protocol::user_input::UserInput;
 use crossterm::event::KeyCode;
@@ -70,25 +81,53 @@ use crate::bottom_pane::ApprovalRequest;
 use crate::bottom_pane::BottomPane;
 use crate::bottom_pane::BottomPaneParams;
 use crate::bottom_pane::CancellationEvent;
+use crate::bottom_pane::ContextUsage;
 use crate::bottom_pane::InputResult;
+use crate::bottom_pane::LocalModeBadge;
+use crate::bottom_pane::OssSamplingView;
+#[cfg(feature = "oss")]
+use crate::bottom_pane::OssServerBadge;
+#[cfg(feature = "oss")]
+use crate::bottom_pane::OssServerState;
 use crate::bottom_pane::SelectionAction;
 use crate::bottom_pane::SelectionItem;
 use crate::bottom_pane::SelectionViewParams;
 use crate::bottom_pane::custom_prompt_view::CustomPromptView;
+use crate::bottom_pane::display_host;
 use crate::bottom_pane::popup_consts::standard_popup_hint_line;
 use crate::clipboard_paste::paste_image_to_temp_png;
 use crate::diff_render::display_path_for;
 use crate::exec_cell::CommandOutput;
 use crate::exec_cell::ExecCell;
 use crate::exec_cell::new_active_exec_command;
+use crate::generation_meter::GenerationMeter;
+use crate::generation_meter::GenerationSpeed;
 use crate::get_git_diff::get_git_diff;
 use crate::history_cell;
 use crate::history_cell::AgentMessageCell;
 use crate::history_cell::HistoryCell;
 use crate::history_cell::McpToolCallCell;
 use crate::markdown::append_markdown;
+#[cfg(feature = "oss")]
+use crate::oss_compare::ComparedAnswer;
+#[cfg(feature = "oss")]
+use crate::oss_compare::LocalCompare;
+#[cfg(feature = "oss")]
+use crate::oss_error_card::OssErrorCard;
+#[cfg(feature = "oss")]
+use crate::oss_health::LocalServerReport;
+#[cfg(feature = "oss")]
+use crate::oss_health::LocalServerStatus;
+#[cfg(feature = "oss")]
+use crate::oss_health::ReconnectProgress;
+#[cfg(feature = "oss")]
+use crate::oss_memory::MemoryUsage;
+#[cfg(feature = "oss")]
+use crate::oss_models::DownloadEstimate;
+use crate::oss_phase::LocalPhase;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
+use crate::request_latency::RequestLatencyLog;
 use crate::slash_command::SlashCommand;
 use crate::status::RateLimitSnapshotDisplay;
 use crate::text_formatting::truncate_text;
@@ -112,6 +151,9 @@ use codex_core::AuthManager;
 use codex_core::ConversationManager;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
+#[cfg(feature = "oss")]
+use codex_core::protocol_config_types::OssBackend;
+use codex_core::protocol_config_types::OssSampling;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_file_search::FileMatch;
 use codex_git_tooling::CreateGhostCommitOptions;
@@ -124,6 +166,26 @@ use strum::IntoEnumIterator;
 
 const MAX_TRACKED_GHOST_COMMITS: usize = 20;
 
+/// Turns resumed in a row after the local server dropped, before the
+/// failure is reported instead.
+#[cfg(feature = "oss")]
+const MAX_LOCAL_RESUMES: u32 = 3;
+
+/// Sent to the model to resume a turn cut off by the local server dropping.
+#[cfg(feature = "oss")]
+const LOCAL_RESUME_PROMPT: &str = "The model server restarted while you were responding, so your last response was cut off. Continue where you left off.";
+
+/// The resume prompt, followed by the part of the answer that streamed
+/// before the drop. A cut-off answer is not recorded in the conversation, so
+/// without it the model would not know where it left off.
+#[cfg(feature = "oss")]
+fn local_resume_prompt(interrupted_answer: &str) -> String {
+    if interrupted_answer.trim().is_empty() {
+        return LOCAL_RESUME_PROMPT.to_string();
+    }
+    format!("{LOCAL_RESUME_PROMPT}\n\nYour response so far:\n\n{interrupted_answer}")
+}
+
 // Track information about an in-flight exec command.
 struct RunningCommand {
     command: Vec<String>,
@@ -229,6 +291,8 @@ pub(crate) struct ChatWidgetInit {
     pub(crate) feedback: codex_feedback::CodexFeedback,
 }
 
+// Without local backend support, some of the `--oss` state is only ever reset.
+#[cfg_attr(not(feature = "oss"), allow(dead_code))]
 pub(crate) struct ChatWidget {
     app_event_tx: AppEventSender,
     codex_op_tx: UnboundedSender<Op>,
@@ -239,6 +303,8 @@ pub(crate) struct ChatWidget {
     session_header: SessionHeader,
     initial_user_message: Option<UserMessage>,
     token_info: Option<TokenUsageInfo>,
+    // Session usage when the current turn started, to report what it used.
+    turn_start_usage: TokenUsage,
     rate_limit_snapshot: Option<RateLimitSnapshotDisplay>,
     rate_limit_warnings: RateLimitWarningState,
     // Stream lifecycle controller
@@ -277,6 +343,38 @@ pub(crate) struct ChatWidget {
     last_rendered_width: std::cell::Cell<Option<usize>>,
     // Feedback sink for /feedback
     feedback: codex_feedback::CodexFeedback,
+    // Last health check of the local `--oss` server, if it is monitored.
+    #[cfg(feature = "oss")]
+    local_server_status: Option<LocalServerStatus>,
+    // Streaming speed of the local model, shown in the footer of `--oss` sessions.
+    generation_meter: GenerationMeter,
+    // Whether the machine was low on memory at the last check, to warn once.
+    under_memory_pressure: bool,
+    // Set while the local server loads the model for the running turn, so
+    // the status shows loading rather than generating.
+    local_model_loading: bool,
+    // Context window the local server runs the model with, when detected.
+    local_context_window: Option<u64>,
+    // Set while waiting for the local server to come back after it dropped
+    // mid-turn; the interrupted turn resumes once it does.
+    reconnecting_local_server: bool,
+    // Set while the supervisor restarts a local server that crashed, so it
+    // going down does not open the recovery popup.
+    restarting_local_server: bool,
+    // Set while commands run in an `--oss` session and the App keeps the
+    // local model from being unloaded meanwhile.
+    keeping_local_model_loaded: bool,
+    // Turns resumed after reconnecting since the last one that completed,
+    // so a server that keeps dropping is not retried forever.
+    local_resumes: u32,
+    // The answer streamed so far in the current turn, sent back with the
+    // resume prompt if the local server drops before it completes.
+    interrupted_answer: String,
+    // Timings of recent requests to the local model, shown by `/latency`.
+    request_latency: RequestLatencyLog,
+    // Progress of an experimental `/compare` of two local models.
+    #[cfg(feature = "oss")]
+    local_compare: LocalCompare,
 }
 
 struct UserMessage {
@@ -350,12 +448,41 @@ impl ChatWidget {
         if self.stream_controller.is_none() {
             self.handle_streaming_delta(message);
         }
+        self.interrupted_answer.clear();
         self.flush_answer_stream_with_separator();
         self.handle_stream_finished();
         self.request_redraw();
     }
 
+    /// Attribute the turn's answer: approved as the local model drafted it,
+    /// or replaced by the cloud reviewer's.
+    fn on_cloud_review(&mut self, review: CloudReviewEvent) {
+        let CloudReviewEvent {
+            draft_model,
+            reviewer_model,
+            revised_message,
+        } = review;
+        match revised_message {
+            Some(message) => {
+                self.add_to_history(history_cell::new_info_event(
+                    format!("Revised by {reviewer_model} (cloud)"),
+                    Some(format!(
+                        "the answer above was drafted by {draft_model} (local)"
+                    )),
+                ));
+                self.on_agent_message(message);
+            }
+            None => self.add_to_history(history_cell::new_info_event(
+                format!("Approved by {reviewer_model} (cloud)"),
+                Some(format!("drafted by {draft_model} (local)")),
+            )),
+        }
+    }
+
     fn on_agent_message_delta(&mut self, delta: String) {
+        self.record_generated_chars(delta.len());
+        self.set_local_phase(LocalPhase::Streaming);
+        self.interrupted_answer.push_str(&delta);
         self.handle_streaming_delta(delta);
     }
 
@@ -363,11 +490,15 @@ impl ChatWidget {
         // For reasoning deltas, do not stream to history. Accumulate the
         // current reasoning block and extract the first bold element
         // (between **/**) as the chunk header. Show this header as status.
+        self.record_generated_chars(delta.len());
         self.reasoning_buffer.push_str(&delta);
 
         if let Some(header) = extract_first_bold(&self.reasoning_buffer) {
             // Update the shimmer header to the extracted reasoning chunk header.
             self.set_status_header(header);
+        } else if self.is_oss_session() {
+            // gpt-oss thinks in plain prose without headers.
+            self.set_local_phase(LocalPhase::Thinking);
         } else {
             // Fallback while we don't yet have a bold header: leave existing header as-is.
         }
@@ -405,15 +536,89 @@ impl ChatWidget {
         self.set_status_header(String::from("Working"));
         self.full_reasoning_buffer.clear();
         self.reasoning_buffer.clear();
+        self.interrupted_answer.clear();
+        self.generation_meter.on_turn_started(Instant::now());
+        self.turn_start_usage = self
+            .token_info
+            .as_ref()
+            .map(|info| info.total_token_usage.clone())
+            .unwrap_or_default();
+        self.maybe_show_local_model_loading();
+        if !self.local_model_loading {
+            self.set_local_phase(LocalPhase::WaitingForFirstToken);
+        }
         self.request_redraw();
     }
 
+    /// In `--oss` sessions, show which phase the turn is in instead of a
+    /// generic "Working".
+    fn set_local_phase(&mut self, phase: LocalPhase) {
+        if self.is_oss_session() {
+            self.set_status_header(phase.status_header());
+        }
+    }
+
+    /// In `--oss` sessions, the first request after the model was evicted
+    /// waits for the server to load it, which can take a while.
+    #[cfg(feature = "oss")]
+    fn maybe_show_local_model_loading(&mut self) {
+        let model = self.config.model.clone();
+        let needs_load = self.is_oss_session()
+            && self
+                .local_server_status
+                .as_ref()
+                .is_some_and(|status| status.needs_load(&model));
+        if !needs_load {
+            return;
+        }
+        self.local_model_loading = true;
+        self.set_status_header(
+            LocalPhase::Loading {
+                model: model.clone(),
+            }
+            .status_header(),
+        );
+        crate::oss_health::spawn_load_watch(&self.config, model, self.app_event_tx.clone());
+    }
+
+    /// A build without local backend support never waits for a local model.
+    #[cfg(not(feature = "oss"))]
+    fn maybe_show_local_model_loading(&mut self) {}
+
+    fn end_local_model_loading(&mut self) {
+        if std::mem::take(&mut self.local_model_loading) {
+            self.set_local_phase(LocalPhase::WaitingForFirstToken);
+        }
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn on_local_model_loaded(&mut self, model: &str) {
+        if let Some(status) = self.local_server_status.as_mut() {
+            status.mark_loaded(model);
+            let badge = status.badge(&self.config.model);
+            self.bottom_pane.set_oss_server(Some(badge));
+        }
+        if model == self.config.model {
+            self.end_local_model_loading();
+        }
+    }
+
     fn on_task_complete(&mut self, last_agent_message: Option<String>) {
         // If a stream is currently active, finalize it.
         self.flush_answer_stream_with_separator();
+        self.add_local_turn_usage();
+        // A finished turn shows the server answers again.
+        #[cfg(feature = "oss")]
+        if let Some(status) = &self.local_server_status {
+            let badge = status.badge(&self.config.model);
+            self.bottom_pane.set_oss_server(Some(badge));
+        }
         // Mark task stopped and request redraw now that all content is in history.
+        self.local_model_loading = false;
+        self.local_resumes = 0;
         self.bottom_pane.set_task_running(false);
         self.running_commands.clear();
+        self.sync_local_keep_alive();
         self.request_redraw();
 
         // If there is a queued user message, send exactly one now to begin the next turn.
@@ -424,6 +629,27 @@ impl ChatWidget {
         });
     }
 
+    /// In `--oss` sessions, report the tokens the turn used once it ends.
+    fn add_local_turn_usage(&mut self) {
+        if !self.is_oss_session() {
+            return;
+        }
+        let Some(info) = &self.token_info else {
+            return;
+        };
+        let total = &info.total_token_usage;
+        let prompt_tokens = total.input_tokens - self.turn_start_usage.input_tokens;
+        let completion_tokens = total.output_tokens - self.turn_start_usage.output_tokens;
+        if prompt_tokens <= 0 && completion_tokens <= 0 {
+            return;
+        }
+        self.add_to_history(history_cell::new_local_turn_usage(
+            prompt_tokens,
+            completion_tokens,
+            total.total_tokens,
+        ));
+    }
+
     pub(crate) fn set_token_info(&mut self, info: Option<TokenUsageInfo>) {
         if let Some(info) = info {
             let context_window = info
@@ -434,7 +660,60 @@ impl ChatWidget {
                     .percent_of_context_window_remaining(window)
             });
             self.bottom_pane.set_context_window_percent(percent);
+            let output_tokens = info.last_token_usage.output_tokens.max(0) as u64;
+            let speed = self
+                .generation_meter
+                .on_output_tokens(output_tokens, Instant::now());
+            self.show_generation_speed(speed);
             self.token_info = Some(info);
+            self.refresh_context_usage();
+        }
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn set_local_context_window(&mut self, window: Option<u64>) {
+        self.local_context_window = window;
+        self.refresh_context_usage();
+    }
+
+    /// In `--oss` sessions, gauge the tokens in context against the window
+    /// detected on the server, falling back to the one the model reports.
+    fn refresh_context_usage(&mut self) {
+        if !self.is_oss_session() {
+            return;
+        }
+        let to_tokens = |value: i64| u64::try_from(value).ok().filter(|tokens| *tokens > 0);
+        let window = self.local_context_window.or_else(|| {
+            self.token_info
+                .as_ref()
+                .and_then(|info| info.model_context_window)
+                .or(self.config.model_context_window)
+                .and_then(to_tokens)
+        });
+        let usage = window.map(|window_tokens| ContextUsage {
+            used_tokens: self
+                .token_info
+                .as_ref()
+                .and_then(|info| to_tokens(info.last_token_usage.tokens_in_context_window()))
+                .unwrap_or(0),
+            window_tokens,
+            compact_at_tokens: self
+                .config
+                .model_auto_compact_token_limit
+                .and_then(to_tokens),
+        });
+        self.bottom_pane.set_context_usage(usage);
+    }
+
+    fn record_generated_chars(&mut self, chars: usize) {
+        self.end_local_model_loading();
+        let speed = self.generation_meter.on_delta(chars, Instant::now());
+        self.show_generation_speed(speed);
+    }
+
+    fn show_generation_speed(&mut self, speed: Option<GenerationSpeed>) {
+        if self.is_oss_session() {
+            self.bottom_pane.set_generation_speed(speed);
         }
     }
 
@@ -476,11 +755,26 @@ impl ChatWidget {
         // Reset running state and clear streaming buffers.
         self.bottom_pane.set_task_running(false);
         self.running_commands.clear();
+        self.sync_local_keep_alive();
         self.stream_controller = None;
     }
 
-    fn on_error(&mut self, message: String) {
+    fn on_error(&mut self, message: String, connection_lost: bool) {
+        #[cfg(not(feature = "oss"))]
+        let _ = connection_lost;
+        #[cfg(feature = "oss")]
+        if self.is_oss_session() && connection_lost && self.local_resumes < MAX_LOCAL_RESUMES {
+            // Keep what streamed of the answer on screen; it is resent with
+            // the resume prompt.
+            self.flush_answer_stream_with_separator();
+            self.finalize_turn();
+            self.begin_local_reconnect(message);
+            return;
+        }
         self.finalize_turn();
+        #[cfg(feature = "oss")]
+        self.add_turn_error(message);
+        #[cfg(not(feature = "oss"))]
         self.add_to_history(history_cell::new_error_event(message));
         self.request_redraw();
 
@@ -488,6 +782,29 @@ impl ChatWidget {
         self.maybe_send_next_queued_input();
     }
 
+    /// In `--oss` sessions, explain the error with the steps that fix it, and
+    /// offer to recover a local server that keeps failing.
+    #[cfg(feature = "oss")]
+    fn add_turn_error(&mut self, message: String) {
+        let backend = self.config.oss.provider.unwrap_or(OssBackend::Ollama);
+        let unhealthy = self.is_oss_session() && crate::oss_health::is_backend_unhealthy(&message);
+        let card = self
+            .is_oss_session()
+            .then(|| OssErrorCard::from_message(&message, backend, &self.config.model, &[]))
+            .flatten();
+        match card {
+            Some(card) => self.add_to_history(history_cell::new_oss_error_card(card)),
+            None => self.add_to_history(history_cell::new_error_event(message)),
+        }
+        if unhealthy {
+            self.bottom_pane.set_oss_server(Some(OssServerBadge {
+                backend,
+                state: OssServerState::Unhealthy,
+            }));
+            self.open_server_unhealthy_popup(backend);
+        }
+    }
+
     /// Handle a turn aborted due to user interrupt (Esc).
     /// When there are queued user messages, restore them into the composer
     /// separated by newlines rather than auto‑submitting the next one.
@@ -496,12 +813,23 @@ impl ChatWidget {
         self.finalize_turn();
 
         if reason != TurnAbortReason::ReviewEnded {
-            self.add_to_history(history_cell::new_error_event(
-                "Conversation interrupted - tell the model what to do differently".to_owned(),
-            ));
+            // Interrupting closes the request, so a local server stops
+            // generating and is free for the next turn right away.
+            let message = if self.is_oss_session() {
+                "Generation stopped on the local server - tell the model what to do differently"
+            } else {
+                "Conversation interrupted - tell the model what to do differently"
+            };
+            self.add_to_history(history_cell::new_error_event(message.to_owned()));
         }
 
-        // If any messages were queued during the task, restore them into the composer.
+        self.restore_queued_messages_to_composer();
+
+        self.request_redraw();
+    }
+
+    /// Move messages queued during the task back into the composer.
+    fn restore_queued_messages_to_composer(&mut self) {
         if !self.queued_user_messages.is_empty() {
             let queued_text = self
                 .queued_user_messages
@@ -522,8 +850,6 @@ impl ChatWidget {
             self.queued_user_messages.clear();
             self.refresh_queued_user_messages();
         }
-
-        self.request_redraw();
     }
 
     fn on_plan_update(&mut self, update: UpdatePlanArgs) {
@@ -578,6 +904,8 @@ impl ChatWidget {
     }
 
     fn on_patch_apply_end(&mut self, event: codex_core::protocol::PatchApplyEndEvent) {
+        // Tool output goes back to the model in a new request.
+        self.set_local_phase(LocalPhase::WaitingForFirstToken);
         let ev2 = event.clone();
         self.defer_or_handle(
             |q| q.push_patch_end(event),
@@ -586,6 +914,7 @@ impl ChatWidget {
     }
 
     fn on_exec_command_end(&mut self, ev: ExecCommandEndEvent) {
+        self.set_local_phase(LocalPhase::WaitingForFirstToken);
         let ev2 = ev.clone();
         self.defer_or_handle(|q| q.push_exec_end(ev), |s| s.handle_exec_end_now(ev2));
     }
@@ -596,6 +925,7 @@ impl ChatWidget {
     }
 
     fn on_mcp_tool_call_end(&mut self, ev: McpToolCallEndEvent) {
+        self.set_local_phase(LocalPhase::WaitingForFirstToken);
         let ev2 = ev.clone();
         self.defer_or_handle(|q| q.push_mcp_end(ev), |s| s.handle_mcp_end_now(ev2));
     }
@@ -718,6 +1048,7 @@ impl ChatWidget {
 
     pub(crate) fn handle_exec_end_now(&mut self, ev: ExecCommandEndEvent) {
         let running = self.running_commands.remove(&ev.call_id);
+        self.sync_local_keep_alive();
         let (command, parsed) = match running {
             Some(rc) => (rc.command, rc.parsed_cmd),
             None => (vec![ev.call_id.clone()], Vec::new()),
@@ -814,6 +1145,7 @@ impl ChatWidget {
                 parsed_cmd: ev.parsed_cmd.clone(),
             },
         );
+        self.sync_local_keep_alive();
         if let Some(cell) = self
             .active_cell
             .as_mut()
@@ -917,7 +1249,7 @@ impl ChatWidget {
         let placeholder = EXAMPLE_PROMPTS[rng.random_range(0..EXAMPLE_PROMPTS.len())].to_string();
         let codex_op_tx = spawn_agent(config.clone(), app_event_tx.clone(), conversation_manager);
 
-        Self {
+        let mut widget = Self {
             app_event_tx: app_event_tx.clone(),
             frame_requester: frame_requester.clone(),
             codex_op_tx,
@@ -938,6 +1270,7 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            turn_start_usage: TokenUsage::default(),
             rate_limit_snapshot: None,
             rate_limit_warnings: RateLimitWarningState::default(),
             stream_controller: None,
@@ -957,9 +1290,25 @@ impl ChatWidget {
             ghost_snapshots: Vec::new(),
             ghost_snapshots_disabled: true,
             needs_final_message_separator: false,
+            #[cfg(feature = "oss")]
+            local_server_status: None,
+            generation_meter: GenerationMeter::default(),
+            under_memory_pressure: false,
+            local_model_loading: false,
+            local_context_window: None,
+            reconnecting_local_server: false,
+            restarting_local_server: false,
+            keeping_local_model_loaded: false,
+            local_resumes: 0,
+            interrupted_answer: String::new(),
+            request_latency: RequestLatencyLog::default(),
+            #[cfg(feature = "oss")]
+            local_compare: LocalCompare::default(),
             last_rendered_width: std::cell::Cell::new(None),
             feedback,
-        }
+        };
+        widget.refresh_local_mode_badge();
+        widget
     }
 
     /// Create a ChatWidget attached to an existing conversation (e.g., a fork).
@@ -984,7 +1333,7 @@ impl ChatWidget {
         let codex_op_tx =
             spawn_agent_from_existing(conversation, session_configured, app_event_tx.clone());
 
-        Self {
+        let mut widget = Self {
             app_event_tx: app_event_tx.clone(),
             frame_requester: frame_requester.clone(),
             codex_op_tx,
@@ -1005,6 +1354,7 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            turn_start_usage: TokenUsage::default(),
             rate_limit_snapshot: None,
             rate_limit_warnings: RateLimitWarningState::default(),
             stream_controller: None,
@@ -1024,9 +1374,25 @@ impl ChatWidget {
             ghost_snapshots: Vec::new(),
             ghost_snapshots_disabled: true,
             needs_final_message_separator: false,
+            #[cfg(feature = "oss")]
+            local_server_status: None,
+            generation_meter: GenerationMeter::default(),
+            under_memory_pressure: false,
+            local_model_loading: false,
+            local_context_window: None,
+            reconnecting_local_server: false,
+            restarting_local_server: false,
+            keeping_local_model_loaded: false,
+            local_resumes: 0,
+            interrupted_answer: String::new(),
+            request_latency: RequestLatencyLog::default(),
+            #[cfg(feature = "oss")]
+            local_compare: LocalCompare::default(),
             last_rendered_width: std::cell::Cell::new(None),
             feedback,
-        }
+        };
+        widget.refresh_local_mode_badge();
+        widget
     }
 
     pub fn desired_height(&self, width: u16) -> u16 {
@@ -1171,6 +1537,14 @@ impl ChatWidget {
                 self.open_review_popup();
             }
             SlashCommand::Model => {
+                #[cfg(feature = "oss")]
+                if self.is_oss_session() {
+                    crate::oss_models::spawn_load_local_models(
+                        self.config.clone(),
+                        self.app_event_tx.clone(),
+                    );
+                    return;
+                }
                 self.open_model_popup();
             }
             SlashCommand::Approvals => {
@@ -1211,6 +1585,57 @@ impl ChatWidget {
             SlashCommand::Status => {
                 self.add_status_output();
             }
+            #[cfg(feature = "oss")]
+            SlashCommand::Memory => {
+                if !self.bottom_pane.toggle_memory_widget() {
+                    self.add_info_message(
+                        "Memory usage is shown in --oss sessions once the local server reports it."
+                            .to_string(),
+                        None,
+                    );
+                }
+            }
+            #[cfg(feature = "oss")]
+            SlashCommand::OssStatus => {
+                if self.is_oss_session() {
+                    crate::oss_health::spawn_status_report(
+                        self.config.clone(),
+                        self.app_event_tx.clone(),
+                    );
+                } else {
+                    self.add_info_message(
+                        "/oss-status reports on the local server of --oss sessions.".to_string(),
+                        None,
+                    );
+                }
+            }
+            SlashCommand::Sampling => {
+                self.open_oss_sampling_view();
+            }
+            #[cfg(feature = "oss")]
+            SlashCommand::Eject => {
+                self.eject_local_model();
+            }
+            SlashCommand::Latency => {
+                self.open_request_latency();
+            }
+            #[cfg(feature = "oss")]
+            SlashCommand::Compare => {
+                self.start_local_compare();
+            }
+            #[cfg(not(feature = "oss"))]
+            SlashCommand::Memory
+            | SlashCommand::OssStatus
+            | SlashCommand::Eject
+            | SlashCommand::Compare => {
+                self.add_info_message(
+                    format!(
+                        "/{} needs a build with local backend support.",
+                        cmd.command()
+                    ),
+                    None,
+                );
+            }
             SlashCommand::Mcp => {
                 self.add_mcp_output();
             }
@@ -1302,6 +1727,13 @@ impl ChatWidget {
         if text.is_empty() && image_paths.is_empty() {
             return;
         }
+        #[cfg(feature = "oss")]
+        if let LocalCompare::Armed { .. } = self.local_compare
+            && !text.is_empty()
+        {
+            self.run_local_compare(text);
+            return;
+        }
 
         self.capture_ghost_snapshot();
 
@@ -1426,6 +1858,7 @@ impl ChatWidget {
         match msg {
             EventMsg::SessionConfigured(e) => self.on_session_configured(e),
             EventMsg::AgentMessage(AgentMessageEvent { message }) => self.on_agent_message(message),
+            EventMsg::CloudReview(review) => self.on_cloud_review(review),
             EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta }) => {
                 self.on_agent_message_delta(delta)
             }
@@ -1447,13 +1880,24 @@ impl ChatWidget {
                 self.set_token_info(ev.info);
                 self.on_rate_limit_snapshot(ev.rate_limits);
             }
-            EventMsg::Error(ErrorEvent { message }) => self.on_error(message),
+            EventMsg::RequestTiming(timing) => {
+                #[cfg(feature = "oss")]
+                if self.is_oss_session() {
+                    self.app_event_tx
+                        .send(AppEvent::RecordLocalPerformance(timing));
+                }
+                self.request_latency.record(timing);
+            }
+            EventMsg::Error(ErrorEvent {
+                message,
+                connection_lost,
+            }) => self.on_error(message, connection_lost),
             EventMsg::TurnAborted(ev) => match ev.reason {
                 TurnAbortReason::Interrupted => {
                     self.on_interrupted_turn(ev.reason);
                 }
                 TurnAbortReason::Replaced => {
-                    self.on_error("Turn aborted: replaced by a new task".to_owned())
+                    self.on_error("Turn aborted: replaced by a new task".to_owned(), false)
                 }
                 TurnAbortReason::ReviewEnded => {
                     self.on_interrupted_turn(ev.reason);
@@ -1684,6 +2128,589 @@ impl ChatWidget {
         });
     }
 
+    fn is_oss_session(&self) -> bool {
+        self.config.model_provider_id == BUILT_IN_OSS_MODEL_PROVIDER_ID
+    }
+
+    /// Open a popup listing the models on the local `--oss` server.
+    #[cfg(feature = "oss")]
+    pub(crate) fn open_local_model_popup(&mut self, models: Vec<LocalModel>) {
+        let items = crate::oss_models::local_model_items(
+            &models,
+            &self.config.model,
+            self.config.oss.provider,
+        );
+        if items.is_empty() {
+            self.add_info_message(
+                "No models are available on the local server.".to_string(),
+                Some("Download one with `codex models pull <model>`.".to_string()),
+            );
+            return;
+        }
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Select Local Model".to_string()),
+            subtitle: Some("Switch the local model for this and future --oss sessions".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to search models".to_string()),
+            fuzzy_search: true,
+            ..Default::default()
+        });
+    }
+
+    /// `/compare`: pick two local models to answer the next message.
+    #[cfg(feature = "oss")]
+    fn start_local_compare(&mut self) {
+        if !self.config.features.enabled(Feature::LocalModelCompare) {
+            self.add_info_message(
+                "/compare is experimental.".to_string(),
+                Some(
+                    "Enable it with `local_model_compare = true` under [features] in config.toml."
+                        .to_string(),
+                ),
+            );
+            return;
+        }
+        if !self.is_oss_session() {
+            self.add_info_message(
+                "/compare compares the local models of --oss sessions.".to_string(),
+                None,
+            );
+            return;
+        }
+        crate::oss_compare::spawn_load_compare_models(
+            self.config.clone(),
+            self.app_event_tx.clone(),
+        );
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn on_local_compare_models_loaded(&mut self, models: Vec<String>) {
+        if models.len() < 2 {
+            self.add_info_message(
+                "Comparing needs two models on the local server.".to_string(),
+                Some("Download another with `codex models pull <model>`.".to_string()),
+            );
+            return;
+        }
+        self.local_compare = LocalCompare::Choosing {
+            models,
+            first: None,
+        };
+        self.show_local_compare_picker();
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn on_local_compare_model_selected(&mut self, model: String) {
+        let LocalCompare::Choosing { models, first } = std::mem::take(&mut self.local_compare)
+        else {
+            return;
+        };
+        match first {
+            None => {
+                self.local_compare = LocalCompare::Choosing {
+                    models,
+                    first: Some(model),
+                };
+                self.show_local_compare_picker();
+            }
+            Some(first) => {
+                self.add_info_message(
+                    format!("Your next message goes to {first} and {model}; answers appear side by side."),
+                    Some("The models see only that message and cannot run tools.".to_string()),
+                );
+                self.local_compare = LocalCompare::Armed {
+                    models: [first, model],
+                };
+            }
+        }
+    }
+
+    #[cfg(feature = "oss")]
+    fn show_local_compare_picker(&mut self) {
+        let LocalCompare::Choosing { models, first } = &self.local_compare else {
+            return;
+        };
+        let items = models
+            .iter()
+            .filter(|model| Some(*model) != first.as_ref())
+            .map(|model| {
+                let selected = model.clone();
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::SelectLocalCompareModel(selected.clone()));
+                })];
+                SelectionItem {
+                    name: model.clone(),
+                    is_current: *model == self.config.model,
+                    actions,
+                    dismiss_on_select: true,
+                    search_value: Some(model.clone()),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        let subtitle = match first {
+            None => "Choose the first model".to_string(),
+            Some(first) => format!("Choose the model to compare with {first}"),
+        };
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Compare Local Models".to_string()),
+            subtitle: Some(subtitle),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to search models".to_string()),
+            fuzzy_search: true,
+            ..Default::default()
+        });
+    }
+
+    #[cfg(feature = "oss")]
+    fn run_local_compare(&mut self, text: String) {
+        let LocalCompare::Armed { models } =
+            std::mem::replace(&mut self.local_compare, LocalCompare::Running)
+        else {
+            return;
+        };
+        self.add_to_history(history_cell::new_user_prompt(text.clone()));
+        self.bottom_pane.set_task_running(true);
+        self.set_status_header(format!("Comparing {} and {}", models[0], models[1]));
+        crate::oss_compare::spawn_compare(
+            self.config.clone(),
+            models,
+            text,
+            self.app_event_tx.clone(),
+        );
+        self.request_redraw();
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn on_local_compare_finished(&mut self, answers: Vec<ComparedAnswer>) {
+        // Answers that arrive after the comparison was cancelled are dropped.
+        if self.local_compare != LocalCompare::Running {
+            return;
+        }
+        self.local_compare = LocalCompare::Off;
+        self.add_to_history(history_cell::LocalComparisonCell::new(answers));
+        self.bottom_pane.set_task_running(false);
+        self.maybe_send_next_queued_input();
+        self.request_redraw();
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn is_comparing_local_models(&self) -> bool {
+        self.local_compare == LocalCompare::Running
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn cancel_local_compare(&mut self) {
+        self.local_compare = LocalCompare::Off;
+        self.bottom_pane.set_task_running(false);
+        self.add_info_message("Comparison cancelled.".to_string(), None);
+        self.restore_queued_messages_to_composer();
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn set_local_server_status(&mut self, status: LocalServerStatus) {
+        let badge = status.badge(&self.config.model);
+        let went_down = !status.reachable
+            && self
+                .local_server_status
+                .as_ref()
+                .is_none_or(|previous| previous.reachable);
+        let came_back = status.reachable
+            && self
+                .local_server_status
+                .as_ref()
+                .is_some_and(|previous| !previous.reachable);
+        let backend = status.backend;
+        let needs_load = status.needs_load(&self.config.model);
+        self.local_server_status = Some(status);
+        self.bottom_pane.set_oss_server(Some(badge));
+        // While reconnecting, the server coming back resumes the turn; while
+        // restarting, the supervisor reports the outcome.
+        if self.reconnecting_local_server || self.restarting_local_server {
+            return;
+        }
+        if went_down {
+            self.open_server_unreachable_popup(backend);
+        } else if came_back {
+            self.add_info_message(format!("The local {backend} server is back."), None);
+            // Load the model now rather than on the next message.
+            if needs_load && !self.bottom_pane.is_task_running() {
+                self.app_event_tx.send(AppEvent::PreloadLocalModel);
+            }
+        }
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn set_local_memory_usage(&mut self, usage: MemoryUsage) {
+        let under_pressure = usage.is_under_pressure();
+        if under_pressure
+            && !self.under_memory_pressure
+            && let Some(host) = usage.host
+        {
+            self.add_info_message(
+                format!(
+                    "⚠ The machine is low on memory ({} of {} free); responses may slow down as it swaps.",
+                    codex_ollama::format_size(host.available_bytes),
+                    codex_ollama::format_size(host.total_bytes)
+                ),
+                Some("Switch to a smaller model or quantization with /model.".to_string()),
+            );
+        }
+        self.under_memory_pressure = under_pressure;
+        self.bottom_pane.set_memory_usage(usage);
+    }
+
+    /// Offer to start the local server, check it again, or switch to the cloud.
+    #[cfg(feature = "oss")]
+    pub(crate) fn open_server_unreachable_popup(&mut self, backend: OssBackend) {
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(format!("The local {backend} server is not responding")),
+            subtitle: Some(
+                "Start it, check again, or continue with the cloud provider".to_string(),
+            ),
+            footer_hint: Some(standard_popup_hint_line()),
+            items: crate::oss_server_prompt::recovery_items(backend),
+            ..Default::default()
+        });
+    }
+
+    /// Offer the same recovery when the server keeps failing requests and
+    /// Codex paused sending it more.
+    #[cfg(feature = "oss")]
+    fn open_server_unhealthy_popup(&mut self, backend: OssBackend) {
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(format!("The local {backend} server keeps failing")),
+            subtitle: Some(
+                "Restart it, check again, or continue with the cloud provider".to_string(),
+            ),
+            footer_hint: Some(standard_popup_hint_line()),
+            items: crate::oss_server_prompt::recovery_items(backend),
+            ..Default::default()
+        });
+    }
+
+    /// Keep the transcript and wait for the local server after it dropped
+    /// in the middle of a turn, instead of ending the session's work there.
+    #[cfg(feature = "oss")]
+    fn begin_local_reconnect(&mut self, message: String) {
+        let backend = self.config.oss.provider.unwrap_or(OssBackend::Ollama);
+        tracing::warn!("lost the local {backend} server mid-turn: {message}");
+        self.reconnecting_local_server = true;
+        self.bottom_pane.set_task_running(true);
+        self.set_status_header(format!("Waiting for the local {backend} server"));
+        self.add_info_message(
+            format!(
+                "Lost the connection to the local {backend} server. The conversation is kept and resumes once the server is back."
+            ),
+            Some("Press Esc to stop waiting.".to_string()),
+        );
+        self.app_event_tx.send(AppEvent::ReconnectLocalServer);
+    }
+
+    pub(crate) fn is_task_running(&self) -> bool {
+        self.bottom_pane.is_task_running()
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn is_reconnecting_local_server(&self) -> bool {
+        self.reconnecting_local_server
+    }
+
+    /// Stop waiting for the local server and hand the queued messages back.
+    #[cfg(feature = "oss")]
+    pub(crate) fn cancel_local_reconnect(&mut self) {
+        if !std::mem::take(&mut self.reconnecting_local_server) {
+            return;
+        }
+        self.app_event_tx.send(AppEvent::CancelLocalReconnect);
+        self.bottom_pane.set_task_running(false);
+        self.restore_queued_messages_to_composer();
+        self.add_info_message(
+            "Stopped waiting for the local server.".to_string(),
+            Some("Send a message to continue once it is back.".to_string()),
+        );
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn on_local_server_reconnect(&mut self, progress: ReconnectProgress) {
+        if !self.reconnecting_local_server {
+            return;
+        }
+        let backend = self.config.oss.provider.unwrap_or(OssBackend::Ollama);
+        match progress {
+            ReconnectProgress::Waiting { attempt } => {
+                self.set_status_header(format!(
+                    "Waiting for the local {backend} server (attempt {attempt})"
+                ));
+            }
+            ReconnectProgress::ReloadingModel => {
+                let model = self.config.model.clone();
+                self.set_status_header(format!("Reloading {model}"));
+            }
+            ReconnectProgress::Reconnected => {
+                self.reconnecting_local_server = false;
+                self.local_resumes += 1;
+                self.add_info_message(
+                    format!(
+                        "Reconnected to the local {backend} server; resuming the interrupted turn."
+                    ),
+                    None,
+                );
+                let text = local_resume_prompt(&std::mem::take(&mut self.interrupted_answer));
+                self.submit_op(Op::UserInput {
+                    items: vec![UserInput::Text { text }],
+                });
+            }
+            ReconnectProgress::Failed(err) => {
+                self.reconnecting_local_server = false;
+                self.bottom_pane.set_task_running(false);
+                self.restore_queued_messages_to_composer();
+                self.add_error_message(format!(
+                    "Stopped waiting for the local {backend} server: {err}"
+                ));
+                self.open_server_unreachable_popup(backend);
+            }
+        }
+        self.request_redraw();
+    }
+
+    /// In `--oss` sessions, ask the App to keep the local model loaded while
+    /// commands run, so a long build or test run does not leave the next
+    /// request waiting for the model to load again.
+    #[cfg(feature = "oss")]
+    fn sync_local_keep_alive(&mut self) {
+        let keep = self.is_oss_session() && !self.running_commands.is_empty();
+        if keep != self.keeping_local_model_loaded {
+            self.keeping_local_model_loaded = keep;
+            self.app_event_tx.send(AppEvent::KeepLocalModelLoaded(keep));
+        }
+    }
+
+    /// A build without local backend support has no local model to keep.
+    #[cfg(not(feature = "oss"))]
+    fn sync_local_keep_alive(&mut self) {}
+
+    /// Progress of the supervisor restarting a local server that crashed.
+    #[cfg(feature = "oss")]
+    pub(crate) fn on_local_server_lifecycle(&mut self, event: ServerLifecycleEvent) {
+        let backend = self.config.oss.provider.unwrap_or(OssBackend::Ollama);
+        match event {
+            ServerLifecycleEvent::Exited { pid } => {
+                self.restarting_local_server = true;
+                self.add_info_message(
+                    format!("The local {backend} server (pid {pid}) exited unexpectedly."),
+                    None,
+                );
+            }
+            ServerLifecycleEvent::Restarting {
+                attempt,
+                max_attempts,
+                delay,
+            } => {
+                self.restarting_local_server = true;
+                self.add_info_message(
+                    format!(
+                        "Restarting the local {backend} server in {}s (attempt {attempt} of {max_attempts})…",
+                        delay.as_secs()
+                    ),
+                    None,
+                );
+            }
+            ServerLifecycleEvent::Restarted { pid } => {
+                self.restarting_local_server = false;
+                let pid = pid.map(|pid| format!(" (pid {pid})")).unwrap_or_default();
+                self.add_info_message(
+                    format!(
+                        "Restarted the local {backend} server{pid} and loaded {}.",
+                        self.config.model
+                    ),
+                    None,
+                );
+            }
+            ServerLifecycleEvent::GaveUp { attempts, error } => {
+                self.restarting_local_server = false;
+                self.add_error_message(format!(
+                    "Gave up restarting the local {backend} server after {attempts} attempts: {error}"
+                ));
+                if !self.reconnecting_local_server {
+                    self.open_server_unreachable_popup(backend);
+                }
+            }
+        }
+        self.request_redraw();
+    }
+
+    /// Result of an attempt to bring the local server back.
+    #[cfg(feature = "oss")]
+    pub(crate) fn on_local_server_recovery(&mut self, result: Result<LocalServerStatus, String>) {
+        match result {
+            Ok(status) if status.reachable => {
+                self.add_info_message(
+                    format!("The local {} server is responding again.", status.backend),
+                    None,
+                );
+                self.set_local_server_status(status);
+            }
+            Ok(status) => {
+                let backend = status.backend;
+                self.set_local_server_status(status);
+                self.add_error_message(format!(
+                    "The local {backend} server is still not responding."
+                ));
+                self.open_server_unreachable_popup(backend);
+            }
+            Err(err) => {
+                let backend = self.config.oss.provider.unwrap_or(OssBackend::Ollama);
+                self.add_error_message(format!("Failed to start the local server: {err}"));
+                self.open_server_unreachable_popup(backend);
+            }
+        }
+    }
+
+    /// A model downloading in the background finished: offer to switch to it.
+    #[cfg(feature = "oss")]
+    pub(crate) fn on_local_model_downloaded(
+        &mut self,
+        model: String,
+        result: Result<(), OssErrorCard>,
+    ) {
+        if let Err(card) = result {
+            self.add_oss_error_card(card);
+            return;
+        }
+        self.add_info_message(format!("Finished downloading {model}"), None);
+        let switch_to = model.clone();
+        let switch: Vec<SelectionAction> = vec![Box::new(move |tx| {
+            tx.send(AppEvent::SwitchToDownloadedModel(switch_to.clone()));
+        })];
+        let items = vec![
+            SelectionItem {
+                name: format!("Switch to {model}"),
+                actions: switch,
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Not now".to_string(),
+                description: Some("Keep the current model".to_string()),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(format!("{model} is ready")),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    /// Show that a local model is being downloaded and loaded.
+    /// Ask before a model switch downloads several gigabytes, showing the
+    /// size, the free disk space, and how long the download may take.
+    #[cfg(feature = "oss")]
+    pub(crate) fn open_local_model_download_confirmation(&mut self, estimate: DownloadEstimate) {
+        let size = codex_ollama::format_size(estimate.size_bytes);
+        let disk_line = match estimate.free_disk_bytes {
+            Some(free) if estimate.exceeds_free_disk() => Line::from(vec![
+                "Disk space      ".dim(),
+                format!(
+                    "only {} free, not enough for the download",
+                    codex_ollama::format_size(free)
+                )
+                .red(),
+            ]),
+            Some(free) => Line::from(vec![
+                "Disk space      ".dim(),
+                format!(
+                    "{} free, {} after the download",
+                    codex_ollama::format_size(free),
+                    codex_ollama::format_size(free - estimate.size_bytes)
+                )
+                .into(),
+            ]),
+            None => Line::from(vec!["Disk space      ".dim(), "unknown".dim()]),
+        };
+        let mut header = ColumnRenderable::new();
+        header.push(Line::from(format!("Download {}?", estimate.model)).bold());
+        header.push(Line::from(vec!["Download size   ".dim(), size.into()]));
+        header.push(disk_line);
+        header.push(Line::from(vec![
+            "Estimated time  ".dim(),
+            format!(
+                "about {} at 100 Mbit/s",
+                crate::oss_startup::format_eta(estimate.estimated_seconds())
+            )
+            .into(),
+        ]));
+
+        let model = estimate.model;
+        let download_model = model.clone();
+        let download_actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+            tx.send(AppEvent::PrepareLocalModel(download_model.clone()));
+        })];
+        let remember_actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+            tx.send(AppEvent::PersistModelDownloadConfirmationHidden);
+            tx.send(AppEvent::PrepareLocalModel(model.clone()));
+        })];
+        let items = vec![
+            SelectionItem {
+                name: "Download".to_string(),
+                description: Some("Start the download now".to_string()),
+                actions: download_actions,
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Download and don't ask again".to_string(),
+                description: Some("Remember this choice".to_string()),
+                actions: remember_actions,
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Cancel".to_string(),
+                description: Some(format!("Keep using {}", self.config.model)),
+                actions: Vec::new(),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            header: Box::new(header),
+            ..Default::default()
+        });
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn on_local_model_preparing(&mut self, model: &str) {
+        self.bottom_pane.set_task_running(true);
+        self.bottom_pane.update_status_header(
+            LocalPhase::Loading {
+                model: model.to_string(),
+            }
+            .status_header(),
+        );
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn on_local_model_download_progress(&mut self, progress: &ModelDownloadProgress) {
+        self.bottom_pane
+            .update_status_header(LocalPhase::downloading(progress).status_header());
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn on_local_model_prepared(&mut self) {
+        self.bottom_pane.set_task_running(false);
+    }
+
     /// Open a popup to choose the reasoning effort (stage 2) for the given model.
     pub(crate) fn open_reasoning_popup(&mut self, preset: ModelPreset) {
         let default_effort: ReasoningEffortConfig = preset.default_reasoning_effort;
@@ -1764,6 +2791,7 @@ impl ChatWidget {
                     model: Some(model_for_action.clone()),
                     effort: Some(effort_for_action),
                     summary: None,
+                    oss_sampling: None,
                 }));
                 tx.send(AppEvent::UpdateModel(model_for_action.clone()));
                 tx.send(AppEvent::UpdateReasoningEffort(effort_for_action));
@@ -1862,6 +2890,7 @@ impl ChatWidget {
                 model: None,
                 effort: None,
                 summary: None,
+                oss_sampling: None,
             }));
             tx.send(AppEvent::UpdateAskForApprovalPolicy(approval));
             tx.send(AppEvent::UpdateSandboxPolicy(sandbox_clone));
@@ -1955,6 +2984,121 @@ impl ChatWidget {
     pub(crate) fn set_model(&mut self, model: &str) {
         self.session_header.set_model(model);
         self.config.model = model.to_string();
+        #[cfg(feature = "oss")]
+        if let Some(status) = &self.local_server_status {
+            self.bottom_pane.set_oss_server(Some(status.badge(model)));
+        }
+        self.refresh_local_mode_badge();
+    }
+
+    /// Open the form for the sampling parameters of the local model.
+    fn open_oss_sampling_view(&mut self) {
+        if !self.is_oss_session() {
+            self.add_info_message(
+                "Sampling parameters apply to the local model of --oss sessions.".to_string(),
+                None,
+            );
+            return;
+        }
+        self.bottom_pane.show_view(Box::new(OssSamplingView::new(
+            self.config.model.clone(),
+            self.config.oss.sampling,
+            self.app_event_tx.clone(),
+        )));
+    }
+
+    /// Unload the local model so another program can use the GPU; the
+    /// session stays open and its next turn loads the model again.
+    #[cfg(feature = "oss")]
+    fn eject_local_model(&mut self) {
+        if !self.is_oss_session() {
+            self.add_info_message(
+                "/eject unloads the local model of --oss sessions.".to_string(),
+                None,
+            );
+            return;
+        }
+        crate::oss_models::spawn_eject_local_model(self.config.clone(), self.app_event_tx.clone());
+    }
+
+    /// Record that `model` left the local server's memory.
+    #[cfg(feature = "oss")]
+    pub(crate) fn on_local_model_unloaded(&mut self, model: &str) {
+        if let Some(status) = self.local_server_status.as_mut() {
+            status.mark_unloaded(model);
+            let badge = status.badge(&self.config.model);
+            self.bottom_pane.set_oss_server(Some(badge));
+        }
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn on_local_model_ejected(&mut self, model: &str, result: Result<(), String>) {
+        match result {
+            Ok(()) => {
+                self.on_local_model_unloaded(model);
+                self.add_info_message(
+                    format!("Unloaded {model} and freed its memory."),
+                    Some("The next message loads it again.".to_string()),
+                );
+            }
+            Err(err) => self.add_error_message(format!("Failed to unload {model}: {err}")),
+        }
+    }
+
+    fn open_request_latency(&mut self) {
+        if !self.is_oss_session() {
+            self.add_info_message(
+                "/latency reports on requests to the local model of --oss sessions.".to_string(),
+                None,
+            );
+            return;
+        }
+        if self.request_latency.is_empty() {
+            self.add_info_message(
+                "No requests to the local model yet; their latency is shown once a turn has run."
+                    .to_string(),
+                None,
+            );
+            return;
+        }
+        self.app_event_tx.send(AppEvent::ShowRequestLatency(
+            self.request_latency.overlay_lines(&self.config.model),
+        ));
+    }
+
+    #[cfg(feature = "oss")]
+    pub(crate) fn on_local_server_report(&mut self, result: Result<LocalServerReport, String>) {
+        match result {
+            Ok(report) => {
+                self.add_to_history(history_cell::new_oss_status_output(report));
+                self.request_redraw();
+            }
+            Err(err) => self.add_error_message(format!("Failed to query the local server: {err}")),
+        }
+    }
+
+    pub(crate) fn set_oss_sampling(&mut self, sampling: OssSampling) {
+        self.config.oss.sampling = sampling;
+    }
+
+    /// Mark the composer with the local model and its host in `--oss`
+    /// sessions.
+    fn refresh_local_mode_badge(&mut self) {
+        let badge = self.is_oss_session().then(|| {
+            let base_url = self
+                .config
+                .oss
+                .base_url
+                .as_deref()
+                .or(self.config.model_provider.base_url.as_deref())
+                .unwrap_or_default();
+            LocalModeBadge::new(
+                self.config.model.clone(),
+                display_host(base_url),
+                self.config.tui_local_badge_color.as_deref(),
+            )
+        });
+        self.bottom_pane.set_local_mode(badge);
     }
 
     pub(crate) fn add_info_message(&mut self, message: String, hint: Option<String>) {
@@ -1967,6 +3111,12 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    #[cfg(feature = "oss")]
+    pub(crate) fn add_oss_error_card(&mut self, card: OssErrorCard) {
+        self.add_to_history(history_cell::new_oss_error_card(card));
+        self.request_redraw();
+    }
+
     pub(crate) fn add_mcp_output(&mut self) {
         if self.config.mcp_servers.is_empty() {
             self.add_to_history(history_cell::empty_mcp_output());
@@ -1986,6 +3136,18 @@ impl ChatWidget {
             return;
         }
 
+        #[cfg(feature = "oss")]
+        if self.reconnecting_local_server {
+            self.cancel_local_reconnect();
+            return;
+        }
+
+        #[cfg(feature = "oss")]
+        if self.is_comparing_local_models() {
+            self.cancel_local_compare();
+            return;
+        }
+
         if self.bottom_pane.is_task_running() {
             self.bottom_pane.show_ctrl_c_quit_hint();
             self.submit_op(Op::Interrupt);
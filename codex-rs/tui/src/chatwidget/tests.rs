@@ -16,6 +16,8 @@ use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningDeltaEvent;
 use codex_core::protocol::AgentReasoningEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::CloudReviewEvent;
+use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
@@ -27,6 +29,7 @@ use codex_core::protocol::InputMessageKind;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::RequestTimingEvent;
 use codex_core::protocol::ReviewCodeLocation;
 use codex_core::protocol::ReviewFinding;
 use codex_core::protocol::ReviewLineRange;
@@ -271,6 +274,7 @@ fn make_chatwidget_manual() -> (
         session_header: SessionHeader::new(cfg.model),
         initial_user_message: None,
         token_info: None,
+        turn_start_usage: TokenUsage::default(),
         rate_limit_snapshot: None,
         rate_limit_warnings: RateLimitWarningState::default(),
         stream_controller: None,
@@ -291,6 +295,20 @@ fn make_chatwidget_manual() -> (
         ghost_snapshots: Vec::new(),
         ghost_snapshots_disabled: false,
         needs_final_message_separator: false,
+        #[cfg(feature = "oss")]
+        local_server_status: None,
+        generation_meter: GenerationMeter::default(),
+        under_memory_pressure: false,
+        local_model_loading: false,
+        local_context_window: None,
+        reconnecting_local_server: false,
+        restarting_local_server: false,
+        keeping_local_model_loaded: false,
+        local_resumes: 0,
+        interrupted_answer: String::new(),
+        request_latency: RequestLatencyLog::default(),
+        #[cfg(feature = "oss")]
+        local_compare: LocalCompare::default(),
         last_rendered_width: std::cell::Cell::new(None),
         feedback: codex_feedback::CodexFeedback::new(),
     };
@@ -1101,6 +1119,33 @@ fn approvals_selection_popup_snapshot() {
     assert_snapshot!("approvals_selection_popup", popup);
 }
 
+#[cfg(feature = "oss")]
+#[test]
+fn local_model_download_confirmation_popup_snapshot() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.model = "gpt-oss:20b".to_string();
+
+    chat.open_local_model_download_confirmation(DownloadEstimate {
+        model: "qwen3-coder:30b".to_string(),
+        size_bytes: 13_780_162_353,
+        free_disk_bytes: Some(50 * 1024 * 1024 * 1024),
+    });
+    let popup = render_bottom_popup(&chat, 80);
+    assert_snapshot!("local_model_download_confirmation_popup", popup);
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let mut events = Vec::new();
+    while let Ok(ev) = rx.try_recv() {
+        match ev {
+            AppEvent::PersistModelDownloadConfirmationHidden => events.push("persist".to_string()),
+            AppEvent::PrepareLocalModel(model) => events.push(model),
+            _ => {}
+        }
+    }
+    assert_eq!(events, vec!["persist", "qwen3-coder:30b"]);
+}
+
 #[test]
 fn full_access_confirmation_popup_snapshot() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
@@ -1523,6 +1568,136 @@ fn interrupt_restores_queued_messages_into_composer() {
     let _ = drain_insert_history(&mut rx);
 }
 
+#[test]
+fn interrupting_a_local_turn_stops_generation_on_the_server() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
+    chat.config.model_provider_id = codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+    chat.bottom_pane.set_task_running(true);
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+    assert_matches!(op_rx.try_recv(), Ok(Op::Interrupt));
+
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
+            reason: TurnAbortReason::Interrupted,
+        }),
+    });
+    let history = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        history.contains("Generation stopped on the local server"),
+        "unexpected history: {history}"
+    );
+}
+
+#[cfg(feature = "oss")]
+#[test]
+fn local_server_coming_back_preloads_the_model() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.model_provider_id = codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+    chat.set_local_server_status(LocalServerStatus {
+        backend: OssBackend::Ollama,
+        reachable: false,
+        loaded_models: None,
+    });
+    chat.set_local_server_status(LocalServerStatus {
+        backend: OssBackend::Ollama,
+        reachable: true,
+        loaded_models: Some(Vec::new()),
+    });
+
+    let mut history = String::new();
+    let mut preloaded = false;
+    while let Ok(ev) = rx.try_recv() {
+        match ev {
+            AppEvent::InsertHistoryCell(cell) => {
+                history.push_str(&lines_to_single_string(&cell.display_lines(80)));
+            }
+            AppEvent::PreloadLocalModel => preloaded = true,
+            _ => {}
+        }
+    }
+    assert!(
+        history.contains("The local ollama server is back."),
+        "unexpected history: {history}"
+    );
+    assert!(preloaded, "expected PreloadLocalModel to be sent");
+}
+
+#[cfg(feature = "oss")]
+#[test]
+fn a_crashed_local_server_is_restarted_without_the_recovery_popup() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.model_provider_id = codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+    chat.set_local_server_status(LocalServerStatus {
+        backend: OssBackend::Ollama,
+        reachable: true,
+        loaded_models: None,
+    });
+
+    chat.on_local_server_lifecycle(ServerLifecycleEvent::Exited { pid: 4242 });
+    chat.on_local_server_lifecycle(ServerLifecycleEvent::Restarting {
+        attempt: 1,
+        max_attempts: 3,
+        delay: std::time::Duration::from_secs(1),
+    });
+    chat.set_local_server_status(LocalServerStatus {
+        backend: OssBackend::Ollama,
+        reachable: false,
+        loaded_models: None,
+    });
+    assert!(
+        chat.bottom_pane.is_normal_backtrack_mode(),
+        "the server going down mid-restart should not open the recovery popup"
+    );
+    chat.on_local_server_lifecycle(ServerLifecycleEvent::Restarted { pid: Some(4343) });
+
+    let history = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    for expected in [
+        "The local ollama server (pid 4242) exited unexpectedly.",
+        "Restarting the local ollama server in 1s (attempt 1 of 3)",
+        "Restarted the local ollama server (pid 4343)",
+    ] {
+        assert!(history.contains(expected), "unexpected history: {history}");
+    }
+    assert!(!chat.restarting_local_server);
+}
+
+#[cfg(feature = "oss")]
+#[test]
+fn ejecting_the_local_model_keeps_the_session_and_reloads_next_turn() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.model_provider_id = codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+    let model = chat.config.model.clone();
+    chat.set_local_server_status(LocalServerStatus {
+        backend: OssBackend::Ollama,
+        reachable: true,
+        loaded_models: Some(vec![model.clone()]),
+    });
+
+    chat.on_local_model_ejected(&model, Ok(()));
+
+    let history = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        history.contains(&format!("Unloaded {model}")),
+        "unexpected history: {history}"
+    );
+    assert!(
+        chat.local_server_status
+            .as_ref()
+            .is_some_and(|status| status.needs_load(&model))
+    );
+}
+
 #[test]
 fn interrupt_prepends_queued_messages_before_existing_composer_text() {
     let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
@@ -2196,6 +2371,45 @@ fn multiple_agent_messages_in_single_turn_emit_multiple_headers() {
     assert!(first_idx < second_idx, "messages out of order: {combined}");
 }
 
+#[test]
+fn cloud_review_attributes_the_answer_to_both_models() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.handle_codex_event(Event {
+        id: "s1".into(),
+        msg: EventMsg::CloudReview(CloudReviewEvent {
+            draft_model: "gpt-oss:20b".into(),
+            reviewer_model: "gpt-5-codex".into(),
+            revised_message: None,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "s2".into(),
+        msg: EventMsg::CloudReview(CloudReviewEvent {
+            draft_model: "gpt-oss:20b".into(),
+            reviewer_model: "gpt-5-codex".into(),
+            revised_message: Some("Use the existing retry helper instead.".into()),
+        }),
+    });
+
+    let combined: String = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect();
+    assert!(
+        combined.contains("Approved by gpt-5-codex (cloud)"),
+        "missing approval: {combined}"
+    );
+    assert!(
+        combined.contains("Revised by gpt-5-codex (cloud)"),
+        "missing revision header: {combined}"
+    );
+    assert!(
+        combined.contains("Use the existing retry helper instead."),
+        "missing revised answer: {combined}"
+    );
+}
+
 #[test]
 fn final_reasoning_then_message_without_deltas_are_rendered() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
@@ -2452,3 +2666,440 @@ printf 'fenced within fenced\n'
 
     assert_snapshot!(term.backend().vt100().screen().contents());
 }
+
+#[cfg(feature = "oss")]
+#[test]
+fn finished_background_download_offers_to_switch() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.on_local_model_downloaded("gpt-oss:20b".to_string(), Ok(()));
+    let popup = render_bottom_popup(&chat, 80);
+    assert!(
+        popup.contains("gpt-oss:20b is ready"),
+        "expected the switch popup, got {popup:?}"
+    );
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let mut switched = false;
+    while let Ok(ev) = rx.try_recv() {
+        if let AppEvent::SwitchToDownloadedModel(model) = ev {
+            assert_eq!(model, "gpt-oss:20b");
+            switched = true;
+        }
+    }
+    assert!(switched, "expected SwitchToDownloadedModel to be sent");
+}
+
+#[test]
+fn oss_turn_errors_render_next_steps() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.model_provider_id = codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+    chat.config.model = "gpt-oss:20b".to_string();
+
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::Error(ErrorEvent {
+            message: "model requires more system memory (13.4 GiB) than is available (8.2 GiB)"
+                .to_string(),
+            connection_lost: false,
+        }),
+    });
+
+    let cells = drain_insert_history(&mut rx);
+    let rendered = cells
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert_snapshot!("oss_turn_error_card", rendered);
+}
+
+#[cfg(feature = "oss")]
+#[test]
+fn dropped_local_server_reconnects_and_resumes_the_turn() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
+    chat.config.model_provider_id = codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+    chat.config.model = "gpt-oss:20b".to_string();
+    chat.config.oss.provider = Some(OssBackend::LmStudio);
+
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: "The bug is in the".into(),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::Error(ErrorEvent {
+            message: "stream disconnected before completion: error decoding response body"
+                .to_string(),
+            connection_lost: true,
+        }),
+    });
+    assert!(chat.is_reconnecting_local_server());
+    assert!(chat.bottom_pane.is_task_running());
+    let mut reconnect_requested = false;
+    while let Ok(ev) = rx.try_recv() {
+        if matches!(ev, AppEvent::ReconnectLocalServer) {
+            reconnect_requested = true;
+        }
+    }
+    assert!(
+        reconnect_requested,
+        "expected ReconnectLocalServer to be sent"
+    );
+
+    // The health monitor noticing the outage does not offer recovery while
+    // the reconnect is underway.
+    chat.set_local_server_status(LocalServerStatus {
+        backend: OssBackend::LmStudio,
+        reachable: false,
+        loaded_models: None,
+    });
+    let popup = render_bottom_popup(&chat, 80);
+    assert!(
+        !popup.contains("is not responding"),
+        "expected no recovery popup, got {popup:?}"
+    );
+
+    chat.on_local_server_reconnect(ReconnectProgress::Reconnected);
+    assert!(!chat.is_reconnecting_local_server());
+    let mut resumed = false;
+    while let Ok(op) = op_rx.try_recv() {
+        if let Op::UserInput { items } = op {
+            assert_eq!(
+                items,
+                vec![UserInput::Text {
+                    text: format!(
+                        "{LOCAL_RESUME_PROMPT}\n\nYour response so far:\n\nThe bug is in the"
+                    ),
+                }]
+            );
+            resumed = true;
+        }
+    }
+    assert!(resumed, "expected the interrupted turn to be resumed");
+}
+
+#[cfg(feature = "oss")]
+#[test]
+fn cancelling_a_local_reconnect_restores_queued_messages() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.model_provider_id = codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::Error(ErrorEvent {
+            message: "error sending request for url: Connection refused".to_string(),
+            connection_lost: true,
+        }),
+    });
+    chat.queued_user_messages
+        .push_back(UserMessage::from("keep going".to_string()));
+
+    chat.on_ctrl_c();
+    assert!(!chat.is_reconnecting_local_server());
+    assert!(!chat.bottom_pane.is_task_running());
+    assert_eq!(chat.bottom_pane.composer_text(), "keep going");
+    let mut cancelled = false;
+    while let Ok(ev) = rx.try_recv() {
+        if matches!(ev, AppEvent::CancelLocalReconnect) {
+            cancelled = true;
+        }
+    }
+    assert!(cancelled, "expected CancelLocalReconnect to be sent");
+}
+
+#[cfg(feature = "oss")]
+#[test]
+fn failing_local_server_is_reported_unhealthy_with_a_way_out() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.model_provider_id = codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+    chat.config.oss.provider = Some(OssBackend::LmStudio);
+
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::Error(ErrorEvent {
+            message: "The local lmstudio server looks unhealthy: 3 requests in a row failed. Requests to it are paused; try again in 30s.".to_string(),
+            connection_lost: false,
+        }),
+    });
+    // Requests are paused, so there is nothing to reconnect to.
+    assert!(!chat.is_reconnecting_local_server());
+
+    let rendered = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        rendered.contains("The local lmstudio server keeps failing"),
+        "expected the error card, got {rendered:?}"
+    );
+    let popup = render_bottom_popup(&chat, 80);
+    assert!(
+        popup.contains("Switch to cloud provider"),
+        "expected the recovery popup, got {popup:?}"
+    );
+}
+
+#[test]
+fn latency_overlay_lists_timings_of_local_requests() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.model_provider_id = codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+
+    chat.dispatch_command(SlashCommand::Latency);
+    assert!(
+        !drain_insert_history(&mut rx).is_empty(),
+        "expected a note that nothing was measured yet"
+    );
+
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::RequestTiming(RequestTimingEvent {
+            connect_ms: 4,
+            prefill_ms: Some(850),
+            time_to_first_token_ms: Some(854),
+            generation_ms: Some(1_000),
+            output_tokens: 31,
+            ..Default::default()
+        }),
+    });
+    chat.dispatch_command(SlashCommand::Latency);
+    match rx.try_recv() {
+        Ok(AppEvent::ShowRequestLatency(lines)) => {
+            let rows: Vec<String> = lines.iter().map(ToString::to_string).collect();
+            assert_eq!(
+                rows.last().map(String::as_str),
+                Some(
+                    "   1             –           –        4 ms      850 ms      854 ms        0 ms        30.0          31"
+                )
+            );
+        }
+        other => panic!("expected ShowRequestLatency, got {other:?}"),
+    }
+}
+
+#[cfg(feature = "oss")]
+#[test]
+fn only_local_request_timings_are_recorded() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    let timing = RequestTimingEvent {
+        connect_ms: 4,
+        prefill_ms: Some(850),
+        time_to_first_token_ms: Some(854),
+        generation_ms: Some(1_000),
+        output_tokens: 31,
+        ..Default::default()
+    };
+    let send = |chat: &mut ChatWidget| {
+        chat.handle_codex_event(Event {
+            id: "turn".into(),
+            msg: EventMsg::RequestTiming(timing),
+        });
+    };
+
+    send(&mut chat);
+    assert!(rx.try_recv().is_err(), "cloud requests are not recorded");
+
+    // The app records their throughput only when `oss.performance_telemetry`
+    // is on, but accounts for their energy in every local session.
+    chat.config.model_provider_id = codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+    send(&mut chat);
+    assert_matches!(
+        rx.try_recv(),
+        Ok(AppEvent::RecordLocalPerformance(recorded)) if recorded == timing
+    );
+}
+
+#[test]
+fn local_turns_report_the_tokens_they_used() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.model_provider_id = codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+    let usage = |input_tokens: i64, output_tokens: i64| TokenUsage {
+        input_tokens,
+        output_tokens,
+        total_tokens: input_tokens + output_tokens,
+        ..TokenUsage::default()
+    };
+    let mut run_turn = |total: TokenUsage, last: TokenUsage| {
+        chat.handle_codex_event(Event {
+            id: "turn".into(),
+            msg: EventMsg::TaskStarted(TaskStartedEvent {
+                model_context_window: None,
+            }),
+        });
+        chat.handle_codex_event(Event {
+            id: "turn".into(),
+            msg: EventMsg::TokenCount(codex_core::protocol::TokenCountEvent {
+                info: Some(TokenUsageInfo {
+                    total_token_usage: total,
+                    last_token_usage: last,
+                    model_context_window: None,
+                }),
+                rate_limits: None,
+            }),
+        });
+        chat.handle_codex_event(Event {
+            id: "turn".into(),
+            msg: EventMsg::TaskComplete(TaskCompleteEvent {
+                last_agent_message: None,
+            }),
+        });
+        drain_insert_history(&mut rx)
+            .iter()
+            .map(|lines| lines_to_single_string(lines))
+            .collect::<String>()
+    };
+
+    let first = run_turn(usage(1_200, 7), usage(1_200, 7));
+    assert!(
+        first.contains("1,200 prompt · 7 completion tokens · 1,207 this session"),
+        "unexpected history: {first}"
+    );
+    let second = run_turn(usage(2_500, 40), usage(1_300, 33));
+    assert!(
+        second.contains("1,300 prompt · 33 completion tokens · 2,540 this session"),
+        "unexpected history: {second}"
+    );
+}
+
+#[test]
+fn local_turn_status_follows_each_phase() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    chat.config.model_provider_id = codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::TaskStarted(TaskStartedEvent {
+            model_context_window: None,
+        }),
+    });
+    assert_eq!(chat.current_status_header, "Waiting for the first token");
+
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
+            delta: "Plan the reply.".to_string(),
+        }),
+    });
+    assert_eq!(chat.current_status_header, "Thinking");
+
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: "Hello".to_string(),
+        }),
+    });
+    assert_eq!(chat.current_status_header, "Responding");
+}
+
+#[test]
+fn local_reasoning_shows_thinking_and_stays_out_of_the_answer() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.model_provider_id = codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::TaskStarted(TaskStartedEvent {
+            model_context_window: None,
+        }),
+    });
+
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
+            delta: "The user greets me; reply briefly.".to_string(),
+        }),
+    });
+    assert_eq!(chat.current_status_header, "Thinking");
+
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::AgentReasoning(AgentReasoningEvent {
+            text: "The user greets me; reply briefly.".to_string(),
+        }),
+    });
+    // Like cloud reasoning, the thinking is collapsed into the transcript.
+    let visible = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(!visible.contains("greets"), "unexpected history: {visible}");
+}
+
+#[cfg(feature = "oss")]
+#[test]
+fn compare_sends_the_next_message_to_both_local_models() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
+    chat.config.model_provider_id = codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+    chat.config.features.enable(Feature::LocalModelCompare);
+
+    chat.on_local_compare_models_loaded(vec![
+        "gpt-oss:20b".to_string(),
+        "qwen3:8b".to_string(),
+        "llama3.2:3b".to_string(),
+    ]);
+    chat.on_local_compare_model_selected("gpt-oss:20b".to_string());
+    chat.on_local_compare_model_selected("qwen3:8b".to_string());
+    assert_eq!(
+        chat.local_compare,
+        LocalCompare::Armed {
+            models: ["gpt-oss:20b".to_string(), "qwen3:8b".to_string()],
+        }
+    );
+
+    chat.bottom_pane
+        .set_composer_text("How should I cache parsed files?".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert!(chat.is_comparing_local_models());
+    assert!(chat.bottom_pane.is_task_running());
+    assert!(
+        op_rx.try_recv().is_err(),
+        "the compared message must not start a turn"
+    );
+    let _ = drain_insert_history(&mut rx);
+
+    chat.on_local_compare_finished(vec![
+        ComparedAnswer {
+            model: "gpt-oss:20b".to_string(),
+            result: Err("HTTP 500".to_string()),
+        },
+        ComparedAnswer {
+            model: "qwen3:8b".to_string(),
+            result: Err("HTTP 500".to_string()),
+        },
+    ]);
+    assert!(!chat.bottom_pane.is_task_running());
+    assert_eq!(chat.local_compare, LocalCompare::Off);
+    let history = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        history.contains("Compared local models"),
+        "unexpected history: {history}"
+    );
+}
+
+#[cfg(feature = "oss")]
+#[test]
+fn the_local_model_is_kept_loaded_while_commands_run() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    let keep_alive_requests = |rx: &mut tokio::sync::mpsc::UnboundedReceiver<AppEvent>| {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .filter_map(|event| match event {
+                AppEvent::KeepLocalModelLoaded(keep) => Some(keep),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    begin_exec(&mut chat, "cloud", "cargo test");
+    end_exec(&mut chat, "cloud", "", "", 0);
+    assert_eq!(keep_alive_requests(&mut rx), Vec::<bool>::new());
+
+    chat.config.model_provider_id = codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string();
+    begin_exec(&mut chat, "build", "cargo build");
+    begin_exec(&mut chat, "test", "cargo test");
+    end_exec(&mut chat, "build", "", "", 0);
+    assert_eq!(keep_alive_requests(&mut rx), vec![true]);
+    end_exec(&mut chat, "test", "", "", 0);
+    assert_eq!(keep_alive_requests(&mut rx), vec![false]);
+}
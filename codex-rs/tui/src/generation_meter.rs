@@ -0,0 +1,117 @@
+//! Generation speed and time to first token, shown in the footer of `--oss`
+//! sessions so users can compare models and quantizations.
+
+use std::time::Duration;
+use std::time::Instant;
+
+/// Rough characters per token, used to estimate the speed while a response
+/// streams, before the backend reports how many tokens it generated.
+const CHARS_PER_TOKEN: f64 = 4.0;
+
+/// Shortest stretch of streaming used for an estimate; shorter ones are
+/// dominated by network jitter.
+const MIN_ESTIMATE_WINDOW: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct GenerationSpeed {
+    pub(crate) tokens_per_second: f64,
+    /// Time from the start of the turn to the first streamed token.
+    pub(crate) time_to_first_token: Option<Duration>,
+}
+
+/// Tracks the streaming of one turn. A turn may span several model requests
+/// when tools are called; each stretch of streaming is measured on its own.
+#[derive(Debug, Default)]
+pub(crate) struct GenerationMeter {
+    turn_started: Option<Instant>,
+    time_to_first_token: Option<Duration>,
+    stream_started: Option<Instant>,
+    streamed_chars: usize,
+    last: Option<GenerationSpeed>,
+}
+
+impl GenerationMeter {
+    pub(crate) fn on_turn_started(&mut self, now: Instant) {
+        self.turn_started = Some(now);
+        self.time_to_first_token = None;
+        self.stream_started = None;
+        self.streamed_chars = 0;
+    }
+
+    /// Record streamed text and return the current estimate.
+    pub(crate) fn on_delta(&mut self, chars: usize, now: Instant) -> Option<GenerationSpeed> {
+        if self.time_to_first_token.is_none()
+            && let Some(started) = self.turn_started
+        {
+            self.time_to_first_token = Some(now.saturating_duration_since(started));
+        }
+        let Some(stream_started) = self.stream_started else {
+            // The first delta only marks the start of the stream.
+            self.stream_started = Some(now);
+            return self.last;
+        };
+        self.streamed_chars += chars;
+        let elapsed = now.saturating_duration_since(stream_started);
+        if elapsed >= MIN_ESTIMATE_WINDOW {
+            self.last = Some(GenerationSpeed {
+                tokens_per_second: self.streamed_chars as f64
+                    / CHARS_PER_TOKEN
+                    / elapsed.as_secs_f64(),
+                time_to_first_token: self.time_to_first_token,
+            });
+        }
+        self.last
+    }
+
+    /// Replace the estimate with the token count the backend reported for
+    /// the request that just finished streaming.
+    pub(crate) fn on_output_tokens(
+        &mut self,
+        output_tokens: u64,
+        now: Instant,
+    ) -> Option<GenerationSpeed> {
+        if let Some(stream_started) = self.stream_started.take() {
+            let elapsed = now.saturating_duration_since(stream_started);
+            if output_tokens > 0 && elapsed >= MIN_ESTIMATE_WINDOW {
+                self.last = Some(GenerationSpeed {
+                    tokens_per_second: output_tokens as f64 / elapsed.as_secs_f64(),
+                    time_to_first_token: self.time_to_first_token,
+                });
+            }
+        }
+        self.streamed_chars = 0;
+        self.last
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn estimates_while_streaming_then_uses_reported_tokens() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut meter = GenerationMeter::default();
+        meter.on_turn_started(start);
+
+        assert_eq!(meter.on_delta(4, at(1_500)), None);
+        // Too soon after the first delta for an estimate.
+        assert_eq!(meter.on_delta(40, at(1_600)), None);
+        assert_eq!(
+            meter.on_delta(360, at(2_500)),
+            Some(GenerationSpeed {
+                tokens_per_second: 100.0,
+                time_to_first_token: Some(Duration::from_millis(1_500)),
+            })
+        );
+        assert_eq!(
+            meter.on_output_tokens(60, at(3_500)),
+            Some(GenerationSpeed {
+                tokens_per_second: 30.0,
+                time_to_first_token: Some(Duration::from_millis(1_500)),
+            })
+        );
+    }
+}
@@ -8,6 +8,14 @@ use crate::exec_cell::spinner;
 use crate::exec_command::relativize_to_home;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::markdown::append_markdown;
+#[cfg(feature = "oss")]
+use crate::oss_compare::ComparedAnswer;
+#[cfg(feature = "oss")]
+use crate::oss_error_card::OssErrorCard;
+#[cfg(feature = "oss")]
+use crate::oss_health::LocalServerReport;
+#[cfg(feature = "oss")]
+use crate::oss_memory::MemoryUsage;
 use crate::render::line_utils::line_to_static;
 use crate::render::line_utils::prefix_lines;
 use crate::render::line_utils::push_owned_lines;
@@ -29,7 +37,10 @@ use codex_core::protocol::FileChange;
 use codex_core::protocol::McpAuthStatus;
 use codex_core::protocol::McpInvocation;
 use codex_core::protocol::SessionConfiguredEvent;
+#[cfg(feature = "oss")]
+use codex_core::protocol_config_types::OssBackend;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_protocol::num_format::format_with_separators;
 use codex_protocol::plan_tool::PlanItemArg;
 use codex_protocol::plan_tool::StepStatus;
 use codex_protocol::plan_tool::UpdatePlanArgs;
@@ -1204,6 +1215,225 @@ pub(crate) fn new_error_event(message: String) -> PlainHistoryCell {
     PlainHistoryCell { lines }
 }
 
+/// Tokens a turn of the local `--oss` model used, reported by the server.
+pub(crate) fn new_local_turn_usage(
+    prompt_tokens: i64,
+    completion_tokens: i64,
+    session_tokens: i64,
+) -> PlainHistoryCell {
+    let line = format!(
+        "  └ {} prompt · {} completion tokens · {} this session",
+        format_with_separators(prompt_tokens),
+        format_with_separators(completion_tokens),
+        format_with_separators(session_tokens),
+    );
+    PlainHistoryCell {
+        lines: vec![line.dim().into()],
+    }
+}
+
+/// A failure of the local `--oss` provider with the steps that fix it.
+#[cfg(feature = "oss")]
+pub(crate) fn new_oss_error_card(card: OssErrorCard) -> PlainHistoryCell {
+    let OssErrorCard {
+        title,
+        detail,
+        next_steps,
+    } = card;
+    let mut lines: Vec<Line<'static>> = vec![vec![format!("■ {title}").red()].into()];
+    if let Some(detail) = detail {
+        lines.push(vec!["  ".into(), detail.dim()].into());
+    }
+    if !next_steps.is_empty() {
+        lines.push("".into());
+        lines.push("  Next steps".bold().into());
+        for step in next_steps {
+            lines.push(vec!["  • ".dim(), step.into()].into());
+        }
+    }
+    PlainHistoryCell { lines }
+}
+
+/// Gap between the two columns of a [`LocalComparisonCell`].
+#[cfg(feature = "oss")]
+const COMPARISON_SEPARATOR: &str = " │ ";
+
+/// Answers of two local models to the same prompt from `/compare`, side by
+/// side.
+#[cfg(feature = "oss")]
+#[derive(Debug)]
+pub(crate) struct LocalComparisonCell {
+    answers: Vec<ComparedAnswer>,
+}
+
+#[cfg(feature = "oss")]
+impl LocalComparisonCell {
+    pub(crate) fn new(answers: Vec<ComparedAnswer>) -> Self {
+        Self { answers }
+    }
+
+    /// Model name, timing, and the answer wrapped to `width`.
+    fn column(answer: &ComparedAnswer, width: usize) -> Vec<Line<'static>> {
+        let mut lines: Vec<Line<'static>> = vec![answer.model.clone().bold().into()];
+        match &answer.result {
+            Ok(reply) => {
+                let mut stats = format!("{:.1} s", reply.elapsed.as_secs_f64());
+                if let Some(speed) = reply.tokens_per_sec() {
+                    stats.push_str(&format!(" · {speed:.0} tok/s"));
+                }
+                lines.extend(word_wrap_lines(&[Line::from(stats.dim())], width));
+                lines.push("".into());
+                let mut answer_lines = Vec::new();
+                append_markdown(&reply.text, None, &mut answer_lines);
+                lines.extend(word_wrap_lines(&answer_lines, width));
+            }
+            Err(err) => {
+                lines.extend(word_wrap_lines(&[Line::from(err.clone().red())], width));
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(feature = "oss")]
+impl HistoryCell for LocalComparisonCell {
+    fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
+        let mut lines: Vec<Line<'static>> =
+            vec![vec!["• ".dim(), "Compared local models".bold()].into()];
+        let columns = self.answers.len().max(1);
+        let separators = COMPARISON_SEPARATOR.width() * (columns - 1);
+        let column_width = ((width as usize).saturating_sub(2 + separators) / columns).max(10);
+        let rendered: Vec<Vec<Line<'static>>> = self
+            .answers
+            .iter()
+            .map(|answer| Self::column(answer, column_width))
+            .collect();
+        let rows = rendered.iter().map(Vec::len).max().unwrap_or(0);
+        for row in 0..rows {
+            let mut spans: Vec<Span<'static>> = vec!["  ".into()];
+            for (index, column) in rendered.iter().enumerate() {
+                let cell = column.get(row).cloned().unwrap_or_default();
+                if index > 0 {
+                    spans.push(COMPARISON_SEPARATOR.dim());
+                }
+                let padding = column_width.saturating_sub(cell.width());
+                spans.extend(cell.spans);
+                // The last column needs no padding.
+                if index + 1 < rendered.len() {
+                    spans.push(" ".repeat(padding).into());
+                }
+            }
+            // Shorter answers end before the row does; drop the trailing gap.
+            if let Some(last) = spans.last_mut() {
+                let trimmed = last.content.trim_end().to_string();
+                last.content = trimmed.into();
+            }
+            lines.push(Line::from(spans));
+        }
+        lines
+    }
+}
+
+/// The `/oss-status` report on the local server.
+#[cfg(feature = "oss")]
+pub(crate) fn new_oss_status_output(report: LocalServerReport) -> PlainHistoryCell {
+    let LocalServerReport {
+        backend,
+        status,
+        host_memory,
+    } = report;
+    let server = &status.server;
+    let name = match backend {
+        OssBackend::Ollama => "Ollama",
+        OssBackend::LmStudio => "LM Studio",
+        OssBackend::Generic => "OpenAI-compatible server",
+    };
+    let mut lines: Vec<Line<'static>> = vec![
+        "/oss-status".magenta().into(),
+        "".into(),
+        vec!["🖥  ".into(), "Local server".bold()].into(),
+        "".into(),
+    ];
+    let server_line: Line<'static> = match (&server.version, server.running) {
+        (_, false) => vec![
+            "  • Server: ".into(),
+            format!("{name} is not reachable at {}", server.host_root).red(),
+        ]
+        .into(),
+        (Some(version), true) => {
+            format!("  • Server: {name} {version} at {}", server.host_root).into()
+        }
+        (None, true) => format!("  • Server: {name} at {}", server.host_root).into(),
+    };
+    lines.push(server_line);
+    if !server.running {
+        lines.push("".into());
+        return PlainHistoryCell { lines };
+    }
+
+    let uptime = status
+        .uptime_secs
+        .map(crate::status_indicator_widget::fmt_elapsed_compact)
+        .unwrap_or_else(|| "unknown (not started by Codex)".to_string());
+    lines.push(format!("  • Uptime: {uptime}").into());
+    let queue = status
+        .active_requests
+        .map(|n| format!("{n} requests in flight"))
+        .unwrap_or_else(|| "not reported".to_string());
+    lines.push(format!("  • Queue: {queue}").into());
+
+    let mut memory: Vec<Span<'static>> = vec!["  • Memory: ".into()];
+    match host_memory {
+        Some(host) => {
+            memory.push(
+                format!(
+                    "{} of {} free",
+                    codex_ollama::format_size(host.available_bytes),
+                    codex_ollama::format_size(host.total_bytes)
+                )
+                .into(),
+            );
+            let usage = MemoryUsage {
+                models: Vec::new(),
+                host: Some(host),
+            };
+            if usage.is_under_pressure() {
+                memory.push(" · ".dim());
+                memory.push("⚠ low memory".red());
+            }
+        }
+        None => memory.push("not reported".dim()),
+    }
+    lines.push(memory.into());
+
+    if status.loaded_models.is_empty() {
+        lines.push("  • Loaded models: none".into());
+    } else {
+        lines.push("  • Loaded models:".into());
+        for model in &status.loaded_models {
+            let mut parts = Vec::new();
+            if let Some(size) = model.size_bytes {
+                parts.push(codex_ollama::format_size(size));
+            }
+            if let (Some(size), Some(vram)) = (model.size_bytes, model.vram_bytes)
+                && size > 0
+            {
+                parts.push(format!("{}% GPU", vram.saturating_mul(100) / size));
+            }
+            if let Some(context) = model.context_length {
+                parts.push(format!("{}k context", context / 1024));
+            }
+            let mut line: Vec<Span<'static>> = vec!["    • ".into(), model.name.clone().into()];
+            if !parts.is_empty() {
+                line.push(format!("  {}", parts.join(" · ")).dim());
+            }
+            lines.push(line.into());
+        }
+    }
+    lines.push("".into());
+    PlainHistoryCell { lines }
+}
+
 /// Render a user‑friendly plan update styled like a checkbox todo list.
 pub(crate) fn new_plan_update(update: UpdatePlanArgs) -> PlanUpdateCell {
     let UpdatePlanArgs { explanation, plan } = update;
@@ -1417,6 +1647,14 @@ mod tests {
     use codex_core::config::Config;
     use codex_core::config::ConfigOverrides;
     use codex_core::config::ConfigToml;
+    #[cfg(feature = "oss")]
+    use codex_ollama::HostMemory;
+    #[cfg(feature = "oss")]
+    use codex_protocol::oss::LoadedModel;
+    #[cfg(feature = "oss")]
+    use codex_protocol::oss::OssRuntimeStatus;
+    #[cfg(feature = "oss")]
+    use codex_protocol::oss::ServerStatus;
     use codex_protocol::parse_command::ParsedCommand;
     use dirs::home_dir;
     use pretty_assertions::assert_eq;
@@ -1451,6 +1689,57 @@ mod tests {
         render_lines(&cell.transcript_lines(u16::MAX))
     }
 
+    #[cfg(feature = "oss")]
+    #[test]
+    fn oss_status_output_lists_loaded_models() {
+        let report = LocalServerReport {
+            backend: OssBackend::Ollama,
+            status: OssRuntimeStatus {
+                server: ServerStatus {
+                    host_root: "http://localhost:11434".to_string(),
+                    running: true,
+                    version: Some("0.11.4".to_string()),
+                    managed_pid: None,
+                },
+                uptime_secs: None,
+                active_requests: None,
+                loaded_models: vec![LoadedModel {
+                    name: "gpt-oss:20b".to_string(),
+                    size_bytes: Some(16 * 1024 * 1024 * 1024),
+                    vram_bytes: Some(12 * 1024 * 1024 * 1024),
+                    context_length: Some(8192),
+                    expires_at: None,
+                }],
+            },
+            host_memory: Some(HostMemory {
+                total_bytes: 32 * 1024 * 1024 * 1024,
+                available_bytes: 2 * 1024 * 1024 * 1024,
+            }),
+        };
+        let cell = new_oss_status_output(report);
+        insta::assert_snapshot!(render_transcript(&cell).join("\n"));
+    }
+
+    #[cfg(feature = "oss")]
+    #[test]
+    fn local_comparison_renders_answers_side_by_side() {
+        let cell = LocalComparisonCell::new(vec![
+            ComparedAnswer {
+                model: "gpt-oss:20b".to_string(),
+                result: Ok(codex_ollama::ModelAnswer {
+                    text: "Use a HashMap by path.".to_string(),
+                    completion_tokens: Some(120),
+                    elapsed: Duration::from_secs(4),
+                }),
+            },
+            ComparedAnswer {
+                model: "qwen3:8b".to_string(),
+                result: Err("HTTP 404: model not found".to_string()),
+            },
+        ]);
+        insta::assert_snapshot!(render_lines(&cell.display_lines(60)).join("\n"));
+    }
+
     #[test]
     fn empty_agent_message_cell_transcript() {
         let cell = AgentMessageCell::new(vec![Line::default()], false);
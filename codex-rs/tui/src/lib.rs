@@ -17,7 +17,6 @@ use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::find_conversation_path_by_id_str;
 use codex_core::protocol::AskForApproval;
-use codex_ollama::DEFAULT_OSS_MODEL;
 use codex_protocol::config_types::SandboxMode;
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use std::fs::OpenOptions;
@@ -45,6 +44,7 @@ mod exec_cell;
 mod exec_command;
 mod file_search;
 mod frames;
+mod generation_meter;
 mod get_git_diff;
 mod history_cell;
 pub mod insert_history;
@@ -54,9 +54,29 @@ mod markdown;
 mod markdown_render;
 mod markdown_stream;
 pub mod onboarding;
+#[cfg(feature = "oss")]
+mod oss_compare;
+#[cfg(feature = "oss")]
+mod oss_error_card;
+#[cfg(feature = "oss")]
+mod oss_health;
+#[cfg(feature = "oss")]
+mod oss_memory;
+#[cfg(feature = "oss")]
+mod oss_models;
+mod oss_phase;
+#[cfg(feature = "oss")]
+mod oss_recommend;
+#[cfg(feature = "oss")]
+mod oss_resume;
+#[cfg(feature = "oss")]
+mod oss_server_prompt;
+#[cfg(feature = "oss")]
+mod oss_startup;
 mod pager_overlay;
 pub mod public_widgets;
 mod render;
+mod request_latency;
 mod resume_picker;
 mod selection_list;
 mod session_log;
@@ -113,16 +133,11 @@ pub async fn run_main(
         )
     };
 
-    // When using `--oss`, let the bootstrapper pick the model (defaulting to
-    // gpt-oss:20b) and ensure it is present locally. Also, force the built‑in
-    // `oss` model provider.
-    let model = if let Some(model) = &cli.model {
-        Some(model.clone())
-    } else if cli.oss {
-        Some(DEFAULT_OSS_MODEL.to_owned())
-    } else {
-        None // No model specified, will use the default.
-    };
+    // When using `--oss` without `-m`, the model comes from `oss.model` (or the
+    // default local model); see `Config::load_from_base_config_with_overrides`.
+    // Without either, `run_ratatui_app` asks for one picked for the workspace.
+    // Also force the built‑in `oss` model provider.
+    let model = cli.model.clone();
 
     let model_provider_override = if cli.oss {
         Some(BUILT_IN_OSS_MODEL_PROVIDER_ID.to_owned())
@@ -149,10 +164,12 @@ pub async fn run_main(
         show_raw_agent_reasoning: cli.oss.then_some(true),
         tools_web_search_request: cli.web_search.then_some(true),
         additional_writable_roots: additional_dirs,
+        oss_provider: cli.oss_provider.map(Into::into),
+        oss_base_url: cli.oss_url.clone(),
     };
     let raw_overrides = cli.config_overrides.raw_overrides.clone();
     let overrides_cli = codex_common::CliConfigOverrides { raw_overrides };
-    let cli_kv_overrides = match overrides_cli.parse_overrides() {
+    let mut cli_kv_overrides = match overrides_cli.parse_overrides() {
         Ok(v) => v,
         #[allow(clippy::print_stderr)]
         Err(e) => {
@@ -160,6 +177,13 @@ pub async fn run_main(
             std::process::exit(1);
         }
     };
+    // The TUI owns the terminal: keep the local provider's clients from
+    // writing to stderr over it, in every config loaded below. What they do
+    // is still traced to the log file.
+    cli_kv_overrides.push((
+        "oss.verbosity".to_string(),
+        toml::Value::String("quiet".to_string()),
+    ));
 
     let config = load_config_or_exit(cli_kv_overrides.clone(), overrides.clone()).await;
 
@@ -202,7 +226,9 @@ pub async fn run_main(
     // use RUST_LOG env var, default to info for codex crates.
     let env_filter = || {
         EnvFilter::try_from_default_env().unwrap_or_else(|_| {
-            EnvFilter::new("codex_core=info,codex_tui=info,codex_rmcp_client=info")
+            EnvFilter::new(
+                "codex_core=info,codex_tui=info,codex_rmcp_client=info,codex_ollama=info",
+            )
         })
     };
 
@@ -221,10 +247,23 @@ pub async fn run_main(
         .with_target(false)
         .with_filter(targets);
 
+    #[cfg(not(feature = "oss"))]
+    if cli.oss || config.oss.offline {
+        return Err(std::io::Error::other(
+            "codex was built without local backend support",
+        ));
+    }
+    #[cfg(feature = "oss")]
+    codex_ollama::ensure_offline_provider(&config)?;
+    #[cfg(feature = "oss")]
     if cli.oss {
-        codex_ollama::ensure_oss_ready(&config)
-            .await
-            .map_err(|e| std::io::Error::other(format!("OSS setup failed: {e}")))?;
+        codex_ollama::ensure_local_base_url(&config).await?;
+        if let Some(warning) = codex_ollama::remote_oss_url_warning(&config) {
+            #[allow(clippy::print_stderr)]
+            {
+                eprintln!("Warning: {warning}");
+            }
+        }
     }
 
     let otel = codex_core::otel_init::build_provider(&config, env!("CARGO_PKG_VERSION"));
@@ -304,6 +343,7 @@ async fn run_ratatui_app(
                     return Ok(AppExitInfo {
                         token_usage: codex_core::protocol::TokenUsage::default(),
                         conversation_id: None,
+                        local_energy: None,
                         update_action: Some(action),
                     });
                 }
@@ -311,6 +351,103 @@ async fn run_ratatui_app(
         }
     }
 
+    // `--oss` without a configured model starts on one picked for the
+    // workspace, unless a session is being resumed on the model it ran on.
+    #[cfg(feature = "oss")]
+    let resuming = cli.resume_picker || cli.resume_last || cli.resume_session_id.is_some();
+    #[cfg(feature = "oss")]
+    let (initial_config, overrides) = if cli.oss
+        && !resuming
+        && overrides.model.is_none()
+        && initial_config.oss.model.is_none()
+    {
+        match oss_recommend::pick_startup_model(&mut tui, &initial_config).await {
+            Ok(oss_recommend::StartupModel::Configured) => (initial_config, overrides),
+            Ok(oss_recommend::StartupModel::Model(model)) => {
+                let overrides = ConfigOverrides {
+                    model: Some(model),
+                    ..overrides
+                };
+                (
+                    load_config_or_exit(cli_kv_overrides.clone(), overrides.clone()).await,
+                    overrides,
+                )
+            }
+            Ok(oss_recommend::StartupModel::Quit) => {
+                restore();
+                return Ok(AppExitInfo {
+                    token_usage: codex_core::protocol::TokenUsage::default(),
+                    conversation_id: None,
+                    local_energy: None,
+                    update_action: None,
+                });
+            }
+            Err(err) => {
+                restore();
+                return Err(color_eyre::eyre::eyre!("OSS setup failed: {err}"));
+            }
+        }
+    } else {
+        (initial_config, overrides)
+    };
+
+    // With `--oss`, the session may start on another model: a local fallback
+    // or the cloud while the requested model downloads, or the cloud when
+    // the local server is down.
+    #[cfg(not(feature = "oss"))]
+    let background_download = None;
+    #[cfg(feature = "oss")]
+    let (initial_config, overrides, background_download) = if cli.oss {
+        let (session_overrides, background_download) =
+            match oss_startup::run_oss_startup(&mut tui, &initial_config).await {
+                Ok(oss_startup::OssStartupOutcome::Ready) => (None, None),
+                Ok(oss_startup::OssStartupOutcome::SwitchToCloud) => {
+                    (Some(cloud_overrides(&overrides)), None)
+                }
+                Ok(oss_startup::OssStartupOutcome::DownloadInBackground { fallback_model }) => {
+                    let session_overrides = match fallback_model {
+                        Some(model) => ConfigOverrides {
+                            model: Some(model),
+                            ..overrides.clone()
+                        },
+                        None => cloud_overrides(&overrides),
+                    };
+                    (Some(session_overrides), Some(initial_config.clone()))
+                }
+                Err(err) => {
+                    restore();
+                    return Err(color_eyre::eyre::eyre!("OSS setup failed: {err}"));
+                }
+            };
+        match session_overrides {
+            None => (initial_config, overrides, background_download),
+            Some(session_overrides) => match Config::load_with_cli_overrides(
+                cli_kv_overrides.clone(),
+                session_overrides.clone(),
+            )
+            .await
+            {
+                Ok(config) => (config, session_overrides, background_download),
+                Err(err) => {
+                    restore();
+                    return Err(color_eyre::eyre::eyre!(
+                        "Failed to load the session configuration: {err}"
+                    ));
+                }
+            },
+        }
+    } else {
+        (initial_config, overrides, None)
+    };
+    let cloud_config = if initial_config.model_provider_id == BUILT_IN_OSS_MODEL_PROVIDER_ID {
+        Config::load_with_cli_overrides(cli_kv_overrides.clone(), cloud_overrides(&overrides))
+            .await
+            .inspect_err(|err| tracing::warn!("no cloud fallback for --oss: {err}"))
+            .ok()
+    } else {
+        None
+    };
+
     // Initialize high-fidelity session event logging if enabled.
     session_log::maybe_init(&initial_config);
 
@@ -349,6 +486,7 @@ async fn run_ratatui_app(
             return Ok(AppExitInfo {
                 token_usage: codex_core::protocol::TokenUsage::default(),
                 conversation_id: None,
+                local_energy: None,
                 update_action: None,
             });
         }
@@ -359,7 +497,7 @@ async fn run_ratatui_app(
                 .map(|d| d == TrustDirectorySelection::Trust)
                 .unwrap_or(false)
         {
-            load_config_or_exit(cli_kv_overrides, overrides).await
+            load_config_or_exit(cli_kv_overrides.clone(), overrides.clone()).await
         } else {
             initial_config
         }
@@ -385,6 +523,7 @@ async fn run_ratatui_app(
                 return Ok(AppExitInfo {
                     token_usage: codex_core::protocol::TokenUsage::default(),
                     conversation_id: None,
+                    local_energy: None,
                     update_action: None,
                 });
             }
@@ -413,6 +552,7 @@ async fn run_ratatui_app(
                 return Ok(AppExitInfo {
                     token_usage: codex_core::protocol::TokenUsage::default(),
                     conversation_id: None,
+                    local_energy: None,
                     update_action: None,
                 });
             }
@@ -422,6 +562,64 @@ async fn run_ratatui_app(
         resume_picker::ResumeSelection::StartFresh
     };
 
+    // A resumed `--oss` session continues on the model it ran on, unless
+    // `-m` picked one.
+    #[cfg(feature = "oss")]
+    let (config, background_download) = match &resume_selection {
+        resume_picker::ResumeSelection::Resume(path)
+            if config.model_provider_id == BUILT_IN_OSS_MODEL_PROVIDER_ID
+                && overrides.model.is_none() =>
+        {
+            let resumed = match oss_resume::resume_model(&mut tui, &config, path).await {
+                Ok(resumed) => resumed,
+                Err(err) => {
+                    restore();
+                    return Err(color_eyre::eyre::eyre!("OSS setup failed: {err}"));
+                }
+            };
+            let model_overrides = |model: String| ConfigOverrides {
+                model: Some(model),
+                ..overrides.clone()
+            };
+            let (session_overrides, download) = match resumed {
+                oss_resume::ResumeModel::Configured => (None, None),
+                oss_resume::ResumeModel::Model(model) => (Some(model_overrides(model)), None),
+                oss_resume::ResumeModel::DownloadInBackground {
+                    model,
+                    fallback_model,
+                } => (
+                    Some(model_overrides(fallback_model)),
+                    Some(model_overrides(model)),
+                ),
+                oss_resume::ResumeModel::SwitchToCloud => (Some(cloud_overrides(&overrides)), None),
+                oss_resume::ResumeModel::Quit => {
+                    restore();
+                    session_log::log_session_end();
+                    return Ok(AppExitInfo {
+                        token_usage: codex_core::protocol::TokenUsage::default(),
+                        conversation_id: None,
+                        local_energy: None,
+                        update_action: None,
+                    });
+                }
+            };
+            let background_download = match download {
+                Some(download) => {
+                    Some(load_config_or_exit(cli_kv_overrides.clone(), download).await)
+                }
+                None => background_download,
+            };
+            match session_overrides {
+                Some(session_overrides) => (
+                    load_config_or_exit(cli_kv_overrides.clone(), session_overrides).await,
+                    background_download,
+                ),
+                None => (config, background_download),
+            }
+        }
+        _ => (config, background_download),
+    };
+
     let Cli { prompt, images, .. } = cli;
 
     let app_result = App::run(
@@ -433,6 +631,8 @@ async fn run_ratatui_app(
         images,
         resume_selection,
         feedback,
+        cloud_config,
+        background_download,
     )
     .await;
 
@@ -493,6 +693,19 @@ async fn load_config_or_exit(
     }
 }
 
+/// `overrides` without `--oss`, for continuing on the default OpenAI provider
+/// when the local server is unavailable.
+fn cloud_overrides(overrides: &ConfigOverrides) -> ConfigOverrides {
+    ConfigOverrides {
+        model: None,
+        model_provider: Some("openai".to_string()),
+        show_raw_agent_reasoning: None,
+        oss_provider: None,
+        oss_base_url: None,
+        ..overrides.clone()
+    }
+}
+
 /// Determine if user has configured a sandbox / approval policy,
 /// or if the current cwd project is already trusted. If not, we need to
 /// show the trust screen.
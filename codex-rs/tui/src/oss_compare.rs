@@ -0,0 +1,81 @@
+//! Experimental `/compare`: the same prompt answered by two local models,
+//! shown side by side to help pick the model to standardize on.
+
+use codex_core::config::Config;
+use codex_ollama::ModelAnswer;
+use codex_ollama::OllamaClient;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+
+/// Answer of one of the compared models.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ComparedAnswer {
+    pub(crate) model: String,
+    pub(crate) result: Result<ModelAnswer, String>,
+}
+
+/// Where a `/compare` is, from choosing the models to the answers arriving.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) enum LocalCompare {
+    #[default]
+    Off,
+    /// A picker is open; `first` is set once the first model was chosen.
+    Choosing {
+        models: Vec<String>,
+        first: Option<String>,
+    },
+    /// The next message is sent to both models.
+    Armed { models: [String; 2] },
+    /// Waiting for the answers.
+    Running,
+}
+
+/// Fetch the models on the local server and report them with
+/// [`AppEvent::LocalCompareModelsLoaded`].
+pub(crate) fn spawn_load_compare_models(config: Config, tx: AppEventSender) {
+    tokio::spawn(async move {
+        let result = codex_ollama::list_local_models(&config)
+            .await
+            .map(|models| models.into_iter().map(|model| model.name).collect())
+            .map_err(|err| err.to_string());
+        tx.send(AppEvent::LocalCompareModelsLoaded(result));
+    });
+}
+
+/// Ask both `models` for an answer to `prompt` and report them with
+/// [`AppEvent::LocalCompareFinished`]. The models run one after the other so
+/// they do not compete for memory, and each is timed on its own.
+pub(crate) fn spawn_compare(
+    config: Config,
+    models: [String; 2],
+    prompt: String,
+    tx: AppEventSender,
+) {
+    tokio::spawn(async move {
+        let client = match OllamaClient::from_oss_provider(&config) {
+            Ok(client) => client,
+            Err(err) => {
+                let answers = models
+                    .into_iter()
+                    .map(|model| ComparedAnswer {
+                        model,
+                        result: Err(err.to_string()),
+                    })
+                    .collect();
+                tx.send(AppEvent::LocalCompareFinished(answers));
+                return;
+            }
+        };
+        let max_tokens = config.oss.sampling.max_tokens;
+        let mut answers = Vec::with_capacity(models.len());
+        for model in models {
+            let result = client
+                .answer(&model, &prompt, max_tokens)
+                .await
+                .map_err(|err| err.to_string());
+            answers.push(ComparedAnswer { model, result });
+        }
+        tx.send(AppEvent::LocalCompareFinished(answers));
+    });
+}
@@ -0,0 +1,306 @@
+//! Failures of the local `--oss` provider rendered with concrete next steps,
+//! such as the command that starts the server or the memory to free, instead
+//! of the raw error text.
+
+use codex_common::fuzzy_match::fuzzy_match;
+use codex_core::protocol_config_types::OssBackend;
+use codex_ollama::MessageId;
+use codex_ollama::OssError;
+use codex_ollama::message;
+
+/// Most model names offered as "did you mean" suggestions.
+const MAX_SUGGESTIONS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OssErrorCard {
+    pub(crate) title: String,
+    /// The underlying error, shown dimmed below the title.
+    pub(crate) detail: Option<String>,
+    pub(crate) next_steps: Vec<String>,
+}
+
+impl OssErrorCard {
+    /// Card for a typed failure of the local provider. `available_models` are
+    /// the models on the server, used to suggest names close to `model`.
+    pub(crate) fn from_error(
+        err: &OssError,
+        backend: OssBackend,
+        model: &str,
+        available_models: &[String],
+    ) -> Self {
+        let detail = Some(err.to_string());
+        let backend_name = backend.to_string();
+        let args = [("backend", backend_name.as_str()), ("model", model)];
+        match err {
+            OssError::ServerUnreachable(_) => Self {
+                title: message(MessageId::CardServerNotResponding, &args),
+                detail,
+                next_steps: server_steps(backend),
+            },
+            OssError::ModelMissing(_) => Self {
+                title: message(MessageId::CardModelNotFound, &args),
+                detail,
+                next_steps: missing_model_steps(backend, model, available_models),
+            },
+            OssError::ModelUnsupported(_) => Self {
+                title: message(MessageId::CardModelUnsupported, &args),
+                detail,
+                next_steps: vec![message(MessageId::CardPickToolModel, &[])],
+            },
+            OssError::DownloadFailed { .. } => Self {
+                title: message(MessageId::CardDownloadFailed, &args),
+                detail,
+                next_steps: vec![
+                    message(MessageId::CardCheckNetwork, &[]),
+                    message(MessageId::CardCheckDisk, &[]),
+                ],
+            },
+            OssError::LicenseNotAccepted {
+                model,
+                license,
+                url,
+            } => {
+                let args = [("model", model.as_str()), ("license", license.as_str())];
+                let mut next_steps = Vec::new();
+                if let Some(url) = url {
+                    next_steps.push(message(MessageId::CardReadLicense, &[("url", url)]));
+                }
+                next_steps.push(message(MessageId::CardAcceptLicense, &args));
+                Self {
+                    title: message(MessageId::CardLicenseNotAccepted, &args),
+                    detail,
+                    next_steps,
+                }
+            }
+            OssError::GenerationFailed(text) => {
+                Self::from_message(text, backend, model, available_models).unwrap_or(Self {
+                    title: message(MessageId::CardModelFailed, &args),
+                    detail,
+                    next_steps: vec![log_step()],
+                })
+            }
+            OssError::Io(err) => {
+                Self::from_message(&err.to_string(), backend, model, available_models).unwrap_or(
+                    Self {
+                        title: message(MessageId::CardProviderFailed, &[]),
+                        detail,
+                        next_steps: vec![message(MessageId::CardRunDoctor, &[])],
+                    },
+                )
+            }
+        }
+    }
+
+    /// Card for an error that reached the session as text, such as a failed
+    /// turn. `None` when the message is not recognized.
+    pub(crate) fn from_message(
+        text: &str,
+        backend: OssBackend,
+        model: &str,
+        available_models: &[String],
+    ) -> Option<Self> {
+        let lower = text.to_lowercase();
+        let detail = Some(text.to_string());
+        let backend_name = backend.to_string();
+        let args = [("backend", backend_name.as_str()), ("model", model)];
+        if lower.contains("memory") || lower.contains("insufficient system resources") {
+            let mut next_steps = Vec::new();
+            match missing_memory(&lower) {
+                Some(missing) => next_steps.push(message(
+                    MessageId::CardFreeMemoryAmount,
+                    &[("size", &missing)],
+                )),
+                None => next_steps.push(message(MessageId::CardFreeMemory, &[])),
+            }
+            next_steps.push(message(MessageId::CardUnloadModels, &[]));
+            next_steps.push(message(MessageId::CardSmallerModel, &[]));
+            return Some(Self {
+                title: message(MessageId::CardOutOfMemory, &args),
+                detail,
+                next_steps,
+            });
+        }
+        if crate::oss_health::is_backend_unhealthy(text) {
+            return Some(Self {
+                title: message(MessageId::CardServerFailing, &args),
+                detail,
+                next_steps: vec![message(MessageId::CardRestartServer, &args), log_step()],
+            });
+        }
+        if lower.contains("connection refused") || lower.contains("error sending request") {
+            return Some(Self {
+                title: message(MessageId::CardServerNotResponding, &args),
+                detail,
+                next_steps: server_steps(backend),
+            });
+        }
+        if lower.contains("not found") && lower.contains("model") {
+            return Some(Self {
+                title: message(MessageId::CardModelNotFound, &args),
+                detail,
+                next_steps: missing_model_steps(backend, model, available_models),
+            });
+        }
+        None
+    }
+}
+
+fn server_steps(backend: OssBackend) -> Vec<String> {
+    let start = match backend {
+        OssBackend::Ollama => MessageId::CardStartOllama,
+        OssBackend::LmStudio => MessageId::CardStartLmStudio,
+        OssBackend::Generic => MessageId::CardStartGeneric,
+    };
+    vec![
+        message(start, &[]),
+        message(MessageId::CardCheckOssUrl, &[]),
+    ]
+}
+
+fn missing_model_steps(
+    backend: OssBackend,
+    model: &str,
+    available_models: &[String],
+) -> Vec<String> {
+    let mut steps = Vec::new();
+    let suggestions = similar_models(model, available_models);
+    if !suggestions.is_empty() {
+        steps.push(message(
+            MessageId::CardDidYouMean,
+            &[("models", &suggestions.join(", "))],
+        ));
+    }
+    let pull = match backend {
+        OssBackend::Ollama => MessageId::CardPullOllama,
+        OssBackend::LmStudio => MessageId::CardPullLmStudio,
+        OssBackend::Generic => MessageId::CardCheckModelList,
+    };
+    steps.push(message(pull, &[("model", model)]));
+    steps.push(message(MessageId::CardPickModel, &[]));
+    steps
+}
+
+/// Models whose names are closest to `model`, best first. A model also
+/// matches on its name without the tag, so `qwen3:8b-instruct` suggests
+/// `qwen3:8b`.
+fn similar_models(model: &str, available_models: &[String]) -> Vec<String> {
+    let base = model.split(':').next().unwrap_or(model);
+    let mut scored: Vec<(i32, &String)> = available_models
+        .iter()
+        .filter(|candidate| candidate.as_str() != model)
+        .filter_map(|candidate| {
+            let score = [model, base]
+                .into_iter()
+                .filter_map(|needle| fuzzy_match(candidate, needle).map(|(_, score)| score))
+                .min()?;
+            Some((score, candidate))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| *score);
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate.clone())
+        .collect()
+}
+
+/// Memory still needed according to Ollama's "model requires more system
+/// memory (13.4 GiB) than is available (8.2 GiB)" error.
+fn missing_memory(lower_message: &str) -> Option<String> {
+    let mut sizes = lower_message.split('(').skip(1).filter_map(|rest| {
+        let (size, _) = rest.split_once(')')?;
+        let (value, unit) = size.trim().split_once(' ')?;
+        let value: f64 = value.parse().ok()?;
+        match unit {
+            "gib" | "gb" => Some(value),
+            "mib" | "mb" => Some(value / 1024.0),
+            _ => None,
+        }
+    });
+    let required = sizes.next()?;
+    let available = sizes.next()?;
+    let missing = required - available;
+    (missing > 0.0).then(|| format!("{missing:.1} GB"))
+}
+
+fn log_step() -> String {
+    message(MessageId::CardServerLog, &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn models(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn unreachable_server_names_the_start_command() {
+        let err = OssError::ServerUnreachable("connection refused".to_string());
+        let card = OssErrorCard::from_error(&err, OssBackend::LmStudio, "qwen3:8b", &[]);
+        assert_eq!(card.title, "The local lmstudio server is not responding");
+        assert_eq!(card.next_steps[0], "Start it with `lms server start`");
+    }
+
+    #[test]
+    fn missing_model_suggests_close_names() {
+        let available = models(&["gpt-oss:20b", "qwen3:8b", "qwen3-coder:30b", "llama3.2"]);
+        assert_eq!(
+            similar_models("qwen3:8b-instruct", &available),
+            vec!["qwen3:8b", "qwen3-coder:30b"]
+        );
+        let card = OssErrorCard::from_error(
+            &OssError::ModelMissing("model 'gpt-oss' not found".to_string()),
+            OssBackend::Ollama,
+            "gpt-oss",
+            &available,
+        );
+        assert_eq!(
+            card.next_steps,
+            vec![
+                "Did you mean gpt-oss:20b?",
+                "Download it with `codex models pull gpt-oss`",
+                "Pick an available model with /model",
+            ]
+        );
+    }
+
+    #[test]
+    fn unaccepted_license_links_the_terms() {
+        let err = OssError::LicenseNotAccepted {
+            model: "hf.co/org/model-GGUF".to_string(),
+            license: "llama3.1".to_string(),
+            url: Some("https://huggingface.co/org/model-GGUF".to_string()),
+        };
+        let card = OssErrorCard::from_error(&err, OssBackend::Ollama, "hf.co/org/model-GGUF", &[]);
+        assert_eq!(
+            card.next_steps,
+            vec![
+                "Read the terms at https://huggingface.co/org/model-GGUF",
+                "Accept them with `codex models pull hf.co/org/model-GGUF`, or add \"llama3.1\" to oss.accepted_licenses",
+            ]
+        );
+    }
+
+    #[test]
+    fn out_of_memory_names_the_memory_to_free() {
+        let card = OssErrorCard::from_message(
+            "model requires more system memory (13.4 GiB) than is available (8.2 GiB)",
+            OssBackend::Ollama,
+            "gpt-oss:20b",
+            &[],
+        )
+        .expect("recognized");
+        assert_eq!(card.title, "Not enough memory to load gpt-oss:20b");
+        assert_eq!(
+            card.next_steps[0],
+            "Free 5.2 GB of memory by closing other applications"
+        );
+        assert_eq!(
+            OssErrorCard::from_message("stream closed", OssBackend::Ollama, "m", &[]),
+            None
+        );
+    }
+}
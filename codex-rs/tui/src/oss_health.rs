@@ -0,0 +1,363 @@
+//! Background health checks of the local `--oss` server, so the footer shows
+//! when the server goes away instead of the next request failing.
+
+use std::time::Duration;
+
+use codex_core::config::Config;
+use codex_core::protocol_config_types::OssBackend;
+use codex_ollama::HostMemory;
+use codex_ollama::OllamaClient;
+use codex_protocol::oss::OssEvent;
+use codex_protocol::oss::OssRuntimeStatus;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::bottom_pane::OssServerBadge;
+use crate::bottom_pane::OssServerState;
+
+/// Interval of the health checks unless `oss.health_check_interval_secs` is set.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Result of one health check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LocalServerStatus {
+    pub(crate) backend: OssBackend,
+    pub(crate) reachable: bool,
+    /// Models currently loaded into memory, when the backend reports them.
+    pub(crate) loaded_models: Option<Vec<String>>,
+}
+
+impl LocalServerStatus {
+    /// Whether the server reports that `model` is not in memory yet, so the
+    /// next request will first load it.
+    pub(crate) fn needs_load(&self, model: &str) -> bool {
+        self.badge(model).state == OssServerState::Idle
+    }
+
+    /// Record that `model` finished loading.
+    pub(crate) fn mark_loaded(&mut self, model: &str) {
+        if let Some(loaded) = &mut self.loaded_models
+            && !loaded.iter().any(|m| m == model)
+        {
+            loaded.push(model.to_string());
+        }
+    }
+
+    /// Record that `model` was unloaded, so the next request loads it again.
+    pub(crate) fn mark_unloaded(&mut self, model: &str) {
+        if let Some(loaded) = &mut self.loaded_models {
+            loaded.retain(|m| m != model);
+        }
+    }
+
+    /// Footer badge for a session using `model`.
+    pub(crate) fn badge(&self, model: &str) -> OssServerBadge {
+        let state = match (&self.loaded_models, self.reachable) {
+            (_, false) => OssServerState::Unreachable,
+            (Some(loaded), true) if !loaded.iter().any(|m| m == model) => OssServerState::Idle,
+            _ => OssServerState::ModelLoaded,
+        };
+        OssServerBadge {
+            backend: self.backend,
+            state,
+        }
+    }
+}
+
+/// Poll the server behind the `oss` provider and send
+/// [`AppEvent::LocalServerStatus`] whenever it goes up or down or its loaded
+/// models change. A zero `oss.health_check_interval_secs` turns this off.
+pub(crate) fn spawn_health_monitor(config: &Config, tx: AppEventSender) -> Option<JoinHandle<()>> {
+    let poll_interval = config
+        .oss
+        .health_check_interval
+        .unwrap_or(DEFAULT_POLL_INTERVAL);
+    if poll_interval.is_zero() {
+        return None;
+    }
+    let client = match OllamaClient::from_oss_provider(config) {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::warn!("not monitoring the local server: {err}");
+            return None;
+        }
+    };
+    let backend = config.oss.provider.unwrap_or(OssBackend::Ollama);
+    Some(tokio::spawn(async move {
+        let mut last: Option<LocalServerStatus> = None;
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            interval.tick().await;
+            let status = check(&client, backend).await;
+            if last.as_ref() != Some(&status) {
+                tx.send(AppEvent::LocalServerStatus(status.clone()));
+                last = Some(status);
+            }
+        }
+    }))
+}
+
+/// Restart the server Codex started for this session when it crashes, and
+/// report each step with [`AppEvent::LocalServerLifecycle`]. Only Ollama
+/// servers are started by Codex, so other backends are not supervised.
+pub(crate) fn spawn_supervisor(config: &Config, tx: AppEventSender) -> Option<JoinHandle<()>> {
+    if config.oss.provider.unwrap_or(OssBackend::Ollama) != OssBackend::Ollama {
+        return None;
+    }
+    let config = config.clone();
+    Some(tokio::spawn(codex_ollama::supervise_managed_server(
+        config,
+        move |event| tx.send(AppEvent::LocalServerLifecycle(event)),
+    )))
+}
+
+/// Start the server when `start` is set, then check it once and report
+/// [`AppEvent::LocalServerRecovery`].
+pub(crate) fn spawn_recovery(config: Config, start: bool, tx: AppEventSender) {
+    let backend = config.oss.provider.unwrap_or(OssBackend::Ollama);
+    tokio::spawn(async move {
+        let result = async {
+            if start {
+                codex_ollama::start_oss_server(&config).await?;
+            }
+            let client = OllamaClient::from_oss_provider(&config)?;
+            Ok::<_, std::io::Error>(check(&client, backend).await)
+        }
+        .await
+        .map_err(|err| err.to_string());
+        tx.send(AppEvent::LocalServerRecovery(result));
+    });
+}
+
+/// Load the session's model after the server came back, so the next turn
+/// does not wait for it. The load is reported by [`spawn_event_listener`].
+pub(crate) fn spawn_preload(config: Config) {
+    tokio::spawn(async move {
+        if let Err(err) = codex_ollama::load_model(&config, &config.model).await {
+            tracing::warn!("preloading {} failed: {err}", config.model);
+        }
+    });
+}
+
+/// Turn the models loaded and unloaded anywhere in this process into
+/// [`AppEvent::LocalModelLoaded`] and [`AppEvent::LocalModelUnloaded`]. The
+/// other [`OssEvent`]s are already reported by the health monitor, the
+/// supervisor, and the download flow.
+pub(crate) fn spawn_event_listener(tx: AppEventSender) -> JoinHandle<()> {
+    let mut events = codex_ollama::subscribe_oss_events();
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(OssEvent::ModelLoaded { model }) => tx.send(AppEvent::LocalModelLoaded(model)),
+                Ok(OssEvent::ModelUnloaded { model }) => {
+                    tx.send(AppEvent::LocalModelUnloaded(model));
+                }
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    })
+}
+
+/// How often the server is asked whether a model being loaded is ready.
+const LOAD_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Give up watching a load after this long; the turn itself reports errors.
+const LOAD_WATCH_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Watch the server until `model` shows up among its loaded models, then
+/// report [`AppEvent::LocalModelLoaded`]. Backends that do not report
+/// loaded models are not watched.
+pub(crate) fn spawn_load_watch(config: &Config, model: String, tx: AppEventSender) {
+    let backend = config.oss.provider.unwrap_or(OssBackend::Ollama);
+    if backend == OssBackend::Generic {
+        return;
+    }
+    let Ok(client) = OllamaClient::from_oss_provider(config) else {
+        return;
+    };
+    tokio::spawn(async move {
+        let deadline = tokio::time::Instant::now() + LOAD_WATCH_TIMEOUT;
+        while tokio::time::Instant::now() < deadline {
+            let status = check(&client, backend).await;
+            if status.loaded_models.iter().flatten().any(|m| m == &model) {
+                tx.send(AppEvent::LocalModelLoaded(model));
+                return;
+            }
+            tokio::time::sleep(LOAD_POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// How often the server is polled after it dropped in the middle of a turn.
+const RECONNECT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Stop waiting for a dropped server after this long and leave recovery to
+/// the user.
+const RECONNECT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Progress of [`spawn_reconnect`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ReconnectProgress {
+    /// The server is still not responding; `attempt` counts the checks.
+    Waiting { attempt: u32 },
+    /// The server is back and the session's model is being loaded again.
+    ReloadingModel,
+    /// The server responds and the model is loaded.
+    Reconnected,
+    /// The server did not come back, or the model failed to load.
+    Failed(String),
+}
+
+/// Whether a failed turn of a local session was rejected because the server
+/// failed too many requests in a row and requests to it are paused.
+pub(crate) fn is_backend_unhealthy(message: &str) -> bool {
+    message.contains("server looks unhealthy")
+}
+
+/// Poll the server behind the `oss` provider until it responds again, load
+/// the session's model, and report each step with
+/// [`AppEvent::LocalServerReconnect`].
+pub(crate) fn spawn_reconnect(config: Config, tx: AppEventSender) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let progress = match reconnect(&config, &tx).await {
+            Ok(()) => ReconnectProgress::Reconnected,
+            Err(err) => ReconnectProgress::Failed(err),
+        };
+        tx.send(AppEvent::LocalServerReconnect(progress));
+    })
+}
+
+async fn reconnect(config: &Config, tx: &AppEventSender) -> Result<(), String> {
+    let client = OllamaClient::from_oss_provider(config).map_err(|err| err.to_string())?;
+    let deadline = tokio::time::Instant::now() + RECONNECT_TIMEOUT;
+    let mut attempt = 1;
+    while !is_reachable(&client).await {
+        if tokio::time::Instant::now() >= deadline {
+            return Err(format!(
+                "it is still not responding after {} minutes",
+                RECONNECT_TIMEOUT.as_secs() / 60
+            ));
+        }
+        tx.send(AppEvent::LocalServerReconnect(ReconnectProgress::Waiting {
+            attempt,
+        }));
+        tokio::time::sleep(RECONNECT_POLL_INTERVAL).await;
+        attempt += 1;
+    }
+    tx.send(AppEvent::LocalServerReconnect(
+        ReconnectProgress::ReloadingModel,
+    ));
+    codex_ollama::load_model(config, &config.model)
+        .await
+        .map_err(|err| format!("loading {} failed: {err}", config.model))
+}
+
+/// Everything `/oss-status` reports about the local server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LocalServerReport {
+    pub(crate) backend: OssBackend,
+    pub(crate) status: OssRuntimeStatus,
+    pub(crate) host_memory: Option<HostMemory>,
+}
+
+/// Gather a [`LocalServerReport`] and send it with
+/// [`AppEvent::LocalServerReport`].
+pub(crate) fn spawn_status_report(config: Config, tx: AppEventSender) {
+    let backend = config.oss.provider.unwrap_or(OssBackend::Ollama);
+    tokio::spawn(async move {
+        let status = async {
+            let client = OllamaClient::from_oss_provider(&config)?;
+            let state_dir = codex_ollama::oss_state_dir(&config);
+            codex_ollama::runtime_status(&client, backend, &state_dir).await
+        }
+        .await;
+        let host_memory = tokio::task::spawn_blocking(codex_ollama::host_memory)
+            .await
+            .ok()
+            .flatten();
+        let result = status
+            .map(|status| LocalServerReport {
+                backend,
+                status,
+                host_memory,
+            })
+            .map_err(|err| err.to_string());
+        tx.send(AppEvent::LocalServerReport(result));
+    });
+}
+
+async fn is_reachable(client: &OllamaClient) -> bool {
+    matches!(
+        tokio::time::timeout(PROBE_TIMEOUT, client.probe_server()).await,
+        Ok(Ok(()))
+    )
+}
+
+async fn check(client: &OllamaClient, backend: OssBackend) -> LocalServerStatus {
+    let reachable = is_reachable(client).await;
+    let loaded_models = if reachable {
+        match backend {
+            OssBackend::Ollama => client.fetch_loaded_model_names().await.ok(),
+            OssBackend::LmStudio => client.fetch_lmstudio_loaded_models().await.ok(),
+            OssBackend::Generic => None,
+        }
+    } else {
+        None
+    };
+    LocalServerStatus {
+        backend,
+        reachable,
+        loaded_models,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn status(reachable: bool, loaded_models: Option<Vec<&str>>) -> LocalServerStatus {
+        LocalServerStatus {
+            backend: OssBackend::Ollama,
+            reachable,
+            loaded_models: loaded_models
+                .map(|models| models.into_iter().map(str::to_string).collect()),
+        }
+    }
+
+    #[test]
+    fn badge_reflects_whether_the_session_model_is_loaded() {
+        let state = |status: LocalServerStatus| status.badge("gpt-oss:20b").state;
+        assert_eq!(state(status(false, None)), OssServerState::Unreachable);
+        assert_eq!(
+            state(status(true, Some(vec!["qwen3:8b"]))),
+            OssServerState::Idle
+        );
+        assert_eq!(
+            state(status(true, Some(vec!["gpt-oss:20b"]))),
+            OssServerState::ModelLoaded
+        );
+        // Backends that cannot report loaded models load on demand.
+        assert_eq!(state(status(true, None)), OssServerState::ModelLoaded);
+    }
+
+    #[test]
+    fn marking_a_model_loaded_ends_the_load() {
+        let mut idle = status(true, Some(vec!["qwen3:8b"]));
+        assert!(idle.needs_load("gpt-oss:20b"));
+        idle.mark_loaded("gpt-oss:20b");
+        assert!(!idle.needs_load("gpt-oss:20b"));
+    }
+
+    #[test]
+    fn marking_a_model_unloaded_makes_the_next_turn_load_it() {
+        let mut loaded = status(true, Some(vec!["gpt-oss:20b", "qwen3:8b"]));
+        loaded.mark_unloaded("gpt-oss:20b");
+        assert!(loaded.needs_load("gpt-oss:20b"));
+        assert!(!loaded.needs_load("qwen3:8b"));
+    }
+}
@@ -0,0 +1,239 @@
+//! Memory used by the local `--oss` model and left on the machine, polled in
+//! the background and shown above the composer so long sessions do not run
+//! into swapping unnoticed.
+
+use std::time::Duration;
+
+use codex_core::config::Config;
+use codex_core::protocol_config_types::OssBackend;
+use codex_ollama::HostMemory;
+use codex_ollama::OllamaClient;
+use codex_ollama::format_size;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize as _;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::WidgetRef;
+use tokio::task::JoinHandle;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Below this share of available memory the machine is considered to be
+/// under memory pressure.
+const PRESSURE_AVAILABLE_PERCENT: u64 = 10;
+
+/// Footprint of one model loaded on the local server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ModelMemory {
+    pub(crate) name: String,
+    pub(crate) total_bytes: u64,
+    /// Part of `total_bytes` held in GPU memory.
+    pub(crate) vram_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct MemoryUsage {
+    /// Models loaded on the server; empty when the backend does not report
+    /// them.
+    pub(crate) models: Vec<ModelMemory>,
+    pub(crate) host: Option<HostMemory>,
+}
+
+impl MemoryUsage {
+    pub(crate) fn is_under_pressure(&self) -> bool {
+        self.host.is_some_and(|host| {
+            host.total_bytes > 0
+                && host.available_bytes.saturating_mul(100)
+                    < host.total_bytes.saturating_mul(PRESSURE_AVAILABLE_PERCENT)
+        })
+    }
+}
+
+/// Poll the server behind the `oss` provider and the machine, and send
+/// [`AppEvent::LocalMemoryUsage`] whenever the usage changes.
+pub(crate) fn spawn_memory_monitor(config: &Config, tx: AppEventSender) -> Option<JoinHandle<()>> {
+    let client = match OllamaClient::from_oss_provider(config) {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::warn!("not monitoring memory of the local server: {err}");
+            return None;
+        }
+    };
+    let backend = config.oss.provider.unwrap_or(OssBackend::Ollama);
+    Some(tokio::spawn(async move {
+        let mut last: Option<MemoryUsage> = None;
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let models = match backend {
+                // Only Ollama reports the footprint of loaded models.
+                OssBackend::Ollama => client
+                    .fetch_loaded_models()
+                    .await
+                    .map(|loaded| {
+                        loaded
+                            .into_iter()
+                            .map(|model| ModelMemory {
+                                name: model.name,
+                                total_bytes: model.size_bytes.unwrap_or(0),
+                                vram_bytes: model.vram_bytes.unwrap_or(0),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                OssBackend::LmStudio | OssBackend::Generic => Vec::new(),
+            };
+            let host = tokio::task::spawn_blocking(codex_ollama::host_memory)
+                .await
+                .ok()
+                .flatten();
+            let usage = MemoryUsage { models, host };
+            if last.as_ref() != Some(&usage) {
+                tx.send(AppEvent::LocalMemoryUsage(usage.clone()));
+                last = Some(usage);
+            }
+        }
+    }))
+}
+
+/// One summary line, expanded with a line per loaded model on request.
+#[derive(Debug, Default)]
+pub(crate) struct MemoryWidget {
+    usage: MemoryUsage,
+    expanded: bool,
+}
+
+impl MemoryWidget {
+    pub(crate) fn set_usage(&mut self, usage: MemoryUsage) {
+        self.usage = usage;
+    }
+
+    pub(crate) fn toggle_expanded(&mut self) {
+        self.expanded = !self.expanded;
+    }
+
+    pub(crate) fn desired_height(&self, _width: u16) -> u16 {
+        self.lines().len() as u16
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let marker = if self.expanded { "▾ " } else { "▸ " };
+        let mut summary: Vec<Span<'static>> = vec!["  ".into(), marker.dim(), "memory ".dim()];
+        let model_total: u64 = self.usage.models.iter().map(|m| m.total_bytes).sum();
+        let model_vram: u64 = self.usage.models.iter().map(|m| m.vram_bytes).sum();
+        let mut parts = Vec::new();
+        if !self.usage.models.is_empty() {
+            parts.push(model_footprint("model", model_total, model_vram));
+        }
+        if let Some(host) = self.usage.host {
+            parts.push(format!(
+                "{} of {} free",
+                format_size(host.available_bytes),
+                format_size(host.total_bytes)
+            ));
+        }
+        if parts.is_empty() {
+            parts.push("not reported by this server".to_string());
+        }
+        summary.push(parts.join(" · ").dim());
+        if self.usage.is_under_pressure() {
+            summary.push(" · ".dim());
+            summary.push("⚠ low memory".red());
+        }
+
+        let mut lines = vec![Line::from(summary)];
+        if self.expanded {
+            for model in &self.usage.models {
+                lines.push(
+                    Line::from(format!(
+                        "    {}",
+                        model_footprint(&model.name, model.total_bytes, model.vram_bytes)
+                    ))
+                    .dim(),
+                );
+            }
+        }
+        lines
+    }
+}
+
+/// e.g. "gpt-oss:20b 13.04 GB (11.18 GB VRAM)".
+fn model_footprint(label: &str, total_bytes: u64, vram_bytes: u64) -> String {
+    if vram_bytes > 0 {
+        format!(
+            "{label} {} ({} VRAM)",
+            format_size(total_bytes),
+            format_size(vram_bytes)
+        )
+    } else {
+        format!("{label} {}", format_size(total_bytes))
+    }
+}
+
+impl WidgetRef for MemoryWidget {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        Paragraph::new(self.lines()).render_ref(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    fn usage(available_gb: u64) -> MemoryUsage {
+        MemoryUsage {
+            models: vec![ModelMemory {
+                name: "gpt-oss:20b".to_string(),
+                total_bytes: 13 * GB,
+                vram_bytes: 11 * GB,
+            }],
+            host: Some(HostMemory {
+                total_bytes: 32 * GB,
+                available_bytes: available_gb * GB,
+            }),
+        }
+    }
+
+    fn text(widget: &MemoryWidget) -> Vec<String> {
+        widget
+            .lines()
+            .into_iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pressure_is_below_a_tenth_of_memory_available() {
+        assert!(!usage(4).is_under_pressure());
+        assert!(usage(3).is_under_pressure());
+        assert!(!MemoryUsage::default().is_under_pressure());
+    }
+
+    #[test]
+    fn expanding_lists_each_model() {
+        let mut widget = MemoryWidget::default();
+        widget.set_usage(usage(3));
+        assert_eq!(
+            text(&widget),
+            vec![
+                "  ▸ memory model 13.00 GB (11.00 GB VRAM) · 3.00 GB of 32.00 GB free · ⚠ low memory"
+            ]
+        );
+        widget.toggle_expanded();
+        assert_eq!(widget.desired_height(80), 2);
+        assert_eq!(text(&widget)[1], "    gpt-oss:20b 13.00 GB (11.00 GB VRAM)");
+    }
+}
@@ -0,0 +1,354 @@
+//! Listing and switching the models of the local `--oss` server from within
+//! a session.
+
+use codex_core::config::Config;
+use codex_core::protocol_config_types::OssBackend;
+use codex_ollama::JsonProgressReporter;
+use codex_ollama::LocalModel;
+use codex_ollama::OllamaClient;
+use codex_ollama::OssError;
+use codex_ollama::PreflightRequirements;
+use codex_ollama::RegistryClient;
+use codex_ollama::contains_model;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::bottom_pane::SelectionAction;
+use crate::bottom_pane::SelectionItem;
+use crate::oss_error_card::OssErrorCard;
+
+/// Fetch the models available on the local server in the background and
+/// report them with [`AppEvent::LocalModelsLoaded`].
+pub(crate) fn spawn_load_local_models(config: Config, tx: AppEventSender) {
+    tokio::spawn(async move {
+        let result = codex_ollama::list_local_models(&config)
+            .await
+            .map_err(|err| err.to_string());
+        tx.send(AppEvent::LocalModelsLoaded(result));
+    });
+}
+
+/// Ask the local server which context window `config.model` runs with and
+/// report it with [`AppEvent::LocalContextWindow`].
+pub(crate) fn spawn_detect_context_window(config: Config, tx: AppEventSender) {
+    tokio::spawn(async move {
+        let window = codex_ollama::detect_context_window(&config).await;
+        tx.send(AppEvent::LocalContextWindow(window));
+    });
+}
+
+/// Downloads at least this large are confirmed before they start.
+const CONFIRM_DOWNLOAD_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Connection speed download times are estimated for, 100 Mbit/s.
+pub(crate) const ESTIMATE_BYTES_PER_SECOND: u64 = 100_000_000 / 8;
+
+/// What downloading a local model costs, shown before the download starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DownloadEstimate {
+    pub(crate) model: String,
+    pub(crate) size_bytes: u64,
+    /// Free space where the models are stored, when it can be determined.
+    pub(crate) free_disk_bytes: Option<u64>,
+}
+
+impl DownloadEstimate {
+    /// Download time at [`ESTIMATE_BYTES_PER_SECOND`].
+    pub(crate) fn estimated_seconds(&self) -> u64 {
+        self.size_bytes / ESTIMATE_BYTES_PER_SECOND
+    }
+
+    /// Whether the download leaves the disk full.
+    pub(crate) fn exceeds_free_disk(&self) -> bool {
+        self.free_disk_bytes
+            .is_some_and(|free| free < self.size_bytes)
+    }
+}
+
+/// Switch to `model`, first asking with
+/// [`AppEvent::ConfirmLocalModelDownload`] when that starts a download of
+/// several gigabytes. Otherwise go straight to
+/// [`AppEvent::PrepareLocalModel`].
+pub(crate) fn spawn_switch_local_model(config: Config, model: String, tx: AppEventSender) {
+    tokio::spawn(async move {
+        let event = match download_estimate(&config, &model).await {
+            Some(estimate) => AppEvent::ConfirmLocalModelDownload(estimate),
+            None => AppEvent::PrepareLocalModel(model),
+        };
+        tx.send(event);
+    });
+}
+
+/// Estimate for `model` when it is missing from the server and large enough
+/// to confirm. Only Ollama downloads models on request; any failure to find
+/// out leaves the decision to the download itself.
+async fn download_estimate(config: &Config, model: &str) -> Option<DownloadEstimate> {
+    if config.oss.offline
+        || config
+            .notices
+            .hide_model_download_confirmation
+            .unwrap_or(false)
+        || !matches!(config.oss.provider, Some(OssBackend::Ollama) | None)
+    {
+        return None;
+    }
+    let client = OllamaClient::from_oss_provider(config).ok()?;
+    let present = client.fetch_models().await.ok()?;
+    if contains_model(&present, model) {
+        return None;
+    }
+    let size_bytes = RegistryClient::new()
+        .fetch_download_size(model)
+        .await
+        .ok()?;
+    if size_bytes < CONFIRM_DOWNLOAD_BYTES {
+        return None;
+    }
+    let free_disk_bytes = tokio::task::spawn_blocking(codex_ollama::available_model_disk_bytes)
+        .await
+        .ok()
+        .flatten();
+    Some(DownloadEstimate {
+        model: model.to_string(),
+        size_bytes,
+        free_disk_bytes,
+    })
+}
+
+/// Download `model` if needed, check that it can drive the agent, and load
+/// it in place of the current model, then report [`AppEvent::LocalModelReady`]
+/// so the session can switch to it.
+pub(crate) fn spawn_prepare_local_model(config: Config, model: String, tx: AppEventSender) {
+    tokio::spawn(async move {
+        let event = match prepare_local_model(&config, &model, &tx).await {
+            Ok(()) => AppEvent::LocalModelReady(model),
+            Err(err) => {
+                let card = error_card(&config, &model, &err).await;
+                AppEvent::LocalModelFailed { model, card }
+            }
+        };
+        tx.send(event);
+    });
+}
+
+/// Error card for `err`, suggesting models on the server with names close
+/// to `model` when it is missing.
+async fn error_card(config: &Config, model: &str, err: &OssError) -> OssErrorCard {
+    let available_models: Vec<String> = match err {
+        OssError::ModelMissing(_) => codex_ollama::list_local_models(config)
+            .await
+            .map(|models| models.into_iter().map(|m| m.name).collect())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    let backend = config.oss.provider.unwrap_or(OssBackend::Ollama);
+    OssErrorCard::from_error(err, backend, model, &available_models)
+}
+
+async fn prepare_local_model(
+    config: &Config,
+    model: &str,
+    tx: &AppEventSender,
+) -> Result<(), OssError> {
+    let mut target = config.clone();
+    target.model = model.to_string();
+    let mut reporter = JsonProgressReporter::new(model, |progress| {
+        tx.send(AppEvent::LocalModelDownloadProgress(progress.clone()));
+        Ok(())
+    });
+    // The same checks as `codex exec --oss`: the conversation continues with
+    // tool calls, so a model without them is refused before switching.
+    let requirements = PreflightRequirements {
+        allow_download: !config.oss.offline,
+        capabilities: vec!["tools".to_string()],
+    };
+    codex_ollama::preflight_oss(&target, &requirements, &mut reporter).await?;
+
+    if config.model != model
+        && let Err(err) = codex_ollama::unload_models(config, Some(&config.model)).await
+    {
+        tracing::debug!("did not unload {}: {err}", config.model);
+    }
+    // Loading the model now keeps the first turn with it from stalling.
+    codex_ollama::load_model(config, model).await?;
+    Ok(())
+}
+
+/// Unload the session's model from the local server to free the memory it
+/// holds, then report [`AppEvent::LocalModelEjected`]. The session is kept;
+/// its next request loads the model again.
+pub(crate) fn spawn_eject_local_model(config: Config, tx: AppEventSender) {
+    tokio::spawn(async move {
+        let result = codex_ollama::unload_models(&config, Some(&config.model))
+            .await
+            .map(|_| ())
+            .map_err(|err| err.to_string());
+        tx.send(AppEvent::LocalModelEjected {
+            model: config.model,
+            result,
+        });
+    });
+}
+
+/// The most recently used local model other than `config.model`, to work
+/// with while `config.model` downloads.
+pub(crate) async fn fallback_local_model(config: &Config) -> Option<String> {
+    let client = OllamaClient::try_from_oss_provider(config).await.ok()?;
+    let state_dir = codex_ollama::oss_state_dir(config);
+    client
+        .fetch_models()
+        .await
+        .ok()?
+        .into_iter()
+        .filter(|model| model != &config.model)
+        .max_by_key(|model| codex_ollama::model_last_used(&state_dir, model))
+}
+
+/// Download `config.model` without showing progress, then report
+/// [`AppEvent::LocalModelDownloaded`].
+pub(crate) fn spawn_background_download(config: Config, tx: AppEventSender) {
+    tokio::spawn(async move {
+        let mut reporter = JsonProgressReporter::new(config.model.clone(), |_| Ok(()));
+        let result = codex_ollama::ensure_oss_ready_with_reporter(&config, &mut reporter).await;
+        let result = match result {
+            Ok(()) => Ok(()),
+            Err(err) => Err(error_card(&config, &config.model, &err).await),
+        };
+        tx.send(AppEvent::LocalModelDownloaded {
+            model: config.model,
+            result,
+        });
+    });
+}
+
+/// Picker rows for `models`. On Ollama, the default `--oss` model is offered
+/// for download when it is not present yet.
+pub(crate) fn local_model_items(
+    models: &[LocalModel],
+    current_model: &str,
+    backend: Option<OssBackend>,
+) -> Vec<SelectionItem> {
+    let mut items: Vec<SelectionItem> = models
+        .iter()
+        .map(|model| {
+            let description = local_model_description(model);
+            model_item(
+                &model.name,
+                (!description.is_empty()).then_some(description),
+                model.name == current_model,
+            )
+        })
+        .collect();
+    let default_model = codex_ollama::DEFAULT_OSS_MODEL;
+    if matches!(backend, Some(OssBackend::Ollama) | None)
+        && !models.iter().any(|model| model.name == default_model)
+    {
+        items.push(model_item(
+            default_model,
+            Some("not downloaded, select to download".to_string()),
+            default_model == current_model,
+        ));
+    }
+    items
+}
+
+fn model_item(name: &str, description: Option<String>, is_current: bool) -> SelectionItem {
+    let model = name.to_string();
+    let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+        tx.send(AppEvent::SwitchLocalModel(model.clone()));
+    })];
+    SelectionItem {
+        name: name.to_string(),
+        description,
+        is_current,
+        actions,
+        dismiss_on_select: true,
+        search_value: Some(name.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Size, quantization, context window, and load state, e.g.
+/// "12.83 GB · MXFP4 · 128k context · loaded".
+fn local_model_description(model: &LocalModel) -> String {
+    let mut parts = Vec::new();
+    if let Some(size) = model.size_bytes {
+        parts.push(codex_ollama::format_size(size));
+    }
+    if let Some(quantization) = &model.quantization {
+        parts.push(quantization.clone());
+    }
+    if let Some(context) = model.context_length {
+        parts.push(format!("{}k context", context / 1024));
+    }
+    if model.loaded {
+        parts.push("loaded".to_string());
+    }
+    parts.join(" · ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn local(name: &str) -> LocalModel {
+        LocalModel {
+            name: name.to_string(),
+            size_bytes: Some(13_780_173_839),
+            quantization: Some("MXFP4".to_string()),
+            parameter_size: Some("20.9B".to_string()),
+            context_length: Some(131_072),
+            loaded: true,
+        }
+    }
+
+    #[test]
+    fn estimate_assumes_a_100_mbit_connection() {
+        let estimate = DownloadEstimate {
+            model: "gpt-oss:20b".to_string(),
+            size_bytes: 13_780_162_353,
+            free_disk_bytes: Some(8 * 1024 * 1024 * 1024),
+        };
+        assert_eq!(estimate.estimated_seconds(), 1_102);
+        assert!(estimate.exceeds_free_disk());
+    }
+
+    #[test]
+    fn description_lists_metadata() {
+        assert_eq!(
+            local_model_description(&local("gpt-oss:20b")),
+            "12.83 GB · MXFP4 · 128k context · loaded"
+        );
+    }
+
+    #[test]
+    fn default_model_is_offered_for_download_on_ollama() {
+        let models = vec![local("qwen3:8b")];
+        let names = |items: Vec<SelectionItem>| -> Vec<(String, bool)> {
+            items
+                .into_iter()
+                .map(|item| (item.name, item.is_current))
+                .collect()
+        };
+        assert_eq!(
+            names(local_model_items(
+                &models,
+                "qwen3:8b",
+                Some(OssBackend::Ollama)
+            )),
+            vec![
+                ("qwen3:8b".to_string(), true),
+                (codex_ollama::DEFAULT_OSS_MODEL.to_string(), false),
+            ]
+        );
+        assert_eq!(
+            names(local_model_items(
+                &models,
+                "qwen3:8b",
+                Some(OssBackend::LmStudio)
+            )),
+            vec![("qwen3:8b".to_string(), true)]
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Phases of a turn of a local `--oss` model, each with its own status text
+//! so a long wait can be told apart from a stalled one.
+
+use codex_protocol::oss::ModelDownloadProgress;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(not(feature = "oss"), allow(dead_code))]
+pub(crate) enum LocalPhase {
+    /// The server is downloading the model's weights.
+    Downloading { model: String, percent: Option<u64> },
+    /// The server is loading the model into memory.
+    Loading { model: String },
+    /// The request was sent and the server is processing the prompt.
+    WaitingForFirstToken,
+    /// The model streams its chain of thought.
+    Thinking,
+    /// The model streams its answer.
+    Streaming,
+}
+
+impl LocalPhase {
+    #[cfg_attr(not(feature = "oss"), allow(dead_code))]
+    pub(crate) fn downloading(progress: &ModelDownloadProgress) -> Self {
+        let percent = match (progress.completed_bytes, progress.total_bytes) {
+            (Some(completed), Some(total)) if total > 0 => {
+                Some((completed as f64 * 100.0 / total as f64).round() as u64)
+            }
+            _ => None,
+        };
+        LocalPhase::Downloading {
+            model: progress.model.clone(),
+            percent,
+        }
+    }
+
+    pub(crate) fn status_header(&self) -> String {
+        match self {
+            LocalPhase::Downloading {
+                model,
+                percent: Some(percent),
+            } => format!("Downloading {model} {percent}%"),
+            LocalPhase::Downloading {
+                model,
+                percent: None,
+            } => format!("Downloading {model}"),
+            LocalPhase::Loading { model } => format!("Loading {model} into memory"),
+            LocalPhase::WaitingForFirstToken => "Waiting for the first token".to_string(),
+            LocalPhase::Thinking => "Thinking".to_string(),
+            LocalPhase::Streaming => "Responding".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::oss::DownloadPhase;
+    use pretty_assertions::assert_eq;
+
+    fn progress(completed_bytes: Option<u64>, total_bytes: Option<u64>) -> ModelDownloadProgress {
+        ModelDownloadProgress {
+            model: "gpt-oss:20b".to_string(),
+            phase: DownloadPhase::Downloading,
+            status: None,
+            completed_bytes,
+            total_bytes,
+            bytes_per_second: None,
+        }
+    }
+
+    #[test]
+    fn each_phase_has_its_own_status() {
+        let headers: Vec<String> = [
+            LocalPhase::downloading(&progress(Some(3_400), Some(13_800))),
+            LocalPhase::downloading(&progress(None, None)),
+            LocalPhase::Loading {
+                model: "gpt-oss:20b".to_string(),
+            },
+            LocalPhase::WaitingForFirstToken,
+            LocalPhase::Thinking,
+            LocalPhase::Streaming,
+        ]
+        .iter()
+        .map(LocalPhase::status_header)
+        .collect();
+        assert_eq!(
+            headers,
+            [
+                "Downloading gpt-oss:20b 25%",
+                "Downloading gpt-oss:20b",
+                "Loading gpt-oss:20b into memory",
+                "Waiting for the first token",
+                "Thinking",
+                "Responding",
+            ]
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! Picking a local model when `--oss` starts without one. Instead of the
+//! default local model, the user chooses from the models ranked for the
+//! workspace and the machine (see [`codex_ollama::recommend_models`]). The
+//! pick is remembered for the workspace, so later sessions there start on it
+//! without asking.
+
+use crate::key_hint;
+use crate::render::Insets;
+use crate::render::renderable::ColumnRenderable;
+use crate::render::renderable::Renderable;
+use crate::render::renderable::RenderableExt as _;
+use crate::selection_list::selection_option_row;
+use crate::tui::FrameRequester;
+use crate::tui::Tui;
+use crate::tui::TuiEvent;
+use codex_core::config::Config;
+use codex_ollama::ModelRecommendation;
+use codex_ollama::ModelRecommendations;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::Widget;
+use ratatui::style::Stylize as _;
+use ratatui::text::Line;
+use ratatui::widgets::Clear;
+use ratatui::widgets::WidgetRef;
+use tokio_stream::StreamExt;
+
+/// Model a new `--oss` session starts on.
+pub(crate) enum StartupModel {
+    /// The configured model, when there is nothing to choose from.
+    Configured,
+    Model(String),
+    Quit,
+}
+
+/// The model to start on when neither `-m` nor `oss.model` names one.
+pub(crate) async fn pick_startup_model(
+    tui: &mut Tui,
+    config: &Config,
+) -> std::io::Result<StartupModel> {
+    let state_dir = codex_ollama::oss_state_dir(config);
+    if let Some(model) = codex_ollama::workspace_model(&state_dir, &config.cwd) {
+        return Ok(startup_model(config, model));
+    }
+    // The startup checks that follow deal with a server that is down.
+    let recommendations = match codex_ollama::recommend_models(config).await {
+        Ok(recommendations) => recommendations,
+        Err(err) => {
+            tracing::info!("no model recommendation: {err}");
+            return Ok(StartupModel::Configured);
+        }
+    };
+    let model = match recommendations.models.as_slice() {
+        [] => return Ok(StartupModel::Configured),
+        [only] => only.model.clone(),
+        _ => {
+            let mut prompt = ModelRecommendationScreen::new(tui.frame_requester(), recommendations);
+            let choice = prompt.run(tui).await?;
+            let _ = tui.terminal.clear();
+            match choice {
+                Some(model) => model,
+                None => return Ok(StartupModel::Quit),
+            }
+        }
+    };
+    if let Err(err) = codex_ollama::record_workspace_model(&state_dir, &config.cwd, &model) {
+        tracing::warn!("could not remember the model picked for the workspace: {err}");
+    }
+    Ok(startup_model(config, model))
+}
+
+fn startup_model(config: &Config, model: String) -> StartupModel {
+    if model == config.model {
+        StartupModel::Configured
+    } else {
+        StartupModel::Model(model)
+    }
+}
+
+/// Full-screen prompt listing the models ranked for the workspace.
+struct ModelRecommendationScreen {
+    request_frame: FrameRequester,
+    recommendations: ModelRecommendations,
+    highlighted: usize,
+    /// `Some(None)` when the user quit instead of choosing.
+    selection: Option<Option<String>>,
+}
+
+impl ModelRecommendationScreen {
+    fn new(request_frame: FrameRequester, recommendations: ModelRecommendations) -> Self {
+        Self {
+            request_frame,
+            recommendations,
+            highlighted: 0,
+            selection: None,
+        }
+    }
+
+    /// Wait for a choice. Returns `None` when the user quits.
+    async fn run(&mut self, tui: &mut Tui) -> std::io::Result<Option<String>> {
+        self.draw(tui)?;
+        let events = tui.event_stream();
+        tokio::pin!(events);
+        while self.selection.is_none() {
+            match events.next().await {
+                Some(TuiEvent::Key(key_event)) => self.handle_key(key_event),
+                Some(TuiEvent::Paste(_)) => {}
+                Some(TuiEvent::Draw) => self.draw(tui)?,
+                None => return Ok(None),
+            }
+        }
+        Ok(self.selection.take().flatten())
+    }
+
+    fn draw(&self, tui: &mut Tui) -> std::io::Result<()> {
+        tui.draw(u16::MAX, |frame| {
+            frame.render_widget_ref(self, frame.area());
+        })
+    }
+
+    fn models(&self) -> &[ModelRecommendation] {
+        &self.recommendations.models
+    }
+
+    fn handle_key(&mut self, key_event: KeyEvent) {
+        if key_event.kind == KeyEventKind::Release {
+            return;
+        }
+        if key_event.modifiers.contains(KeyModifiers::CONTROL)
+            && matches!(key_event.code, KeyCode::Char('c') | KeyCode::Char('d'))
+        {
+            self.selection = Some(None);
+            return;
+        }
+        let count = self.models().len();
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.set_highlight((self.highlighted + count - 1) % count)
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.set_highlight((self.highlighted + 1) % count)
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                let index = (c as usize) - ('1' as usize);
+                if index < count {
+                    self.select(index);
+                }
+            }
+            KeyCode::Enter => self.select(self.highlighted),
+            KeyCode::Esc => self.selection = Some(None),
+            _ => {}
+        }
+    }
+
+    fn set_highlight(&mut self, highlighted: usize) {
+        if self.highlighted != highlighted {
+            self.highlighted = highlighted;
+            self.request_frame.schedule_frame();
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        self.highlighted = index;
+        self.selection = Some(self.models().get(index).map(|r| r.model.clone()));
+        self.request_frame.schedule_frame();
+    }
+}
+
+impl WidgetRef for &ModelRecommendationScreen {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let mut column = ColumnRenderable::new();
+        column.push("");
+        column.push(Line::from(vec![
+            "  ".into(),
+            "Pick a local model for this workspace".bold(),
+        ]));
+        column.push(Line::from(
+            format!(
+                "  {} · {}",
+                self.recommendations.workspace.describe(),
+                self.recommendations.hardware.describe()
+            )
+            .dim(),
+        ));
+        column.push("");
+        for (index, recommendation) in self.models().iter().enumerate() {
+            column.push(selection_option_row(
+                index,
+                format!("{} — {}", recommendation.model, recommendation.reason),
+                index == self.highlighted,
+            ));
+        }
+        column.push("");
+        column.push(
+            Line::from(vec![
+                "Press ".dim(),
+                key_hint::plain(KeyCode::Enter).into(),
+                " to start · ".dim(),
+                key_hint::plain(KeyCode::Esc).into(),
+                " to quit".dim(),
+            ])
+            .inset(Insets::tlbr(0, 2, 0, 0)),
+        );
+        column.render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_ollama::HardwareProfile;
+    use codex_ollama::WorkspaceProfile;
+    use pretty_assertions::assert_eq;
+
+    fn press(screen: &mut ModelRecommendationScreen, code: KeyCode) {
+        screen.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn screen() -> ModelRecommendationScreen {
+        let recommendation = |model: &str| ModelRecommendation {
+            model: model.to_string(),
+            size_bytes: None,
+            installed: true,
+            reason: "installed".to_string(),
+        };
+        ModelRecommendationScreen::new(
+            FrameRequester::test_dummy(),
+            ModelRecommendations {
+                workspace: WorkspaceProfile::default(),
+                hardware: HardwareProfile {
+                    memory_bytes: None,
+                    gpu_memory_bytes: None,
+                    unified_memory: false,
+                },
+                models: vec![
+                    recommendation("qwen3-coder:30b"),
+                    recommendation("gpt-oss:20b"),
+                ],
+            },
+        )
+    }
+
+    #[test]
+    fn keys_pick_a_ranked_model_or_quit() {
+        let mut prompt = screen();
+        press(&mut prompt, KeyCode::Enter);
+        assert_eq!(prompt.selection, Some(Some("qwen3-coder:30b".to_string())));
+
+        let mut prompt = screen();
+        press(&mut prompt, KeyCode::Char('2'));
+        assert_eq!(prompt.selection, Some(Some("gpt-oss:20b".to_string())));
+
+        let mut prompt = screen();
+        press(&mut prompt, KeyCode::Esc);
+        assert_eq!(prompt.selection, Some(None));
+    }
+}
@@ -0,0 +1,303 @@
+//! Resuming a saved `--oss` session on the model it ran on. The local server
+//! may have changed since: the model may have been removed, or the session
+//! may have run on another model than the one configured now. The recorded
+//! model goes through the same readiness checks as a new session's, and when
+//! it cannot be made ready, the user picks a model to continue with instead
+//! of a first turn failing against a model the server does not have.
+
+use std::path::Path;
+
+use crate::key_hint;
+use crate::oss_startup;
+use crate::oss_startup::OssStartupOutcome;
+use crate::render::Insets;
+use crate::render::renderable::ColumnRenderable;
+use crate::render::renderable::Renderable;
+use crate::render::renderable::RenderableExt as _;
+use crate::selection_list::selection_option_row;
+use crate::tui::FrameRequester;
+use crate::tui::Tui;
+use crate::tui::TuiEvent;
+use codex_core::RolloutRecorder;
+use codex_core::config::Config;
+use codex_ollama::OllamaClient;
+use codex_ollama::OssError;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::Widget;
+use ratatui::style::Stylize as _;
+use ratatui::text::Line;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::WidgetRef;
+use ratatui::widgets::Wrap;
+use tokio_stream::StreamExt;
+
+/// Model a resumed `--oss` session continues with.
+pub(crate) enum ResumeModel {
+    /// The configured model, which the startup checks already prepared.
+    Configured,
+    /// The model the session ran on, or the one picked instead of it.
+    Model(String),
+    /// The user chose to keep downloading the recorded model in the
+    /// background and resume with `fallback_model` meanwhile.
+    DownloadInBackground {
+        model: String,
+        fallback_model: String,
+    },
+    /// The server is down and the user chose to use the cloud provider.
+    SwitchToCloud,
+    Quit,
+}
+
+/// Make sure the model the rollout at `path` last ran on is ready, as the
+/// startup checks only prepared `config.model`.
+pub(crate) async fn resume_model(
+    tui: &mut Tui,
+    config: &Config,
+    path: &Path,
+) -> Result<ResumeModel, OssError> {
+    let recorded = match RolloutRecorder::last_local_model(path).await {
+        Ok(Some(model)) if model != config.model => model,
+        Ok(_) => return Ok(ResumeModel::Configured),
+        Err(err) => {
+            tracing::warn!("could not read the model of the resumed session: {err}");
+            return Ok(ResumeModel::Configured);
+        }
+    };
+    let mut recorded_config = config.clone();
+    recorded_config.model = recorded.clone();
+    let message = match oss_startup::run_oss_startup(tui, &recorded_config).await {
+        Ok(OssStartupOutcome::Ready) => return Ok(ResumeModel::Model(recorded)),
+        Ok(OssStartupOutcome::SwitchToCloud) => return Ok(ResumeModel::SwitchToCloud),
+        Ok(OssStartupOutcome::DownloadInBackground { fallback_model }) => {
+            return Ok(ResumeModel::DownloadInBackground {
+                model: recorded,
+                fallback_model: fallback_model.unwrap_or_else(|| config.model.clone()),
+            });
+        }
+        // The user quit the prompt to start the server.
+        Err(err @ OssError::ServerUnreachable(_)) => return Err(err),
+        Err(err) => err.to_string(),
+    };
+
+    let installed = installed_models(config).await;
+    let choices = substitute_choices(&config.model, &recorded, installed);
+    let mut prompt = ModelSubstituteScreen::new(tui.frame_requester(), recorded, message, choices);
+    let choice = prompt.run(tui).await?;
+    let _ = tui.terminal.clear();
+    Ok(match choice {
+        Some(model) if model == config.model => ResumeModel::Configured,
+        Some(model) => ResumeModel::Model(model),
+        None => ResumeModel::Quit,
+    })
+}
+
+/// Models on the server, most recently used by Codex first.
+async fn installed_models(config: &Config) -> Vec<String> {
+    let Ok(client) = OllamaClient::try_from_oss_provider(config).await else {
+        return Vec::new();
+    };
+    let state_dir = codex_ollama::oss_state_dir(config);
+    let mut models = client.fetch_models().await.unwrap_or_default();
+    models.sort_by_key(|model| std::cmp::Reverse(codex_ollama::model_last_used(&state_dir, model)));
+    models
+}
+
+/// Models offered instead of `recorded`: the configured one first, as it is
+/// known to be ready, then the other installed ones.
+fn substitute_choices(configured: &str, recorded: &str, installed: Vec<String>) -> Vec<String> {
+    let mut choices = vec![configured.to_string()];
+    for model in installed {
+        if model != recorded && !choices.contains(&model) {
+            choices.push(model);
+        }
+    }
+    choices
+}
+
+/// Full-screen prompt to pick another model when the recorded one cannot
+/// be made ready.
+struct ModelSubstituteScreen {
+    request_frame: FrameRequester,
+    recorded: String,
+    message: String,
+    choices: Vec<String>,
+    highlighted: usize,
+    /// `Some(None)` when the user quit instead of choosing.
+    selection: Option<Option<String>>,
+}
+
+impl ModelSubstituteScreen {
+    fn new(
+        request_frame: FrameRequester,
+        recorded: String,
+        message: String,
+        choices: Vec<String>,
+    ) -> Self {
+        Self {
+            request_frame,
+            recorded,
+            message,
+            choices,
+            highlighted: 0,
+            selection: None,
+        }
+    }
+
+    /// Wait for a choice. Returns `None` when the user quits.
+    async fn run(&mut self, tui: &mut Tui) -> std::io::Result<Option<String>> {
+        self.draw(tui)?;
+        let events = tui.event_stream();
+        tokio::pin!(events);
+        while self.selection.is_none() {
+            match events.next().await {
+                Some(TuiEvent::Key(key_event)) => self.handle_key(key_event),
+                Some(TuiEvent::Paste(_)) => {}
+                Some(TuiEvent::Draw) => self.draw(tui)?,
+                None => return Ok(None),
+            }
+        }
+        Ok(self.selection.take().flatten())
+    }
+
+    fn draw(&self, tui: &mut Tui) -> std::io::Result<()> {
+        tui.draw(u16::MAX, |frame| {
+            frame.render_widget_ref(self, frame.area());
+        })
+    }
+
+    fn handle_key(&mut self, key_event: KeyEvent) {
+        if key_event.kind == KeyEventKind::Release {
+            return;
+        }
+        if key_event.modifiers.contains(KeyModifiers::CONTROL)
+            && matches!(key_event.code, KeyCode::Char('c') | KeyCode::Char('d'))
+        {
+            self.selection = Some(None);
+            return;
+        }
+        let count = self.choices.len();
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.set_highlight((self.highlighted + count - 1) % count)
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.set_highlight((self.highlighted + 1) % count)
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                let index = (c as usize) - ('1' as usize);
+                if index < count {
+                    self.select(index);
+                }
+            }
+            KeyCode::Enter => self.select(self.highlighted),
+            KeyCode::Esc => self.selection = Some(None),
+            _ => {}
+        }
+    }
+
+    fn set_highlight(&mut self, highlighted: usize) {
+        if self.highlighted != highlighted {
+            self.highlighted = highlighted;
+            self.request_frame.schedule_frame();
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        self.highlighted = index;
+        self.selection = Some(self.choices.get(index).cloned());
+        self.request_frame.schedule_frame();
+    }
+}
+
+impl WidgetRef for &ModelSubstituteScreen {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let mut column = ColumnRenderable::new();
+        column.push("");
+        column.push(Line::from(vec![
+            "  ✗ ".red(),
+            "This session ran on ".bold(),
+            self.recorded.clone().cyan().bold(),
+            ", which is not available".bold(),
+        ]));
+        column.push("");
+        column.push(
+            Paragraph::new(Line::from(self.message.clone().dim()))
+                .wrap(Wrap { trim: false })
+                .inset(Insets::tlbr(0, 2, 0, 0)),
+        );
+        column.push("");
+        column.push(Line::from("  Continue the session with:"));
+        for (index, model) in self.choices.iter().enumerate() {
+            column.push(selection_option_row(
+                index,
+                model.clone(),
+                index == self.highlighted,
+            ));
+        }
+        column.push("");
+        column.push(
+            Line::from(vec![
+                "Press ".dim(),
+                key_hint::plain(KeyCode::Enter).into(),
+                " to continue · ".dim(),
+                key_hint::plain(KeyCode::Esc).into(),
+                " to quit".dim(),
+            ])
+            .inset(Insets::tlbr(0, 2, 0, 0)),
+        );
+        column.render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn press(screen: &mut ModelSubstituteScreen, code: KeyCode) {
+        screen.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn the_configured_model_is_offered_first() {
+        assert_eq!(
+            substitute_choices(
+                "gpt-oss:20b",
+                "qwen3-coder:30b",
+                vec![
+                    "qwen3-coder:30b".to_string(),
+                    "mistral".to_string(),
+                    "gpt-oss:20b".to_string(),
+                ],
+            ),
+            vec!["gpt-oss:20b".to_string(), "mistral".to_string()]
+        );
+    }
+
+    #[test]
+    fn keys_pick_a_model_or_quit() {
+        let screen = || {
+            ModelSubstituteScreen::new(
+                FrameRequester::test_dummy(),
+                "qwen3-coder:30b".to_string(),
+                "model 'qwen3-coder:30b' not found".to_string(),
+                vec!["gpt-oss:20b".to_string(), "mistral".to_string()],
+            )
+        };
+        let mut prompt = screen();
+        press(&mut prompt, KeyCode::Down);
+        press(&mut prompt, KeyCode::Enter);
+        assert_eq!(prompt.selection, Some(Some("mistral".to_string())));
+
+        let mut prompt = screen();
+        press(&mut prompt, KeyCode::Esc);
+        assert_eq!(prompt.selection, Some(None));
+    }
+}
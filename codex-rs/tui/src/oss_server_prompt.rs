@@ -0,0 +1,279 @@
+//! What to do when the local `--oss` server does not answer: start it, check
+//! again, or continue with the cloud provider. Offered as a full-screen prompt
+//! before the session starts and as a popup when the server goes away later.
+
+use crate::app_event::AppEvent;
+use crate::bottom_pane::SelectionAction;
+use crate::bottom_pane::SelectionItem;
+use crate::key_hint;
+use crate::render::Insets;
+use crate::render::renderable::ColumnRenderable;
+use crate::render::renderable::Renderable;
+use crate::render::renderable::RenderableExt as _;
+use crate::selection_list::selection_option_row;
+use crate::tui::FrameRequester;
+use crate::tui::Tui;
+use crate::tui::TuiEvent;
+use codex_core::protocol_config_types::OssBackend;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::Widget;
+use ratatui::style::Stylize as _;
+use ratatui::text::Line;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::WidgetRef;
+use ratatui::widgets::Wrap;
+use tokio_stream::StreamExt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ServerRecovery {
+    StartServer,
+    Retry,
+    SwitchToCloud,
+}
+
+impl ServerRecovery {
+    fn label(self) -> &'static str {
+        match self {
+            ServerRecovery::StartServer => "Start server",
+            ServerRecovery::Retry => "Retry",
+            ServerRecovery::SwitchToCloud => "Switch to cloud provider",
+        }
+    }
+
+    fn description(self, backend: OssBackend) -> &'static str {
+        match (self, backend) {
+            (ServerRecovery::StartServer, OssBackend::LmStudio) => "Run `lms server start`",
+            (ServerRecovery::StartServer, _) => "Run `ollama serve` in the background",
+            (ServerRecovery::Retry, _) => "Check the server again",
+            (ServerRecovery::SwitchToCloud, _) => "Continue with the default OpenAI provider",
+        }
+    }
+}
+
+/// Choices offered for `backend`. Generic servers cannot be started by Codex.
+pub(crate) fn recovery_choices(backend: OssBackend) -> Vec<ServerRecovery> {
+    let mut choices = Vec::new();
+    if backend != OssBackend::Generic {
+        choices.push(ServerRecovery::StartServer);
+    }
+    choices.push(ServerRecovery::Retry);
+    choices.push(ServerRecovery::SwitchToCloud);
+    choices
+}
+
+/// Popup rows that send [`AppEvent::RecoverLocalServer`].
+pub(crate) fn recovery_items(backend: OssBackend) -> Vec<SelectionItem> {
+    recovery_choices(backend)
+        .into_iter()
+        .map(|choice| {
+            let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                tx.send(AppEvent::RecoverLocalServer(choice));
+            })];
+            SelectionItem {
+                name: choice.label().to_string(),
+                description: Some(choice.description(backend).to_string()),
+                actions,
+                dismiss_on_select: true,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Full-screen prompt shown when the server is down before the session starts.
+pub(crate) struct ServerUnreachableScreen {
+    request_frame: FrameRequester,
+    backend: OssBackend,
+    message: String,
+    /// Outcome of the last attempt, e.g. why starting the server failed.
+    notice: Option<String>,
+    choices: Vec<ServerRecovery>,
+    highlighted: usize,
+    /// `Some(None)` when the user quit instead of choosing.
+    selection: Option<Option<ServerRecovery>>,
+}
+
+impl ServerUnreachableScreen {
+    pub(crate) fn new(request_frame: FrameRequester, backend: OssBackend, message: String) -> Self {
+        Self {
+            request_frame,
+            backend,
+            message,
+            notice: None,
+            choices: recovery_choices(backend),
+            highlighted: 0,
+            selection: None,
+        }
+    }
+
+    pub(crate) fn set_message(&mut self, message: String) {
+        self.message = message;
+        self.request_frame.schedule_frame();
+    }
+
+    pub(crate) fn set_notice(&mut self, notice: Option<String>) {
+        self.notice = notice;
+        self.request_frame.schedule_frame();
+    }
+
+    /// Wait for a choice. Returns `None` when the user quits.
+    pub(crate) async fn run(&mut self, tui: &mut Tui) -> std::io::Result<Option<ServerRecovery>> {
+        self.selection = None;
+        self.draw(tui)?;
+        let events = tui.event_stream();
+        tokio::pin!(events);
+        while self.selection.is_none() {
+            match events.next().await {
+                Some(TuiEvent::Key(key_event)) => self.handle_key(key_event),
+                Some(TuiEvent::Paste(_)) => {}
+                Some(TuiEvent::Draw) => self.draw(tui)?,
+                None => return Ok(None),
+            }
+        }
+        Ok(self.selection.flatten())
+    }
+
+    pub(crate) fn draw(&self, tui: &mut Tui) -> std::io::Result<()> {
+        tui.draw(u16::MAX, |frame| {
+            frame.render_widget_ref(self, frame.area());
+        })
+    }
+
+    fn handle_key(&mut self, key_event: KeyEvent) {
+        if key_event.kind == KeyEventKind::Release {
+            return;
+        }
+        if key_event.modifiers.contains(KeyModifiers::CONTROL)
+            && matches!(key_event.code, KeyCode::Char('c') | KeyCode::Char('d'))
+        {
+            self.selection = Some(None);
+            return;
+        }
+        let count = self.choices.len();
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.set_highlight((self.highlighted + count - 1) % count)
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.set_highlight((self.highlighted + 1) % count)
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                let index = (c as usize) - ('1' as usize);
+                if index < count {
+                    self.select(index);
+                }
+            }
+            KeyCode::Enter => self.select(self.highlighted),
+            KeyCode::Esc => self.selection = Some(None),
+            _ => {}
+        }
+    }
+
+    fn set_highlight(&mut self, highlighted: usize) {
+        if self.highlighted != highlighted {
+            self.highlighted = highlighted;
+            self.request_frame.schedule_frame();
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        self.highlighted = index;
+        self.selection = Some(self.choices.get(index).copied());
+        self.request_frame.schedule_frame();
+    }
+}
+
+impl WidgetRef for &ServerUnreachableScreen {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let mut column = ColumnRenderable::new();
+        column.push("");
+        column.push(Line::from(vec![
+            "  ✗ ".red(),
+            format!("The local {} server is not responding", self.backend).bold(),
+        ]));
+        column.push("");
+        column.push(
+            Paragraph::new(Line::from(self.message.clone().dim()))
+                .wrap(Wrap { trim: false })
+                .inset(Insets::tlbr(0, 2, 0, 0)),
+        );
+        if let Some(notice) = &self.notice {
+            column.push("");
+            column.push(
+                Paragraph::new(Line::from(notice.clone().red()))
+                    .wrap(Wrap { trim: false })
+                    .inset(Insets::tlbr(0, 2, 0, 0)),
+            );
+        }
+        column.push("");
+        for (index, choice) in self.choices.iter().enumerate() {
+            column.push(selection_option_row(
+                index,
+                choice.label().to_string(),
+                index == self.highlighted,
+            ));
+        }
+        column.push("");
+        column.push(
+            Line::from(vec![
+                "Press ".dim(),
+                key_hint::plain(KeyCode::Enter).into(),
+                " to continue · ".dim(),
+                key_hint::plain(KeyCode::Esc).into(),
+                " to quit".dim(),
+            ])
+            .inset(Insets::tlbr(0, 2, 0, 0)),
+        );
+        column.render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn screen(backend: OssBackend) -> ServerUnreachableScreen {
+        ServerUnreachableScreen::new(
+            FrameRequester::test_dummy(),
+            backend,
+            "connection refused".to_string(),
+        )
+    }
+
+    fn press(screen: &mut ServerUnreachableScreen, code: KeyCode) {
+        screen.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn generic_servers_cannot_be_started() {
+        assert_eq!(
+            recovery_choices(OssBackend::Generic),
+            vec![ServerRecovery::Retry, ServerRecovery::SwitchToCloud]
+        );
+        assert_eq!(recovery_choices(OssBackend::LmStudio).len(), 3);
+    }
+
+    #[test]
+    fn keys_select_a_choice_or_quit() {
+        let mut prompt = screen(OssBackend::Ollama);
+        press(&mut prompt, KeyCode::Up);
+        press(&mut prompt, KeyCode::Enter);
+        assert_eq!(prompt.selection, Some(Some(ServerRecovery::SwitchToCloud)));
+
+        let mut prompt = screen(OssBackend::Ollama);
+        press(&mut prompt, KeyCode::Char('2'));
+        assert_eq!(prompt.selection, Some(Some(ServerRecovery::Retry)));
+
+        let mut prompt = screen(OssBackend::Ollama);
+        press(&mut prompt, KeyCode::Esc);
+        assert_eq!(prompt.selection, Some(None));
+    }
+}
@@ -0,0 +1,348 @@
+//! `--oss` readiness checks run inside the TUI, so that downloading the model
+//! renders as a progress bar instead of raw terminal output that would be
+//! painted over by the first frame, and a server that is down can be started
+//! without leaving Codex.
+
+use crate::key_hint;
+use crate::oss_models;
+use crate::oss_server_prompt::ServerRecovery;
+use crate::oss_server_prompt::ServerUnreachableScreen;
+use crate::render::Insets;
+use crate::render::renderable::ColumnRenderable;
+use crate::render::renderable::Renderable;
+use crate::render::renderable::RenderableExt as _;
+use crate::tui::FrameRequester;
+use crate::tui::Tui;
+use crate::tui::TuiEvent;
+use codex_core::config::Config;
+use codex_core::protocol_config_types::OssBackend;
+use codex_ollama::JsonProgressReporter;
+use codex_ollama::OssError;
+use codex_protocol::oss::DownloadPhase;
+use codex_protocol::oss::ModelDownloadProgress;
+use crossterm::event::KeyCode;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::Widget;
+use ratatui::style::Stylize as _;
+use ratatui::text::Line;
+use ratatui::widgets::Clear;
+use ratatui::widgets::WidgetRef;
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+
+/// Width of the bar, in cells.
+const PROGRESS_BAR_WIDTH: usize = 30;
+
+pub(crate) enum OssStartupOutcome {
+    Ready,
+    /// The server is down and the user chose to use the cloud provider.
+    SwitchToCloud,
+    /// The user chose to keep downloading the model in the background and
+    /// start the session with `fallback_model`, another local model, or
+    /// with the cloud provider when there is none.
+    DownloadInBackground {
+        fallback_model: Option<String>,
+    },
+}
+
+enum Readiness {
+    Ready,
+    Backgrounded,
+}
+
+/// Ensure the local server is up and the model is downloaded, showing the
+/// download progress on screen. Ctrl+C cancels the download and `b` moves it
+/// to the background. When the server does not answer, the user can start
+/// it, retry, or switch to the cloud.
+pub(crate) async fn run_oss_startup(
+    tui: &mut Tui,
+    config: &Config,
+) -> Result<OssStartupOutcome, OssError> {
+    let backend = config.oss.provider.unwrap_or(OssBackend::Ollama);
+    let mut prompt: Option<ServerUnreachableScreen> = None;
+    loop {
+        let message = match ensure_ready(tui, config).await {
+            Ok(Readiness::Ready) => return Ok(OssStartupOutcome::Ready),
+            Ok(Readiness::Backgrounded) => {
+                let fallback_model = oss_models::fallback_local_model(config).await;
+                return Ok(OssStartupOutcome::DownloadInBackground { fallback_model });
+            }
+            Err(OssError::ServerUnreachable(message)) => message,
+            Err(err) => return Err(err),
+        };
+        let prompt = prompt.get_or_insert_with(|| {
+            ServerUnreachableScreen::new(tui.frame_requester(), backend, message.clone())
+        });
+        prompt.set_message(message.clone());
+        loop {
+            match prompt.run(tui).await? {
+                None => return Err(OssError::ServerUnreachable(message)),
+                Some(ServerRecovery::SwitchToCloud) => {
+                    let _ = tui.terminal.clear();
+                    return Ok(OssStartupOutcome::SwitchToCloud);
+                }
+                Some(ServerRecovery::Retry) => {
+                    prompt.set_notice(Some("The server is still not responding.".to_string()));
+                    break;
+                }
+                Some(ServerRecovery::StartServer) => {
+                    prompt.set_notice(Some(format!("Starting the {backend} server…")));
+                    prompt.draw(tui)?;
+                    match codex_ollama::start_oss_server(config).await {
+                        Ok(()) => {
+                            prompt.set_notice(None);
+                            break;
+                        }
+                        Err(err) => {
+                            prompt.set_notice(Some(format!("Failed to start the server: {err}")));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn ensure_ready(tui: &mut Tui, config: &Config) -> Result<Readiness, OssError> {
+    let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+    let mut reporter = JsonProgressReporter::new(config.model.clone(), move |progress| {
+        let _ = progress_tx.send(progress.clone());
+        Ok(())
+    });
+    let ready = codex_ollama::ensure_oss_ready_with_reporter(config, &mut reporter);
+    tokio::pin!(ready);
+
+    let mut view = ModelDownloadView::new(tui.frame_requester(), config.model.clone());
+    let mut events = tui.event_stream();
+    let result = loop {
+        tokio::select! {
+            result = &mut ready => break result.map(|()| Readiness::Ready),
+            Some(progress) = progress_rx.recv() => view.update(progress),
+            Some(event) = events.next() => match event {
+                TuiEvent::Key(key) if key_hint::ctrl(KeyCode::Char('c')).is_press(key) => {
+                    break Err(OssError::Io(std::io::Error::new(
+                        std::io::ErrorKind::Interrupted,
+                        format!("Download of '{}' was cancelled.", config.model),
+                    )));
+                }
+                // The download restarts in the background; Ollama resumes
+                // from the blobs it already has.
+                TuiEvent::Key(key)
+                    if view.is_visible() && key_hint::plain(KeyCode::Char('b')).is_press(key) =>
+                {
+                    break Ok(Readiness::Backgrounded);
+                }
+                TuiEvent::Draw if view.is_visible() => {
+                    let drawn = tui.draw(u16::MAX, |frame| {
+                        frame.render_widget_ref(&view, frame.area());
+                    });
+                    if let Err(err) = drawn {
+                        tracing::warn!("failed to draw download progress: {err}");
+                    }
+                }
+                _ => {}
+            },
+        }
+    };
+    if view.is_visible() {
+        let _ = tui.terminal.clear();
+    }
+    result
+}
+
+/// Full-screen progress of a model download.
+struct ModelDownloadView {
+    request_frame: FrameRequester,
+    model: String,
+    progress: Option<ModelDownloadProgress>,
+    /// Download speed averaged over recent events, for a steadier ETA.
+    smoothed_bytes_per_second: Option<f64>,
+}
+
+impl ModelDownloadView {
+    fn new(request_frame: FrameRequester, model: String) -> Self {
+        Self {
+            request_frame,
+            model,
+            progress: None,
+            smoothed_bytes_per_second: None,
+        }
+    }
+
+    /// Nothing is drawn until the server reports a download, so a model that
+    /// is already present starts without flashing this screen.
+    fn is_visible(&self) -> bool {
+        self.progress.is_some()
+    }
+
+    fn update(&mut self, progress: ModelDownloadProgress) {
+        if let Some(speed) = progress.bytes_per_second {
+            let speed = speed as f64;
+            self.smoothed_bytes_per_second = Some(match self.smoothed_bytes_per_second {
+                Some(previous) => previous * 0.7 + speed * 0.3,
+                None => speed,
+            });
+        }
+        self.progress = Some(progress);
+        self.request_frame.schedule_frame();
+    }
+}
+
+impl WidgetRef for &ModelDownloadView {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let Some(progress) = &self.progress else {
+            return;
+        };
+        let mut column = ColumnRenderable::new();
+        column.push("");
+        column.push(Line::from(vec![
+            "  ".into(),
+            phase_label(progress.phase).bold(),
+            " ".into(),
+            self.model.clone().cyan(),
+        ]));
+        column.push("");
+        if let Some(fraction) = download_fraction(progress) {
+            column.push(
+                Line::from(vec![
+                    progress_bar(fraction, PROGRESS_BAR_WIDTH).cyan(),
+                    format!(" {:.1}%", fraction * 100.0).into(),
+                ])
+                .inset(Insets::tlbr(0, 2, 0, 0)),
+            );
+            column.push(
+                Line::from(
+                    progress_summary(progress, self.smoothed_bytes_per_second)
+                        .join(" · ")
+                        .dim(),
+                )
+                .inset(Insets::tlbr(0, 2, 0, 0)),
+            );
+        } else if let Some(status) = &progress.status {
+            column.push(Line::from(status.clone().dim()).inset(Insets::tlbr(0, 2, 0, 0)));
+        }
+        column.push("");
+        column.push(
+            Line::from(vec![
+                "Press ".dim(),
+                key_hint::ctrl(KeyCode::Char('c')).into(),
+                " to cancel · ".dim(),
+                key_hint::plain(KeyCode::Char('b')).into(),
+                " to continue in the background".dim(),
+            ])
+            .inset(Insets::tlbr(0, 2, 0, 0)),
+        );
+        column.render(area, buf);
+    }
+}
+
+fn phase_label(phase: DownloadPhase) -> &'static str {
+    match phase {
+        DownloadPhase::Manifest => "Preparing download of",
+        DownloadPhase::Downloading => "Downloading",
+        DownloadPhase::Verifying => "Verifying",
+        DownloadPhase::Writing => "Finishing download of",
+        DownloadPhase::Done => "Downloaded",
+        DownloadPhase::Failed => "Failed to download",
+    }
+}
+
+fn download_fraction(progress: &ModelDownloadProgress) -> Option<f64> {
+    let total = progress.total_bytes.filter(|total| *total > 0)?;
+    let completed = progress.completed_bytes.unwrap_or(0).min(total);
+    Some(completed as f64 / total as f64)
+}
+
+fn progress_bar(fraction: f64, width: usize) -> String {
+    let filled = ((fraction.clamp(0.0, 1.0) * width as f64).round() as usize).min(width);
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+/// Downloaded and total size, speed, and time remaining.
+fn progress_summary(
+    progress: &ModelDownloadProgress,
+    bytes_per_second: Option<f64>,
+) -> Vec<String> {
+    let completed = progress.completed_bytes.unwrap_or(0);
+    let mut parts = Vec::new();
+    if let Some(total) = progress.total_bytes {
+        parts.push(format!(
+            "{} / {}",
+            codex_ollama::format_size(completed),
+            codex_ollama::format_size(total)
+        ));
+        if let Some(speed) = bytes_per_second.filter(|speed| *speed >= 1.0) {
+            parts.push(format!("{}/s", codex_ollama::format_size(speed as u64)));
+            let remaining = total.saturating_sub(completed) as f64 / speed;
+            parts.push(format!("{} left", format_eta(remaining as u64)));
+        }
+    }
+    parts
+}
+
+pub(crate) fn format_eta(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn progress(completed: u64, total: u64) -> ModelDownloadProgress {
+        ModelDownloadProgress {
+            model: "gpt-oss:20b".to_string(),
+            phase: DownloadPhase::Downloading,
+            status: None,
+            completed_bytes: Some(completed),
+            total_bytes: Some(total),
+            bytes_per_second: Some(1024 * 1024),
+        }
+    }
+
+    #[test]
+    fn progress_bar_fills_proportionally() {
+        assert_eq!(progress_bar(0.0, 4), "░░░░");
+        assert_eq!(progress_bar(0.5, 4), "██░░");
+        assert_eq!(progress_bar(1.5, 4), "████");
+    }
+
+    #[test]
+    fn summary_includes_speed_and_eta() {
+        let progress = progress(512 * 1024 * 1024, 1024 * 1024 * 1024);
+        assert_eq!(download_fraction(&progress), Some(0.5));
+        assert_eq!(
+            progress_summary(&progress, Some(4.0 * 1024.0 * 1024.0)),
+            vec!["512.0 MB / 1.00 GB", "4.0 MB/s", "2m 08s left"]
+        );
+        assert_eq!(
+            progress_summary(&progress, None),
+            vec!["512.0 MB / 1.00 GB"]
+        );
+    }
+
+    #[test]
+    fn view_smooths_speed_and_stays_hidden_until_progress() {
+        let mut view = ModelDownloadView::new(FrameRequester::test_dummy(), "m".to_string());
+        assert!(!view.is_visible());
+        view.update(progress(0, 100));
+        let mut slower = progress(10, 100);
+        slower.bytes_per_second = Some(0);
+        view.update(slower);
+        assert!(view.is_visible());
+        assert_eq!(view.smoothed_bytes_per_second, Some(1024.0 * 1024.0 * 0.7));
+    }
+
+    #[test]
+    fn eta_uses_largest_units() {
+        assert_eq!(format_eta(42), "42s");
+        assert_eq!(format_eta(128), "2m 08s");
+        assert_eq!(format_eta(7_500), "2h 05m");
+    }
+}
@@ -0,0 +1,166 @@
+//! Latency of recent requests to the local `--oss` model, shown by
+//! `/latency` so slow prompt processing can be told apart from slow
+//! generation.
+
+use std::collections::VecDeque;
+
+use codex_core::protocol::RequestTimingEvent;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+
+/// Requests kept for the overlay; older ones are dropped.
+const MAX_REQUESTS: usize = 50;
+
+/// Width of each column after the request number, so the values line up.
+const COLUMN_WIDTH: usize = 12;
+
+#[derive(Debug, Default)]
+pub(crate) struct RequestLatencyLog {
+    requests: VecDeque<RequestTimingEvent>,
+    /// Requests recorded over the session, numbering the rows.
+    recorded: usize,
+}
+
+impl RequestLatencyLog {
+    pub(crate) fn record(&mut self, timing: RequestTimingEvent) {
+        if self.requests.len() == MAX_REQUESTS {
+            self.requests.pop_front();
+        }
+        self.requests.push_back(timing);
+        self.recorded += 1;
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// One row per request, most recent last.
+    pub(crate) fn overlay_lines(&self, model: &str) -> Vec<Line<'static>> {
+        let mut lines: Vec<Line<'static>> = vec![
+            format!("Recent requests to {model}").bold().into(),
+            "Prefill is the time the server spent on the prompt before the first token."
+                .dim()
+                .into(),
+            "Connection includes the TLS handshake of https servers and is near zero on a reused connection."
+                .dim()
+                .into(),
+            "".into(),
+            row(
+                "#",
+                [
+                    "connection",
+                    "write",
+                    "headers",
+                    "prefill",
+                    "first token",
+                    "total",
+                    "tokens/s",
+                    "tokens",
+                ],
+            )
+            .dim()
+            .into(),
+        ];
+        let first = self.recorded - self.requests.len() + 1;
+        for (index, timing) in self.requests.iter().enumerate() {
+            let speed = timing
+                .tokens_per_second()
+                .map_or_else(not_measured, |speed| format!("{speed:.1}"));
+            lines.push(
+                row(
+                    &(first + index).to_string(),
+                    [
+                        millis(timing.connection_ms),
+                        millis(timing.request_write_ms),
+                        millis(Some(timing.connect_ms)),
+                        millis(timing.prefill_ms),
+                        millis(timing.time_to_first_token_ms),
+                        millis(Some(timing.total_ms)),
+                        speed,
+                        timing.output_tokens.to_string(),
+                    ],
+                )
+                .into(),
+            );
+        }
+        lines
+    }
+}
+
+fn row<S: AsRef<str>>(number: &str, columns: [S; 8]) -> String {
+    let mut row = format!("{number:>4}  ");
+    for column in columns {
+        row.push_str(&format!("{:>COLUMN_WIDTH$}", column.as_ref()));
+    }
+    row
+}
+
+fn millis(ms: Option<u64>) -> String {
+    match ms {
+        Some(ms) if ms >= 10_000 => format!("{:.1} s", ms as f64 / 1000.0),
+        Some(ms) => format!("{ms} ms"),
+        None => not_measured(),
+    }
+}
+
+fn not_measured() -> String {
+    "–".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn timing(connect_ms: u64, prefill_ms: Option<u64>) -> RequestTimingEvent {
+        RequestTimingEvent {
+            connection_ms: Some(1),
+            tls: false,
+            request_write_ms: Some(0),
+            connect_ms,
+            prefill_ms,
+            time_to_first_token_ms: prefill_ms.map(|prefill| connect_ms + prefill),
+            generation_ms: prefill_ms.map(|_| 2_000),
+            output_tokens: if prefill_ms.is_some() { 81 } else { 0 },
+            total_ms: connect_ms + prefill_ms.map_or(0, |prefill| prefill + 2_000),
+        }
+    }
+
+    fn text(lines: Vec<Line<'static>>) -> Vec<String> {
+        lines
+            .into_iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rows_show_each_phase_of_a_request() {
+        let mut log = RequestLatencyLog::default();
+        log.record(timing(12, Some(14_310)));
+        log.record(timing(3, None));
+        assert_eq!(
+            text(log.overlay_lines("gpt-oss:20b"))[4..],
+            [
+                "   #    connection       write     headers     prefill first token       total    tokens/s      tokens",
+                "   1          1 ms        0 ms       12 ms      14.3 s      14.3 s      16.3 s        40.0          81",
+                "   2          1 ms        0 ms        3 ms           –           –        3 ms           –           0",
+            ]
+        );
+    }
+
+    #[test]
+    fn only_recent_requests_are_kept() {
+        let mut log = RequestLatencyLog::default();
+        for connect_ms in 0..=MAX_REQUESTS as u64 {
+            log.record(timing(connect_ms, None));
+        }
+        let lines = text(log.overlay_lines("gpt-oss:20b"));
+        assert_eq!(lines.len(), 5 + MAX_REQUESTS);
+        assert!(lines[5].starts_with("   2 "));
+    }
+}
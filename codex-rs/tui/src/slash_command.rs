@@ -22,6 +22,14 @@ pub enum SlashCommand {
     Diff,
     Mention,
     Status,
+    OssStatus,
+    // Short so that "/mo" keeps matching /model and /mention first.
+    #[strum(serialize = "mem")]
+    Memory,
+    Sampling,
+    Eject,
+    Latency,
+    Compare,
     Mcp,
     Logout,
     Quit,
@@ -44,6 +52,12 @@ impl SlashCommand {
             SlashCommand::Diff => "show git diff (including untracked files)",
             SlashCommand::Mention => "mention a file",
             SlashCommand::Status => "show current session configuration and token usage",
+            SlashCommand::OssStatus => "show the local server's version, loaded models, and memory",
+            SlashCommand::Memory => "expand or collapse memory usage of the local model",
+            SlashCommand::Sampling => "adjust sampling parameters of the local model",
+            SlashCommand::Eject => "unload the local model to free GPU memory until the next turn",
+            SlashCommand::Latency => "show the latency of recent requests to the local model",
+            SlashCommand::Compare => "answer the next message with two local models (experimental)",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Approvals => "choose what Codex can do without approval",
             SlashCommand::Mcp => "list configured MCP tools",
@@ -68,11 +82,17 @@ impl SlashCommand {
             | SlashCommand::Undo
             | SlashCommand::Model
             | SlashCommand::Approvals
+            | SlashCommand::Compare
+            | SlashCommand::Eject
             | SlashCommand::Review
             | SlashCommand::Logout => false,
             SlashCommand::Diff
             | SlashCommand::Mention
             | SlashCommand::Status
+            | SlashCommand::OssStatus
+            | SlashCommand::Memory
+            | SlashCommand::Sampling
+            | SlashCommand::Latency
             | SlashCommand::Mcp
             | SlashCommand::Feedback
             | SlashCommand::Quit => true,
@@ -68,6 +68,37 @@ export type ThreadErrorEvent = {
   message: string;
 };
 
+/** Progress of a local model download with `--oss`, emitted before the thread starts. */
+export type ModelDownloadProgressEvent = {
+  type: "model_download.progress";
+  model: string;
+  phase: "manifest" | "downloading" | "verifying" | "writing" | "done" | "failed";
+  /** Status text reported by the server, or the error message when `phase` is `failed`. */
+  status: string | null;
+  /** Bytes downloaded so far, summed over all layers. */
+  completed_bytes: number | null;
+  total_bytes: number | null;
+  /** Download speed since the previous event. */
+  bytes_per_second: number | null;
+};
+
+/** Something that happened to the local server or its models. */
+export type OssEvent =
+  | { type: "server_detected"; backend: string; base_url: string }
+  | { type: "server_started"; backend: string; base_url: string }
+  | { type: "download_started"; model: string }
+  | ({ type: "download_progress" } & Omit<ModelDownloadProgressEvent, "type">)
+  | { type: "download_finished"; model: string; error: string | null }
+  | { type: "model_loaded"; model: string }
+  | { type: "model_unloaded"; model: string }
+  | { type: "backend_unhealthy"; backend: string; message: string };
+
+/** Something happened to the local server or its models with `--oss`. */
+export type LocalServerEvent = {
+  type: "local_server.event";
+  event: OssEvent;
+};
+
 /** Top-level JSONL events emitted by codex exec. */
 export type ThreadEvent =
   | ThreadStartedEvent
@@ -77,4 +108,6 @@ export type ThreadEvent =
   | ItemStartedEvent
   | ItemUpdatedEvent
   | ItemCompletedEvent
-  | ThreadErrorEvent;
+  | ThreadErrorEvent
+  | ModelDownloadProgressEvent
+  | LocalServerEvent;
@@ -9,6 +9,9 @@ export type {
   ItemCompletedEvent,
   ThreadError,
   ThreadErrorEvent,
+  ModelDownloadProgressEvent,
+  LocalServerEvent,
+  OssEvent,
   Usage,
 } from "./events";
 export type {